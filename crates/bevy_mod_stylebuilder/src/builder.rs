@@ -197,6 +197,20 @@ impl BorderRadiusParam for i32 {
     }
 }
 
+/// Distinct radii for each corner, in CSS order: top-left, top-right, bottom-right, bottom-left.
+impl<TL: LengthParam, TR: LengthParam, BR: LengthParam, BL: LengthParam> BorderRadiusParam
+    for (TL, TR, BR, BL)
+{
+    fn to_border_radius(self) -> ui::BorderRadius {
+        ui::BorderRadius {
+            top_left: self.0.to_val(),
+            top_right: self.1.to_val(),
+            bottom_right: self.2.to_val(),
+            bottom_left: self.3.to_val(),
+        }
+    }
+}
+
 /// Trait that represents an optional float
 pub trait OptFloatParam {
     fn to_val(self) -> Option<f32>;
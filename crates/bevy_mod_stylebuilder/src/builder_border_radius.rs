@@ -1,8 +1,26 @@
-use super::builder::{BorderRadiusParam, StyleBuilder};
+use bevy::ui;
+
+use super::builder::{BorderRadiusParam, LengthParam, StyleBuilder};
 
 #[allow(missing_docs)]
 pub trait StyleBuilderBorderRadius {
     fn border_radius(&mut self, radius: impl BorderRadiusParam) -> &mut Self;
+    fn border_radius_top_left(&mut self, radius: impl LengthParam) -> &mut Self;
+    fn border_radius_top_right(&mut self, radius: impl LengthParam) -> &mut Self;
+    fn border_radius_bottom_left(&mut self, radius: impl LengthParam) -> &mut Self;
+    fn border_radius_bottom_right(&mut self, radius: impl LengthParam) -> &mut Self;
+}
+
+/// Modify the existing border radius of the target, or insert a new one.
+fn update_border_radius(sb: &mut StyleBuilder, update: impl FnOnce(&mut ui::BorderRadius)) {
+    match sb.target.get_mut::<ui::BorderRadius>() {
+        Some(mut radius) => update(&mut radius),
+        None => {
+            let mut radius = ui::BorderRadius::default();
+            update(&mut radius);
+            sb.target.insert(radius);
+        }
+    }
 }
 
 impl<'a, 'w> StyleBuilderBorderRadius for StyleBuilder<'a, 'w> {
@@ -10,4 +28,24 @@ impl<'a, 'w> StyleBuilderBorderRadius for StyleBuilder<'a, 'w> {
         self.target.insert(radius.to_border_radius());
         self
     }
+
+    fn border_radius_top_left(&mut self, radius: impl LengthParam) -> &mut Self {
+        update_border_radius(self, |r| r.top_left = radius.to_val());
+        self
+    }
+
+    fn border_radius_top_right(&mut self, radius: impl LengthParam) -> &mut Self {
+        update_border_radius(self, |r| r.top_right = radius.to_val());
+        self
+    }
+
+    fn border_radius_bottom_left(&mut self, radius: impl LengthParam) -> &mut Self {
+        update_border_radius(self, |r| r.bottom_left = radius.to_val());
+        self
+    }
+
+    fn border_radius_bottom_right(&mut self, radius: impl LengthParam) -> &mut Self {
+        update_border_radius(self, |r| r.bottom_right = radius.to_val());
+        self
+    }
 }
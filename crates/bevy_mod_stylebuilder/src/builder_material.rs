@@ -0,0 +1,70 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{Assets, Handle},
+    prelude::Component,
+    ui::UiMaterial,
+};
+
+use super::builder::StyleBuilder;
+
+/// Marks a material handle which was created by [`StyleBuilderMaterial::material`], and which
+/// is therefore not shared with other entities.
+#[derive(Component)]
+struct OwnedMaterial<M: UiMaterial>(PhantomData<M>);
+
+/// Trait which adds custom [`UiMaterial`] support to [`StyleBuilder`]. The node is drawn with the
+/// material, in addition to its background color, so it's usually best to leave the background
+/// transparent. The material type must be registered with `UiMaterialPlugin::<M>`.
+///
+/// When used within a dynamic style, the material parameters are updated in place each time
+/// the style is recomputed, rather than allocating a new material:
+///
+/// ```ignore
+/// Element::<NodeBundle>::new().style_dyn(
+///     |(from, to), sb| {
+///         sb.material(GradientMaterial { from, to });
+///     },
+///     (from_color, to_color),
+/// )
+/// ```
+#[allow(missing_docs)]
+pub trait StyleBuilderMaterial {
+    /// Draw the node with the given material parameters.
+    fn material<M: UiMaterial>(&mut self, material: M) -> &mut Self;
+
+    /// Draw the node with an existing material, which may be shared with other nodes.
+    fn material_handle<M: UiMaterial>(&mut self, handle: Handle<M>) -> &mut Self;
+}
+
+impl<'a, 'w> StyleBuilderMaterial for StyleBuilder<'a, 'w> {
+    fn material<M: UiMaterial>(&mut self, material: M) -> &mut Self {
+        let owned = match self.target.get::<Handle<M>>() {
+            Some(handle) if self.target.contains::<OwnedMaterial<M>>() => Some(handle.clone()),
+            _ => None,
+        };
+        match owned {
+            Some(handle) => {
+                self.target.world_scope(|world| {
+                    if let Some(existing) = world.resource_mut::<Assets<M>>().get_mut(&handle) {
+                        *existing = material;
+                    }
+                });
+            }
+            None => {
+                let handle = self
+                    .target
+                    .world_scope(|world| world.resource_mut::<Assets<M>>().add(material));
+                self.target
+                    .insert((handle, OwnedMaterial::<M>(PhantomData)));
+            }
+        }
+        self
+    }
+
+    fn material_handle<M: UiMaterial>(&mut self, handle: Handle<M>) -> &mut Self {
+        self.target.remove::<OwnedMaterial<M>>();
+        self.target.insert(handle);
+        self
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::{prelude::Resource, ui};
+
+use super::builder::StyleBuilder;
+
+/// Resource which defines the size of one spacing step, used by [`StyleBuilderSpacing`]. Using
+/// spacing steps rather than raw pixel values keeps the rhythm of a layout consistent, and
+/// allows a theme to make the whole UI more or less dense. The default step is 4 pixels.
+///
+/// Styles are only computed when an element is built, so changing the scale does not affect
+/// existing elements.
+#[derive(Resource, Clone, Debug)]
+pub struct SpacingScale {
+    /// Size of one step, in pixels.
+    pub unit: f32,
+}
+
+impl Default for SpacingScale {
+    fn default() -> Self {
+        Self { unit: 4. }
+    }
+}
+
+/// Trait that represents a number of spacing steps.
+pub trait SpacingParam {
+    fn to_steps(self) -> f32;
+}
+
+impl SpacingParam for i32 {
+    fn to_steps(self) -> f32 {
+        self as f32
+    }
+}
+
+impl SpacingParam for f32 {
+    fn to_steps(self) -> f32 {
+        self
+    }
+}
+
+/// Spacing helpers which measure padding, margins and gaps in steps of the [`SpacingScale`]:
+///
+/// ```ignore
+/// ss.px(4).py(2).gap_y(1);
+/// ```
+#[allow(missing_docs)]
+pub trait StyleBuilderSpacing {
+    fn p(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn px(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn py(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn pt(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn pr(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn pb(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn pl(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn m(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn mx(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn my(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn mt(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn mr(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn mb(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn ml(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn gap_x(&mut self, steps: impl SpacingParam) -> &mut Self;
+    fn gap_y(&mut self, steps: impl SpacingParam) -> &mut Self;
+}
+
+impl<'a, 'w> StyleBuilder<'a, 'w> {
+    /// Convert a number of spacing steps to a length.
+    fn spacing(&self, steps: impl SpacingParam) -> ui::Val {
+        let unit = self
+            .target
+            .world()
+            .get_resource::<SpacingScale>()
+            .map_or(SpacingScale::default().unit, |scale| scale.unit);
+        ui::Val::Px(steps.to_steps() * unit)
+    }
+}
+
+impl<'a, 'w> StyleBuilderSpacing for StyleBuilder<'a, 'w> {
+    fn p(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.padding = ui::UiRect::all(self.spacing(steps));
+        self.style_changed = true;
+        self
+    }
+
+    fn px(&mut self, steps: impl SpacingParam) -> &mut Self {
+        let val = self.spacing(steps);
+        self.style.padding.left = val;
+        self.style.padding.right = val;
+        self.style_changed = true;
+        self
+    }
+
+    fn py(&mut self, steps: impl SpacingParam) -> &mut Self {
+        let val = self.spacing(steps);
+        self.style.padding.top = val;
+        self.style.padding.bottom = val;
+        self.style_changed = true;
+        self
+    }
+
+    fn pt(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.padding.top = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn pr(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.padding.right = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn pb(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.padding.bottom = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn pl(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.padding.left = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn m(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.margin = ui::UiRect::all(self.spacing(steps));
+        self.style_changed = true;
+        self
+    }
+
+    fn mx(&mut self, steps: impl SpacingParam) -> &mut Self {
+        let val = self.spacing(steps);
+        self.style.margin.left = val;
+        self.style.margin.right = val;
+        self.style_changed = true;
+        self
+    }
+
+    fn my(&mut self, steps: impl SpacingParam) -> &mut Self {
+        let val = self.spacing(steps);
+        self.style.margin.top = val;
+        self.style.margin.bottom = val;
+        self.style_changed = true;
+        self
+    }
+
+    fn mt(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.margin.top = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn mr(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.margin.right = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn mb(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.margin.bottom = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn ml(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.margin.left = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn gap_x(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.column_gap = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+
+    fn gap_y(&mut self, steps: impl SpacingParam) -> &mut Self {
+        self.style.row_gap = self.spacing(steps);
+        self.style_changed = true;
+        self
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::{prelude::Resource, text::Font};
+
+use super::builder::{HandleOrOwnedPath, StyleBuilder};
+use super::builder_font::StyleBuilderFont;
+
+/// Standard levels of the typographic scale.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TextLevel {
+    Heading1,
+    Heading2,
+    Heading3,
+    Body,
+    Small,
+}
+
+/// The font and size used for one level of the [`TypeScale`].
+#[derive(Clone, Debug)]
+pub struct TextPreset {
+    /// Font to use, or `None` to inherit the font from the parent.
+    pub font: Option<HandleOrOwnedPath<Font>>,
+    /// Font size, in pixels.
+    pub size: f32,
+}
+
+impl TextPreset {
+    /// Construct a preset which inherits the font, and only sets the size.
+    pub fn size(size: f32) -> Self {
+        Self { font: None, size }
+    }
+}
+
+/// Resource which defines the typographic presets used by
+/// [`StyleBuilderTextStyle::text_style`]. A theme can replace this resource to change the
+/// fonts and sizes used for headings and body text throughout the UI.
+#[derive(Resource, Clone, Debug)]
+pub struct TypeScale {
+    pub heading1: TextPreset,
+    pub heading2: TextPreset,
+    pub heading3: TextPreset,
+    pub body: TextPreset,
+    pub small: TextPreset,
+}
+
+impl TypeScale {
+    /// Returns the preset for the given level.
+    pub fn preset(&self, level: TextLevel) -> &TextPreset {
+        match level {
+            TextLevel::Heading1 => &self.heading1,
+            TextLevel::Heading2 => &self.heading2,
+            TextLevel::Heading3 => &self.heading3,
+            TextLevel::Body => &self.body,
+            TextLevel::Small => &self.small,
+        }
+    }
+}
+
+impl Default for TypeScale {
+    fn default() -> Self {
+        Self {
+            heading1: TextPreset::size(28.),
+            heading2: TextPreset::size(22.),
+            heading3: TextPreset::size(18.),
+            body: TextPreset::size(16.),
+            small: TextPreset::size(13.),
+        }
+    }
+}
+
+#[allow(missing_docs)]
+pub trait StyleBuilderTextStyle {
+    /// Set the font and font size from a level of the [`TypeScale`].
+    fn text_style(&mut self, level: TextLevel) -> &mut Self;
+}
+
+impl<'a, 'w> StyleBuilderTextStyle for StyleBuilder<'a, 'w> {
+    fn text_style(&mut self, level: TextLevel) -> &mut Self {
+        let preset = self.target.world().get_resource::<TypeScale>().map_or_else(
+            || TypeScale::default().preset(level).clone(),
+            |scale| scale.preset(level).clone(),
+        );
+        if let Some(font) = preset.font.as_ref() {
+            self.font(font);
+        }
+        self.font_size(preset.size);
+        self
+    }
+}
@@ -5,7 +5,10 @@ mod builder_border_color;
 mod builder_border_radius;
 mod builder_font;
 mod builder_layout;
+mod builder_material;
 mod builder_outline;
+mod builder_spacing;
+mod builder_text_style;
 mod builder_visibility;
 mod builder_z_index;
 mod text_styles;
@@ -27,7 +30,10 @@ pub use builder_border_color::StyleBuilderBorderColor;
 pub use builder_border_radius::StyleBuilderBorderRadius;
 pub use builder_font::StyleBuilderFont;
 pub use builder_layout::StyleBuilderLayout;
+pub use builder_material::StyleBuilderMaterial;
 pub use builder_outline::StyleBuilderOutline;
+pub use builder_spacing::{SpacingParam, SpacingScale, StyleBuilderSpacing};
+pub use builder_text_style::{StyleBuilderTextStyle, TextLevel, TextPreset, TypeScale};
 pub use builder_visibility::StyleBuilderVisibility;
 pub use builder_z_index::StyleBuilderZIndex;
 use text_styles::update_text_styles;
@@ -150,6 +156,8 @@ pub struct StyleBuilderPlugin;
 
 impl Plugin for StyleBuilderPlugin {
     fn build(&self, app: &mut bevy::app::App) {
-        app.add_systems(Update, update_text_styles.in_set(StyleBuilderSystemSet));
+        app.init_resource::<SpacingScale>()
+            .init_resource::<TypeScale>()
+            .add_systems(Update, update_text_styles.in_set(StyleBuilderSystemSet));
     }
 }
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::{view::reaction_control_system, Callback, Cx};
+
+/// Event which carries a message from game systems to views. Views receive the messages
+/// with [`Cx::use_event`]. The message type must be registered with
+/// [`RegisterUiBridge::register_ui_message`].
+#[derive(Event, Clone, Debug)]
+pub struct UiMessage<T: Send + Sync + 'static>(pub T);
+
+/// Event which carries a command from views to game systems. Game systems receive the
+/// commands with an `EventReader<UiCommand<T>>`. The command type must be registered with
+/// [`RegisterUiBridge::register_ui_command`].
+#[derive(Event, Clone, Debug)]
+pub struct UiCommand<T: Send + Sync + 'static>(pub T);
+
+/// Resource which holds the messages received during the current frame.
+#[derive(Resource)]
+struct UiMessages<T: Send + Sync + 'static>(Vec<T>);
+
+impl<T: Send + Sync + 'static> Default for UiMessages<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+/// Trait which adds methods to [`App`] for registering the types of messages which are
+/// exchanged between views and game systems.
+///
+/// ```ignore
+/// app.register_ui_message::<Score>()
+///     .register_ui_command::<PauseGame>();
+///
+/// // In a game system:
+/// fn update_score(mut messages: EventWriter<UiMessage<Score>>) {
+///     messages.send(UiMessage(Score(10)));
+/// }
+///
+/// // In a view:
+/// let score = cx.use_event::<Score>().last().copied();
+/// let pause = cx.create_emitter::<PauseGame>();
+/// ```
+pub trait RegisterUiBridge {
+    /// Register a message type which game systems can send to views.
+    fn register_ui_message<T: Clone + Send + Sync + 'static>(&mut self) -> &mut Self;
+
+    /// Register a command type which views can send to game systems.
+    fn register_ui_command<T: Send + Sync + 'static>(&mut self) -> &mut Self;
+}
+
+impl RegisterUiBridge for App {
+    fn register_ui_message<T: Clone + Send + Sync + 'static>(&mut self) -> &mut Self {
+        self.add_event::<UiMessage<T>>()
+            .init_resource::<UiMessages<T>>()
+            .add_systems(
+                Update,
+                collect_ui_messages::<T>.before(reaction_control_system),
+            )
+    }
+
+    fn register_ui_command<T: Send + Sync + 'static>(&mut self) -> &mut Self {
+        self.add_event::<UiCommand<T>>()
+    }
+}
+
+impl<'p, 'w> Cx<'p, 'w> {
+    /// Return the [`UiMessage`]s of type `T` which were sent since the previous frame. The
+    /// current presenter is re-run when new messages arrive, and again when they are cleared.
+    pub fn use_event<T: Send + Sync + 'static>(&self) -> &[T] {
+        if !self.world().contains_resource::<UiMessages<T>>() {
+            panic!(
+                "UI message type {} is not registered",
+                std::any::type_name::<T>()
+            );
+        }
+        &self.use_resource::<UiMessages<T>>().0
+    }
+
+    /// Create a callback which sends its argument to game systems as a [`UiCommand`].
+    pub fn create_emitter<T: Send + Sync + 'static>(&mut self) -> Callback<T> {
+        self.create_callback(|command: In<T>, mut commands: EventWriter<UiCommand<T>>| {
+            commands.send(UiCommand(command.0));
+        })
+    }
+}
+
+fn collect_ui_messages<T: Clone + Send + Sync + 'static>(
+    mut events: EventReader<UiMessage<T>>,
+    mut messages: ResMut<UiMessages<T>>,
+) {
+    // Avoid marking the resource as changed when there is nothing to do, so that views
+    // don't react every frame.
+    if events.is_empty() {
+        if !messages.0.is_empty() {
+            messages.0.clear();
+        }
+        return;
+    }
+    messages.0.clear();
+    messages
+        .0
+        .extend(events.read().map(|event| event.0.clone()));
+}
@@ -1,4 +1,8 @@
-use std::{cell::RefCell, marker::PhantomData, sync::Arc};
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    sync::Arc,
+};
 
 use bevy::{
     ecs::world::DeferredWorld,
@@ -7,7 +11,7 @@ use bevy::{
 };
 
 use crate::{mutable::Mutable, tracking_scope::HookState, Callback, MutableCell, WriteMutable};
-use crate::{tracking_scope::TrackingScope, ReadMutable};
+use crate::{ownership::OwnedBy, tracking_scope::TrackingScope, ReadMutable};
 
 #[derive(Clone)]
 struct Memo<R: Clone, D: Clone> {
@@ -41,6 +45,9 @@ pub struct Cx<'p, 'w> {
 
     /// Set of reactive resources referenced by the presenter.
     pub(crate) tracking: RefCell<&'p mut TrackingScope>,
+
+    /// Whether reads add dependencies to the tracking scope, see [`Cx::track_explicit`].
+    auto_track: Cell<bool>,
 }
 
 impl<'p, 'w> Cx<'p, 'w> {
@@ -50,6 +57,7 @@ impl<'p, 'w> Cx<'p, 'w> {
             world,
             owner,
             tracking: RefCell::new(tracking),
+            auto_track: Cell::new(true),
         }
     }
 
@@ -82,7 +90,8 @@ impl<'p, 'w> Cx<'p, 'w> {
                 panic!("Expected create_entity() hook, found something else");
             }
             None => {
-                let entity = self.world_mut().spawn_empty().id();
+                let owner = self.owner;
+                let entity = self.world_mut().spawn(OwnedBy(owner)).id();
                 self.tracking
                     .borrow_mut()
                     .push_hook(HookState::Entity(entity));
@@ -373,7 +382,9 @@ impl<'p, 'w> Cx<'p, 'w> {
     /// Return a reference to the resource of the given type. Calling this function
     /// adds the resource as a dependency of the current presenter invocation.
     pub fn use_resource<T: Resource>(&self) -> &T {
-        self.tracking.borrow_mut().track_resource::<T>(self.world);
+        if self.auto_track.get() {
+            self.track_resource::<T>();
+        }
         self.world.resource::<T>()
     }
 
@@ -395,9 +406,11 @@ impl<'p, 'w> Cx<'p, 'w> {
                         panic!("Unknown component type: {}", std::any::type_name::<C>())
                     });
                 let result = c.get::<C>();
-                self.tracking
-                    .borrow_mut()
-                    .track_component_id(entity, cid, result.is_some());
+                if self.auto_track.get() {
+                    self.tracking
+                        .borrow_mut()
+                        .track_component_id(entity, cid, result.is_some());
+                }
                 result
             }
             None => None,
@@ -414,6 +427,45 @@ impl<'p, 'w> Cx<'p, 'w> {
         }
     }
 
+    /// Returns true if the Component `C` on the given entity has been added or mutated since
+    /// the previous rebuild of the current scope. This is useful for running animations or
+    /// one-shot effects when a component changes, without having to keep a copy of the
+    /// previous value. Always returns false the first time the scope is built.
+    ///
+    /// Like [`use_component`](Cx::use_component), this adds the component as a dependency of
+    /// the current scope.
+    pub fn use_component_changed<C: Component>(&mut self, entity: Entity) -> bool {
+        let this_run = self.world.read_change_tick();
+        let hook = self.tracking.borrow_mut().next_hook();
+        let last_run = match hook {
+            Some(HookState::Tick(last_run)) => {
+                self.tracking
+                    .borrow_mut()
+                    .replace_hook(HookState::Tick(this_run));
+                Some(last_run)
+            }
+            Some(_) => {
+                panic!("Expected use_component_changed() hook, found something else");
+            }
+            None => {
+                self.tracking
+                    .borrow_mut()
+                    .push_hook(HookState::Tick(this_run));
+                None
+            }
+        };
+        if self.auto_track.get() {
+            self.track_component::<C>(entity);
+        }
+        let Some(last_run) = last_run else {
+            return false;
+        };
+        self.world
+            .get_entity(entity)
+            .and_then(|e| e.get_change_ticks::<C>())
+            .is_some_and(|ticks| ticks.is_changed(last_run, this_run))
+    }
+
     /// Return a reference to the Component `C` on the owner entity of the current
     /// context, or one of it's ancestors. This searches up the entity tree until it finds
     /// a component of the given type. If found, the component is added to the current tracking
@@ -432,6 +484,62 @@ impl<'p, 'w> Cx<'p, 'w> {
         }
     }
 
+    /// Run a closure with automatic dependency tracking turned off. Resources, components and
+    /// mutables which are read within the closure do not subscribe the current scope, so
+    /// changes to them will not cause a rebuild. Dependencies can still be added explicitly
+    /// with [`track_resource`], [`track_component`] and [`track_mutable`]:
+    ///
+    /// ```ignore
+    /// let selected = cx.track_explicit(|cx| {
+    ///     // Only re-render when the selection changes, not when the rest of the
+    ///     // editor state does.
+    ///     cx.track_component::<Selection>(editor);
+    ///     cx.use_component::<EditorState>(editor).unwrap().is_selected(item)
+    /// });
+    /// ```
+    ///
+    /// Hooks such as [`create_mutable`] can still be called within the closure.
+    ///
+    /// [`track_resource`]: Cx::track_resource
+    /// [`track_component`]: Cx::track_component
+    /// [`track_mutable`]: Cx::track_mutable
+    /// [`create_mutable`]: Cx::create_mutable
+    pub fn track_explicit<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let prev = self.auto_track.replace(false);
+        let result = f(self);
+        self.auto_track.set(prev);
+        result
+    }
+
+    /// Add a resource as a dependency of the current scope without reading it.
+    pub fn track_resource<T: Resource>(&self) {
+        self.tracking.borrow_mut().track_resource::<T>(self.world);
+    }
+
+    /// Add a component of the given entity as a dependency of the current scope without
+    /// reading it. The scope will also react if the component is added or removed.
+    pub fn track_component<C: Component>(&self, entity: Entity) {
+        let cid = self
+            .world
+            .components()
+            .component_id::<C>()
+            .unwrap_or_else(|| panic!("Unknown component type: {}", std::any::type_name::<C>()));
+        let exists = self
+            .world
+            .get_entity(entity)
+            .is_some_and(|e| e.contains::<C>());
+        self.tracking
+            .borrow_mut()
+            .track_component_id(entity, cid, exists);
+    }
+
+    /// Add a mutable as a dependency of the current scope without reading it.
+    pub fn track_mutable<T>(&self, mutable: &Mutable<T>) {
+        self.tracking
+            .borrow_mut()
+            .track_component_id(mutable.cell, mutable.component, true);
+    }
+
     /// Add a cleanup function which is run once before the next reaction, or when the owner
     /// entity for this context is despawned.
     pub fn on_cleanup(&mut self, cleanup: impl FnOnce(&mut DeferredWorld) + Send + Sync + 'static) {
@@ -444,9 +552,9 @@ impl<'p, 'w> ReadMutable for Cx<'p, 'w> {
     where
         T: Send + Sync + Copy + 'static,
     {
-        self.tracking
-            .borrow_mut()
-            .track_component_id(mutable.cell, mutable.component, true);
+        if self.auto_track.get() {
+            self.track_mutable(mutable);
+        }
         self.world.read_mutable(mutable)
     }
 
@@ -454,9 +562,9 @@ impl<'p, 'w> ReadMutable for Cx<'p, 'w> {
     where
         T: Send + Sync + Clone + 'static,
     {
-        self.tracking
-            .borrow_mut()
-            .track_component_id(mutable.cell, mutable.component, true);
+        if self.auto_track.get() {
+            self.track_mutable(mutable);
+        }
         self.world.read_mutable_clone(mutable)
     }
 
@@ -464,9 +572,9 @@ impl<'p, 'w> ReadMutable for Cx<'p, 'w> {
     where
         T: Send + Sync + 'static,
     {
-        self.tracking
-            .borrow_mut()
-            .track_component_id(mutable.cell, mutable.component, true);
+        if self.auto_track.get() {
+            self.track_mutable(mutable);
+        }
         self.world.read_mutable_as_ref(mutable)
     }
 
@@ -474,9 +582,9 @@ impl<'p, 'w> ReadMutable for Cx<'p, 'w> {
     where
         T: Send + Sync + 'static,
     {
-        self.tracking
-            .borrow_mut()
-            .track_component_id(mutable.cell, mutable.component, true);
+        if self.auto_track.get() {
+            self.track_mutable(mutable);
+        }
         self.world.read_mutable_map(mutable, f)
     }
 }
@@ -503,3 +611,36 @@ impl<'p, 'w> WriteMutable for Cx<'p, 'w> {
         self.world.update_mutable(mutable, updater);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component)]
+    struct Counter(i32);
+
+    #[test]
+    fn test_use_component_changed() {
+        let mut world = World::default();
+        let mut scope = TrackingScope::new(world.change_tick());
+        let owner = world.spawn_empty().id();
+        let target = world.spawn(Counter(0)).id();
+
+        // Not reported as changed on the first build.
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        assert!(!cx.use_component_changed::<Counter>(target));
+
+        // Mutate the component, then rebuild.
+        world.increment_change_tick();
+        world.get_mut::<Counter>(target).unwrap().0 = 1;
+        scope.rewind_hooks();
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        assert!(cx.use_component_changed::<Counter>(target));
+
+        // Rebuild again without mutating the component.
+        world.increment_change_tick();
+        scope.rewind_hooks();
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        assert!(!cx.use_component_changed::<Counter>(target));
+    }
+}
@@ -0,0 +1,199 @@
+use bevy::core::Name;
+use bevy::ecs::world::{DeferredWorld, World};
+use bevy::prelude::Entity;
+
+use crate::{Cx, NodeSpan, OnMount, OnUnmount, View};
+
+/// A reusable modification which can be applied to the display nodes of any [`View`], such as
+/// adding a debug outline, attaching an analytics event handler, or inserting a drag handle.
+/// Decorators are applied with [`DecorateView::decorate`].
+///
+/// `apply` is called for every output node of the decorated view each time the view is built,
+/// rebuilt or re-attached, so it should be idempotent, for example by inserting components
+/// rather than spawning new entities. When a node is no longer part of the view's output,
+/// `remove` is called to undo the decoration. Nodes which are despawned along with the view
+/// are not passed to `remove`.
+///
+/// Any function or closure taking `(&mut World, Entity)` can be used as a decorator.
+pub trait ViewDecorator: Send + Sync + 'static {
+    /// Apply the decoration to a display node.
+    fn apply(&self, world: &mut World, node: Entity);
+
+    /// Remove the decoration from a display node which is no longer part of the view's output.
+    #[allow(unused)]
+    fn remove(&self, world: &mut World, node: Entity) {}
+}
+
+impl<F: Fn(&mut World, Entity) + Send + Sync + 'static> ViewDecorator for F {
+    fn apply(&self, world: &mut World, node: Entity) {
+        (self)(world, node)
+    }
+}
+
+/// A [`View`] whose output nodes are modified by a [`ViewDecorator`].
+pub struct Decorated<V: View, D: ViewDecorator> {
+    inner: V,
+    decorator: D,
+}
+
+impl<V: View, D: ViewDecorator> Decorated<V, D> {
+    /// Construct a new `Decorated` view.
+    pub fn new(inner: V, decorator: D) -> Self {
+        Self { inner, decorator }
+    }
+
+    /// Apply the decorator to all of the current output nodes, and remove it from nodes
+    /// which were previously decorated but which are no longer part of the output.
+    fn apply(&self, world: &mut World, state: &mut (V::State, NodeSpan)) {
+        let nodes = NodeSpan::from_view(&self.inner, world, &state.0);
+        for node in state.1.diff(&nodes).removed {
+            if world.get_entity(node).is_some() {
+                self.decorator.remove(world, node);
+            }
+        }
+        for node in nodes.iter() {
+            self.decorator.apply(world, node);
+        }
+        state.1 = nodes;
+    }
+}
+
+impl<V: View, D: ViewDecorator> View for Decorated<V, D> {
+    /// Inner state, and the list of nodes which have been decorated.
+    type State = (V::State, NodeSpan);
+
+    fn nodes(&self, world: &World, state: &Self::State, out: &mut Vec<Entity>) {
+        self.inner.nodes(world, &state.0, out);
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let mut state = (self.inner.build(cx), NodeSpan::new());
+        self.apply(cx.world_mut(), &mut state);
+        state
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        let changed = self.inner.rebuild(cx, &mut state.0);
+        self.apply(cx.world_mut(), state);
+        changed
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        let changed = self.inner.attach_children(world, &mut state.0);
+        self.apply(world, state);
+        changed
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        self.inner.raze(world, &mut state.0);
+    }
+}
+
+/// A [`View`] which inserts a [`Name`] component on each of its output nodes, so that they can
+/// be identified in entity inspectors and logs. If the view produces a single node, it is given
+/// the name as-is; if it produces several (for example, the items of a [`For`]), each node is
+/// named with its index appended: `"inspector/row[0]"`, `"inspector/row[1]"` and so on.
+///
+/// [`For`]: crate::For
+pub struct Named<V: View> {
+    inner: V,
+    name: String,
+}
+
+impl<V: View> Named<V> {
+    /// Construct a new `Named` view.
+    pub fn new(inner: V, name: impl Into<String>) -> Self {
+        Self {
+            inner,
+            name: name.into(),
+        }
+    }
+
+    /// Insert names on the output nodes, if either the name or the list of nodes has changed.
+    fn apply(&self, world: &mut World, state: &mut (V::State, Vec<Entity>, String)) {
+        let mut nodes: Vec<Entity> = Vec::new();
+        self.inner.nodes(world, &state.0, &mut nodes);
+        if nodes == state.1 && self.name == state.2 {
+            return;
+        }
+        let indexed = nodes.len() > 1;
+        for (index, node) in nodes.iter().enumerate() {
+            let name = if indexed {
+                format!("{}[{}]", self.name, index)
+            } else {
+                self.name.clone()
+            };
+            world.entity_mut(*node).insert(Name::new(name));
+        }
+        state.1 = nodes;
+        state.2.clone_from(&self.name);
+    }
+}
+
+impl<V: View> View for Named<V> {
+    /// Inner state, the list of nodes which have been named, and the name they were given.
+    type State = (V::State, Vec<Entity>, String);
+
+    fn nodes(&self, world: &World, state: &Self::State, out: &mut Vec<Entity>) {
+        self.inner.nodes(world, &state.0, out);
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let mut state = (self.inner.build(cx), Vec::new(), String::new());
+        self.apply(cx.world_mut(), &mut state);
+        state
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        let changed = self.inner.rebuild(cx, &mut state.0);
+        self.apply(cx.world_mut(), state);
+        changed
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        let changed = self.inner.attach_children(world, &mut state.0);
+        self.apply(world, state);
+        changed
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        self.inner.raze(world, &mut state.0);
+    }
+}
+
+/// Extension trait which adds the `.decorate()`, `.named()`, `.on_mount()` and `.on_unmount()`
+/// methods to all views.
+pub trait DecorateView: View + Sized {
+    /// Apply a [`ViewDecorator`] to the output nodes of this view.
+    fn decorate<D: ViewDecorator>(self, decorator: D) -> Decorated<Self, D> {
+        Decorated::new(self, decorator)
+    }
+
+    /// Set the debug name of the output nodes of this view. See [`Named`].
+    ///
+    /// Note that [`Element`] has its own `.named()` method, which names both the display
+    /// node and the view entity.
+    ///
+    /// [`Element`]: crate::Element
+    fn named(self, name: impl Into<String>) -> Named<Self> {
+        Named::new(self, name)
+    }
+
+    /// Call a function once when this view is first built. See [`OnMount`].
+    fn on_mount<F: Fn(&mut World, &[Entity]) + Send + Sync + 'static>(
+        self,
+        on_mount: F,
+    ) -> OnMount<Self, F> {
+        OnMount::new(self, on_mount)
+    }
+
+    /// Call a function when this view is razed. See [`OnUnmount`].
+    fn on_unmount<F: Fn(&mut World) + Send + Sync + 'static>(
+        self,
+        on_unmount: F,
+    ) -> OnUnmount<Self, F> {
+        OnUnmount::new(self, on_unmount)
+    }
+}
+
+impl<V: View> DecorateView for V {}
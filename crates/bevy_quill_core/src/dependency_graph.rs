@@ -0,0 +1,261 @@
+use std::fmt::Write;
+
+use bevy::{
+    core::Name,
+    ecs::component::ComponentId,
+    hierarchy::Parent,
+    prelude::{Entity, World},
+    utils::get_short_name,
+};
+
+use crate::TrackingScope;
+
+/// A dependency of a tracking scope on a component of a specific entity. Mutables appear as
+/// dependencies on their `MutableCell` component.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ComponentDependency {
+    /// The entity which holds the component.
+    pub entity: Entity,
+    /// The short type name of the component.
+    pub component: String,
+}
+
+/// The dependencies of a single tracking scope.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScopeDependencies {
+    /// The entity which holds the [`TrackingScope`].
+    pub entity: Entity,
+    /// The debug name of the scope entity, if it has one.
+    pub name: Option<String>,
+    /// The parent of the scope entity, if any.
+    pub parent: Option<Entity>,
+    /// The short type names of the resources that the scope depends on.
+    pub resources: Vec<String>,
+    /// The components that the scope depends on.
+    pub components: Vec<ComponentDependency>,
+}
+
+/// A snapshot of which tracking scopes depend on which resources, components and mutables.
+/// This is intended for debugging reactivity problems, such as a view which re-renders too
+/// often because it subscribes to a frequently-changing resource. The graph can be exported
+/// in Graphviz DOT format or as JSON:
+///
+/// ```ignore
+/// let graph = DependencyGraph::capture(world);
+/// std::fs::write("deps.dot", graph.to_dot()).unwrap();
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct DependencyGraph {
+    /// All tracking scopes, ordered by entity.
+    pub scopes: Vec<ScopeDependencies>,
+}
+
+impl DependencyGraph {
+    /// Capture the current dependencies of every tracking scope in the world.
+    pub fn capture(world: &World) -> Self {
+        let type_name = |id: ComponentId| {
+            world
+                .components()
+                .get_info(id)
+                .map_or_else(|| format!("{:?}", id), |info| get_short_name(info.name()))
+        };
+        let mut scopes: Vec<ScopeDependencies> = world
+            .iter_entities()
+            .filter_map(|entt| {
+                let scope = entt.get::<TrackingScope>()?;
+                let mut resources: Vec<String> = scope.resource_deps().map(type_name).collect();
+                resources.sort();
+                let mut components: Vec<ComponentDependency> = scope
+                    .component_deps()
+                    .map(|(entity, id)| ComponentDependency {
+                        entity,
+                        component: type_name(id),
+                    })
+                    .collect();
+                components.sort_by(|a, b| (a.entity, &a.component).cmp(&(b.entity, &b.component)));
+                Some(ScopeDependencies {
+                    entity: entt.id(),
+                    name: entt.get::<Name>().map(|name| name.as_str().to_string()),
+                    parent: entt.get::<Parent>().map(|parent| parent.get()),
+                    resources,
+                    components,
+                })
+            })
+            .collect();
+        scopes.sort_by_key(|scope| scope.entity);
+        Self { scopes }
+    }
+
+    /// Export the graph in Graphviz DOT format. Scopes are drawn as boxes, resources as
+    /// ellipses and components as notes; dashed edges connect scopes to their parent scope.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph dependencies {\n    rankdir=LR;\n");
+        let mut resources: Vec<&str> = Vec::new();
+        let mut components: Vec<&ComponentDependency> = Vec::new();
+        for scope in self.scopes.iter() {
+            let label = match scope.name {
+                Some(ref name) => format!("{} {}", scope.entity, name),
+                None => scope.entity.to_string(),
+            };
+            writeln!(
+                out,
+                "    \"{}\" [shape=box, label=\"{}\"];",
+                scope.entity,
+                escape(&label)
+            )
+            .unwrap();
+            if let Some(parent) = scope.parent {
+                if self.scopes.iter().any(|s| s.entity == parent) {
+                    writeln!(
+                        out,
+                        "    \"{}\" -> \"{}\" [style=dashed];",
+                        parent, scope.entity
+                    )
+                    .unwrap();
+                }
+            }
+            for resource in scope.resources.iter() {
+                writeln!(
+                    out,
+                    "    \"{}\" -> \"res:{}\";",
+                    scope.entity,
+                    escape(resource)
+                )
+                .unwrap();
+                if !resources.contains(&resource.as_str()) {
+                    resources.push(resource);
+                }
+            }
+            for component in scope.components.iter() {
+                writeln!(
+                    out,
+                    "    \"{}\" -> \"cmp:{}:{}\";",
+                    scope.entity,
+                    component.entity,
+                    escape(&component.component)
+                )
+                .unwrap();
+                if !components.contains(&component) {
+                    components.push(component);
+                }
+            }
+        }
+        for resource in resources {
+            let resource = escape(resource);
+            writeln!(
+                out,
+                "    \"res:{}\" [shape=ellipse, label=\"{}\"];",
+                resource, resource
+            )
+            .unwrap();
+        }
+        for component in components {
+            let name = escape(&component.component);
+            writeln!(
+                out,
+                "    \"cmp:{}:{}\" [shape=note, label=\"{} {}\"];",
+                component.entity, name, component.entity, name
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Export the graph as a JSON document of the form
+    /// `{"scopes": [{"entity", "name", "parent", "resources", "components"}]}`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"scopes\":[");
+        for (i, scope) in self.scopes.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(out, "{{\"entity\":\"{}\",\"name\":", scope.entity).unwrap();
+            match scope.name {
+                Some(ref name) => write!(out, "\"{}\"", escape(name)).unwrap(),
+                None => out.push_str("null"),
+            }
+            out.push_str(",\"parent\":");
+            match scope.parent {
+                Some(parent) => write!(out, "\"{}\"", parent).unwrap(),
+                None => out.push_str("null"),
+            }
+            out.push_str(",\"resources\":[");
+            for (j, resource) in scope.resources.iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                write!(out, "\"{}\"", escape(resource)).unwrap();
+            }
+            out.push_str("],\"components\":[");
+            for (j, component) in scope.components.iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                write!(
+                    out,
+                    "{{\"entity\":\"{}\",\"component\":\"{}\"}}",
+                    component.entity,
+                    escape(&component.component)
+                )
+                .unwrap();
+            }
+            out.push_str("]}");
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+/// Escape a string for use inside a quoted DOT or JSON string.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Resource;
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Counter;
+
+    #[test]
+    fn test_capture_and_export() {
+        let mut world = World::default();
+        world.init_resource::<Counter>();
+        let mut scope = TrackingScope::new(world.change_tick());
+        scope.track_resource::<Counter>(&world);
+        let entity = world.spawn((scope, Name::new("Root \"view\""))).id();
+
+        let graph = DependencyGraph::capture(&world);
+        assert_eq!(graph.scopes.len(), 1);
+        assert_eq!(graph.scopes[0].entity, entity);
+        assert_eq!(graph.scopes[0].resources, vec!["Counter".to_string()]);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains(&format!("\"{}\" -> \"res:Counter\";", entity)));
+        assert!(dot.contains("Root \\\"view\\\""));
+
+        let json = graph.to_json();
+        assert_eq!(
+            json,
+            format!(
+                "{{\"scopes\":[{{\"entity\":\"{}\",\"name\":\"Root \\\"view\\\"\",\"parent\":null,\
+                 \"resources\":[\"Counter\"],\"components\":[]}}]}}",
+                entity
+            )
+        );
+    }
+}
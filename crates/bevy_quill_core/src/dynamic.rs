@@ -3,7 +3,7 @@ use std::sync::Arc;
 use bevy::ecs::world::{DeferredWorld, World};
 use bevy::prelude::Entity;
 
-use crate::{AnyView, BoxedState, View, ViewChild};
+use crate::{state_pool::release_state, AnyView, BoxedState, View, ViewChild};
 
 /// A view which understands that it's children may change type. When this happens,
 /// the old children are razed and the new children are built.
@@ -46,7 +46,8 @@ impl View for Dynamic {
             let view = self.children.0.clone();
             let new_state = view.build(cx);
             state.0 = view;
-            state.1 = new_state;
+            let old_state = std::mem::replace(&mut state.1, new_state);
+            release_state(cx.world_mut(), old_state);
             true
         }
     }
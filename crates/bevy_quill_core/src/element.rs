@@ -7,6 +7,7 @@ use crate::{
     cx::Cx,
     effects::{self, AppendEffect, CallbackEffect, EffectTuple, EntityEffect},
     insert::{ConditionalInsertComponentEffect, InsertBundleEffect, StaticInsertBundleEffect},
+    ownership::{self, OwnedBy},
     style::{ApplyDynamicStylesEffect, ApplyStaticStylesEffect},
     view::View,
 };
@@ -216,7 +217,11 @@ impl<B: Bundle + Default, C: View, E: EffectTuple + 'static> View for Element<B,
             }
             None => cx
                 .world_mut()
-                .spawn((B::default(), Name::new(self.debug_name.clone())))
+                .spawn((
+                    B::default(),
+                    Name::new(self.debug_name.clone()),
+                    OwnedBy(owner),
+                ))
                 .id(),
         };
 
@@ -263,7 +268,14 @@ impl<B: Bundle + Default, C: View, E: EffectTuple + 'static> View for Element<B,
         self.children.attach_children(world, &mut state.1);
         let mut nodes: Vec<Entity> = Vec::new();
         self.children.nodes(world, &state.1, &mut nodes);
-        world.entity_mut(state.0).replace_children(&nodes.to_vec());
+        nodes.retain(|node| {
+            let stale = ownership::is_stale(world, *node);
+            if stale {
+                warn!("Element {} has stale child node {}", self.debug_name, node);
+            }
+            !stale
+        });
+        world.entity_mut(state.0).replace_children(&nodes);
         false
     }
 }
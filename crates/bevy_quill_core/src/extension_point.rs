@@ -0,0 +1,193 @@
+use std::{hash::Hash, sync::Arc};
+
+use bevy::{prelude::Resource, utils::HashMap};
+
+use crate::{Cx, For, IntoViewChild, MountHandle, View, ViewChild, ViewHandle, ViewTemplate};
+
+/// Identifies a contribution registered with an [`ExtensionRegistry`], so that it can be
+/// unregistered later.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ContributionId(u64);
+
+type ContributionFactory = Arc<dyn Fn() -> ViewChild + Send + Sync>;
+
+/// A UI fragment contributed to an extension point.
+#[derive(Clone)]
+pub struct Contribution {
+    id: ContributionId,
+    order: i32,
+    factory: ContributionFactory,
+}
+
+impl Contribution {
+    /// The id of this contribution.
+    pub fn id(&self) -> ContributionId {
+        self.id
+    }
+
+    /// Create the view for this contribution.
+    pub fn create(&self) -> ViewChild {
+        (self.factory)()
+    }
+}
+
+impl PartialEq for Contribution {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && Arc::ptr_eq(&self.factory, &other.factory)
+    }
+}
+
+/// Resource which holds the UI contributions for a family of extension points, indexed by a key
+/// of type `K`. Host UIs display the contributions for a key using [`ExtensionPoint`], while
+/// plugins add contributions with `register`. For example, an editor might define:
+///
+/// ```ignore
+/// #[derive(Clone, PartialEq, Eq, Hash)]
+/// enum EditorSlot {
+///     Toolbar,
+///     InspectorSection,
+/// }
+///
+/// app.init_resource::<ExtensionRegistry<EditorSlot>>();
+/// ```
+///
+/// and a plugin can then add a toolbar button:
+///
+/// ```ignore
+/// world
+///     .resource_mut::<ExtensionRegistry<EditorSlot>>()
+///     .register(EditorSlot::Toolbar, || Button::new().children("Export").into_view_child());
+/// ```
+#[derive(Resource)]
+pub struct ExtensionRegistry<K: Hash + Eq + Send + Sync + 'static> {
+    next_id: u64,
+    entries: HashMap<K, Vec<Contribution>>,
+}
+
+impl<K: Hash + Eq + Send + Sync + 'static> Default for ExtensionRegistry<K> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            entries: HashMap::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Send + Sync + 'static> ExtensionRegistry<K> {
+    /// Register a factory which creates the contributed view for the given key.
+    pub fn register<F: Fn() -> ViewChild + Send + Sync + 'static>(
+        &mut self,
+        key: K,
+        factory: F,
+    ) -> ContributionId {
+        self.register_ordered(key, 0, factory)
+    }
+
+    /// Register a factory with an explicit sort order. Contributions with a lower order are
+    /// displayed first; contributions with the same order are displayed in registration order.
+    pub fn register_ordered<F: Fn() -> ViewChild + Send + Sync + 'static>(
+        &mut self,
+        key: K,
+        order: i32,
+        factory: F,
+    ) -> ContributionId {
+        let id = ContributionId(self.next_id);
+        self.next_id += 1;
+        let entries = self.entries.entry(key).or_default();
+        let index = entries.partition_point(|c| c.order <= order);
+        entries.insert(
+            index,
+            Contribution {
+                id,
+                order,
+                factory: Arc::new(factory),
+            },
+        );
+        id
+    }
+
+    /// Register a view which has already been built, such as one spawned by a plugin with
+    /// [`ViewHandle::spawn`]. The handle is mounted wherever the extension point is displayed,
+    /// so it should only be used with keys that are displayed in a single place.
+    pub fn register_handle(&mut self, key: K, handle: ViewHandle) -> ContributionId {
+        self.register(key, move || MountHandle::new(handle).into_view_child())
+    }
+
+    /// Remove a contribution. Returns false if it was not registered.
+    pub fn unregister(&mut self, id: ContributionId) -> bool {
+        for entries in self.entries.values_mut() {
+            if let Some(index) = entries.iter().position(|c| c.id == id) {
+                entries.remove(index);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the contributions for the given key, in display order.
+    pub fn contributions(&self, key: &K) -> &[Contribution] {
+        self.entries
+            .get(key)
+            .map_or(&[], |entries| entries.as_slice())
+    }
+}
+
+/// Displays all of the contributions registered for a key in an [`ExtensionRegistry`]. The
+/// contributions are inserted in place, without a wrapping element, so the host controls the
+/// layout. The display is updated whenever contributions are registered or unregistered.
+#[derive(Clone, PartialEq)]
+pub struct ExtensionPoint<K: Hash + Eq + Clone + Send + Sync + 'static> {
+    key: K,
+}
+
+impl<K: Hash + Eq + Clone + Send + Sync + 'static> ExtensionPoint<K> {
+    /// Construct a new extension point which displays the contributions for `key`.
+    pub fn new(key: K) -> Self {
+        Self { key }
+    }
+}
+
+impl<K: Hash + Eq + Clone + Send + Sync + 'static> ViewTemplate for ExtensionPoint<K> {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let contributions = cx
+            .use_resource::<ExtensionRegistry<K>>()
+            .contributions(&self.key)
+            .to_vec();
+        For::each_keyed(contributions, Contribution::id, Contribution::create)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Entity, World};
+
+    use crate::{Element, TrackingScope};
+
+    use super::*;
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    enum Slot {
+        Toolbar,
+    }
+
+    #[test]
+    fn test_extension_point() {
+        let mut world = World::default();
+        let mut registry = ExtensionRegistry::<Slot>::default();
+        registry.register(Slot::Toolbar, || Element::<()>::new().into_view_child());
+        registry.register(Slot::Toolbar, || Element::<()>::new().into_view_child());
+        world.insert_resource(registry);
+
+        let mut scope = TrackingScope::new(world.change_tick());
+        let owner = world.spawn_empty().id();
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        let point = ExtensionPoint::new(Slot::Toolbar);
+        let state = point.build(&mut cx);
+
+        let mut nodes: Vec<Entity> = Vec::new();
+        point.nodes(&world, &state, &mut nodes);
+        assert_eq!(nodes.len(), 2);
+    }
+}
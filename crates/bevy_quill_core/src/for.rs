@@ -1,11 +1,18 @@
-use crate::{ForIndex, View};
+use std::{hash::Hash, ops::Range};
+
+use bevy::{ecs::query::QueryFilter, prelude::Entity};
+
+use crate::{Cx, ForIndex, ForKeyed, View};
 
 use super::ForEach;
 
 /// A namespace that contains constructor functions for various kinds of for-loops:
 /// * `For::each()`
 /// * `For::each_cmp()`
+/// * `For::each_keyed()`
 /// * `For::index()`
+/// * `For::range()`
+/// * `For::from_query()`
 pub struct For;
 
 impl For {
@@ -24,6 +31,35 @@ impl For {
         ForIndex::new(items, each_fn)
     }
 
+    /// Construct an index for loop over a range of integers. The callback is called once for
+    /// each index in the range. Like [`For::index`], child views are matched by position, so
+    /// changing the end of the range only adds or removes views at the end.
+    pub fn range<V: View, F: Send + Sync + Clone + 'static + Fn(usize) -> V>(
+        range: Range<usize>,
+        each_fn: F,
+    ) -> ForIndex<usize, V, impl Fn(&usize, usize) -> V + Send + Sync + Clone + 'static, ()> {
+        let items: Vec<usize> = range.collect();
+        ForIndex::new(&items, move |index: &usize, _| each_fn(*index))
+    }
+
+    /// Construct a keyed for loop over the entities which match the query filter `F`, in
+    /// entity order. Child views are matched by entity id, so adding or removing an entity
+    /// only adds or removes the corresponding view.
+    ///
+    /// The query is evaluated when the enclosing template runs, and does not by itself
+    /// subscribe to changes in the set of matching entities; the template should depend on
+    /// some other signal, such as a resource, which changes when entities are added or
+    /// removed.
+    pub fn from_query<F: QueryFilter, V: View, EachFn: Fn(&Entity) -> V + Send>(
+        cx: &mut Cx,
+        each: EachFn,
+    ) -> ForEach<Entity, Vec<Entity>, V, impl Fn(&Entity, &Entity) -> bool, EachFn, ()> {
+        let world = cx.world_mut();
+        let mut entities: Vec<Entity> = world.query_filtered::<Entity, F>().iter(world).collect();
+        entities.sort();
+        ForEach::new(entities, |a, b| a == b, each)
+    }
+
     /// Transforms an iterator of items into an array of child views, one for each element in
     /// the original sequence. The order of child views is determined by the order of the
     /// input items. During rebuilds, the list of child views may be re-ordered based on a
@@ -58,4 +94,29 @@ impl For {
     ) -> ForEach<Item, Iter, V, impl Fn(&Item, &Item) -> bool, F, ()> {
         ForEach::new(iter, |a, b| a == b, each)
     }
+
+    /// Transforms an iterator of items into an array of child views, one for each element in
+    /// the original sequence, where each item is identified by the key returned by `key`.
+    /// During rebuilds, child views are matched by key rather than by value or position, so
+    /// inserting, removing or re-ordering items preserves the view state of the other items,
+    /// and an item whose key is unchanged but whose value has changed is patched in place
+    /// rather than rebuilt from scratch. Items whose value is unchanged are not re-rendered.
+    ///
+    /// ```ignore
+    /// For::each_keyed(todos, |todo| todo.id, |todo| TodoRow { todo: todo.clone() })
+    /// ```
+    pub fn each_keyed<
+        Item: Clone + PartialEq + Send + Sync,
+        Key: Clone + Hash + Eq + Send + Sync,
+        Iter: IntoIterator<Item = Item> + Clone + Send + Sync,
+        V: View,
+        K: Fn(&Item) -> Key + Send,
+        F: Fn(&Item) -> V + Send,
+    >(
+        iter: Iter,
+        key: K,
+        each: F,
+    ) -> ForKeyed<Item, Key, Iter, V, K, F, ()> {
+        ForKeyed::new(iter, key, each)
+    }
 }
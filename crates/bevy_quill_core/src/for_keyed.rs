@@ -0,0 +1,265 @@
+use std::hash::Hash;
+
+use bevy::{
+    ecs::world::{DeferredWorld, World},
+    prelude::Entity,
+    utils::HashMap,
+};
+
+use crate::{Cx, View};
+
+pub struct KeyedListItem<Key, Value, V: View> {
+    key: Key,
+    value: Value,
+    view: Option<V>,
+    state: Option<V::State>,
+}
+
+impl<Key, Value, V: View> KeyedListItem<Key, Value, V> {
+    fn nodes(&self, world: &World, out: &mut Vec<Entity>) {
+        self.view
+            .as_ref()
+            .unwrap()
+            .nodes(world, self.state.as_ref().unwrap(), out);
+    }
+
+    fn raze(&mut self, world: &mut DeferredWorld) {
+        if let (Some(ref view), Some(mut state)) = (self.view.take(), self.state.take()) {
+            view.raze(world, &mut state);
+        }
+    }
+}
+
+/// For each key in `next`, find the index of the item in `prev` which has the same key, if
+/// any. Each previous item is matched at most once, so if a key appears more than once, the
+/// occurrences are matched in order, and any extra occurrences in `next` are unmatched.
+fn match_keys<Key: Hash + Eq>(prev: &[Key], next: &[Key]) -> Vec<Option<usize>> {
+    let mut indices: HashMap<&Key, Vec<usize>> = HashMap::default();
+    for (index, key) in prev.iter().enumerate().rev() {
+        indices.entry(key).or_default().push(index);
+    }
+    next.iter()
+        .map(|key| indices.get_mut(key).and_then(|stack| stack.pop()))
+        .collect()
+}
+
+#[doc(hidden)]
+pub struct ForKeyed<
+    Item: Send + Clone,
+    Key: Send + Clone,
+    Iter: IntoIterator<Item = Item> + Clone,
+    V: View,
+    K: Fn(&Item) -> Key + Send,
+    F: Fn(&Item) -> V + Send,
+    FB: View,
+> {
+    iter: Iter,
+    key: K,
+    each: F,
+    fallback: Option<FB>,
+}
+
+impl<
+        Item: Send + Clone,
+        Key: Send + Clone,
+        Iter: IntoIterator<Item = Item> + Clone,
+        V: View,
+        K: Fn(&Item) -> Key + Send,
+        F: Fn(&Item) -> V + Send,
+    > ForKeyed<Item, Key, Iter, V, K, F, ()>
+{
+    pub fn new(iter: Iter, key: K, each: F) -> Self {
+        Self {
+            iter,
+            key,
+            each,
+            fallback: None,
+        }
+    }
+}
+
+impl<
+        Item: Send + Clone,
+        Key: Send + Clone,
+        Iter: IntoIterator<Item = Item> + Clone,
+        V: View,
+        K: Fn(&Item) -> Key + Send,
+        F: Fn(&Item) -> V + Send,
+        FB: View,
+    > ForKeyed<Item, Key, Iter, V, K, F, FB>
+{
+    pub fn with_fallback<FB2: View>(
+        self,
+        fallback: FB2,
+    ) -> ForKeyed<Item, Key, Iter, V, K, F, FB2> {
+        ForKeyed::<Item, Key, Iter, V, K, F, FB2> {
+            iter: self.iter,
+            key: self.key,
+            each: self.each,
+            fallback: Some(fallback),
+        }
+    }
+}
+
+impl<
+        Item: Send + Sync + Clone + PartialEq + 'static,
+        Key: Send + Sync + Clone + Hash + Eq + 'static,
+        Iter: IntoIterator<Item = Item> + Clone + Send + Sync + 'static,
+        V: View,
+        K: Fn(&Item) -> Key + Send + Sync + 'static,
+        F: Fn(&Item) -> V + Send + Sync + 'static,
+        FB: View,
+    > View for ForKeyed<Item, Key, Iter, V, K, F, FB>
+{
+    type State = (Vec<KeyedListItem<Key, Item, V>>, Option<FB::State>);
+
+    fn nodes(&self, world: &World, state: &Self::State, out: &mut Vec<Entity>) {
+        state.0.iter().for_each(|item| item.nodes(world, out));
+        if let Some(ref fallback) = self.fallback {
+            if let Some(ref fbstate) = state.1 {
+                fallback.nodes(world, fbstate, out);
+            }
+        }
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let mut state = (Vec::new(), None);
+        self.rebuild(cx, &mut state);
+        state
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        let items = self.iter.clone().into_iter().collect::<Vec<_>>();
+        let next_len = items.len();
+        let keys = items.iter().map(&self.key).collect::<Vec<_>>();
+        let prev_keys = state
+            .0
+            .iter()
+            .map(|item| item.key.clone())
+            .collect::<Vec<_>>();
+        let matches = match_keys(&prev_keys, &keys);
+
+        let mut changed = false;
+        let mut next_state: Vec<KeyedListItem<Key, Item, V>> = Vec::with_capacity(next_len);
+        let mut last_index: Option<usize> = None;
+        for ((item, key), matched) in items.into_iter().zip(keys).zip(matches) {
+            let Some(index) = matched else {
+                // A new key, so build a new view.
+                let view = (self.each)(&item);
+                let view_state = view.build(cx);
+                next_state.push(KeyedListItem {
+                    key,
+                    value: item,
+                    view: Some(view),
+                    state: Some(view_state),
+                });
+                changed = true;
+                continue;
+            };
+
+            // An existing key: move the view and its state, and patch it if the item changed.
+            if last_index.is_some_and(|last| index < last) {
+                changed = true;
+            }
+            last_index = Some(index);
+            let prev = &mut state.0[index];
+            let mut view = prev.view.take();
+            let mut view_state = prev.state.take();
+            if prev.value != item {
+                let next_view = (self.each)(&item);
+                changed |= next_view.rebuild(cx, view_state.as_mut().unwrap());
+                view = Some(next_view);
+            }
+            next_state.push(KeyedListItem {
+                key,
+                value: item,
+                view,
+                state: view_state,
+            });
+        }
+
+        // Raze the views whose keys are gone.
+        for prev in state.0.iter_mut() {
+            if prev.view.is_some() {
+                prev.raze(&mut DeferredWorld::from(cx.world_mut()));
+                changed = true;
+            }
+        }
+
+        // Handle fallback
+        if let Some(ref fallback) = self.fallback {
+            match state.1 {
+                // If there are > 0 items, destroy fallback if present.
+                Some(ref mut fb_ent) if next_len > 0 => {
+                    fallback.raze(&mut DeferredWorld::from(cx.world_mut()), fb_ent);
+                    state.1 = None;
+                    changed = true;
+                }
+
+                // If there are no items, render fallback unless already rendered.
+                None if next_len == 0 => {
+                    state.1 = Some(fallback.build(cx));
+                    changed = true;
+                }
+
+                // Otherwise, no change.
+                _ => {}
+            }
+        }
+
+        state.0 = next_state;
+        changed
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        let mut changed = false;
+        for child_state in state.0.iter_mut() {
+            if let Some(ref view) = child_state.view {
+                changed |= view.attach_children(world, child_state.state.as_mut().unwrap());
+            }
+        }
+        if let Some(ref mut fbstate) = state.1 {
+            changed |= self
+                .fallback
+                .as_ref()
+                .unwrap()
+                .attach_children(world, fbstate);
+        }
+        changed
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        for child_state in state.0.iter_mut() {
+            if let Some(ref view) = child_state.view {
+                view.raze(world, child_state.state.as_mut().unwrap());
+            }
+        }
+        if let Some(ref mut fbstate) = state.1 {
+            self.fallback.as_ref().unwrap().raze(world, fbstate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::match_keys;
+
+    #[test]
+    fn test_match_keys_reorder() {
+        let matches = match_keys(&["a", "b", "c"], &["c", "a", "b"]);
+        assert_eq!(matches, vec![Some(2), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_match_keys_insert_remove() {
+        // "b" is removed and "d" is inserted in the middle.
+        let matches = match_keys(&["a", "b", "c"], &["a", "d", "c"]);
+        assert_eq!(matches, vec![Some(0), None, Some(2)]);
+    }
+
+    #[test]
+    fn test_match_keys_duplicates() {
+        let matches = match_keys(&["a", "a", "b"], &["a", "b", "a", "a"]);
+        assert_eq!(matches, vec![Some(0), Some(2), Some(1), None]);
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+
+use crate::{Cx, Element, IntoViewChild, View, ViewChild, ViewTemplate};
+
+/// A widget which lays out its children in a CSS grid. The column and row templates, gaps
+/// and flow direction are set with typed builder methods, rather than raw style closures:
+///
+/// ```ignore
+/// Grid::new()
+///     .column_count(3)
+///     .gap(4.)
+///     .children(For::each(items, |item| ItemCard::new(item)))
+/// ```
+///
+/// Children are placed automatically in flow order; wrap a child in a [`GridItem`] to give it
+/// an explicit position or span. Since the layout is computed by Bevy, the grid re-flows
+/// whenever the list of children changes.
+#[derive(Clone, PartialEq)]
+pub struct Grid {
+    columns: Vec<ui::RepeatedGridTrack>,
+    rows: Vec<ui::RepeatedGridTrack>,
+    auto_rows: Vec<ui::GridTrack>,
+    auto_flow: ui::GridAutoFlow,
+    row_gap: ui::Val,
+    column_gap: ui::Val,
+    style: StyleHandle,
+    children: ViewChild,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            auto_rows: Vec::new(),
+            auto_flow: ui::GridAutoFlow::Row,
+            row_gap: ui::Val::Px(0.),
+            column_gap: ui::Val::Px(0.),
+            style: StyleHandle::default(),
+            children: Default::default(),
+        }
+    }
+}
+
+impl Grid {
+    /// Create a new grid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the column template.
+    pub fn columns(mut self, columns: Vec<ui::RepeatedGridTrack>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Set the column template to `count` columns of equal width.
+    pub fn column_count(mut self, count: u16) -> Self {
+        self.columns = vec![ui::RepeatedGridTrack::flex(count, 1.)];
+        self
+    }
+
+    /// Set the row template.
+    pub fn rows(mut self, rows: Vec<ui::RepeatedGridTrack>) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    /// Set the size of rows which are created implicitly, beyond the row template.
+    pub fn auto_rows(mut self, rows: Vec<ui::GridTrack>) -> Self {
+        self.auto_rows = rows;
+        self
+    }
+
+    /// Set the direction in which children are placed automatically.
+    pub fn auto_flow(mut self, flow: ui::GridAutoFlow) -> Self {
+        self.auto_flow = flow;
+        self
+    }
+
+    /// Set both the row and column gap.
+    pub fn gap(mut self, gap: impl LengthParam) -> Self {
+        self.row_gap = gap.to_val();
+        self.column_gap = self.row_gap;
+        self
+    }
+
+    /// Set the gap between rows.
+    pub fn row_gap(mut self, gap: impl LengthParam) -> Self {
+        self.row_gap = gap.to_val();
+        self
+    }
+
+    /// Set the gap between columns.
+    pub fn column_gap(mut self, gap: impl LengthParam) -> Self {
+        self.column_gap = gap.to_val();
+        self
+    }
+
+    /// Set additional styles to be applied to the grid.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the child views for this element.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+impl ViewTemplate for Grid {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .style_dyn(
+                |(columns, rows, auto_rows, auto_flow, row_gap, column_gap), sb| {
+                    sb.display(ui::Display::Grid)
+                        .grid_template_columns(columns)
+                        .grid_template_rows(rows)
+                        .grid_auto_rows(auto_rows)
+                        .grid_auto_flow(auto_flow)
+                        .row_gap(row_gap)
+                        .column_gap(column_gap);
+                },
+                (
+                    self.columns.clone(),
+                    self.rows.clone(),
+                    self.auto_rows.clone(),
+                    self.auto_flow,
+                    self.row_gap,
+                    self.column_gap,
+                ),
+            )
+            .style(self.style.clone())
+            .children(self.children.clone())
+    }
+}
+
+/// A child of a [`Grid`] with an explicit placement. Rows and columns are numbered from 1,
+/// as in CSS; negative numbers count from the end of the template.
+#[derive(Clone, PartialEq)]
+pub struct GridItem {
+    row: ui::GridPlacement,
+    column: ui::GridPlacement,
+    style: StyleHandle,
+    children: ViewChild,
+}
+
+impl Default for GridItem {
+    fn default() -> Self {
+        Self {
+            row: ui::GridPlacement::auto(),
+            column: ui::GridPlacement::auto(),
+            style: StyleHandle::default(),
+            children: Default::default(),
+        }
+    }
+}
+
+impl GridItem {
+    /// Create a new grid item, which is placed automatically.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place the item starting at the given row.
+    pub fn row(mut self, start: i16) -> Self {
+        self.row = self.row.set_start(start);
+        self
+    }
+
+    /// Set the number of rows which the item spans.
+    pub fn row_span(mut self, span: u16) -> Self {
+        self.row = self.row.set_span(span);
+        self
+    }
+
+    /// Place the item starting at the given column.
+    pub fn column(mut self, start: i16) -> Self {
+        self.column = self.column.set_start(start);
+        self
+    }
+
+    /// Set the number of columns which the item spans.
+    pub fn column_span(mut self, span: u16) -> Self {
+        self.column = self.column.set_span(span);
+        self
+    }
+
+    /// Set additional styles to be applied to the item.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the child views for this element.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+impl ViewTemplate for GridItem {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .style_dyn(
+                |(row, column), sb| {
+                    sb.grid_row(row).grid_column(column);
+                },
+                (self.row, self.column),
+            )
+            .style(self.style.clone())
+            .children(self.children.clone())
+    }
+}
@@ -0,0 +1,153 @@
+use std::{hash::Hash, marker::PhantomData};
+
+use bevy::{
+    prelude::{Resource, World},
+    utils::HashMap,
+};
+
+use crate::{mutable::MutableCell, Cx, Mutable};
+
+/// A reactive map in which each key can be subscribed to individually. This is useful for
+/// per-item state which is shared across many views, such as the selection state of items
+/// in a list or nodes in a graph: changing the state of one item only re-renders the views
+/// which read that item, rather than every view that reads the store.
+///
+/// The store is a resource, and must be added to the app before use:
+///
+/// ```ignore
+/// app.init_resource::<KeyedStore<Entity, bool>>();
+///
+/// // In a template:
+/// let selected = cx.use_keyed::<Entity, bool>(&self.node);
+///
+/// // In a system or callback:
+/// KeyedStore::<Entity, bool>::set(world, node, true);
+/// ```
+///
+/// Each key is backed by a mutable, which is created the first time the key is read or
+/// written. Keys which have never been written read as `V::default()`.
+#[derive(Resource)]
+pub struct KeyedStore<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Default + Clone + PartialEq + Send + Sync + 'static,
+{
+    cells: HashMap<K, Mutable<V>>,
+}
+
+impl<K, V> Default for KeyedStore<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Default + Clone + PartialEq + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            cells: HashMap::default(),
+        }
+    }
+}
+
+impl<K, V> KeyedStore<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Default + Clone + PartialEq + Send + Sync + 'static,
+{
+    /// Returns the mutable for the given key, creating it if it doesn't exist.
+    fn cell(world: &mut World, key: &K) -> Mutable<V> {
+        if let Some(cell) = world.resource::<Self>().cells.get(key) {
+            return *cell;
+        }
+        let component = world.init_component::<MutableCell<V>>();
+        let cell = world.spawn(MutableCell(V::default())).id();
+        let mutable = Mutable {
+            cell,
+            component,
+            marker: PhantomData,
+        };
+        world
+            .resource_mut::<Self>()
+            .cells
+            .insert(key.clone(), mutable);
+        mutable
+    }
+
+    /// Read the value for a key, without subscribing to it.
+    pub fn get(&self, world: &World, key: &K) -> V {
+        self.cells
+            .get(key)
+            .and_then(|cell| world.get::<MutableCell<V>>(cell.cell))
+            .map_or_else(V::default, |cell| cell.0.clone())
+    }
+
+    /// Set the value for a key. Views which read the key are only notified if the value
+    /// is different.
+    pub fn set(world: &mut World, key: K, value: V) {
+        let cell = Self::cell(world, &key).cell;
+        let mut entry = world.get_mut::<MutableCell<V>>(cell).unwrap();
+        if entry.0 != value {
+            entry.0 = value;
+        }
+    }
+
+    /// Reset all keys to the default value.
+    pub fn clear(world: &mut World) {
+        let cells: Vec<_> = world
+            .resource::<Self>()
+            .cells
+            .values()
+            .map(|cell| cell.cell)
+            .collect();
+        for cell in cells {
+            if let Some(mut entry) = world.get_mut::<MutableCell<V>>(cell) {
+                if entry.0 != V::default() {
+                    entry.0 = V::default();
+                }
+            }
+        }
+    }
+
+    /// Iterate over the keys which have been read or written.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.cells.keys()
+    }
+}
+
+impl<'p, 'w> Cx<'p, 'w> {
+    /// Read the value for a key in a [`KeyedStore`], subscribing the current scope to changes
+    /// to that key only.
+    pub fn use_keyed<K, V>(&mut self, key: &K) -> V
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        V: Default + Clone + PartialEq + Send + Sync + 'static,
+    {
+        let cell = KeyedStore::<K, V>::cell(self.world_mut(), key);
+        cell.get_clone(&*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TrackingScope;
+
+    use super::*;
+
+    #[test]
+    fn test_keyed_store() {
+        let mut world = World::default();
+        world.init_resource::<KeyedStore<u32, bool>>();
+        let owner = world.spawn_empty().id();
+        let mut scope = TrackingScope::new(world.change_tick());
+
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        assert!(!cx.use_keyed::<u32, bool>(&1));
+
+        world.increment_change_tick();
+        let tick = world.change_tick();
+        KeyedStore::<u32, bool>::set(&mut world, 2, true);
+        assert!(!scope.dependencies_changed(&world, tick));
+
+        KeyedStore::<u32, bool>::set(&mut world, 1, true);
+        assert!(scope.dependencies_changed(&world, tick));
+        assert!(world.resource::<KeyedStore<u32, bool>>().get(&world, &1));
+    }
+}
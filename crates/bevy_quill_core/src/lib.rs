@@ -1,81 +1,221 @@
 #![feature(impl_trait_in_assoc_type, associated_type_defaults)]
+mod bridge;
 mod callback;
 mod cond;
 mod cx;
+mod decorate;
+mod dependency_graph;
 mod dynamic;
 pub mod effects;
 mod element;
+mod extension_point;
 mod r#for;
 mod for_each;
 mod for_index;
+mod for_keyed;
+mod grid;
 pub mod insert;
+mod keyed_store;
 mod lcs;
+mod lifecycle;
+mod measure;
 mod mutable;
+mod node_span;
+mod ownership;
+mod persist;
+mod phases;
 mod portal;
+mod preload;
+mod root_schedule;
+mod scene_view;
+mod slots;
+mod stack;
+mod state_pool;
 mod style;
 mod switch;
+mod text_computed;
 mod text_view;
+mod time;
 mod tracking_scope;
+mod ui_definition;
 mod view;
 mod view_child;
+mod view_handle;
+mod view_registry;
 mod view_template;
+mod window;
+mod wrap_if;
 
 use bevy::{
-    app::{App, Plugin, Startup, Update},
-    prelude::IntoSystemConfigs,
+    app::{App, Plugin, PostUpdate, Startup, Update},
+    prelude::{IntoSystemConfigs, IntoSystemSetConfigs},
+    transform::{
+        systems::{propagate_transforms, sync_simple_transforms},
+        TransformSystem,
+    },
+    ui::{ui_layout_system, UiSystem},
 };
 use bevy_mod_stylebuilder::{StyleBuilderPlugin, StyleBuilderSystemSet};
 
 pub mod prelude {
     pub use super::QuillPlugin;
+    pub use crate::bridge::{RegisterUiBridge, UiCommand, UiMessage};
     pub use crate::callback::*;
     pub use crate::cond::Cond;
     pub use crate::cx::Cx;
     pub use crate::cx::EffectOptions;
+    pub use crate::decorate::{DecorateView, Decorated, Named, ViewDecorator};
     pub use crate::element::*;
+    pub use crate::extension_point::{ExtensionPoint, ExtensionRegistry};
     pub use crate::for_each::ForEach;
     pub use crate::for_index::ForIndex;
+    pub use crate::for_keyed::ForKeyed;
+    pub use crate::format_text;
+    pub use crate::grid::{Grid, GridItem};
+    pub use crate::keyed_store::KeyedStore;
+    pub use crate::lifecycle::{OnMount, OnUnmount};
     pub use crate::mutable::*;
+    pub use crate::node_span::{NodeSpan, NodeSpanDiff};
+    pub use crate::ownership::{despawn_owned, OwnedBy, OwnershipRegistry};
+    pub use crate::persist::{PersistPlugin, PersistedState};
+    pub use crate::phases::{LayoutPass, LayoutPassRequest, QuillSet};
+    pub use crate::preload::{Preload, PreloadProgress};
     pub use crate::r#for::For;
+    pub use crate::root_schedule::{InvalidateHandle, UpdateSchedule};
+    pub use crate::scene_view::SceneView;
+    pub use crate::slots::{ViewSlots, WithSlots};
+    pub use crate::stack::{Stack, StackLayer};
+    pub use crate::state_pool::{ViewStatePool, ViewStatePoolStats};
     pub use crate::switch::Switch;
+    pub use crate::text_computed::TextComputed;
+    pub use crate::time::TimeSnapshot;
     pub use crate::tracking_scope::TriggerReaction;
+    pub use crate::ui_definition::{
+        CallbackRegistry, UiDefinition, UiDefinitionPlugin, UiDefinitionView, UiState,
+    };
     pub use crate::view::*;
-    pub use crate::view_child::{IntoViewChild, ViewChild};
+    pub use crate::view_child::{IntoMemoViewChild, IntoViewChild, ViewChild};
+    pub use crate::view_handle::{MountHandle, ViewHandle};
+    pub use crate::view_registry::{ReflectView, ViewDescription, ViewTypeRegistry};
     pub use crate::view_template::ViewTemplate;
+    pub use crate::window::{WindowCursor, WindowOverrides, WindowTitle};
+    pub use crate::wrap_if::WrapIf;
 }
 
+pub use bridge::{RegisterUiBridge, UiCommand, UiMessage};
 pub use callback::*;
 pub use cond::Cond;
 pub use cx::Cx;
 pub use cx::EffectOptions;
+pub use decorate::{DecorateView, Decorated, Named, ViewDecorator};
+pub use dependency_graph::{ComponentDependency, DependencyGraph, ScopeDependencies};
 pub use dynamic::Dynamic;
 pub use element::*;
+pub use extension_point::{Contribution, ContributionId, ExtensionPoint, ExtensionRegistry};
 pub use for_each::ForEach;
 pub use for_index::ForIndex;
+pub use for_keyed::ForKeyed;
+pub use grid::{Grid, GridItem};
+pub use keyed_store::KeyedStore;
+pub use lifecycle::{OnMount, OnUnmount};
+use measure::measure_reaction_system;
 pub use mutable::*;
+pub use node_span::{NodeSpan, NodeSpanDiff};
+use ownership::init_ownership_registry;
+pub use ownership::{despawn_owned, is_stale, DespawnOwned, OwnedBy, OwnershipRegistry};
+pub use persist::{FileStorage, PersistPlugin, PersistStorage, PersistedState};
+use phases::{run_deferred_effects, run_layout_pass, DeferredEffects};
+pub use phases::{LayoutPass, LayoutPassRequest, QuillSet};
 pub use portal::Portal;
+use preload::update_preload_progress;
+pub use preload::{Preload, PreloadProgress};
 pub use r#for::For;
+pub use root_schedule::{InvalidateHandle, UpdateSchedule};
+pub use scene_view::SceneView;
+pub use slots::{ViewSlots, WithSlots};
+pub use stack::{Stack, StackLayer};
+pub use state_pool::{ViewStatePool, ViewStatePoolStats};
 pub use switch::Switch;
-use tracking_scope::cleanup_tracking_scopes;
+pub use text_computed::TextComputed;
+use time::update_throttled_time;
+pub use time::TimeSnapshot;
 pub use tracking_scope::TrackingScope;
 pub use tracking_scope::TrackingScopeTracing;
 pub use tracking_scope::TriggerReaction;
+use tracking_scope::{cleanup_tracking_scopes, DirtyEpoch};
+pub use ui_definition::{
+    CallbackRegistry, UiDefinition, UiDefinitionError, UiDefinitionLoader, UiDefinitionPlugin,
+    UiDefinitionView, UiNodeDef, UiState, UiValue,
+};
 pub use view::*;
+pub use view_child::IntoMemoViewChild;
 pub use view_child::IntoViewChild;
 pub use view_child::ViewChild;
+pub use view_handle::{MountHandle, ViewHandle};
+pub use view_registry::{ReflectView, ViewDescription, ViewTypeRegistry};
 pub use view_template::ViewTemplate;
+use window::apply_window_overrides;
+pub use window::{WindowCursor, WindowOverrides, WindowTitle};
+pub use wrap_if::WrapIf;
 
 pub struct QuillPlugin;
 
 impl Plugin for QuillPlugin {
     fn build(&self, app: &mut App) {
+        // Hooks must be registered before any `OwnedBy` component is inserted.
+        init_ownership_registry(app.world_mut());
         app.add_plugins(StyleBuilderPlugin)
+            .init_resource::<DeferredEffects>()
+            .init_resource::<DirtyEpoch>()
+            .init_resource::<LayoutPassRequest>()
+            .init_resource::<ReactionLimits>()
+            .init_resource::<ViewStatePool>()
+            .init_resource::<ViewTypeRegistry>()
+            .init_resource::<WindowOverrides>()
             .add_systems(Startup, (cleanup_tracking_scopes, cleanup_view_roots))
+            .add_systems(
+                Update,
+                (update_preload_progress, update_throttled_time).before(reaction_control_system),
+            )
+            .configure_sets(Update, QuillSet::Reactions.before(StyleBuilderSystemSet))
+            .configure_sets(
+                PostUpdate,
+                (QuillSet::Measure, QuillSet::Relayout, QuillSet::Effects)
+                    .chain()
+                    .after(UiSystem::Layout)
+                    .after(TransformSystem::TransformPropagate),
+            )
             .add_systems(
                 Update,
                 (build_views, reaction_control_system, reattach_children)
                     .chain()
-                    .before(StyleBuilderSystemSet),
-            );
+                    .in_set(QuillSet::Reactions),
+            )
+            .add_systems(
+                PostUpdate,
+                (measure_reaction_system, reattach_children)
+                    .chain()
+                    .in_set(QuillSet::Measure),
+            )
+            .add_systems(PostUpdate, run_layout_pass.in_set(QuillSet::Relayout))
+            .add_systems(PostUpdate, run_deferred_effects.in_set(QuillSet::Effects))
+            .init_schedule(LayoutPass)
+            // This is a separate instance of `ui_layout_system` from the one in `PostUpdate`,
+            // with its own change ticks and event readers, so it re-syncs everything which has
+            // changed since it last ran, including changes that the main instance has already
+            // applied. That's harmless, since syncing a node with the `UiSurface` is idempotent:
+            // styles are overwritten, existing measures are kept, and removals of nodes which
+            // are no longer in the surface are ignored.
+            .add_systems(
+                LayoutPass,
+                (
+                    ui_layout_system,
+                    (sync_simple_transforms, propagate_transforms),
+                    reaction_control_system,
+                    reattach_children,
+                )
+                    .chain(),
+            )
+            .add_systems(PostUpdate, apply_window_overrides);
     }
 }
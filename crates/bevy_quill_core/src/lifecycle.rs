@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use bevy::ecs::world::{DeferredWorld, World};
+use bevy::prelude::Entity;
+
+use crate::{Cx, View};
+
+/// A [`View`] which calls a function once its inner view has been built, passing in the
+/// output nodes. This is useful for imperative setup, such as starting a sound, that should
+/// happen once when the view appears rather than on every rebuild.
+pub struct OnMount<V: View, F: Fn(&mut World, &[Entity])> {
+    inner: V,
+    on_mount: F,
+}
+
+impl<V: View, F: Fn(&mut World, &[Entity]) + Send + Sync + 'static> OnMount<V, F> {
+    /// Construct a new `OnMount` view.
+    pub fn new(inner: V, on_mount: F) -> Self {
+        Self { inner, on_mount }
+    }
+}
+
+impl<V: View, F: Fn(&mut World, &[Entity]) + Send + Sync + 'static> View for OnMount<V, F> {
+    type State = V::State;
+
+    fn nodes(&self, world: &World, state: &Self::State, out: &mut Vec<Entity>) {
+        self.inner.nodes(world, state, out);
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let state = self.inner.build(cx);
+        let mut nodes: Vec<Entity> = Vec::new();
+        self.inner.nodes(cx.world(), &state, &mut nodes);
+        (self.on_mount)(cx.world_mut(), &nodes);
+        state
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        self.inner.rebuild(cx, state)
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        self.inner.attach_children(world, state)
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        self.inner.raze(world, state);
+    }
+}
+
+/// A [`View`] which calls a function when its inner view is razed, for imperative teardown
+/// such as releasing a GPU resource. Because razing happens while the world is borrowed,
+/// the function is run as a deferred command, after the view's entities have been despawned.
+pub struct OnUnmount<V: View, F: Fn(&mut World)> {
+    inner: V,
+    on_unmount: Arc<F>,
+}
+
+impl<V: View, F: Fn(&mut World) + Send + Sync + 'static> OnUnmount<V, F> {
+    /// Construct a new `OnUnmount` view.
+    pub fn new(inner: V, on_unmount: F) -> Self {
+        Self {
+            inner,
+            on_unmount: Arc::new(on_unmount),
+        }
+    }
+}
+
+impl<V: View, F: Fn(&mut World) + Send + Sync + 'static> View for OnUnmount<V, F> {
+    type State = V::State;
+
+    fn nodes(&self, world: &World, state: &Self::State, out: &mut Vec<Entity>) {
+        self.inner.nodes(world, state, out);
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        self.inner.build(cx)
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        self.inner.rebuild(cx, state)
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        self.inner.attach_children(world, state)
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        self.inner.raze(world, state);
+        let on_unmount = self.on_unmount.clone();
+        world
+            .commands()
+            .add(move |world: &mut World| (on_unmount)(world));
+    }
+}
@@ -0,0 +1,253 @@
+use bevy::{
+    ecs::component::Tick,
+    hierarchy::Parent,
+    math::{Rect, Vec2},
+    prelude::{Entity, GlobalTransform, World},
+    ui::Node,
+};
+
+use crate::{
+    tracking_scope::ReactionEpoch, view::run_reactions, Cx, ReactionLimits, TrackingScope,
+};
+
+impl<'p, 'w> Cx<'p, 'w> {
+    /// Returns the laid-out size of the display entity `id`, in logical pixels, and subscribes
+    /// the current scope to changes in that size. Returns `None` if the entity does not exist
+    /// or is not a UI node.
+    ///
+    /// Layout is computed after views are built; when it changes, the scope re-runs in the
+    /// [`QuillSet::Measure`](crate::QuillSet::Measure) phase of the same frame. An element
+    /// which has just been created is not laid out until the next frame, so it measures as
+    /// zero the first time. This makes it possible to render content that depends on the
+    /// available space, such as truncating a label or collapsing a toolbar into an overflow
+    /// menu:
+    ///
+    /// ```ignore
+    /// let width = cx.use_measured_size(toolbar_id).map_or(0., |size| size.x);
+    /// ```
+    pub fn use_measured_size(&self, id: Entity) -> Option<Vec2> {
+        self.tracking.borrow_mut().set_reads_layout();
+        self.use_component::<Node>(id).map(|node| node.size())
+    }
+
+    /// Returns the laid-out rectangle of the display entity `id`, in logical window
+    /// coordinates. Unlike [`Cx::use_measured_size`], this also subscribes to changes in the
+    /// position of the entity. Returns `None` if the entity does not exist or is not a UI node.
+    pub fn use_measured_rect(&self, id: Entity) -> Option<Rect> {
+        self.tracking.borrow_mut().set_reads_layout();
+        let node = self.use_component::<Node>(id)?;
+        let transform = self.use_component::<GlobalTransform>(id)?;
+        Some(node.logical_rect(transform))
+    }
+
+    /// Returns the laid-out rectangle of the display entity `id`, in the local coordinate space
+    /// of `ancestor`: that is, relative to the top-left corner of the ancestor, in the same units
+    /// as the ancestor's children use for positioning. This is useful when drawing overlays,
+    /// such as the edges between nodes in a graph, which need to line up with elements nested
+    /// some way down the hierarchy.
+    ///
+    /// The result accounts for the UI scale factor and for any scaling or rotation applied to
+    /// either entity; if the element is rotated relative to the ancestor, its bounding box is
+    /// returned. The current scope is subscribed to layout changes of both entities.
+    ///
+    /// Returns `None` if either entity does not exist (for example, if the ancestor has been
+    /// despawned) or is not a UI node.
+    pub fn rect_relative_to(&self, id: Entity, ancestor: Entity) -> Option<Rect> {
+        self.tracking.borrow_mut().set_reads_layout();
+        let node = self.use_component::<Node>(id)?;
+        let transform = self.use_component::<GlobalTransform>(id)?;
+        let ancestor_node = self.use_component::<Node>(ancestor)?;
+        let ancestor_transform = self.use_component::<GlobalTransform>(ancestor)?;
+        Some(relative_rect(
+            node.size(),
+            transform,
+            ancestor_node.size(),
+            ancestor_transform,
+        ))
+    }
+}
+
+/// Re-run the scopes which read layout data and whose dependencies have changed, after layout
+/// has been computed. Unlike the main reaction pass, this doesn't visit the whole view hierarchy,
+/// and runs a single iteration: any further changes are picked up by the next frame's reactions.
+pub(crate) fn measure_reaction_system(world: &mut World) {
+    let tick = world.change_tick();
+    if world
+        .get_resource::<ReactionEpoch>()
+        .is_some_and(|epoch| epoch.is_clean(world, tick))
+    {
+        return;
+    }
+    let changed = changed_layout_scopes(world, tick);
+    if changed.is_empty() {
+        return;
+    }
+    let limits = world
+        .get_resource::<ReactionLimits>()
+        .cloned()
+        .unwrap_or_default();
+    run_reactions(world, &changed, tick, &limits);
+}
+
+/// Find the scopes which read layout data and whose dependencies have changed, ordered so that
+/// parents are rebuilt before their descendants.
+fn changed_layout_scopes(world: &mut World, tick: Tick) -> Vec<Entity> {
+    let mut scopes = world.query::<(Entity, &TrackingScope)>();
+    let world: &World = world;
+    let mut changed: Vec<Entity> = scopes
+        .iter(world)
+        .filter(|(_, scope)| scope.reads_layout() && scope.dependencies_changed(world, tick))
+        .map(|(entity, _)| entity)
+        .collect();
+    changed.sort_by_cached_key(|entity| {
+        std::iter::successors(Some(*entity), |entity| {
+            world.get::<Parent>(*entity).map(Parent::get)
+        })
+        .count()
+    });
+    changed
+}
+
+/// Compute the bounding rect of a node of the given size and transform, relative to the
+/// top-left corner of another node. UI transforms are centered on the node.
+pub(crate) fn relative_rect(
+    size: Vec2,
+    transform: &GlobalTransform,
+    ancestor_size: Vec2,
+    ancestor_transform: &GlobalTransform,
+) -> Rect {
+    let to_ancestor = ancestor_transform.affine().inverse() * transform.affine();
+    let half = size * 0.5;
+    let origin = ancestor_size * 0.5;
+    let corner = |x: f32, y: f32| {
+        to_ancestor
+            .transform_point3(Vec2::new(x, y).extend(0.))
+            .truncate()
+            + origin
+    };
+    let first = corner(-half.x, -half.y);
+    [
+        corner(half.x, -half.y),
+        corner(-half.x, half.y),
+        corner(half.x, half.y),
+    ]
+    .into_iter()
+    .fold(Rect::from_corners(first, first), |rect, point| {
+        rect.union_point(point)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::change_detection::DetectChangesMut,
+        math::{Quat, Vec3},
+        prelude::Transform,
+    };
+
+    use super::*;
+
+    fn at(x: f32, y: f32) -> GlobalTransform {
+        GlobalTransform::from_translation(Vec3::new(x, y, 0.))
+    }
+
+    #[test]
+    fn test_relative_rect() {
+        // Child centered at (150, 120) with size 20x10, ancestor centered at (200, 150) with
+        // size 400x300: the ancestor's top-left corner is at the origin.
+        let rect = relative_rect(
+            Vec2::new(20., 10.),
+            &at(150., 120.),
+            Vec2::new(400., 300.),
+            &at(200., 150.),
+        );
+        assert_eq!(rect, Rect::new(140., 115., 160., 125.));
+
+        // Scrolling the ancestor moves both, leaving the relative rect unchanged.
+        let rect = relative_rect(
+            Vec2::new(20., 10.),
+            &at(100., 120.),
+            Vec2::new(400., 300.),
+            &at(150., 150.),
+        );
+        assert_eq!(rect, Rect::new(140., 115., 160., 125.));
+    }
+
+    #[test]
+    fn test_relative_rect_transformed() {
+        // An ancestor which is scaled up by 2x: distances in its local space are halved.
+        let ancestor = GlobalTransform::from(
+            Transform::from_translation(Vec3::new(200., 200., 0.)).with_scale(Vec3::splat(2.)),
+        );
+        let rect = relative_rect(
+            Vec2::new(20., 20.),
+            &GlobalTransform::from(
+                Transform::from_translation(Vec3::new(220., 220., 0.)).with_scale(Vec3::splat(2.)),
+            ),
+            Vec2::new(100., 100.),
+            &ancestor,
+        );
+        assert!((rect.min - Vec2::new(50., 50.)).length() < 0.001);
+        assert!((rect.max - Vec2::new(70., 70.)).length() < 0.001);
+
+        // A rotated child returns its bounding box.
+        let rect = relative_rect(
+            Vec2::new(20., 10.),
+            &GlobalTransform::from(
+                Transform::from_translation(Vec3::new(50., 50., 0.))
+                    .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+            ),
+            Vec2::new(100., 100.),
+            &at(50., 50.),
+        );
+        assert!((rect.size() - Vec2::new(10., 20.)).length() < 0.001);
+        assert!((rect.center() - Vec2::new(50., 50.)).length() < 0.001);
+    }
+
+    #[test]
+    fn test_rect_relative_to_missing() {
+        let mut world = World::default();
+        let node = world
+            .spawn((Node::default(), GlobalTransform::default()))
+            .id();
+        let removed = world
+            .spawn((Node::default(), GlobalTransform::default()))
+            .id();
+        world.despawn(removed);
+        let owner = world.spawn_empty().id();
+        let mut scope = TrackingScope::new(world.change_tick());
+
+        let cx = Cx::new(&mut world, owner, &mut scope);
+        assert!(cx.rect_relative_to(node, node).is_some());
+        assert!(cx.rect_relative_to(node, removed).is_none());
+        assert!(cx.rect_relative_to(removed, node).is_none());
+        assert!(cx.rect_relative_to(owner, node).is_none());
+    }
+
+    #[test]
+    fn test_changed_layout_scopes() {
+        let mut world = World::default();
+        let node = world.spawn(Node::default()).id();
+        let owner = world.spawn_empty().id();
+        let tick = world.change_tick();
+
+        // Both scopes depend on the node, but only one reads it as layout data.
+        let mut measured = TrackingScope::new(tick);
+        Cx::new(&mut world, owner, &mut measured).use_measured_size(node);
+        let mut plain = TrackingScope::new(tick);
+        Cx::new(&mut world, owner, &mut plain).use_component::<Node>(node);
+        assert!(measured.reads_layout());
+        assert!(!plain.reads_layout());
+        let measured = world.spawn(measured).id();
+        world.spawn(plain);
+
+        // Nothing has changed yet.
+        let tick = world.change_tick();
+        assert!(changed_layout_scopes(&mut world, tick).is_empty());
+
+        world.increment_change_tick();
+        world.get_mut::<Node>(node).unwrap().set_changed();
+        let tick = world.change_tick();
+        assert_eq!(changed_layout_scopes(&mut world, tick), vec![measured]);
+    }
+}
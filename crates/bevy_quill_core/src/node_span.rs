@@ -0,0 +1,152 @@
+use std::ops::Range;
+
+use bevy::prelude::{Entity, World};
+
+use crate::View;
+
+/// An ordered list of the display nodes produced by a [`View`]. This is a convenience for
+/// custom `View` implementations, such as virtualized lists or grids, which need to manage
+/// the attachment of child nodes themselves.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct NodeSpan(Vec<Entity>);
+
+/// The difference between two [`NodeSpan`]s, as computed by [`NodeSpan::diff`].
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct NodeSpanDiff {
+    /// Nodes which are in the new span but not in the old one.
+    pub added: Vec<Entity>,
+    /// Nodes which are in the old span but not in the new one.
+    pub removed: Vec<Entity>,
+    /// True if the nodes common to both spans appear in a different order.
+    pub reordered: bool,
+}
+
+impl NodeSpanDiff {
+    /// Returns true if the two spans were identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && !self.reordered
+    }
+}
+
+impl NodeSpan {
+    /// Create an empty span.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect the output nodes of a view.
+    pub fn from_view<V: View>(view: &V, world: &World, state: &V::State) -> Self {
+        let mut nodes: Vec<Entity> = Vec::new();
+        view.nodes(world, state, &mut nodes);
+        Self(nodes)
+    }
+
+    /// Iterate over the nodes in the span.
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Returns the number of nodes in the span.
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the span contains no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns true if the span contains the given node.
+    pub fn contains(&self, node: Entity) -> bool {
+        self.0.contains(&node)
+    }
+
+    /// Returns the position of the given node within the span.
+    pub fn position(&self, node: Entity) -> Option<usize> {
+        self.0.iter().position(|n| *n == node)
+    }
+
+    /// Returns a new span containing the given range of nodes. The range is clamped to the
+    /// length of the span.
+    pub fn slice(&self, range: Range<usize>) -> NodeSpan {
+        let end = range.end.min(self.0.len());
+        let start = range.start.min(end);
+        Self(self.0[start..end].to_vec())
+    }
+
+    /// Append the nodes of another span to this one.
+    pub fn extend(&mut self, other: &NodeSpan) {
+        self.0.extend_from_slice(&other.0);
+    }
+
+    /// Compute which nodes have been added or removed in `next` relative to `self`.
+    pub fn diff(&self, next: &NodeSpan) -> NodeSpanDiff {
+        let added: Vec<Entity> = next.iter().filter(|n| !self.contains(*n)).collect();
+        let removed: Vec<Entity> = self.iter().filter(|n| !next.contains(*n)).collect();
+        let reordered = self
+            .iter()
+            .filter(|n| next.contains(*n))
+            .ne(next.iter().filter(|n| self.contains(*n)));
+        NodeSpanDiff {
+            added,
+            removed,
+            reordered,
+        }
+    }
+
+    /// Returns the nodes as a slice.
+    pub fn as_slice(&self) -> &[Entity] {
+        &self.0
+    }
+
+    /// Convert the span into a vector of nodes.
+    pub fn into_vec(self) -> Vec<Entity> {
+        self.0
+    }
+}
+
+impl From<Vec<Entity>> for NodeSpan {
+    fn from(nodes: Vec<Entity>) -> Self {
+        Self(nodes)
+    }
+}
+
+impl FromIterator<Entity> for NodeSpan {
+    fn from_iter<I: IntoIterator<Item = Entity>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice() {
+        let mut world = World::default();
+        let span: NodeSpan = (0..4).map(|_| world.spawn_empty().id()).collect();
+        assert_eq!(span.count(), 4);
+        assert_eq!(span.slice(1..3).as_slice(), &span.as_slice()[1..3]);
+        assert_eq!(span.slice(3..10).count(), 1);
+        assert!(span.slice(5..10).is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut world = World::default();
+        let e: Vec<Entity> = (0..4).map(|_| world.spawn_empty().id()).collect();
+        let a = NodeSpan::from(vec![e[0], e[1], e[2]]);
+
+        assert!(a.diff(&a).is_empty());
+
+        let diff = a.diff(&NodeSpan::from(vec![e[0], e[2], e[3]]));
+        assert_eq!(diff.added, vec![e[3]]);
+        assert_eq!(diff.removed, vec![e[1]]);
+        assert!(!diff.reordered);
+
+        let diff = a.diff(&NodeSpan::from(vec![e[2], e[1], e[0]]));
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.reordered);
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::{
+    ecs::world::Command,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+/// Component which records the view scope that spawned an entity. This is inserted on
+/// display entities created by [`Element`] and on entities created by [`Cx::create_entity`].
+///
+/// [`Element`]: crate::Element
+/// [`Cx::create_entity`]: crate::Cx::create_entity
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OwnedBy(pub Entity);
+
+/// Resource which indexes entities by the view scope that owns them. This is kept up to date
+/// automatically as [`OwnedBy`] components are added and removed.
+#[derive(Resource, Default)]
+pub struct OwnershipRegistry {
+    owned: HashMap<Entity, HashSet<Entity>>,
+}
+
+impl OwnershipRegistry {
+    /// Iterate over the entities owned by the given scope.
+    pub fn owned(&self, scope: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.owned.get(&scope).into_iter().flatten().copied()
+    }
+
+    /// Returns the number of entities owned by the given scope.
+    pub fn count(&self, scope: Entity) -> usize {
+        self.owned.get(&scope).map_or(0, |owned| owned.len())
+    }
+}
+
+/// Returns true if the entity is no longer valid as a child node: either it has been
+/// despawned, or it is owned by a view scope which has been despawned.
+pub fn is_stale(world: &World, entity: Entity) -> bool {
+    match world.get_entity(entity) {
+        None => true,
+        Some(entt) => entt
+            .get::<OwnedBy>()
+            .is_some_and(|owner| world.get_entity(owner.0).is_none()),
+    }
+}
+
+/// Despawn all of the entities owned by the given view scope. This is used as a sweep after a
+/// view has been razed, to catch any entities that the view did not clean up itself.
+pub fn despawn_owned(world: &mut World, scope: Entity) {
+    let Some(mut registry) = world.get_resource_mut::<OwnershipRegistry>() else {
+        return;
+    };
+    let owned: Vec<Entity> = registry
+        .owned
+        .remove(&scope)
+        .map(|owned| owned.into_iter().collect())
+        .unwrap_or_default();
+    for entity in owned {
+        if world.get_entity(entity).is_some() {
+            world.despawn(entity);
+        }
+    }
+}
+
+/// A command which calls [`despawn_owned`].
+pub struct DespawnOwned(pub Entity);
+
+impl Command for DespawnOwned {
+    fn apply(self, world: &mut World) {
+        despawn_owned(world, self.0);
+    }
+}
+
+/// Initialize the [`OwnershipRegistry`] and register the [`OwnedBy`] hooks which maintain it.
+/// This is called from [`QuillPlugin::build`](crate::QuillPlugin), since component hooks can't
+/// be registered once the component is in use.
+pub(crate) fn init_ownership_registry(world: &mut World) {
+    world.init_resource::<OwnershipRegistry>();
+    world
+        .register_component_hooks::<OwnedBy>()
+        .on_add(|mut world, entity, _component| {
+            let owner = world.get::<OwnedBy>(entity).unwrap().0;
+            if let Some(mut registry) = world.get_resource_mut::<OwnershipRegistry>() {
+                registry.owned.entry(owner).or_default().insert(entity);
+            }
+        })
+        .on_remove(|mut world, entity, _component| {
+            let owner = world.get::<OwnedBy>(entity).unwrap().0;
+            if let Some(mut registry) = world.get_resource_mut::<OwnershipRegistry>() {
+                if let Some(owned) = registry.owned.get_mut(&owner) {
+                    owned.remove(&entity);
+                    if owned.is_empty() {
+                        registry.owned.remove(&owner);
+                    }
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_despawn_owned() {
+        let mut world = World::default();
+        init_ownership_registry(&mut world);
+        let scope = world.spawn_empty().id();
+        let a = world.spawn(OwnedBy(scope)).id();
+        let b = world.spawn(OwnedBy(scope)).id();
+        assert_eq!(world.resource::<OwnershipRegistry>().count(scope), 2);
+
+        // Despawning an entity removes it from the registry.
+        world.despawn(a);
+        assert_eq!(world.resource::<OwnershipRegistry>().count(scope), 1);
+        assert!(!is_stale(&world, b));
+
+        // Entities whose owner is gone are stale.
+        world.despawn(scope);
+        assert!(is_stale(&world, a));
+        assert!(is_stale(&world, b));
+
+        despawn_owned(&mut world, scope);
+        assert!(world.get_entity(b).is_none());
+        assert_eq!(world.resource::<OwnershipRegistry>().count(scope), 0);
+    }
+}
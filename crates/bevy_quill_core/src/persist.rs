@@ -0,0 +1,249 @@
+use std::{path::PathBuf, sync::Arc};
+
+use bevy::{app::AppExit, prelude::*, utils::HashMap};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{mutable::MutableCell, Cx, Mutable};
+
+/// A place where persisted view state is kept between runs of the app. Each value is stored
+/// under its key as a RON string.
+pub trait PersistStorage: Send + Sync + 'static {
+    /// Read all of the stored values.
+    fn load(&self) -> HashMap<String, String>;
+
+    /// Replace the stored values.
+    fn save(&self, values: &HashMap<String, String>);
+}
+
+/// [`PersistStorage`] which keeps values in a RON file.
+pub struct FileStorage {
+    path: PathBuf,
+}
+
+impl FileStorage {
+    /// Create a storage backend which reads and writes the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl PersistStorage for FileStorage {
+    fn load(&self) -> HashMap<String, String> {
+        let Ok(text) = std::fs::read_to_string(&self.path) else {
+            // Nothing has been saved yet.
+            return HashMap::default();
+        };
+        ron::from_str(&text).unwrap_or_else(|err| {
+            warn!("Could not read persisted state {:?}: {}", self.path, err);
+            HashMap::default()
+        })
+    }
+
+    fn save(&self, values: &HashMap<String, String>) {
+        let result = ron::ser::to_string_pretty(values, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|text| std::fs::write(&self.path, text).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Could not write persisted state {:?}: {}", self.path, err);
+        }
+    }
+}
+
+/// Marks a mutable cell whose value has already been restored.
+#[derive(Component)]
+struct PersistKey;
+
+/// Resource which holds persisted view state. Values are read from storage when the
+/// [`PersistPlugin`] is added, and written back when the app exits.
+#[derive(Resource)]
+pub struct PersistedState {
+    values: HashMap<String, String>,
+    /// Mutable cells which are currently mounted, and a function to serialize each one.
+    live: HashMap<String, (Entity, fn(&World, Entity) -> Option<String>)>,
+    storage: Arc<dyn PersistStorage>,
+}
+
+impl PersistedState {
+    /// Create the persisted state, loading the values from `storage`.
+    pub fn new(storage: Arc<dyn PersistStorage>) -> Self {
+        Self {
+            values: storage.load(),
+            live: HashMap::default(),
+            storage,
+        }
+    }
+
+    /// Return the value stored under `key`, if there is one and it has the right type.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        ron::from_str(self.values.get(key)?).ok()
+    }
+
+    /// Store a value under `key`.
+    pub fn set<T: Serialize>(&mut self, key: impl Into<String>, value: &T) {
+        let key = key.into();
+        match ron::to_string(value) {
+            Ok(text) => {
+                self.values.insert(key, text);
+            }
+            Err(err) => warn!("Could not persist {}: {}", key, err),
+        }
+    }
+
+    /// Forget the value stored under `key`.
+    pub fn remove(&mut self, key: &str) {
+        self.values.remove(key);
+        self.live.remove(key);
+    }
+
+    /// Copy the values of mounted cells, then write all values to storage.
+    pub fn save(&mut self, world: &World) {
+        for (key, (cell, serialize)) in self.live.iter() {
+            if let Some(text) = serialize(world, *cell) {
+                self.values.insert(key.clone(), text);
+            }
+        }
+        self.storage.save(&self.values);
+    }
+}
+
+fn serialize_cell<T: Serialize + Send + Sync + 'static>(
+    world: &World,
+    cell: Entity,
+) -> Option<String> {
+    let value = &world.get::<MutableCell<T>>(cell)?.0;
+    ron::to_string(value).ok()
+}
+
+impl<T> Mutable<T>
+where
+    T: PartialEq + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// Persist the value of this [`Mutable`] under `key`, so that it survives restarting the
+    /// app. When the mutable is first created, it is given the value which was saved under the
+    /// key, if any. Does nothing unless the [`PersistPlugin`] has been added.
+    ///
+    /// ```ignore
+    /// let tab = cx.create_mutable(0).persist(cx, "settings.tab");
+    /// ```
+    ///
+    /// Keys should be unique: if several views persist the same key, the last one to be saved
+    /// wins.
+    pub fn persist(self, cx: &mut Cx, key: impl Into<String>) -> Self {
+        if !cx.world().contains_resource::<PersistedState>() {
+            return self;
+        }
+        let key = key.into();
+        let cell = self.cell;
+        let world = cx.world_mut();
+        if world.get::<PersistKey>(cell).is_none() {
+            let saved = world.resource::<PersistedState>().get::<T>(&key);
+            let mut entt = world.entity_mut(cell);
+            entt.insert(PersistKey);
+            if let Some(saved) = saved {
+                let mut value = entt.get_mut::<MutableCell<T>>().unwrap();
+                if value.0 != saved {
+                    value.0 = saved;
+                }
+            }
+        }
+        world
+            .resource_mut::<PersistedState>()
+            .live
+            .insert(key.clone(), (cell, serialize_cell::<T>));
+
+        // Remember the value when the view is razed, so that it is restored when the view
+        // is built again.
+        cx.on_cleanup(move |world| {
+            let Some(text) = serialize_cell::<T>(world, cell) else {
+                return;
+            };
+            let mut state = world.resource_mut::<PersistedState>();
+            if state.live.get(&key).map(|(e, _)| *e) == Some(cell) {
+                state.live.remove(&key);
+            }
+            state.values.insert(key, text);
+        });
+        self
+    }
+}
+
+/// Plugin which saves [persisted](Mutable::persist) view state when the app exits, and
+/// restores it the next time the app is run. By default, the state is kept in a file named
+/// `quill_state.ron` in the working directory; use [`PersistPlugin::new`] to store it
+/// elsewhere.
+pub struct PersistPlugin {
+    storage: Arc<dyn PersistStorage>,
+}
+
+impl PersistPlugin {
+    /// Create the plugin with a custom storage backend.
+    pub fn new(storage: impl PersistStorage) -> Self {
+        Self {
+            storage: Arc::new(storage),
+        }
+    }
+}
+
+impl Default for PersistPlugin {
+    fn default() -> Self {
+        Self::new(FileStorage::new("quill_state.ron"))
+    }
+}
+
+impl Plugin for PersistPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PersistedState::new(self.storage.clone()))
+            .add_systems(Last, save_persisted_state);
+    }
+}
+
+fn save_persisted_state(world: &mut World) {
+    if world
+        .get_resource::<Events<AppExit>>()
+        .is_none_or(|events| events.is_empty())
+    {
+        return;
+    }
+    world.resource_scope(|world, mut state: Mut<PersistedState>| state.save(world));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryStorage(Mutex<HashMap<String, String>>);
+
+    impl PersistStorage for Arc<MemoryStorage> {
+        fn load(&self) -> HashMap<String, String> {
+            self.0.lock().unwrap().clone()
+        }
+
+        fn save(&self, values: &HashMap<String, String>) {
+            *self.0.lock().unwrap() = values.clone();
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let storage = Arc::new(MemoryStorage::default());
+        let mut world = World::default();
+        let cell = world.spawn(MutableCell(42i32)).id();
+
+        let mut state = PersistedState::new(Arc::new(storage.clone()));
+        state.set("name", &"Quill".to_string());
+        state
+            .live
+            .insert("answer".to_string(), (cell, serialize_cell::<i32>));
+        state.save(&world);
+
+        let state = PersistedState::new(Arc::new(storage));
+        assert_eq!(state.get::<String>("name"), Some("Quill".to_string()));
+        assert_eq!(state.get::<i32>("answer"), Some(42));
+        // Values of the wrong type are ignored.
+        assert_eq!(state.get::<bool>("answer"), None);
+        assert_eq!(state.get::<i32>("missing"), None);
+    }
+}
@@ -0,0 +1,265 @@
+use bevy::{
+    ecs::schedule::{ScheduleLabel, SystemSet},
+    prelude::{Resource, World},
+};
+
+use crate::Cx;
+
+/// The phases of a Quill UI update, in the order in which they run each frame:
+///
+/// 1. [`QuillSet::Reactions`], in `Update`: new view roots are built, and scopes whose
+///    dependencies have changed are rebuilt.
+/// 2. [`QuillSet::Measure`], in `PostUpdate` after UI layout and transform propagation: scopes
+///    which read layout data with [`Cx::use_measured_size`] and related hooks are rebuilt so
+///    that they see the layout of the current frame rather than the previous one. Other scopes
+///    are not visited. Since styling and layout have already run, changes that these scopes
+///    make to the UI are laid out on the next frame, unless they request a layout pass.
+/// 3. [`QuillSet::Relayout`], in `PostUpdate` after the measure pass: if a layout pass has
+///    been requested, the [`LayoutPass`] schedule is run.
+/// 4. [`QuillSet::Effects`], in `PostUpdate` after the relayout phase: deferred effects queued
+///    with [`Cx::after_layout`] are run.
+///
+/// Systems which modify state that views depend on should run before `Reactions`; systems
+/// which need the final output of the UI for the frame should run after `Effects`.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuillSet {
+    /// Build and rebuild views.
+    Reactions,
+    /// Rebuild views which read layout data, after layout has been computed.
+    Measure,
+    /// Run the [`LayoutPass`] schedule, if requested.
+    Relayout,
+    /// Run deferred effects.
+    Effects,
+}
+
+/// Schedule which computes the UI layout a second time within a frame, and then rebuilds the
+/// views which depend on it. This runs at most once per frame, in the [`QuillSet::Relayout`]
+/// phase, and only if it has been requested with [`LayoutPassRequest`] or
+/// [`Cx::request_layout_pass`].
+///
+/// Elements which are positioned relative to other elements, such as popups, can only be
+/// placed once both have been laid out; without a second pass, a newly-opened popup appears
+/// in the wrong place for a frame. Systems which position elements after layout can be added
+/// to this schedule so that they see the updated layout too.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LayoutPass;
+
+/// Resource which requests a run of the [`LayoutPass`] schedule. Requests which are made after
+/// the [`QuillSet::Relayout`] phase, including ones made during the layout pass itself, take
+/// effect on the following frame.
+#[derive(Resource, Default)]
+pub struct LayoutPassRequest {
+    requested: bool,
+}
+
+impl LayoutPassRequest {
+    /// Request a layout pass.
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Returns true if a layout pass has been requested.
+    pub fn is_requested(&self) -> bool {
+        self.requested
+    }
+}
+
+/// A deferred effect, see [`Cx::after_layout`].
+type DeferredEffect = Box<dyn FnOnce(&mut World) + Send + Sync + 'static>;
+
+/// Resource which holds the deferred effects which are waiting for the
+/// [`QuillSet::Effects`] phase.
+#[derive(Resource, Default)]
+pub(crate) struct DeferredEffects(Vec<DeferredEffect>);
+
+impl<'p, 'w> Cx<'p, 'w> {
+    /// Run a closure once in the [`QuillSet::Effects`] phase of the current frame, after
+    /// layout has been computed and the measure pass has run. Effects run in the order in
+    /// which they were queued. This is useful for work that needs the final layout of the
+    /// frame, such as scrolling a newly-selected item into view:
+    ///
+    /// ```ignore
+    /// cx.after_layout(move |world| {
+    ///     scroll_into_view(world, item_id);
+    /// });
+    /// ```
+    ///
+    /// Unlike [`create_effect`](Cx::create_effect), this is not a hook, so it can be called
+    /// conditionally, and the closure runs every time it is queued.
+    pub fn after_layout(&mut self, effect: impl FnOnce(&mut World) + Send + Sync + 'static) {
+        self.world_mut()
+            .get_resource_or_insert_with(DeferredEffects::default)
+            .0
+            .push(Box::new(effect));
+    }
+
+    /// Request a run of the [`LayoutPass`] schedule in the current frame, so that changes this
+    /// view makes in response to layout, such as positioning a popup next to its anchor, are
+    /// laid out before the frame is rendered. This should be called only when the view has
+    /// actually changed something which depends on layout, since each pass recomputes the
+    /// layout of the whole UI.
+    pub fn request_layout_pass(&mut self) {
+        self.world_mut()
+            .get_resource_or_insert_with(LayoutPassRequest::default)
+            .request();
+    }
+}
+
+/// Run the [`LayoutPass`] schedule if it has been requested. Requests which are made during
+/// the pass are carried over to the next frame, so the pass runs at most once per frame.
+pub(crate) fn run_layout_pass(world: &mut World) {
+    let Some(mut request) = world.get_resource_mut::<LayoutPassRequest>() else {
+        return;
+    };
+    if !std::mem::take(&mut request.requested) {
+        return;
+    }
+    world.run_schedule(LayoutPass);
+}
+
+/// Run the deferred effects which have been queued since the last run. Effects which are
+/// queued by other effects run on the following frame.
+pub(crate) fn run_deferred_effects(world: &mut World) {
+    let Some(mut effects) = world.get_resource_mut::<DeferredEffects>() else {
+        return;
+    };
+    let effects = std::mem::take(&mut effects.0);
+    for effect in effects {
+        effect(world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{
+            App, Assets, BuildWorldChildren, Camera, DespawnRecursiveExt, NodeBundle, ResMut,
+            Schedule, Shader,
+        },
+        ui::{ui_layout_system, Node, Style, UiPlugin, Val},
+        window::{WindowResized, WindowScaleFactorChanged},
+    };
+
+    use crate::TrackingScope;
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<i32>);
+
+    #[derive(Resource, Default)]
+    struct PassCount(usize);
+
+    #[test]
+    fn test_deferred_effects() {
+        let mut world = World::default();
+        world.init_resource::<Log>();
+        let mut scope = TrackingScope::new(world.change_tick());
+        let owner = world.spawn_empty().id();
+
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        cx.after_layout(|world| world.resource_mut::<Log>().0.push(1));
+        cx.after_layout(|world| {
+            world.resource_mut::<Log>().0.push(2);
+            // Queued by an effect, so postponed to the next run.
+            world
+                .resource_mut::<DeferredEffects>()
+                .0
+                .push(Box::new(|world| world.resource_mut::<Log>().0.push(3)));
+        });
+
+        // Nothing runs until the effects phase.
+        assert!(world.resource::<Log>().0.is_empty());
+        run_deferred_effects(&mut world);
+        assert_eq!(world.resource::<Log>().0, vec![1, 2]);
+
+        // Each effect runs once.
+        run_deferred_effects(&mut world);
+        assert_eq!(world.resource::<Log>().0, vec![1, 2, 3]);
+        run_deferred_effects(&mut world);
+        assert_eq!(world.resource::<Log>().0, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_layout_pass() {
+        let mut world = World::default();
+        world.init_resource::<PassCount>();
+        world.init_resource::<LayoutPassRequest>();
+        let mut schedule = Schedule::new(LayoutPass);
+        schedule.add_systems(
+            |mut count: ResMut<PassCount>, mut request: ResMut<LayoutPassRequest>| {
+                count.0 += 1;
+                // Requesting another pass from within the pass postpones it.
+                request.request();
+            },
+        );
+        world.add_schedule(schedule);
+
+        // Nothing has been requested.
+        run_layout_pass(&mut world);
+        assert_eq!(world.resource::<PassCount>().0, 0);
+
+        let mut scope = TrackingScope::new(world.change_tick());
+        let owner = world.spawn_empty().id();
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        cx.request_layout_pass();
+        run_layout_pass(&mut world);
+        assert_eq!(world.resource::<PassCount>().0, 1);
+        assert!(world.resource::<LayoutPassRequest>().is_requested());
+
+        // The postponed request runs the next time.
+        run_layout_pass(&mut world);
+        assert_eq!(world.resource::<PassCount>().0, 2);
+    }
+
+    #[test]
+    fn test_layout_system_runs_twice() {
+        // The layout pass runs its own instance of `ui_layout_system`, which must agree with the
+        // main one when both run in the same frame.
+        let mut app = App::new();
+        app.init_resource::<Assets<Shader>>()
+            .add_event::<WindowScaleFactorChanged>()
+            .add_event::<WindowResized>()
+            .add_plugins(UiPlugin);
+        let world = app.world_mut();
+        world.spawn(Camera::default());
+        let mut main = Schedule::default();
+        main.add_systems(ui_layout_system);
+        let mut pass = Schedule::new(LayoutPass);
+        pass.add_systems(ui_layout_system);
+
+        let style = |width| Style {
+            width: Val::Px(width),
+            height: Val::Px(10.),
+            ..Default::default()
+        };
+        let root = world.spawn(NodeBundle::default()).id();
+        let child = world
+            .spawn(NodeBundle {
+                style: style(100.),
+                ..Default::default()
+            })
+            .set_parent(root)
+            .id();
+        let width = |world: &World| world.get::<Node>(child).unwrap().size().x;
+
+        main.run(world);
+        pass.run(world);
+        assert_eq!(width(world), 100.);
+
+        // A change made between the two runs is picked up by the second one, and re-syncing it
+        // in the main instance later doesn't change the result.
+        *world.get_mut::<Style>(child).unwrap() = style(200.);
+        pass.run(world);
+        assert_eq!(width(world), 200.);
+        main.run(world);
+        assert_eq!(width(world), 200.);
+
+        // Both instances see the removal, which must not panic in the second one.
+        world.entity_mut(child).despawn_recursive();
+        main.run(world);
+        pass.run(world);
+        main.run(world);
+    }
+}
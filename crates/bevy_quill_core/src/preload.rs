@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use bevy::{
+    asset::{AssetPath, AssetServer, RecursiveDependencyLoadState, UntypedHandle},
+    prelude::{Component, DetectChangesMut, Query, Res},
+};
+
+use crate::{Cond, Cx, IntoViewChild, View, ViewChild, ViewTemplate};
+
+/// The aggregate loading state of the assets in a [`Preload`].
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PreloadProgress {
+    /// Number of assets which have finished loading, including their dependencies.
+    pub loaded: usize,
+    /// Number of assets which failed to load.
+    pub failed: usize,
+    /// Total number of assets.
+    pub total: usize,
+}
+
+impl PreloadProgress {
+    /// The fraction of assets which have either loaded or failed, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        }
+    }
+
+    /// True if every asset has either loaded or failed.
+    pub fn is_complete(&self) -> bool {
+        self.loaded + self.failed >= self.total
+    }
+}
+
+/// Component on the scope entity of a [`Preload`] which keeps the assets alive while the
+/// view is mounted.
+#[derive(Component)]
+pub(crate) struct PreloadHandles(Vec<UntypedHandle>);
+
+type PreloadContentFn = Arc<dyn Fn(PreloadProgress) -> ViewChild + Send + Sync>;
+
+/// A view which starts loading a set of assets when it is mounted, and passes the aggregate
+/// loading progress to a closure which produces its children. This can be used to show a
+/// progress bar while the assets for a panel are loading:
+///
+/// ```ignore
+/// Preload::new(["textures/icons.png", "fonts/mono.ttf"], |progress| {
+///     Cond::new(
+///         progress.is_complete(),
+///         EditorPanel,
+///         ProgressBar::new(progress.fraction()),
+///     )
+/// })
+/// ```
+///
+/// Alternatively, with `blocking(true)` the closure is not called until loading is finished,
+/// and the `fallback` view is shown instead. Assets which fail to load count towards
+/// completion, so that a missing file doesn't block the UI forever; check
+/// [`PreloadProgress::failed`] to handle errors.
+///
+/// The assets are kept loaded for as long as the view is mounted.
+#[derive(Clone)]
+pub struct Preload {
+    paths: Vec<AssetPath<'static>>,
+    blocking: bool,
+    fallback: ViewChild,
+    content: PreloadContentFn,
+}
+
+impl Preload {
+    /// Construct a new `Preload` view from a list of asset paths, and a closure which produces
+    /// the child views.
+    pub fn new<P, V, F>(paths: impl IntoIterator<Item = P>, content: F) -> Self
+    where
+        P: Into<AssetPath<'static>>,
+        V: IntoViewChild,
+        F: Fn(PreloadProgress) -> V + Send + Sync + 'static,
+    {
+        Self {
+            paths: paths.into_iter().map(|path| path.into()).collect(),
+            blocking: false,
+            fallback: ViewChild::default(),
+            content: Arc::new(move |progress| content(progress).into_view_child()),
+        }
+    }
+
+    /// If true, don't render the children until all of the assets have loaded.
+    pub fn blocking(mut self, blocking: bool) -> Self {
+        self.blocking = blocking;
+        self
+    }
+
+    /// Set the view to display while a blocking preload is in progress.
+    pub fn fallback(mut self, fallback: impl IntoViewChild) -> Self {
+        self.fallback = fallback.into_view_child();
+        self
+    }
+}
+
+impl PartialEq for Preload {
+    fn eq(&self, other: &Self) -> bool {
+        self.paths == other.paths
+            && self.blocking == other.blocking
+            && self.fallback == other.fallback
+            && Arc::ptr_eq(&self.content, &other.content)
+    }
+}
+
+impl ViewTemplate for Preload {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let owner = cx.owner();
+        cx.create_effect(
+            move |world, paths| {
+                let server = world.resource::<AssetServer>();
+                let handles: Vec<UntypedHandle> = paths
+                    .into_iter()
+                    .map(|path: AssetPath<'static>| server.load_untyped(path).untyped())
+                    .collect();
+                let progress = PreloadProgress {
+                    total: handles.len(),
+                    ..Default::default()
+                };
+                world
+                    .entity_mut(owner)
+                    .insert((PreloadHandles(handles), progress));
+            },
+            self.paths.clone(),
+        );
+        let progress = cx
+            .use_component::<PreloadProgress>(owner)
+            .copied()
+            .unwrap_or_default();
+        let ready = !self.blocking || progress.is_complete();
+        let content = if ready {
+            (self.content)(progress)
+        } else {
+            ViewChild::default()
+        };
+        Cond::new(ready, content, self.fallback.clone())
+    }
+}
+
+/// System which updates the progress of [`Preload`] views. The progress is only written
+/// when it changes, so that views are not re-rendered every frame.
+pub(crate) fn update_preload_progress(
+    mut query: Query<(&PreloadHandles, &mut PreloadProgress)>,
+    server: Option<Res<AssetServer>>,
+) {
+    let Some(server) = server else {
+        return;
+    };
+    for (handles, mut progress) in query.iter_mut() {
+        let mut next = PreloadProgress {
+            total: handles.0.len(),
+            ..Default::default()
+        };
+        for handle in handles.0.iter() {
+            match server.get_recursive_dependency_load_state(handle.id()) {
+                Some(RecursiveDependencyLoadState::Loaded) => next.loaded += 1,
+                Some(RecursiveDependencyLoadState::Failed) => next.failed += 1,
+                _ => {}
+            }
+        }
+        progress.set_if_neq(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preload_progress() {
+        let progress = PreloadProgress::default();
+        assert!(progress.is_complete());
+        assert_eq!(progress.fraction(), 1.0);
+
+        let progress = PreloadProgress {
+            loaded: 1,
+            failed: 1,
+            total: 4,
+        };
+        assert!(!progress.is_complete());
+        assert_eq!(progress.fraction(), 0.5);
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bevy::prelude::Component;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum UpdateMode {
+    EveryFrame,
+    FixedInterval(Duration),
+    OnDemand,
+}
+
+/// Component which controls how often a view hierarchy reacts to changes. Insert it on the
+/// same entity as the [`ViewRoot`]; roots without an `UpdateSchedule` react every frame.
+///
+/// Throttling is useful for views which display rapidly-changing values that don't need to be
+/// shown at full frame rate, such as a stats panel:
+///
+/// ```ignore
+/// commands.spawn((StatsPanel.to_root(), UpdateSchedule::fixed_rate(10.0)));
+/// ```
+///
+/// Changes which happen while a root is throttled are not lost: they are applied the next
+/// time the root is due for an update.
+///
+/// [`ViewRoot`]: crate::ViewRoot
+#[derive(Component, Debug)]
+pub struct UpdateSchedule {
+    mode: UpdateMode,
+    last_update: Option<Duration>,
+    invalidated: Arc<AtomicBool>,
+}
+
+impl UpdateSchedule {
+    fn new(mode: UpdateMode) -> Self {
+        Self {
+            mode,
+            last_update: None,
+            invalidated: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// React to changes every frame. This is the default.
+    pub fn every_frame() -> Self {
+        Self::new(UpdateMode::EveryFrame)
+    }
+
+    /// React to changes at most `hz` times per second.
+    pub fn fixed_rate(hz: f32) -> Self {
+        Self::new(UpdateMode::FixedInterval(Duration::from_secs_f32(1.0 / hz)))
+    }
+
+    /// React to changes at most once per `interval`.
+    pub fn fixed_interval(interval: Duration) -> Self {
+        Self::new(UpdateMode::FixedInterval(interval))
+    }
+
+    /// Only react when invalidated via an [`InvalidateHandle`].
+    pub fn on_demand() -> Self {
+        Self::new(UpdateMode::OnDemand)
+    }
+
+    /// Returns a handle which can be used to request an update of this root.
+    pub fn handle(&self) -> InvalidateHandle {
+        InvalidateHandle(self.invalidated.clone())
+    }
+
+    /// True if this schedule ever skips frames.
+    pub(crate) fn is_throttled(&self) -> bool {
+        self.mode != UpdateMode::EveryFrame
+    }
+
+    /// True if the root has been explicitly invalidated since its last update.
+    pub(crate) fn is_invalidated(&self) -> bool {
+        self.invalidated.load(Ordering::Relaxed)
+    }
+
+    /// True if the root should react to changes at time `now`.
+    pub(crate) fn is_due(&self, now: Duration) -> bool {
+        match self.mode {
+            UpdateMode::EveryFrame => true,
+            UpdateMode::FixedInterval(interval) => {
+                self.is_invalidated()
+                    || self
+                        .last_update
+                        .is_none_or(|last| now.saturating_sub(last) >= interval)
+            }
+            UpdateMode::OnDemand => self.is_invalidated(),
+        }
+    }
+
+    /// Record that the root has been updated at time `now`.
+    pub(crate) fn mark_updated(&mut self, now: Duration) {
+        self.last_update = Some(now);
+        self.invalidated.store(false, Ordering::Relaxed);
+    }
+}
+
+impl Default for UpdateSchedule {
+    fn default() -> Self {
+        Self::every_frame()
+    }
+}
+
+/// Handle which requests an update of a view root with an [`UpdateSchedule`]. Invalidating a
+/// root re-runs its top-level template on the next frame, even if none of its dependencies
+/// have changed. Handles are cheap to clone and can be stored in resources or callbacks.
+#[derive(Clone, Debug)]
+pub struct InvalidateHandle(Arc<AtomicBool>);
+
+impl InvalidateHandle {
+    /// Request an update of the root.
+    pub fn invalidate(&self) {
+        // An invalidated root is due for an update, which forces the next reaction pass to
+        // scan the scopes.
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_interval() {
+        let mut schedule = UpdateSchedule::fixed_interval(Duration::from_millis(100));
+        assert!(schedule.is_due(Duration::ZERO));
+        schedule.mark_updated(Duration::ZERO);
+        assert!(!schedule.is_due(Duration::from_millis(50)));
+        assert!(schedule.is_due(Duration::from_millis(100)));
+
+        // Invalidation forces an update before the interval has elapsed.
+        schedule.mark_updated(Duration::from_millis(100));
+        schedule.invalidated.store(true, Ordering::Relaxed);
+        assert!(schedule.is_due(Duration::from_millis(110)));
+    }
+
+    #[test]
+    fn test_on_demand() {
+        let mut schedule = UpdateSchedule::on_demand();
+        assert!(!schedule.is_due(Duration::from_secs(10)));
+        schedule.invalidated.store(true, Ordering::Relaxed);
+        assert!(schedule.is_due(Duration::from_secs(10)));
+        schedule.mark_updated(Duration::from_secs(10));
+        assert!(!schedule.is_due(Duration::from_secs(20)));
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::ecs::world::{DeferredWorld, World};
+use bevy::hierarchy::BuildWorldChildren;
+use bevy::prelude::Entity;
+
+use crate::{Cx, NodeSpan, View};
+
+/// A `SceneView` attaches the display nodes of its children to an entity in the 2D or 3D scene,
+/// such as a transform, rather than to the UI tree. This allows the same templates and hooks
+/// which are used for UI to produce reactive in-world content: sprites, meshes or gizmos.
+/// Children will typically be elements with a non-UI bundle, such as
+/// `Element::<SpatialBundle>` or `Element::<PbrBundle>`.
+///
+/// Unlike UI elements, which replace all of the children of their display node, a `SceneView`
+/// only manages the children it produced itself: other children of the scene parent are left
+/// alone. Like a [`Portal`], it produces no nodes in the enclosing view.
+///
+/// [`Portal`]: crate::Portal
+pub struct SceneView<A: View> {
+    parent: Entity,
+    children: A,
+}
+
+impl<A: View> SceneView<A> {
+    /// Construct a new [`SceneView`] which attaches its children to the given scene entity.
+    pub fn new(parent: Entity, children: A) -> Self {
+        Self { parent, children }
+    }
+
+    /// Re-parent the children's nodes to the scene parent, if they have changed.
+    fn attach(&self, world: &mut World, state: &mut (A::State, Entity, NodeSpan)) {
+        let nodes = NodeSpan::from_view(&self.children, world, &state.0);
+        if state.1 != self.parent {
+            // The scene parent changed, detach everything from the old one.
+            if let Some(mut old_parent) = world.get_entity_mut(state.1) {
+                old_parent.remove_children(state.2.as_slice());
+            }
+            state.1 = self.parent;
+            state.2 = NodeSpan::new();
+        } else if state.2.diff(&nodes).is_empty() {
+            return;
+        }
+
+        let Some(mut parent) = world.get_entity_mut(self.parent) else {
+            return;
+        };
+        let current: Vec<Entity> = state
+            .2
+            .iter()
+            .filter(|node| nodes.contains(*node))
+            .collect();
+        parent.remove_children(&current);
+        parent.push_children(nodes.as_slice());
+        state.2 = nodes;
+    }
+}
+
+impl<A: View> View for SceneView<A> {
+    /// Child state, the current scene parent, and the nodes which are attached to it.
+    type State = (A::State, Entity, NodeSpan);
+
+    fn nodes(&self, _world: &World, _state: &Self::State, _out: &mut Vec<Entity>) {}
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let mut state = (self.children.build(cx), self.parent, NodeSpan::new());
+        self.attach(cx.world_mut(), &mut state);
+        state
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        self.children.rebuild(cx, &mut state.0);
+        self.attach(cx.world_mut(), state);
+        false
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        self.children.attach_children(world, &mut state.0);
+        self.attach(world, state);
+        false
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        self.children.raze(world, &mut state.0);
+    }
+}
+
+impl<A: View + Clone> Clone for SceneView<A> {
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent,
+            children: self.children.clone(),
+        }
+    }
+}
+
+impl<A: View + PartialEq> PartialEq for SceneView<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.parent == other.parent && self.children.eq(&other.children)
+    }
+}
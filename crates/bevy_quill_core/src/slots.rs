@@ -0,0 +1,95 @@
+use crate::{IntoViewChild, ViewChild};
+
+/// A collection of named, type-erased child views. This allows a [`ViewTemplate`] to accept
+/// several distinct groups of children, such as a header, body and footer, rather than
+/// a single `children` parameter.
+///
+/// Templates store a `ViewSlots` as one of their fields and implement [`WithSlots`], which
+/// adds the `.children_slot()` builder method:
+///
+/// ```ignore
+/// Card::new()
+///     .children_slot("header", "Title")
+///     .children_slot("body", (Button::new().children("Ok"), "Some text"))
+/// ```
+///
+/// Inside `create()`, the template retrieves each group with [`ViewSlots::get`].
+///
+/// [`ViewTemplate`]: crate::ViewTemplate
+#[derive(Clone, Default, PartialEq)]
+pub struct ViewSlots(Vec<(&'static str, ViewChild)>);
+
+impl ViewSlots {
+    /// Create an empty set of slots.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the contents of the named slot, replacing any previous contents.
+    pub fn set(&mut self, name: &'static str, children: impl IntoViewChild) {
+        let children = children.into_view_child();
+        match self.0.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, slot)) => *slot = children,
+            None => self.0.push((name, children)),
+        }
+    }
+
+    /// Returns the contents of the named slot, or an empty view if the slot was not set.
+    pub fn get(&self, name: &str) -> ViewChild {
+        self.try_get(name).unwrap_or_default()
+    }
+
+    /// Returns the contents of the named slot, or `None` if the slot was not set.
+    pub fn try_get(&self, name: &str) -> Option<ViewChild> {
+        self.0
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, children)| children.clone())
+    }
+
+    /// Returns true if the named slot was set.
+    pub fn has(&self, name: &str) -> bool {
+        self.0.iter().any(|(n, _)| *n == name)
+    }
+
+    /// Iterate over the names of the slots which were set, in the order they were first set.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.iter().map(|(n, _)| *n)
+    }
+}
+
+/// Trait for templates which accept named groups of children.
+pub trait WithSlots: Sized {
+    /// Returns a mutable reference to the template's slots.
+    fn slots_mut(&mut self) -> &mut ViewSlots;
+
+    /// Set the child views for the named slot.
+    fn children_slot(mut self, name: &'static str, children: impl IntoViewChild) -> Self {
+        self.slots_mut().set(name, children);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots() {
+        let mut slots = ViewSlots::new();
+        assert!(!slots.has("header"));
+        assert!(slots.try_get("header").is_none());
+
+        slots.set("header", "Title");
+        slots.set("footer", "Footer");
+        let header = slots.get("header");
+        assert!(slots.has("header"));
+        assert!(slots.get("header") == header);
+        assert_eq!(slots.names().collect::<Vec<_>>(), vec!["header", "footer"]);
+
+        // Replacing a slot keeps its position.
+        slots.set("header", "New Title");
+        assert!(slots.get("header") != header);
+        assert_eq!(slots.names().collect::<Vec<_>>(), vec!["header", "footer"]);
+    }
+}
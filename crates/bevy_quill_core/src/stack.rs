@@ -0,0 +1,140 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+
+use crate::{Cx, Element, IntoViewChild, View, ViewChild, ViewTemplate};
+
+fn style_stack(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Grid)
+        .grid_template_columns(vec![ui::RepeatedGridTrack::flex(1, 1.)])
+        .grid_template_rows(vec![ui::RepeatedGridTrack::flex(1, 1.)]);
+}
+
+fn style_stack_layer(ss: &mut StyleBuilder) {
+    ss.grid_row(ui::GridPlacement::start(1))
+        .grid_column(ui::GridPlacement::start(1));
+}
+
+/// A widget which layers its children on top of each other, for example a floating toolbar
+/// over a canvas, or a badge over a preview image. The stack is as large as its largest
+/// layer; later layers are drawn on top of earlier ones.
+///
+/// ```ignore
+/// Stack::new().children((
+///     StackLayer::new().children(Canvas),
+///     StackLayer::new()
+///         .align(ui::JustifySelf::End, ui::AlignSelf::Start)
+///         .passthrough(true)
+///         .children(Toolbar),
+/// ))
+/// ```
+///
+/// Each child should be a [`StackLayer`].
+#[derive(Clone, PartialEq, Default)]
+pub struct Stack {
+    style: StyleHandle,
+    children: ViewChild,
+}
+
+impl Stack {
+    /// Create a new stack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set additional styles to be applied to the stack.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the layers of the stack.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+impl ViewTemplate for Stack {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .style((style_stack, self.style.clone()))
+            .children(self.children.clone())
+    }
+}
+
+/// A single layer of a [`Stack`].
+#[derive(Clone, PartialEq)]
+pub struct StackLayer {
+    justify: ui::JustifySelf,
+    align: ui::AlignSelf,
+    passthrough: bool,
+    style: StyleHandle,
+    children: ViewChild,
+}
+
+impl Default for StackLayer {
+    fn default() -> Self {
+        Self {
+            justify: ui::JustifySelf::Stretch,
+            align: ui::AlignSelf::Stretch,
+            passthrough: false,
+            style: StyleHandle::default(),
+            children: Default::default(),
+        }
+    }
+}
+
+impl StackLayer {
+    /// Create a new layer which fills the stack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the horizontal and vertical alignment of the layer within the stack.
+    pub fn align(mut self, horizontal: ui::JustifySelf, vertical: ui::AlignSelf) -> Self {
+        self.justify = horizontal;
+        self.align = vertical;
+        self
+    }
+
+    /// If true, the layer itself does not receive pointer events, so that clicks on the empty
+    /// parts of the layer reach the layers underneath. Children of the layer can still be
+    /// clicked.
+    pub fn passthrough(mut self, passthrough: bool) -> Self {
+        self.passthrough = passthrough;
+        self
+    }
+
+    /// Set additional styles to be applied to the layer.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the child views for this layer.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+impl ViewTemplate for StackLayer {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .style(style_stack_layer)
+            .style_dyn(
+                |(justify, align, passthrough), sb| {
+                    sb.justify_self(justify)
+                        .align_self(align)
+                        .pointer_events(!passthrough);
+                },
+                (self.justify, self.align, self.passthrough),
+            )
+            .style(self.style.clone())
+            .children(self.children.clone())
+    }
+}
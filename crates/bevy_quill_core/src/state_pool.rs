@@ -0,0 +1,127 @@
+use std::any::{Any, TypeId};
+
+use bevy::{
+    ecs::change_detection::DetectChangesMut,
+    prelude::{Resource, World},
+    utils::HashMap,
+};
+
+use crate::BoxedState;
+
+/// Maximum number of free boxes kept for each state type.
+const MAX_FREE_PER_TYPE: usize = 32;
+
+/// Counters which measure how effective the [`ViewStatePool`] is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ViewStatePoolStats {
+    /// Number of view states which needed a new allocation.
+    pub allocated: usize,
+    /// Number of view states which reused the allocation of a razed state.
+    pub reused: usize,
+    /// Number of razed states which were returned to the pool.
+    pub released: usize,
+}
+
+/// Resource which keeps the boxes of type-erased view states after their views are razed, so
+/// that the allocations can be reused when a view of the same type is built again. This reduces
+/// allocator churn in views such as [`Dynamic`](crate::Dynamic) and [`Switch`](crate::Switch)
+/// which swap their children back and forth.
+///
+/// A pooled box still holds the razed state until it is reused, so the number of boxes kept
+/// for each type is limited.
+#[derive(Resource, Default)]
+pub struct ViewStatePool {
+    free: HashMap<TypeId, Vec<BoxedState>>,
+    stats: ViewStatePoolStats,
+}
+
+impl ViewStatePool {
+    /// Counters for the number of allocations which were made and reused.
+    pub fn stats(&self) -> ViewStatePoolStats {
+        self.stats
+    }
+
+    /// Reset the counters to zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = ViewStatePoolStats::default();
+    }
+
+    /// Drop all of the pooled boxes.
+    pub fn clear(&mut self) {
+        self.free.clear();
+    }
+}
+
+/// Box a view state, reusing a pooled allocation if there is one.
+pub(crate) fn alloc_state<S: Any + Send + Sync>(world: &mut World, state: S) -> BoxedState {
+    let Some(mut pool) = world.get_resource_mut::<ViewStatePool>() else {
+        return Box::new(state);
+    };
+    // The pool is internal bookkeeping, so it shouldn't trigger reactions.
+    let pool = pool.bypass_change_detection();
+    if let Some(mut boxed) = pool
+        .free
+        .get_mut(&TypeId::of::<S>())
+        .and_then(|free| free.pop())
+    {
+        *boxed.downcast_mut::<S>().unwrap() = state;
+        pool.stats.reused += 1;
+        return boxed;
+    }
+    pool.stats.allocated += 1;
+    Box::new(state)
+}
+
+/// Return the box of a razed view state to the pool.
+pub(crate) fn release_state(world: &mut World, state: BoxedState) {
+    let Some(mut pool) = world.get_resource_mut::<ViewStatePool>() else {
+        return;
+    };
+    let pool = pool.bypass_change_detection();
+    let free = pool.free.entry(state.as_ref().type_id()).or_default();
+    if free.len() < MAX_FREE_PER_TYPE {
+        free.push(state);
+        pool.stats.released += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuse() {
+        let mut world = World::default();
+        world.init_resource::<ViewStatePool>();
+
+        let state = alloc_state(&mut world, 1u32);
+        let ptr = state.as_ref() as *const _ as *const u8;
+        release_state(&mut world, state);
+
+        // A state of another type doesn't reuse the box.
+        let other = alloc_state(&mut world, 1u64);
+        assert_eq!(*other.downcast_ref::<u64>().unwrap(), 1);
+
+        let state = alloc_state(&mut world, 2u32);
+        assert_eq!(*state.downcast_ref::<u32>().unwrap(), 2);
+        assert_eq!(state.as_ref() as *const _ as *const u8, ptr);
+
+        let stats = world.resource::<ViewStatePool>().stats();
+        assert_eq!(
+            stats,
+            ViewStatePoolStats {
+                allocated: 2,
+                reused: 1,
+                released: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_no_pool() {
+        let mut world = World::default();
+        let state = alloc_state(&mut world, 1u32);
+        release_state(&mut world, state);
+        assert!(!world.contains_resource::<ViewStatePool>());
+    }
+}
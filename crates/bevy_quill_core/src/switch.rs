@@ -1,4 +1,7 @@
-use crate::{AnyView, BoxedState, Cx, View};
+use crate::{
+    state_pool::{alloc_state, release_state},
+    AnyView, BoxedState, Cx, View,
+};
 use bevy::ecs::world::{DeferredWorld, World};
 use bevy::prelude::Entity;
 
@@ -178,7 +181,10 @@ impl<
         let index = self.cases.find(&self.value);
         match index {
             Some(ndx) => (Some(ndx), self.cases.at(ndx).build(cx)),
-            None => (None, Box::new(self.fallback.build(cx))),
+            None => {
+                let state = self.fallback.build(cx);
+                (None, alloc_state(cx.world_mut(), state))
+            }
         }
     }
 
@@ -194,7 +200,9 @@ impl<
                     .at(index)
                     .raze(&mut DeferredWorld::from(cx.world_mut()), &mut state.1);
                 state.0 = Some(new_index);
-                state.1 = self.cases.at(new_index).build(cx);
+                let new_state = self.cases.at(new_index).build(cx);
+                let old_state = std::mem::replace(&mut state.1, new_state);
+                release_state(cx.world_mut(), old_state);
                 true
             }
 
@@ -203,7 +211,10 @@ impl<
                     .at(index)
                     .raze(&mut DeferredWorld::from(cx.world_mut()), &mut state.1);
                 state.0 = None;
-                state.1 = Box::new(self.fallback.build(cx));
+                let new_state = self.fallback.build(cx);
+                let new_state = alloc_state(cx.world_mut(), new_state);
+                let old_state = std::mem::replace(&mut state.1, new_state);
+                release_state(cx.world_mut(), old_state);
                 true
             }
 
@@ -213,7 +224,9 @@ impl<
                         .raze(&mut DeferredWorld::from(cx.world_mut()), st)
                 }
                 state.0 = Some(new_index);
-                state.1 = self.cases.at(new_index).build(cx);
+                let new_state = self.cases.at(new_index).build(cx);
+                let old_state = std::mem::replace(&mut state.1, new_state);
+                release_state(cx.world_mut(), old_state);
                 true
             }
 
@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use bevy::{
+    core::Name,
+    ecs::world::DeferredWorld,
+    hierarchy::{BuildChildren, BuildWorldChildren},
+    prelude::{Component, Entity, World},
+};
+
+use crate::{
+    text_view::{build_text_view, rebuild_text_view},
+    tracking_scope::TrackingScope,
+    AnyViewAdapter, Cx, View, ViewThunk,
+};
+
+type TextFn = Arc<dyn Fn(&mut Cx) -> String + Send + Sync>;
+
+/// A text view whose content is computed by a closure. The closure runs in its own reactive
+/// scope, so when a resource or mutable that it reads changes, only the text is recomputed,
+/// rather than the whole enclosing template. The text node is only updated if the resulting
+/// string is different.
+///
+/// The [`format_text!`] macro provides a shorthand for the common case:
+///
+/// ```ignore
+/// let counter = cx.create_mutable(0);
+/// Element::<NodeBundle>::new().children((
+///     "Count: ",
+///     format_text!(cx => "{}", counter.get(cx)),
+/// ))
+/// ```
+///
+/// [`format_text!`]: crate::format_text
+pub struct TextComputed {
+    text: TextFn,
+}
+
+impl TextComputed {
+    /// Construct a new `TextComputed` view from a closure which produces the text.
+    pub fn new<F: Fn(&mut Cx) -> String + Send + Sync + 'static>(text: F) -> Self {
+        Self {
+            text: Arc::new(text),
+        }
+    }
+}
+
+/// Component on the scope entity of a [`TextComputed`] which holds the closure and the
+/// text node.
+#[derive(Component)]
+struct TextComputedCell {
+    text: TextFn,
+    node: Entity,
+}
+
+impl View for TextComputed {
+    /// The scope entity, and the text node as of the last time children were attached.
+    type State = (Entity, Entity);
+
+    fn nodes(&self, world: &World, state: &Self::State, out: &mut Vec<Entity>) {
+        if let Some(cell) = world.get::<TextComputedCell>(state.0) {
+            out.push(cell.node);
+        }
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let tick = cx.world_mut().change_tick();
+        let parent = cx.owner();
+        let scope_entity = cx
+            .world_mut()
+            .spawn(Name::new("TextComputed"))
+            .set_parent(parent)
+            .id();
+        let mut scope = TrackingScope::new(tick);
+        let mut cx_inner = Cx::new(cx.world_mut(), scope_entity, &mut scope);
+        let text = (self.text)(&mut cx_inner);
+        let node = build_text_view(cx.world_mut(), &text);
+        cx.world_mut().entity_mut(scope_entity).insert((
+            TextComputedCell {
+                text: self.text.clone(),
+                node,
+            },
+            scope,
+            ViewThunk(&TextComputedAdapter),
+        ));
+        (scope_entity, node)
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        // Closures can't be compared, so replace the closure and let the scope recompute the
+        // text. If the string is unchanged, this is cheap.
+        let mut entt = cx.world_mut().entity_mut(state.0);
+        if let Some(mut cell) = entt.get_mut::<TextComputedCell>() {
+            cell.text = self.text.clone();
+        }
+        if let Some(scope) = entt.get::<TrackingScope>() {
+            scope.set_changed(entt.world());
+        }
+        false
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        let Some(cell) = world.get::<TextComputedCell>(state.0) else {
+            return false;
+        };
+        if cell.node != state.1 {
+            state.1 = cell.node;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        if let Some(cell) = world.get::<TextComputedCell>(state.0) {
+            let node = cell.node;
+            world.commands().entity(node).remove_parent().despawn();
+        }
+        world.commands().entity(state.0).remove_parent().despawn();
+    }
+}
+
+/// Adapter which recomputes the text of a [`TextComputed`] when its dependencies change.
+struct TextComputedAdapter;
+
+impl AnyViewAdapter for TextComputedAdapter {
+    fn nodes(&self, world: &mut World, entity: Entity, out: &mut Vec<Entity>) {
+        if let Some(cell) = world.get::<TextComputedCell>(entity) {
+            out.push(cell.node);
+        }
+    }
+
+    fn rebuild(&self, world: &mut World, entity: Entity, scope: &mut TrackingScope) -> bool {
+        let Some(cell) = world.get::<TextComputedCell>(entity) else {
+            return false;
+        };
+        let text_fn = cell.text.clone();
+        let mut node = cell.node;
+        let mut cx = Cx::new(world, entity, scope);
+        let text = text_fn(&mut cx);
+        let changed = rebuild_text_view(world, &text, &mut node);
+        if changed {
+            world.get_mut::<TextComputedCell>(entity).unwrap().node = node;
+        }
+        changed
+    }
+
+    fn raze(&self, _world: &mut DeferredWorld, _entity: Entity) {}
+
+    fn attach_children(&self, _world: &mut World, _entity: Entity) -> bool {
+        false
+    }
+}
+
+/// Create a [`TextComputed`] view using `format!` syntax. The first argument names the
+/// [`Cx`] parameter of the closure, which can be used to read reactive values:
+///
+/// ```ignore
+/// format_text!(cx => "{} of {}", index.get(cx) + 1, cx.use_resource::<Items>().len())
+/// ```
+#[macro_export]
+macro_rules! format_text {
+    ($cx:ident => $($arg:tt)*) => {
+        $crate::TextComputed::new(move |$cx: &mut $crate::Cx| format!($($arg)*))
+    };
+}
@@ -10,7 +10,7 @@ use bevy::{
 use bevy::log::info;
 use bevy_mod_stylebuilder::UseInheritedTextStyles;
 
-use crate::{cx::Cx, View};
+use crate::{cx::Cx, View, ViewMemo};
 
 impl View for String {
     type State = Entity;
@@ -34,6 +34,9 @@ impl View for String {
         // Delete the text node.
         world.commands().entity(*state).remove_parent().despawn();
     }
+    fn memo(&self) -> Option<ViewMemo> {
+        Some(ViewMemo::new(self.clone()))
+    }
 }
 
 impl<'a: 'static> View for &'a str {
@@ -58,9 +61,12 @@ impl<'a: 'static> View for &'a str {
         // Delete the text node.
         world.commands().entity(*state).remove_parent().despawn();
     }
+    fn memo(&self) -> Option<ViewMemo> {
+        Some(ViewMemo::new(*self))
+    }
 }
 
-fn build_text_view(world: &mut World, text: &str) -> Entity {
+pub(crate) fn build_text_view(world: &mut World, text: &str) -> Entity {
     world
         .spawn((
             TextBundle {
@@ -72,7 +78,7 @@ fn build_text_view(world: &mut World, text: &str) -> Entity {
         .id()
 }
 
-fn rebuild_text_view(world: &mut World, text: &str, state: &mut Entity) -> bool {
+pub(crate) fn rebuild_text_view(world: &mut World, text: &str, state: &mut Entity) -> bool {
     // If it's a single node and has a text component
     let mut entt = world.entity_mut(*state);
     if let Some(mut old_text) = entt.get_mut::<Text>() {
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::Cx;
+
+/// The game time, as seen by a view which called [`Cx::use_time`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimeSnapshot {
+    /// Time elapsed since the app started, as of the last update.
+    pub elapsed: Duration,
+    /// Time elapsed between the previous update and the last one. This is at least the
+    /// throttle interval, rather than the length of a single frame.
+    pub delta: Duration,
+}
+
+impl TimeSnapshot {
+    /// [`TimeSnapshot::elapsed`] in seconds.
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    /// [`TimeSnapshot::delta`] in seconds.
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+}
+
+/// Component which holds the time seen by a [`Cx::use_time`] hook. It is only updated once
+/// the throttle interval has passed, so that views which depend on it don't react every frame.
+#[derive(Component)]
+pub(crate) struct ThrottledTime {
+    interval: Duration,
+    snapshot: TimeSnapshot,
+}
+
+impl<'p, 'w> Cx<'p, 'w> {
+    /// Return the current game time, and re-run the current presenter when it changes, but
+    /// no more often than once every `throttle` interval. This makes it possible to build
+    /// clocks, FPS counters and simple animations out of ordinary templates:
+    ///
+    /// ```ignore
+    /// let time = cx.use_time(Duration::from_millis(500));
+    /// let fps = 1. / cx.use_resource_untracked::<Time>().delta_seconds();
+    /// format!("{:.0} FPS at {:.1}s", fps, time.elapsed_seconds())
+    /// ```
+    ///
+    /// A throttle of [`Duration::ZERO`] updates every frame.
+    pub fn use_time(&mut self, throttle: Duration) -> TimeSnapshot {
+        let entity = self.create_entity();
+        let elapsed = self.world().resource::<Time>().elapsed();
+        let mut entt = self.world_mut().entity_mut(entity);
+        match entt.get_mut::<ThrottledTime>() {
+            Some(mut throttled) => {
+                if throttled.interval != throttle {
+                    throttled.bypass_change_detection().interval = throttle;
+                }
+            }
+            None => {
+                entt.insert(ThrottledTime {
+                    interval: throttle,
+                    snapshot: TimeSnapshot {
+                        elapsed,
+                        delta: Duration::ZERO,
+                    },
+                });
+            }
+        }
+        self.use_component::<ThrottledTime>(entity)
+            .unwrap()
+            .snapshot
+    }
+}
+
+/// Update the time seen by each [`Cx::use_time`] hook whose throttle interval has passed.
+pub(crate) fn update_throttled_time(time: Res<Time>, mut query: Query<&mut ThrottledTime>) {
+    let elapsed = time.elapsed();
+    for mut throttled in query.iter_mut() {
+        let delta = elapsed.saturating_sub(throttled.snapshot.elapsed);
+        if delta > Duration::ZERO && delta >= throttled.interval {
+            throttled.snapshot = TimeSnapshot { elapsed, delta };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn test_throttled_time() {
+        let mut world = World::default();
+        world.init_resource::<Time>();
+        let entity = world
+            .spawn(ThrottledTime {
+                interval: Duration::from_millis(100),
+                snapshot: TimeSnapshot::default(),
+            })
+            .id();
+        let snapshot = |world: &World| world.get::<ThrottledTime>(entity).unwrap().snapshot;
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(60));
+        world.run_system_once(update_throttled_time);
+        assert_eq!(snapshot(&world), TimeSnapshot::default());
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(60));
+        world.run_system_once(update_throttled_time);
+        assert_eq!(
+            snapshot(&world),
+            TimeSnapshot {
+                elapsed: Duration::from_millis(120),
+                delta: Duration::from_millis(120),
+            }
+        );
+    }
+}
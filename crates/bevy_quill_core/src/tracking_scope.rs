@@ -1,6 +1,9 @@
 use std::{
     any::Any,
-    sync::{atomic::AtomicBool, Arc},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
 };
 
 use bevy::{
@@ -22,6 +25,8 @@ pub(crate) enum HookState {
     Callback(Arc<dyn AnyCallback + Send + Sync>),
     Effect(Arc<dyn Any + Send + Sync + 'static>),
     Memo(Arc<dyn Any + Send + Sync + 'static>),
+    /// The change tick of the reaction which last ran the hook.
+    Tick(Tick),
 }
 
 /// A component that tracks the dependencies of a reactive task.
@@ -43,6 +48,13 @@ pub struct TrackingScope {
     /// a component or resource dependency mutation.
     changed: AtomicBool,
 
+    /// Whether the scope reads layout data, such as with [`Cx::use_measured_size`], in which
+    /// case it is also re-run in the [`QuillSet::Measure`] phase.
+    ///
+    /// [`Cx::use_measured_size`]: crate::Cx::use_measured_size
+    /// [`QuillSet::Measure`]: crate::QuillSet::Measure
+    reads_layout: bool,
+
     /// Engine tick used for determining if components have changed. This represents the
     /// time of the previous reaction.
     pub(crate) tick: Tick,
@@ -52,6 +64,70 @@ pub struct TrackingScope {
     pub(crate) cleanups: Vec<Box<dyn FnOnce(&mut DeferredWorld) + 'static + Sync + Send>>,
 }
 
+/// Resource holding a counter which is advanced whenever a tracking scope is added, or is
+/// explicitly marked as changed. Together with [`ReactionEpoch`], this lets the reaction control
+/// system detect idle frames without visiting every scope. The counter is atomic so that it can
+/// be advanced while scopes are borrowed from the world.
+#[derive(Resource, Default)]
+pub(crate) struct DirtyEpoch(AtomicU64);
+
+impl DirtyEpoch {
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The current dirty epoch of the world.
+fn dirty_epoch(world: &World) -> u64 {
+    world
+        .get_resource::<DirtyEpoch>()
+        .map_or(0, DirtyEpoch::get)
+}
+
+/// Advance the dirty epoch, forcing the next reaction pass to scan all scopes.
+pub(crate) fn mark_dirty(world: &World) {
+    if let Some(epoch) = world.get_resource::<DirtyEpoch>() {
+        epoch.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Resource which records the state of the world as of the last full reaction pass: the dirty
+/// epoch, and the combined dependencies of all tracking scopes. If the epoch has not advanced
+/// and none of the combined dependencies have changed, then no scope can have changed either,
+/// and the pass can be skipped.
+#[derive(Resource)]
+pub(crate) struct ReactionEpoch {
+    epoch: u64,
+    deps: TrackingScope,
+}
+
+impl ReactionEpoch {
+    /// Capture the current epoch of `world` and the union of the dependencies of the given
+    /// scopes.
+    pub(crate) fn capture<'a>(
+        world: &World,
+        tick: Tick,
+        scopes: impl Iterator<Item = &'a TrackingScope>,
+    ) -> Self {
+        let mut deps = TrackingScope::new(tick);
+        for scope in scopes {
+            deps.component_deps
+                .extend(scope.component_deps.iter().copied());
+            deps.resource_deps
+                .extend(scope.resource_deps.iter().copied());
+        }
+        Self {
+            epoch: dirty_epoch(world),
+            deps,
+        }
+    }
+
+    /// Returns true if nothing that any scope depends on has changed since the last pass.
+    pub(crate) fn is_clean(&self, world: &World, tick: Tick) -> bool {
+        self.epoch == dirty_epoch(world) && !self.deps.dependencies_changed(world, tick)
+    }
+}
+
 /// A resource which, if inserted, displays the view entities that have reacted this frame.
 #[derive(Resource)]
 pub struct TrackingScopeTracing(pub Vec<Entity>);
@@ -71,6 +147,7 @@ impl TrackingScope {
             component_deps: HashSet::default(),
             resource_deps: HashSet::default(),
             changed: AtomicBool::new(false),
+            reads_layout: false,
             tick,
             cleanups: Vec::new(),
         }
@@ -98,6 +175,12 @@ impl TrackingScope {
         }
     }
 
+    /// Return to the first hook, so that the hooks can be replayed by a second invocation of
+    /// the same presenter.
+    pub(crate) fn rewind_hooks(&mut self) {
+        self.next_hook_index = 0;
+    }
+
     /// Add a cleanup function which will be run once before the next reaction.
     pub(crate) fn add_cleanup(
         &mut self,
@@ -136,10 +219,36 @@ impl TrackingScope {
         self.component_deps.insert((entity, component, exists));
     }
 
+    /// Iterate over the resources that this scope depends on.
+    pub(crate) fn resource_deps(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.resource_deps.iter().copied()
+    }
+
+    /// Iterate over the entity components that this scope depends on.
+    pub(crate) fn component_deps(&self) -> impl Iterator<Item = (Entity, ComponentId)> + '_ {
+        self.component_deps.iter().map(|(e, c, _)| (*e, *c))
+    }
+
     /// Mark the scope as changed for reasons other than a component or resource dependency.
-    pub(crate) fn set_changed(&self) {
-        self.changed
-            .store(true, std::sync::atomic::Ordering::Relaxed);
+    /// `world` is the world which contains the scope.
+    pub(crate) fn set_changed(&self, world: &World) {
+        self.changed.store(true, Ordering::Relaxed);
+        mark_dirty(world);
+    }
+
+    /// Mark the scope as reading layout data.
+    pub(crate) fn set_reads_layout(&mut self) {
+        self.reads_layout = true;
+    }
+
+    /// Returns true if the scope read layout data during its last reaction.
+    pub(crate) fn reads_layout(&self) -> bool {
+        self.reads_layout
+    }
+
+    /// Clear the explicit change flag set by `set_changed`.
+    pub(crate) fn clear_changed(&self) {
+        self.changed.store(false, Ordering::Relaxed);
     }
 
     /// Returns true if any of the dependencies of this scope have been updated since
@@ -147,7 +256,7 @@ impl TrackingScope {
     pub(crate) fn dependencies_changed(&self, world: &World, tick: Tick) -> bool {
         self.components_changed(world, tick)
             || self.resources_changed(world, tick)
-            || self.changed.load(std::sync::atomic::Ordering::Relaxed)
+            || self.changed.load(Ordering::Relaxed)
     }
 
     pub(crate) fn components_changed(&self, world: &World, tick: Tick) -> bool {
@@ -177,10 +286,9 @@ impl TrackingScope {
         self.resource_deps = std::mem::take(&mut other.resource_deps);
         self.cleanups = std::mem::take(&mut other.cleanups);
         self.hook_states = std::mem::take(&mut other.hook_states);
-        self.changed.store(
-            other.changed.load(std::sync::atomic::Ordering::Relaxed),
-            std::sync::atomic::Ordering::Relaxed,
-        );
+        self.changed
+            .store(other.changed.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.reads_layout = other.reads_layout;
     }
 
     pub(crate) fn take_hooks(&mut self, other: &mut Self) {
@@ -192,13 +300,18 @@ struct DespawnEntityCmd(Entity);
 
 impl Command for DespawnEntityCmd {
     fn apply(self, world: &mut World) {
-        world.despawn(self.0);
+        // The entity may already have been despawned by `despawn_owned`.
+        if world.get_entity(self.0).is_some() {
+            world.despawn(self.0);
+        }
     }
 }
 
 pub(crate) fn cleanup_tracking_scopes(world: &mut World) {
     world
         .register_component_hooks::<TrackingScope>()
+        // A new scope has dependencies which are not yet part of the `ReactionEpoch`.
+        .on_add(|world, _entity, _component| mark_dirty(&world))
         .on_remove(|mut world, entity, _component| {
             let mut scope = world.get_mut::<TrackingScope>(entity).unwrap();
             let mut cleanups = std::mem::take(&mut scope.cleanups);
@@ -217,7 +330,7 @@ pub(crate) fn cleanup_tracking_scopes(world: &mut World) {
                     HookState::Callback(callback) => {
                         world.commands().add(UnregisterCallbackCmd(callback));
                     }
-                    HookState::Effect(_) | HookState::Memo(_) => {
+                    HookState::Effect(_) | HookState::Memo(_) | HookState::Tick(_) => {
                         // Nothing to do
                     }
                 }
@@ -230,9 +343,9 @@ pub struct TriggerReaction(pub Entity);
 
 impl Command for TriggerReaction {
     fn apply(self, world: &mut World) {
-        if let Some(mut scope_ent) = world.get_entity_mut(self.0) {
-            if let Some(scope) = scope_ent.get_mut::<TrackingScope>() {
-                TrackingScope::set_changed(&scope);
+        if let Some(scope_ent) = world.get_entity(self.0) {
+            if let Some(scope) = scope_ent.get::<TrackingScope>() {
+                scope.set_changed(world);
             } else {
                 warn!("No tracking scope found for entity {:?}", self.0);
             }
@@ -275,4 +388,59 @@ mod tests {
         let tick = world.change_tick();
         assert!(scope.dependencies_changed(&world, tick));
     }
+
+    #[test]
+    fn test_reaction_epoch() {
+        let mut world = World::default();
+        world.init_resource::<DirtyEpoch>();
+        world.insert_resource(TestResource(false));
+        let tick = world.change_tick();
+        let mut scope = TrackingScope::new(tick);
+        scope.track_resource::<TestResource>(&world);
+
+        // Nothing changed since the capture
+        let epoch = ReactionEpoch::capture(&world, tick, [&scope].into_iter());
+        assert!(epoch.is_clean(&world, tick));
+
+        // Mutate a resource that some scope depends on
+        world.increment_change_tick();
+        world.get_resource_mut::<TestResource>().unwrap().0 = true;
+        let tick = world.change_tick();
+        assert!(!epoch.is_clean(&world, tick));
+
+        // Explicitly mark a scope as changed
+        let epoch = ReactionEpoch::capture(&world, tick, [&scope].into_iter());
+        assert!(epoch.is_clean(&world, tick));
+        scope.set_changed(&world);
+        assert!(!epoch.is_clean(&world, tick));
+    }
+
+    #[test]
+    fn test_trigger_reaction() {
+        let mut world = World::default();
+        world.init_resource::<DirtyEpoch>();
+        let tick = world.change_tick();
+        let scope_ent = world.spawn(TrackingScope::new(tick)).id();
+        let epoch = dirty_epoch(&world);
+
+        TriggerReaction(scope_ent).apply(&mut world);
+        assert!(dirty_epoch(&world) > epoch);
+        let scope = world.get::<TrackingScope>(scope_ent).unwrap();
+        assert!(scope.changed.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_rewind_hooks() {
+        let mut world = World::default();
+        let tick = world.change_tick();
+        let mut scope = TrackingScope::new(tick);
+        let entity = world.spawn_empty().id();
+        scope.push_hook(HookState::Entity(entity));
+        assert!(scope.next_hook().is_none());
+
+        // Replaying returns the same hook.
+        scope.rewind_hooks();
+        assert!(matches!(scope.next_hook(), Some(HookState::Entity(e)) if e == entity));
+        assert!(scope.next_hook().is_none());
+    }
 }
@@ -0,0 +1,403 @@
+use std::{any::TypeId, collections::BTreeMap, fmt, sync::Arc};
+
+use bevy::{
+    app::{App, Plugin, Update},
+    asset::{
+        io::Reader, Asset, AssetApp, AssetEvent, AssetLoader, Assets, AsyncReadExt, Handle,
+        LoadContext,
+    },
+    log::warn,
+    prelude::{Component, EventReader, IntoSystem, IntoSystemConfigs, Query, Res, Resource, World},
+    reflect::{Reflect, TypePath},
+    utils::HashMap,
+};
+use serde::Deserialize;
+
+use crate::{
+    reaction_control_system, Callback, Cx, For, View, ViewDescription, ViewTemplate,
+    ViewTypeRegistry,
+};
+
+/// A property value in a UI definition.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum UiValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl UiValue {
+    /// Convert the value to a reflected value. Numbers are converted to the numeric type of the
+    /// target field, if known, since RON doesn't distinguish between numeric types.
+    fn to_reflect(&self, field_type: Option<TypeId>) -> Box<dyn Reflect> {
+        match self {
+            UiValue::Bool(value) => Box::new(*value),
+            UiValue::Int(value) => {
+                coerce_number(*value as f64, field_type).unwrap_or_else(|| Box::new(*value))
+            }
+            UiValue::Float(value) => {
+                coerce_number(*value, field_type).unwrap_or_else(|| Box::new(*value as f32))
+            }
+            UiValue::String(value) => Box::new(value.clone()),
+        }
+    }
+}
+
+fn coerce_number(value: f64, field_type: Option<TypeId>) -> Option<Box<dyn Reflect>> {
+    let field_type = field_type?;
+    if field_type == TypeId::of::<f32>() {
+        Some(Box::new(value as f32))
+    } else if field_type == TypeId::of::<f64>() {
+        Some(Box::new(value))
+    } else if field_type == TypeId::of::<i32>() {
+        Some(Box::new(value as i32))
+    } else if field_type == TypeId::of::<i64>() {
+        Some(Box::new(value as i64))
+    } else if field_type == TypeId::of::<u32>() {
+        Some(Box::new(value as u32))
+    } else if field_type == TypeId::of::<u64>() {
+        Some(Box::new(value as u64))
+    } else if field_type == TypeId::of::<usize>() {
+        Some(Box::new(value as usize))
+    } else {
+        None
+    }
+}
+
+/// A node in a UI definition: a view type registered with the [`ViewTypeRegistry`], with its
+/// properties, bindings, event handlers and children.
+#[derive(Clone, Debug, Deserialize)]
+pub struct UiNodeDef {
+    /// The registered name of the view type.
+    pub view: String,
+    /// Constant property values.
+    #[serde(default)]
+    pub props: BTreeMap<String, UiValue>,
+    /// Properties which are bound to named values in the [`UiState`] resource.
+    #[serde(default)]
+    pub bind: BTreeMap<String, String>,
+    /// Event handlers, which are resolved by name against the [`CallbackRegistry`].
+    #[serde(default)]
+    pub on: BTreeMap<String, String>,
+    /// Child nodes.
+    #[serde(default)]
+    pub children: Vec<UiNodeDef>,
+}
+
+impl UiNodeDef {
+    /// Build the view description for this node, resolving bindings and event handlers.
+    fn describe(
+        &self,
+        views: &ViewTypeRegistry,
+        state: &UiState,
+        callbacks: &CallbackRegistry,
+    ) -> ViewDescription {
+        let mut description = ViewDescription::new(self.view.clone());
+        for (name, value) in self.props.iter() {
+            let field_type = views.field_type(&self.view, name);
+            description
+                .props
+                .insert_boxed(name.as_str(), value.to_reflect(field_type));
+        }
+        for (name, key) in self.bind.iter() {
+            match state.get(key) {
+                Some(value) => description
+                    .props
+                    .insert_boxed(name.as_str(), value.clone_value()),
+                None => warn!("Unknown UI state: {}", key),
+            }
+        }
+        for (event, key) in self.on.iter() {
+            match callbacks.get(key) {
+                Some(callback) => {
+                    description.handlers.insert(event.clone(), callback);
+                }
+                None => warn!("Unknown callback: {}", key),
+            }
+        }
+        description.children = self
+            .children
+            .iter()
+            .map(|child| child.describe(views, state, callbacks))
+            .collect();
+        description
+    }
+}
+
+/// An asset containing a declarative description of a view tree, loaded from a `.ui.ron`
+/// file. For example:
+///
+/// ```ron
+/// (
+///     view: "Panel",
+///     props: { "title": "Audio" },
+///     children: [
+///         (view: "Label", bind: { "text": "volume_label" }),
+///         (view: "Button", props: { "label": "Mute" }, on: { "click": "toggle_mute" }),
+///     ],
+/// )
+/// ```
+///
+/// View types are resolved against the [`ViewTypeRegistry`], bindings against the [`UiState`]
+/// resource and event handlers against the [`CallbackRegistry`]. Use [`UiDefinitionView`] to
+/// display the asset.
+#[derive(Asset, TypePath, Debug)]
+pub struct UiDefinition {
+    root: Arc<UiNodeDef>,
+}
+
+impl UiDefinition {
+    /// The root node of the definition.
+    pub fn root(&self) -> &UiNodeDef {
+        &self.root
+    }
+}
+
+/// Error loading a [`UiDefinition`].
+#[derive(Debug)]
+pub enum UiDefinitionError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for UiDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UiDefinitionError::Io(err) => write!(f, "Could not read UI definition: {}", err),
+            UiDefinitionError::Parse(err) => write!(f, "Could not parse UI definition: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for UiDefinitionError {}
+
+impl From<std::io::Error> for UiDefinitionError {
+    fn from(err: std::io::Error) -> Self {
+        UiDefinitionError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for UiDefinitionError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        UiDefinitionError::Parse(err)
+    }
+}
+
+/// Asset loader for `.ui.ron` files.
+#[derive(Default)]
+pub struct UiDefinitionLoader;
+
+impl AssetLoader for UiDefinitionLoader {
+    type Asset = UiDefinition;
+    type Settings = ();
+    type Error = UiDefinitionError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let root = ron::de::from_bytes::<UiNodeDef>(&bytes)?;
+        Ok(UiDefinition {
+            root: Arc::new(root),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ui.ron"]
+    }
+}
+
+/// Resource which holds named values that properties in a [`UiDefinition`] can be bound to.
+/// Views which display a definition are re-rendered when any value changes.
+#[derive(Resource, Default)]
+pub struct UiState {
+    values: HashMap<String, Box<dyn Reflect>>,
+}
+
+impl UiState {
+    /// Set a named value.
+    pub fn set<T: Reflect>(&mut self, name: impl Into<String>, value: T) {
+        self.values.insert(name.into(), Box::new(value));
+    }
+
+    /// Get a named value.
+    pub fn get(&self, name: &str) -> Option<&dyn Reflect> {
+        self.values.get(name).map(|value| value.as_ref())
+    }
+}
+
+/// Resource which maps names to callbacks, for use as event handlers in a [`UiDefinition`].
+#[derive(Resource, Default)]
+pub struct CallbackRegistry {
+    callbacks: HashMap<String, Callback>,
+}
+
+impl CallbackRegistry {
+    /// Register a one-shot system as a named callback.
+    pub fn register<M, S: IntoSystem<(), (), M> + 'static>(
+        world: &mut World,
+        name: impl Into<String>,
+        system: S,
+    ) -> Callback {
+        let callback = Callback {
+            id: world.register_system(system),
+        };
+        world
+            .resource_mut::<Self>()
+            .callbacks
+            .insert(name.into(), callback);
+        callback
+    }
+
+    /// Add an existing callback under the given name.
+    pub fn insert(&mut self, name: impl Into<String>, callback: Callback) {
+        self.callbacks.insert(name.into(), callback);
+    }
+
+    /// Look up a callback by name.
+    pub fn get(&self, name: &str) -> Option<Callback> {
+        self.callbacks.get(name).copied()
+    }
+}
+
+/// Component on the scope entity of a [`UiDefinitionView`] which holds the most recently
+/// loaded version of the definition.
+#[derive(Component)]
+struct UiDefinitionSource {
+    handle: Handle<UiDefinition>,
+    root: Option<Arc<UiNodeDef>>,
+}
+
+/// Displays the view tree described by a [`UiDefinition`] asset. Nothing is displayed until
+/// the asset has loaded. When the asset is modified, for example by hot reloading, the view
+/// tree is unmounted and mounted again from the new definition.
+#[derive(Clone, PartialEq)]
+pub struct UiDefinitionView(pub Handle<UiDefinition>);
+
+impl ViewTemplate for UiDefinitionView {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let owner = cx.owner();
+        cx.create_effect(
+            move |world, handle| {
+                let root = world
+                    .resource::<Assets<UiDefinition>>()
+                    .get(&handle)
+                    .map(|definition| definition.root.clone());
+                world
+                    .entity_mut(owner)
+                    .insert(UiDefinitionSource { handle, root });
+            },
+            self.0.clone(),
+        );
+        let root = cx
+            .use_component::<UiDefinitionSource>(owner)
+            .and_then(|source| source.root.clone());
+        // Comparing by identity means that a reloaded definition is mounted from scratch.
+        For::each_cmp(root, Arc::ptr_eq, |root| UiDefinitionInstance(root.clone()))
+    }
+}
+
+/// A single mounted version of a UI definition.
+#[derive(Clone)]
+struct UiDefinitionInstance(Arc<UiNodeDef>);
+
+impl PartialEq for UiDefinitionInstance {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl ViewTemplate for UiDefinitionInstance {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let views = cx.use_resource::<ViewTypeRegistry>();
+        let state = cx.use_resource::<UiState>();
+        let callbacks = cx.use_resource::<CallbackRegistry>();
+        let description = self.0.describe(views, state, callbacks);
+        views.instantiate(&description)
+    }
+}
+
+/// System which updates [`UiDefinitionView`]s when their asset is loaded or modified.
+fn update_ui_definitions(
+    mut events: EventReader<AssetEvent<UiDefinition>>,
+    assets: Res<Assets<UiDefinition>>,
+    mut query: Query<&mut UiDefinitionSource>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(definition) = assets.get(*id) else {
+            continue;
+        };
+        for mut source in query.iter_mut() {
+            if source.handle.id() == *id {
+                source.root = Some(definition.root.clone());
+            }
+        }
+    }
+}
+
+/// Plugin which adds support for loading view trees from `.ui.ron` files. Requires the
+/// `AssetPlugin`.
+pub struct UiDefinitionPlugin;
+
+impl Plugin for UiDefinitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<UiDefinition>()
+            .init_asset_loader::<UiDefinitionLoader>()
+            .init_resource::<UiState>()
+            .init_resource::<CallbackRegistry>()
+            .add_systems(
+                Update,
+                update_ui_definitions.before(reaction_control_system),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_definition() {
+        let root: UiNodeDef = ron::de::from_str(
+            r#"(
+                view: "Panel",
+                props: { "title": "Audio", "width": 200 },
+                children: [
+                    (view: "Label", bind: { "text": "volume" }),
+                    (view: "Button", on: { "click": "mute" }),
+                ],
+            )"#,
+        )
+        .unwrap();
+        assert_eq!(root.view, "Panel");
+        assert_eq!(root.props["title"], UiValue::String("Audio".to_string()));
+        assert_eq!(root.props["width"], UiValue::Int(200));
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].bind["text"], "volume");
+        assert_eq!(root.children[1].on["click"], "mute");
+    }
+
+    #[test]
+    fn test_coerce_values() {
+        let value = UiValue::Int(3).to_reflect(Some(TypeId::of::<f32>()));
+        assert_eq!(value.downcast_ref::<f32>(), Some(&3.0));
+        let value = UiValue::Float(2.5).to_reflect(None);
+        assert_eq!(value.downcast_ref::<f32>(), Some(&2.5));
+        let value = UiValue::Int(3).to_reflect(None);
+        assert_eq!(value.downcast_ref::<i64>(), Some(&3));
+    }
+}
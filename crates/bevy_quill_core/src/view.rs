@@ -1,20 +1,24 @@
 use crate::{
     cx::Cx,
-    tracking_scope::{TrackingScope, TrackingScopeTracing},
+    root_schedule::UpdateSchedule,
+    state_pool::alloc_state,
+    tracking_scope::{mark_dirty, ReactionEpoch, TrackingScope, TrackingScopeTracing},
 };
 use bevy::{
     // core::{DebugName, Name},
-    ecs::{system::SystemState, world::DeferredWorld},
-    hierarchy::{Children, HierarchyQueryExt, Parent},
+    core::Name,
+    ecs::{
+        change_detection::DetectChangesMut, component::Tick, system::SystemState,
+        world::DeferredWorld,
+    },
+    hierarchy::{Children, Parent},
     log::warn,
-    prelude::{Added, Component, Entity, Query, With, World},
-    utils::hashbrown::HashSet,
+    prelude::{Added, Component, Entity, Query, Resource, With, World},
+    time::Time,
+    utils::{hashbrown::HashSet, Instant},
 };
 use impl_trait_for_tuples::*;
-use std::{
-    any::Any,
-    sync::{Arc, Mutex},
-};
+use std::{any::Any, collections::VecDeque, time::Duration};
 
 #[cfg(feature = "verbose")]
 use bevy::log::info;
@@ -75,6 +79,101 @@ pub trait View: Sync + Send + 'static {
     fn view_type_id(&self) -> std::any::TypeId {
         std::any::TypeId::of::<Self>()
     }
+
+    /// Return a copy of this view which the parent view can keep alongside the state, and
+    /// compare with the next version of the view in order to skip rebuilding it when nothing
+    /// has changed. This should only return `Some` for views whose rebuild doesn't add any
+    /// dependencies to the current tracking scope, since those would be lost when the rebuild
+    /// is skipped.
+    fn memo(&self) -> Option<ViewMemo> {
+        None
+    }
+}
+
+/// A type-erased copy of a [`View`], returned by [`View::memo`].
+pub struct ViewMemo(Box<dyn MemoValue>);
+
+impl ViewMemo {
+    /// Create a memo from a copy of a view.
+    pub fn new<T: PartialEq + Clone + Send + Sync + 'static>(value: T) -> Self {
+        Self(Box::new(value))
+    }
+}
+
+// Memos are stored in the state of tuple views, which must be cloneable when the tuple is used
+// within a `For` loop.
+impl Clone for ViewMemo {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_memo())
+    }
+}
+
+impl PartialEq for ViewMemo {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_memo(other.0.as_any())
+    }
+}
+
+trait MemoValue: Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+    fn eq_memo(&self, other: &dyn Any) -> bool;
+    fn clone_memo(&self) -> Box<dyn MemoValue>;
+}
+
+impl<T: PartialEq + Clone + Send + Sync + 'static> MemoValue for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_memo(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<T>().is_some_and(|other| self == other)
+    }
+
+    fn clone_memo(&self) -> Box<dyn MemoValue> {
+        Box::new(self.clone())
+    }
+}
+
+/// Rebuild one member of a tuple view, unless it is equal to the version which was last built.
+fn rebuild_memoized<V: View>(
+    view: &V,
+    cx: &mut Cx,
+    state: &mut (V::State, Option<ViewMemo>),
+) -> bool {
+    let memo = view.memo();
+    if memo.is_some() && memo == state.1 {
+        return false;
+    }
+    state.1 = memo;
+    view.rebuild(cx, &mut state.0)
+}
+
+/// Output the nodes of one member of a tuple view.
+fn nodes_memoized<V: View>(
+    view: &V,
+    world: &World,
+    state: &(V::State, Option<ViewMemo>),
+    out: &mut Vec<Entity>,
+) {
+    view.nodes(world, &state.0, out)
+}
+
+/// Raze one member of a tuple view.
+fn raze_memoized<V: View>(
+    view: &V,
+    world: &mut DeferredWorld,
+    state: &mut (V::State, Option<ViewMemo>),
+) {
+    view.raze(world, &mut state.0)
+}
+
+/// Attach the children of one member of a tuple view.
+fn attach_children_memoized<V: View>(
+    view: &V,
+    world: &mut World,
+    state: &mut (V::State, Option<ViewMemo>),
+) -> bool {
+    view.attach_children(world, &mut state.0)
 }
 
 /// Marker on a [`View`] entity to indicate that it's output [`Vec<Entity>`] has changed, and that
@@ -114,12 +213,33 @@ impl<V: View> ViewState<V> {
     }
 }
 
+/// Component which holds a [`View`] and its state.
+///
+/// View state is only ever accessed from exclusive systems which have `&mut World`, so no
+/// locking is required. While the view is being rebuilt (or razed, or re-attached), the state is
+/// temporarily taken out of the component so that the view can be given mutable access to the
+/// world; during that time the cell is empty. Views never access their own cell re-entrantly,
+/// only the cells of other entities.
 #[derive(Component)]
-pub struct ViewStateCell<V: View>(pub Arc<Mutex<ViewState<V>>>);
+pub struct ViewStateCell<V: View>(pub(crate) Option<ViewState<V>>);
 
 impl<V: View> ViewStateCell<V> {
     pub fn new(view: V) -> Self {
-        Self(Arc::new(Mutex::new(ViewState { view, state: None })))
+        Self(Some(ViewState { view, state: None }))
+    }
+
+    /// Remove the view state from the cell on the given entity.
+    fn take(world: &mut World, entity: Entity) -> Option<ViewState<V>> {
+        world
+            .get_mut::<ViewStateCell<V>>(entity)
+            .and_then(|mut cell| cell.bypass_change_detection().0.take())
+    }
+
+    /// Return the view state to the cell on the given entity, if it still exists.
+    fn restore(world: &mut World, entity: Entity, vstate: ViewState<V>) {
+        if let Some(mut cell) = world.get_mut::<ViewStateCell<V>>(entity) {
+            cell.bypass_change_detection().0 = Some(vstate);
+        }
     }
 
     pub fn create_thunk(&self) -> ViewThunk {
@@ -175,45 +295,48 @@ pub trait AnyViewAdapter: Sync + Send + 'static {
 
 impl<V: View> AnyViewAdapter for ViewAdapter<V> {
     fn nodes(&self, world: &mut World, entity: Entity, out: &mut Vec<Entity>) {
-        if let Some(view_cell) = world.entity(entity).get::<ViewStateCell<V>>() {
-            let vstate = view_cell.0.lock().unwrap();
-            match &vstate.state {
-                Some(state) => vstate.view.nodes(world, state, out),
-                None => {}
-            }
+        let world: &World = world;
+        if let Some(ViewState {
+            view,
+            state: Some(state),
+        }) = world
+            .get::<ViewStateCell<V>>(entity)
+            .and_then(|cell| cell.0.as_ref())
+        {
+            view.nodes(world, state, out);
         }
     }
 
     fn rebuild(&self, world: &mut World, entity: Entity, scope: &mut TrackingScope) -> bool {
+        let Some(mut vstate) = ViewStateCell::<V>::take(world, entity) else {
+            return false;
+        };
         let mut cx = Cx::new(world, entity, scope);
-        if let Some(view_cell) = cx
-            .world_mut()
-            .entity_mut(entity)
-            .get_mut::<ViewStateCell<V>>()
-        {
-            let inner = view_cell.0.clone();
-            let mut vstate = inner.lock().unwrap();
-            vstate.rebuild(&mut cx)
-        } else {
-            false
-        }
+        let changed = vstate.rebuild(&mut cx);
+        ViewStateCell::restore(world, entity, vstate);
+        changed
     }
 
     fn raze(&self, world: &mut DeferredWorld, entity: Entity) {
-        if let Some(vsh) = world.entity(entity).get::<ViewStateCell<V>>() {
-            let inner = vsh.0.clone();
-            inner.lock().unwrap().raze(world);
+        let Some(mut vstate) = world
+            .get_mut::<ViewStateCell<V>>(entity)
+            .and_then(|mut cell| cell.bypass_change_detection().0.take())
+        else {
+            return;
+        };
+        vstate.raze(world);
+        if let Some(mut cell) = world.get_mut::<ViewStateCell<V>>(entity) {
+            cell.bypass_change_detection().0 = Some(vstate);
         }
     }
 
     fn attach_children(&self, world: &mut World, entity: Entity) -> bool {
-        if let Some(view_cell) = world.entity(entity).get::<ViewStateCell<V>>() {
-            let vs = view_cell.0.clone();
-            let mut inner = vs.lock().unwrap();
-            inner.attach_children(world)
-        } else {
-            false
-        }
+        let Some(mut vstate) = ViewStateCell::<V>::take(world, entity) else {
+            return false;
+        };
+        let changed = vstate.attach_children(world);
+        ViewStateCell::restore(world, entity, vstate);
+        changed
     }
 }
 
@@ -257,6 +380,9 @@ impl View for () {
     }
 
     fn raze(&self, _world: &mut DeferredWorld, _state: &mut Self::State) {}
+    fn memo(&self) -> Option<ViewMemo> {
+        Some(ViewMemo::new(()))
+    }
 }
 
 impl<V: View> View for (V,) {
@@ -277,37 +403,49 @@ impl<V: View> View for (V,) {
     fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
         self.0.raze(world, state)
     }
+    fn memo(&self) -> Option<ViewMemo> {
+        self.0.memo()
+    }
 }
 
 #[impl_for_tuples(2, 32)]
 #[tuple_types_custom_trait_bound(View)]
 impl View for Tuple {
-    for_tuples!( type State = ( #( Tuple::State ),* ); );
+    // Each member's state is stored along with its memo, so that members which haven't
+    // changed can skip their rebuild.
+    for_tuples!( type State = ( #( (Tuple::State, Option<ViewMemo>) ),* ); );
 
     #[rustfmt::skip]
     fn nodes(&self, world: &World, state: &Self::State, out: &mut Vec<Entity>) {
-        for_tuples!(#( self.Tuple.nodes(world, &state.Tuple, out); )*);
+        for_tuples!(#( nodes_memoized(&self.Tuple, world, &state.Tuple, out); )*);
     }
 
     fn build(&self, cx: &mut Cx) -> Self::State {
-        for_tuples!((#( self.Tuple.build(cx) ),*))
+        for_tuples!((#( (self.Tuple.build(cx), self.Tuple.memo()) ),*))
     }
 
     fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
         let mut changed = false;
-        for_tuples!(#( changed |= self.Tuple.rebuild(cx, &mut state.Tuple); )*);
+        for_tuples!(#( changed |= rebuild_memoized(&self.Tuple, cx, &mut state.Tuple); )*);
         changed
     }
 
     fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
-        for_tuples!(#( self.Tuple.raze(world, &mut state.Tuple); )*)
+        for_tuples!(#( raze_memoized(&self.Tuple, world, &mut state.Tuple); )*)
     }
 
     fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
         let mut changed = false;
-        for_tuples!(#( changed |= self.Tuple.attach_children(world, &mut state.Tuple); )*);
+        for_tuples!(#( changed |= attach_children_memoized(&self.Tuple, world, &mut state.Tuple); )*);
         changed
     }
+    fn memo(&self) -> Option<ViewMemo> {
+        // A tuple can only be skipped if all of its members can.
+        let memos: Option<Vec<ViewMemo>> = [for_tuples!(#( self.Tuple.memo() ),*)]
+            .into_iter()
+            .collect();
+        Some(ViewMemo::new(memos?))
+    }
 }
 
 /// An optional [`View`], renders nothing if the view is `None`. Note that this is not dynamic,
@@ -368,6 +506,7 @@ pub trait AnyView: Sync + Send + 'static {
     fn attach_children(&self, world: &mut World, state: &mut BoxedState) -> bool;
     fn raze(&self, world: &mut DeferredWorld, state: &mut BoxedState);
     fn view_type_id(&self) -> std::any::TypeId;
+    fn as_any(&self) -> &dyn Any;
 }
 
 impl<V: View> AnyView for V {
@@ -378,7 +517,8 @@ impl<V: View> AnyView for V {
     }
 
     fn build(&self, cx: &mut Cx) -> BoxedState {
-        Box::new(View::build(self, cx))
+        let state = View::build(self, cx);
+        alloc_state(cx.world_mut(), state)
     }
 
     fn rebuild(&self, cx: &mut Cx, state: &mut BoxedState) -> bool {
@@ -409,6 +549,10 @@ impl<V: View> AnyView for V {
     fn view_type_id(&self) -> std::any::TypeId {
         View::view_type_id(self)
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 pub(crate) fn build_views(world: &mut World) {
@@ -425,18 +569,162 @@ pub(crate) fn build_views(world: &mut World) {
     }
 }
 
-const MAX_DIVERGENCE_CT: usize = 32;
+/// What the reaction control system should do when reactions fail to converge within
+/// [`ReactionLimits::max_iterations`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CyclePolicy {
+    /// Panic, naming the scopes which are still changing.
+    #[default]
+    Panic,
+    /// Log a warning naming the scopes which are still changing, and discard their pending
+    /// reactions, breaking the cycle.
+    Warn,
+    /// Log a warning naming the scopes which are still changing, and postpone their pending
+    /// reactions to the next frame.
+    Defer,
+}
+
+/// Resource which limits the number of reaction iterations per frame. A view whose rebuild
+/// mutates state that its own scope (or an ancestor's) depends on will invalidate itself
+/// forever; the limit turns this into a diagnosable error rather than a hang.
+#[derive(Resource, Clone, Debug)]
+pub struct ReactionLimits {
+    /// Maximum number of reaction iterations per frame.
+    pub max_iterations: usize,
+    /// How to handle reactions which are still pending after `max_iterations`.
+    pub policy: CyclePolicy,
+    /// If set, reactions in the [`ReactionPriority::Low`] lane are postponed to the next frame
+    /// once the reaction control system has run for longer than this.
+    pub low_priority_budget: Option<Duration>,
+    /// If set, a warning naming the template is logged when a single view takes longer than
+    /// this to rebuild. The time includes building any new child views.
+    pub slow_rebuild_threshold: Option<Duration>,
+    /// If true, a rebuild which exceeds `slow_rebuild_threshold` panics instead of logging a
+    /// warning. This only applies to debug builds.
+    pub panic_on_slow_rebuild: bool,
+    /// If true, each [`ViewTemplate::create`](crate::ViewTemplate::create) is called twice,
+    /// and a warning is logged if the two views differ. This catches templates which aren't
+    /// deterministic, such as ones which use random numbers or iterate over a `HashMap`, and
+    /// which defeat memoization. Only views which can be compared (see [`View::memo`]) are
+    /// checked; a warning is logged once for each template whose view can't be. This only
+    /// applies to debug builds.
+    pub strict_templates: bool,
+}
+
+impl Default for ReactionLimits {
+    fn default() -> Self {
+        Self {
+            max_iterations: 32,
+            policy: CyclePolicy::Panic,
+            low_priority_budget: None,
+            slow_rebuild_threshold: None,
+            panic_on_slow_rebuild: false,
+            strict_templates: false,
+        }
+    }
+}
+
+/// Component which assigns view scopes to a priority lane. Within each reaction iteration,
+/// scopes in higher lanes are rebuilt first, so that interactive feedback such as a text caret
+/// or a drag preview is not held up by expensive, less important views such as a minimap.
+/// Low priority work can also be postponed when the frame is over budget, see
+/// [`ReactionLimits::low_priority_budget`].
+///
+/// The priority is inherited by all of the scopes below the entity it is inserted on, so it
+/// can be placed on a view root, or on a template's scope entity with [`Cx::insert`]:
+///
+/// ```ignore
+/// cx.insert(ReactionPriority::Low);
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum ReactionPriority {
+    /// Background views which can lag behind.
+    Low,
+    /// The default lane.
+    #[default]
+    Normal,
+    /// Views which provide immediate feedback to user input.
+    High,
+}
+
+/// Format a list of scope entities for diagnostic messages, using their names if present.
+fn describe_scopes(world: &World, scopes: &[Entity]) -> String {
+    scopes
+        .iter()
+        .map(|entity| match world.get::<Name>(*entity) {
+            Some(name) => format!("{} ({})", name, entity),
+            None => entity.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
 /// Reaction control system (RCS)
 pub(crate) fn reaction_control_system(world: &mut World) {
     // Record the changed entities for debugging purposes.
     let is_tracing = world.get_resource_mut::<TrackingScopeTracing>().is_some();
     let mut all_reactions: Vec<Entity> = Vec::new();
+
+    // Roots which are throttled by an `UpdateSchedule` are skipped unless they are due.
+    let now = world
+        .get_resource::<Time>()
+        .map_or(Duration::ZERO, |time| time.elapsed());
+    let mut skipped: HashSet<Entity> = HashSet::new();
+    let mut due: Vec<Entity> = Vec::new();
+    let mut schedules = world.query_filtered::<(Entity, &UpdateSchedule), With<ViewRoot>>();
+    for (root, schedule) in schedules.iter(world) {
+        if !schedule.is_throttled() {
+            continue;
+        }
+        if schedule.is_due(now) {
+            due.push(root);
+        } else {
+            skipped.insert(root);
+        }
+    }
+
+    // Skip idle frames: if nothing that any scope depends on has changed, there is no need
+    // to visit the scopes individually. Changes to throttled roots are held back until they
+    // are due, so a due root always forces a scan.
+    let tick = world.change_tick();
+    if due.is_empty()
+        && world
+            .get_resource::<ReactionEpoch>()
+            .is_some_and(|epoch| epoch.is_clean(world, tick))
+    {
+        if let Some(mut tracing) = world.get_resource_mut::<TrackingScopeTracing>() {
+            tracing.0.clear();
+        }
+        return;
+    }
+
+    // Invalidated roots re-run their top-level templates.
+    for root in due.iter() {
+        if !world
+            .get::<UpdateSchedule>(*root)
+            .is_some_and(|schedule| schedule.is_invalidated())
+        {
+            continue;
+        }
+        if let Some(children) = world.get::<Children>(*root) {
+            for child in children.iter() {
+                if let Some(scope) = world.get::<TrackingScope>(*child) {
+                    scope.set_changed(world);
+                }
+            }
+        }
+    }
+
+    let limits = world
+        .get_resource::<ReactionLimits>()
+        .cloned()
+        .unwrap_or_default();
+    let start = Instant::now();
     let mut iteration_ct: usize = 0;
-    let mut divergence_ct: usize = 0;
-    let mut prev_change_ct: usize = 0;
+    let mut deferred = false;
+    let mut low_deferred = false;
 
-    loop {
+    let last_run = loop {
         let this_run = if iteration_ct > 0 {
             world.increment_change_tick()
         } else {
@@ -444,76 +732,118 @@ pub(crate) fn reaction_control_system(world: &mut World) {
         };
 
         // Scan changed resources. Need to do this in top-down order, so that parents update
-        // before children.
+        // before children. Each scope inherits the priority lane of its nearest ancestor.
         let mut st: SystemState<(
             Query<Entity, With<ViewRoot>>,
             Query<&Children>,
             Query<(Entity, &TrackingScope)>,
+            Query<&ReactionPriority>,
         )> = SystemState::new(world);
-        let (roots, children, scopes) = st.get(world);
+        let (roots, children, scopes, priorities) = st.get(world);
         let roots = roots.iter().collect::<Vec<_>>();
-        let mut changed: Vec<Entity> = Vec::with_capacity(64);
+        let mut changed_high: Vec<Entity> = Vec::new();
+        let mut changed_normal: Vec<Entity> = Vec::with_capacity(64);
+        let mut changed_low: Vec<Entity> = Vec::new();
+        let mut queue: VecDeque<(Entity, ReactionPriority)> = VecDeque::new();
         for root in roots {
-            children.iter_descendants(root).for_each(|child| {
+            if skipped.contains(&root) {
+                continue;
+            }
+            let priority = priorities.get(root).copied().unwrap_or_default();
+            if let Ok(root_children) = children.get(root) {
+                queue.extend(root_children.iter().map(|child| (*child, priority)));
+            }
+            while let Some((child, inherited)) = queue.pop_front() {
+                let priority = priorities.get(child).copied().unwrap_or(inherited);
                 if let Ok(scope) = scopes.get(child) {
                     if scope.1.dependencies_changed(world, this_run) {
-                        changed.push(child);
+                        match priority {
+                            ReactionPriority::Low if low_deferred => deferred = true,
+                            ReactionPriority::Low => changed_low.push(child),
+                            ReactionPriority::Normal => changed_normal.push(child),
+                            ReactionPriority::High => changed_high.push(child),
+                        }
                     }
                 }
-            });
+                if let Ok(grandchildren) = children.get(child) {
+                    queue.extend(grandchildren.iter().map(|gc| (*gc, priority)));
+                }
+            }
         }
+        let mut changed = changed_high;
+        changed.append(&mut changed_normal);
 
         // Quit if there are no changes.
-        if changed.is_empty() {
-            break;
+        if changed.is_empty() && changed_low.is_empty() {
+            break this_run;
         }
 
-        if is_tracing {
-            all_reactions.extend(changed.clone());
+        // Cycle detection: reactions which are still happening after this many iterations
+        // will most likely never converge.
+        if iteration_ct >= limits.max_iterations {
+            changed.append(&mut changed_low);
+            let message = format!(
+                "Reactions failed to converge after {} iterations, still changing: {}",
+                iteration_ct,
+                describe_scopes(world, &changed)
+            );
+            match limits.policy {
+                CyclePolicy::Panic => panic!("{}", message),
+                CyclePolicy::Warn => {
+                    warn!("{}", message);
+                    let mut scopes = world.query::<&mut TrackingScope>();
+                    for scope_entity in changed.iter() {
+                        if let Ok(mut scope) = scopes.get_mut(world, *scope_entity) {
+                            scope.clear_changed();
+                            scope.tick = this_run;
+                        }
+                    }
+                }
+                CyclePolicy::Defer => {
+                    warn!("{}", message);
+                    deferred = true;
+                }
+            }
+            break this_run;
         }
 
         // println!("Reaction iteration: {}", iteration_ct);
 
-        // Do all cleanups first.
-        run_cleanups(world, &changed);
-
-        // Now rebuild all changed views and record depdendencies.
-        let mut scopes = world.query::<(Entity, &mut TrackingScope, &ViewThunk)>();
-        for scope_entity in changed.iter() {
-            // if let Some(name) = world.get::<Name>(*scope_entity) {
-            //     println!("Updating {}", name);
-            // } else {
-            //     println!("Updating {}", *scope_entity);
-            // }
-            // Run the reaction. Continue if this scope got deleted as a side effect of updating
-            // another scope.
-            let Ok((_, mut scope, view_cell)) = scopes.get_mut(world, *scope_entity) else {
-                continue;
-            };
-            let mut next_scope = TrackingScope::new(this_run);
-            next_scope.take_hooks(scope.as_mut());
-            let output_changed = view_cell.0.rebuild(world, *scope_entity, &mut next_scope);
-            if output_changed {
-                #[cfg(feature = "verbose")]
-                info!("View output changed: {}", *scope_entity);
-                world.entity_mut(*scope_entity).insert(OutputChanged);
+        // Rebuild the high and normal lanes, then the low lane if there is time left.
+        run_reactions(world, &changed, this_run, &limits);
+        if !changed_low.is_empty() {
+            if limits
+                .low_priority_budget
+                .is_some_and(|budget| start.elapsed() > budget)
+            {
+                low_deferred = true;
+                deferred = true;
+                changed_low.clear();
+            } else {
+                run_reactions(world, &changed_low, this_run, &limits);
             }
+        }
 
-            // Replace deps and cleanups in the current scope with the next scope.
-            let (_, mut scope, _) = scopes.get_mut(world, *scope_entity).unwrap();
-            scope.take_deps(&mut next_scope);
-            scope.tick = this_run;
+        if is_tracing {
+            all_reactions.extend(changed);
+            all_reactions.extend(changed_low);
         }
 
         iteration_ct += 1;
-        let change_ct = changed.len();
-        if change_ct >= prev_change_ct {
-            divergence_ct += 1;
-            if divergence_ct > MAX_DIVERGENCE_CT {
-                panic!("Reactions failed to converge, num changes: {}", change_ct);
-            }
+    };
+
+    // All scopes are now up to date, so record their combined dependencies for the next frame.
+    let mut scopes = world.query::<&TrackingScope>();
+    let epoch = ReactionEpoch::capture(world, last_run, scopes.iter(world));
+    world.insert_resource(epoch);
+    if deferred {
+        // Make sure that the postponed reactions are picked up next frame.
+        mark_dirty(world);
+    }
+    for root in due {
+        if let Some(mut schedule) = world.get_mut::<UpdateSchedule>(root) {
+            schedule.mark_updated(now);
         }
-        prev_change_ct = change_ct;
     }
 
     // Record the changed entities for diagnostic purposes.
@@ -522,6 +852,61 @@ pub(crate) fn reaction_control_system(world: &mut World) {
     }
 }
 
+/// Rebuild the given scopes, and record their new dependencies.
+pub(crate) fn run_reactions(
+    world: &mut World,
+    changed: &[Entity],
+    this_run: Tick,
+    limits: &ReactionLimits,
+) {
+    // Do all cleanups first.
+    run_cleanups(world, changed);
+
+    // Now rebuild all changed views and record depdendencies.
+    let mut scopes = world.query::<(Entity, &mut TrackingScope, &ViewThunk)>();
+    for scope_entity in changed.iter() {
+        // if let Some(name) = world.get::<Name>(*scope_entity) {
+        //     println!("Updating {}", name);
+        // } else {
+        //     println!("Updating {}", *scope_entity);
+        // }
+        // Run the reaction. Continue if this scope got deleted as a side effect of updating
+        // another scope.
+        let Ok((_, mut scope, view_cell)) = scopes.get_mut(world, *scope_entity) else {
+            continue;
+        };
+        let mut next_scope = TrackingScope::new(this_run);
+        next_scope.take_hooks(scope.as_mut());
+        let rebuild_start = Instant::now();
+        let output_changed = view_cell.0.rebuild(world, *scope_entity, &mut next_scope);
+        if let Some(threshold) = limits.slow_rebuild_threshold {
+            let elapsed = rebuild_start.elapsed();
+            if elapsed > threshold {
+                let message = format!(
+                    "Rebuilding {} took {:?}, which exceeds the threshold of {:?}",
+                    describe_scopes(world, &[*scope_entity]),
+                    elapsed,
+                    threshold
+                );
+                if cfg!(debug_assertions) && limits.panic_on_slow_rebuild {
+                    panic!("{}", message);
+                }
+                warn!("{}", message);
+            }
+        }
+        if output_changed {
+            #[cfg(feature = "verbose")]
+            info!("View output changed: {}", *scope_entity);
+            world.entity_mut(*scope_entity).insert(OutputChanged);
+        }
+
+        // Replace deps and cleanups in the current scope with the next scope.
+        let (_, mut scope, _) = scopes.get_mut(world, *scope_entity).unwrap();
+        scope.take_deps(&mut next_scope);
+        scope.tick = this_run;
+    }
+}
+
 // Call registered cleanup functions
 fn run_cleanups(world: &mut World, changed: &[Entity]) {
     let mut deferred = DeferredWorld::from(world);
@@ -7,12 +7,33 @@ use bevy::prelude::Entity;
 
 type BoxedState = Box<dyn Any + Send + Sync>;
 
+/// Function which compares two type-erased views of the same concrete type.
+type ViewEqFn = fn(&dyn Any, &dyn Any) -> bool;
+
 /// A wrapper around a type-erased view. This is useful when passing views as parameters.
-pub struct ViewChild(pub(crate) Arc<dyn AnyView>);
+///
+/// By default, two `ViewChild` values are only equal if they are clones of each other, which
+/// means that a template which takes a `ViewChild` parameter will be rebuilt whenever its parent
+/// is. Children created with [`ViewChild::memo`] are compared by value instead, so that the
+/// parent template can be skipped when the child is unchanged.
+pub struct ViewChild(pub(crate) Arc<dyn AnyView>, Option<ViewEqFn>);
 
 impl ViewChild {
     pub fn new<V: View + 'static>(views: V) -> Self {
-        Self(Arc::new(views))
+        Self(Arc::new(views), None)
+    }
+
+    /// Construct a `ViewChild` which compares equal to other `ViewChild`s containing an
+    /// equal view of the same type.
+    pub fn memo<V: View + PartialEq + 'static>(view: V) -> Self {
+        Self(Arc::new(view), Some(view_eq::<V>))
+    }
+}
+
+fn view_eq<V: PartialEq + 'static>(a: &dyn Any, b: &dyn Any) -> bool {
+    match (a.downcast_ref::<V>(), b.downcast_ref::<V>()) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
     }
 }
 
@@ -46,19 +67,25 @@ impl View for ViewChild {
 
 impl Clone for ViewChild {
     fn clone(&self) -> Self {
-        Self(self.0.clone())
+        Self(self.0.clone(), self.1)
     }
 }
 
 impl Default for ViewChild {
     fn default() -> Self {
-        Self(Arc::new(()))
+        Self::memo(())
     }
 }
 
 impl PartialEq for ViewChild {
     fn eq(&self, other: &Self) -> bool {
-        Arc::ptr_eq(&self.0, &other.0)
+        if Arc::ptr_eq(&self.0, &other.0) {
+            return true;
+        }
+        match (self.1, other.1) {
+            (Some(eq), Some(_)) => eq(self.0.as_any(), other.0.as_any()),
+            _ => false,
+        }
     }
 }
 
@@ -72,6 +99,17 @@ impl<V: View> IntoViewChild for V {
     }
 }
 
+/// Conversion into a [`ViewChild`] which is compared by value, see [`ViewChild::memo`].
+pub trait IntoMemoViewChild {
+    fn into_memo_view_child(self) -> ViewChild;
+}
+
+impl<V: View + PartialEq> IntoMemoViewChild for V {
+    fn into_memo_view_child(self) -> ViewChild {
+        ViewChild::memo(self)
+    }
+}
+
 // TODO: Figure out how to specialize for ChildViews so that we don't double-wrap when
 // calling into_child_views on a ChildViews instance.
 // impl IntoChildViews for ChildViews {
@@ -0,0 +1,132 @@
+use bevy::{
+    ecs::world::DeferredWorld,
+    hierarchy::{BuildChildren, BuildWorldChildren},
+    prelude::{Entity, World},
+};
+
+use crate::{Cx, TrackingScope, View, ViewStateCell, ViewThunk};
+
+/// A handle to a view which has been built outside of any view hierarchy, for example by a
+/// plugin which wants to contribute a UI fragment to a host-defined slot. The handle can
+/// later be mounted into a view hierarchy with [`MountHandle`].
+///
+/// While a handle is not mounted, its view does not react to changes. Mounting and unmounting
+/// does not destroy the view; call [`ViewHandle::despawn`] when it is no longer needed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ViewHandle(Entity);
+
+impl ViewHandle {
+    /// Spawn a new entity holding the view, and build it.
+    pub fn spawn<V: View>(world: &mut World, view: V) -> Self {
+        let cell = ViewStateCell::new(view);
+        let thunk = cell.create_thunk();
+        let entity = world.spawn(cell).id();
+        let tick = world.change_tick();
+        let mut scope = TrackingScope::new(tick);
+        thunk.rebuild(world, entity, &mut scope);
+        world.entity_mut(entity).insert((thunk, scope));
+        Self(entity)
+    }
+
+    /// The entity which holds the view and its state.
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+
+    /// Returns the display nodes produced by the view.
+    pub fn nodes(&self, world: &mut World) -> Vec<Entity> {
+        let mut nodes: Vec<Entity> = Vec::new();
+        if let Some(thunk) = world.get::<ViewThunk>(self.0).map(|t| t.0) {
+            thunk.nodes(world, self.0, &mut nodes);
+        }
+        nodes
+    }
+
+    /// Raze the view and despawn the handle entity.
+    pub fn despawn(self, world: &mut World) {
+        if let Some(thunk) = world.get::<ViewThunk>(self.0).map(|t| t.0) {
+            thunk.raze(&mut DeferredWorld::from(&mut *world), self.0);
+        }
+        world.flush_commands();
+        if let Some(entt) = world.get_entity_mut(self.0) {
+            entt.despawn();
+        }
+    }
+}
+
+/// A view which mounts a [`ViewHandle`] as a child, displaying the handle's nodes in place
+/// of itself. While mounted, the handle's view reacts to changes like any other part of the
+/// hierarchy. When the `MountHandle` is razed, the handle is detached but not despawned, so
+/// it can be mounted again elsewhere.
+#[derive(Clone, PartialEq)]
+pub struct MountHandle(pub ViewHandle);
+
+impl MountHandle {
+    /// Construct a new `MountHandle` for the given handle.
+    pub fn new(handle: ViewHandle) -> Self {
+        Self(handle)
+    }
+
+    fn mount(&self, world: &mut World, owner: Entity) -> Vec<Entity> {
+        let handle = self.0.entity();
+        if world.get_entity(handle).is_none() {
+            return Vec::new();
+        }
+        world.entity_mut(handle).set_parent(owner);
+        // The handle's view may have missed changes while unmounted.
+        if let Some(scope) = world.get::<TrackingScope>(handle) {
+            scope.set_changed(world);
+        }
+        self.0.nodes(world)
+    }
+}
+
+impl View for MountHandle {
+    /// The mounted handle, and its display nodes as of the last attach.
+    type State = (ViewHandle, Vec<Entity>);
+
+    fn nodes(&self, _world: &World, state: &Self::State, out: &mut Vec<Entity>) {
+        out.extend(state.1.iter().copied());
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let owner = cx.owner();
+        (self.0, self.mount(cx.world_mut(), owner))
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        if state.0 == self.0 {
+            return false;
+        }
+        self.raze(&mut DeferredWorld::from(cx.world_mut()), state);
+        *state = self.build(cx);
+        true
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        let nodes = if world.get_entity(state.0.entity()).is_some() {
+            state.0.nodes(world)
+        } else {
+            Vec::new()
+        };
+        if nodes != state.1 {
+            state.1 = nodes;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        let handle = state.0.entity();
+        if world.get_entity(handle).is_none() {
+            return;
+        }
+        for node in state.1.drain(..) {
+            if world.get_entity(node).is_some() {
+                world.commands().entity(node).remove_parent();
+            }
+        }
+        world.commands().entity(handle).remove_parent();
+    }
+}
@@ -0,0 +1,362 @@
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
+
+use bevy::{
+    log::warn,
+    prelude::Resource,
+    reflect::{DynamicStruct, Reflect, Struct, TypeInfo, TypePath, Typed},
+    utils::HashMap,
+};
+
+use crate::{Callback, Cx, IntoViewChild, View, ViewChild, ViewTemplate};
+
+/// A description of a view tree which is constructed at runtime: the registered name of a view
+/// type, the values of its reflected fields, and its children.
+#[derive(Debug)]
+pub struct ViewDescription {
+    /// The name under which the view type was registered with the [`ViewTypeRegistry`].
+    pub type_name: String,
+    /// Values for the fields of the view. Fields which are not present keep their default value.
+    pub props: DynamicStruct,
+    /// Callbacks for the events of the view, by event name.
+    pub handlers: HashMap<String, Callback>,
+    /// Child view descriptions.
+    pub children: Vec<ViewDescription>,
+}
+
+impl ViewDescription {
+    /// Construct a description of a view with the given type name and default properties.
+    pub fn new(type_name: impl Into<String>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            props: DynamicStruct::default(),
+            handlers: HashMap::default(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Set the value of a field.
+    pub fn with_prop<T: Reflect>(mut self, name: &str, value: T) -> Self {
+        self.props.insert(name, value);
+        self
+    }
+
+    /// Set the callback for an event. The event name must have been registered for the view
+    /// type with [`ViewTypeRegistry::register_handler`].
+    pub fn with_handler(mut self, event: impl Into<String>, callback: Callback) -> Self {
+        self.handlers.insert(event.into(), callback);
+        self
+    }
+
+    /// Add a child view description.
+    pub fn with_child(mut self, child: ViewDescription) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+impl Clone for ViewDescription {
+    fn clone(&self) -> Self {
+        Self {
+            type_name: self.type_name.clone(),
+            props: self.props.clone_dynamic(),
+            handlers: self.handlers.clone(),
+            children: self.children.clone(),
+        }
+    }
+}
+
+type ViewFactory =
+    Arc<dyn Fn(&ViewTypeRegistry, &ViewDescription, ViewChild) -> ViewChild + Send + Sync>;
+
+struct ViewTypeEntry {
+    factory: ViewFactory,
+    type_info: Option<&'static TypeInfo>,
+}
+
+/// Resource which maps type names to view templates, so that views can be instantiated from
+/// data, such as a layout loaded from a file. View templates which derive [`Reflect`] and
+/// [`Default`] can be registered directly:
+///
+/// ```ignore
+/// #[derive(Clone, PartialEq, Default, Reflect)]
+/// struct Heading {
+///     text: String,
+/// }
+///
+/// world
+///     .resource_mut::<ViewTypeRegistry>()
+///     .register::<Heading>()
+///     .register_with_children::<Panel>(|panel, children| panel.children(children));
+/// ```
+///
+/// Instances are created by starting with the default value of the template, and then applying
+/// the properties in the [`ViewDescription`]. Views that can't be constructed this way can be
+/// registered with a custom factory function using [`ViewTypeRegistry::register_factory`].
+///
+/// Since callbacks can't be reflected, event handlers are set with a setter function registered
+/// for each event:
+///
+/// ```ignore
+/// registry.register_handler::<Button>("click", |button, callback| button.on_click(callback));
+/// ```
+#[derive(Resource, Default)]
+pub struct ViewTypeRegistry {
+    types: HashMap<String, ViewTypeEntry>,
+    handlers: HashMap<(String, String), Arc<dyn Any + Send + Sync>>,
+}
+
+impl ViewTypeRegistry {
+    /// Register a view template under its short type name, for example `"Heading"`. Any
+    /// children in the description are ignored.
+    pub fn register<T>(&mut self) -> &mut Self
+    where
+        T: ViewTemplate + Clone + PartialEq + Default + Reflect + TypePath + Typed,
+    {
+        self.register_entry::<T>(Arc::new(|registry, description, _| {
+            registry.construct::<T>(description).into_view_child()
+        }))
+    }
+
+    /// Register a view template which accepts children. The `set_children` function is called
+    /// with the constructed template and the instantiated children.
+    pub fn register_with_children<T>(&mut self, set_children: fn(T, ViewChild) -> T) -> &mut Self
+    where
+        T: ViewTemplate + Clone + PartialEq + Default + Reflect + TypePath + Typed,
+    {
+        self.register_entry::<T>(Arc::new(move |registry, description, children| {
+            set_children(registry.construct::<T>(description), children).into_view_child()
+        }))
+    }
+
+    fn register_entry<T: TypePath + Typed>(&mut self, factory: ViewFactory) -> &mut Self {
+        self.types.insert(
+            T::short_type_path().to_string(),
+            ViewTypeEntry {
+                factory,
+                type_info: Some(T::type_info()),
+            },
+        );
+        self
+    }
+
+    /// Register a setter which attaches the callback for the named event to a view template.
+    pub fn register_handler<T>(
+        &mut self,
+        event: impl Into<String>,
+        set_handler: fn(T, Callback) -> T,
+    ) -> &mut Self
+    where
+        T: ViewTemplate + TypePath,
+    {
+        self.handlers.insert(
+            (T::short_type_path().to_string(), event.into()),
+            Arc::new(set_handler),
+        );
+        self
+    }
+
+    /// Register a factory function which constructs a view from a set of properties and
+    /// children, under an explicit name.
+    pub fn register_factory<F>(&mut self, name: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn(&DynamicStruct, ViewChild) -> ViewChild + Send + Sync + 'static,
+    {
+        self.types.insert(
+            name.into(),
+            ViewTypeEntry {
+                factory: Arc::new(move |_, description, children| {
+                    factory(&description.props, children)
+                }),
+                type_info: None,
+            },
+        );
+        self
+    }
+
+    /// True if a view type with the given name has been registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.types.contains_key(name)
+    }
+
+    /// Returns the type of the named field of a registered view type, if known. Types
+    /// registered with a custom factory have no field information.
+    pub fn field_type(&self, name: &str, field: &str) -> Option<TypeId> {
+        match self.types.get(name)?.type_info? {
+            TypeInfo::Struct(info) => info.field(field).map(|field| field.type_id()),
+            _ => None,
+        }
+    }
+
+    /// Construct the view tree for a description. Views whose type is not registered are
+    /// replaced by an empty view, and a warning is logged.
+    pub fn instantiate(&self, description: &ViewDescription) -> ViewChild {
+        let Some(entry) = self.types.get(&description.type_name) else {
+            warn!("Unknown view type: {}", description.type_name);
+            return ViewChild::default();
+        };
+        let children: Vec<ViewChild> = description
+            .children
+            .iter()
+            .map(|child| self.instantiate(child))
+            .collect();
+        (entry.factory)(self, description, children.into_view_child())
+    }
+
+    /// Construct a view template from its default value, overridden by the properties and
+    /// handlers in the description.
+    fn construct<T: Default + Reflect + TypePath>(&self, description: &ViewDescription) -> T {
+        let mut value = T::default();
+        if let Err(err) = value.try_apply(&description.props) {
+            warn!(
+                "Invalid properties for view {}: {}",
+                description.type_name, err
+            );
+        }
+        for (event, callback) in description.handlers.iter() {
+            let key = (T::short_type_path().to_string(), event.clone());
+            match self
+                .handlers
+                .get(&key)
+                .and_then(|setter| setter.downcast_ref::<fn(T, Callback) -> T>())
+            {
+                Some(set_handler) => value = set_handler(value, *callback),
+                None => warn!(
+                    "View {} has no event named {}",
+                    description.type_name, event
+                ),
+            }
+        }
+        value
+    }
+}
+
+/// A view which is constructed at runtime from a [`ViewDescription`], using the view types
+/// registered in the [`ViewTypeRegistry`]. The view is rebuilt if the registry changes.
+#[derive(Clone)]
+pub struct ReflectView(Arc<ViewDescription>);
+
+impl ReflectView {
+    /// Construct a new `ReflectView` from a description.
+    pub fn new(description: impl Into<Arc<ViewDescription>>) -> Self {
+        Self(description.into())
+    }
+}
+
+impl PartialEq for ReflectView {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl ViewTemplate for ReflectView {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        cx.use_resource::<ViewTypeRegistry>().instantiate(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::World, reflect::GetField};
+
+    use super::*;
+
+    #[derive(Clone, PartialEq, Default, Reflect)]
+    struct Label {
+        text: String,
+        size: f32,
+        #[reflect(ignore)]
+        on_click: Option<Callback>,
+    }
+
+    impl ViewTemplate for Label {
+        type View = String;
+
+        fn create(&self, _cx: &mut Cx) -> Self::View {
+            self.text.clone()
+        }
+    }
+
+    #[test]
+    fn test_instantiate() {
+        let mut registry = ViewTypeRegistry::default();
+        registry.register::<Label>();
+        assert!(registry.contains("Label"));
+
+        let view = registry
+            .instantiate(&ViewDescription::new("Label").with_prop("text", "Hello".to_string()));
+        let label = view.0.as_any().downcast_ref::<Label>().unwrap();
+        assert_eq!(label.text, "Hello");
+        assert_eq!(label.size, 0.);
+
+        // Properties of the wrong type leave the default value in place.
+        let view = registry.instantiate(&ViewDescription::new("Label").with_prop("text", 1));
+        let label = view.0.as_any().downcast_ref::<Label>().unwrap();
+        assert_eq!(label.text, "");
+
+        let view = registry.instantiate(&ViewDescription::new("Unknown"));
+        assert!(view.0.as_any().downcast_ref::<Label>().is_none());
+    }
+
+    #[test]
+    fn test_handlers() {
+        let mut world = World::default();
+        let callback = Callback {
+            id: world.register_system(|| {}),
+        };
+        let mut registry = ViewTypeRegistry::default();
+        registry
+            .register::<Label>()
+            .register_handler::<Label>("click", |label, callback| Label {
+                on_click: Some(callback),
+                ..label
+            });
+        assert_eq!(
+            registry.field_type("Label", "size"),
+            Some(TypeId::of::<f32>())
+        );
+
+        let view =
+            registry.instantiate(&ViewDescription::new("Label").with_handler("click", callback));
+        let label = view.0.as_any().downcast_ref::<Label>().unwrap();
+        assert_eq!(label.on_click, Some(callback));
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut world = World::default();
+        let callback = Callback {
+            id: world.register_system(|| {}),
+        };
+        let description = ViewDescription::new("Label")
+            .with_prop("text", "Hello".to_string())
+            .with_prop("size", 12.)
+            .with_handler("click", callback)
+            .with_child(ViewDescription::new("Label").with_prop("text", "Child".to_string()));
+
+        let mut copy = description.clone();
+        assert_eq!(copy.type_name, "Label");
+        assert_eq!(
+            copy.props.get_field::<String>("text"),
+            Some(&"Hello".to_string())
+        );
+        assert_eq!(copy.props.get_field::<f64>("size"), Some(&12.));
+        assert_eq!(copy.handlers.get("click"), Some(&callback));
+        assert_eq!(copy.children.len(), 1);
+        assert_eq!(
+            copy.children[0].props.get_field::<String>("text"),
+            Some(&"Child".to_string())
+        );
+
+        // The copy is independent of the original.
+        copy.props.insert("text", "Changed".to_string());
+        assert_eq!(
+            description.props.get_field::<String>("text"),
+            Some(&"Hello".to_string())
+        );
+    }
+}
@@ -1,11 +1,15 @@
-use crate::{cx::Cx, tracking_scope::TrackingScope, AnyViewAdapter, View, ViewThunk};
+use crate::{
+    cx::Cx, ownership::DespawnOwned, tracking_scope::TrackingScope, AnyViewAdapter, ReactionLimits,
+    View, ViewMemo, ViewThunk,
+};
 use bevy::{
     core::Name,
-    ecs::world::DeferredWorld,
+    ecs::{change_detection::DetectChangesMut, world::DeferredWorld},
     hierarchy::{BuildChildren, BuildWorldChildren},
-    prelude::{Component, Entity, World},
+    log::warn,
+    prelude::{Component, Entity, Resource, World},
+    utils::HashSet,
 };
-use std::sync::{Arc, Mutex};
 
 #[cfg(feature = "verbose")]
 use bevy::log::info;
@@ -37,10 +41,12 @@ impl<VT: ViewTemplate + Clone + PartialEq> View for VT {
         // state.1.clone()
 
         let entity = state.0;
-        let entt = world.entity(entity);
-        let cell = entt.get::<ViewTemplateStateCell<VT>>().unwrap();
-        let inner = cell.0.lock().unwrap();
-        inner.nodes(world, out);
+        let cell = world.get::<ViewTemplateStateCell<VT>>(entity).unwrap();
+        match cell.0 {
+            Some(ref inner) => inner.nodes(world, out),
+            // The template is in the middle of being rebuilt, use the cached nodes.
+            None => out.extend(state.1.iter().copied()),
+        }
     }
 
     fn build(&self, cx: &mut Cx) -> Self::State {
@@ -58,14 +64,14 @@ impl<VT: ViewTemplate + Clone + PartialEq> View for VT {
 
         let mut scope = TrackingScope::new(tick);
         let mut cx_inner = Cx::new(cx.world_mut(), child_entity, &mut scope);
-        let view = self.create(&mut cx_inner);
+        let view = create_view(self, &mut cx_inner);
         let state = view.build(&mut cx_inner);
         let mut nodes: Vec<Entity> = Vec::new();
         view.nodes(cx.world(), &state, &mut nodes);
         let cell = ViewTemplateState::new(self.clone(), view, state);
         let thunk = cell.create_thunk();
         cx.world_mut().entity_mut(child_entity).insert((
-            ViewTemplateStateCell(Arc::new(Mutex::new(cell))),
+            ViewTemplateStateCell(Some(cell)),
             scope,
             thunk,
         ));
@@ -80,13 +86,12 @@ impl<VT: ViewTemplate + Clone + PartialEq> View for VT {
 
         let mut entt = cx.world_mut().entity_mut(entity);
         let cell = entt.get::<ViewTemplateStateCell<VT>>().unwrap();
-        let mut inner = cell.0.lock().unwrap();
-        if inner.template != *self {
+        if cell.0.as_ref().is_some_and(|inner| inner.template != *self) {
             // Update the template and trigger a rebuild on the child template.
-            inner.template = self.clone();
-            drop(inner);
-            let scope = entt.get_mut::<TrackingScope>().unwrap();
-            scope.set_changed();
+            let mut cell = entt.get_mut::<ViewTemplateStateCell<VT>>().unwrap();
+            cell.bypass_change_detection().0.as_mut().unwrap().template = self.clone();
+            let scope = entt.get::<TrackingScope>().unwrap();
+            scope.set_changed(entt.world());
         }
 
         // False because we haven't changed the output yet.
@@ -100,11 +105,11 @@ impl<VT: ViewTemplate + Clone + PartialEq> View for VT {
         info!("attach_children() {}", entity);
         assert!(world.get_entity(entity).is_some());
 
-        let entt = world.entity_mut(entity);
-        let cell = entt.get::<ViewTemplateStateCell<VT>>().unwrap();
-        let inner = cell.0.clone();
         let mut nodes: Vec<Entity> = Vec::new();
-        inner.lock().unwrap().nodes(world, &mut nodes);
+        let cell = world.get::<ViewTemplateStateCell<VT>>(entity).unwrap();
+        if let Some(ref inner) = cell.0 {
+            inner.nodes(world, &mut nodes);
+        }
         if state.1 != nodes {
             state.1 = nodes;
             true
@@ -119,14 +124,74 @@ impl<VT: ViewTemplate + Clone + PartialEq> View for VT {
         #[cfg(feature = "verbose")]
         info!("raze() {}", entity);
 
-        let entt = world.entity_mut(entity);
-        let cell = entt.get::<ViewTemplateStateCell<VT>>().unwrap().0.clone();
-        let mut inner = cell.lock().unwrap();
-        inner.raze(world);
+        let mut cell = world.get_mut::<ViewTemplateStateCell<VT>>(entity).unwrap();
+        if let Some(mut inner) = cell.bypass_change_detection().0.take() {
+            inner.raze(world);
+        }
         world.commands().entity(entity).remove_parent().despawn();
+        // Sweep up any entities owned by the template that weren't despawned by `raze()`.
+        world.commands().add(DespawnOwned(entity));
+    }
+
+    fn memo(&self) -> Option<ViewMemo> {
+        // A template is rebuilt in its own tracking scope, so skipping it is always safe.
+        Some(ViewMemo::new(self.clone()))
+    }
+}
+
+/// Call `create()` on a template. In strict mode, the template is created a second time,
+/// replaying the same hooks, and a warning is logged if the two views differ. Views which can't
+/// be compared (their [`View::memo`] is `None`) can't be checked; a warning is logged the first
+/// time each such template is created, so that strict mode doesn't silently pass.
+fn create_view<VT: ViewTemplate>(template: &VT, cx: &mut Cx) -> VT::View {
+    let view = template.create(cx);
+    let strict = cx
+        .world()
+        .get_resource::<ReactionLimits>()
+        .is_some_and(|limits| limits.strict_templates);
+    if !cfg!(debug_assertions) || !strict {
+        return view;
     }
+    let Some(memo) = view.memo() else {
+        let name = std::any::type_name::<VT>();
+        let mut unchecked = cx
+            .world_mut()
+            .get_resource_or_insert_with(UncheckedTemplates::default);
+        if !unchecked.0.contains(name) {
+            unchecked.0.insert(name);
+            warn!(
+                "Template {} can't be checked in strict mode, because the view it creates \
+                can't be compared.",
+                name
+            );
+        }
+        return view;
+    };
+
+    // `create()` is the first thing called in the template's scope, so rewinding to the first
+    // hook replays the same hooks. Cleanups registered by the second call are discarded, since
+    // the second view is never built.
+    let num_cleanups = {
+        let mut tracking = cx.tracking.borrow_mut();
+        tracking.rewind_hooks();
+        tracking.cleanups.len()
+    };
+    let other = template.create(cx);
+    cx.tracking.borrow_mut().cleanups.truncate(num_cleanups);
+    if other.memo().as_ref() != Some(&memo) {
+        warn!(
+            "Template {} created a different view when called twice. Templates should only \
+            depend on their properties and tracked state.",
+            std::any::type_name::<VT>()
+        );
+    }
+    view
 }
 
+/// Names of the templates which have already been reported as uncheckable in strict mode.
+#[derive(Resource, Default)]
+struct UncheckedTemplates(HashSet<&'static str>);
+
 struct ViewTemplateState<VT: ViewTemplate> {
     template: VT,
     view: VT::View,
@@ -147,7 +212,7 @@ impl<VT: ViewTemplate> ViewTemplateState<VT> {
     }
 
     fn rebuild(&mut self, cx: &mut Cx) -> bool {
-        self.view = self.template.create(cx);
+        self.view = create_view(&self.template, cx);
         self.view.rebuild(cx, &mut self.state)
     }
 
@@ -167,20 +232,27 @@ impl<VT: ViewTemplate> ViewTemplateState<VT> {
     }
 }
 
+/// Component which holds a template, the view it generated and the view's state. As with
+/// [`ViewStateCell`], the contents are taken out of the cell while the template is being
+/// rebuilt, rather than being guarded by a lock.
+///
+/// [`ViewStateCell`]: crate::ViewStateCell
 #[derive(Component)]
-pub struct ViewTemplateStateCell<VF: ViewTemplate>(Arc<Mutex<ViewTemplateState<VF>>>);
+pub struct ViewTemplateStateCell<VF: ViewTemplate>(Option<ViewTemplateState<VF>>);
 
 impl<VT: ViewTemplate> ViewTemplateStateCell<VT> {
-    fn nodes(&self, world: &World, out: &mut Vec<Entity>) {
-        self.0.lock().unwrap().nodes(world, out);
-    }
-
-    pub fn raze(&self, world: &mut DeferredWorld) {
-        self.0.lock().unwrap().raze(world);
+    /// Remove the template state from the cell on the given entity.
+    fn take(world: &mut World, entity: Entity) -> Option<ViewTemplateState<VT>> {
+        world
+            .get_mut::<ViewTemplateStateCell<VT>>(entity)
+            .and_then(|mut cell| cell.bypass_change_detection().0.take())
     }
 
-    pub fn attach_children(&self, world: &mut World) -> bool {
-        self.0.lock().unwrap().attach_children(world)
+    /// Return the template state to the cell on the given entity, if it still exists.
+    fn restore(world: &mut World, entity: Entity, inner: ViewTemplateState<VT>) {
+        if let Some(mut cell) = world.get_mut::<ViewTemplateStateCell<VT>>(entity) {
+            cell.bypass_change_detection().0 = Some(inner);
+        }
     }
 }
 
@@ -190,40 +262,44 @@ pub struct ViewTemplateAdapter<VF: ViewTemplate> {
 
 impl<VF: ViewTemplate> AnyViewAdapter for ViewTemplateAdapter<VF> {
     fn nodes(&self, world: &mut World, entity: Entity, out: &mut Vec<Entity>) {
-        if let Some(view_cell) = world.entity(entity).get::<ViewTemplateStateCell<VF>>() {
-            view_cell.nodes(world, out)
+        let world: &World = world;
+        if let Some(inner) = world
+            .get::<ViewTemplateStateCell<VF>>(entity)
+            .and_then(|cell| cell.0.as_ref())
+        {
+            inner.nodes(world, out)
         }
     }
 
     fn rebuild(&self, world: &mut World, entity: Entity, scope: &mut TrackingScope) -> bool {
+        let Some(mut inner) = ViewTemplateStateCell::<VF>::take(world, entity) else {
+            return false;
+        };
         let mut cx = Cx::new(world, entity, scope);
-        if let Some(view_cell) = cx
-            .world_mut()
-            .entity(entity)
-            .get::<ViewTemplateStateCell<VF>>()
-        {
-            let vs = view_cell.0.clone();
-            let mut inner = vs.lock().unwrap();
-            inner.rebuild(&mut cx)
-        } else {
-            false
-        }
+        let changed = inner.rebuild(&mut cx);
+        ViewTemplateStateCell::restore(world, entity, inner);
+        changed
     }
 
     fn attach_children(&self, world: &mut World, entity: Entity) -> bool {
-        if let Some(view_cell) = world.entity(entity).get::<ViewTemplateStateCell<VF>>() {
-            let vs = view_cell.0.clone();
-            let mut inner = vs.lock().unwrap();
-            inner.attach_children(world)
-        } else {
-            false
-        }
+        let Some(mut inner) = ViewTemplateStateCell::<VF>::take(world, entity) else {
+            return false;
+        };
+        let changed = inner.attach_children(world);
+        ViewTemplateStateCell::restore(world, entity, inner);
+        changed
     }
 
     fn raze(&self, world: &mut DeferredWorld, entity: Entity) {
-        if let Some(view_cell) = world.entity_mut(entity).get::<ViewTemplateStateCell<VF>>() {
-            let inner = view_cell.0.clone();
-            inner.lock().unwrap().raze(world);
+        let Some(mut inner) = world
+            .get_mut::<ViewTemplateStateCell<VF>>(entity)
+            .and_then(|mut cell| cell.bypass_change_detection().0.take())
+        else {
+            return;
+        };
+        inner.raze(world);
+        if let Some(mut cell) = world.get_mut::<ViewTemplateStateCell<VF>>(entity) {
+            cell.bypass_change_detection().0 = Some(inner);
         }
     }
 }
@@ -0,0 +1,137 @@
+use bevy::{
+    prelude::*,
+    window::{CursorIcon, PrimaryWindow},
+};
+
+use crate::{Cx, ViewTemplate};
+
+/// Resource which holds the window title and cursor requested by views. When several views
+/// make a request, the most recent one wins; when a view is unmounted, its request is removed,
+/// and the previous request (or the window's original state) is restored.
+#[derive(Resource, Default)]
+pub struct WindowOverrides {
+    titles: Vec<(Entity, String)>,
+    cursors: Vec<(Entity, CursorIcon)>,
+    original_title: Option<String>,
+    original_cursor: Option<CursorIcon>,
+}
+
+impl WindowOverrides {
+    /// The title requested by views, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.titles.last().map(|(_, title)| title.as_str())
+    }
+
+    /// The cursor icon requested by views, if any.
+    pub fn cursor(&self) -> Option<CursorIcon> {
+        self.cursors.last().map(|(_, icon)| *icon)
+    }
+}
+
+impl<'p, 'w> Cx<'p, 'w> {
+    /// Set the title of the primary window. The title is restored when this view is unmounted,
+    /// or when it reacts without calling `set_window_title` again.
+    pub fn set_window_title(&mut self, title: impl Into<String>) {
+        let owner = self.owner();
+        let title = title.into();
+        let mut overrides = self.world_mut().resource_mut::<WindowOverrides>();
+        overrides.titles.retain(|(entity, _)| *entity != owner);
+        overrides.titles.push((owner, title));
+        self.on_cleanup(move |world| {
+            if let Some(mut overrides) = world.get_resource_mut::<WindowOverrides>() {
+                overrides.titles.retain(|(entity, _)| *entity != owner);
+            }
+        });
+    }
+
+    /// Set the cursor icon of the primary window, overriding the cursor of any element under
+    /// the pointer. This is typically called while a drag is in progress:
+    ///
+    /// ```ignore
+    /// if dragging {
+    ///     cx.set_cursor(CursorIcon::Grabbing);
+    /// }
+    /// ```
+    ///
+    /// The cursor is restored when this view is unmounted, or when it reacts without calling
+    /// `set_cursor` again.
+    pub fn set_cursor(&mut self, icon: CursorIcon) {
+        let owner = self.owner();
+        let mut overrides = self.world_mut().resource_mut::<WindowOverrides>();
+        overrides.cursors.retain(|(entity, _)| *entity != owner);
+        overrides.cursors.push((owner, icon));
+        self.on_cleanup(move |world| {
+            if let Some(mut overrides) = world.get_resource_mut::<WindowOverrides>() {
+                overrides.cursors.retain(|(entity, _)| *entity != owner);
+            }
+        });
+    }
+}
+
+/// View which sets the title of the primary window while it is mounted.
+#[derive(Clone, PartialEq)]
+pub struct WindowTitle(pub String);
+
+impl ViewTemplate for WindowTitle {
+    type View = ();
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        cx.set_window_title(self.0.clone());
+    }
+}
+
+/// View which sets the cursor icon of the primary window while it is mounted.
+#[derive(Clone, PartialEq)]
+pub struct WindowCursor(pub CursorIcon);
+
+impl ViewTemplate for WindowCursor {
+    type View = ();
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        cx.set_cursor(self.0);
+    }
+}
+
+/// Apply the requested title and cursor to the primary window. The cursor is applied every
+/// frame, since other systems may set the cursor based on which element is hovered.
+pub(crate) fn apply_window_overrides(
+    mut overrides: ResMut<WindowOverrides>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let overrides = overrides.bypass_change_detection();
+
+    match overrides.titles.last() {
+        Some((_, title)) => {
+            if overrides.original_title.is_none() {
+                overrides.original_title = Some(window.title.clone());
+            }
+            if window.title != *title {
+                window.title.clone_from(title);
+            }
+        }
+        None => {
+            if let Some(original) = overrides.original_title.take() {
+                window.title = original;
+            }
+        }
+    }
+
+    match overrides.cursors.last() {
+        Some((_, icon)) => {
+            if overrides.original_cursor.is_none() {
+                overrides.original_cursor = Some(window.cursor.icon);
+            }
+            if window.cursor.icon != *icon {
+                window.cursor.icon = *icon;
+            }
+        }
+        None => {
+            if let Some(original) = overrides.original_cursor.take() {
+                window.cursor.icon = original;
+            }
+        }
+    }
+}
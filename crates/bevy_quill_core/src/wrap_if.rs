@@ -0,0 +1,200 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::ecs::world::{DeferredWorld, World};
+use bevy::hierarchy::BuildWorldChildren;
+use bevy::prelude::Entity;
+
+use crate::{Cx, View, ViewChild, ViewThunk};
+
+/// Shared between a [`WrapIf`] and the placeholder view that it passes to the wrapper.
+#[derive(Default)]
+struct WrapSlot {
+    /// Output nodes of the wrapped child, as of the last build or rebuild.
+    nodes: Vec<Entity>,
+    /// The view entity in which the placeholder was built, if it has been built.
+    owner: Option<Entity>,
+}
+
+/// Placeholder view which stands in for the child of a [`WrapIf`] within the wrapper. It
+/// outputs the child's nodes, but does not own them: building and razing the child is
+/// the responsibility of the `WrapIf`.
+struct WrapSlotView(Arc<Mutex<WrapSlot>>);
+
+impl View for WrapSlotView {
+    type State = ();
+
+    fn nodes(&self, _world: &World, _state: &Self::State, out: &mut Vec<Entity>) {
+        out.extend(self.0.lock().unwrap().nodes.iter().copied());
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        self.0.lock().unwrap().owner = Some(cx.owner());
+    }
+
+    fn rebuild(&self, _cx: &mut Cx, _state: &mut Self::State) -> bool {
+        false
+    }
+
+    fn raze(&self, _world: &mut DeferredWorld, _state: &mut Self::State) {
+        self.0.lock().unwrap().owner = None;
+    }
+}
+
+pub struct WrapIfState<W: View, C: View> {
+    owner: Entity,
+    child: C::State,
+    wrapper: Option<(W, W::State)>,
+    slot: Arc<Mutex<WrapSlot>>,
+    slot_view: ViewChild,
+}
+
+/// A view which wraps its child in another view, such as a tooltip or a scroll view, only
+/// when a condition is true. Unlike switching between two views with [`Cond`], the child is
+/// not razed when the wrapper is added or removed, so any state it holds (mutables, text
+/// input, scroll position and so on) is preserved; only its display nodes are re-parented.
+///
+/// The wrapper function receives a placeholder [`ViewChild`] which should be placed somewhere
+/// within the wrapper's children:
+///
+/// ```ignore
+/// WrapIf::new(
+///     show_tooltip,
+///     |child| Element::<NodeBundle>::new().style(style_tooltip_anchor).children(child),
+///     Button::new().children("Save"),
+/// )
+/// ```
+///
+/// [`Cond`]: crate::Cond
+pub struct WrapIf<W: View, F: Fn(ViewChild) -> W, C: View> {
+    test: bool,
+    wrap: F,
+    child: C,
+}
+
+impl<W: View, F: Fn(ViewChild) -> W + Send + Sync + 'static, C: View> WrapIf<W, F, C> {
+    /// Construct a new `WrapIf` View.
+    pub fn new(test: bool, wrap: F, child: C) -> Self {
+        Self { test, wrap, child }
+    }
+
+    /// Create the wrapper view and build it.
+    fn build_wrapper(&self, cx: &mut Cx, slot_view: &ViewChild) -> (W, W::State) {
+        let wrapper = (self.wrap)(slot_view.clone());
+        let wrapper_state = wrapper.build(cx);
+        (wrapper, wrapper_state)
+    }
+
+    /// Update the cached output nodes of the child, returning true if they changed.
+    fn update_nodes(&self, world: &World, state: &mut WrapIfState<W, C>) -> bool {
+        let mut nodes: Vec<Entity> = Vec::new();
+        self.child.nodes(world, &state.child, &mut nodes);
+        let mut slot = state.slot.lock().unwrap();
+        if slot.nodes != nodes {
+            slot.nodes = nodes;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Re-attach the child's nodes to the wrapper, after the child's output has changed.
+    fn reattach_wrapped(&self, world: &mut World, state: &mut WrapIfState<W, C>) {
+        let Some((wrapper, wrapper_state)) = state.wrapper.as_mut() else {
+            return;
+        };
+        wrapper.attach_children(world, wrapper_state);
+        // If the placeholder ended up inside a nested template, that template has its own
+        // view entity, and needs to be told to re-attach separately.
+        let slot_owner = state.slot.lock().unwrap().owner;
+        if let Some(slot_owner) = slot_owner.filter(|e| *e != state.owner) {
+            if let Some(thunk) = world.get::<ViewThunk>(slot_owner).map(|t| t.0) {
+                thunk.attach_children(world, slot_owner);
+            }
+        }
+    }
+}
+
+impl<W: View, F: Fn(ViewChild) -> W + Send + Sync + 'static, C: View> View for WrapIf<W, F, C> {
+    type State = WrapIfState<W, C>;
+
+    fn nodes(&self, world: &World, state: &Self::State, out: &mut Vec<Entity>) {
+        match state.wrapper {
+            Some((ref wrapper, ref wrapper_state)) => wrapper.nodes(world, wrapper_state, out),
+            None => self.child.nodes(world, &state.child, out),
+        }
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let child = self.child.build(cx);
+        let slot = Arc::new(Mutex::new(WrapSlot::default()));
+        let slot_view = ViewChild::new(WrapSlotView(slot.clone()));
+        let mut state = WrapIfState {
+            owner: cx.owner(),
+            child,
+            wrapper: None,
+            slot,
+            slot_view,
+        };
+        self.update_nodes(cx.world(), &mut state);
+        if self.test {
+            state.wrapper = Some(self.build_wrapper(cx, &state.slot_view));
+        }
+        state
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        let child_changed = self.child.rebuild(cx, &mut state.child);
+        let nodes_changed = self.update_nodes(cx.world(), state);
+        match (self.test, state.wrapper.is_some()) {
+            (true, true) => {
+                let (wrapper, wrapper_state) = state.wrapper.as_mut().unwrap();
+                *wrapper = (self.wrap)(state.slot_view.clone());
+                let changed = wrapper.rebuild(cx, wrapper_state);
+                if child_changed || nodes_changed {
+                    self.reattach_wrapped(cx.world_mut(), state);
+                }
+                changed
+            }
+
+            (true, false) => {
+                // Building the wrapper moves the child's nodes into it.
+                state.wrapper = Some(self.build_wrapper(cx, &state.slot_view));
+                true
+            }
+
+            (false, true) => {
+                // Detach the child's nodes before razing the wrapper, so that they don't get
+                // despawned along with it.
+                let nodes = state.slot.lock().unwrap().nodes.clone();
+                for node in nodes {
+                    cx.world_mut().entity_mut(node).remove_parent();
+                }
+                let (wrapper, mut wrapper_state) = state.wrapper.take().unwrap();
+                wrapper.raze(&mut DeferredWorld::from(cx.world_mut()), &mut wrapper_state);
+                true
+            }
+
+            (false, false) => child_changed || nodes_changed,
+        }
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        let child_changed = self.child.attach_children(world, &mut state.child);
+        let nodes_changed = self.update_nodes(world, state);
+        if state.wrapper.is_some() {
+            if child_changed || nodes_changed {
+                self.reattach_wrapped(world, state);
+            }
+            false
+        } else {
+            child_changed || nodes_changed
+        }
+    }
+
+    fn raze(&self, world: &mut DeferredWorld, state: &mut Self::State) {
+        if let Some((wrapper, mut wrapper_state)) = state.wrapper.take() {
+            wrapper.raze(world, &mut wrapper_state);
+        }
+        self.child.raze(world, &mut state.child);
+    }
+}
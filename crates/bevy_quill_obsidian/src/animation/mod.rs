@@ -5,6 +5,96 @@ use bevy::{
     math::{cubic_splines::CubicSegment, Vec2},
     ui::{self, BackgroundColor, BorderColor, Style},
 };
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Setting;
+
+/// Global settings for widget animations, such as opening menus and dialogs, and expanding
+/// disclosures. All of the animations in Obsidian respect these settings, and custom widgets
+/// can read them with [`UseAnimationSettings`](crate::hooks::UseAnimationSettings).
+///
+/// This is a [`Setting`], so it can be persisted with
+/// [`register_setting`](crate::settings::RegisterSetting::register_setting).
+#[derive(Resource, Reflect, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnimationSettings {
+    /// Multiplier for the speed of all widget animations: 2.0 plays them twice as fast.
+    pub speed: f32,
+
+    /// If true, animations are skipped, and widgets jump straight to their final state. This
+    /// is for users who are sensitive to motion.
+    pub reduced_motion: bool,
+}
+
+impl Default for AnimationSettings {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            reduced_motion: false,
+        }
+    }
+}
+
+impl Setting for AnimationSettings {
+    const KEY: &'static str = "animation";
+    const LABEL: &'static str = "Animation";
+}
+
+impl AnimationSettings {
+    /// Returns the actual duration of an animation which lasts `duration` seconds at normal
+    /// speed. This is zero if reduced motion is enabled.
+    pub fn duration(&self, duration: f32) -> f32 {
+        if self.reduced_motion {
+            0.
+        } else {
+            duration / self.speed.max(0.01)
+        }
+    }
+
+    /// Convert a frame time into animation time. If reduced motion is enabled, this is
+    /// infinite, so that any animation advanced by it finishes immediately.
+    pub fn scale_delta(&self, delta: f32) -> f32 {
+        if self.reduced_motion {
+            f32::INFINITY
+        } else {
+            delta * self.speed.max(0.01)
+        }
+    }
+}
+
+/// The timing of a kind of widget animation, at normal speed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnimationPreset {
+    /// Duration of the animation, in seconds.
+    pub duration: f32,
+
+    /// Control points of the cubic bezier easing curve.
+    pub easing: (Vec2, Vec2),
+}
+
+impl AnimationPreset {
+    /// The default easing curve.
+    const EASE: (Vec2, Vec2) = (Vec2::new(0.25, 0.1), Vec2::new(0.25, 1.0));
+
+    /// Opening and closing menus. This is very short: the transition is mostly used to wait
+    /// for the menu items to be created before focusing them.
+    pub const MENU: Self = Self {
+        duration: 0.01,
+        easing: Self::EASE,
+    };
+
+    /// Opening and closing dialogs.
+    pub const DIALOG: Self = Self {
+        duration: 0.3,
+        easing: Self::EASE,
+    };
+
+    /// Expanding and collapsing disclosures and other accordion-style sections.
+    pub const DISCLOSURE: Self = Self {
+        duration: 0.3,
+        easing: Self::EASE,
+    };
+}
 
 /// Trait that represents a property that can be animated, such as background color,
 /// transform, and so on.
@@ -160,8 +250,9 @@ where
 {
     /// Create a new animated transition.
     pub fn new(origin: T::ValueType, target: T::ValueType, duration: f32, delay: f32) -> Self {
+        let (p1, p2) = AnimationPreset::EASE;
         Self {
-            timing: CubicSegment::new_bezier(Vec2::new(0.25, 0.1), Vec2::new(0.25, 1.0)),
+            timing: CubicSegment::new_bezier(p1, p2),
             origin,
             target,
             clock: 0.0,
@@ -172,17 +263,49 @@ where
 
     /// Start a new animated transition.
     /// If the entity already has an animated transition of the same type, the transition will be
-    /// restarted with the new target value.
+    /// restarted with the new target value. If reduced motion is enabled in the
+    /// [`AnimationSettings`], the target value is applied immediately.
     pub fn start(entity: &mut EntityWorldMut, target: T::ValueType, duration: f32) {
+        let (p1, p2) = AnimationPreset::EASE;
+        Self::start_with(entity, target, duration, p1, p2);
+    }
+
+    /// Start a new animated transition, with the duration and easing curve of a preset.
+    pub fn start_preset(
+        entity: &mut EntityWorldMut,
+        target: T::ValueType,
+        preset: AnimationPreset,
+    ) {
+        let (p1, p2) = preset.easing;
+        Self::start_with(entity, target, preset.duration, p1, p2);
+    }
+
+    fn start_with(
+        entity: &mut EntityWorldMut,
+        target: T::ValueType,
+        duration: f32,
+        p1: Vec2,
+        p2: Vec2,
+    ) {
         // If we're already animating to the same target, don't restart the animation.
         if let Some(anim) = entity.get_mut::<Self>() {
             if anim.target == target {
                 return;
             }
         }
+        let reduced_motion = entity
+            .world()
+            .get_resource::<AnimationSettings>()
+            .is_some_and(|settings| settings.reduced_motion);
         if let Some(mut cmp) = entity.get_mut::<T::ComponentType>() {
             let origin = T::current(&cmp);
+            if reduced_motion {
+                T::update(&mut cmp, 1.0, origin, target);
+                entity.remove::<Self>();
+                return;
+            }
             let mut transition = Self::new(origin, target, duration, 0.0);
+            transition.with_timing(p1, p2);
             transition.advance(&mut cmp, 0.0);
             entity.insert(transition);
         }
@@ -223,9 +346,11 @@ where
         mut commands: Commands,
         mut query: Query<(Entity, &mut AnimatedTransition<T>, &mut T::ComponentType)>,
         time: Res<Time>,
+        settings: Res<AnimationSettings>,
     ) {
+        let delta = settings.scale_delta(time.delta_seconds());
         for (entity, mut transition, mut cmp) in query.iter_mut() {
-            transition.advance(&mut cmp, time.delta_seconds());
+            transition.advance(&mut cmp, delta);
             if transition.clock >= transition.delay + transition.duration {
                 commands.entity(entity).remove::<AnimatedTransition<T>>();
             }
@@ -238,7 +363,7 @@ pub struct AnimatedTransitionPlugin;
 
 impl Plugin for AnimatedTransitionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.init_resource::<AnimationSettings>().add_systems(
             Update,
             (
                 AnimatedTransition::<AnimatedBackgroundColor>::run_animations,
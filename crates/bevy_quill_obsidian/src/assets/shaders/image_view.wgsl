@@ -0,0 +1,39 @@
+// This shader draws an image with optional channel isolation and a pixel grid.
+#import bevy_ui::ui_vertex_output::UiVertexOutput
+
+@group(1) @binding(0)
+var<uniform> channels: vec4<f32>;
+
+@group(1) @binding(1)
+var<uniform> options: vec4<f32>;
+
+@group(1) @binding(2)
+var image: texture_2d<f32>;
+
+@fragment
+fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
+    let dims = vec2<f32>(textureDimensions(image));
+    let texel_pos = in.uv * dims;
+    // Use textureLoad rather than a sampler so that magnified pixels have hard edges.
+    let texel = textureLoad(image, vec2<i32>(clamp(floor(texel_pos), vec2(0.), dims - 1.)), 0);
+
+    var c: vec3<f32>;
+    if options.x > 0.5 {
+        // Isolated channel, displayed as grayscale.
+        c = vec3<f32>(dot(texel, channels));
+    } else {
+        // Composite over a checkerboard so that transparency is visible.
+        let uv = (in.uv - vec2<f32>(0.5, 0.5)) * in.size / 8.;
+        let check = select(0.0, 1.0, (fract(uv.x) < 0.5) != (fract(uv.y) < 0.5));
+        let bg = mix(vec3<f32>(0.13, 0.13, 0.13), vec3<f32>(0.3, 0.3, 0.3), check);
+        c = mix(bg, texel.rgb, texel.a);
+    }
+
+    // Pixel grid: darken fragments within one screen pixel of a texel boundary.
+    let edge = fract(texel_pos);
+    let width = fwidth(texel_pos);
+    let on_grid = any(edge < width);
+    c = mix(c, vec3<f32>(0.0, 0.0, 0.0), select(0.0, options.y, on_grid));
+
+    return vec4<f32>(c, 1.0);
+}
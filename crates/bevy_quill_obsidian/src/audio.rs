@@ -0,0 +1,111 @@
+use bevy::{
+    audio::{AudioBundle, AudioSource, PlaybackSettings, Volume},
+    prelude::*,
+};
+
+/// The kinds of sounds played by widgets.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InteractionSound {
+    /// A widget was activated: a button, checkbox, disclosure toggle or menu item was clicked,
+    /// a menu was opened, or a slider was grabbed.
+    Click,
+    /// The pointer moved over a button.
+    Hover,
+    /// The user tried to use a widget which is disabled.
+    Error,
+    /// A dialog was opened.
+    Notification,
+}
+
+/// Resource which holds the sounds played by widgets. No sounds are played unless they are
+/// assigned, so applications which want audio feedback, such as game menus, opt in by setting
+/// the sounds:
+///
+/// ```ignore
+/// fn setup_sounds(mut sounds: ResMut<InteractionSounds>, server: Res<AssetServer>) {
+///     sounds.click = Some(server.load("sounds/click.ogg"));
+///     sounds.hover = Some(server.load("sounds/hover.ogg"));
+/// }
+/// ```
+#[derive(Resource, Clone)]
+pub struct InteractionSounds {
+    /// Sound played when a widget is activated.
+    pub click: Option<Handle<AudioSource>>,
+    /// Sound played when the pointer moves over a button.
+    pub hover: Option<Handle<AudioSource>>,
+    /// Sound played when the user tries to use a disabled widget.
+    pub error: Option<Handle<AudioSource>>,
+    /// Sound played when a dialog opens.
+    pub notification: Option<Handle<AudioSource>>,
+    /// Volume of all interaction sounds.
+    pub volume: f32,
+    /// Whether interaction sounds are played at all.
+    pub enabled: bool,
+}
+
+impl Default for InteractionSounds {
+    fn default() -> Self {
+        Self {
+            click: None,
+            hover: None,
+            error: None,
+            notification: None,
+            volume: 1.,
+            enabled: true,
+        }
+    }
+}
+
+impl InteractionSounds {
+    /// The sound assigned to a kind of interaction, if any.
+    pub fn get(&self, sound: InteractionSound) -> Option<&Handle<AudioSource>> {
+        match sound {
+            InteractionSound::Click => self.click.as_ref(),
+            InteractionSound::Hover => self.hover.as_ref(),
+            InteractionSound::Error => self.error.as_ref(),
+            InteractionSound::Notification => self.notification.as_ref(),
+        }
+    }
+}
+
+/// Marker component which silences the widgets on this entity and all of its descendants.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MuteSounds;
+
+/// Trait which defines a method to play an interaction sound.
+pub trait PlayInteractionSound {
+    /// Play an interaction sound on behalf of a widget, unless the widget or one of its
+    /// ancestors has a [`MuteSounds`] component.
+    fn play_interaction_sound(&mut self, widget: Entity, sound: InteractionSound);
+}
+
+impl PlayInteractionSound for World {
+    fn play_interaction_sound(&mut self, widget: Entity, sound: InteractionSound) {
+        let Some(sounds) = self.get_resource::<InteractionSounds>() else {
+            return;
+        };
+        if !sounds.enabled {
+            return;
+        }
+        let Some(source) = sounds.get(sound).cloned() else {
+            return;
+        };
+        let volume = sounds.volume;
+
+        let mut entity = widget;
+        loop {
+            if self.get::<MuteSounds>(entity).is_some() {
+                return;
+            }
+            match self.get::<Parent>(entity) {
+                Some(parent) => entity = parent.get(),
+                None => break,
+            }
+        }
+
+        self.spawn(AudioBundle {
+            source,
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+        });
+    }
+}
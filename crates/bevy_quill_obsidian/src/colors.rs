@@ -1,4 +1,4 @@
-use bevy::color::Srgba;
+use bevy::color::{LinearRgba, Mix, Srgba};
 
 /// Standard colors for the Obsidian UI.
 
@@ -27,3 +27,28 @@ pub const DESTRUCTIVE_ACC: Srgba = Srgba::new(0.733, 0.475, 0.612, 1.0);
 pub const TRANSPARENT: Srgba = Srgba::new(0.0, 0.0, 0.0, 0.0);
 pub const FOCUS: Srgba = Srgba::new(0.055, 0.647, 0.914, 0.15);
 pub const TEXT_SELECT: Srgba = Srgba::new(0.055, 0.647, 0.914, 0.5);
+
+/// Relative luminance of a color, as defined by WCAG, from 0 (black) to 1 (white). Colors which
+/// are not opaque are first composited over [`BACKGROUND`].
+pub fn relative_luminance(color: Srgba) -> f32 {
+    let color = LinearRgba::from(BACKGROUND).mix(&LinearRgba::from(color), color.alpha);
+    0.2126 * color.red + 0.7152 * color.green + 0.0722 * color.blue
+}
+
+/// Contrast ratio between two colors, as defined by WCAG, from 1 (no contrast) to 21.
+pub fn contrast_ratio(a: Srgba, b: Srgba) -> f32 {
+    let la = relative_luminance(a);
+    let lb = relative_luminance(b);
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Returns a readable text color for the given background: either the standard light
+/// [`FOREGROUND`] color, or the dark [`U1`] color, whichever has more contrast. This is useful
+/// for text drawn over user-chosen colors.
+pub fn text_color_for(background: Srgba) -> Srgba {
+    if contrast_ratio(FOREGROUND, background) >= contrast_ratio(U1, background) {
+        FOREGROUND
+    } else {
+        U1
+    }
+}
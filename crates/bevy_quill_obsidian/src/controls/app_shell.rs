@@ -0,0 +1,341 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::colors;
+
+use super::{Splitter, SplitterDirection};
+
+/// How a sidebar is positioned relative to the main content area.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum SidebarMode {
+    /// The sidebar takes up space in the layout, and the content area shrinks to make room.
+    #[default]
+    Pinned,
+
+    /// The sidebar floats on top of the content area.
+    Overlay,
+}
+
+/// Resource which holds the current sidebar sizes and visibility for the [`AppShell`].
+/// Because this is stored in a resource rather than in the view, the sidebar widths are
+/// preserved when the shell is rebuilt or re-mounted.
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct AppShellLayout {
+    /// Width of the left sidebar in pixels.
+    pub left_width: f32,
+
+    /// Width of the right sidebar in pixels.
+    pub right_width: f32,
+
+    /// Whether the left sidebar is expanded.
+    pub left_open: bool,
+
+    /// Whether the right sidebar is expanded.
+    pub right_open: bool,
+}
+
+impl Default for AppShellLayout {
+    fn default() -> Self {
+        Self {
+            left_width: 250.,
+            right_width: 250.,
+            left_open: true,
+            right_open: true,
+        }
+    }
+}
+
+impl AppShellLayout {
+    /// Expand or collapse the left sidebar.
+    pub fn toggle_left(&mut self) {
+        self.left_open = !self.left_open;
+    }
+
+    /// Expand or collapse the right sidebar.
+    pub fn toggle_right(&mut self) {
+        self.right_open = !self.right_open;
+    }
+}
+
+fn style_shell(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .position(ui::PositionType::Absolute)
+        .left(0)
+        .top(0)
+        .right(0)
+        .bottom(0)
+        .background_color(colors::BACKGROUND);
+}
+
+fn style_header(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .flex_shrink(0.)
+        .gap(4)
+        .padding((8, 4))
+        .background_color(colors::U2)
+        .border_bottom(1)
+        .border_color(colors::U1);
+}
+
+fn style_body(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Stretch)
+        .position(ui::PositionType::Relative)
+        .flex_grow(1.)
+        .min_height(0);
+}
+
+fn style_sidebar_container(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Stretch)
+        .flex_shrink(0.);
+}
+
+fn style_sidebar(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .background_color(colors::U2)
+        .padding(8)
+        .gap(8);
+}
+
+fn style_content(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .flex_grow(1.)
+        .min_width(0);
+}
+
+fn sidebar_mode_style(mode: SidebarMode, left: bool, sb: &mut StyleBuilder) {
+    match mode {
+        SidebarMode::Pinned => {
+            sb.position(ui::PositionType::Relative)
+                .left(ui::Val::Auto)
+                .right(ui::Val::Auto)
+                .z_index(0);
+        }
+        SidebarMode::Overlay => {
+            sb.position(ui::PositionType::Absolute).top(0).bottom(0);
+            if left {
+                sb.left(0).right(ui::Val::Auto);
+            } else {
+                sb.right(0).left(ui::Val::Auto);
+            }
+            sb.z_index(10);
+        }
+    }
+}
+
+/// Application shell layout, consisting of a header, a main content area, and optional
+/// collapsible left and right sidebars which can be resized by dragging.
+///
+/// Sidebar sizes and visibility are stored in the [`AppShellLayout`] resource.
+#[derive(Clone, PartialEq)]
+pub struct AppShell {
+    /// Content of the header bar.
+    pub header: Option<ViewChild>,
+
+    /// Content of the left sidebar.
+    pub left: Option<ViewChild>,
+
+    /// Content of the right sidebar.
+    pub right: Option<ViewChild>,
+
+    /// Whether the left sidebar is pinned or floats above the content.
+    pub left_mode: SidebarMode,
+
+    /// Whether the right sidebar is pinned or floats above the content.
+    pub right_mode: SidebarMode,
+
+    /// Minimum width of a sidebar when dragging.
+    pub min_sidebar_width: f32,
+
+    /// Maximum width of a sidebar when dragging.
+    pub max_sidebar_width: f32,
+
+    /// The main content area.
+    pub children: ViewChild,
+
+    /// Additional styles to be applied to the shell.
+    pub style: StyleHandle,
+}
+
+impl Default for AppShell {
+    fn default() -> Self {
+        Self {
+            header: None,
+            left: None,
+            right: None,
+            left_mode: SidebarMode::Pinned,
+            right_mode: SidebarMode::Pinned,
+            min_sidebar_width: 150.,
+            max_sidebar_width: 600.,
+            children: ViewChild::default(),
+            style: StyleHandle::default(),
+        }
+    }
+}
+
+impl AppShell {
+    /// Create a new application shell.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the content of the header bar.
+    pub fn header(mut self, header: impl IntoViewChild) -> Self {
+        self.header = Some(header.into_view_child());
+        self
+    }
+
+    /// Set the content of the left sidebar.
+    pub fn left(mut self, left: impl IntoViewChild) -> Self {
+        self.left = Some(left.into_view_child());
+        self
+    }
+
+    /// Set the content of the right sidebar.
+    pub fn right(mut self, right: impl IntoViewChild) -> Self {
+        self.right = Some(right.into_view_child());
+        self
+    }
+
+    /// Set the positioning mode of the left sidebar.
+    pub fn left_mode(mut self, mode: SidebarMode) -> Self {
+        self.left_mode = mode;
+        self
+    }
+
+    /// Set the positioning mode of the right sidebar.
+    pub fn right_mode(mut self, mode: SidebarMode) -> Self {
+        self.right_mode = mode;
+        self
+    }
+
+    /// Set the range of widths that the sidebars can be resized to.
+    pub fn sidebar_width_range(mut self, min: f32, max: f32) -> Self {
+        self.min_sidebar_width = min;
+        self.max_sidebar_width = max;
+        self
+    }
+
+    /// Set the content of the main area.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+
+    /// Set additional styles to be applied to the shell.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+impl ViewTemplate for AppShell {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let layout = cx.use_resource::<AppShellLayout>().clone();
+        let min_width = self.min_sidebar_width;
+        let max_width = self.max_sidebar_width;
+
+        let on_resize_left =
+            cx.create_callback(move |value: In<f32>, mut layout: ResMut<AppShellLayout>| {
+                layout.left_width = value.clamp(min_width, max_width);
+            });
+
+        // The splitter reports increasing values when dragged to the right, but the right
+        // sidebar grows when dragged to the left, so the value is negated.
+        let on_resize_right =
+            cx.create_callback(move |value: In<f32>, mut layout: ResMut<AppShellLayout>| {
+                layout.right_width = (-*value).clamp(min_width, max_width);
+            });
+
+        let left_mode = self.left_mode;
+        let right_mode = self.right_mode;
+
+        Element::<NodeBundle>::new()
+            .named("AppShell")
+            .style((style_shell, self.style.clone()))
+            .children((
+                Cond::new(
+                    self.header.is_some(),
+                    Element::<NodeBundle>::new()
+                        .named("AppShell::Header")
+                        .style(style_header)
+                        .children(self.header.clone().unwrap_or_default()),
+                    (),
+                ),
+                Element::<NodeBundle>::new()
+                    .named("AppShell::Body")
+                    .style(style_body)
+                    .children((
+                        Cond::new(
+                            layout.left_open && self.left.is_some(),
+                            Element::<NodeBundle>::new()
+                                .named("AppShell::Left")
+                                .style(style_sidebar_container)
+                                .style_dyn(
+                                    move |mode, sb| sidebar_mode_style(mode, true, sb),
+                                    left_mode,
+                                )
+                                .children((
+                                    Element::<NodeBundle>::new()
+                                        .style(style_sidebar)
+                                        .style_dyn(
+                                            |width, sb| {
+                                                sb.width(width);
+                                            },
+                                            layout.left_width,
+                                        )
+                                        .children(self.left.clone().unwrap_or_default()),
+                                    Splitter::new()
+                                        .direction(SplitterDirection::Vertical)
+                                        .value(layout.left_width)
+                                        .on_change(on_resize_left),
+                                )),
+                            (),
+                        ),
+                        Element::<NodeBundle>::new()
+                            .named("AppShell::Content")
+                            .style(style_content)
+                            .children(self.children.clone()),
+                        Cond::new(
+                            layout.right_open && self.right.is_some(),
+                            Element::<NodeBundle>::new()
+                                .named("AppShell::Right")
+                                .style(style_sidebar_container)
+                                .style_dyn(
+                                    move |mode, sb| sidebar_mode_style(mode, false, sb),
+                                    right_mode,
+                                )
+                                .children((
+                                    Splitter::new()
+                                        .direction(SplitterDirection::Vertical)
+                                        .value(-layout.right_width)
+                                        .on_change(on_resize_right),
+                                    Element::<NodeBundle>::new()
+                                        .style(style_sidebar)
+                                        .style_dyn(
+                                            |width, sb| {
+                                                sb.width(width);
+                                            },
+                                            layout.right_width,
+                                        )
+                                        .children(self.right.clone().unwrap_or_default()),
+                                )),
+                            (),
+                        ),
+                    )),
+            ))
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::{color::Srgba, prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{colors, typography};
+
+fn style_avatar(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .justify_content(ui::JustifyContent::Center)
+        .align_items(ui::AlignItems::Center)
+        .flex_shrink(0.);
+}
+
+/// Displays a user's picture, or their initials if no picture is available.
+#[derive(Clone, PartialEq)]
+pub struct Avatar {
+    /// The name of the user. Used to compute the fallback initials.
+    pub name: String,
+
+    /// Optional image to display instead of the initials.
+    pub image: Option<HandleOrOwnedPath<Image>>,
+
+    /// Diameter of the avatar in pixels.
+    pub size: f32,
+
+    /// Background color used when displaying initials. The initials are drawn in a light or
+    /// dark color, whichever contrasts more with the background.
+    pub color: Srgba,
+
+    /// Additional styles to be applied to the avatar.
+    pub style: StyleHandle,
+}
+
+impl Avatar {
+    /// Create a new avatar for the given user name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..default()
+        }
+    }
+
+    /// Set the image to display, from a `&str` or `Handle<Image>`.
+    pub fn image(mut self, image: impl Into<HandleOrOwnedPath<Image>>) -> Self {
+        self.image = Some(image.into());
+        self
+    }
+
+    /// Set the diameter of the avatar.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the background color used when displaying initials.
+    pub fn color(mut self, color: impl Into<Srgba>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Set additional styles to be applied to the avatar.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+impl Default for Avatar {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            image: None,
+            size: 24.,
+            color: colors::PRIMARY,
+            style: StyleHandle::default(),
+        }
+    }
+}
+
+/// Compute up to two uppercase initials from a name, e.g. "Ada Lovelace" -> "AL".
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+impl ViewTemplate for Avatar {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let size = self.size;
+
+        Element::<NodeBundle>::new()
+            .named("Avatar")
+            .style((
+                typography::text_default,
+                style_avatar,
+                move |ss: &mut StyleBuilder| {
+                    ss.width(size)
+                        .height(size)
+                        .border_radius(size * 0.5)
+                        .font_size(size * 0.42);
+                },
+                self.style.clone(),
+            ))
+            .style_dyn(
+                |(image, color), sb| match image {
+                    Some(image) => {
+                        sb.background_color(Option::<Color>::None)
+                            .background_image(&image);
+                    }
+                    None => {
+                        // Keep the initials readable over light background colors.
+                        sb.background_image(MaybeHandleOrPath::None)
+                            .background_color(color)
+                            .color(colors::text_color_for(color));
+                    }
+                },
+                (self.image.clone(), self.color),
+            )
+            .children(Cond::new(self.image.is_none(), initials(&self.name), ()))
+    }
+}
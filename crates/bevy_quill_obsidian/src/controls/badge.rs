@@ -0,0 +1,147 @@
+use bevy::{color::Srgba, prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{colors, size::Size, typography};
+
+fn style_badge(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .justify_content(ui::JustifyContent::Center)
+        .align_items(ui::AlignItems::Center)
+        .padding((4, 0))
+        .color(colors::FOREGROUND);
+}
+
+/// A small bubble which displays a numeric count, such as the number of unread notifications.
+#[derive(Clone, PartialEq)]
+pub struct Badge {
+    /// The count to display.
+    pub count: u32,
+
+    /// Counts greater than this value are displayed as "max+".
+    pub max: u32,
+
+    /// If true, the badge is still displayed when the count is zero.
+    pub show_zero: bool,
+
+    /// Background color of the badge.
+    pub color: Srgba,
+
+    /// Text color of the badge. If `None`, a light or dark color is chosen automatically for
+    /// readability against the background color.
+    pub text_color: Option<Srgba>,
+
+    /// Badge size.
+    pub size: Size,
+
+    /// Additional styles to be applied to the badge.
+    pub style: StyleHandle,
+}
+
+impl Badge {
+    /// Create a new badge with the given count.
+    pub fn new(count: u32) -> Self {
+        Self { count, ..default() }
+    }
+
+    /// Set the count to display.
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Set the maximum count to display before showing "max+".
+    pub fn max(mut self, max: u32) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Set whether to display the badge when the count is zero.
+    pub fn show_zero(mut self, show_zero: bool) -> Self {
+        self.show_zero = show_zero;
+        self
+    }
+
+    /// Set the background color of the badge.
+    pub fn color(mut self, color: impl Into<Srgba>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Set the text color of the badge, instead of choosing it automatically.
+    pub fn text_color(mut self, color: impl Into<Srgba>) -> Self {
+        self.text_color = Some(color.into());
+        self
+    }
+
+    /// Set the badge size.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set additional styles to be applied to the badge.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// The text displayed inside the badge.
+    fn label(&self) -> String {
+        if self.count > self.max {
+            format!("{}+", self.max)
+        } else {
+            format!("{}", self.count)
+        }
+    }
+}
+
+impl Default for Badge {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            max: 99,
+            show_zero: false,
+            color: colors::DESTRUCTIVE,
+            text_color: None,
+            size: Size::Xxs,
+            style: StyleHandle::default(),
+        }
+    }
+}
+
+impl ViewTemplate for Badge {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let size = self.size;
+        let height = (size.font_size() * 1.4).round();
+
+        Cond::new(
+            self.count > 0 || self.show_zero,
+            Element::<NodeBundle>::new()
+                .named("Badge")
+                .style((
+                    typography::text_default,
+                    style_badge,
+                    move |ss: &mut StyleBuilder| {
+                        ss.min_width(height)
+                            .height(height)
+                            .border_radius(height * 0.5)
+                            .font_size(size.font_size() * 0.85);
+                    },
+                    self.style.clone(),
+                ))
+                .style_dyn(
+                    |(color, text_color), sb| {
+                        sb.background_color(color)
+                            .color(text_color.unwrap_or_else(|| colors::text_color_for(color)));
+                    },
+                    (self.color, self.text_color),
+                )
+                .children(self.label()),
+            (),
+        )
+    }
+}
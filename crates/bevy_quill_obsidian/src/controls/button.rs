@@ -1,5 +1,6 @@
-use super::{Disabled, IsDisabled};
+use super::{Disabled, IsDisabled, UseInheritedDisabled};
 use crate::{
+    audio::{InteractionSound, MuteSounds, PlayInteractionSound},
     colors,
     cursor::StyleBuilderCursor,
     focus::{AutoFocus, KeyPressEvent, TabIndex},
@@ -93,6 +94,9 @@ pub struct Button {
 
     /// If true, render the button in a 'minimal' style with no background and reduced padding.
     pub minimal: bool,
+
+    /// If true, the button doesn't play interaction sounds.
+    pub silent: bool,
 }
 
 impl Button {
@@ -171,17 +175,32 @@ impl Button {
         self.autofocus = autofocus;
         self
     }
+
+    /// Set whether the button plays interaction sounds.
+    pub fn silent(mut self, silent: bool) -> Self {
+        self.silent = silent;
+        self
+    }
 }
 
 impl ViewTemplate for Button {
     type View = impl View;
 
     fn create(&self, cx: &mut Cx) -> Self::View {
+        let disabled = self.disabled || cx.use_inherited_disabled();
         let id = cx.create_entity();
         let variant = self.variant;
         let pressed = cx.create_mutable::<bool>(false);
         let hovering = cx.is_hovered(id);
         let focused = cx.is_focus_visible(id);
+        cx.create_effect(
+            move |world, (hovering, disabled)| {
+                if hovering && !disabled {
+                    world.play_interaction_sound(id, InteractionSound::Hover);
+                }
+            },
+            (hovering, disabled),
+        );
 
         let corners = self.corners;
         let minimal = self.minimal;
@@ -214,14 +233,15 @@ impl ViewTemplate for Button {
                         colors::FOREGROUND
                     });
                 },
-                self.disabled,
+                disabled,
             )
             .insert_dyn(TabIndex, self.tab_index)
             // The reason we do this is to avoid capturing `disabled` in the bevy_mod_picking event
             // handlers, as this would require removing and inserting them every time the disabled
             // state changes.
-            .insert_if(self.disabled, || Disabled)
+            .insert_if(disabled, || Disabled)
             .insert_if(self.autofocus, || AutoFocus)
+            .insert_if(self.silent, || MuteSounds)
             .insert_dyn(
                 move |_| {
                     (
@@ -234,9 +254,12 @@ impl ViewTemplate for Button {
                                     .get_resource_mut::<ListenerInput<Pointer<Click>>>()
                                     .unwrap();
                                 event.stop_propagation();
+                                world.play_interaction_sound(id, InteractionSound::Click);
                                 if let Some(on_click) = on_click {
                                     world.run_callback(on_click, ());
                                 }
+                            } else {
+                                world.play_interaction_sound(id, InteractionSound::Error);
                             }
                         }),
                         On::<Pointer<DragStart>>::run(move |world: &mut World| {
@@ -275,6 +298,7 @@ impl ViewTemplate for Button {
                                         || event.key_code == KeyCode::Space)
                                 {
                                     event.stop_propagation();
+                                    world.play_interaction_sound(id, InteractionSound::Click);
                                     if let Some(on_click) = on_click {
                                         world.run_callback(on_click, ());
                                     }
@@ -302,7 +326,7 @@ impl ViewTemplate for Button {
                             };
                             sb.background_color(color);
                         },
-                        (minimal, variant, self.disabled, pressed.get(cx), hovering),
+                        (minimal, variant, disabled, pressed.get(cx), hovering),
                     )
                     .style_dyn(
                         move |focused, sb| {
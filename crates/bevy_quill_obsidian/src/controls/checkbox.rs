@@ -12,6 +12,7 @@ use bevy_mod_stylebuilder::*;
 use bevy_quill_core::*;
 
 use crate::{
+    audio::{InteractionSound, PlayInteractionSound},
     colors,
     cursor::StyleBuilderCursor,
     focus::{KeyPressEvent, TabIndex},
@@ -19,7 +20,7 @@ use crate::{
     typography,
 };
 
-use super::{Disabled, IsDisabled};
+use super::{Disabled, IsDisabled, UseInheritedDisabled};
 
 fn style_checkbox(ss: &mut StyleBuilder) {
     ss.display(ui::Display::Flex)
@@ -131,6 +132,7 @@ impl ViewTemplate for Checkbox {
 
     /// Construct a checkbox widget.
     fn create(&self, cx: &mut Cx) -> Self::View {
+        let disabled = self.disabled || cx.use_inherited_disabled();
         let id = cx.create_entity();
         let pressed = cx.create_mutable::<bool>(false);
         let hovering = cx.is_hovered(id);
@@ -145,7 +147,7 @@ impl ViewTemplate for Checkbox {
             // The reason we do this is to avoid capturing `checked` and `disabled` in the
             // bevy_mod_picking event handlers, as this would require removing and inserting
             // them every time the checked or disabled state changes.
-            .insert_if(self.disabled, || Disabled)
+            .insert_if(disabled, || Disabled)
             .insert_if(self.checked, || Checked)
             .insert_dyn(
                 move |_| {
@@ -155,10 +157,13 @@ impl ViewTemplate for Checkbox {
                             let mut focus = world.get_resource_mut::<Focus>().unwrap();
                             focus.0 = Some(id);
                             if !world.is_disabled(id) {
+                                world.play_interaction_sound(id, InteractionSound::Click);
                                 let next_checked = world.get::<Checked>(id).is_some();
                                 if let Some(on_click) = on_change {
                                     world.run_callback(on_click, !next_checked);
                                 }
+                            } else {
+                                world.play_interaction_sound(id, InteractionSound::Error);
                             }
                         }),
                         On::<Pointer<DragStart>>::run(move |world: &mut World| {
@@ -197,6 +202,7 @@ impl ViewTemplate for Checkbox {
                                         || event.key_code == KeyCode::Space)
                                 {
                                     event.stop_propagation();
+                                    world.play_interaction_sound(id, InteractionSound::Click);
                                     let next_checked = world.get::<Checked>(id).is_some();
                                     if let Some(on_click) = on_change {
                                         world.run_callback(on_click, !next_checked);
@@ -256,7 +262,7 @@ impl ViewTemplate for Checkbox {
                                 colors::FOREGROUND
                             });
                         },
-                        self.disabled,
+                        disabled,
                     )
                     .children(self.label.clone()),
             ))
@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, ui};
+use bevy_quill_core::*;
+
+use super::{Button, ButtonVariant, Dialog, DialogBody, DialogFooter, DialogHeader};
+
+/// Options for a confirmation dialog.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ConfirmOptions {
+    /// Label of the button which confirms the action.
+    pub confirm_label: String,
+    /// Label of the button which cancels the action.
+    pub cancel_label: String,
+    /// If true, the confirm button is styled as a dangerous action.
+    pub destructive: bool,
+}
+
+impl Default for ConfirmOptions {
+    fn default() -> Self {
+        Self {
+            confirm_label: "OK".to_string(),
+            cancel_label: "Cancel".to_string(),
+            destructive: false,
+        }
+    }
+}
+
+impl ConfirmOptions {
+    /// Options for a destructive action, such as deleting something, with the given label
+    /// on the confirm button.
+    pub fn destructive(confirm_label: impl Into<String>) -> Self {
+        Self {
+            confirm_label: confirm_label.into(),
+            destructive: true,
+            ..default()
+        }
+    }
+}
+
+/// The displayed content of a confirmation dialog.
+#[derive(Clone, PartialEq)]
+struct ConfirmContent {
+    id: u64,
+    title: String,
+    message: String,
+    options: ConfirmOptions,
+}
+
+type ConfirmResultFn = Box<dyn FnOnce(&mut World, bool) + Send + Sync>;
+
+struct ConfirmRequest {
+    content: ConfirmContent,
+    on_result: Option<ConfirmResultFn>,
+}
+
+/// Resource which manages the application's confirmation dialogs. Requests are shown one at a
+/// time, in order, by the [`DialogHost`] view, which must be present somewhere in the view
+/// hierarchy.
+///
+/// ```ignore
+/// world
+///     .resource_mut::<Dialogs>()
+///     .confirm(
+///         "Delete nodes",
+///         "Delete the selected nodes? This cannot be undone.",
+///         ConfirmOptions::destructive("Delete"),
+///     )
+///     .then(|world, confirmed| {
+///         if confirmed {
+///             delete_selected_nodes(world);
+///         }
+///     });
+/// ```
+///
+/// Closing the dialog with the escape key, or by clicking outside of it, counts as cancelling.
+#[derive(Resource, Default)]
+pub struct Dialogs {
+    current: Option<ConfirmRequest>,
+    queue: VecDeque<ConfirmRequest>,
+    next_id: u64,
+}
+
+impl Dialogs {
+    /// Ask the user to confirm an action. Call [`PendingConfirm::then`] on the result to
+    /// receive the user's choice.
+    pub fn confirm(
+        &mut self,
+        title: impl Into<String>,
+        message: impl Into<String>,
+        options: ConfirmOptions,
+    ) -> PendingConfirm<'_> {
+        self.next_id += 1;
+        let request = ConfirmRequest {
+            content: ConfirmContent {
+                id: self.next_id,
+                title: title.into(),
+                message: message.into(),
+                options,
+            },
+            on_result: None,
+        };
+        let request = if self.current.is_none() {
+            self.current.insert(request)
+        } else {
+            self.queue.push_back(request);
+            self.queue.back_mut().unwrap()
+        };
+        PendingConfirm(request)
+    }
+
+    /// True if a confirmation dialog is being shown, or is waiting to be shown.
+    pub fn is_pending(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Close the current dialog with the given choice, and show the next one, if any.
+    fn resolve(world: &mut World, confirmed: bool) {
+        let mut dialogs = world.resource_mut::<Dialogs>();
+        let request = dialogs.current.take();
+        dialogs.current = dialogs.queue.pop_front();
+        if let Some(on_result) = request.and_then(|request| request.on_result) {
+            on_result(world, confirmed);
+        }
+    }
+}
+
+/// A confirmation request which has been queued by [`Dialogs::confirm`].
+pub struct PendingConfirm<'a>(&'a mut ConfirmRequest);
+
+impl<'a> PendingConfirm<'a> {
+    /// Set the function which is called with the user's choice: `true` if the action was
+    /// confirmed, `false` if it was cancelled.
+    pub fn then<F: FnOnce(&mut World, bool) + Send + Sync + 'static>(self, on_result: F) {
+        self.0.on_result = Some(Box::new(on_result));
+    }
+}
+
+/// View which displays the confirmation dialogs requested via [`Dialogs`].
+#[derive(Default, Clone, PartialEq)]
+pub struct DialogHost;
+
+impl ViewTemplate for DialogHost {
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let current = cx
+            .use_resource::<Dialogs>()
+            .current
+            .as_ref()
+            .map(|request| request.content.clone());
+        let open = current.is_some();
+
+        // Keep displaying the last dialog while it animates closed.
+        let shown = cx.create_mutable::<Option<ConfirmContent>>(None);
+        cx.create_effect(
+            move |world, current| {
+                if current.is_some() {
+                    shown.set_clone(world, current);
+                }
+            },
+            current.clone(),
+        );
+        let content = current.or_else(|| shown.get_clone(cx));
+
+        let on_cancel = cx.create_callback(|world: &mut World| Dialogs::resolve(world, false));
+        let on_confirm = cx.create_callback(|world: &mut World| Dialogs::resolve(world, true));
+
+        Dialog::new()
+            .width(ui::Val::Px(400.))
+            .open(open)
+            .on_close(on_cancel)
+            .children(
+                content
+                    .map(|content| {
+                        (
+                            DialogHeader::new().children(content.title),
+                            DialogBody::new().children(content.message),
+                            DialogFooter::new().children((
+                                Button::new()
+                                    .children(content.options.cancel_label)
+                                    .on_click(on_cancel),
+                                Button::new()
+                                    .children(content.options.confirm_label)
+                                    .variant(if content.options.destructive {
+                                        ButtonVariant::Danger
+                                    } else {
+                                        ButtonVariant::Primary
+                                    })
+                                    .autofocus(true)
+                                    .on_click(on_confirm),
+                            )),
+                        )
+                            .into_view_child()
+                    })
+                    .unwrap_or_default(),
+            )
+    }
+}
@@ -0,0 +1,1371 @@
+use crate::{
+    colors,
+    floating::{FloatAlign, FloatPosition, FloatSide, Floating},
+    focus::{KeyCharEvent, KeyPressEvent, TabIndex},
+    hooks::{UseIsFocus, UseIsHover},
+    size::Size,
+    typography,
+};
+use bevy::{
+    a11y::{
+        accesskit::{HasPopup, NodeBuilder, Role},
+        AccessibilityNode, Focus,
+    },
+    prelude::*,
+    ui,
+};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use super::{
+    button_bg_color, style_button, style_button_bg, ButtonVariant, Disabled, Icon, IconButton,
+    IsDisabled, ScrollView, Spacer, UseInheritedDisabled,
+};
+
+/// A calendar date, in the proleptic Gregorian calendar.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct CalendarDate {
+    /// The year, e.g. 2024.
+    pub year: i32,
+    /// The month, in the range `1..=12`.
+    pub month: u32,
+    /// The day of the month, starting at 1.
+    pub day: u32,
+}
+
+impl Default for CalendarDate {
+    fn default() -> Self {
+        Self::new(1970, 1, 1)
+    }
+}
+
+impl CalendarDate {
+    /// Construct a new date. Out-of-range months and days are clamped to the nearest valid
+    /// value.
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        let month = month.clamp(1, 12);
+        Self {
+            year,
+            month,
+            day: day.clamp(1, Self::days_in_month(year, month)),
+        }
+    }
+
+    /// Returns true if the given year is a leap year.
+    pub fn is_leap_year(year: i32) -> bool {
+        (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+    }
+
+    /// Returns the number of days in the given month.
+    pub fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            2 if Self::is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// Number of days since 1970-01-01.
+    pub fn to_days(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let mp = (self.month as i64 + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    /// Construct a date from the number of days since 1970-01-01.
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+        let year = (yoe + era * 400) as i32 + if month <= 2 { 1 } else { 0 };
+        Self { year, month, day }
+    }
+
+    /// Day of the week, where 0 is Monday and 6 is Sunday.
+    pub fn weekday(&self) -> u32 {
+        (self.to_days() + 3).rem_euclid(7) as u32
+    }
+
+    /// Return the date which is `days` days after this one.
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Return the date which is `months` months after this one. If the day does not exist
+    /// in the target month, the last day of that month is used instead.
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        Self::new(
+            index.div_euclid(12),
+            index.rem_euclid(12) as u32 + 1,
+            self.day,
+        )
+    }
+
+    /// Return the first day of this date's month.
+    pub fn first_of_month(&self) -> Self {
+        Self::new(self.year, self.month, 1)
+    }
+
+    /// Clamp this date to an optional range.
+    pub fn clamp_to(&self, min: Option<Self>, max: Option<Self>) -> Self {
+        let mut result = *self;
+        if let Some(min) = min {
+            result = result.max(min);
+        }
+        if let Some(max) = max {
+            result = result.min(max);
+        }
+        result
+    }
+}
+
+/// A time of day, with minute precision.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub struct TimeOfDay {
+    /// The hour, in the range `0..24`.
+    pub hour: u32,
+    /// The minute, in the range `0..60`.
+    pub minute: u32,
+}
+
+impl TimeOfDay {
+    /// Construct a new time of day. Out-of-range values are clamped.
+    pub fn new(hour: u32, minute: u32) -> Self {
+        Self {
+            hour: hour.min(23),
+            minute: minute.min(59),
+        }
+    }
+
+    /// Return the time which is `minutes` minutes after this one, wrapping around midnight.
+    pub fn add_minutes(&self, minutes: i32) -> Self {
+        let total = (self.hour * 60 + self.minute) as i32 + minutes;
+        let total = total.rem_euclid(24 * 60) as u32;
+        Self {
+            hour: total / 60,
+            minute: total % 60,
+        }
+    }
+
+    /// Clamp this time to an optional range.
+    pub fn clamp_to(&self, min: Option<Self>, max: Option<Self>) -> Self {
+        let mut result = *self;
+        if let Some(min) = min {
+            result = result.max(min);
+        }
+        if let Some(max) = max {
+            result = result.min(max);
+        }
+        result
+    }
+}
+
+/// The order in which the parts of a date are written.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum DateOrder {
+    /// Year, month, day, e.g. `2024-03-15`.
+    #[default]
+    YearMonthDay,
+    /// Month, day, year, e.g. `03/15/2024`.
+    MonthDayYear,
+    /// Day, month, year, e.g. `15.03.2024`.
+    DayMonthYear,
+}
+
+/// Resource which controls how the [`DatePicker`] and [`TimePicker`] widgets format and parse
+/// dates and times. Replace this resource to change the conventions for the whole app.
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct DateLocale {
+    /// Order of the date parts.
+    pub order: DateOrder,
+    /// Separator between the date parts.
+    pub separator: char,
+    /// Whether the week starts on Monday (otherwise Sunday).
+    pub week_starts_monday: bool,
+    /// Whether to use a 12-hour clock with AM/PM.
+    pub hour12: bool,
+    /// Names of the months, starting with January.
+    pub month_names: [&'static str; 12],
+    /// Abbreviated names of the weekdays, starting with Monday.
+    pub weekday_names: [&'static str; 7],
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+impl Default for DateLocale {
+    fn default() -> Self {
+        Self {
+            order: DateOrder::YearMonthDay,
+            separator: '-',
+            week_starts_monday: true,
+            hour12: false,
+            month_names: MONTH_NAMES,
+            weekday_names: WEEKDAY_NAMES,
+        }
+    }
+}
+
+impl DateLocale {
+    /// US conventions: `03/15/2024`, 12-hour clock, weeks start on Sunday.
+    pub fn en_us() -> Self {
+        Self {
+            order: DateOrder::MonthDayYear,
+            separator: '/',
+            week_starts_monday: false,
+            hour12: true,
+            ..default()
+        }
+    }
+
+    /// UK conventions: `15/03/2024`, 24-hour clock, weeks start on Monday.
+    pub fn en_gb() -> Self {
+        Self {
+            order: DateOrder::DayMonthYear,
+            separator: '/',
+            ..default()
+        }
+    }
+
+    /// Format a date as a string.
+    pub fn format_date(&self, date: CalendarDate) -> String {
+        let sep = self.separator;
+        match self.order {
+            DateOrder::YearMonthDay => {
+                format!("{:04}{sep}{:02}{sep}{:02}", date.year, date.month, date.day)
+            }
+            DateOrder::MonthDayYear => {
+                format!("{:02}{sep}{:02}{sep}{:04}", date.month, date.day, date.year)
+            }
+            DateOrder::DayMonthYear => {
+                format!("{:02}{sep}{:02}{sep}{:04}", date.day, date.month, date.year)
+            }
+        }
+    }
+
+    /// Parse a date which was typed by the user. Any non-digit character is accepted as a
+    /// separator, and two-digit years are interpreted as being in the 21st century.
+    pub fn parse_date(&self, text: &str) -> Option<CalendarDate> {
+        let parts = text
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<i32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let [a, b, c] = parts[..] else {
+            return None;
+        };
+        let (year, month, day) = match self.order {
+            DateOrder::YearMonthDay => (a, b, c),
+            DateOrder::MonthDayYear => (c, a, b),
+            DateOrder::DayMonthYear => (c, b, a),
+        };
+        let year = if year < 100 { year + 2000 } else { year };
+        if !(1..=12).contains(&month) {
+            return None;
+        }
+        if day < 1 || day as u32 > CalendarDate::days_in_month(year, month as u32) {
+            return None;
+        }
+        Some(CalendarDate::new(year, month as u32, day as u32))
+    }
+
+    /// Format a time of day as a string.
+    pub fn format_time(&self, time: TimeOfDay) -> String {
+        if self.hour12 {
+            let hour = match time.hour % 12 {
+                0 => 12,
+                h => h,
+            };
+            let suffix = if time.hour < 12 { "AM" } else { "PM" };
+            format!("{}:{:02} {}", hour, time.minute, suffix)
+        } else {
+            format!("{:02}:{:02}", time.hour, time.minute)
+        }
+    }
+
+    /// Parse a time which was typed by the user, such as `14:30`, `1430` or `2:30 pm`.
+    pub fn parse_time(&self, text: &str) -> Option<TimeOfDay> {
+        let text = text.trim().to_ascii_lowercase();
+        let (text, pm) = if let Some(rest) = text.strip_suffix("pm").or(text.strip_suffix('p')) {
+            (rest.trim().to_string(), Some(true))
+        } else if let Some(rest) = text.strip_suffix("am").or(text.strip_suffix('a')) {
+            (rest.trim().to_string(), Some(false))
+        } else {
+            (text.clone(), None)
+        };
+        let parts = text
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        let (hour, minute) = match parts[..] {
+            [hm] if hm.len() > 2 => {
+                let (h, m) = hm.split_at(hm.len() - 2);
+                (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?)
+            }
+            [h] => (h.parse::<u32>().ok()?, 0),
+            [h, m] => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+            _ => return None,
+        };
+        let hour = match pm {
+            Some(_) if !(1..=12).contains(&hour) => return None,
+            Some(true) => hour % 12 + 12,
+            Some(false) => hour % 12,
+            None => hour,
+        };
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+        Some(TimeOfDay::new(hour, minute))
+    }
+
+    /// Format the title of a calendar page, e.g. "March 2024".
+    pub fn format_month(&self, date: CalendarDate) -> String {
+        format!(
+            "{} {}",
+            self.month_names[date.month as usize - 1],
+            date.year
+        )
+    }
+}
+
+fn style_picker_field(ss: &mut StyleBuilder) {
+    ss.padding_left(6).padding_right(0).min_width(96);
+}
+
+fn style_picker_barrier(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .left(0)
+        .top(0)
+        .right(0)
+        .bottom(0)
+        .z_index(100);
+}
+
+fn style_picker_popup(ss: &mut StyleBuilder) {
+    ss.background_color(colors::U1)
+        .border_radius(4.0)
+        .position(ui::PositionType::Absolute)
+        .display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .border_color(Srgba::BLACK)
+        .border(1)
+        .padding(4)
+        .gap(4);
+}
+
+fn style_calendar_header(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .justify_content(ui::JustifyContent::SpaceBetween)
+        .align_items(ui::AlignItems::Center);
+}
+
+fn style_calendar_grid(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Grid)
+        .grid_template_columns(vec![ui::RepeatedGridTrack::px(7, 26.)])
+        .grid_auto_rows(vec![ui::GridTrack::px(22.)])
+        .gap(1);
+}
+
+fn style_calendar_weekday(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .justify_content(ui::JustifyContent::Center)
+        .align_items(ui::AlignItems::Center)
+        .color(colors::DIM);
+}
+
+fn style_picker_cell(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .justify_content(ui::JustifyContent::Center)
+        .align_items(ui::AlignItems::Center)
+        .border_radius(3.0);
+}
+
+fn style_time_columns(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .gap(4)
+        .height(160);
+}
+
+fn style_time_column(ss: &mut StyleBuilder) {
+    ss.width(44);
+}
+
+fn style_time_column_inner(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch);
+}
+
+fn style_time_cell(ss: &mut StyleBuilder) {
+    ss.height(20);
+}
+
+/// The clickable field which displays the current value of a picker and opens the popup.
+/// It also handles text entry; navigation keys are forwarded to the picker via `on_step`.
+#[derive(Clone, PartialEq)]
+struct PickerField {
+    id: Entity,
+    label: String,
+    size: Size,
+    disabled: bool,
+    tab_index: i32,
+    open: Mutable<bool>,
+    typed: Mutable<String>,
+    style: StyleHandle,
+    on_open: Callback,
+    on_step: Callback<KeyPressEvent>,
+    on_text: Callback<String>,
+}
+
+impl ViewTemplate for PickerField {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let disabled = self.disabled || cx.use_inherited_disabled();
+        let id = self.id;
+        let hovering = cx.is_hovered(id);
+        let focused = cx.is_focus_visible(id);
+        let size = self.size;
+        let open = self.open;
+        let typed = self.typed;
+        let on_open = self.on_open;
+        let on_step = self.on_step;
+        let on_text = self.on_text;
+        let typed_text = typed.get_clone(cx);
+
+        Element::<NodeBundle>::for_entity(id)
+            .style((
+                typography::text_default,
+                style_button,
+                style_picker_field,
+                move |ss: &mut StyleBuilder| {
+                    ss.min_height(size.height()).font_size(size.font_size());
+                },
+                self.style.clone(),
+            ))
+            .style_dyn(
+                |disabled, sb| {
+                    sb.color(if disabled {
+                        colors::FOREGROUND.with_alpha(0.2)
+                    } else {
+                        colors::FOREGROUND
+                    });
+                },
+                disabled,
+            )
+            .insert_dyn(TabIndex, self.tab_index)
+            .insert_if(disabled, || Disabled)
+            .insert_dyn(
+                move |open| {
+                    AccessibilityNode::from({
+                        let mut builder = NodeBuilder::new(Role::ComboBox);
+                        builder.set_has_popup(HasPopup::Dialog);
+                        builder.set_expanded(open);
+                        builder
+                    })
+                },
+                open.get(cx),
+            )
+            .insert_dyn(
+                move |_| {
+                    (
+                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                            let mut focus = world.get_resource_mut::<Focus>().unwrap();
+                            focus.0 = Some(id);
+                            if !world.is_disabled(id) {
+                                let mut event = world
+                                    .get_resource_mut::<ListenerInput<Pointer<Click>>>()
+                                    .unwrap();
+                                event.stop_propagation();
+                                world.run_callback(on_open, ());
+                            }
+                        }),
+                        On::<KeyCharEvent>::run(move |world: &mut World| {
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<KeyCharEvent>>()
+                                .unwrap();
+                            let key = event.key;
+                            if is_entry_char(key) {
+                                event.stop_propagation();
+                                typed.update(world, |mut text| text.push(key));
+                            }
+                        }),
+                        On::<KeyPressEvent>::run(move |world: &mut World| {
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                .unwrap();
+                            let key_event = KeyPressEvent::clone(&event);
+                            match key_event.key_code {
+                                KeyCode::ArrowLeft
+                                | KeyCode::ArrowRight
+                                | KeyCode::ArrowUp
+                                | KeyCode::ArrowDown
+                                | KeyCode::PageUp
+                                | KeyCode::PageDown => {
+                                    event.stop_propagation();
+                                    typed.set_clone(world, String::new());
+                                    world.run_callback(on_step, key_event);
+                                }
+                                KeyCode::Enter if !key_event.repeat => {
+                                    event.stop_propagation();
+                                    let text = typed.get_clone(world);
+                                    if text.is_empty() {
+                                        world.run_callback(on_open, ());
+                                    } else {
+                                        typed.set_clone(world, String::new());
+                                        world.run_callback(on_text, text);
+                                    }
+                                }
+                                KeyCode::Backspace => {
+                                    event.stop_propagation();
+                                    typed.update(world, |mut text| {
+                                        text.pop();
+                                    });
+                                }
+                                KeyCode::Escape => {
+                                    event.stop_propagation();
+                                    typed.set_clone(world, String::new());
+                                    open.set(world, false);
+                                }
+                                _ => {}
+                            }
+                        }),
+                    )
+                },
+                (),
+            )
+            .children((
+                Element::<NodeBundle>::new()
+                    .style(style_button_bg)
+                    .style_dyn(
+                        |(disabled, open, hovering, focused), sb| {
+                            sb.background_color(button_bg_color(
+                                ButtonVariant::Default,
+                                disabled,
+                                open,
+                                hovering,
+                            ))
+                            .border_radius(4.0);
+                            if focused {
+                                sb.outline_color(colors::FOCUS)
+                                    .outline_width(2)
+                                    .outline_offset(2);
+                            } else {
+                                sb.outline_color(Option::<Color>::None);
+                            }
+                        },
+                        (disabled, open.get(cx), hovering, focused),
+                    ),
+                if typed_text.is_empty() {
+                    self.label.clone()
+                } else {
+                    typed_text
+                },
+                Spacer,
+                Icon::new("embedded://bevy_quill_obsidian/assets/icons/chevron_down.png")
+                    .color(Color::from(colors::DIM))
+                    .style(|ss: &mut StyleBuilder| {
+                        ss.margin_right(4);
+                    }),
+            ))
+    }
+}
+
+/// Popup panel anchored below a picker field. Clicking outside the panel closes it.
+#[derive(Clone, PartialEq)]
+struct PickerPopup {
+    anchor: Entity,
+    open: Mutable<bool>,
+    children: ViewChild,
+}
+
+impl ViewTemplate for PickerPopup {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let open = self.open;
+        Portal::new(
+            Element::<NodeBundle>::new()
+                .style(style_picker_barrier)
+                .insert_dyn(
+                    move |_| {
+                        (
+                            On::<Pointer<Click>>::run(move |world: &mut World| {
+                                let mut event = world
+                                    .get_resource_mut::<ListenerInput<Pointer<Click>>>()
+                                    .unwrap();
+                                event.stop_propagation();
+                                open.set(world, false);
+                            }),
+                            ZIndex::Global(100),
+                        )
+                    },
+                    (),
+                )
+                .children(
+                    Element::<NodeBundle>::new()
+                        .style((typography::text_default, style_picker_popup))
+                        .insert_dyn(
+                            |anchor| Floating {
+                                anchor,
+                                position: vec![
+                                    FloatPosition {
+                                        side: FloatSide::Bottom,
+                                        align: FloatAlign::Start,
+                                        stretch: false,
+                                        gap: 2.0,
+                                    },
+                                    FloatPosition {
+                                        side: FloatSide::Top,
+                                        align: FloatAlign::Start,
+                                        stretch: false,
+                                        gap: 2.0,
+                                    },
+                                ],
+                            },
+                            self.anchor,
+                        )
+                        .insert_dyn(
+                            |_| {
+                                On::<Pointer<Click>>::run(|world: &mut World| {
+                                    let mut event = world
+                                        .get_resource_mut::<ListenerInput<Pointer<Click>>>()
+                                        .unwrap();
+                                    event.stop_propagation();
+                                })
+                            },
+                            (),
+                        )
+                        .children(self.children.clone()),
+                ),
+        )
+    }
+}
+
+/// A single selectable cell within a picker popup, such as a day or an hour.
+#[derive(Clone, PartialEq)]
+struct PickerCell<T: Copy + PartialEq + Send + Sync + 'static> {
+    value: T,
+    label: String,
+    selected: bool,
+    dimmed: bool,
+    disabled: bool,
+    on_select: Callback<T>,
+    style: StyleHandle,
+}
+
+impl<T: Copy + PartialEq + Send + Sync + 'static> ViewTemplate for PickerCell<T> {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let disabled = self.disabled || cx.use_inherited_disabled();
+        let id = cx.create_entity();
+        let hovering = cx.is_hovered(id);
+        let value = self.value;
+        let on_select = self.on_select;
+
+        Element::<NodeBundle>::for_entity(id)
+            .style((style_picker_cell, self.style.clone()))
+            .style_dyn(
+                |(selected, dimmed, disabled, hovering), sb| {
+                    sb.background_color(match (selected, hovering && !disabled) {
+                        (true, _) => colors::PRIMARY,
+                        (false, true) => colors::U3,
+                        (false, false) => colors::TRANSPARENT,
+                    })
+                    .color(if disabled {
+                        colors::FOREGROUND.with_alpha(0.2)
+                    } else if dimmed {
+                        colors::DIM
+                    } else {
+                        colors::FOREGROUND
+                    });
+                },
+                (self.selected, self.dimmed, disabled, hovering),
+            )
+            .insert_if(disabled, || Disabled)
+            .insert_dyn(
+                move |_| {
+                    On::<Pointer<Click>>::run(move |world: &mut World| {
+                        let mut event = world
+                            .get_resource_mut::<ListenerInput<Pointer<Click>>>()
+                            .unwrap();
+                        event.stop_propagation();
+                        if !world.is_disabled(id) {
+                            world.run_callback(on_select, value);
+                        }
+                    })
+                },
+                (),
+            )
+            .children(self.label.clone())
+    }
+}
+
+/// Returns true if the character may be typed into a date or time field.
+fn is_entry_char(c: char) -> bool {
+    c.is_ascii_digit()
+        || matches!(
+            c,
+            '-' | '/' | '.' | ':' | ' ' | 'a' | 'A' | 'p' | 'P' | 'm' | 'M'
+        )
+}
+
+/// Snapshot of the date picker properties, used by the event handlers.
+#[derive(Clone, PartialEq)]
+struct DatePickerProps {
+    value: CalendarDate,
+    min: Option<CalendarDate>,
+    max: Option<CalendarDate>,
+    on_change: Option<Callback<CalendarDate>>,
+}
+
+impl DatePickerProps {
+    fn commit(&self, world: &mut World, date: CalendarDate) {
+        let date = date.clamp_to(self.min, self.max);
+        if let Some(on_change) = self.on_change {
+            if date != self.value {
+                world.run_callback(on_change, date);
+            }
+        }
+    }
+}
+
+/// A field for choosing a date, which displays a calendar in a popup when clicked.
+///
+/// When focused, the date can be changed with the arrow keys (day and week), Page Up and
+/// Page Down (month), or by typing a date and pressing Enter. Dates are formatted and parsed
+/// according to the [`DateLocale`] resource.
+#[derive(Clone, PartialEq, Default)]
+pub struct DatePicker {
+    /// The currently selected date.
+    pub value: CalendarDate,
+
+    /// The earliest date which can be chosen.
+    pub min: Option<CalendarDate>,
+
+    /// The latest date which can be chosen.
+    pub max: Option<CalendarDate>,
+
+    /// Field size.
+    pub size: Size,
+
+    /// Whether the picker is disabled.
+    pub disabled: bool,
+
+    /// Additional styles to be applied to the field.
+    pub style: StyleHandle,
+
+    /// The tab index of the field.
+    pub tab_index: i32,
+
+    /// Callback called when a new date is chosen.
+    pub on_change: Option<Callback<CalendarDate>>,
+}
+
+impl DatePicker {
+    /// Create a new date picker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the currently selected date.
+    pub fn value(mut self, value: CalendarDate) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Set the earliest date which can be chosen.
+    pub fn min(mut self, min: CalendarDate) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Set the latest date which can be chosen.
+    pub fn max(mut self, max: CalendarDate) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Set the field size.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set whether the picker is disabled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Set additional styles to be applied to the field.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the tab index of the field.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
+    /// Set callback when a new date is chosen.
+    pub fn on_change(mut self, on_change: Callback<CalendarDate>) -> Self {
+        self.on_change = Some(on_change);
+        self
+    }
+}
+
+impl ViewTemplate for DatePicker {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let open = cx.create_mutable::<bool>(false);
+        let page = cx.create_mutable::<CalendarDate>(self.value.first_of_month());
+        let typed = cx.create_mutable::<String>(String::new());
+        let locale = cx.use_resource::<DateLocale>().clone();
+        let props = cx.create_capture(DatePickerProps {
+            value: self.value,
+            min: self.min,
+            max: self.max,
+            on_change: self.on_change,
+        });
+
+        let on_open = cx.create_callback(move |world: &mut World| {
+            let value = props.get_clone(world).value;
+            page.set(world, value.first_of_month());
+            open.update(world, |mut state| {
+                *state = !*state;
+            });
+        });
+        let on_step = cx.create_callback(move |event: In<KeyPressEvent>, world: &mut World| {
+            let st = props.get_clone(world);
+            let date = match event.key_code {
+                KeyCode::ArrowLeft => st.value.add_days(-1),
+                KeyCode::ArrowRight => st.value.add_days(1),
+                KeyCode::ArrowUp => st.value.add_days(-7),
+                KeyCode::ArrowDown => st.value.add_days(7),
+                KeyCode::PageUp => st.value.add_months(-1),
+                KeyCode::PageDown => st.value.add_months(1),
+                _ => return,
+            };
+            page.set(world, date.clamp_to(st.min, st.max).first_of_month());
+            st.commit(world, date);
+        });
+        let on_text = cx.create_callback(move |text: In<String>, world: &mut World| {
+            let date = world.resource::<DateLocale>().parse_date(&text);
+            if let Some(date) = date {
+                props.get_clone(world).commit(world, date);
+            }
+        });
+        let on_select = cx.create_callback(move |date: In<CalendarDate>, world: &mut World| {
+            open.set(world, false);
+            props.get_clone(world).commit(world, *date);
+        });
+        let on_prev = cx.create_callback(move |world: &mut World| {
+            page.update(world, |mut p| *p = p.add_months(-1));
+        });
+        let on_next = cx.create_callback(move |world: &mut World| {
+            page.update(world, |mut p| *p = p.add_months(1));
+        });
+
+        // Build the calendar page: six weeks, starting on the first day of the week which
+        // contains the first of the month.
+        let first = page.get(cx);
+        let week_start = if locale.week_starts_monday { 0 } else { 6 };
+        let offset = (first.weekday() + 7 - week_start) % 7;
+        let start = first.add_days(-(offset as i64));
+        let (min, max) = (self.min, self.max);
+        let cells = (0..42)
+            .map(|i| {
+                let date = start.add_days(i);
+                PickerCell {
+                    value: date,
+                    label: date.day.to_string(),
+                    selected: date == self.value,
+                    dimmed: date.month != first.month,
+                    disabled: date.clamp_to(min, max) != date,
+                    on_select,
+                    style: StyleHandle::default(),
+                }
+            })
+            .collect::<Vec<_>>();
+        let weekdays = (0..7)
+            .map(|i| locale.weekday_names[(i + week_start as usize) % 7])
+            .collect::<Vec<_>>();
+
+        Element::<NodeBundle>::new().named("DatePicker").children((
+            PickerField {
+                id,
+                label: locale.format_date(self.value),
+                size: self.size,
+                disabled: self.disabled,
+                tab_index: self.tab_index,
+                open,
+                typed,
+                style: self.style.clone(),
+                on_open,
+                on_step,
+                on_text,
+            },
+            Cond::new(
+                open.get(cx),
+                PickerPopup {
+                    anchor: id,
+                    open,
+                    children: (
+                        Element::<NodeBundle>::new()
+                            .style(style_calendar_header)
+                            .children((
+                                IconButton::new(
+                                    "embedded://bevy_quill_obsidian/assets/icons/chevron_left.png",
+                                )
+                                .size(Size::Xs)
+                                .minimal(true)
+                                .on_click(on_prev),
+                                locale.format_month(first),
+                                IconButton::new(
+                                    "embedded://bevy_quill_obsidian/assets/icons/chevron_right.png",
+                                )
+                                .size(Size::Xs)
+                                .minimal(true)
+                                .on_click(on_next),
+                            )),
+                        Element::<NodeBundle>::new()
+                            .style(style_calendar_grid)
+                            .children((
+                                For::index(&weekdays, |name, _| {
+                                    Element::<NodeBundle>::new()
+                                        .style(style_calendar_weekday)
+                                        .children(*name)
+                                }),
+                                For::index(&cells, |cell, _| cell.clone()),
+                            )),
+                    )
+                        .into_view_child(),
+                },
+                (),
+            ),
+        ))
+    }
+}
+
+/// Snapshot of the time picker properties, used by the event handlers.
+#[derive(Clone, PartialEq)]
+struct TimePickerProps {
+    value: TimeOfDay,
+    min: Option<TimeOfDay>,
+    max: Option<TimeOfDay>,
+    minute_step: u32,
+    on_change: Option<Callback<TimeOfDay>>,
+}
+
+impl TimePickerProps {
+    fn commit(&self, world: &mut World, time: TimeOfDay) {
+        let time = time.clamp_to(self.min, self.max);
+        if let Some(on_change) = self.on_change {
+            if time != self.value {
+                world.run_callback(on_change, time);
+            }
+        }
+    }
+}
+
+/// A field for choosing a time of day, which displays lists of hours and minutes in a popup
+/// when clicked.
+///
+/// When focused, the time can be changed with the arrow keys (by `minute_step` minutes, or
+/// by an hour when Shift is held), or by typing a time and pressing Enter. Times are
+/// formatted and parsed according to the [`DateLocale`] resource.
+#[derive(Clone, PartialEq)]
+pub struct TimePicker {
+    /// The currently selected time.
+    pub value: TimeOfDay,
+
+    /// The earliest time which can be chosen.
+    pub min: Option<TimeOfDay>,
+
+    /// The latest time which can be chosen.
+    pub max: Option<TimeOfDay>,
+
+    /// Interval between the minutes shown in the popup.
+    pub minute_step: u32,
+
+    /// Field size.
+    pub size: Size,
+
+    /// Whether the picker is disabled.
+    pub disabled: bool,
+
+    /// Additional styles to be applied to the field.
+    pub style: StyleHandle,
+
+    /// The tab index of the field.
+    pub tab_index: i32,
+
+    /// Callback called when a new time is chosen.
+    pub on_change: Option<Callback<TimeOfDay>>,
+}
+
+impl Default for TimePicker {
+    fn default() -> Self {
+        Self {
+            value: TimeOfDay::default(),
+            min: None,
+            max: None,
+            minute_step: 15,
+            size: Size::default(),
+            disabled: false,
+            style: StyleHandle::default(),
+            tab_index: 0,
+            on_change: None,
+        }
+    }
+}
+
+impl TimePicker {
+    /// Create a new time picker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the currently selected time.
+    pub fn value(mut self, value: TimeOfDay) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Set the earliest time which can be chosen.
+    pub fn min(mut self, min: TimeOfDay) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Set the latest time which can be chosen.
+    pub fn max(mut self, max: TimeOfDay) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Set the interval between the minutes shown in the popup.
+    pub fn minute_step(mut self, step: u32) -> Self {
+        self.minute_step = step.clamp(1, 30);
+        self
+    }
+
+    /// Set the field size.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set whether the picker is disabled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Set additional styles to be applied to the field.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the tab index of the field.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
+    /// Set callback when a new time is chosen.
+    pub fn on_change(mut self, on_change: Callback<TimeOfDay>) -> Self {
+        self.on_change = Some(on_change);
+        self
+    }
+}
+
+impl ViewTemplate for TimePicker {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let open = cx.create_mutable::<bool>(false);
+        let typed = cx.create_mutable::<String>(String::new());
+        let locale = cx.use_resource::<DateLocale>().clone();
+        let props = cx.create_capture(TimePickerProps {
+            value: self.value,
+            min: self.min,
+            max: self.max,
+            minute_step: self.minute_step,
+            on_change: self.on_change,
+        });
+
+        let on_open = cx.create_callback(move |world: &mut World| {
+            open.update(world, |mut state| {
+                *state = !*state;
+            });
+        });
+        let on_step = cx.create_callback(move |event: In<KeyPressEvent>, world: &mut World| {
+            let st = props.get_clone(world);
+            let step = if event.shift {
+                60
+            } else {
+                st.minute_step as i32
+            };
+            let time = match event.key_code {
+                KeyCode::ArrowUp => st.value.add_minutes(-step),
+                KeyCode::ArrowDown => st.value.add_minutes(step),
+                _ => return,
+            };
+            st.commit(world, time);
+        });
+        let on_text = cx.create_callback(move |text: In<String>, world: &mut World| {
+            let time = world.resource::<DateLocale>().parse_time(&text);
+            if let Some(time) = time {
+                props.get_clone(world).commit(world, time);
+            }
+        });
+        let on_select = cx.create_callback(move |time: In<TimeOfDay>, world: &mut World| {
+            props.get_clone(world).commit(world, *time);
+        });
+
+        let value = self.value;
+        let (min, max) = (self.min, self.max);
+        let in_range = |time: TimeOfDay| time.clamp_to(min, max) == time;
+        let hours = (0..24)
+            .map(|hour| {
+                let time = TimeOfDay::new(hour, value.minute);
+                PickerCell {
+                    value: time,
+                    label: if locale.hour12 {
+                        let suffix = if hour < 12 { "AM" } else { "PM" };
+                        format!("{} {}", if hour % 12 == 0 { 12 } else { hour % 12 }, suffix)
+                    } else {
+                        format!("{:02}", hour)
+                    },
+                    selected: hour == value.hour,
+                    dimmed: false,
+                    disabled: !in_range(time),
+                    on_select,
+                    style: StyleHandle::new(style_time_cell),
+                }
+            })
+            .collect::<Vec<_>>();
+        let minutes = (0..60)
+            .step_by(self.minute_step.max(1) as usize)
+            .map(|minute| {
+                let time = TimeOfDay::new(value.hour, minute);
+                PickerCell {
+                    value: time,
+                    label: format!("{:02}", minute),
+                    selected: minute == value.minute,
+                    dimmed: false,
+                    disabled: !in_range(time),
+                    on_select,
+                    style: StyleHandle::new(style_time_cell),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Element::<NodeBundle>::new().named("TimePicker").children((
+            PickerField {
+                id,
+                label: locale.format_time(self.value),
+                size: self.size,
+                disabled: self.disabled,
+                tab_index: self.tab_index,
+                open,
+                typed,
+                style: self.style.clone(),
+                on_open,
+                on_step,
+                on_text,
+            },
+            Cond::new(
+                open.get(cx),
+                PickerPopup {
+                    anchor: id,
+                    open,
+                    children: Element::<NodeBundle>::new()
+                        .style(style_time_columns)
+                        .children((
+                            ScrollView::new()
+                                .style(style_time_column)
+                                .content_style(style_time_column_inner)
+                                .scroll_enable_y(true)
+                                .children(For::index(&hours, |cell, _| cell.clone())),
+                            ScrollView::new()
+                                .style(style_time_column)
+                                .content_style(style_time_column_inner)
+                                .scroll_enable_y(true)
+                                .children(For::index(&minutes, |cell, _| cell.clone())),
+                        ))
+                        .into_view_child(),
+                },
+                (),
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> CalendarDate {
+        CalendarDate::new(year, month, day)
+    }
+
+    #[test]
+    fn test_leap_years() {
+        assert!(CalendarDate::is_leap_year(2024));
+        assert!(CalendarDate::is_leap_year(2000));
+        assert!(!CalendarDate::is_leap_year(1900));
+        assert!(!CalendarDate::is_leap_year(2023));
+        assert_eq!(CalendarDate::days_in_month(2024, 2), 29);
+        assert_eq!(CalendarDate::days_in_month(2023, 2), 28);
+        assert_eq!(CalendarDate::days_in_month(2023, 4), 30);
+        assert_eq!(CalendarDate::days_in_month(2023, 12), 31);
+    }
+
+    #[test]
+    fn test_new_clamps() {
+        assert_eq!(date(2023, 2, 30), date(2023, 2, 28));
+        assert_eq!(date(2024, 2, 30).day, 29);
+        assert_eq!(date(2024, 13, 0), date(2024, 12, 1));
+    }
+
+    #[test]
+    fn test_days_round_trip() {
+        assert_eq!(date(1970, 1, 1).to_days(), 0);
+        assert_eq!(CalendarDate::from_days(-1), date(1969, 12, 31));
+        let start = date(1899, 12, 1).to_days();
+        for days in start..start + 365 * 202 {
+            assert_eq!(CalendarDate::from_days(days).to_days(), days);
+        }
+    }
+
+    #[test]
+    fn test_add_days() {
+        assert_eq!(date(2024, 2, 28).add_days(1), date(2024, 2, 29));
+        assert_eq!(date(2024, 2, 28).add_days(2), date(2024, 3, 1));
+        assert_eq!(date(2023, 2, 28).add_days(1), date(2023, 3, 1));
+        assert_eq!(date(2023, 12, 31).add_days(1), date(2024, 1, 1));
+        assert_eq!(date(2024, 3, 1).add_days(-1), date(2024, 2, 29));
+    }
+
+    #[test]
+    fn test_add_months() {
+        // Months roll over into the next and previous years.
+        assert_eq!(date(2024, 11, 15).add_months(2), date(2025, 1, 15));
+        assert_eq!(date(2024, 1, 15).add_months(-1), date(2023, 12, 15));
+        assert_eq!(date(2024, 1, 15).add_months(-13), date(2022, 12, 15));
+
+        // Days which don't exist in the target month are clamped.
+        assert_eq!(date(2024, 1, 31).add_months(1), date(2024, 2, 29));
+        assert_eq!(date(2023, 1, 31).add_months(1), date(2023, 2, 28));
+        assert_eq!(date(2024, 5, 31).add_months(1), date(2024, 6, 30));
+    }
+
+    #[test]
+    fn test_weekday_of_first() {
+        assert_eq!(date(1970, 1, 1).weekday(), 3);
+        assert_eq!(date(2024, 1, 1).weekday(), 0);
+        assert_eq!(date(2000, 2, 1).weekday(), 1);
+        assert_eq!(date(2024, 3, 1).weekday(), 4);
+        assert_eq!(date(2023, 10, 1).weekday(), 6);
+        assert_eq!(date(2024, 3, 15).first_of_month(), date(2024, 3, 1));
+    }
+
+    #[test]
+    fn test_clamp_to() {
+        let min = Some(date(2024, 1, 1));
+        let max = Some(date(2024, 12, 31));
+        assert_eq!(date(2023, 6, 1).clamp_to(min, max), date(2024, 1, 1));
+        assert_eq!(date(2025, 6, 1).clamp_to(min, max), date(2024, 12, 31));
+        assert_eq!(date(2024, 6, 1).clamp_to(min, max), date(2024, 6, 1));
+        assert_eq!(date(2025, 6, 1).clamp_to(None, None), date(2025, 6, 1));
+    }
+
+    #[test]
+    fn test_locale_dates() {
+        let d = date(2024, 3, 5);
+        assert_eq!(DateLocale::default().format_date(d), "2024-03-05");
+        assert_eq!(DateLocale::en_us().format_date(d), "03/05/2024");
+        assert_eq!(DateLocale::en_gb().format_date(d), "05/03/2024");
+        assert_eq!(DateLocale::default().format_month(d), "March 2024");
+
+        for locale in [
+            DateLocale::default(),
+            DateLocale::en_us(),
+            DateLocale::en_gb(),
+        ] {
+            assert_eq!(locale.parse_date(&locale.format_date(d)), Some(d));
+        }
+        assert_eq!(DateLocale::en_us().parse_date("3/5/24"), Some(d));
+        assert_eq!(DateLocale::en_gb().parse_date("5.3.2024"), Some(d));
+
+        let locale = DateLocale::default();
+        assert_eq!(locale.parse_date("2024-02-29"), Some(date(2024, 2, 29)));
+        assert_eq!(locale.parse_date("2023-02-29"), None);
+        assert_eq!(locale.parse_date("2024-13-01"), None);
+        assert_eq!(locale.parse_date("2024-03"), None);
+        assert_eq!(locale.parse_date(""), None);
+    }
+
+    #[test]
+    fn test_locale_times() {
+        let us = DateLocale::en_us();
+        let default = DateLocale::default();
+        assert_eq!(us.format_time(TimeOfDay::new(0, 5)), "12:05 AM");
+        assert_eq!(us.format_time(TimeOfDay::new(13, 30)), "1:30 PM");
+        assert_eq!(default.format_time(TimeOfDay::new(13, 30)), "13:30");
+
+        assert_eq!(default.parse_time("14:30"), Some(TimeOfDay::new(14, 30)));
+        assert_eq!(default.parse_time("1430"), Some(TimeOfDay::new(14, 30)));
+        assert_eq!(default.parse_time("2:30 pm"), Some(TimeOfDay::new(14, 30)));
+        assert_eq!(default.parse_time("12am"), Some(TimeOfDay::new(0, 0)));
+        assert_eq!(default.parse_time("9"), Some(TimeOfDay::new(9, 0)));
+        assert_eq!(default.parse_time("13pm"), None);
+        assert_eq!(default.parse_time("24:00"), None);
+        assert_eq!(default.parse_time(""), None);
+    }
+}
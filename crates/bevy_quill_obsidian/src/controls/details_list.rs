@@ -0,0 +1,470 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    cursor::StyleBuilderCursor,
+    floating::{FloatAlign, FloatPosition, FloatSide, Floating},
+    hooks::UseIsHover,
+    size::Size,
+    typography,
+};
+
+use super::DisclosureToggle;
+
+fn style_details_list(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .gap(2);
+}
+
+fn style_group_header(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(4)
+        .padding((0, 2))
+        .color(colors::FOREGROUND);
+}
+
+fn style_group_items(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .gap(2);
+}
+
+fn style_row(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .min_height(20)
+        .gap(8);
+}
+
+fn style_label(ss: &mut StyleBuilder) {
+    ss.flex_shrink(0.)
+        .overflow(ui::OverflowAxis::Clip)
+        .color(colors::DIM);
+}
+
+fn style_value(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.)
+        .min_width(0)
+        .overflow(ui::OverflowAxis::Clip)
+        .padding((4, 0))
+        .border_radius(3.0)
+        .color(colors::FOREGROUND);
+}
+
+fn style_tooltip(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .max_width(400)
+        .padding((6, 3))
+        .border_radius(3.0)
+        .background_color(colors::U3)
+        .color(colors::FOREGROUND);
+}
+
+/// Copy `text` to the system clipboard.
+#[cfg(not(target_arch = "wasm32"))]
+fn copy_to_clipboard(text: &str) {
+    let result =
+        arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text.to_string()));
+    if let Err(err) = result {
+        warn!("Could not copy to clipboard: {}", err);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn copy_to_clipboard(_text: &str) {
+    warn!("Copying to the clipboard is not supported on this platform");
+}
+
+/// Shorten `text` to at most `max_chars` characters, ending with an ellipsis. Returns the text,
+/// and whether it was shortened.
+fn truncate(text: &str, max_chars: usize) -> (String, bool) {
+    match text.char_indices().nth(max_chars) {
+        Some(_) if max_chars > 0 => {
+            let end = text
+                .char_indices()
+                .nth(max_chars - 1)
+                .map_or(text.len(), |(index, _)| index);
+            (format!("{}\u{2026}", &text[..end]), true)
+        }
+        _ => (text.to_string(), false),
+    }
+}
+
+/// A single row of a [`DetailsList`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct DetailsItem {
+    /// The name of the value.
+    pub label: String,
+
+    /// The value, formatted as text.
+    pub value: String,
+
+    /// If true, clicking the value copies it to the clipboard.
+    pub copyable: bool,
+}
+
+impl DetailsItem {
+    /// Create a new item with the given label and value.
+    pub fn new(label: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            value: value.into(),
+            copyable: true,
+        }
+    }
+
+    /// Set whether clicking the value copies it to the clipboard.
+    pub fn copyable(mut self, copyable: bool) -> Self {
+        self.copyable = copyable;
+        self
+    }
+}
+
+/// A titled group of rows within a [`DetailsList`], which can be collapsed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DetailsGroup {
+    /// The title of the group. Groups with an empty title have no header, and can't be
+    /// collapsed.
+    pub title: String,
+
+    /// The rows in this group.
+    pub items: Vec<DetailsItem>,
+
+    /// Whether the group is initially collapsed.
+    pub collapsed: bool,
+}
+
+impl DetailsGroup {
+    /// Create a new, empty group with the given title.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            items: Vec::new(),
+            collapsed: false,
+        }
+    }
+
+    /// Add a row to the group.
+    pub fn item(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.items.push(DetailsItem::new(label, value));
+        self
+    }
+
+    /// Add a row to the group, with options.
+    pub fn with_item(mut self, item: DetailsItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Set whether the group is initially collapsed.
+    pub fn collapsed(mut self, collapsed: bool) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+}
+
+/// Displays a list of label/value pairs, such as the properties of an entity or the version
+/// information in an about dialog. Labels are aligned in a column, long values are truncated
+/// and shown in full when hovered, and clicking a value copies it to the clipboard.
+///
+/// ```ignore
+/// DetailsList::new()
+///     .item("Name", name)
+///     .group(
+///         DetailsGroup::new("Transform")
+///             .item("Translation", format!("{:?}", transform.translation))
+///             .item("Scale", format!("{:?}", transform.scale)),
+///     )
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct DetailsList {
+    /// The groups of rows to display.
+    pub groups: Vec<DetailsGroup>,
+
+    /// Width of the label column, in pixels.
+    pub label_width: f32,
+
+    /// Values longer than this many characters are truncated.
+    pub max_value_chars: usize,
+
+    /// Callback called with the text of a value when it is copied.
+    pub on_copy: Option<Callback<String>>,
+
+    /// Additional styles to be applied to the list.
+    pub style: StyleHandle,
+}
+
+impl Default for DetailsList {
+    fn default() -> Self {
+        Self {
+            groups: Vec::new(),
+            label_width: 120.,
+            max_value_chars: 40,
+            on_copy: None,
+            style: StyleHandle::default(),
+        }
+    }
+}
+
+impl DetailsList {
+    /// Create a new, empty details list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a row which isn't part of a titled group. Consecutive rows are displayed together.
+    pub fn item(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        match self.groups.last_mut() {
+            Some(group) if group.title.is_empty() => {
+                group.items.push(DetailsItem::new(label, value));
+            }
+            _ => self
+                .groups
+                .push(DetailsGroup::new(String::new()).item(label, value)),
+        }
+        self
+    }
+
+    /// Add a group of rows.
+    pub fn group(mut self, group: DetailsGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    /// Set the width of the label column.
+    pub fn label_width(mut self, width: f32) -> Self {
+        self.label_width = width;
+        self
+    }
+
+    /// Set the number of characters after which values are truncated.
+    pub fn max_value_chars(mut self, max_chars: usize) -> Self {
+        self.max_value_chars = max_chars;
+        self
+    }
+
+    /// Set the callback called when a value is copied.
+    pub fn on_copy(mut self, callback: Callback<String>) -> Self {
+        self.on_copy = Some(callback);
+        self
+    }
+
+    /// Set additional styles to be applied to the list.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+impl ViewTemplate for DetailsList {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let label_width = self.label_width;
+        let max_value_chars = self.max_value_chars;
+        let on_copy = self.on_copy;
+        Element::<NodeBundle>::new()
+            .named("DetailsList")
+            .style((
+                typography::text_default,
+                style_details_list,
+                self.style.clone(),
+            ))
+            .children(For::index(&self.groups, move |group, _| DetailsGroupView {
+                group: group.clone(),
+                label_width,
+                max_value_chars,
+                on_copy,
+            }))
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct DetailsGroupView {
+    group: DetailsGroup,
+    label_width: f32,
+    max_value_chars: usize,
+    on_copy: Option<Callback<String>>,
+}
+
+impl ViewTemplate for DetailsGroupView {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let expanded = cx.create_mutable(!self.group.collapsed);
+        let has_header = !self.group.title.is_empty();
+        let is_expanded = expanded.get(cx) || !has_header;
+        let label_width = self.label_width;
+        let max_value_chars = self.max_value_chars;
+        let on_copy = self.on_copy;
+
+        (
+            Cond::new(
+                has_header,
+                Element::<NodeBundle>::new()
+                    .style(style_group_header)
+                    .children((
+                        DisclosureToggle::new()
+                            .size(Size::Xs)
+                            .expanded(is_expanded)
+                            .on_change(cx.create_callback(
+                                move |value: In<bool>, world: &mut World| {
+                                    expanded.set(world, *value);
+                                },
+                            )),
+                        self.group.title.clone(),
+                    )),
+                (),
+            ),
+            Cond::new(
+                is_expanded,
+                Element::<NodeBundle>::new()
+                    .style(style_group_items)
+                    .style_dyn(
+                        |has_header, sb| {
+                            if has_header {
+                                sb.padding_left(12);
+                            }
+                        },
+                        has_header,
+                    )
+                    .children(For::index(&self.group.items, move |item, _| DetailsRow {
+                        item: item.clone(),
+                        label_width,
+                        max_value_chars,
+                        on_copy,
+                    })),
+                (),
+            ),
+        )
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct DetailsRow {
+    item: DetailsItem,
+    label_width: f32,
+    max_value_chars: usize,
+    on_copy: Option<Callback<String>>,
+}
+
+impl ViewTemplate for DetailsRow {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let hovering = cx.is_hovered(id);
+        let copied = cx.create_mutable(false);
+        let (text, truncated) = truncate(&self.item.value, self.max_value_chars);
+        let copyable = self.item.copyable;
+        let value = self.item.value.clone();
+        let on_copy = self.on_copy;
+
+        // Show the full value when it's been cut short, and confirm when it's been copied.
+        let tooltip = if copied.get(cx) {
+            Some("Copied".to_string())
+        } else if truncated {
+            Some(self.item.value.clone())
+        } else {
+            None
+        };
+
+        Element::<NodeBundle>::new()
+            .named("DetailsRow")
+            .style(style_row)
+            .children((
+                Element::<NodeBundle>::new()
+                    .style(style_label)
+                    .style_dyn(
+                        |width, sb| {
+                            sb.width(width);
+                        },
+                        self.label_width,
+                    )
+                    .children(self.item.label.clone()),
+                Element::<NodeBundle>::for_entity(id)
+                    .style(style_value)
+                    .style_dyn(
+                        |(copyable, hovering), sb| {
+                            if copyable {
+                                sb.cursor(CursorIcon::Pointer);
+                            }
+                            if copyable && hovering {
+                                sb.background_color(colors::U3);
+                            } else {
+                                sb.background_color(Option::<Color>::None);
+                            }
+                        },
+                        (copyable, hovering),
+                    )
+                    .insert_dyn(
+                        move |(copyable, value)| {
+                            On::<Pointer<Click>>::run(move |world: &mut World| {
+                                if !copyable {
+                                    return;
+                                }
+                                copy_to_clipboard(&value);
+                                copied.set(world, true);
+                                if let Some(on_copy) = on_copy {
+                                    world.run_callback(on_copy, value.clone());
+                                }
+                            })
+                        },
+                        (copyable, value),
+                    )
+                    .effect(
+                        move |cx, _, hovering| {
+                            if !hovering {
+                                copied.set(cx, false);
+                            }
+                        },
+                        hovering,
+                    )
+                    .children(text),
+                Cond::new(
+                    hovering && tooltip.is_some(),
+                    Portal::new(
+                        Element::<NodeBundle>::new()
+                            .style((typography::text_default, style_tooltip))
+                            .insert_dyn(
+                                |anchor| {
+                                    (
+                                        Floating {
+                                            anchor,
+                                            position: vec![
+                                                FloatPosition {
+                                                    side: FloatSide::Bottom,
+                                                    align: FloatAlign::Start,
+                                                    stretch: false,
+                                                    gap: 2.0,
+                                                },
+                                                FloatPosition {
+                                                    side: FloatSide::Top,
+                                                    align: FloatAlign::Start,
+                                                    stretch: false,
+                                                    gap: 2.0,
+                                                },
+                                            ],
+                                        },
+                                        Pickable::IGNORE,
+                                        ZIndex::Global(100),
+                                    )
+                                },
+                                id,
+                            )
+                            .children(tooltip.unwrap_or_default()),
+                    ),
+                    (),
+                ),
+            ))
+    }
+}
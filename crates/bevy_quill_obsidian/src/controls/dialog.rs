@@ -12,7 +12,8 @@ use bevy_mod_stylebuilder::*;
 use bevy_quill_core::*;
 
 use crate::{
-    animation::{AnimatedBackgroundColor, AnimatedScale, AnimatedTransition},
+    animation::{AnimatedBackgroundColor, AnimatedScale, AnimatedTransition, AnimationPreset},
+    audio::{InteractionSound, PlayInteractionSound},
     colors,
     focus::{KeyPressEvent, TabGroup},
     hooks::{BistableTransitionState, CreateBistableTransition},
@@ -54,10 +55,10 @@ fn style_dialog(ss: &mut StyleBuilder) {
     // .selector(".entering > &,.entered > &", |ss| ss.scale(1.));
 }
 
-const TRANSITION_DURATION: f32 = 0.3;
-
 /// Displays a modal dialog box. This will display the dialog frame and the backdrop overlay.
-/// Use the dialog header/body/footer controls to get the standard layout.
+/// Use the dialog header/body/footer controls to get the standard layout. Alternatively,
+/// set the `"header"`, `"body"` and `"footer"` slots via `.children_slot()`, and they
+/// will be wrapped in the standard controls automatically.
 #[derive(Default, Clone, PartialEq)]
 pub struct Dialog {
     /// The width of the dialog, one of several standard widths.
@@ -70,6 +71,9 @@ pub struct Dialog {
     /// The content of the dialog.
     pub children: ViewChild,
 
+    /// Named content slots: `"header"`, `"body"` and `"footer"`.
+    pub slots: ViewSlots,
+
     /// Callback called when the dialog's close button is clicked.
     pub on_close: Option<Callback>,
 
@@ -114,21 +118,30 @@ impl Dialog {
     }
 }
 
+impl WithSlots for Dialog {
+    fn slots_mut(&mut self) -> &mut ViewSlots {
+        &mut self.slots
+    }
+}
+
 impl ViewTemplate for Dialog {
     type View = impl View;
     fn create(&self, cx: &mut Cx) -> Self::View {
         let on_close = self.on_close;
         let on_exited = self.on_exited;
-        let state = cx.create_bistable_transition(self.open, TRANSITION_DURATION);
+        let state = cx.create_bistable_transition(self.open, AnimationPreset::DIALOG.duration);
         let children = self.children.clone();
+        let slots = self.slots.clone();
         let width = self.width;
         let save_focus = cx.create_mutable::<Option<Entity>>(None);
+        let owner = cx.owner();
 
         cx.create_effect(
-            |world, (open, save_focus)| {
+            move |world, (open, save_focus)| {
                 if open {
                     let focus = world.get_resource::<Focus>().unwrap();
                     save_focus.set(world, focus.0);
+                    world.play_interaction_sound(owner, InteractionSound::Notification);
                 }
             },
             (self.open, save_focus),
@@ -195,10 +208,10 @@ impl ViewTemplate for Dialog {
                                 | BistableTransitionState::Exiting
                                 | BistableTransitionState::Exited => colors::U1.with_alpha(0.0),
                             };
-                            AnimatedTransition::<AnimatedBackgroundColor>::start(
+                            AnimatedTransition::<AnimatedBackgroundColor>::start_preset(
                                 &mut entt,
                                 target,
-                                TRANSITION_DURATION,
+                                AnimationPreset::DIALOG,
                             );
                         },
                         state,
@@ -239,15 +252,32 @@ impl ViewTemplate for Dialog {
                                         | BistableTransitionState::Entered
                                         | BistableTransitionState::ExitStart => Vec3::splat(1.0),
                                     };
-                                    AnimatedTransition::<AnimatedScale>::start(
+                                    AnimatedTransition::<AnimatedScale>::start_preset(
                                         &mut entt,
                                         target,
-                                        TRANSITION_DURATION,
+                                        AnimationPreset::DIALOG,
                                     );
                                 },
                                 state,
                             )
-                            .children(children.clone()),
+                            .children((
+                                children.clone(),
+                                Cond::new(
+                                    slots.has("header"),
+                                    DialogHeader::new().children(slots.get("header")),
+                                    (),
+                                ),
+                                Cond::new(
+                                    slots.has("body"),
+                                    DialogBody::new().children(slots.get("body")),
+                                    (),
+                                ),
+                                Cond::new(
+                                    slots.has("footer"),
+                                    DialogFooter::new().children(slots.get("footer")),
+                                    (),
+                                ),
+                            )),
                     ),
             ),
             (),
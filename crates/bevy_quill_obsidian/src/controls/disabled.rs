@@ -1,5 +1,5 @@
-use bevy::prelude::{Component, Entity, World};
-use bevy_quill_core::Cx;
+use bevy::prelude::{Component, Entity, Parent, World};
+use bevy_quill_core::{Cx, IntoViewChild, View, ViewChild, ViewTemplate};
 
 /// A marker component to indicate that a widget is disabled.
 #[derive(Component, Debug, Clone, Copy)]
@@ -22,3 +22,80 @@ impl IsDisabled for World {
         self.get::<Disabled>(entity).is_some()
     }
 }
+
+/// Context component, inserted by [`DisabledGroup`], which disables the controls within it.
+#[derive(Component, Debug, Clone, Copy)]
+struct DisabledContext(bool);
+
+/// Trait which defines a method to check whether a widget is within a disabled
+/// [`DisabledGroup`].
+pub trait UseInheritedDisabled {
+    /// Returns true if any enclosing [`DisabledGroup`] is disabled. The current scope is
+    /// re-run when this changes.
+    fn use_inherited_disabled(&self) -> bool;
+}
+
+impl<'p, 'w> UseInheritedDisabled for Cx<'p, 'w> {
+    fn use_inherited_disabled(&self) -> bool {
+        // Unlike `use_inherited_component`, this keeps searching past an enabled group, so
+        // that a nested group can't re-enable controls that an outer group has disabled.
+        let mut entity = self.owner();
+        loop {
+            if let Some(DisabledContext(true)) = self.use_component::<DisabledContext>(entity) {
+                return true;
+            }
+            match self.world().get::<Parent>(entity) {
+                Some(parent) => entity = parent.get(),
+                None => return false,
+            }
+        }
+    }
+}
+
+/// A view which disables all of the controls within it, without needing to pass a `disabled`
+/// flag to each one. This is useful for forms or panels which are read-only while some
+/// condition holds:
+///
+/// ```ignore
+/// DisabledGroup::new()
+///     .disabled(!editable)
+///     .children((
+///         Checkbox::new().label("Visible"),
+///         Button::new().children("Apply"),
+///     ))
+/// ```
+#[derive(Default, Clone, PartialEq)]
+pub struct DisabledGroup {
+    /// Whether the controls within the group are disabled.
+    pub disabled: bool,
+
+    /// The content of the group.
+    pub children: ViewChild,
+}
+
+impl DisabledGroup {
+    /// Create a new disabled group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the controls within the group are disabled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Set the content of the group.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+impl ViewTemplate for DisabledGroup {
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        cx.insert(DisabledContext(self.disabled));
+        self.children.clone()
+    }
+}
@@ -1,6 +1,7 @@
-use super::Icon;
+use super::{Icon, UseInheritedDisabled};
 use crate::{
-    animation::{AnimatedRotation, AnimatedTransition},
+    animation::{AnimatedRotation, AnimatedTransition, AnimationPreset},
+    audio::{InteractionSound, PlayInteractionSound},
     colors,
     cursor::StyleBuilderCursor,
     focus::{KeyPressEvent, TabIndex},
@@ -107,6 +108,7 @@ impl DisclosureToggle {
 impl ViewTemplate for DisclosureToggle {
     type View = impl View;
     fn create(&self, cx: &mut Cx) -> Self::View {
+        let disabled = self.disabled || cx.use_inherited_disabled();
         let id = cx.create_entity();
         let hovering = cx.is_hovered(id);
         let focused = cx.is_focus_visible(id);
@@ -126,10 +128,13 @@ impl ViewTemplate for DisclosureToggle {
                             let mut focus = world.get_resource_mut::<Focus>().unwrap();
                             focus.0 = Some(id);
                             if !disabled {
+                                world.play_interaction_sound(id, InteractionSound::Click);
                                 let next_checked = expanded;
                                 if let Some(on_click) = on_change {
                                     world.run_callback(on_click, !next_checked);
                                 }
+                            } else {
+                                world.play_interaction_sound(id, InteractionSound::Error);
                             }
                         }),
                         On::<KeyPressEvent>::run({
@@ -143,6 +148,7 @@ impl ViewTemplate for DisclosureToggle {
                                             || event.key_code == KeyCode::Space)
                                     {
                                         event.stop_propagation();
+                                        world.play_interaction_sound(id, InteractionSound::Click);
                                         let next_checked = expanded;
                                         if let Some(on_click) = on_change {
                                             world.run_callback(on_click, !next_checked);
@@ -153,7 +159,7 @@ impl ViewTemplate for DisclosureToggle {
                         }),
                     )
                 },
-                (self.disabled, self.expanded),
+                (disabled, self.expanded),
             )
             .effect(
                 move |cx, en, checked| {
@@ -164,7 +170,11 @@ impl ViewTemplate for DisclosureToggle {
                         0.
                     };
                     let target = Quat::from_rotation_z(angle);
-                    AnimatedTransition::<AnimatedRotation>::start(&mut entt, target, 0.3);
+                    AnimatedTransition::<AnimatedRotation>::start_preset(
+                        &mut entt,
+                        target,
+                        AnimationPreset::DISCLOSURE,
+                    );
                 },
                 self.expanded,
             )
@@ -186,7 +196,7 @@ impl ViewTemplate for DisclosureToggle {
             .children(
                 Icon::new("embedded://bevy_quill_obsidian/assets/icons/chevron_right.png")
                     .color({
-                        match (self.disabled, hovering) {
+                        match (disabled, hovering) {
                             (true, _) => Color::from(colors::DIM).with_alpha(0.2),
                             (false, true) => Color::from(colors::FOREGROUND),
                             (false, false) => Color::from(colors::DIM),
@@ -0,0 +1,244 @@
+use bevy::{a11y::Focus, prelude::*, ui};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    focus::{KeyCharEvent, KeyPressEvent, TabIndex},
+    hooks::UseIsFocus,
+    typography,
+};
+
+use super::{Disabled, IsDisabled, UseInheritedDisabled};
+
+/// Maximum interval between two clicks for them to count as a double-click, in seconds.
+const DOUBLE_CLICK_TIME: f32 = 0.4;
+
+fn style_editable_label(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .min_height(20)
+        .padding((2, 0))
+        .border_radius(3.0)
+        .color(colors::FOREGROUND);
+}
+
+fn style_caret(ss: &mut StyleBuilder) {
+    ss.width(1).height(14).background_color(colors::FOREGROUND);
+}
+
+/// The current value of an [`EditableLabel`], stored on the label entity so that event handlers
+/// always see the latest value.
+#[derive(Component)]
+struct EditableLabelValue(String);
+
+/// A label which displays a text value, and which can be edited in place by double-clicking
+/// it, or by pressing F2 while it has focus. Pressing Enter or moving focus elsewhere commits
+/// the edit, and Escape cancels it.
+#[derive(Default, Clone, PartialEq)]
+pub struct EditableLabel {
+    /// The text value.
+    pub value: String,
+
+    /// Additional styles to be applied to the label.
+    pub style: StyleHandle,
+
+    /// Callback called with the new value when an edit is committed. Not called if the value
+    /// is unchanged.
+    pub on_change: Option<Callback<String>>,
+
+    /// The tab index of the label (default 0).
+    pub tab_index: i32,
+}
+
+impl EditableLabel {
+    /// Create a new editable label.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text value.
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = value.into();
+        self
+    }
+
+    /// Set additional styles to be applied to the label.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the callback called when an edit is committed.
+    pub fn on_change(mut self, on_change: Callback<String>) -> Self {
+        self.on_change = Some(on_change);
+        self
+    }
+
+    /// Set the tab index of the label.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+}
+
+/// Start editing, with the current value as the initial text.
+fn begin_edit(world: &mut World, id: Entity, editing: Mutable<Option<String>>) {
+    if world.is_disabled(id) {
+        return;
+    }
+    let value = world
+        .get::<EditableLabelValue>(id)
+        .map(|value| value.0.clone())
+        .unwrap_or_default();
+    editing.set_clone(world, Some(value));
+}
+
+/// Finish editing, and report the new value if it has changed.
+fn commit_edit(
+    world: &mut World,
+    id: Entity,
+    editing: Mutable<Option<String>>,
+    on_change: Option<Callback<String>>,
+) {
+    let Some(text) = editing.get_clone(world) else {
+        return;
+    };
+    editing.set_clone(world, None);
+    let changed = world
+        .get::<EditableLabelValue>(id)
+        .is_none_or(|value| value.0 != text);
+    if let (true, Some(on_change)) = (changed, on_change) {
+        world.run_callback(on_change, text);
+    }
+}
+
+impl ViewTemplate for EditableLabel {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let editing = cx.create_mutable::<Option<String>>(None);
+        let last_click = cx.create_mutable::<f32>(f32::NEG_INFINITY);
+        let focused = cx.is_focused(id);
+        let focus_visible = cx.is_focus_visible(id);
+        let on_change = self.on_change;
+        let disabled = cx.use_inherited_disabled();
+
+        // Commit the edit when focus moves elsewhere.
+        cx.create_effect(
+            move |world, focused| {
+                if !focused {
+                    commit_edit(world, id, editing, on_change);
+                }
+            },
+            focused,
+        );
+
+        let text = editing.get_clone(cx);
+        let is_editing = text.is_some();
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("EditableLabel")
+            .style((
+                typography::text_default,
+                style_editable_label,
+                self.style.clone(),
+            ))
+            .style_dyn(
+                |(is_editing, focus_visible), sb| {
+                    sb.background_color(if is_editing {
+                        colors::U1
+                    } else {
+                        colors::TRANSPARENT
+                    });
+                    if is_editing || focus_visible {
+                        sb.outline_color(colors::FOCUS).outline_width(2);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                (is_editing, focus_visible),
+            )
+            .insert_dyn(TabIndex, self.tab_index)
+            .insert_dyn(EditableLabelValue, self.value.clone())
+            .insert_if(disabled, || Disabled)
+            .insert_dyn(
+                move |_| {
+                    (
+                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                            let mut focus = world.get_resource_mut::<Focus>().unwrap();
+                            focus.0 = Some(id);
+                            if editing.get_clone(world).is_some() {
+                                return;
+                            }
+                            let now = world.resource::<Time>().elapsed_seconds();
+                            if now - last_click.get(world) < DOUBLE_CLICK_TIME {
+                                last_click.set(world, f32::NEG_INFINITY);
+                                begin_edit(world, id, editing);
+                            } else {
+                                last_click.set(world, now);
+                            }
+                        }),
+                        On::<KeyCharEvent>::run(move |world: &mut World| {
+                            let is_editing = editing.get_clone(world).is_some();
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<KeyCharEvent>>()
+                                .unwrap();
+                            let key = event.key;
+                            if is_editing && !key.is_control() {
+                                event.stop_propagation();
+                                editing.update(world, |mut text| {
+                                    if let Some(text) = text.as_mut() {
+                                        text.push(key);
+                                    }
+                                });
+                            }
+                        }),
+                        On::<KeyPressEvent>::run(move |world: &mut World| {
+                            let is_editing = editing.get_clone(world).is_some();
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                .unwrap();
+                            let key_code = event.key_code;
+                            if !is_editing {
+                                if key_code == KeyCode::F2 {
+                                    event.stop_propagation();
+                                    begin_edit(world, id, editing);
+                                }
+                                return;
+                            }
+                            match key_code {
+                                KeyCode::Enter => {
+                                    event.stop_propagation();
+                                    commit_edit(world, id, editing, on_change);
+                                }
+                                KeyCode::Escape => {
+                                    event.stop_propagation();
+                                    editing.set_clone(world, None);
+                                }
+                                KeyCode::Backspace => {
+                                    event.stop_propagation();
+                                    editing.update(world, |mut text| {
+                                        if let Some(text) = text.as_mut() {
+                                            text.pop();
+                                        }
+                                    });
+                                }
+                                _ => {}
+                            }
+                        }),
+                    )
+                },
+                (),
+            )
+            .children(match text {
+                Some(text) => {
+                    (text, Element::<NodeBundle>::new().style(style_caret)).into_view_child()
+                }
+                None => self.value.clone().into_view_child(),
+            })
+    }
+}
@@ -0,0 +1,183 @@
+use std::ops::Range;
+
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::colors;
+
+fn style_highlighted_text(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .flex_wrap(ui::FlexWrap::Wrap)
+        .align_items(ui::AlignItems::Baseline);
+}
+
+fn style_match(ss: &mut StyleBuilder) {
+    ss.color(colors::LIGHT)
+        .background_color(colors::TEXT_SELECT);
+}
+
+/// Return the byte ranges of `text` which match `query`, ignoring case. Matches don't
+/// overlap. An empty query matches nothing.
+pub fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    let mut result = Vec::new();
+    if query.is_empty() {
+        return result;
+    }
+    let mut search_from = 0;
+    for (start, _) in text.char_indices() {
+        if start < search_from {
+            continue;
+        }
+        // Compare one character at a time, since changing the case of a character can change
+        // its length in bytes.
+        let mut query_chars = query.chars().flat_map(char::to_lowercase);
+        let mut end = start;
+        let mut matched = false;
+        for (offset, ch) in text[start..].char_indices() {
+            let mut lower = ch.to_lowercase();
+            if !lower.all(|c| query_chars.next() == Some(c)) {
+                break;
+            }
+            end = start + offset + ch.len_utf8();
+            if query_chars.clone().next().is_none() {
+                matched = true;
+                break;
+            }
+        }
+        if matched {
+            result.push(start..end);
+            search_from = end;
+        }
+    }
+    result
+}
+
+/// Displays a string with the parts that match a search query emphasized, such as the results
+/// of a search or filter.
+#[derive(Clone, PartialEq, Default)]
+pub struct HighlightedText {
+    /// The text to display.
+    pub text: String,
+
+    /// The search query. Every occurrence of the query within the text is emphasized,
+    /// ignoring case.
+    pub query: String,
+
+    /// Byte ranges of the text to emphasize, instead of the matches of `query`. This is
+    /// used when the matches are computed some other way, such as by a fuzzy matcher.
+    pub ranges: Option<Vec<Range<usize>>>,
+
+    /// Style of the emphasized parts of the text.
+    pub highlight_style: StyleHandle,
+
+    /// Additional styles to be applied to the text.
+    pub style: StyleHandle,
+}
+
+impl HighlightedText {
+    /// Create a new highlighted text with the given text.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..default()
+        }
+    }
+
+    /// Set the search query to highlight.
+    pub fn query(mut self, query: impl Into<String>) -> Self {
+        self.query = query.into();
+        self
+    }
+
+    /// Set the byte ranges of the text to highlight, instead of searching for the query.
+    pub fn ranges(mut self, ranges: Vec<Range<usize>>) -> Self {
+        self.ranges = Some(ranges);
+        self
+    }
+
+    /// Set the style of the emphasized parts of the text.
+    pub fn highlight_style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.highlight_style = style.into_handle();
+        self
+    }
+
+    /// Set additional styles to be applied to the text.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Split the text into runs, each of which is either emphasized or not.
+    fn segments(&self) -> Vec<(String, bool)> {
+        let ranges = match self.ranges {
+            Some(ref ranges) => ranges.clone(),
+            None => find_matches(&self.text, &self.query),
+        };
+        let mut segments = Vec::with_capacity(ranges.len() * 2 + 1);
+        let mut pos = 0;
+        for range in ranges {
+            // Ignore ranges which are out of order or don't fall on character boundaries.
+            if range.start < pos
+                || range.end > self.text.len()
+                || !self.text.is_char_boundary(range.start)
+                || !self.text.is_char_boundary(range.end)
+            {
+                continue;
+            }
+            if range.start > pos {
+                segments.push((self.text[pos..range.start].to_string(), false));
+            }
+            if range.end > range.start {
+                segments.push((self.text[range.clone()].to_string(), true));
+            }
+            pos = range.end;
+        }
+        if pos < self.text.len() || segments.is_empty() {
+            segments.push((self.text[pos..].to_string(), false));
+        }
+        segments
+    }
+}
+
+impl ViewTemplate for HighlightedText {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let highlight_style = self.highlight_style.clone();
+        Element::<NodeBundle>::new()
+            .named("HighlightedText")
+            .style((style_highlighted_text, self.style.clone()))
+            .children(For::index(
+                &self.segments(),
+                move |(text, emphasized), _| TextSegment {
+                    text: text.clone(),
+                    emphasized: *emphasized,
+                    highlight_style: highlight_style.clone(),
+                },
+            ))
+    }
+}
+
+/// One run of text within a [`HighlightedText`], either emphasized or plain.
+#[derive(Clone, PartialEq)]
+struct TextSegment {
+    text: String,
+    emphasized: bool,
+    highlight_style: StyleHandle,
+}
+
+impl ViewTemplate for TextSegment {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Cond::new(
+            self.emphasized,
+            Element::<NodeBundle>::new()
+                .style((style_match, self.highlight_style.clone()))
+                .children(self.text.clone()),
+            self.text.clone(),
+        )
+    }
+}
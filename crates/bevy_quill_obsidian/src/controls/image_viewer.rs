@@ -0,0 +1,421 @@
+use bevy::{prelude::*, ui, utils::HashSet};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors, hooks::UseElementRect, materials::ImageViewMaterial, scrolling::ScrollWheel,
+    size::Size, typography, RoundedCorners,
+};
+
+use super::{Button, ButtonVariant, Spacer};
+
+/// Zoom level at which the pixel grid becomes visible.
+const PIXEL_GRID_ZOOM: f32 = 8.;
+
+/// Minimum and maximum zoom levels.
+const MIN_ZOOM: f32 = 1. / 32.;
+const MAX_ZOOM: f32 = 64.;
+
+fn style_image_viewer(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .gap(4);
+}
+
+fn style_toolbar(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(4)
+        .color(colors::FOREGROUND);
+}
+
+fn style_channels(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .gap(1);
+}
+
+fn style_viewport(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Relative)
+        .flex_grow(1.)
+        .min_height(64)
+        .overflow(ui::OverflowAxis::Clip)
+        .background_color(colors::U1)
+        .border_radius(4.);
+}
+
+fn style_image(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute);
+}
+
+/// Which color channels of the image are displayed.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ImageChannel {
+    /// Display the full color image, with transparency shown as a checkerboard.
+    #[default]
+    All,
+    /// Display only the red channel, as grayscale.
+    Red,
+    /// Display only the green channel, as grayscale.
+    Green,
+    /// Display only the blue channel, as grayscale.
+    Blue,
+    /// Display only the alpha channel, as grayscale.
+    Alpha,
+}
+
+impl ImageChannel {
+    /// Channel mask passed to the shader.
+    fn mask(&self) -> Vec4 {
+        match self {
+            ImageChannel::All => Vec4::ONE,
+            ImageChannel::Red => Vec4::X,
+            ImageChannel::Green => Vec4::Y,
+            ImageChannel::Blue => Vec4::Z,
+            ImageChannel::Alpha => Vec4::W,
+        }
+    }
+
+    /// Short label displayed on the channel button.
+    fn label(&self) -> &'static str {
+        match self {
+            ImageChannel::All => "RGB",
+            ImageChannel::Red => "R",
+            ImageChannel::Green => "G",
+            ImageChannel::Blue => "B",
+            ImageChannel::Alpha => "A",
+        }
+    }
+}
+
+/// Widget which displays an image which can be panned by dragging and zoomed with the mouse
+/// wheel. At high zoom levels, a grid is drawn between pixels. Individual color channels can
+/// be displayed in isolation, which is useful when inspecting generated textures.
+#[derive(Clone, PartialEq, Default)]
+pub struct ImageViewer {
+    /// The image to display.
+    pub image: Handle<Image>,
+
+    /// Whether to display the toolbar with zoom and channel controls.
+    pub show_toolbar: bool,
+
+    /// Additional styles to be applied to the widget.
+    pub style: StyleHandle,
+}
+
+impl ImageViewer {
+    /// Create a new image viewer for the given image.
+    pub fn new(image: Handle<Image>) -> Self {
+        Self {
+            image,
+            show_toolbar: true,
+            style: StyleHandle::default(),
+        }
+    }
+
+    /// Set whether to display the toolbar.
+    pub fn show_toolbar(mut self, show: bool) -> Self {
+        self.show_toolbar = show;
+        self
+    }
+
+    /// Set additional styles to be applied to the widget.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+/// Current view transform of an [`ImageViewer`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct ImageViewTransform {
+    /// Scale factor, where 1.0 displays one image pixel per logical pixel.
+    zoom: f32,
+    /// Offset of the image center from the viewport center, in logical pixels.
+    pan: Vec2,
+    /// If true, the zoom is recomputed to fit the image whenever the viewport changes size.
+    fit: bool,
+}
+
+impl Default for ImageViewTransform {
+    fn default() -> Self {
+        Self {
+            zoom: 1.,
+            pan: Vec2::ZERO,
+            fit: true,
+        }
+    }
+}
+
+/// Compute the zoom level which fits the image within the viewport.
+fn fit_zoom(image_size: Vec2, viewport_size: Vec2) -> f32 {
+    if image_size.x <= 0. || image_size.y <= 0. {
+        return 1.;
+    }
+    (viewport_size / image_size)
+        .min_element()
+        .clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// The image displayed by an [`ImageViewer`], and its size once it has loaded. This is kept on
+/// the viewport entity, so that the viewer only reacts to changes to its own image.
+#[derive(Component, Clone)]
+pub(crate) struct ViewedImage {
+    image: Handle<Image>,
+    size: Vec2,
+}
+
+/// Update the size of each viewed image when its handle changes or the image is modified.
+pub(crate) fn update_viewed_images(
+    mut events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    mut viewers: Query<&mut ViewedImage>,
+) {
+    let modified: HashSet<AssetId<Image>> = events
+        .read()
+        .map(|event| match event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::Removed { id }
+            | AssetEvent::Unused { id }
+            | AssetEvent::LoadedWithDependencies { id } => *id,
+        })
+        .collect();
+    for mut viewed in viewers.iter_mut() {
+        if !viewed.is_changed() && !modified.contains(&viewed.image.id()) {
+            continue;
+        }
+        let size = images
+            .get(&viewed.image)
+            .map(|image| image.size_f32())
+            .unwrap_or_default();
+        if viewed.size != size {
+            viewed.size = size;
+        }
+    }
+}
+
+impl ViewTemplate for ImageViewer {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id_viewport = cx.create_entity();
+        let transform = cx.create_mutable(ImageViewTransform::default());
+        let channel = cx.create_mutable(ImageChannel::All);
+        let viewport_size = cx.use_element_rect(id_viewport).size();
+        // Only react to changes to the viewed image, not to every image asset.
+        let image_size = cx
+            .use_component::<ViewedImage>(id_viewport)
+            .map(|viewed| viewed.size)
+            .unwrap_or_default();
+
+        let xform = transform.get(cx);
+        let zoom = if xform.fit {
+            fit_zoom(image_size, viewport_size)
+        } else {
+            xform.zoom
+        };
+        let pan = if xform.fit { Vec2::ZERO } else { xform.pan };
+        let display_size = image_size * zoom;
+        let position = (viewport_size - display_size) * 0.5 + pan;
+        let current_channel = channel.get(cx);
+
+        // Wrap material creation in a memo, we only want to create the material once.
+        let image = self.image.clone();
+        let material = cx.create_memo(
+            move |world, _| {
+                let mut ui_materials = world
+                    .get_resource_mut::<Assets<ImageViewMaterial>>()
+                    .unwrap();
+                ui_materials.add(ImageViewMaterial {
+                    channels: Vec4::ONE,
+                    options: Vec4::ZERO,
+                    image: image.clone(),
+                })
+            },
+            (),
+        );
+
+        // Update material parameters
+        cx.create_effect(
+            |world, (material, image, channel, zoom)| {
+                let mut ui_materials = world
+                    .get_resource_mut::<Assets<ImageViewMaterial>>()
+                    .unwrap();
+                let material = ui_materials.get_mut(material.id()).unwrap();
+                material.image = image;
+                material.channels = channel.mask();
+                material.options = Vec4::new(
+                    if channel == ImageChannel::All { 0. } else { 1. },
+                    if zoom >= PIXEL_GRID_ZOOM { 0.5 } else { 0. },
+                    0.,
+                    0.,
+                );
+            },
+            (material.clone(), self.image.clone(), current_channel, zoom),
+        );
+
+        let on_fit = cx.create_callback(move |world: &mut World| {
+            transform.set(world, ImageViewTransform::default());
+        });
+        let on_actual_size = cx.create_callback(move |world: &mut World| {
+            transform.set(
+                world,
+                ImageViewTransform {
+                    zoom: 1.,
+                    pan: Vec2::ZERO,
+                    fit: false,
+                },
+            );
+        });
+
+        Element::<NodeBundle>::new()
+            .named("ImageViewer")
+            .style((
+                typography::text_default,
+                style_image_viewer,
+                self.style.clone(),
+            ))
+            .children((
+                Cond::new(
+                    self.show_toolbar,
+                    Element::<NodeBundle>::new()
+                        .named("ImageViewer::Toolbar")
+                        .style(style_toolbar)
+                        .children((
+                            Button::new()
+                                .size(Size::Xs)
+                                .selected(xform.fit)
+                                .on_click(on_fit)
+                                .children("Fit"),
+                            Button::new()
+                                .size(Size::Xs)
+                                .selected(!xform.fit && xform.zoom == 1.)
+                                .on_click(on_actual_size)
+                                .children("100%"),
+                            format!("{:.0}%", zoom * 100.),
+                            Spacer,
+                            ChannelButtons { channel },
+                        )),
+                    (),
+                ),
+                Element::<NodeBundle>::for_entity(id_viewport)
+                    .named("ImageViewer::Viewport")
+                    .style(style_viewport)
+                    .insert_dyn(
+                        |image| ViewedImage {
+                            image,
+                            size: Vec2::ZERO,
+                        },
+                        self.image.clone(),
+                    )
+                    .insert_dyn(
+                        move |_| {
+                            (
+                                On::<Pointer<Drag>>::run(move |world: &mut World| {
+                                    let event = world
+                                        .get_resource::<ListenerInput<Pointer<Drag>>>()
+                                        .unwrap();
+                                    let delta = event.delta;
+                                    transform.update(world, |mut xf| {
+                                        xf.pan += delta;
+                                        xf.fit = false;
+                                    });
+                                }),
+                                On::<ScrollWheel>::run(move |world: &mut World| {
+                                    let mut event = world
+                                        .get_resource_mut::<ListenerInput<ScrollWheel>>()
+                                        .unwrap();
+                                    event.stop_propagation();
+                                    let factor = (-event.delta.y * 0.01).exp2();
+                                    transform.update(world, |mut xf| {
+                                        let new_zoom = (xf.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+                                        // Zoom about the center of the viewport.
+                                        let scale = new_zoom / xf.zoom;
+                                        xf.pan *= scale;
+                                        xf.zoom = new_zoom;
+                                        xf.fit = false;
+                                    });
+                                }),
+                            )
+                        },
+                        (),
+                    )
+                    // When leaving "fit" mode, start from the zoom level that was displayed.
+                    .effect(
+                        move |cx, _, zoom| {
+                            let world = cx.world_mut();
+                            let xf = transform.get(world);
+                            if xf.fit && xf.zoom != zoom {
+                                transform.set(world, ImageViewTransform { zoom, ..xf });
+                            }
+                        },
+                        zoom,
+                    )
+                    .children(
+                        Element::<MaterialNodeBundle<ImageViewMaterial>>::new()
+                            .named("ImageViewer::Image")
+                            .style(style_image)
+                            .style_dyn(
+                                |(position, size), sb| {
+                                    sb.left(position.x)
+                                        .top(position.y)
+                                        .width(size.x)
+                                        .height(size.y);
+                                },
+                                (position, display_size),
+                            )
+                            .insert_dyn(|m| m, material.clone()),
+                    ),
+            ))
+    }
+}
+
+/// Row of buttons for selecting which channel to display.
+#[derive(Clone, PartialEq)]
+struct ChannelButtons {
+    channel: Mutable<ImageChannel>,
+}
+
+impl ViewTemplate for ChannelButtons {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let channel = self.channel;
+        let current = channel.get(cx);
+        let channels = [
+            ImageChannel::All,
+            ImageChannel::Red,
+            ImageChannel::Green,
+            ImageChannel::Blue,
+            ImageChannel::Alpha,
+        ];
+        let callbacks = channels.map(|ch| {
+            cx.create_callback(move |world: &mut World| {
+                channel.set(world, ch);
+            })
+        });
+
+        Element::<NodeBundle>::new()
+            .style(style_channels)
+            .children(For::index(&channels, move |ch, index| {
+                Button::new()
+                    .size(Size::Xs)
+                    .variant(if *ch == current {
+                        ButtonVariant::Selected
+                    } else {
+                        ButtonVariant::Default
+                    })
+                    .corners(match index {
+                        0 => RoundedCorners::Left,
+                        4 => RoundedCorners::Right,
+                        _ => RoundedCorners::None,
+                    })
+                    .on_click(callbacks[index])
+                    .children(ch.label())
+            }))
+    }
+}
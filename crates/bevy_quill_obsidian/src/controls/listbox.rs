@@ -0,0 +1,520 @@
+use bevy::{
+    a11y::{
+        accesskit::{NodeBuilder, Role},
+        AccessibilityNode, Focus,
+    },
+    prelude::*,
+    ui,
+};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    focus::{KeyPressEvent, TabIndex},
+    hooks::{UseIsFocus, UseIsHover},
+    typography,
+};
+
+use super::{listview::row_bg_color, ScrollView};
+
+fn style_listbox(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .background_color(colors::U1)
+        .border_radius(5.0)
+        .padding(3);
+}
+
+fn style_listbox_scroll(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.);
+}
+
+fn style_listbox_inner(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .align_self(ui::AlignSelf::Stretch)
+        .justify_self(ui::JustifySelf::Stretch);
+}
+
+fn style_listbox_row(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Relative).padding((6, 3));
+}
+
+fn style_drop_indicator(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .left(0)
+        .right(0)
+        .height(2)
+        .background_color(colors::FOCUS)
+        .pointer_events(false);
+}
+
+/// Determines how many items can be selected at once in a [`ListBox`].
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum ListBoxSelection {
+    /// Exactly one item can be selected.
+    #[default]
+    Single,
+
+    /// Any number of items can be selected, using Shift to select ranges and Ctrl to
+    /// toggle individual items.
+    Multiple,
+}
+
+/// An item in a [`ListBox`].
+#[derive(Clone, PartialEq)]
+pub struct ListBoxItem<K: PartialEq + Clone> {
+    /// Unique key for this item.
+    pub key: K,
+
+    /// The content of the row.
+    pub children: ViewChild,
+}
+
+impl<K: PartialEq + Clone> ListBoxItem<K> {
+    /// Create a new list box item.
+    pub fn new(key: K, children: impl IntoViewChild) -> Self {
+        Self {
+            key,
+            children: children.into_view_child(),
+        }
+    }
+}
+
+/// Describes an item being moved to a new position within a [`ListBox`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ListBoxReorder {
+    /// The original index of the item that was moved.
+    pub from: usize,
+
+    /// The index that the item should occupy once it has been removed from its original
+    /// position and re-inserted, i.e. `items.insert(to, items.remove(from))`.
+    pub to: usize,
+}
+
+/// A scrollable list of selectable items. Supports single or multiple selection,
+/// keyboard navigation, and re-ordering items by dragging.
+#[derive(Clone, PartialEq)]
+pub struct ListBox<K: PartialEq + Clone> {
+    /// The items in the list.
+    pub items: Vec<ListBoxItem<K>>,
+
+    /// Keys of the currently selected items.
+    pub selection: Vec<K>,
+
+    /// Whether one or many items can be selected.
+    pub selection_mode: ListBoxSelection,
+
+    /// Additional styles to be applied to the list box.
+    pub style: StyleHandle,
+
+    /// The tab index of the list box.
+    pub tab_index: i32,
+
+    /// Callback called when the selection changes.
+    pub on_change: Option<Callback<Vec<K>>>,
+
+    /// Callback called when an item is dragged to a new position. If this is not set,
+    /// items cannot be dragged.
+    pub on_reorder: Option<Callback<ListBoxReorder>>,
+}
+
+impl<K: PartialEq + Clone> Default for ListBox<K> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            selection: Vec::new(),
+            selection_mode: ListBoxSelection::Single,
+            style: StyleHandle::default(),
+            tab_index: 0,
+            on_change: None,
+            on_reorder: None,
+        }
+    }
+}
+
+impl<K: PartialEq + Clone> ListBox<K> {
+    /// Create a new list box.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the items in the list.
+    pub fn items(mut self, items: impl IntoIterator<Item = ListBoxItem<K>>) -> Self {
+        self.items = items.into_iter().collect();
+        self
+    }
+
+    /// Set the keys of the currently selected items.
+    pub fn selection(mut self, selection: impl IntoIterator<Item = K>) -> Self {
+        self.selection = selection.into_iter().collect();
+        self
+    }
+
+    /// Set whether one or many items can be selected.
+    pub fn selection_mode(mut self, mode: ListBoxSelection) -> Self {
+        self.selection_mode = mode;
+        self
+    }
+
+    /// Set additional styles to be applied to the list box.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the tab index of the list box.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
+    /// Set callback when the selection changes.
+    pub fn on_change(mut self, callback: Callback<Vec<K>>) -> Self {
+        self.on_change = Some(callback);
+        self
+    }
+
+    /// Set callback when an item is dragged to a new position.
+    pub fn on_reorder(mut self, callback: Callback<ListBoxReorder>) -> Self {
+        self.on_reorder = Some(callback);
+        self
+    }
+}
+
+/// Snapshot of the list box properties, captured so that event handlers can see the
+/// current values without being re-created on every change.
+#[derive(Clone, PartialEq)]
+struct ListBoxState<K: PartialEq + Clone> {
+    keys: Vec<K>,
+    selection: Vec<K>,
+    mode: ListBoxSelection,
+    on_change: Option<Callback<Vec<K>>>,
+    on_reorder: Option<Callback<ListBoxReorder>>,
+}
+
+impl<K: PartialEq + Clone> ListBoxState<K> {
+    /// Compute the new selection after an item is chosen. `extend` selects the range from
+    /// the anchor, `toggle` adds or removes the item from the existing selection.
+    fn select(&self, anchor: Option<usize>, index: usize, extend: bool, toggle: bool) -> Vec<K> {
+        let key = &self.keys[index];
+        if self.mode == ListBoxSelection::Single {
+            return vec![key.clone()];
+        }
+        match (extend, anchor) {
+            (true, Some(anchor)) => {
+                let range = anchor.min(index)..=anchor.max(index);
+                let mut selection = if toggle {
+                    self.selection.clone()
+                } else {
+                    Vec::new()
+                };
+                for key in self.keys[range].iter() {
+                    if !selection.contains(key) {
+                        selection.push(key.clone());
+                    }
+                }
+                selection
+            }
+            _ if toggle => {
+                let mut selection = self.selection.clone();
+                if let Some(pos) = selection.iter().position(|k| k == key) {
+                    selection.remove(pos);
+                } else {
+                    selection.push(key.clone());
+                }
+                selection
+            }
+            _ => vec![key.clone()],
+        }
+    }
+}
+
+/// State of an in-progress drag operation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct ListBoxDrag {
+    /// Index of the row being dragged.
+    source: usize,
+
+    /// Position at which the dragged row would be inserted, in the range `0..=len`.
+    target: Option<usize>,
+}
+
+/// Update the selection and notify the owner of the list box.
+fn change_selection<K: PartialEq + Clone + Send + Sync + 'static>(
+    world: &mut World,
+    state: &ListBoxState<K>,
+    anchor: Mutable<Option<usize>>,
+    cursor: Mutable<Option<usize>>,
+    index: usize,
+    extend: bool,
+    toggle: bool,
+) {
+    let selection = state.select(anchor.get(world), index, extend, toggle);
+    if !extend || anchor.get(world).is_none() {
+        anchor.set(world, Some(index));
+    }
+    cursor.set(world, Some(index));
+    if let Some(on_change) = state.on_change {
+        world.run_callback(on_change, selection);
+    }
+}
+
+/// Returns the state of the (shift, ctrl) modifier keys.
+fn modifier_keys(world: &World) -> (bool, bool) {
+    let keys = world.resource::<ButtonInput<KeyCode>>();
+    (
+        keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+        keys.any_pressed([
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]),
+    )
+}
+
+impl<K: PartialEq + Clone + Send + Sync + 'static> ViewTemplate for ListBox<K> {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let focused = cx.is_focus_visible(id);
+        let anchor = cx.create_mutable::<Option<usize>>(None);
+        let cursor = cx.create_mutable::<Option<usize>>(None);
+        let drag = cx.create_mutable::<Option<ListBoxDrag>>(None);
+        let state = cx.create_capture(ListBoxState {
+            keys: self.items.iter().map(|item| item.key.clone()).collect(),
+            selection: self.selection.clone(),
+            mode: self.selection_mode,
+            on_change: self.on_change,
+            on_reorder: self.on_reorder,
+        });
+
+        let cursor_index = cursor.get(cx);
+        let drop_target = drag.get(cx).and_then(|d| d.target);
+        let len = self.items.len();
+        let rows = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| ListBoxRow {
+                index,
+                children: item.children.clone(),
+                selected: self.selection.contains(&item.key),
+                cursor: focused && cursor_index == Some(index),
+                drop_before: drop_target == Some(index),
+                drop_after: index + 1 == len && drop_target == Some(len),
+                state,
+                anchor,
+                cursor_state: cursor,
+                drag,
+                list: id,
+            })
+            .collect::<Vec<_>>();
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("ListBox")
+            .style((style_listbox, self.style.clone()))
+            .insert_dyn(TabIndex, self.tab_index)
+            .insert_dyn(
+                move |_| {
+                    (
+                        AccessibilityNode::from(NodeBuilder::new(Role::ListBox)),
+                        On::<KeyPressEvent>::run(move |world: &mut World| {
+                            let event = world.resource::<ListenerInput<KeyPressEvent>>();
+                            let key_code = event.key_code;
+                            let shift = event.shift;
+                            let st = state.get_clone(world);
+                            let len = st.keys.len();
+                            if len == 0 {
+                                return;
+                            }
+                            let multiple = st.mode == ListBoxSelection::Multiple;
+                            let current = cursor.get(world);
+                            let next = match key_code {
+                                KeyCode::ArrowUp => {
+                                    current.map_or(len - 1, |i| i.saturating_sub(1))
+                                }
+                                KeyCode::ArrowDown => current.map_or(0, |i| (i + 1).min(len - 1)),
+                                KeyCode::Home => 0,
+                                KeyCode::End => len - 1,
+                                KeyCode::Space if multiple => {
+                                    world
+                                        .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                        .unwrap()
+                                        .stop_propagation();
+                                    if let Some(index) = current {
+                                        change_selection(
+                                            world, &st, anchor, cursor, index, false, true,
+                                        );
+                                    }
+                                    return;
+                                }
+                                KeyCode::KeyA if multiple && modifier_keys(world).1 => {
+                                    world
+                                        .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                        .unwrap()
+                                        .stop_propagation();
+                                    if let Some(on_change) = st.on_change {
+                                        world.run_callback(on_change, st.keys.clone());
+                                    }
+                                    return;
+                                }
+                                _ => return,
+                            };
+                            world
+                                .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                .unwrap()
+                                .stop_propagation();
+                            change_selection(world, &st, anchor, cursor, next, shift, false);
+                        }),
+                    )
+                },
+                (),
+            )
+            .children(
+                ScrollView::new()
+                    .style(style_listbox_scroll)
+                    .content_style(style_listbox_inner)
+                    .scroll_enable_y(true)
+                    .children(For::index(&rows, |row, _| row.clone()).named("ListBox::Row")),
+            )
+    }
+}
+
+/// A single row within a [`ListBox`].
+#[derive(Clone, PartialEq)]
+struct ListBoxRow<K: PartialEq + Clone> {
+    index: usize,
+    children: ViewChild,
+    selected: bool,
+    cursor: bool,
+    drop_before: bool,
+    drop_after: bool,
+    state: Mutable<ListBoxState<K>>,
+    anchor: Mutable<Option<usize>>,
+    cursor_state: Mutable<Option<usize>>,
+    drag: Mutable<Option<ListBoxDrag>>,
+    list: Entity,
+}
+
+impl<K: PartialEq + Clone + Send + Sync + 'static> ViewTemplate for ListBoxRow<K> {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let hovering = cx.is_hovered(id);
+        let list = self.list;
+        let state = self.state;
+        let anchor = self.anchor;
+        let cursor = self.cursor_state;
+        let drag = self.drag;
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("ListBox::Row")
+            .style((typography::text_default, style_listbox_row))
+            .style_dyn(
+                |(hovering, selected, cursor), sb| {
+                    sb.background_color(row_bg_color(false, selected, hovering));
+                    if cursor {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_width(1)
+                            .outline_offset(-1);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                (hovering, self.selected, self.cursor),
+            )
+            .insert_dyn(
+                move |index| {
+                    (
+                        AccessibilityNode::from(NodeBuilder::new(Role::ListItem)),
+                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                            let mut focus = world.get_resource_mut::<Focus>().unwrap();
+                            focus.0 = Some(list);
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<Pointer<Click>>>()
+                                .unwrap();
+                            event.stop_propagation();
+                            let (shift, ctrl) = modifier_keys(world);
+                            let st = state.get_clone(world);
+                            change_selection(world, &st, anchor, cursor, index, shift, ctrl);
+                        }),
+                        On::<Pointer<DragStart>>::run(move |world: &mut World| {
+                            if state.get_clone(world).on_reorder.is_some() {
+                                drag.set(
+                                    world,
+                                    Some(ListBoxDrag {
+                                        source: index,
+                                        target: None,
+                                    }),
+                                );
+                            }
+                        }),
+                        On::<Pointer<DragEnter>>::run(move |world: &mut World| {
+                            if let Some(mut d) = drag.get(world) {
+                                // Insert above rows that are above the dragged row, and below
+                                // rows that are below it.
+                                d.target = match index {
+                                    i if i < d.source => Some(i),
+                                    i if i > d.source => Some(i + 1),
+                                    _ => None,
+                                };
+                                drag.set(world, Some(d));
+                            }
+                        }),
+                        On::<Pointer<DragEnd>>::run(move |world: &mut World| {
+                            let Some(d) = drag.get(world) else {
+                                return;
+                            };
+                            drag.set(world, None);
+                            let Some(target) = d.target else {
+                                return;
+                            };
+                            if target == d.source || target == d.source + 1 {
+                                return;
+                            }
+                            let to = if target > d.source {
+                                target - 1
+                            } else {
+                                target
+                            };
+                            if let Some(on_reorder) = state.get_clone(world).on_reorder {
+                                world.run_callback(
+                                    on_reorder,
+                                    ListBoxReorder { from: d.source, to },
+                                );
+                            }
+                        }),
+                    )
+                },
+                self.index,
+            )
+            .children((
+                Cond::new(
+                    self.drop_before,
+                    Element::<NodeBundle>::new()
+                        .named("ListBox::DropIndicator")
+                        .style((style_drop_indicator, |ss: &mut StyleBuilder| {
+                            ss.top(-1);
+                        })),
+                    (),
+                ),
+                self.children.clone(),
+                Cond::new(
+                    self.drop_after,
+                    Element::<NodeBundle>::new()
+                        .named("ListBox::DropIndicator")
+                        .style((style_drop_indicator, |ss: &mut StyleBuilder| {
+                            ss.bottom(-1);
+                        })),
+                    (),
+                ),
+            ))
+    }
+}
@@ -1,4 +1,6 @@
 use crate::{
+    animation::AnimationPreset,
+    audio::{InteractionSound, PlayInteractionSound},
     colors,
     floating::{FloatAlign, FloatPosition, FloatSide, Floating},
     focus::{AutoFocus, KeyPressEvent, NavAction, TabGroup, TabIndex, TabNavigation},
@@ -21,6 +23,7 @@ use bevy_quill_core::*;
 
 use super::{
     button_bg_color, style_button, style_button_bg, ButtonVariant, Disabled, Icon, Spacer,
+    UseInheritedDisabled,
 };
 
 /// View context component which stores the anchor element id for a menu.
@@ -175,9 +178,10 @@ impl ViewTemplate for MenuButton {
     type View = impl View;
 
     fn create(&self, cx: &mut Cx) -> Self::View {
+        let disabled = self.disabled || cx.use_inherited_disabled();
         let id_anchor = self.anchor.unwrap_or_else(|| cx.create_entity());
         let open = cx.create_mutable::<bool>(false);
-        let state = cx.create_bistable_transition(open.get(cx), 0.01);
+        let state = cx.create_bistable_transition(open.get(cx), AnimationPreset::MENU.duration);
         let hovering = cx.is_hovered(id_anchor);
         let focused = cx.is_focus_visible(id_anchor);
 
@@ -237,9 +241,12 @@ impl ViewTemplate for MenuButton {
                                     .get_resource_mut::<ListenerInput<Pointer<Click>>>()
                                     .unwrap();
                                 event.stop_propagation();
+                                world.play_interaction_sound(id_anchor, InteractionSound::Click);
                                 open.update(world, |mut state| {
                                     *state = !*state;
                                 });
+                            } else {
+                                world.play_interaction_sound(id_anchor, InteractionSound::Error);
                             }
                         }),
                         On::<KeyPressEvent>::run({
@@ -254,6 +261,10 @@ impl ViewTemplate for MenuButton {
                                             || event.key_code == KeyCode::Space)
                                     {
                                         event.stop_propagation();
+                                        world.play_interaction_sound(
+                                            id_anchor,
+                                            InteractionSound::Click,
+                                        );
                                         open.update(world, |mut state| {
                                             *state = !*state;
                                         });
@@ -277,7 +288,7 @@ impl ViewTemplate for MenuButton {
                 open.get(cx),
             )
             .insert_if(self.autofocus, || AutoFocus)
-            .insert_if(self.disabled, || Disabled)
+            .insert_if(disabled, || Disabled)
             .children((
                 Element::<NodeBundle>::new()
                     .named("MenuButton::Background")
@@ -288,7 +299,7 @@ impl ViewTemplate for MenuButton {
                             let color = button_bg_color(variant, disabled, open, hovering);
                             sb.background_color(color);
                         },
-                        (self.variant, self.disabled, open.get(cx), hovering),
+                        (self.variant, disabled, open.get(cx), hovering),
                     )
                     .style_dyn(
                         move |is_focused, sb| {
@@ -430,7 +441,7 @@ impl ViewTemplate for MenuPopup {
     type View = impl View;
     fn create(&self, cx: &mut Cx) -> Self::View {
         // Adds a delay to ensure the menu items are created before setting focus.
-        let state = cx.create_bistable_transition(true, 0.01);
+        let state = cx.create_bistable_transition(true, AnimationPreset::MENU.duration);
         let context = cx.use_inherited_component::<MenuAnchor>().unwrap();
         let owner_id = cx.owner();
 
@@ -594,6 +605,7 @@ impl ViewTemplate for MenuItem {
     type View = impl View;
 
     fn create(&self, cx: &mut Cx) -> Self::View {
+        let disabled = self.disabled || cx.use_inherited_disabled();
         let id = cx.create_entity();
         let owner_id = cx.owner();
         let pressed = cx.create_mutable::<bool>(false);
@@ -603,7 +615,7 @@ impl ViewTemplate for MenuItem {
 
         Element::<NodeBundle>::for_entity(id)
             .named("MenuItem")
-            .insert_if(self.disabled, || Disabled)
+            .insert_if(disabled, || Disabled)
             .style((style_menu_item, self.style.clone()))
             .insert_dyn(
                 move |_| {
@@ -619,8 +631,11 @@ impl ViewTemplate for MenuItem {
                                 focus.0 = Some(id);
                                 if let Some(on_click) = on_click {
                                     writer.send(MenuCloseEvent { target: owner_id });
+                                    world.play_interaction_sound(id, InteractionSound::Click);
                                     world.run_callback(on_click, ());
                                 }
+                            } else {
+                                world.play_interaction_sound(id, InteractionSound::Error);
                             }
                         }),
                         On::<Pointer<DragStart>>::run(move |world: &mut World| {
@@ -668,6 +683,7 @@ impl ViewTemplate for MenuItem {
                                     event.stop_propagation();
                                     if let Some(on_click) = on_click {
                                         writer.send(MenuCloseEvent { target: owner_id });
+                                        world.play_interaction_sound(id, InteractionSound::Click);
                                         world.run_callback(on_click, ());
                                     }
                                 }
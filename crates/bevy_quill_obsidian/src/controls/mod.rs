@@ -1,41 +1,74 @@
+mod app_shell;
+mod avatar;
+mod badge;
 mod button;
 mod checkbox;
 mod color_edit;
+mod confirm;
+mod date_picker;
+mod details_list;
 mod dialog;
 mod disabled;
 mod disclosure_toggle;
+mod editable_label;
 mod flex;
 mod gradient_slider;
+mod highlighted_text;
 mod icon;
 mod icon_button;
+mod image_viewer;
+mod listbox;
 mod listview;
 mod menu;
+mod pill;
 mod scrollview;
+mod shortcut_overlay;
+mod skeleton;
 mod slider;
 mod spacer;
 mod spinbox;
 mod splitter;
 mod swatch;
 mod swatch_grid;
+mod table;
 mod tool_palette;
+mod tool_window;
 
+pub use app_shell::*;
+pub use avatar::*;
+pub use badge::*;
 pub use button::*;
 pub use checkbox::*;
 pub use color_edit::{ColorEdit, ColorEditState, ColorMode, RecentColors};
+pub use confirm::{ConfirmOptions, DialogHost, Dialogs, PendingConfirm};
+pub use date_picker::*;
+pub use details_list::{DetailsGroup, DetailsItem, DetailsList};
 pub use dialog::*;
 pub use disabled::*;
 pub use disclosure_toggle::*;
+pub use editable_label::EditableLabel;
 pub use flex::*;
 pub use gradient_slider::*;
+pub use highlighted_text::{find_matches, HighlightedText};
 pub use icon::*;
 pub use icon_button::*;
+pub(crate) use image_viewer::update_viewed_images;
+pub use image_viewer::{ImageChannel, ImageViewer};
+pub use listbox::{ListBox, ListBoxItem, ListBoxReorder, ListBoxSelection};
 pub use listview::{ListRow, ListView};
 pub use menu::*;
+pub use pill::*;
 pub use scrollview::*;
+pub use shortcut_overlay::ShortcutOverlay;
+pub(crate) use skeleton::animate_skeletons;
+pub use skeleton::{Skeleton, SkeletonShape, Suspense};
 pub use slider::*;
 pub use spacer::*;
 pub use spinbox::*;
 pub use splitter::*;
-pub use swatch::Swatch;
-pub use swatch_grid::SwatchGrid;
+pub use swatch::{Swatch, SwatchColor};
+pub use swatch_grid::{ColorPalette, SwatchGrid};
+pub use table::{Table, TableColumn, TableData, TableSort};
 pub use tool_palette::*;
+pub(crate) use tool_window::clamp_tool_windows;
+pub use tool_window::{ToolWindow, ToolWindowState, ToolWindows};
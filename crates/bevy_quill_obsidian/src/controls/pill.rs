@@ -0,0 +1,123 @@
+use bevy::{color::Srgba, prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{colors, size::Size, typography};
+
+fn style_pill(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .justify_content(ui::JustifyContent::Center)
+        .align_items(ui::AlignItems::Center)
+        .gap(4)
+        .color(colors::FOREGROUND);
+}
+
+/// The variant determines the pill's color scheme.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum PillVariant {
+    /// A neutral status.
+    #[default]
+    Default,
+
+    /// A prominent, informational status.
+    Primary,
+
+    /// A status indicating success or completion.
+    Success,
+
+    /// A status indicating a warning.
+    Warning,
+
+    /// A status indicating an error.
+    Danger,
+}
+
+impl PillVariant {
+    /// Return the background color for this variant.
+    pub fn color(&self) -> Srgba {
+        match self {
+            PillVariant::Default => colors::U3,
+            PillVariant::Primary => colors::PRIMARY,
+            PillVariant::Success => colors::RESOURCE,
+            PillVariant::Warning => colors::CODE,
+            PillVariant::Danger => colors::DESTRUCTIVE,
+        }
+    }
+}
+
+/// A rounded label used to display a short status, tag or category.
+#[derive(Default, Clone, PartialEq)]
+pub struct Pill {
+    /// Color variant.
+    pub variant: PillVariant,
+
+    /// Pill size.
+    pub size: Size,
+
+    /// The content to display inside the pill.
+    pub children: ViewChild,
+
+    /// Additional styles to be applied to the pill.
+    pub style: StyleHandle,
+}
+
+impl Pill {
+    /// Create a new pill.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the pill color variant.
+    pub fn variant(mut self, variant: PillVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Set the pill size.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the child views for this element.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+
+    /// Set additional styles to be applied to the pill.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+impl ViewTemplate for Pill {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let size = self.size;
+
+        Element::<NodeBundle>::new()
+            .named("Pill")
+            .style((
+                typography::text_default,
+                style_pill,
+                move |ss: &mut StyleBuilder| {
+                    ss.min_height(size.height() * 0.8)
+                        .padding((size.font_size() * 0.6, 0))
+                        .border_radius(size.height() * 0.4)
+                        .font_size(size.font_size() * 0.9);
+                },
+                self.style.clone(),
+            ))
+            .style_dyn(
+                |variant, sb| {
+                    sb.background_color(variant.color());
+                },
+                self.variant,
+            )
+            .children(self.children.clone())
+    }
+}
@@ -5,7 +5,10 @@ use bevy_quill_core::*;
 
 use crate::{
     colors,
-    scrolling::{ScrollArea, ScrollBar, ScrollBarThumb, ScrollContent, ScrollWheel},
+    scrolling::{
+        ScrollArea, ScrollBar, ScrollBarThumb, ScrollCallbacks, ScrollContent, ScrollIntoView,
+        ScrollWheel,
+    },
 };
 
 // Style definitions for scrollview widget.
@@ -97,6 +100,10 @@ pub struct ScrollView {
     /// Optional entity id to use for the scrolling element. This is useful for querying the
     /// current scroll position.
     pub entity: Option<Entity>,
+    /// How focused elements are scrolled into view.
+    pub scroll_into_view: ScrollIntoView,
+    /// Callbacks for infinite scrolling and pull-to-refresh.
+    pub callbacks: ScrollCallbacks,
 }
 
 impl ScrollView {
@@ -141,6 +148,40 @@ impl ScrollView {
         self.entity = entity;
         self
     }
+
+    /// Set the minimum distance between an element which is scrolled into view and the edges
+    /// of the scroll area.
+    pub fn reveal_margin(mut self, margin: f32) -> Self {
+        self.scroll_into_view.margin = margin;
+        self
+    }
+
+    /// Whether to animate scrolling when an element is scrolled into view.
+    pub fn smooth_scroll(mut self, smooth: bool) -> Self {
+        self.scroll_into_view.smooth = smooth;
+        self
+    }
+
+    /// Set the callback which is called when the view is scrolled near the end of the content,
+    /// for loading more content on demand.
+    pub fn on_reach_end(mut self, callback: Callback) -> Self {
+        self.callbacks.on_reach_end = Some(callback);
+        self
+    }
+
+    /// Set how close to the end of the content the view must be scrolled before calling
+    /// `on_reach_end`.
+    pub fn reach_end_threshold(mut self, threshold: f32) -> Self {
+        self.callbacks.reach_end_threshold = threshold;
+        self
+    }
+
+    /// Enable pull-to-refresh: the callback is called when the user scrolls up past the top
+    /// of the content.
+    pub fn on_refresh(mut self, callback: Callback) -> Self {
+        self.callbacks.on_refresh = Some(callback);
+        self
+    }
 }
 
 impl ViewTemplate for ScrollView {
@@ -183,6 +224,10 @@ impl ViewTemplate for ScrollView {
                                 On::<ScrollWheel>::listener_component_mut::<ScrollArea>(
                                     move |ev, scrolling| {
                                         ev.stop_propagation();
+                                        // Scrolling up while already at the top is a pull.
+                                        if ev.delta.y > 0. && scrolling.scroll_top <= 0. {
+                                            scrolling.overscroll += ev.delta.y;
+                                        }
                                         scrolling.scroll_by(-ev.delta.x, -ev.delta.y);
                                     },
                                 ),
@@ -190,6 +235,8 @@ impl ViewTemplate for ScrollView {
                         },
                         (),
                     )
+                    .insert_dyn(|options| options, self.scroll_into_view)
+                    .insert_dyn(|callbacks| callbacks, self.callbacks)
                     .style(style_scroll_region)
                     .children(
                         Element::<NodeBundle>::new()
@@ -0,0 +1,213 @@
+use std::{cmp::Reverse, ops::Range};
+
+use bevy::{prelude::*, ui};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    focus::{AutoFocus, KeyCharEvent, KeyPressEvent, TabIndex},
+    fuzzy::fuzzy_match,
+    hotkeys::{HotkeyBinding, HotkeyRegistry, ShortcutOverlayOpen},
+    scrolling::StickyHeader,
+    typography::text_strong,
+};
+
+use super::{Dialog, DialogBody, DialogHeader, HighlightedText, ScrollView, Spacer};
+
+fn style_search(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .background_color(colors::U1)
+        .border_radius(4.0)
+        .padding((6, 3))
+        .margin_bottom(6)
+        .min_height(24);
+}
+
+fn style_shortcut_list(ss: &mut StyleBuilder) {
+    ss.height(360);
+}
+
+fn style_category(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch);
+}
+
+fn style_category_header(ss: &mut StyleBuilder) {
+    ss.background_color(colors::U2)
+        .color(colors::DIM)
+        .padding((4, 4));
+}
+
+fn style_shortcut_row(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .padding((4, 2));
+}
+
+fn style_key_label(ss: &mut StyleBuilder) {
+    ss.background_color(colors::U3)
+        .border_radius(3.0)
+        .padding((4, 1));
+}
+
+/// A dialog which lists the keyboard shortcuts in the [`HotkeyRegistry`], grouped by category.
+/// Typing while the dialog is open filters the list. The dialog is opened and closed by typing
+/// `?`, or by setting the [`ShortcutOverlayOpen`] resource; add it anywhere in the view
+/// hierarchy, since it is displayed in a portal:
+///
+/// ```ignore
+/// Element::<NodeBundle>::new().children((EditorLayout, ShortcutOverlay))
+/// ```
+#[derive(Default, Clone, PartialEq)]
+pub struct ShortcutOverlay;
+
+impl ViewTemplate for ShortcutOverlay {
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let open = cx.use_resource::<ShortcutOverlayOpen>().0;
+        let search = cx.create_mutable::<String>(String::new());
+        let on_close = cx.create_callback(|mut open: ResMut<ShortcutOverlayOpen>| {
+            open.0 = false;
+        });
+
+        // Clear the search text when the overlay is closed.
+        cx.create_effect(
+            move |world, open| {
+                if !open {
+                    search.set_clone(world, String::new());
+                }
+            },
+            open,
+        );
+
+        let text = search.get_clone(cx);
+        let registry = cx.use_resource::<HotkeyRegistry>();
+        // Each binding is paired with the parts of its description which match the search
+        // text, best matches first.
+        let categories: Vec<ShortcutCategory> = registry
+            .categories()
+            .into_iter()
+            .filter_map(|category| {
+                let mut bindings: Vec<(i32, HotkeyBinding)> = registry
+                    .iter()
+                    .filter(|binding| binding.category == category)
+                    .filter_map(|binding| Some((binding.match_score(&text)?, binding.clone())))
+                    .collect();
+                bindings.sort_by_key(|(score, _)| Reverse(*score));
+                let bindings: Vec<_> = bindings
+                    .into_iter()
+                    .map(|(_, binding)| {
+                        let ranges = fuzzy_match(&text, &binding.description)
+                            .map(|m| m.ranges(&binding.description))
+                            .unwrap_or_default();
+                        (binding, ranges)
+                    })
+                    .collect();
+                (!bindings.is_empty()).then_some(ShortcutCategory {
+                    name: category,
+                    bindings,
+                })
+            })
+            .collect();
+
+        Dialog::new()
+            .width(ui::Val::Px(460.))
+            .open(open)
+            .on_close(on_close)
+            .children((
+                DialogHeader::new().children("Keyboard Shortcuts"),
+                DialogBody::new().children((
+                    Element::<NodeBundle>::new()
+                        .named("ShortcutOverlay::Search")
+                        .style(style_search)
+                        .insert((TabIndex(0), AutoFocus))
+                        .insert_dyn(
+                            move |_| {
+                                (
+                                    On::<KeyCharEvent>::run(move |world: &mut World| {
+                                        let mut event = world
+                                            .get_resource_mut::<ListenerInput<KeyCharEvent>>()
+                                            .unwrap();
+                                        let key = event.key;
+                                        if !key.is_control() && key != '?' {
+                                            event.stop_propagation();
+                                            search.update(world, |mut text| text.push(key));
+                                        }
+                                    }),
+                                    On::<KeyPressEvent>::run(move |world: &mut World| {
+                                        let mut event = world
+                                            .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                            .unwrap();
+                                        if event.key_code == KeyCode::Backspace {
+                                            event.stop_propagation();
+                                            search.update(world, |mut text| {
+                                                text.pop();
+                                            });
+                                        }
+                                    }),
+                                )
+                            },
+                            (),
+                        )
+                        .style_dyn(
+                            |empty, sb| {
+                                sb.color(if empty {
+                                    colors::DIM
+                                } else {
+                                    colors::FOREGROUND
+                                });
+                            },
+                            text.is_empty(),
+                        )
+                        .children(if text.is_empty() {
+                            "Type to search...".to_string()
+                        } else {
+                            text
+                        }),
+                    ScrollView::new()
+                        .style(style_shortcut_list)
+                        .scroll_enable_y(true)
+                        .children(For::each(categories, |category| category.clone())),
+                )),
+            ))
+    }
+}
+
+/// A category heading in the [`ShortcutOverlay`], followed by the matching bindings in the
+/// category.
+#[derive(Clone, PartialEq)]
+struct ShortcutCategory {
+    name: String,
+    bindings: Vec<(HotkeyBinding, Vec<Range<usize>>)>,
+}
+
+impl ViewTemplate for ShortcutCategory {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .style(style_category)
+            .children((
+                Element::<NodeBundle>::new()
+                    .style((text_strong, style_category_header))
+                    .insert(StickyHeader::default())
+                    .children(self.name.clone()),
+                For::each(self.bindings.clone(), |(binding, ranges)| {
+                    Element::<NodeBundle>::new()
+                        .style(style_shortcut_row)
+                        .children((
+                            HighlightedText::new(binding.description.clone())
+                                .ranges(ranges.clone()),
+                            Spacer,
+                            Element::<NodeBundle>::new()
+                                .style(style_key_label)
+                                .children(binding.hotkey.label()),
+                        ))
+                }),
+            ))
+    }
+}
@@ -0,0 +1,219 @@
+use bevy::{
+    color::{Mix, Srgba},
+    prelude::*,
+    ui,
+};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{animation::AnimationSettings, colors};
+
+/// Period of the shimmer animation, in seconds.
+const SHIMMER_PERIOD: f32 = 1.6;
+
+/// Marker component for skeleton blocks which have the shimmer animation.
+#[derive(Component, Default, Clone)]
+pub(crate) struct SkeletonShimmer;
+
+fn style_skeleton_lines(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .gap(6);
+}
+
+fn style_skeleton_block(ss: &mut StyleBuilder) {
+    ss.background_color(colors::U2).flex_shrink(0.);
+}
+
+/// The approximate shape of the content that is being loaded.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SkeletonShape {
+    /// One or more lines of text. The last line is drawn shorter than the others.
+    Text {
+        /// Number of lines.
+        lines: usize,
+        /// Height of each line in pixels.
+        line_height: f32,
+    },
+
+    /// A rectangular block such as an image or a preview.
+    Rect {
+        /// Size of the block in pixels.
+        size: Vec2,
+    },
+
+    /// A circle, such as an avatar or an icon.
+    Circle {
+        /// Diameter of the circle in pixels.
+        diameter: f32,
+    },
+}
+
+impl Default for SkeletonShape {
+    fn default() -> Self {
+        SkeletonShape::Text {
+            lines: 1,
+            line_height: 12.,
+        }
+    }
+}
+
+/// Animated placeholder which is displayed while content is loading.
+#[derive(Clone, PartialEq, Default)]
+pub struct Skeleton {
+    /// Shape of the placeholder.
+    pub shape: SkeletonShape,
+
+    /// Additional styles to be applied to the placeholder.
+    pub style: StyleHandle,
+}
+
+impl Skeleton {
+    /// Create a placeholder for one or more lines of text.
+    pub fn text(lines: usize) -> Self {
+        Self {
+            shape: SkeletonShape::Text {
+                lines,
+                line_height: 12.,
+            },
+            ..default()
+        }
+    }
+
+    /// Create a rectangular placeholder.
+    pub fn rect(size: Vec2) -> Self {
+        Self {
+            shape: SkeletonShape::Rect { size },
+            ..default()
+        }
+    }
+
+    /// Create a circular placeholder.
+    pub fn circle(diameter: f32) -> Self {
+        Self {
+            shape: SkeletonShape::Circle { diameter },
+            ..default()
+        }
+    }
+
+    /// Set the height of each line, for text placeholders.
+    pub fn line_height(mut self, height: f32) -> Self {
+        if let SkeletonShape::Text {
+            ref mut line_height,
+            ..
+        } = self.shape
+        {
+            *line_height = height;
+        }
+        self
+    }
+
+    /// Set additional styles to be applied to the placeholder.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+impl ViewTemplate for Skeleton {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let (widths, height, radius) = match self.shape {
+            SkeletonShape::Text { lines, line_height } => {
+                let widths = (0..lines)
+                    .map(|i| {
+                        if i + 1 == lines && lines > 1 {
+                            ui::Val::Percent(60.)
+                        } else {
+                            ui::Val::Percent(100.)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                (widths, line_height, 3.)
+            }
+            SkeletonShape::Rect { size } => (vec![ui::Val::Px(size.x)], size.y, 4.),
+            SkeletonShape::Circle { diameter } => {
+                (vec![ui::Val::Px(diameter)], diameter, diameter * 0.5)
+            }
+        };
+
+        Element::<NodeBundle>::new()
+            .named("Skeleton")
+            .style((style_skeleton_lines, self.style.clone()))
+            .children(For::index(&widths, move |width, _| {
+                let width = *width;
+                Element::<NodeBundle>::new()
+                    .style((style_skeleton_block, move |ss: &mut StyleBuilder| {
+                        ss.width(width).height(height).border_radius(radius);
+                    }))
+                    .insert(SkeletonShimmer)
+            }))
+    }
+}
+
+/// Displays a placeholder view until the content is ready. If no fallback is specified,
+/// a single-line [`Skeleton`] is shown.
+#[derive(Clone, PartialEq, Default)]
+pub struct Suspense {
+    /// Whether the content is ready to be displayed.
+    pub ready: bool,
+
+    /// The view to display while the content is not ready.
+    pub fallback: Option<ViewChild>,
+
+    /// The content to display once it is ready.
+    pub children: ViewChild,
+}
+
+impl Suspense {
+    /// Create a new `Suspense` view.
+    pub fn new(ready: bool) -> Self {
+        Self { ready, ..default() }
+    }
+
+    /// Set the placeholder to display while the content is not ready.
+    pub fn fallback(mut self, fallback: impl IntoViewChild) -> Self {
+        self.fallback = Some(fallback.into_view_child());
+        self
+    }
+
+    /// Set the child views for this element.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+impl ViewTemplate for Suspense {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let fallback = self
+            .fallback
+            .clone()
+            .unwrap_or_else(|| Skeleton::text(1).into_view_child());
+        Cond::new(self.ready, self.children.clone(), fallback)
+    }
+}
+
+/// System which animates the color of all skeleton blocks. All blocks pulse in unison so that
+/// a panel full of placeholders reads as a single loading state.
+pub(crate) fn animate_skeletons(
+    time: Res<Time>,
+    settings: Res<AnimationSettings>,
+    mut query: Query<&mut BackgroundColor, With<SkeletonShimmer>>,
+) {
+    // With reduced motion, the skeleton is shown in a fixed color.
+    let t = if settings.reduced_motion {
+        0.5
+    } else {
+        let phase = (time.elapsed_seconds() * settings.speed / SHIMMER_PERIOD).fract();
+        0.5 - 0.5 * (phase * std::f32::consts::TAU).cos()
+    };
+    let color: Srgba = colors::U2.mix(&colors::U3, t * 0.6);
+    for mut bg in query.iter_mut() {
+        bg.0 = color.into();
+    }
+}
@@ -6,7 +6,11 @@ use bevy_mod_stylebuilder::*;
 use bevy_quill_core::*;
 
 use crate::{
-    colors, cursor::StyleBuilderCursor, hooks::UseElementRect, materials::SliderRectMaterial,
+    audio::{InteractionSound, PlayInteractionSound},
+    colors,
+    cursor::StyleBuilderCursor,
+    hooks::UseElementRect,
+    materials::SliderRectMaterial,
     RoundedCorners,
 };
 
@@ -274,6 +278,7 @@ impl ViewTemplate for Slider {
                                 .get_resource_mut::<ListenerInput<Pointer<DragStart>>>()
                                 .unwrap();
                             event.stop_propagation();
+                            world.play_interaction_sound(slider_id, InteractionSound::Click);
                             let mut entt = world.entity_mut(slider_id);
                             let value = entt.get::<SliderState>().unwrap().value;
                             entt.insert(DragState {
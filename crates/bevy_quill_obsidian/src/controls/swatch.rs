@@ -27,6 +27,27 @@ fn style_selection(ss: &mut StyleBuilder) {
         .flex_grow(1.);
 }
 
+/// Component which records the color displayed by a [`Swatch`]. Widgets which accept
+/// swatches via drag and drop can use this to find the color that was dropped.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct SwatchColor(pub Srgba);
+
+impl SwatchColor {
+    /// Look up the swatch color for an entity which was dropped onto a widget. Because the
+    /// pointer may have picked a child of the swatch, this also searches the ancestors of
+    /// the entity.
+    pub fn find(world: &World, entity: Entity) -> Option<Srgba> {
+        let mut current = Some(entity);
+        while let Some(e) = current {
+            if let Some(color) = world.get::<SwatchColor>(e) {
+                return Some(color.0);
+            }
+            current = world.get::<Parent>(e).map(|p| p.get());
+        }
+        None
+    }
+}
+
 /// Color swatch widget. This displays a solid color, and can also display a checkerboard
 /// pattern behind the color if it has an alpha of less than 1.
 #[derive(Clone, PartialEq, Default)]
@@ -111,6 +132,7 @@ impl ViewTemplate for Swatch {
             .named("Swatch")
             .style((style_swatch, self.style.clone()))
             .insert_dyn(|m| m, material.clone())
+            .insert_dyn(SwatchColor, color)
             .insert_dyn(
                 |(color, on_click)| {
                     On::<Pointer<Click>>::run(move |world: &mut World| {
@@ -138,7 +160,14 @@ impl ViewTemplate for Swatch {
             )
             .children(Cond::new(
                 selected,
-                Element::<NodeBundle>::new().style(style_selection),
+                Element::<NodeBundle>::new()
+                    .style(style_selection)
+                    .style_dyn(
+                        |color, sb| {
+                            sb.border_color(colors::text_color_for(color));
+                        },
+                        color,
+                    ),
                 (),
             ))
     }
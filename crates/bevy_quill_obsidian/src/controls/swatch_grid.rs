@@ -1,10 +1,13 @@
+use std::{io, path::Path};
+
 use bevy::{color::Srgba, prelude::*, ui};
+use bevy_mod_picking::{pointer::PointerButton, prelude::*};
 use bevy_mod_stylebuilder::*;
 use bevy_quill_core::*;
 
 use crate::colors;
 
-use super::Swatch;
+use super::{Icon, Swatch};
 
 fn style_swatch_grid(ss: &mut StyleBuilder) {
     ss.border(1)
@@ -28,6 +31,95 @@ fn style_empty_slot(ss: &mut StyleBuilder) {
         .border_color(colors::U2.lighter(0.01));
 }
 
+fn style_palette_slot(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .align_items(ui::AlignItems::Stretch)
+        .justify_content(ui::JustifyContent::Stretch);
+}
+
+fn style_add_slot(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .justify_content(ui::JustifyContent::Center)
+        .align_items(ui::AlignItems::Center)
+        .border(1)
+        .border_radius(3)
+        .min_width(16)
+        .min_height(16)
+        .border_color(colors::U3);
+}
+
+/// A user-editable palette of colors. Unlike [`RecentColors`](super::RecentColors), colors
+/// are only added or removed when the user asks, and the palette can be saved to disk.
+#[derive(Resource, Default, Clone, PartialEq, Debug)]
+pub struct ColorPalette(pub Vec<Srgba>);
+
+impl ColorPalette {
+    /// Add a color to the end of the palette, if it's not already present.
+    pub fn add(&mut self, color: Srgba) {
+        if !self.0.contains(&color) {
+            self.0.push(color);
+        }
+    }
+
+    /// Remove the color at the given index.
+    pub fn remove(&mut self, index: usize) {
+        if index < self.0.len() {
+            self.0.remove(index);
+        }
+    }
+
+    /// Move a color to a new position in the palette.
+    pub fn move_color(&mut self, from: usize, to: usize) {
+        if from < self.0.len() && to < self.0.len() {
+            let color = self.0.remove(from);
+            self.0.insert(to, color);
+        }
+    }
+
+    /// Save the palette to a text file, with one hex color per line.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = self
+            .0
+            .iter()
+            .map(|c| c.to_hex())
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(path, text)
+    }
+
+    /// Load a palette from a text file written by [`ColorPalette::save`]. Lines which are
+    /// not valid hex colors are ignored.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Self(
+            text.lines()
+                .filter_map(|line| Srgba::hex(line.trim()).ok())
+                .collect(),
+        ))
+    }
+}
+
+/// Identifies the position of a swatch within an editable [`SwatchGrid`].
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+struct PaletteSlot {
+    grid: Entity,
+    index: usize,
+}
+
+impl PaletteSlot {
+    /// Find the palette slot containing the given entity.
+    fn find(world: &World, entity: Entity) -> Option<Self> {
+        let mut current = Some(entity);
+        while let Some(e) = current {
+            if let Some(slot) = world.get::<PaletteSlot>(e) {
+                return Some(*slot);
+            }
+            current = world.get::<Parent>(e).map(|p| p.get());
+        }
+        None
+    }
+}
+
 /// Color swatch widget. This displays a solid color, and can also display a checkerboard
 /// pattern behind the color if it has an alpha of less than 1.
 #[derive(Clone, PartialEq)]
@@ -47,6 +139,16 @@ pub struct SwatchGrid {
 
     /// Callback called when a swatch is clicked
     pub on_change: Option<Callback<Srgba>>,
+
+    /// If set, an "add" button is displayed after the last color, which calls this callback.
+    pub on_add: Option<Callback>,
+
+    /// If set, right-clicking a swatch calls this callback with the index of the swatch.
+    pub on_remove: Option<Callback<usize>>,
+
+    /// If set, swatches can be dragged onto other swatches in the grid, which calls this
+    /// callback with the (from, to) indices.
+    pub on_reorder: Option<Callback<(usize, usize)>>,
 }
 
 impl SwatchGrid {
@@ -84,6 +186,24 @@ impl SwatchGrid {
         self.on_change = Some(on_click);
         self
     }
+
+    /// Set the callback called when the "add" button is clicked.
+    pub fn on_add(mut self, on_add: Callback) -> Self {
+        self.on_add = Some(on_add);
+        self
+    }
+
+    /// Set the callback called when a swatch is removed.
+    pub fn on_remove(mut self, on_remove: Callback<usize>) -> Self {
+        self.on_remove = Some(on_remove);
+        self
+    }
+
+    /// Set the callback called when a swatch is dragged to a new position.
+    pub fn on_reorder(mut self, on_reorder: Callback<(usize, usize)>) -> Self {
+        self.on_reorder = Some(on_reorder);
+        self
+    }
 }
 
 impl Default for SwatchGrid {
@@ -94,6 +214,9 @@ impl Default for SwatchGrid {
             selected: Srgba::default(),
             style: Default::default(),
             on_change: None,
+            on_add: None,
+            on_remove: None,
+            on_reorder: None,
         }
     }
 }
@@ -101,10 +224,14 @@ impl Default for SwatchGrid {
 impl ViewTemplate for SwatchGrid {
     type View = impl View;
     fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
         let num_cells = (self.grid_size.x * self.grid_size.y) as usize;
         let grid_size = self.grid_size;
         let selected = self.selected;
         let on_change = self.on_change;
+        let on_add = self.on_add;
+        let on_remove = self.on_remove;
+        let on_reorder = self.on_reorder;
 
         let on_click = cx.create_callback(move |color: In<Srgba>, world: &mut World| {
             if let Some(on_change) = on_change.as_ref() {
@@ -113,9 +240,10 @@ impl ViewTemplate for SwatchGrid {
         });
 
         let num_colors = self.colors.len().min(num_cells);
-        let padding_len = num_cells - num_colors;
+        let show_add = on_add.is_some() && num_colors < num_cells;
+        let padding_len = num_cells - num_colors - if show_add { 1 } else { 0 };
 
-        Element::<NodeBundle>::new()
+        Element::<NodeBundle>::for_entity(id)
             .named("SwatchGrid")
             .style((
                 style_swatch_grid,
@@ -130,12 +258,73 @@ impl ViewTemplate for SwatchGrid {
             ))
             .children((
                 // Generate cells for each color
-                For::each(self.colors[0..num_colors].to_owned(), move |color| {
-                    Swatch::new(*color)
-                        .selected(selected == *color)
-                        .style(style_swatch)
-                        .on_click(on_click)
+                For::index(&self.colors[0..num_colors], move |color, index| {
+                    Element::<NodeBundle>::new()
+                        .style(style_palette_slot)
+                        .insert(PaletteSlot { grid: id, index })
+                        .insert_dyn(
+                            move |_| {
+                                (
+                                    On::<Pointer<Click>>::run(move |world: &mut World| {
+                                        let mut event = world
+                                            .get_resource_mut::<ListenerInput<Pointer<Click>>>()
+                                            .unwrap();
+                                        if event.button == PointerButton::Secondary {
+                                            event.stop_propagation();
+                                            if let Some(on_remove) = on_remove {
+                                                world.run_callback(on_remove, index);
+                                            }
+                                        }
+                                    }),
+                                    On::<Pointer<Drop>>::run(move |world: &mut World| {
+                                        let mut event = world
+                                            .get_resource_mut::<ListenerInput<Pointer<Drop>>>()
+                                            .unwrap();
+                                        event.stop_propagation();
+                                        let dropped = event.dropped;
+                                        let (Some(on_reorder), Some(source)) =
+                                            (on_reorder, PaletteSlot::find(world, dropped))
+                                        else {
+                                            return;
+                                        };
+                                        if source.grid == id && source.index != index {
+                                            world.run_callback(on_reorder, (source.index, index));
+                                        }
+                                    }),
+                                )
+                            },
+                            (),
+                        )
+                        .children(
+                            Swatch::new(*color)
+                                .selected(selected == *color)
+                                .style(style_swatch)
+                                .on_click(on_click),
+                        )
                 }),
+                // Button to add a new color
+                Cond::new(
+                    show_add,
+                    Element::<NodeBundle>::new()
+                        .named("SwatchGrid::Add")
+                        .style(style_add_slot)
+                        .insert_dyn(
+                            move |_| {
+                                On::<Pointer<Click>>::run(move |world: &mut World| {
+                                    if let Some(on_add) = on_add {
+                                        world.run_callback(on_add, ());
+                                    }
+                                })
+                            },
+                            (),
+                        )
+                        .children(
+                            Icon::new("embedded://bevy_quill_obsidian/assets/icons/add.png")
+                                .size(Vec2::splat(12.))
+                                .color(Color::from(colors::DIM)),
+                        ),
+                    (),
+                ),
                 // Pad with empty cells
                 For::each(0..padding_len, move |_| {
                     Element::<NodeBundle>::new().style(style_empty_slot)
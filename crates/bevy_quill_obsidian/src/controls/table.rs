@@ -0,0 +1,617 @@
+use std::{cmp::Ordering, io, ops::Range, sync::Arc};
+
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{colors, scrolling::ScrollArea, typography};
+
+use super::ScrollView;
+
+/// Number of extra rows and columns which are built on either side of the visible region, so
+/// that they are ready before they are scrolled into view.
+const OVERSCAN: usize = 2;
+
+fn style_table(ss: &mut StyleBuilder) {
+    ss.background_color(colors::U1).border_radius(5.0);
+}
+
+fn style_table_content(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Relative);
+}
+
+fn style_layer(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute).left(0).top(0);
+}
+
+fn style_frozen_layer(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .top(0)
+        .background_color(colors::U1)
+        .border_right(1)
+        .border_color(colors::U3);
+}
+
+fn style_header(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .left(0)
+        .background_color(colors::U2);
+}
+
+fn style_cell(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .display(ui::Display::Flex)
+        .align_items(ui::AlignItems::Center)
+        .padding((6, 0))
+        .overflow(ui::OverflowAxis::Clip)
+        .color(colors::FOREGROUND);
+}
+
+fn style_frozen_header(ss: &mut StyleBuilder) {
+    ss.background_color(colors::U2);
+}
+
+fn style_header_cell(ss: &mut StyleBuilder) {
+    ss.color(colors::DIM);
+}
+
+/// Description of a column of a [`Table`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct TableColumn {
+    /// Column heading.
+    pub name: String,
+
+    /// Width of the column, in pixels.
+    pub width: f32,
+}
+
+impl TableColumn {
+    /// Create a new column with the given heading and width.
+    pub fn new(name: impl Into<String>, width: f32) -> Self {
+        Self {
+            name: name.into(),
+            width,
+        }
+    }
+}
+
+/// The contents of a [`Table`]: a list of columns, and a list of rows, each of which has a
+/// string for every column.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TableData {
+    /// The columns of the table.
+    pub columns: Vec<TableColumn>,
+
+    /// The cells of each row. Rows with fewer cells than there are columns are padded with
+    /// empty cells.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TableData {
+    /// Create a new table with the given columns, and no rows.
+    pub fn new(columns: Vec<TableColumn>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row to the table.
+    pub fn with_row(mut self, row: Vec<String>) -> Self {
+        self.rows.push(row);
+        self
+    }
+
+    /// The text of a cell, or an empty string if the cell doesn't exist.
+    pub fn cell(&self, row: usize, column: usize) -> &str {
+        self.rows
+            .get(row)
+            .and_then(|cells| cells.get(column))
+            .map_or("", |cell| cell.as_str())
+    }
+
+    /// The horizontal offset of each column, followed by the total width.
+    fn column_offsets(&self) -> Vec<f32> {
+        let mut offsets = Vec::with_capacity(self.columns.len() + 1);
+        let mut x = 0.;
+        offsets.push(x);
+        for column in self.columns.iter() {
+            x += column.width;
+            offsets.push(x);
+        }
+        offsets
+    }
+}
+
+/// The column which a [`Table`] is sorted by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TableSort {
+    /// Index of the column.
+    pub column: usize,
+
+    /// If true, rows are sorted from largest to smallest.
+    pub descending: bool,
+}
+
+/// Compare two cells, numerically if they are both numbers.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+/// Write a line of CSV, quoting fields if needed.
+fn write_csv_line<'a>(
+    writer: &mut impl io::Write,
+    fields: impl Iterator<Item = &'a str>,
+) -> io::Result<()> {
+    for (index, field) in fields.enumerate() {
+        if index > 0 {
+            write!(writer, ",")?;
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            write!(writer, "{}", field)?;
+        }
+    }
+    writeln!(writer)
+}
+
+/// A row of a table, serialized as a map from column names to cells.
+struct ExportRow<'a> {
+    data: &'a TableData,
+    row: usize,
+}
+
+impl<'a> Serialize for ExportRow<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.data.columns.len()))?;
+        for (index, column) in self.data.columns.iter().enumerate() {
+            map.serialize_entry(&column.name, self.data.cell(self.row, index))?;
+        }
+        map.end()
+    }
+}
+
+/// A cell which is currently displayed.
+#[derive(Clone, PartialEq)]
+struct VisibleCell {
+    left: f32,
+    width: f32,
+    text: String,
+}
+
+/// A row of cells which are currently displayed.
+#[derive(Clone, PartialEq)]
+struct VisibleRow {
+    top: f32,
+    height: f32,
+    cells: Vec<VisibleCell>,
+}
+
+impl ViewTemplate for VisibleRow {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let top = self.top;
+        let height = self.height;
+        For::index(&self.cells, move |cell, _| {
+            Element::<NodeBundle>::new()
+                .style(style_cell)
+                .style_dyn(
+                    |(left, top, width, height), sb| {
+                        sb.left(left).top(top).width(width).height(height);
+                    },
+                    (cell.left, top, cell.width, height),
+                )
+                .children(cell.text.clone())
+        })
+    }
+}
+
+/// A scrollable table of text cells. Only the rows and columns which are scrolled into view are
+/// built, so the table can display large amounts of data, such as matrices with many columns.
+/// Leading columns can be frozen, so that they remain in place when the table is scrolled
+/// horizontally, and the header row remains in place when the table is scrolled vertically.
+#[derive(Clone, PartialEq)]
+pub struct Table {
+    /// The contents of the table.
+    pub data: Arc<TableData>,
+
+    /// Number of leading columns which don't scroll horizontally.
+    pub frozen_columns: usize,
+
+    /// Height of each row, including the header, in pixels.
+    pub row_height: f32,
+
+    /// The column to sort rows by. If `None`, rows are displayed in their original order.
+    pub sort: Option<TableSort>,
+
+    /// Only rows which contain this text in one of their cells, ignoring case, are displayed.
+    pub filter: String,
+
+    /// Additional styles to be applied to the table.
+    pub style: StyleHandle,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self {
+            data: Arc::new(TableData::default()),
+            frozen_columns: 0,
+            row_height: 24.,
+            sort: None,
+            filter: String::new(),
+            style: StyleHandle::default(),
+        }
+    }
+}
+
+impl Table {
+    /// Create a new table displaying `data`.
+    pub fn new(data: impl Into<Arc<TableData>>) -> Self {
+        Self {
+            data: data.into(),
+            ..default()
+        }
+    }
+
+    /// Set the number of leading columns which don't scroll horizontally.
+    pub fn frozen_columns(mut self, count: usize) -> Self {
+        self.frozen_columns = count;
+        self
+    }
+
+    /// Set the height of each row.
+    pub fn row_height(mut self, height: f32) -> Self {
+        self.row_height = height;
+        self
+    }
+
+    /// Sort rows by the given column.
+    pub fn sort(mut self, column: usize, descending: bool) -> Self {
+        self.sort = Some(TableSort { column, descending });
+        self
+    }
+
+    /// Only display rows which contain the given text.
+    pub fn filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = filter.into();
+        self
+    }
+
+    /// Set additional styles to be applied to the table.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// The indices of the rows which are displayed, after filtering and sorting, in the order
+    /// in which they are displayed.
+    pub fn row_order(&self) -> Vec<usize> {
+        let filter = self.filter.to_lowercase();
+        let mut order: Vec<usize> = (0..self.data.rows.len())
+            .filter(|row| {
+                filter.is_empty()
+                    || self.data.rows[*row]
+                        .iter()
+                        .any(|cell| cell.to_lowercase().contains(&filter))
+            })
+            .collect();
+        if let Some(sort) = self.sort {
+            // The sort is stable, so equal rows keep their original order.
+            order.sort_by(|a, b| {
+                let ord = compare_cells(
+                    self.data.cell(*a, sort.column),
+                    self.data.cell(*b, sort.column),
+                );
+                if sort.descending {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            });
+        }
+        order
+    }
+
+    /// Write the displayed rows, after filtering and sorting, as CSV. The first line contains
+    /// the column headings.
+    pub fn export_csv(&self, mut writer: impl io::Write) -> io::Result<()> {
+        let headings = self.data.columns.iter().map(|column| column.name.as_str());
+        write_csv_line(&mut writer, headings)?;
+        for row in self.row_order() {
+            let cells = (0..self.data.columns.len()).map(|column| self.data.cell(row, column));
+            write_csv_line(&mut writer, cells)?;
+        }
+        Ok(())
+    }
+
+    /// Write the displayed rows, after filtering and sorting, as a JSON array of objects, each
+    /// of which maps column headings to cells.
+    pub fn export_json(&self, writer: impl io::Write) -> io::Result<()> {
+        let rows: Vec<ExportRow> = self
+            .row_order()
+            .into_iter()
+            .map(|row| ExportRow {
+                data: &self.data,
+                row,
+            })
+            .collect();
+        serde_json::to_writer_pretty(writer, &rows).map_err(io::Error::from)
+    }
+
+    /// Build the visible cells of the rows at the given positions (where `None` is the header
+    /// row) within `columns`. `order` maps positions to rows of the data.
+    fn visible_rows(
+        &self,
+        rows: impl Iterator<Item = Option<usize>>,
+        columns: Range<usize>,
+        offsets: &[f32],
+        order: &[usize],
+    ) -> Vec<VisibleRow> {
+        rows.map(|row| VisibleRow {
+            top: row.map_or(0., |row| (row + 1) as f32 * self.row_height),
+            height: self.row_height,
+            cells: columns
+                .clone()
+                .map(|column| VisibleCell {
+                    left: offsets[column],
+                    width: self.data.columns[column].width,
+                    text: match row {
+                        Some(row) => self.data.cell(order[row], column).to_string(),
+                        None => self.data.columns[column].name.clone(),
+                    },
+                })
+                .collect(),
+        })
+        .collect()
+    }
+}
+
+impl ViewTemplate for Table {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let scroll_id = cx.create_entity();
+        let (scroll, visible) =
+            cx.use_component::<ScrollArea>(scroll_id)
+                .map_or((Vec2::ZERO, Vec2::ZERO), |area| {
+                    (
+                        Vec2::new(area.scroll_left, area.scroll_top),
+                        area.visible_size,
+                    )
+                });
+
+        let data = &self.data;
+        let num_columns = data.columns.len();
+        let frozen = self.frozen_columns.min(num_columns);
+        let offsets = data.column_offsets();
+        let frozen_width = offsets[frozen];
+        let total_width = offsets[num_columns];
+        let order = self.row_order();
+        let total_height = (order.len() + 1) as f32 * self.row_height;
+
+        // Until the table has been laid out, build enough to fill a typical view.
+        let visible = if visible == Vec2::ZERO {
+            Vec2::new(1000., 1000.)
+        } else {
+            visible
+        };
+
+        // Columns which are scrolled into view, not counting the frozen columns.
+        let first_column = offsets[frozen..num_columns]
+            .iter()
+            .zip(offsets[frozen + 1..].iter())
+            .position(|(_, right)| *right > scroll.x + frozen_width)
+            .map_or(num_columns, |i| i + frozen);
+        let last_column = offsets[first_column..num_columns]
+            .iter()
+            .position(|left| *left >= scroll.x + visible.x)
+            .map_or(num_columns, |i| i + first_column);
+        let columns = first_column.saturating_sub(OVERSCAN).max(frozen)
+            ..(last_column + OVERSCAN).min(num_columns);
+
+        // Rows which are scrolled into view.
+        let first_row = ((scroll.y / self.row_height) as usize).saturating_sub(1 + OVERSCAN);
+        let last_row = (((scroll.y + visible.y) / self.row_height).ceil() as usize + OVERSCAN)
+            .min(order.len());
+        let rows = first_row.min(last_row)..last_row;
+
+        let body = self.visible_rows(rows.clone().map(Some), columns.clone(), &offsets, &order);
+        let frozen_body = self.visible_rows(rows.map(Some), 0..frozen, &offsets, &order);
+        let header = self.visible_rows(std::iter::once(None), columns, &offsets, &order);
+        let frozen_header = self.visible_rows(std::iter::once(None), 0..frozen, &offsets, &order);
+
+        ScrollView::new()
+            .entity(Some(scroll_id))
+            .style((style_table, self.style.clone()))
+            .content_style(style_table_content)
+            .scroll_enable_x(true)
+            .scroll_enable_y(true)
+            .children(
+                Element::<NodeBundle>::new()
+                    .named("Table")
+                    .style(typography::text_default)
+                    .style_dyn(
+                        |(width, height), sb| {
+                            sb.width(width).height(height);
+                        },
+                        (total_width, total_height),
+                    )
+                    .children((
+                        // Scrolling cells
+                        Element::<NodeBundle>::new()
+                            .named("Table::Body")
+                            .style(style_layer)
+                            .children(For::each(body, |row| row.clone())),
+                        // Frozen columns, which are moved to counteract horizontal scrolling.
+                        Element::<NodeBundle>::new()
+                            .named("Table::Frozen")
+                            .style(style_frozen_layer)
+                            .style_dyn(
+                                |(left, width, height), sb| {
+                                    sb.left(left).width(width).height(height);
+                                },
+                                (scroll.x, frozen_width, total_height),
+                            )
+                            .children(For::each(frozen_body, |row| row.clone())),
+                        // Header row, which is moved to counteract vertical scrolling.
+                        Element::<NodeBundle>::new()
+                            .named("Table::Header")
+                            .style((style_header, style_header_cell))
+                            .style_dyn(
+                                |(top, width, height), sb| {
+                                    sb.top(top).width(width).height(height);
+                                },
+                                (scroll.y, total_width, self.row_height),
+                            )
+                            .children((
+                                For::each(header, |row| row.clone()),
+                                Element::<NodeBundle>::new()
+                                    .style((style_frozen_layer, style_frozen_header))
+                                    .style_dyn(
+                                        |(left, width, height), sb| {
+                                            sb.left(left).width(width).height(height);
+                                        },
+                                        (scroll.x, frozen_width, self.row_height),
+                                    )
+                                    .children(For::each(frozen_header, |row| row.clone())),
+                            )),
+                    )),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    fn data() -> TableData {
+        TableData::new(vec![
+            TableColumn::new("Name", 100.),
+            TableColumn::new("Size", 60.),
+        ])
+        .with_row(row(&["beta", "10"]))
+        .with_row(row(&["Alpha", "9"]))
+        .with_row(row(&["gamma", "10"]))
+        .with_row(row(&["delta"]))
+    }
+
+    #[test]
+    fn test_compare_cells() {
+        // Numbers are compared by value, not as text.
+        assert_eq!(compare_cells("9", "10"), Ordering::Less);
+        assert_eq!(compare_cells(" 2.5", "-3 "), Ordering::Greater);
+        assert_eq!(compare_cells("1.0", "1"), Ordering::Equal);
+
+        // Anything else is compared as text.
+        assert_eq!(compare_cells("apple", "banana"), Ordering::Less);
+        assert_eq!(compare_cells("10", "apple"), Ordering::Less);
+        assert_eq!(compare_cells("", "0"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_row_order_unsorted() {
+        let table = Table::new(data());
+        assert_eq!(table.row_order(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_row_order_sorted() {
+        // Text is compared by code point, so upper case sorts first.
+        let table = Table::new(data()).sort(0, false);
+        assert_eq!(table.row_order(), [1, 0, 3, 2]);
+
+        // Missing cells sort as empty strings. Equal rows keep their original order, in both
+        // directions.
+        let table = Table::new(data()).sort(1, false);
+        assert_eq!(table.row_order(), [3, 1, 0, 2]);
+        let table = Table::new(data()).sort(1, true);
+        assert_eq!(table.row_order(), [0, 2, 1, 3]);
+
+        // Sorting by a column which doesn't exist leaves the rows in order.
+        let table = Table::new(data()).sort(5, false);
+        assert_eq!(table.row_order(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_row_order_filtered() {
+        // The filter ignores case, and matches any cell.
+        let table = Table::new(data()).filter("ALPHA");
+        assert_eq!(table.row_order(), [1]);
+        let table = Table::new(data()).filter("10").sort(0, true);
+        assert_eq!(table.row_order(), [2, 0]);
+        let table = Table::new(data()).filter("zeta");
+        assert!(table.row_order().is_empty());
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let data = TableData::new(vec![
+            TableColumn::new("Name", 100.),
+            TableColumn::new("Notes, etc.", 100.),
+        ])
+        .with_row(row(&["plain", "a, b"]))
+        .with_row(row(&["say \"hi\"", "line 1\nline 2"]))
+        .with_row(row(&["missing"]));
+        let mut csv = Vec::new();
+        Table::new(data).export_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "Name,\"Notes, etc.\"\n\
+            plain,\"a, b\"\n\
+            \"say \"\"hi\"\"\",\"line 1\nline 2\"\n\
+            missing,\n"
+        );
+    }
+
+    #[test]
+    fn test_export_csv_sorted_and_filtered() {
+        let mut csv = Vec::new();
+        Table::new(data())
+            .filter("a")
+            .sort(0, true)
+            .export_csv(&mut csv)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "Name,Size\ngamma,10\ndelta,\nbeta,10\nAlpha,9\n"
+        );
+    }
+
+    #[test]
+    fn test_export_json() {
+        let data = TableData::new(vec![
+            TableColumn::new("Name", 100.),
+            TableColumn::new("Notes", 100.),
+        ])
+        .with_row(row(&["say \"hi\"", "a, b\nc"]))
+        .with_row(row(&["missing"]));
+        let mut json = Vec::new();
+        Table::new(data)
+            .sort(0, false)
+            .export_json(&mut json)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([
+                { "Name": "missing", "Notes": "" },
+                { "Name": "say \"hi\"", "Notes": "a, b\nc" },
+            ])
+        );
+
+        // Quotes and newlines are escaped in the output.
+        let text = String::from_utf8(json).unwrap();
+        assert!(text.contains(r#""say \"hi\"""#));
+        assert!(text.contains(r#""a, b\nc""#));
+    }
+}
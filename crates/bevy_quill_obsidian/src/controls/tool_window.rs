@@ -0,0 +1,486 @@
+use std::collections::BTreeMap;
+
+use bevy::{prelude::*, ui, window::PrimaryWindow};
+use bevy_mod_picking::{events::PointerCancel, prelude::*};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+use serde::{Deserialize, Serialize};
+
+use super::IconButton;
+use crate::{colors, cursor::StyleBuilderCursor, size::Size, typography::text_default};
+
+/// Base z-index of tool windows. This is below popups and dialogs, which use 100 and above.
+const TOOL_WINDOW_Z_INDEX: i32 = 50;
+
+/// The position, size and visibility of a tool window.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ToolWindowState {
+    /// Distance of the left edge of the window from the left of the app window, in pixels.
+    pub left: f32,
+
+    /// Distance of the top edge of the window from the top of the app window, in pixels.
+    pub top: f32,
+
+    /// Width of the window in pixels.
+    pub width: f32,
+
+    /// Height of the window in pixels, when not minimized.
+    pub height: f32,
+
+    /// Whether the window is open.
+    pub open: bool,
+
+    /// Whether the window is minimized to its title bar.
+    pub minimized: bool,
+}
+
+impl Default for ToolWindowState {
+    fn default() -> Self {
+        Self::new(Rect::new(40., 40., 280., 360.))
+    }
+}
+
+impl ToolWindowState {
+    /// Construct a closed window state with the given rectangle.
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            left: rect.min.x,
+            top: rect.min.y,
+            width: rect.width(),
+            height: rect.height(),
+            open: false,
+            minimized: false,
+        }
+    }
+
+    /// The rectangle occupied by the window when not minimized.
+    pub fn rect(&self) -> Rect {
+        Rect::new(
+            self.left,
+            self.top,
+            self.left + self.width,
+            self.top + self.height,
+        )
+    }
+
+    /// Return a copy of this state which has been moved and shrunk so that it fits within
+    /// `bounds`, the size of the app window.
+    fn clamped(&self, bounds: Vec2) -> Self {
+        let width = self.width.min(bounds.x);
+        let height = self.height.min(bounds.y);
+        Self {
+            left: self.left.clamp(0., (bounds.x - width).max(0.)),
+            top: self.top.clamp(0., (bounds.y - height).max(0.)),
+            width,
+            height,
+            ..*self
+        }
+    }
+}
+
+/// Resource which manages the floating [`ToolWindow`]s: which ones are open, where they are,
+/// and their stacking order. Windows are identified by names chosen by the application, which
+/// opens and closes them here, for example from a menu. The positions and sizes are saved
+/// between sessions by [`LayoutPersistence`](crate::LayoutPersistence).
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
+pub struct ToolWindows {
+    windows: BTreeMap<String, ToolWindowState>,
+
+    /// Names of the windows in stacking order, with the top-most last.
+    order: Vec<String>,
+}
+
+impl ToolWindows {
+    /// Construct the window manager from previously-saved window states.
+    pub fn from_saved(windows: &BTreeMap<String, ToolWindowState>) -> Self {
+        Self {
+            windows: windows.clone(),
+            order: windows.keys().cloned().collect(),
+        }
+    }
+
+    /// The states of all the windows, by name.
+    pub fn windows(&self) -> &BTreeMap<String, ToolWindowState> {
+        &self.windows
+    }
+
+    /// The state of the named window, if it has been opened or displayed before.
+    pub fn get(&self, name: &str) -> Option<&ToolWindowState> {
+        self.windows.get(name)
+    }
+
+    /// Whether the named window is open.
+    pub fn is_open(&self, name: &str) -> bool {
+        self.windows.get(name).is_some_and(|state| state.open)
+    }
+
+    /// Register a window with its default rectangle, unless it is already known.
+    pub fn register(&mut self, name: &str, rect: Rect) {
+        if !self.windows.contains_key(name) {
+            self.windows
+                .insert(name.to_string(), ToolWindowState::new(rect));
+            self.order.push(name.to_string());
+        }
+    }
+
+    /// Open the named window, restore it if minimized, and bring it to the front.
+    pub fn open(&mut self, name: &str) {
+        let state = self.windows.entry(name.to_string()).or_default();
+        state.open = true;
+        state.minimized = false;
+        self.raise(name);
+    }
+
+    /// Close the named window. Its position and size are kept for when it is reopened.
+    pub fn close(&mut self, name: &str) {
+        if let Some(state) = self.windows.get_mut(name) {
+            state.open = false;
+        }
+    }
+
+    /// Open the named window if it is closed, otherwise close it.
+    pub fn toggle(&mut self, name: &str) {
+        if self.is_open(name) {
+            self.close(name);
+        } else {
+            self.open(name);
+        }
+    }
+
+    /// Minimize the named window to its title bar, or restore it.
+    pub fn set_minimized(&mut self, name: &str, minimized: bool) {
+        if let Some(state) = self.windows.get_mut(name) {
+            state.minimized = minimized;
+        }
+    }
+
+    /// Bring the named window in front of the other tool windows.
+    pub fn raise(&mut self, name: &str) {
+        if self.order.last().map(String::as_str) != Some(name) {
+            self.order.retain(|n| n != name);
+            self.order.push(name.to_string());
+        }
+    }
+
+    /// Move the named window so that its top-left corner is at `position`.
+    pub fn move_to(&mut self, name: &str, position: Vec2) {
+        if let Some(state) = self.windows.get_mut(name) {
+            state.left = position.x;
+            state.top = position.y;
+        }
+    }
+
+    /// Resize the named window.
+    pub fn resize(&mut self, name: &str, size: Vec2) {
+        if let Some(state) = self.windows.get_mut(name) {
+            state.width = size.x;
+            state.height = size.y;
+        }
+    }
+
+    fn z_index(&self, name: &str) -> i32 {
+        let index = self.order.iter().position(|n| n == name).unwrap_or(0);
+        TOOL_WINDOW_Z_INDEX + index as i32
+    }
+}
+
+/// Keep the tool windows within the app window when it is resized, or when a window is moved.
+pub(crate) fn clamp_tool_windows(
+    mut tool_windows: ResMut<ToolWindows>,
+    primary: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = primary.get_single() else {
+        return;
+    };
+    let bounds = Vec2::new(window.width(), window.height());
+    // Read through a shared reference first, so that change detection only fires if a window
+    // actually moves.
+    let clamped = tool_windows
+        .windows
+        .iter()
+        .filter_map(|(name, state)| {
+            let clamped = state.clamped(bounds);
+            (clamped != *state).then(|| (name.clone(), clamped))
+        })
+        .collect::<Vec<_>>();
+    for (name, state) in clamped {
+        tool_windows.windows.insert(name, state);
+    }
+}
+
+/// The state of the tool window at the start of a move or resize.
+#[derive(Component, Clone, Copy)]
+struct ToolWindowDrag(Option<ToolWindowState>);
+
+fn style_tool_window(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .background_color(colors::U2)
+        .border_color(colors::U1)
+        .border(2)
+        .border_radius(5.0);
+}
+
+fn style_title_bar(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(4)
+        .padding((8, 2))
+        .background_color(colors::U1)
+        .cursor(CursorIcon::Move);
+}
+
+fn style_title(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.).font_size(14).color(colors::FOREGROUND);
+}
+
+fn style_body(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .flex_grow(1.)
+        .min_height(0)
+        .overflow(ui::OverflowAxis::Clip);
+}
+
+fn style_resize_grip(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .right(0)
+        .bottom(0)
+        .width(12)
+        .height(12)
+        .cursor(CursorIcon::NwseResize);
+}
+
+/// A floating, non-modal tool window, for palettes and inspectors which users want to tear off
+/// from the main layout. The window has a title bar which can be dragged to move it, a grip in
+/// the bottom-right corner to resize it, and buttons to minimize and close it. Unlike a
+/// [`Dialog`](super::Dialog), it has no scrim, so the rest of the app remains usable.
+///
+/// The window's state is kept in the [`ToolWindows`] resource under its name, which is used to
+/// open and close it. The window is positioned relative to its parent, which should cover the
+/// whole app window, for example the root of the UI.
+#[derive(Clone, PartialEq)]
+pub struct ToolWindow {
+    /// The name of the window, used to open and close it and to save its position.
+    pub name: String,
+
+    /// The title shown in the title bar.
+    pub title: String,
+
+    /// The position and size of the window the first time it is opened.
+    pub default_rect: Rect,
+
+    /// The minimum size of the window when resizing.
+    pub min_size: Vec2,
+
+    /// The content of the window.
+    pub children: ViewChild,
+}
+
+impl Default for ToolWindow {
+    fn default() -> Self {
+        let state = ToolWindowState::default();
+        Self {
+            name: String::new(),
+            title: String::new(),
+            default_rect: state.rect(),
+            min_size: Vec2::new(120., 80.),
+            children: ViewChild::default(),
+        }
+    }
+}
+
+impl ToolWindow {
+    /// Create a new tool window with the given name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..default()
+        }
+    }
+
+    /// Set the title shown in the title bar.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the position and size of the window the first time it is opened.
+    pub fn default_rect(mut self, rect: Rect) -> Self {
+        self.default_rect = rect;
+        self
+    }
+
+    /// Set the minimum size of the window when resizing.
+    pub fn min_size(mut self, min_size: Vec2) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Set the content of the window.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+/// Event handlers which update the named window while the entity `id` is dragged. `apply` is
+/// given the state of the window at the start of the drag and the distance dragged so far.
+fn drag_handlers(
+    id: Entity,
+    name: String,
+    apply: impl Fn(&mut ToolWindows, &str, ToolWindowState, Vec2) + Send + Sync + 'static,
+) -> impl Bundle {
+    let start_name = name.clone();
+    (
+        ToolWindowDrag(None),
+        On::<Pointer<DragStart>>::run(move |world: &mut World| {
+            let state = world.resource::<ToolWindows>().get(&start_name).copied();
+            world.get_mut::<ToolWindowDrag>(id).unwrap().0 = state;
+        }),
+        On::<Pointer<Drag>>::run(move |world: &mut World| {
+            let distance = world.resource::<ListenerInput<Pointer<Drag>>>().distance;
+            let Some(start) = world.get::<ToolWindowDrag>(id).unwrap().0 else {
+                return;
+            };
+            apply(
+                &mut world.resource_mut::<ToolWindows>(),
+                &name,
+                start,
+                distance,
+            );
+        }),
+        On::<Pointer<DragEnd>>::listener_component_mut::<ToolWindowDrag>(move |_, drag| {
+            drag.0 = None;
+        }),
+        On::<Pointer<PointerCancel>>::listener_component_mut::<ToolWindowDrag>(move |_, drag| {
+            drag.0 = None;
+        }),
+    )
+}
+
+impl ViewTemplate for ToolWindow {
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let name = self.name.clone();
+        let title_id = cx.create_entity();
+        let grip_id = cx.create_entity();
+
+        // Register the window so that its default position is used when it is first opened.
+        cx.create_effect(
+            |world, (name, rect)| {
+                // Check first, so that change detection doesn't fire for known windows.
+                if world.resource::<ToolWindows>().get(&name).is_none() {
+                    world.resource_mut::<ToolWindows>().register(&name, rect);
+                }
+            },
+            (name.clone(), self.default_rect),
+        );
+
+        let tool_windows = cx.use_resource::<ToolWindows>();
+        let state = tool_windows.get(&name).copied().unwrap_or_default();
+        let z_index = tool_windows.z_index(&name);
+
+        let min_size = self.min_size;
+
+        let on_minimize = cx.create_callback({
+            let name = name.clone();
+            move |mut tool_windows: ResMut<ToolWindows>| {
+                let minimized = tool_windows.get(&name).is_some_and(|state| state.minimized);
+                tool_windows.set_minimized(&name, !minimized);
+            }
+        });
+        let on_close = cx.create_callback({
+            let name = name.clone();
+            move |mut tool_windows: ResMut<ToolWindows>| {
+                tool_windows.close(&name);
+            }
+        });
+
+        let title_bar = Element::<NodeBundle>::for_entity(title_id)
+            .named("ToolWindow::TitleBar")
+            .style(style_title_bar)
+            .insert_dyn(
+                move |name| {
+                    drag_handlers(title_id, name, |tool_windows, name, start, distance| {
+                        tool_windows.move_to(name, Vec2::new(start.left, start.top) + distance);
+                    })
+                },
+                name.clone(),
+            )
+            .children((
+                Element::<NodeBundle>::new()
+                    .style((text_default, style_title))
+                    .children(self.title.clone()),
+                IconButton::new("embedded://bevy_quill_obsidian/assets/icons/remove.png")
+                    .size(Size::Xs)
+                    .minimal(true)
+                    .on_click(on_minimize),
+                IconButton::new("embedded://bevy_quill_obsidian/assets/icons/close.png")
+                    .size(Size::Xs)
+                    .minimal(true)
+                    .on_click(on_close),
+            ));
+
+        let resize_grip = Element::<NodeBundle>::for_entity(grip_id)
+            .named("ToolWindow::ResizeGrip")
+            .style(style_resize_grip)
+            .insert_dyn(
+                move |name| {
+                    drag_handlers(grip_id, name, move |tool_windows, name, start, distance| {
+                        let size = Vec2::new(start.width, start.height) + distance;
+                        tool_windows.resize(name, size.max(min_size));
+                    })
+                },
+                name.clone(),
+            );
+
+        Cond::new(
+            state.open,
+            Element::<NodeBundle>::new()
+                .named("ToolWindow")
+                .style(style_tool_window)
+                .style_dyn(
+                    |state, sb| {
+                        sb.left(state.left).top(state.top).width(state.width);
+                        if state.minimized {
+                            sb.height(ui::Val::Auto);
+                        } else {
+                            sb.height(state.height);
+                        }
+                    },
+                    state,
+                )
+                .insert_dyn(
+                    |(name, z_index)| {
+                        (
+                            ZIndex::Global(z_index),
+                            On::<Pointer<Down>>::run(move |world: &mut World| {
+                                world.resource_mut::<ToolWindows>().raise(&name);
+                            }),
+                        )
+                    },
+                    (name, z_index),
+                )
+                .children((
+                    title_bar,
+                    Cond::new(
+                        !state.minimized,
+                        (
+                            Element::<NodeBundle>::new()
+                                .named("ToolWindow::Body")
+                                .style(style_body)
+                                .children(self.children.clone()),
+                            resize_grip,
+                        ),
+                        (),
+                    ),
+                )),
+            (),
+        )
+    }
+}
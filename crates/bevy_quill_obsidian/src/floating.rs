@@ -1,10 +1,16 @@
 use bevy::{
-    ecs::{component::Component, entity::Entity, query::Without, system::Query},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::Without,
+        system::{Query, ResMut},
+    },
     math::Rect,
     transform::components::GlobalTransform,
     ui::{self, Node, Style},
     window::Window,
 };
+use bevy_quill_core::LayoutPassRequest;
 
 /// Which side of the anchor element the floating element should be placed.
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
@@ -81,10 +87,14 @@ impl Clone for Floating {
     }
 }
 
+/// Position floating elements relative to their anchors. This runs after layout, since it
+/// needs the size of both; if an element moves, a second layout pass is requested so that it
+/// appears in the right place on the first frame it is shown.
 pub(crate) fn position_floating(
     mut query: Query<(&mut Style, &Node, &Floating, &GlobalTransform)>,
     anchor_query: Query<(&Node, &GlobalTransform), Without<Floating>>,
     windows: Query<&Window>,
+    mut layout_pass: ResMut<LayoutPassRequest>,
 ) {
     let Ok(window) = windows.get_single() else {
         return;
@@ -218,18 +228,30 @@ pub(crate) fn position_floating(
                 best_rect.max.y += window_rect.max.y - best_rect.max.y;
             }
 
-            style.left = ui::Val::Px(best_rect.min.x);
-            style.top = ui::Val::Px(best_rect.min.y);
-            if best_position.stretch {
-                match best_position.side {
-                    FloatSide::Top | FloatSide::Bottom => {
-                        style.min_width = ui::Val::Px(best_rect.width());
-                    }
-
-                    FloatSide::Left | FloatSide::Right => {
-                        style.min_height = ui::Val::Px(best_rect.height());
-                    }
+            let left = ui::Val::Px(best_rect.min.x);
+            let top = ui::Val::Px(best_rect.min.y);
+            let (min_width, min_height) = match best_position.side {
+                FloatSide::Top | FloatSide::Bottom if best_position.stretch => {
+                    (ui::Val::Px(best_rect.width()), style.min_height)
                 }
+                FloatSide::Left | FloatSide::Right if best_position.stretch => {
+                    (style.min_width, ui::Val::Px(best_rect.height()))
+                }
+                _ => (style.min_width, style.min_height),
+            };
+
+            // Only touch the style if the element has moved, so that layout is not
+            // recomputed every frame.
+            if style.left != left
+                || style.top != top
+                || style.min_width != min_width
+                || style.min_height != min_height
+            {
+                style.left = left;
+                style.top = top;
+                style.min_width = min_width;
+                style.min_height = min_height;
+                layout_pass.request();
             }
         }
     }
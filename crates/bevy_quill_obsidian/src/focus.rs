@@ -31,7 +31,7 @@ pub struct KeyCharEvent {
 }
 
 /// Bubbling event for key press.
-#[derive(Clone, Event, EntityEvent)]
+#[derive(Clone, PartialEq, Event, EntityEvent)]
 #[can_bubble]
 pub struct KeyPressEvent {
     /// The target of the event
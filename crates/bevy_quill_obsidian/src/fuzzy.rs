@@ -0,0 +1,249 @@
+use std::{cmp::Reverse, ops::Range};
+
+/// Score for each character of the pattern which is matched.
+const SCORE_MATCH: i32 = 16;
+
+/// Bonus for a match at the start of a word, such as after a space or underscore, or at a
+/// lower-to-upper case transition.
+const BONUS_WORD_START: i32 = 8;
+
+/// Bonus for a match which immediately follows the previous match.
+const BONUS_CONSECUTIVE: i32 = 4;
+
+/// Penalty for each character skipped between matches.
+const PENALTY_GAP: i32 = 1;
+
+/// Maximum penalty for a single gap, so that long candidates aren't penalized too much.
+const MAX_GAP_PENALTY: i32 = 8;
+
+/// The result of matching a pattern against a candidate string.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// How well the pattern matched. Higher is better.
+    pub score: i32,
+
+    /// Byte offsets of the characters of the candidate which matched the pattern, in order.
+    pub indices: Vec<usize>,
+}
+
+impl FuzzyMatch {
+    /// Return the matched characters of `text` as byte ranges, with adjacent characters
+    /// merged. `text` should be the candidate which was matched. These ranges can be passed
+    /// to [`HighlightedText`](crate::controls::HighlightedText).
+    pub fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for &index in self.indices.iter() {
+            let Some(ch) = text.get(index..).and_then(|rest| rest.chars().next()) else {
+                continue;
+            };
+            let end = index + ch.len_utf8();
+            match ranges.last_mut() {
+                Some(last) if last.end == index => last.end = end,
+                _ => ranges.push(index..end),
+            }
+        }
+        ranges
+    }
+}
+
+/// True if two characters are equal, ignoring case.
+fn chars_match(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// True if `ch` begins a word, given the character before it.
+fn is_word_start(prev: Option<char>, ch: char) -> bool {
+    match prev {
+        None => true,
+        Some(prev) => {
+            (!prev.is_alphanumeric() && ch.is_alphanumeric())
+                || (prev.is_lowercase() && ch.is_uppercase())
+                || (!prev.is_numeric() && ch.is_numeric())
+        }
+    }
+}
+
+/// Match `pattern` against `candidate`, ignoring case. The characters of the pattern must
+/// appear in the candidate in order, but not necessarily next to each other, so "tgl" matches
+/// "Toggle Grid Lines". Returns `None` if the candidate doesn't match.
+///
+/// Matches are scored so that the best candidates can be listed first: matches at the start of
+/// words, and runs of consecutive matches, score higher, while gaps between matches score lower.
+/// An empty pattern matches every candidate with a score of zero.
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch::default());
+    }
+    let chars: Vec<(usize, char)> = candidate.char_indices().collect();
+
+    // Find the first position at which the whole pattern has been matched.
+    let mut p = 0;
+    let mut end = None;
+    for (i, (_, ch)) in chars.iter().enumerate() {
+        if chars_match(*ch, pattern[p]) {
+            p += 1;
+            if p == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // Scan backwards from there to find the shortest match which ends at that position.
+    let mut positions = vec![0; pattern.len()];
+    let mut p = pattern.len();
+    for i in (0..=end).rev() {
+        if chars_match(chars[i].1, pattern[p - 1]) {
+            p -= 1;
+            positions[p] = i;
+            if p == 0 {
+                break;
+            }
+        }
+    }
+
+    // The backwards scan favors late matches within the window; move each match forward to a
+    // word start if there is one before the next match, which reads better when highlighted.
+    for k in 0..positions.len() {
+        let lower = if k == 0 { 0 } else { positions[k - 1] + 1 };
+        let current = positions[k];
+        let prev_char = |i: usize| if i == 0 { None } else { Some(chars[i - 1].1) };
+        if is_word_start(prev_char(current), chars[current].1) {
+            continue;
+        }
+        if let Some(i) = (lower..current).find(|&i| {
+            chars_match(chars[i].1, pattern[k]) && is_word_start(prev_char(i), chars[i].1)
+        }) {
+            positions[k] = i;
+        }
+    }
+
+    let mut score = 0;
+    for (k, &i) in positions.iter().enumerate() {
+        score += SCORE_MATCH;
+        let prev = if i == 0 { None } else { Some(chars[i - 1].1) };
+        if is_word_start(prev, chars[i].1) {
+            score += BONUS_WORD_START;
+        }
+        if k > 0 {
+            let gap = (i - positions[k - 1] - 1) as i32;
+            if gap == 0 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= (gap * PENALTY_GAP).min(MAX_GAP_PENALTY);
+            }
+        } else {
+            // Matches which start later in the candidate are slightly worse.
+            score -= (i as i32 * PENALTY_GAP).min(MAX_GAP_PENALTY);
+        }
+    }
+
+    Some(FuzzyMatch {
+        score,
+        indices: positions.iter().map(|&i| chars[i].0).collect(),
+    })
+}
+
+/// Match `pattern` against each of `items`, and return the items which match, best first.
+/// `key` returns the text to match for an item. Items with equal scores keep their original
+/// order.
+///
+/// ```ignore
+/// let results = fuzzy_filter(&query, operators.iter(), |op| op.name.as_str());
+/// for (op, m) in results {
+///     HighlightedText::new(op.name.clone()).ranges(m.ranges(&op.name))
+/// }
+/// ```
+pub fn fuzzy_filter<T>(
+    pattern: &str,
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> &str,
+) -> Vec<(T, FuzzyMatch)> {
+    let mut results: Vec<(T, FuzzyMatch)> = items
+        .into_iter()
+        .filter_map(|item| {
+            let m = fuzzy_match(pattern, key(&item))?;
+            Some((item, m))
+        })
+        .collect();
+    results.sort_by_key(|(_, m)| Reverse(m.score));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_pattern() {
+        assert_eq!(fuzzy_match("", "anything"), Some(FuzzyMatch::default()));
+        assert_eq!(fuzzy_match("", ""), Some(FuzzyMatch::default()));
+
+        // Every item matches, in the original order.
+        let results = fuzzy_filter("", ["b", "a", "c"], |item| item);
+        let items: Vec<_> = results.iter().map(|(item, _)| *item).collect();
+        assert_eq!(items, ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(fuzzy_match("grid", "Gird"), None);
+        assert_eq!(fuzzy_match("grid", ""), None);
+        assert_eq!(fuzzy_match("gridd", "Grid"), None);
+    }
+
+    #[test]
+    fn test_case_folding() {
+        let lower = fuzzy_match("grid", "GRID").unwrap();
+        let upper = fuzzy_match("GRID", "grid").unwrap();
+        assert_eq!(lower.indices, [0, 1, 2, 3]);
+        assert_eq!(lower, upper);
+
+        // Non-ASCII characters are folded too, and ranges are byte offsets.
+        let m = fuzzy_match("É", "Café").unwrap();
+        assert_eq!(m.indices, [3]);
+        let ranges = m.ranges("Café");
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0], 3..5);
+    }
+
+    #[test]
+    fn test_non_contiguous() {
+        let m = fuzzy_match("gl", "Grid Lines").unwrap();
+        assert_eq!(m.indices, [0, 5]);
+        assert_eq!(m.ranges("Grid Lines"), [0..1, 5..6]);
+
+        // Adjacent matches are merged into a single range.
+        let m = fuzzy_match("grl", "Grid Lines").unwrap();
+        assert_eq!(m.ranges("Grid Lines"), [0..2, 5..6]);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let results = fuzzy_filter(
+            "grid",
+            ["agrarian kid", "Gird", "Show Grid", "Grid"],
+            |item| item,
+        );
+        let items: Vec<_> = results.iter().map(|(item, _)| *item).collect();
+        // An exact match is best, then a match at a later word start, then a scattered match.
+        assert_eq!(items, ["Grid", "Show Grid", "agrarian kid"]);
+
+        // Consecutive matches score higher than gaps.
+        let consecutive = fuzzy_match("ab", "abxx").unwrap();
+        let gap = fuzzy_match("ab", "axxb").unwrap();
+        assert!(consecutive.score > gap.score);
+
+        // Word starts score higher than matches in the middle of a word.
+        let word_start = fuzzy_match("b", "a b").unwrap();
+        let middle = fuzzy_match("b", "aab").unwrap();
+        assert!(word_start.score > middle.score);
+
+        // Items with equal scores keep their original order.
+        let results = fuzzy_filter("a", ["ya", "xa"], |item| item);
+        assert_eq!(results[0].1.score, results[1].1.score);
+        assert_eq!(results[0].0, "ya");
+    }
+}
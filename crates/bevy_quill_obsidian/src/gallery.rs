@@ -0,0 +1,303 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    controls::{
+        Button, ButtonVariant, Checkbox, DisclosureToggle, IconButton, MenuButton, MenuItem,
+        MenuPopup, Pill, PillVariant, ScrollView, Slider, SpinBox,
+    },
+    size::Size,
+    typography,
+};
+
+/// The sizes which can be selected in the gallery toolbar.
+const SIZES: [Size; 5] = [Size::Xs, Size::Sm, Size::Md, Size::Lg, Size::Xl];
+
+/// Plugin which displays a gallery of all the widgets, each shown in both its normal and
+/// disabled state. Hover and focus states can be seen by moving the pointer over a widget,
+/// or by pressing Tab. The toolbar adjusts the widget size and the UI scale.
+///
+/// This is useful for checking the appearance of widgets after making changes, and for
+/// authors of custom themes:
+///
+/// ```ignore
+/// App::new()
+///     .add_plugins((DefaultPlugins, DefaultPickingPlugins, QuillPlugin, ObsidianUiPlugin))
+///     .add_plugins(GalleryPlugin)
+///     .run();
+/// ```
+pub struct GalleryPlugin;
+
+impl Plugin for GalleryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_gallery);
+    }
+}
+
+fn spawn_gallery(mut commands: Commands) {
+    let camera = commands.spawn(Camera2dBundle::default()).id();
+    commands.spawn(Gallery { camera }.to_root());
+}
+
+fn style_gallery(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .position(ui::PositionType::Absolute)
+        .left(0)
+        .right(0)
+        .top(0)
+        .bottom(0)
+        .background_color(colors::BACKGROUND);
+}
+
+fn style_toolbar(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .column_gap(4)
+        .padding(6)
+        .background_color(colors::U2);
+}
+
+fn style_body(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.);
+}
+
+fn style_rows(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .row_gap(8)
+        .padding(8);
+}
+
+fn style_row(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .min_height(32);
+}
+
+fn style_cell(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .width(220);
+}
+
+fn style_label(ss: &mut StyleBuilder) {
+    ss.width(140).color(colors::DIM);
+}
+
+/// One row of the gallery, showing a widget in each of its states.
+#[derive(Clone, PartialEq)]
+struct GalleryRow {
+    name: &'static str,
+    normal: ViewChild,
+    disabled: ViewChild,
+}
+
+impl GalleryRow {
+    /// Create a row from a function which builds the widget, given whether it is disabled.
+    fn new<V: IntoViewChild>(name: &'static str, widget: impl Fn(bool) -> V) -> Self {
+        Self {
+            name,
+            normal: widget(false).into_view_child(),
+            disabled: widget(true).into_view_child(),
+        }
+    }
+}
+
+impl ViewTemplate for GalleryRow {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new().style(style_row).children((
+            Element::<NodeBundle>::new()
+                .style(style_label)
+                .children(self.name),
+            Element::<NodeBundle>::new()
+                .style(style_cell)
+                .children(self.normal.clone()),
+            Element::<NodeBundle>::new()
+                .style(style_cell)
+                .children(self.disabled.clone()),
+        ))
+    }
+}
+
+/// The gallery view which is mounted by [`GalleryPlugin`].
+#[derive(Clone, PartialEq)]
+pub struct Gallery {
+    /// The camera used to display the gallery.
+    pub camera: Entity,
+}
+
+impl ViewTemplate for Gallery {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let size = cx.create_mutable(Size::Md);
+        let checked = cx.create_mutable(true);
+        let expanded = cx.create_mutable(false);
+        let value = cx.create_mutable::<f32>(50.);
+        let on_size =
+            SIZES.map(|s| cx.create_callback(move |world: &mut World| size.set(world, s)));
+        let on_checked = cx.create_callback(move |v: In<bool>, world: &mut World| {
+            checked.set(world, *v);
+        });
+        let on_expanded = cx.create_callback(move |v: In<bool>, world: &mut World| {
+            expanded.set(world, *v);
+        });
+        let on_value = cx.create_callback(move |v: In<f32>, world: &mut World| {
+            value.set(world, *v);
+        });
+        let on_scale = cx.create_callback(|v: In<f32>, mut scale: ResMut<UiScale>| {
+            scale.0 = *v;
+        });
+
+        let sz = size.get(cx);
+        let scale = cx.use_resource::<UiScale>().0;
+        let checked = checked.get(cx);
+        let expanded = expanded.get(cx);
+        let value = value.get(cx);
+
+        Element::<NodeBundle>::new()
+            .named("Gallery")
+            .insert_dyn(TargetCamera, self.camera)
+            .style((typography::text_default, style_gallery))
+            .children((
+                Element::<NodeBundle>::new().style(style_toolbar).children((
+                    "Size:",
+                    For::index(&SIZES, move |s, index| {
+                        Button::new()
+                            .size(Size::Sm)
+                            .selected(*s == sz)
+                            .on_click(on_size[index])
+                            .children(format!("{:?}", s))
+                    }),
+                    "UI Scale:",
+                    Slider::new()
+                        .range(0.5..=2.)
+                        .precision(2)
+                        .value(scale)
+                        .style(|ss: &mut StyleBuilder| {
+                            ss.width(120);
+                        })
+                        .on_change(on_scale),
+                )),
+                ScrollView::new()
+                    .style(style_body)
+                    .content_style(style_rows)
+                    .children((
+                        Element::<NodeBundle>::new().style(style_row).children((
+                            Element::<NodeBundle>::new().style(style_label),
+                            Element::<NodeBundle>::new()
+                                .style(style_cell)
+                                .children("Normal"),
+                            Element::<NodeBundle>::new()
+                                .style(style_cell)
+                                .children("Disabled"),
+                        )),
+                        GalleryRow::new("Button", |disabled| {
+                            Button::new()
+                                .size(sz)
+                                .disabled(disabled)
+                                .children("Default")
+                        }),
+                        GalleryRow::new("Button (primary)", |disabled| {
+                            Button::new()
+                                .size(sz)
+                                .variant(ButtonVariant::Primary)
+                                .disabled(disabled)
+                                .children("Primary")
+                        }),
+                        GalleryRow::new("Button (danger)", |disabled| {
+                            Button::new()
+                                .size(sz)
+                                .variant(ButtonVariant::Danger)
+                                .disabled(disabled)
+                                .children("Danger")
+                        }),
+                        GalleryRow::new("Button (selected)", |disabled| {
+                            Button::new()
+                                .size(sz)
+                                .selected(true)
+                                .disabled(disabled)
+                                .children("Selected")
+                        }),
+                        GalleryRow::new("Button (minimal)", |disabled| {
+                            Button::new()
+                                .size(sz)
+                                .minimal(true)
+                                .disabled(disabled)
+                                .children("Minimal")
+                        }),
+                        GalleryRow::new("IconButton", |disabled| {
+                            IconButton::new("embedded://bevy_quill_obsidian/assets/icons/add.png")
+                                .size(sz)
+                                .disabled(disabled)
+                        }),
+                        GalleryRow::new("MenuButton", |disabled| {
+                            MenuButton::new()
+                                .size(sz)
+                                .disabled(disabled)
+                                .children("Menu")
+                                .popup(MenuPopup::new().children((
+                                    MenuItem::new().label("First"),
+                                    MenuItem::new().label("Second"),
+                                )))
+                        }),
+                        GalleryRow::new("Checkbox", |disabled| {
+                            Checkbox::new()
+                                .checked(checked)
+                                .disabled(disabled)
+                                .on_change(on_checked)
+                                .label("Checkbox")
+                        }),
+                        GalleryRow::new("DisclosureToggle", |disabled| {
+                            DisclosureToggle::new()
+                                .size(sz)
+                                .expanded(expanded)
+                                .disabled(disabled)
+                                .on_change(on_expanded)
+                        }),
+                        GalleryRow::new("Slider", |disabled| {
+                            Slider::new()
+                                .range(0. ..=100.)
+                                .value(value)
+                                .disabled(disabled)
+                                .style(|ss: &mut StyleBuilder| {
+                                    ss.width(160);
+                                })
+                                .on_change(on_value)
+                        }),
+                        GalleryRow::new("SpinBox", |disabled| {
+                            SpinBox::new()
+                                .range(0. ..=100.)
+                                .value(value)
+                                .disabled(disabled)
+                                .style(|ss: &mut StyleBuilder| {
+                                    ss.width(160);
+                                })
+                                .on_change(on_value)
+                        }),
+                        // Pills aren't interactive, so both columns are the same.
+                        GalleryRow::new("Pill", |_| {
+                            (
+                                Pill::new().size(sz).children("Default"),
+                                Pill::new()
+                                    .size(sz)
+                                    .variant(PillVariant::Success)
+                                    .children("Success"),
+                                Pill::new()
+                                    .size(sz)
+                                    .variant(PillVariant::Danger)
+                                    .children("Danger"),
+                            )
+                        }),
+                    )),
+            ))
+    }
+}
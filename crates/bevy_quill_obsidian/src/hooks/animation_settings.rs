@@ -0,0 +1,21 @@
+use bevy_quill_core::Cx;
+
+use crate::animation::AnimationSettings;
+
+/// Trait which adds `use_animation_settings` to [`Cx`].
+pub trait UseAnimationSettings {
+    /// Return the global [`AnimationSettings`], and re-run the presenter when they change.
+    /// Custom widgets which animate should use this to scale their durations, and to skip
+    /// their animations when reduced motion is enabled:
+    ///
+    /// ```ignore
+    /// let duration = cx.use_animation_settings().duration(AnimationPreset::DIALOG.duration);
+    /// ```
+    fn use_animation_settings(&self) -> &AnimationSettings;
+}
+
+impl<'p, 'w> UseAnimationSettings for Cx<'p, 'w> {
+    fn use_animation_settings(&self) -> &AnimationSettings {
+        self.use_resource::<AnimationSettings>()
+    }
+}
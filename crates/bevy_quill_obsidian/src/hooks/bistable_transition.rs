@@ -1,12 +1,15 @@
 use bevy::prelude::*;
 use bevy_quill_core::Cx;
 
+use crate::animation::AnimationSettings;
+
 /// Plugin that runs the timers for bistable transitions.
 pub struct BistableTransitionPlugin;
 
 impl Plugin for BistableTransitionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, enter_exit_state_machine);
+        app.init_resource::<AnimationSettings>()
+            .add_systems(Update, enter_exit_state_machine);
     }
 }
 
@@ -72,7 +75,8 @@ pub trait CreateBistableTransition {
     ///    transition proceed through the `EnterStart`, `Entering`, and `Entered` states. When
     ///    `open` is `false`, the transition proceeds through the `ExitStart`, `Exiting`, and
     ///    `Exited` states.
-    /// * `delay` - The duration of the transition, in seconds.
+    /// * `delay` - The duration of the transition, in seconds, at normal speed. The actual
+    ///    duration depends on the [`AnimationSettings`].
     fn create_bistable_transition(&mut self, open: bool, delay: f32) -> BistableTransitionState;
 }
 
@@ -111,7 +115,9 @@ impl<'w, 'p> CreateBistableTransition for Cx<'w, 'p> {
 pub fn enter_exit_state_machine(
     mut query: Query<(&mut BistableTransitionStateMachine, &mut TransitionTimer)>,
     time: Res<Time>,
+    settings: Res<AnimationSettings>,
 ) {
+    let delta = settings.scale_delta(time.delta_seconds());
     for (mut ee, mut tt) in query.iter_mut() {
         match ee.state {
             BistableTransitionState::EnterStart => {
@@ -124,7 +130,7 @@ pub fn enter_exit_state_machine(
             }
             BistableTransitionState::Entering => {
                 if ee.open {
-                    tt.timer += time.delta_seconds();
+                    tt.timer += delta;
                     if tt.timer > ee.delay {
                         ee.state = BistableTransitionState::Entered;
                     }
@@ -149,7 +155,7 @@ pub fn enter_exit_state_machine(
                 if ee.open {
                     ee.state = BistableTransitionState::EnterStart;
                 } else {
-                    tt.timer += time.delta_seconds();
+                    tt.timer += delta;
                     if tt.timer > ee.delay {
                         ee.state = BistableTransitionState::Exited;
                     }
@@ -12,20 +12,22 @@ pub trait UseElementRect {
 impl<'p, 'w> UseElementRect for Cx<'p, 'w> {
     fn use_element_rect(&mut self, id: Entity) -> Rect {
         match (
-            self.use_component::<Node>(id),
+            self.use_measured_size(id),
             self.use_component_untracked::<GlobalTransform>(id),
         ) {
-            (Some(node), Some(transform)) => node.logical_rect(transform),
+            (Some(size), Some(transform)) => {
+                Rect::from_center_size(transform.translation().truncate(), size)
+            }
             _ => Rect::new(0., 0., 0., 0.),
         }
     }
 
     fn use_element_size(&mut self, id: Entity) -> Vec2 {
         match (
-            self.use_component::<Node>(id),
+            self.use_measured_size(id),
             self.use_component_untracked::<GlobalTransform>(id),
         ) {
-            (Some(node), Some(transform)) => node.logical_rect(transform).size(),
+            (Some(size), Some(_)) => size,
             _ => Vec2::default(),
         }
     }
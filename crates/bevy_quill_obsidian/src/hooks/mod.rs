@@ -1,8 +1,10 @@
+mod animation_settings;
 mod bistable_transition;
 mod element_rect;
 mod is_focus;
 pub(crate) mod is_hover;
 
+pub use animation_settings::UseAnimationSettings;
 pub use bistable_transition::{
     BistableTransitionPlugin, BistableTransitionState, CreateBistableTransition,
 };
@@ -0,0 +1,217 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonInput, ButtonState,
+    },
+    prelude::*,
+};
+use bevy_quill_core::{Callback, RunCallback};
+
+use crate::fuzzy::fuzzy_match;
+
+/// A key combination, consisting of a key and a set of modifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hotkey {
+    /// The key which triggers the hotkey.
+    pub key: KeyCode,
+    /// Whether either control key must be held down.
+    pub ctrl: bool,
+    /// Whether either shift key must be held down.
+    pub shift: bool,
+    /// Whether either alt key must be held down.
+    pub alt: bool,
+    /// Whether either super (command, windows) key must be held down.
+    pub super_key: bool,
+}
+
+impl Hotkey {
+    /// Construct a new hotkey for a key with no modifiers.
+    pub fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+            super_key: false,
+        }
+    }
+
+    /// Require the control key.
+    pub fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    /// Require the shift key.
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    /// Require the alt key.
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Require the super key.
+    pub fn super_key(mut self) -> Self {
+        self.super_key = true;
+        self
+    }
+
+    /// True if the key was pressed this frame, with exactly the required modifiers held down.
+    pub fn just_pressed(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        keys.just_pressed(self.key)
+            && keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) == self.ctrl
+            && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) == self.shift
+            && keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) == self.alt
+            && keys.any_pressed([KeyCode::SuperLeft, KeyCode::SuperRight]) == self.super_key
+    }
+
+    /// A human-readable description of the key combination, such as `"Ctrl+Shift+S"`.
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        for (held, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.alt, "Alt+"),
+            (self.shift, "Shift+"),
+            (self.super_key, "Super+"),
+        ] {
+            if held {
+                label.push_str(name);
+            }
+        }
+        let key = format!("{:?}", self.key);
+        label.push_str(
+            key.strip_prefix("Key")
+                .or_else(|| key.strip_prefix("Digit"))
+                .or_else(|| key.strip_prefix("Arrow"))
+                .unwrap_or(&key),
+        );
+        label
+    }
+}
+
+/// A hotkey, along with a description of what it does.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HotkeyBinding {
+    /// The key combination.
+    pub hotkey: Hotkey,
+    /// Category under which the hotkey is listed, such as `"Edit"` or `"View"`.
+    pub category: String,
+    /// Description of the action.
+    pub description: String,
+    /// Callback to run when the hotkey is pressed. Bindings without a callback are listed in
+    /// the [`ShortcutOverlay`](crate::controls::ShortcutOverlay), but are handled elsewhere.
+    pub on_press: Option<Callback>,
+}
+
+impl HotkeyBinding {
+    /// Construct a new hotkey binding.
+    pub fn new(
+        hotkey: Hotkey,
+        category: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            hotkey,
+            category: category.into(),
+            description: description.into(),
+            on_press: None,
+        }
+    }
+
+    /// Set the callback to run when the hotkey is pressed.
+    pub fn on_press(mut self, callback: Callback) -> Self {
+        self.on_press = Some(callback);
+        self
+    }
+
+    /// True if `text` [fuzzy matches](crate::fuzzy::fuzzy_match) the binding's description,
+    /// category or key label.
+    pub fn matches(&self, text: &str) -> bool {
+        self.match_score(text).is_some()
+    }
+
+    /// The best [fuzzy match](crate::fuzzy::fuzzy_match) score of `text` against the binding's
+    /// description, category or key label, or `None` if none of them match.
+    pub fn match_score(&self, text: &str) -> Option<i32> {
+        let label = self.hotkey.label();
+        [
+            self.description.as_str(),
+            self.category.as_str(),
+            label.as_str(),
+        ]
+        .into_iter()
+        .filter_map(|field| fuzzy_match(text, field))
+        .map(|m| m.score)
+        .max()
+    }
+}
+
+/// Resource which lists the application's keyboard shortcuts. Bindings which have a callback
+/// are dispatched when pressed, regardless of which element has keyboard focus.
+#[derive(Resource, Default)]
+pub struct HotkeyRegistry {
+    bindings: Vec<HotkeyBinding>,
+}
+
+impl HotkeyRegistry {
+    /// Add a hotkey binding.
+    pub fn add(&mut self, binding: HotkeyBinding) -> &mut Self {
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Iterate over the bindings, in the order in which they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &HotkeyBinding> {
+        self.bindings.iter()
+    }
+
+    /// The distinct categories of the bindings, in the order in which they first appear.
+    pub fn categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = Vec::new();
+        for binding in self.bindings.iter() {
+            if !categories.contains(&binding.category) {
+                categories.push(binding.category.clone());
+            }
+        }
+        categories
+    }
+}
+
+/// Resource which controls whether the [`ShortcutOverlay`](crate::controls::ShortcutOverlay)
+/// is shown. It is toggled by typing `?`.
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub struct ShortcutOverlayOpen(pub bool);
+
+pub(crate) fn dispatch_hotkeys(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    registry: Res<HotkeyRegistry>,
+) {
+    for binding in registry.iter() {
+        if let Some(on_press) = binding.on_press {
+            if binding.hotkey.just_pressed(&keys) {
+                commands.add(move |world: &mut World| world.run_callback(on_press, ()));
+            }
+        }
+    }
+}
+
+pub(crate) fn toggle_shortcut_overlay(
+    mut key_events: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut open: ResMut<ShortcutOverlayOpen>,
+) {
+    for ev in key_events.read() {
+        if ev.state == ButtonState::Pressed && keys.just_pressed(ev.key_code) {
+            if let Key::Character(ref ch) = ev.logical_key {
+                if ch.as_str() == "?" {
+                    open.0 = !open.0;
+                }
+            }
+        }
+    }
+}
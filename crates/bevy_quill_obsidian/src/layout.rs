@@ -0,0 +1,508 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::controls::{AppShellLayout, ToolWindowState, ToolWindows};
+
+/// Version of the layout file format written by this version of the library. This is separate
+/// from the application's own layout version, which is set with [`LayoutPersistence::version`].
+const FORMAT_VERSION: u32 = 1;
+
+/// How long to wait after the last change before writing the layout file, in seconds. This
+/// avoids writing the file on every frame while a splitter is being dragged.
+const SAVE_DELAY: f32 = 1.;
+
+/// A saved splitter position. A position stored as a percentage of the space being split keeps
+/// its proportions when the window is resized, while a pixel position keeps its size.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SplitterPosition {
+    /// Offset of the splitter in logical pixels.
+    Pixels(f32),
+
+    /// Offset of the splitter as a percentage (0 - 100) of the space being split.
+    Percent(f32),
+}
+
+impl SplitterPosition {
+    /// The position in pixels, given the size of the space being split.
+    pub fn to_pixels(self, extent: f32) -> f32 {
+        match self {
+            SplitterPosition::Pixels(px) => px,
+            SplitterPosition::Percent(pct) => pct * extent * 0.01,
+        }
+    }
+
+    /// Construct a position of the same kind as this one from a pixel offset, given the size of
+    /// the space being split.
+    pub fn with_pixels(self, px: f32, extent: f32) -> Self {
+        match self {
+            SplitterPosition::Pixels(_) => SplitterPosition::Pixels(px),
+            SplitterPosition::Percent(_) if extent > 0. => {
+                SplitterPosition::Percent(px * 100. / extent)
+            }
+            SplitterPosition::Percent(pct) => SplitterPosition::Percent(pct),
+        }
+    }
+}
+
+/// The saved state of the [`AppShell`](crate::controls::AppShell) sidebars.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DockLayout {
+    /// Width of the left sidebar in pixels.
+    pub left_width: f32,
+
+    /// Width of the right sidebar in pixels.
+    pub right_width: f32,
+
+    /// Whether the left sidebar is expanded.
+    pub left_open: bool,
+
+    /// Whether the right sidebar is expanded.
+    pub right_open: bool,
+}
+
+impl From<&AppShellLayout> for DockLayout {
+    fn from(shell: &AppShellLayout) -> Self {
+        Self {
+            left_width: shell.left_width,
+            right_width: shell.right_width,
+            left_open: shell.left_open,
+            right_open: shell.right_open,
+        }
+    }
+}
+
+impl From<DockLayout> for AppShellLayout {
+    fn from(dock: DockLayout) -> Self {
+        Self {
+            left_width: dock.left_width,
+            right_width: dock.right_width,
+            left_open: dock.left_open,
+            right_open: dock.right_open,
+        }
+    }
+}
+
+/// Resource which holds the layout state that is saved between sessions. The dock layout and
+/// window size are tracked automatically, as are the tool windows; splitters and panels are
+/// identified by names chosen by the application, which reads and updates them here.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedLayout {
+    /// Version of the file format.
+    format: u32,
+
+    /// Version of the application's layout, see [`LayoutPersistence::version`].
+    pub version: u32,
+
+    /// Splitter positions, by name.
+    pub splitters: BTreeMap<String, SplitterPosition>,
+
+    /// Layout of the [`AppShell`](crate::controls::AppShell) sidebars.
+    pub dock: Option<DockLayout>,
+
+    /// Names of the panels which are open.
+    pub open_panels: BTreeSet<String>,
+
+    /// Logical size of the primary window.
+    pub window_size: Option<(f32, f32)>,
+
+    /// Position, size and visibility of the [`ToolWindow`](crate::controls::ToolWindow)s, by
+    /// name.
+    pub tool_windows: BTreeMap<String, ToolWindowState>,
+}
+
+impl Default for SavedLayout {
+    fn default() -> Self {
+        Self {
+            format: FORMAT_VERSION,
+            version: 0,
+            splitters: BTreeMap::new(),
+            dock: None,
+            open_panels: BTreeSet::new(),
+            window_size: None,
+            tool_windows: BTreeMap::new(),
+        }
+    }
+}
+
+impl SavedLayout {
+    /// The saved position of the named splitter, or `default` if it has not been saved.
+    pub fn splitter(&self, name: &str, default: SplitterPosition) -> SplitterPosition {
+        self.splitters.get(name).copied().unwrap_or(default)
+    }
+
+    /// Store the position of the named splitter.
+    pub fn set_splitter(&mut self, name: &str, position: SplitterPosition) {
+        if self.splitters.get(name) != Some(&position) {
+            self.splitters.insert(name.to_string(), position);
+        }
+    }
+
+    /// Store the position of the named splitter from a pixel offset. The position is stored as
+    /// the same kind as the existing position, or `default` if there is none.
+    pub fn set_splitter_pixels(
+        &mut self,
+        name: &str,
+        px: f32,
+        extent: f32,
+        default: SplitterPosition,
+    ) {
+        let position = self.splitter(name, default).with_pixels(px, extent);
+        self.set_splitter(name, position);
+    }
+
+    /// Whether the named panel was open.
+    pub fn is_panel_open(&self, name: &str) -> bool {
+        self.open_panels.contains(name)
+    }
+
+    /// Record whether the named panel is open.
+    pub fn set_panel_open(&mut self, name: &str, open: bool) {
+        if open != self.is_panel_open(name) {
+            if open {
+                self.open_panels.insert(name.to_string());
+            } else {
+                self.open_panels.remove(name);
+            }
+        }
+    }
+}
+
+/// A function which upgrades a saved layout from one application layout version to the next.
+pub type LayoutMigration = fn(&mut SavedLayout);
+
+/// Plugin which saves the layout of the application when it changes, and restores it on the
+/// next launch. The layout is stored in `layout.ron` within a directory named after the
+/// application in the platform's configuration directory.
+///
+/// When the application's layout changes in an incompatible way, such as renaming a splitter,
+/// increase the [`version`](Self::version) and register a [`migration`](Self::migration) which
+/// converts layouts saved by the older version.
+pub struct LayoutPersistence {
+    app_name: String,
+    version: u32,
+    migrations: Vec<(u32, LayoutMigration)>,
+}
+
+impl LayoutPersistence {
+    /// Create a new layout persistence plugin for the named application.
+    pub fn new(app_name: impl Into<String>) -> Self {
+        Self {
+            app_name: app_name.into(),
+            version: 0,
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Set the current version of the application's layout.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Register a migration which upgrades layouts saved with version `from` to version
+    /// `from + 1`. Layouts which can't be upgraded to the current version are discarded.
+    pub fn migration(mut self, from: u32, migration: LayoutMigration) -> Self {
+        self.migrations.push((from, migration));
+        self
+    }
+
+    /// Path of the layout file, or `None` if the platform has no configuration directory.
+    pub fn path(&self) -> Option<PathBuf> {
+        Some(config_dir()?.join(&self.app_name).join("layout.ron"))
+    }
+
+    fn load(&self, path: &Path) -> Option<SavedLayout> {
+        let text = std::fs::read_to_string(path).ok()?;
+        let mut layout = match ron::from_str::<SavedLayout>(&text) {
+            Ok(layout) => layout,
+            Err(err) => {
+                warn!("Failed to read layout {}: {}", path.display(), err);
+                return None;
+            }
+        };
+        if layout.format > FORMAT_VERSION || layout.version > self.version {
+            warn!(
+                "Layout {} was saved by a newer version, ignoring",
+                path.display()
+            );
+            return None;
+        }
+        while layout.version < self.version {
+            let (_, migration) = self
+                .migrations
+                .iter()
+                .find(|(from, _)| *from == layout.version)?;
+            migration(&mut layout);
+            layout.version += 1;
+        }
+        layout.format = FORMAT_VERSION;
+        Some(layout)
+    }
+}
+
+impl Plugin for LayoutPersistence {
+    fn build(&self, app: &mut App) {
+        let path = self.path();
+        let layout = path
+            .as_ref()
+            .and_then(|path| self.load(path))
+            .unwrap_or_else(|| SavedLayout {
+                version: self.version,
+                ..default()
+            });
+        if let Some(dock) = layout.dock {
+            app.insert_resource(AppShellLayout::from(dock));
+        }
+        app.insert_resource(ToolWindows::from_saved(&layout.tool_windows))
+            .insert_resource(layout)
+            .add_systems(Startup, restore_window_size)
+            .add_systems(
+                Update,
+                (track_dock_layout, track_window_size, track_tool_windows),
+            );
+        if let Some(path) = path {
+            app.insert_resource(LayoutFile(path))
+                .add_systems(Last, save_layout);
+        }
+    }
+}
+
+#[derive(Resource)]
+struct LayoutFile(PathBuf);
+
+/// The platform's directory for per-user configuration files.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        })
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+}
+
+fn restore_window_size(
+    layout: Res<SavedLayout>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if let (Some((width, height)), Ok(mut window)) = (layout.window_size, windows.get_single_mut())
+    {
+        window.resolution.set(width, height);
+    }
+}
+
+fn track_dock_layout(shell: Res<AppShellLayout>, mut layout: ResMut<SavedLayout>) {
+    if shell.is_changed() {
+        let dock = Some(DockLayout::from(shell.as_ref()));
+        if layout.dock != dock {
+            layout.dock = dock;
+        }
+    }
+}
+
+fn track_tool_windows(tool_windows: Res<ToolWindows>, mut layout: ResMut<SavedLayout>) {
+    if tool_windows.is_changed() && layout.tool_windows != *tool_windows.windows() {
+        layout.tool_windows = tool_windows.windows().clone();
+    }
+}
+
+fn track_window_size(
+    mut resized: EventReader<WindowResized>,
+    primary: Query<Entity, With<PrimaryWindow>>,
+    mut layout: ResMut<SavedLayout>,
+) {
+    let Ok(primary) = primary.get_single() else {
+        return;
+    };
+    for event in resized.read() {
+        let size = Some((event.width, event.height));
+        if event.window == primary && layout.window_size != size {
+            layout.window_size = size;
+        }
+    }
+}
+
+/// Write the layout file a short time after the layout changes, or when the app exits.
+fn save_layout(
+    layout: Res<SavedLayout>,
+    file: Res<LayoutFile>,
+    time: Res<Time>,
+    mut exit: EventReader<AppExit>,
+    mut pending: Local<Option<f32>>,
+) {
+    let now = time.elapsed_seconds();
+    if layout.is_changed() && !layout.is_added() {
+        *pending = Some(now + SAVE_DELAY);
+    }
+    let exiting = exit.read().count() > 0;
+    match *pending {
+        Some(deadline) if exiting || now >= deadline => *pending = None,
+        _ => return,
+    }
+    let result = ron::ser::to_string_pretty(layout.as_ref(), default())
+        .map_err(|err| err.to_string())
+        .and_then(|text| {
+            if let Some(dir) = file.0.parent() {
+                std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+            }
+            std::fs::write(&file.0, text).map_err(|err| err.to_string())
+        });
+    if let Err(err) = result {
+        warn!("Failed to save layout {}: {}", file.0.display(), err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a layout file to a temporary directory, and return its path.
+    fn write_layout(name: &str, text: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quill-layout-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.ron", name));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    /// A layout saved by version 0 of an app, before tool windows were saved.
+    const LAYOUT_V0: &str = r#"(
+        format: 1,
+        version: 0,
+        splitters: { "left": Pixels(200.0) },
+        open_panels: ["log"],
+    )"#;
+
+    fn rename_left_splitter(layout: &mut SavedLayout) {
+        if let Some(position) = layout.splitters.remove("left") {
+            layout.splitters.insert("sidebar".to_string(), position);
+        }
+    }
+
+    fn open_inspector(layout: &mut SavedLayout) {
+        layout.set_panel_open("inspector", true);
+    }
+
+    #[test]
+    fn test_splitter_position() {
+        assert_eq!(SplitterPosition::Pixels(120.).to_pixels(400.), 120.);
+        assert_eq!(SplitterPosition::Percent(25.).to_pixels(400.), 100.);
+
+        // The kind of position is kept when it is moved.
+        assert_eq!(
+            SplitterPosition::Pixels(120.).with_pixels(150., 400.),
+            SplitterPosition::Pixels(150.)
+        );
+        assert_eq!(
+            SplitterPosition::Percent(25.).with_pixels(200., 400.),
+            SplitterPosition::Percent(50.)
+        );
+        // A percentage can't be computed without an extent, so it is left unchanged.
+        assert_eq!(
+            SplitterPosition::Percent(25.).with_pixels(200., 0.),
+            SplitterPosition::Percent(25.)
+        );
+    }
+
+    #[test]
+    fn test_set_splitter_pixels() {
+        let mut layout = SavedLayout::default();
+        let default = SplitterPosition::Percent(50.);
+        assert_eq!(layout.splitter("main", default), default);
+        layout.set_splitter_pixels("main", 100., 400., default);
+        assert_eq!(
+            layout.splitter("main", default),
+            SplitterPosition::Percent(25.)
+        );
+        layout.set_splitter("main", SplitterPosition::Pixels(80.));
+        layout.set_splitter_pixels("main", 100., 400., default);
+        assert_eq!(
+            layout.splitter("main", default),
+            SplitterPosition::Pixels(100.)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut layout = SavedLayout::default();
+        layout.set_splitter("main", SplitterPosition::Percent(30.));
+        layout.set_panel_open("log", true);
+        layout.window_size = Some((800., 600.));
+        layout
+            .tool_windows
+            .insert("palette".to_string(), ToolWindowState::default());
+        let text = ron::ser::to_string_pretty(&layout, default()).unwrap();
+        let path = write_layout("round_trip", &text);
+        assert_eq!(LayoutPersistence::new("test").load(&path), Some(layout));
+    }
+
+    #[test]
+    fn test_load_older_version() {
+        let path = write_layout("v0", LAYOUT_V0);
+
+        // Without a version bump, the layout is loaded as is. Fields which weren't saved yet
+        // take their default values.
+        let layout = LayoutPersistence::new("test").load(&path).unwrap();
+        assert_eq!(layout.version, 0);
+        assert_eq!(
+            layout.splitter("left", SplitterPosition::Pixels(0.)),
+            SplitterPosition::Pixels(200.)
+        );
+        assert!(layout.is_panel_open("log"));
+        assert!(layout.tool_windows.is_empty());
+        assert_eq!(layout.dock, None);
+
+        // Each migration is applied in turn to reach the current version.
+        let layout = LayoutPersistence::new("test")
+            .version(2)
+            .migration(1, open_inspector)
+            .migration(0, rename_left_splitter)
+            .load(&path)
+            .unwrap();
+        assert_eq!(layout.version, 2);
+        assert!(!layout.splitters.contains_key("left"));
+        assert_eq!(
+            layout.splitter("sidebar", SplitterPosition::Pixels(0.)),
+            SplitterPosition::Pixels(200.)
+        );
+        assert!(layout.is_panel_open("log"));
+        assert!(layout.is_panel_open("inspector"));
+    }
+
+    #[test]
+    fn test_load_unusable() {
+        // A layout which can't be migrated to the current version is discarded.
+        let path = write_layout("v0_unmigrated", LAYOUT_V0);
+        let persistence = LayoutPersistence::new("test")
+            .version(2)
+            .migration(0, rename_left_splitter);
+        assert_eq!(persistence.load(&path), None);
+
+        // So is a layout saved by a newer version of the app or the library.
+        let path = write_layout("newer_version", "(format: 1, version: 3)");
+        assert_eq!(persistence.load(&path), None);
+        let path = write_layout("newer_format", "(format: 2, version: 2)");
+        assert_eq!(persistence.load(&path), None);
+
+        // And one which can't be read.
+        let path = write_layout("invalid", "not a layout");
+        assert_eq!(persistence.load(&path), None);
+        assert_eq!(persistence.load(&path.with_extension("missing")), None);
+    }
+}
@@ -1,11 +1,14 @@
 #![feature(impl_trait_in_assoc_type, associated_type_defaults)]
-use bevy::{asset::embedded_asset, prelude::*};
+use bevy::{asset::embedded_asset, prelude::*, transform::TransformSystem, ui::UiSystem};
 
 mod rounded_corners;
 
 /// Utilities for animating component properties.
 pub mod animation;
 
+/// Sounds played when the user interacts with widgets.
+pub mod audio;
+
 /// Module containing standard color definitions.
 #[allow(missing_docs)]
 pub mod colors;
@@ -22,15 +25,30 @@ pub mod focus;
 /// Utilities for floating popups.
 pub mod floating;
 
+/// Fuzzy matching of search text, for filtering lists.
+pub mod fuzzy;
+
+/// A showcase of all the widgets, for testing and theming.
+pub mod gallery;
+
 /// Module containing extensions to `Cx`.
 pub mod hooks;
 
+/// Application-wide keyboard shortcuts.
+pub mod hotkeys;
+
+/// Saving and restoring the layout of panels, splitters and windows between sessions.
+pub mod layout;
+
 /// Module containing custom materials.
 mod materials;
 
 /// Utilities for managing scrolling views.
 pub mod scrolling;
 
+/// User preferences which are saved between sessions.
+pub mod settings;
+
 /// Module containing standard sizes.
 pub mod size;
 
@@ -47,8 +65,11 @@ pub mod prelude {
 }
 
 use bevy_mod_picking::prelude::EventListenerPlugin;
-use controls::{MenuCloseEvent, RecentColors};
-use materials::{GradientRectMaterial, SliderRectMaterial, SwatchRectMaterial};
+use bevy_quill_core::QuillSet;
+use controls::{
+    AppShellLayout, ColorPalette, DateLocale, Dialogs, MenuCloseEvent, RecentColors, ToolWindows,
+};
+use materials::{GradientRectMaterial, ImageViewMaterial, SliderRectMaterial, SwatchRectMaterial};
 pub use rounded_corners::RoundedCorners;
 
 pub use hooks::is_hover::UseIsHover as _;
@@ -83,10 +104,12 @@ impl Plugin for ObsidianUiPlugin {
         embedded_asset!(app, "assets/icons/tune.png");
         embedded_asset!(app, "assets/icons/undo.png");
         embedded_asset!(app, "assets/shaders/gradient_rect.wgsl");
+        embedded_asset!(app, "assets/shaders/image_view.wgsl");
         embedded_asset!(app, "assets/shaders/swatch_rect.wgsl");
         embedded_asset!(app, "assets/shaders/slider_rect.wgsl");
         app.add_plugins((
             UiMaterialPlugin::<GradientRectMaterial>::default(),
+            UiMaterialPlugin::<ImageViewMaterial>::default(),
             UiMaterialPlugin::<SliderRectMaterial>::default(),
             UiMaterialPlugin::<SwatchRectMaterial>::default(),
             hooks::BistableTransitionPlugin,
@@ -102,12 +125,41 @@ impl Plugin for ObsidianUiPlugin {
             Update,
             (
                 scrolling::handle_scroll_events,
-                scrolling::update_scroll_positions,
+                (
+                    scrolling::scroll_focus_into_view,
+                    scrolling::reveal_scroll_targets,
+                    scrolling::animate_scroll_positions,
+                    scrolling::update_scroll_callbacks,
+                    scrolling::update_scroll_positions,
+                    scrolling::update_sticky_headers,
+                )
+                    .chain(),
+                scrolling::init_sticky_headers,
                 hooks::is_hover::update_hover_states,
                 cursor::update_cursor,
+                controls::animate_skeletons,
+                controls::clamp_tool_windows,
+                controls::update_viewed_images,
+                hotkeys::dispatch_hotkeys,
+                hotkeys::toggle_shortcut_overlay,
             ),
         )
+        .init_resource::<audio::InteractionSounds>()
         .init_resource::<RecentColors>()
-        .add_systems(PostUpdate, floating::position_floating);
+        .init_resource::<ColorPalette>()
+        .init_resource::<AppShellLayout>()
+        .init_resource::<DateLocale>()
+        .init_resource::<Dialogs>()
+        .init_resource::<ToolWindows>()
+        .init_resource::<hotkeys::HotkeyRegistry>()
+        .init_resource::<hotkeys::ShortcutOverlayOpen>()
+        .insert_resource(typography::type_scale())
+        .add_systems(
+            PostUpdate,
+            floating::position_floating
+                .after(UiSystem::Layout)
+                .after(TransformSystem::TransformPropagate)
+                .before(QuillSet::Relayout),
+        );
     }
 }
@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::render::render_resource::*;
+
+#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+pub(crate) struct ImageViewMaterial {
+    /// Which channels to display.
+    #[uniform(0)]
+    pub(crate) channels: Vec4,
+    /// x: 1.0 if a single channel is isolated, y: opacity of the pixel grid.
+    #[uniform(1)]
+    pub(crate) options: Vec4,
+    #[texture(2)]
+    pub(crate) image: Handle<Image>,
+}
+
+impl UiMaterial for ImageViewMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://bevy_quill_obsidian/assets/shaders/image_view.wgsl".into()
+    }
+}
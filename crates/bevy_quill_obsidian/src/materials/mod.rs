@@ -1,11 +1,13 @@
 // mod dot_grid;
 // mod draw_path;
 mod gradient_rect;
+mod image_view;
 mod slider_rect;
 mod swatch_rect;
 
 // pub(crate) use dot_grid::DotGridMaterial;
 // pub(crate) use draw_path::*;
 pub(crate) use gradient_rect::GradientRectMaterial;
+pub(crate) use image_view::ImageViewMaterial;
 pub(crate) use slider_rect::SliderRectMaterial;
 pub(crate) use swatch_rect::SwatchRectMaterial;
@@ -1,5 +1,8 @@
-use bevy::{input::mouse::MouseWheel, prelude::*, render::view::visibility, ui};
+use bevy::{a11y::Focus, input::mouse::MouseWheel, prelude::*, render::view::visibility, ui};
 use bevy_mod_picking::{focus::HoverMap, pointer::PointerId, prelude::EntityEvent};
+use bevy_quill_core::{Callback, RunCallback};
+
+use crate::{animation::AnimationSettings, focus::FocusVisible};
 
 /// Mouse wheel entity event
 #[derive(Clone, Event, EntityEvent, Debug)]
@@ -33,11 +36,24 @@ pub struct ScrollArea {
 
     /// Entity id of the Y scrollbar
     pub id_scrollbar_y: Option<Entity>,
+
+    /// Scroll position that is being animated towards, if any.
+    pub(crate) scroll_target: Option<Vec2>,
+
+    /// Descendant entity which is to be scrolled into view on the next update.
+    pub(crate) reveal: Option<Entity>,
+
+    /// Distance the user has attempted to scroll past the top, for pull-to-refresh.
+    pub(crate) overscroll: f32,
+
+    /// Content height when the end of the content was last reached.
+    pub(crate) reached_end: Option<f32>,
 }
 
 impl ScrollArea {
     /// Offset the current scroll position by the given values.
     pub fn scroll_by(&mut self, dx: f32, dy: f32) {
+        self.scroll_target = None;
         // Apply max constraint first, then min - don't use clamp() here.
         self.scroll_left = (self.scroll_left + dx)
             .min(self.content_size.x - self.visible_size.x)
@@ -49,11 +65,19 @@ impl ScrollArea {
 
     /// Scroll to the given scroll position (values clamped).
     pub fn scroll_to(&mut self, x: f32, y: f32) {
+        self.scroll_target = None;
         // Apply max constraint first, then min - don't use clamp() here.
         self.scroll_left = x.min(self.content_size.x - self.visible_size.x).max(0.);
         self.scroll_top = y.min(self.content_size.y - self.visible_size.y).max(0.);
     }
 
+    /// Scroll the minimum distance needed to make the given descendant entity fully visible,
+    /// plus the margin from [`ScrollIntoView`]. The scroll position is updated on the next
+    /// frame, once the layout of the entity is known.
+    pub fn scroll_into_view(&mut self, entity: Entity) {
+        self.reveal = Some(entity);
+    }
+
     /// Current scroll position
     pub fn scroll_position(&self) -> Vec2 {
         Vec2::new(self.scroll_left, self.scroll_top)
@@ -70,10 +94,87 @@ impl ScrollArea {
     }
 }
 
+/// Options which control how a [`ScrollArea`] scrolls descendant elements into view, either
+/// when they receive keyboard focus or when [`ScrollArea::scroll_into_view`] is called.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct ScrollIntoView {
+    /// Minimum distance to leave between the element and the edges of the visible area.
+    pub margin: f32,
+
+    /// Whether to animate the scroll position, rather than jumping immediately.
+    pub smooth: bool,
+}
+
+impl Default for ScrollIntoView {
+    fn default() -> Self {
+        Self {
+            margin: 8.,
+            smooth: true,
+        }
+    }
+}
+
 /// Marker component indicating this entity is the scrolling content area.
 #[derive(Component, Clone, Default)]
 pub struct ScrollContent;
 
+/// Callbacks which a [`ScrollArea`] invokes in response to the scroll position.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct ScrollCallbacks {
+    /// Called when the visible area comes within `reach_end_threshold` of the bottom of the
+    /// content. This can be used to load more content on demand. The callback is invoked once
+    /// each time the end is reached, and again if the end is still in view after the content
+    /// has changed size, so that the view can be filled one page at a time.
+    pub on_reach_end: Option<Callback>,
+
+    /// Distance from the end of the content at which `on_reach_end` is called.
+    pub reach_end_threshold: f32,
+
+    /// Called when the user scrolls past the top of the content by at least
+    /// `refresh_threshold`. While pulling, the content is displaced to give feedback.
+    pub on_refresh: Option<Callback>,
+
+    /// Distance which the user must pull down in order to trigger `on_refresh`.
+    pub refresh_threshold: f32,
+}
+
+impl Default for ScrollCallbacks {
+    fn default() -> Self {
+        Self {
+            on_reach_end: None,
+            reach_end_threshold: 100.,
+            on_refresh: None,
+            refresh_threshold: 64.,
+        }
+    }
+}
+
+/// Component which makes an element inside a [`ScrollView`](crate::controls::ScrollView) stick
+/// to the top of the visible area while its parent is scrolled through, similar to
+/// `position: sticky` in CSS. The parent element is the "section": once the bottom of the
+/// section scrolls past, the header scrolls away with it, so the header of the next section
+/// appears to push it out.
+///
+/// Typically the header is the first child of each section:
+///
+/// ```ignore
+/// Element::<NodeBundle>::new().children((
+///     Element::<NodeBundle>::new()
+///         .insert(StickyHeader::default())
+///         .style(style_section_header)
+///         .children(category_name),
+///     For::each(entries, |entry| EntryRow(entry.clone())),
+/// ))
+/// ```
+///
+/// The header is drawn over the content of its section, so it should have an opaque
+/// background.
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct StickyHeader {
+    /// Offset currently applied to the element.
+    pub(crate) offset: f32,
+}
+
 /// Marker component indicating this entity is the scrollbar on the X-axis.
 #[derive(Component)]
 pub struct ScrollBar {
@@ -91,6 +192,147 @@ pub struct ScrollBar {
 #[derive(Component)]
 pub struct ScrollBarThumb;
 
+/// Returns the scroll offset needed to bring `target` within `visible`, inset by `margin`. If
+/// the target is larger than the visible area, its top-left corner is aligned instead.
+fn reveal_offset(visible: Rect, target: Rect, margin: f32) -> Vec2 {
+    let axis = |min: f32, max: f32, start: f32, end: f32| {
+        if start - margin < min || end - start + margin * 2. > max - min {
+            start - margin - min
+        } else if end + margin > max {
+            end + margin - max
+        } else {
+            0.
+        }
+    };
+    Vec2::new(
+        axis(visible.min.x, visible.max.x, target.min.x, target.max.x),
+        axis(visible.min.y, visible.max.y, target.min.y, target.max.y),
+    )
+}
+
+/// When keyboard focus moves, scroll the focused element into view within each of its
+/// enclosing scroll areas. Focus changes made with the pointer are ignored, since the element
+/// under the pointer is already visible.
+pub(crate) fn scroll_focus_into_view(
+    focus: Res<Focus>,
+    focus_visible: Res<FocusVisible>,
+    mut query: Query<&mut ScrollArea>,
+    query_parent: Query<&Parent>,
+) {
+    if !focus.is_changed() || !focus_visible.0 {
+        return;
+    }
+    let Some(focus_elt) = focus.0 else {
+        return;
+    };
+    let mut entity = focus_elt;
+    while let Ok(parent) = query_parent.get(entity) {
+        entity = parent.get();
+        if let Ok(mut scrolling) = query.get_mut(entity) {
+            scrolling.scroll_into_view(focus_elt);
+        }
+    }
+}
+
+/// Compute the scroll position for scroll areas which have a pending
+/// [`ScrollArea::scroll_into_view`] request.
+pub(crate) fn reveal_scroll_targets(
+    mut query: Query<(
+        &Node,
+        &GlobalTransform,
+        &mut ScrollArea,
+        Option<&ScrollIntoView>,
+    )>,
+    query_target: Query<(&Node, &GlobalTransform), Without<ScrollArea>>,
+) {
+    for (node, gt, mut scrolling, options) in query.iter_mut() {
+        let Some(target) = scrolling.reveal.take() else {
+            continue;
+        };
+        let Ok((target_node, target_gt)) = query_target.get(target) else {
+            continue;
+        };
+        let options = options.copied().unwrap_or_default();
+        let offset = reveal_offset(
+            node.logical_rect(gt),
+            target_node.logical_rect(target_gt),
+            options.margin,
+        );
+        if offset == Vec2::ZERO {
+            continue;
+        }
+        let position = scrolling.scroll_position() + offset;
+        if options.smooth {
+            let max = (scrolling.content_size - scrolling.visible_size).max(Vec2::ZERO);
+            scrolling.scroll_target = Some(position.min(max).max(Vec2::ZERO));
+        } else {
+            scrolling.scroll_to(position.x, position.y);
+        }
+    }
+}
+
+/// Move animated scroll areas towards their target position.
+pub(crate) fn animate_scroll_positions(
+    mut query: Query<&mut ScrollArea>,
+    time: Res<Time>,
+    settings: Res<AnimationSettings>,
+) {
+    let t = 1. - (-settings.scale_delta(time.delta_seconds()) * 15.).exp();
+    for mut scrolling in query.iter_mut() {
+        let Some(target) = scrolling.scroll_target else {
+            continue;
+        };
+        let position = scrolling.scroll_position().lerp(target, t);
+        if position.distance(target) < 0.5 {
+            scrolling.scroll_left = target.x;
+            scrolling.scroll_top = target.y;
+            scrolling.scroll_target = None;
+        } else {
+            scrolling.scroll_left = position.x;
+            scrolling.scroll_top = position.y;
+        }
+    }
+}
+
+/// Invoke the [`ScrollCallbacks`] of scroll areas, and relax any pull-to-refresh gesture which
+/// is in progress.
+pub(crate) fn update_scroll_callbacks(
+    mut commands: Commands,
+    mut query: Query<(&mut ScrollArea, Option<&ScrollCallbacks>)>,
+    time: Res<Time>,
+) {
+    for (mut scrolling, callbacks) in query.iter_mut() {
+        let callbacks = callbacks.copied().unwrap_or_default();
+
+        match callbacks.on_refresh {
+            Some(on_refresh) if scrolling.overscroll >= callbacks.refresh_threshold => {
+                scrolling.overscroll = 0.;
+                commands.add(move |world: &mut World| world.run_callback(on_refresh, ()));
+            }
+            Some(_) => {
+                let overscroll = scrolling.overscroll * (-time.delta_seconds() * 10.).exp();
+                scrolling.overscroll = if overscroll < 0.5 { 0. } else { overscroll };
+            }
+            None => scrolling.overscroll = 0.,
+        }
+
+        if let Some(on_reach_end) = callbacks.on_reach_end {
+            // Wait until the content has been laid out.
+            let content_height = scrolling.content_size.y;
+            if content_height <= 0. {
+                continue;
+            }
+            let remaining = content_height - scrolling.visible_size.y - scrolling.scroll_top;
+            if remaining > callbacks.reach_end_threshold {
+                scrolling.reached_end = None;
+            } else if scrolling.reached_end != Some(content_height) {
+                scrolling.reached_end = Some(content_height);
+                commands.add(move |world: &mut World| world.run_callback(on_reach_end, ()));
+            }
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 pub(crate) fn update_scroll_positions(
     mut query: Query<(&Node, &mut ScrollArea, &GlobalTransform, &Children)>,
@@ -130,7 +372,7 @@ pub(crate) fn update_scroll_positions(
                 .max(0.);
 
             style.left = ui::Val::Px(-scrolling.scroll_left);
-            style.top = ui::Val::Px(-scrolling.scroll_top);
+            style.top = ui::Val::Px(scrolling.overscroll * 0.5 - scrolling.scroll_top);
         } else {
             scrolling.content_size.x = 0.;
             scrolling.content_size.y = 0.;
@@ -196,6 +438,71 @@ pub(crate) fn update_scroll_positions(
     }
 }
 
+/// Ensure that sticky headers are drawn over the other children of their section.
+pub(crate) fn init_sticky_headers(
+    mut commands: Commands,
+    query: Query<Entity, (Added<StickyHeader>, Without<ZIndex>)>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).insert(ZIndex::Local(1));
+    }
+}
+
+/// Offset sticky headers so that they remain at the top of the visible area. Positions are
+/// measured relative to the scroll content, using the current scroll position rather than the
+/// one from the last layout, so that headers don't lag behind while scrolling.
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_sticky_headers(
+    mut query: Query<(
+        &mut StickyHeader,
+        &mut Style,
+        &Node,
+        &GlobalTransform,
+        &Parent,
+    )>,
+    query_section: Query<(&Node, &GlobalTransform), Without<StickyHeader>>,
+    query_content: Query<(&Node, &GlobalTransform, &Parent), With<ScrollContent>>,
+    query_parent: Query<&Parent>,
+    query_scroll_area: Query<&ScrollArea>,
+) {
+    for (mut sticky, mut style, node, gt, parent) in query.iter_mut() {
+        let Ok((section, section_gt)) = query_section.get(parent.get()) else {
+            continue;
+        };
+
+        // Find the enclosing scroll content.
+        let mut entity = parent.get();
+        let content = loop {
+            if let Ok(content) = query_content.get(entity) {
+                break Some(content);
+            }
+            match query_parent.get(entity) {
+                Ok(parent) => entity = parent.get(),
+                Err(_) => break None,
+            }
+        };
+        let Some((content, content_gt, content_parent)) = content else {
+            continue;
+        };
+        let Ok(scrolling) = query_scroll_area.get(content_parent.get()) else {
+            continue;
+        };
+
+        let content_top = content.logical_rect(content_gt).min.y;
+        let header_rect = node.logical_rect(gt);
+        let header_top = header_rect.min.y - content_top - sticky.offset;
+        let section_bottom = section.logical_rect(section_gt).max.y - content_top;
+        let offset = (scrolling.scroll_top - header_top)
+            .min(section_bottom - header_top - header_rect.height())
+            .max(0.);
+        sticky.offset = offset;
+        // Check the style as well, since it may have been reset by a style builder.
+        if style.top != ui::Val::Px(offset) {
+            style.top = ui::Val::Px(offset);
+        }
+    }
+}
+
 pub(crate) fn handle_scroll_events(
     mut scroll_evr: EventReader<MouseWheel>,
     mut writer: EventWriter<ScrollWheel>,
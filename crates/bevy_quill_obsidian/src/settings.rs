@@ -0,0 +1,263 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_quill_core::Cx;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::layout::config_dir;
+
+/// How long to wait after a setting changes before writing it to disk, in seconds.
+const SAVE_DELAY: f32 = 0.5;
+
+/// A group of user preferences, stored as a resource. Each setting type is saved to its own
+/// file, so adding fields to a setting with `#[serde(default)]` keeps the user's other values.
+///
+/// ```ignore
+/// #[derive(Resource, Reflect, Clone, Default, Serialize, Deserialize)]
+/// #[serde(default)]
+/// struct EditorSettings {
+///     grid_snap: bool,
+///     autosave_interval: f32,
+/// }
+///
+/// impl Setting for EditorSettings {
+///     const KEY: &'static str = "editor";
+///     const LABEL: &'static str = "Editor";
+/// }
+///
+/// app.add_plugins(SettingsPlugin::new("my_app"))
+///     .register_setting::<EditorSettings>();
+/// ```
+pub trait Setting: Resource + Reflect + Default + Clone + Serialize + DeserializeOwned {
+    /// Unique key for this setting, used as the file name.
+    const KEY: &'static str;
+
+    /// Title of the setting in the settings dialog.
+    const LABEL: &'static str;
+}
+
+/// Event which is sent when the value of a setting changes.
+#[derive(Event, Clone)]
+pub struct SettingChanged<T: Setting> {
+    /// The new value of the setting.
+    pub value: T,
+}
+
+/// Type-erased function which modifies a setting through reflection.
+type UpdateSetting = fn(&mut World, &dyn Fn(&mut dyn Reflect));
+
+/// Type-erased accessors for a registered setting.
+#[derive(Clone, Copy)]
+pub struct SettingInfo {
+    /// Unique key of the setting.
+    pub key: &'static str,
+
+    /// Title of the setting in the settings dialog.
+    pub label: &'static str,
+
+    reflect: fn(&World) -> &dyn Reflect,
+    update: UpdateSetting,
+    track: fn(&Cx),
+    reset: fn(&mut World),
+    load: fn(&mut World, &Path),
+}
+
+impl SettingInfo {
+    fn of<T: Setting>() -> Self {
+        Self {
+            key: T::KEY,
+            label: T::LABEL,
+            reflect: reflect_setting::<T>,
+            update: update_setting::<T>,
+            track: track_setting::<T>,
+            reset: reset_setting::<T>,
+            load: load_setting::<T>,
+        }
+    }
+
+    /// The current value of the setting.
+    pub fn reflect<'a>(&self, world: &'a World) -> &'a dyn Reflect {
+        (self.reflect)(world)
+    }
+
+    /// Apply a closure to the value of the setting.
+    pub fn update(&self, world: &mut World, f: &dyn Fn(&mut dyn Reflect)) {
+        (self.update)(world, f)
+    }
+
+    /// Add the setting as a dependency of the current presenter invocation.
+    pub fn track(&self, cx: &Cx) {
+        (self.track)(cx)
+    }
+
+    /// Restore the default value of the setting.
+    pub fn reset(&self, world: &mut World) {
+        (self.reset)(world)
+    }
+}
+
+/// Resource which holds the list of registered settings, and where they are stored.
+#[derive(Resource, Default)]
+pub struct Settings {
+    dir: Option<PathBuf>,
+    entries: Vec<SettingInfo>,
+}
+
+impl Settings {
+    /// The registered settings, in order of registration.
+    pub fn entries(&self) -> &[SettingInfo] {
+        &self.entries
+    }
+
+    /// Directory where the settings are stored, or `None` if settings are not saved.
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Restore the default values of all settings.
+    pub fn reset_all(world: &mut World) {
+        let entries = world.resource::<Settings>().entries.clone();
+        for entry in entries {
+            entry.reset(world);
+        }
+    }
+}
+
+/// Plugin which loads and saves user settings. The settings are stored in a directory named
+/// after the application in the platform's configuration directory.
+pub struct SettingsPlugin {
+    app_name: String,
+}
+
+impl SettingsPlugin {
+    /// Create a new settings plugin for the named application.
+    pub fn new(app_name: impl Into<String>) -> Self {
+        Self {
+            app_name: app_name.into(),
+        }
+    }
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(dir) = config_dir().map(|dir| dir.join(&self.app_name).join("settings")) else {
+            app.init_resource::<Settings>();
+            return;
+        };
+        let world = app.world_mut();
+        let mut settings = world.get_resource_or_insert_with(Settings::default);
+        settings.dir = Some(dir.clone());
+        // Load any settings which were registered before the plugin was added.
+        let entries = settings.entries.clone();
+        for entry in entries {
+            (entry.load)(world, &dir);
+        }
+    }
+}
+
+/// Trait which adds `register_setting` to [`App`].
+pub trait RegisterSetting {
+    /// Register a setting type. The saved value is loaded, if there is one, and the setting
+    /// is saved whenever it changes.
+    fn register_setting<T: Setting>(&mut self) -> &mut Self;
+}
+
+impl RegisterSetting for App {
+    fn register_setting<T: Setting>(&mut self) -> &mut Self {
+        let world = self.world_mut();
+        let mut settings = world.get_resource_or_insert_with(Settings::default);
+        if settings.entries.iter().any(|entry| entry.key == T::KEY) {
+            warn!("Setting {} is already registered", T::KEY);
+            return self;
+        }
+        settings.entries.push(SettingInfo::of::<T>());
+        let dir = settings.dir.clone();
+        world.init_resource::<T>();
+        if let Some(dir) = dir {
+            load_setting::<T>(world, &dir);
+        }
+        self.add_event::<SettingChanged<T>>()
+            .add_systems(Last, save_setting::<T>)
+    }
+}
+
+/// Trait which adds `use_setting` to [`Cx`].
+pub trait UseSetting {
+    /// Return the current value of a setting, and re-run the presenter when it changes.
+    fn use_setting<T: Setting>(&self) -> &T;
+}
+
+impl<'p, 'w> UseSetting for Cx<'p, 'w> {
+    fn use_setting<T: Setting>(&self) -> &T {
+        self.use_resource::<T>()
+    }
+}
+
+fn setting_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{}.ron", key))
+}
+
+fn reflect_setting<T: Setting>(world: &World) -> &dyn Reflect {
+    world.resource::<T>()
+}
+
+fn update_setting<T: Setting>(world: &mut World, f: &dyn Fn(&mut dyn Reflect)) {
+    let mut value = world.resource_mut::<T>();
+    f(value.as_reflect_mut());
+}
+
+fn track_setting<T: Setting>(cx: &Cx) {
+    cx.track_resource::<T>();
+}
+
+fn reset_setting<T: Setting>(world: &mut World) {
+    *world.resource_mut::<T>() = T::default();
+}
+
+fn load_setting<T: Setting>(world: &mut World, dir: &Path) {
+    let path = setting_path(dir, T::KEY);
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    match ron::from_str::<T>(&text) {
+        Ok(value) => world.insert_resource(value),
+        Err(err) => warn!("Failed to read setting {}: {}", path.display(), err),
+    }
+}
+
+/// Send a [`SettingChanged`] event when a setting changes, and write it to disk a short time
+/// later, or when the app exits.
+fn save_setting<T: Setting>(
+    value: Res<T>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+    mut exit: EventReader<AppExit>,
+    mut changed: EventWriter<SettingChanged<T>>,
+    mut pending: Local<Option<f32>>,
+) {
+    let now = time.elapsed_seconds();
+    if value.is_changed() && !value.is_added() {
+        changed.send(SettingChanged {
+            value: value.clone(),
+        });
+        *pending = Some(now + SAVE_DELAY);
+    }
+    let exiting = exit.read().count() > 0;
+    match *pending {
+        Some(deadline) if exiting || now >= deadline => *pending = None,
+        _ => return,
+    }
+    let Some(dir) = settings.dir() else {
+        return;
+    };
+    let path = setting_path(dir, T::KEY);
+    let result = ron::ser::to_string_pretty(value.as_ref(), default())
+        .map_err(|err| err.to_string())
+        .and_then(|text| {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+            std::fs::write(&path, text).map_err(|err| err.to_string())
+        });
+    if let Err(err) = result {
+        warn!("Failed to save setting {}: {}", path.display(), err);
+    }
+}
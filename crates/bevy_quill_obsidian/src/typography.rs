@@ -1,4 +1,4 @@
-use bevy_mod_stylebuilder::{StyleBuilder, StyleBuilderFont};
+use bevy_mod_stylebuilder::{StyleBuilder, StyleBuilderFont, TextPreset, TypeScale};
 
 /// Default text style for UI.
 pub fn text_default(ss: &mut StyleBuilder) {
@@ -11,3 +11,20 @@ pub fn text_strong(ss: &mut StyleBuilder) {
     ss.font("embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Bold.ttf")
         .font_size(16);
 }
+
+/// The typographic scale for the Obsidian theme, used by `text_style()`.
+pub fn type_scale() -> TypeScale {
+    let bold = "embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Bold.ttf";
+    let medium = "embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Medium.ttf";
+    let preset = |font: &str, size: f32| TextPreset {
+        font: Some(font.into()),
+        size,
+    };
+    TypeScale {
+        heading1: preset(bold, 24.),
+        heading2: preset(bold, 20.),
+        heading3: preset(bold, 18.),
+        body: preset(medium, 16.),
+        small: preset(medium, 13.),
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_quill_obsidian::scrolling::ScrollArea;
+
+use crate::{ConnectionTarget, DragAction, DragMode, Gesture, GestureState, GraphEvent};
+
+/// Distance from the edge of the viewport within which dragging causes the graph to pan.
+const AUTO_PAN_MARGIN: f32 = 40.;
+
+/// Initial panning speed, in pixels per second, when the pointer is at the edge of the viewport.
+const AUTO_PAN_SPEED: f32 = 300.;
+
+/// Maximum speed multiplier, reached after panning continuously for a while.
+const AUTO_PAN_MAX_ACCEL: f32 = 4.;
+
+/// Marks the content element of a graph, and identifies the scrolling element which contains it.
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(crate) struct GraphCanvas {
+    pub(crate) scroll_area: Entity,
+}
+
+/// How strongly the pointer position should pan along one axis: negative near the start edge,
+/// positive near the end edge, and zero elsewhere.
+fn edge_factor(pos: f32, min: f32, max: f32) -> f32 {
+    if pos < min + AUTO_PAN_MARGIN {
+        -((min + AUTO_PAN_MARGIN - pos) / AUTO_PAN_MARGIN).min(1.)
+    } else if pos > max - AUTO_PAN_MARGIN {
+        ((pos - (max - AUTO_PAN_MARGIN)) / AUTO_PAN_MARGIN).min(1.)
+    } else {
+        0.
+    }
+}
+
+/// Scrolls the graph when a node or connection is dragged near the edge of the viewport,
+/// speeding up the longer the pointer stays there.
+pub(crate) fn auto_pan_graph(
+    mut gesture_state: ResMut<GestureState>,
+    time: Res<Time>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    canvases: Query<(Entity, &GraphCanvas)>,
+    mut scroll_areas: Query<(&mut ScrollArea, &Node, &GlobalTransform)>,
+    mut writer: EventWriter<GraphEvent>,
+) {
+    let dragging = matches!(gesture_state.mode, DragMode::Move | DragMode::Connect);
+    let pointer = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    let (Some(pointer), true) = (pointer, dragging) else {
+        if gesture_state.pan_time != 0. {
+            gesture_state.pan_time = 0.;
+        }
+        return;
+    };
+
+    for (canvas_id, canvas) in canvases.iter() {
+        let Ok((mut scroll_area, node, transform)) = scroll_areas.get_mut(canvas.scroll_area)
+        else {
+            continue;
+        };
+        let rect = node.logical_rect(transform);
+        if !rect.inflate(AUTO_PAN_MARGIN).contains(pointer) {
+            continue;
+        }
+        let direction = Vec2::new(
+            edge_factor(pointer.x, rect.min.x, rect.max.x),
+            edge_factor(pointer.y, rect.min.y, rect.max.y),
+        );
+        if direction == Vec2::ZERO {
+            if gesture_state.pan_time != 0. {
+                gesture_state.pan_time = 0.;
+            }
+            continue;
+        }
+
+        gesture_state.pan_time += time.delta_seconds();
+        let accel = (1. + gesture_state.pan_time).min(AUTO_PAN_MAX_ACCEL);
+        let delta = direction * AUTO_PAN_SPEED * accel * time.delta_seconds();
+        let prev = Vec2::new(scroll_area.scroll_left, scroll_area.scroll_top);
+        scroll_area.scroll_by(delta.x, delta.y);
+        let moved = Vec2::new(scroll_area.scroll_left, scroll_area.scroll_top) - prev;
+        if moved == Vec2::ZERO {
+            continue;
+        }
+
+        // The content has moved underneath the pointer, so update the gesture to match.
+        match gesture_state.mode {
+            DragMode::Move => {
+                gesture_state.pan_offset += moved;
+                writer.send(GraphEvent {
+                    target: canvas_id,
+                    gesture: gesture_state.move_gesture(DragAction::Update),
+                });
+            }
+            DragMode::Connect => {
+                if let (Some(anchor), ConnectionTarget::Location(pos)) =
+                    (gesture_state.anchor, gesture_state.target)
+                {
+                    gesture_state.target = ConnectionTarget::Location(pos + moved);
+                    writer.send(GraphEvent {
+                        target: canvas_id,
+                        gesture: Gesture::Connect(anchor, gesture_state.target, DragAction::Update),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_quill_obsidian::scrolling::ScrollArea;
+
+use crate::{auto_pan::GraphCanvas, touchpad::ScrollMomentum, Gesture};
+
+/// Smallest zoom factor that [`GraphViewState::apply_gesture`] will zoom out to.
+const MIN_ZOOM: f32 = 0.1;
+
+/// Largest zoom factor that [`GraphViewState::apply_gesture`] will zoom in to.
+const MAX_ZOOM: f32 = 4.;
+
+/// The view state of a single graph document: scroll position, zoom and selection. This
+/// component lives on the document entity passed to
+/// [`GraphDisplay::document`](crate::GraphDisplay::document), so that an editor with several
+/// tabs can switch documents within one graph display and find each one as it was left.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct GraphViewState {
+    /// Scroll position of the graph. This is kept up to date by the graph display while the
+    /// document is shown, and restored when the document is shown again.
+    pub scroll: Vec2,
+
+    /// Zoom factor of the graph. While the document is shown, this is used instead of the
+    /// zoom factor of the graph display.
+    pub zoom: f32,
+
+    /// The nodes which are selected in this document.
+    pub selection: HashSet<Entity>,
+}
+
+impl Default for GraphViewState {
+    fn default() -> Self {
+        Self {
+            scroll: Vec2::ZERO,
+            zoom: 1.,
+            selection: HashSet::default(),
+        }
+    }
+}
+
+impl GraphViewState {
+    /// Update the selection or zoom in response to a gesture. Returns true if the gesture was
+    /// handled; other gestures, such as moving nodes, are left to the application.
+    pub fn apply_gesture(&mut self, gesture: &Gesture) -> bool {
+        match gesture {
+            Gesture::Select(node) => {
+                if !self.selection.contains(node) {
+                    self.selection.clear();
+                    self.selection.insert(*node);
+                }
+            }
+            Gesture::SelectAdd(node) => {
+                self.selection.insert(*node);
+            }
+            Gesture::SelectRemove(node) => {
+                self.selection.remove(node);
+            }
+            Gesture::SelectToggle(node) => {
+                if !self.selection.remove(node) {
+                    self.selection.insert(*node);
+                }
+            }
+            Gesture::SelectClear => self.selection.clear(),
+            Gesture::Zoom(factor, _) => {
+                self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Placed on the scrolling element of a graph display which is showing a document.
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(crate) struct GraphDocumentHost {
+    pub(crate) document: Entity,
+    /// Whether the document's scroll position has been applied to the scrolling element.
+    restored: bool,
+}
+
+impl GraphDocumentHost {
+    pub(crate) fn new(document: Entity) -> Self {
+        Self {
+            document,
+            restored: false,
+        }
+    }
+}
+
+/// Restore the scroll position of a document when it is shown, and record it as it changes.
+pub(crate) fn sync_graph_view_state(
+    mut hosts: Query<(Entity, &mut GraphDocumentHost, &mut ScrollArea)>,
+    mut documents: Query<&mut GraphViewState>,
+    mut canvases: Query<(&GraphCanvas, &mut ScrollMomentum)>,
+) {
+    for (entity, mut host, mut scroll_area) in hosts.iter_mut() {
+        let Ok(mut state) = documents.get_mut(host.document) else {
+            continue;
+        };
+        if !host.restored {
+            // Wait until the content has been laid out, so that the scroll position isn't
+            // clamped to an empty graph.
+            if scroll_area.content_size().x <= 0. {
+                continue;
+            }
+            host.restored = true;
+            scroll_area.scroll_to(state.scroll.x, state.scroll.y);
+            // Don't let a swipe on the previous document keep scrolling this one.
+            for (canvas, mut momentum) in canvases.iter_mut() {
+                if canvas.scroll_area == entity {
+                    *momentum = ScrollMomentum::default();
+                }
+            }
+        } else if scroll_area.is_changed() {
+            let scroll = scroll_area.scroll_position();
+            if state.scroll != scroll {
+                state.scroll = scroll;
+            }
+        }
+    }
+}
@@ -3,14 +3,17 @@ use std::ops::Mul;
 use bevy::{prelude::*, ui};
 use bevy_mod_picking::prelude::*;
 use bevy_mod_stylebuilder::{
-    StyleBuilder, StyleBuilderLayout, StyleBuilderPointerEvents, StyleBuilderVisibility,
+    StyleBuilder, StyleBuilderBackground, StyleBuilderBorderRadius, StyleBuilderFont,
+    StyleBuilderLayout, StyleBuilderPointerEvents, StyleBuilderVisibility,
 };
 use bevy_quill_core::prelude::*;
-use bevy_quill_obsidian::cursor::StyleBuilderCursor;
+use bevy_quill_obsidian::{colors, cursor::StyleBuilderCursor, hooks::UseIsHover, typography};
 
 use crate::{
+    graph_display::LevelOfDetail,
     materials::{DrawPathMaterial, DrawablePath},
     relative_pos::RelativeWorldPositions,
+    terminal_display::{TerminalGlyph, TerminalKind, TerminalShape},
     ConnectionAnchor, ConnectionTarget, DragAction, DragMode, Gesture, GestureState, GraphEvent,
 };
 
@@ -28,6 +31,134 @@ fn style_edge_hitbox(ss: &mut StyleBuilder) {
         .cursor(CursorIcon::Grab);
 }
 
+fn style_edge_label_anchor(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .display(ui::Display::Flex)
+        .justify_content(ui::JustifyContent::Center)
+        .align_items(ui::AlignItems::Center)
+        .width(0)
+        .height(0)
+        .pointer_events(false);
+}
+
+fn style_edge_label(ss: &mut StyleBuilder) {
+    ss.flex_shrink(0.)
+        .padding((4, 0))
+        .border_radius(3.0)
+        .pointer_events(true);
+}
+
+/// Records the endpoints of a displayed edge, so that nodes can be dropped onto it.
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(crate) struct EdgeGeometry {
+    pub(crate) edge_id: Option<Entity>,
+    pub(crate) src: Vec2,
+    pub(crate) dst: Vec2,
+    pub(crate) src_color: Srgba,
+    pub(crate) dst_color: Srgba,
+}
+
+/// Terminals which a displayed edge is attached to, and how its path is drawn, so that the
+/// path can be updated when the terminals move without rebuilding the edge view.
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(crate) struct EdgeTerminals {
+    pub(crate) src: Option<Entity>,
+    pub(crate) dst: Option<Entity>,
+    pub(crate) width: f32,
+    pub(crate) simplified: bool,
+    pub(crate) kind: TerminalKind,
+}
+
+impl EdgeGeometry {
+    /// True if the edge passes through the given rectangle, not counting edges which start or
+    /// end at the rectangle, such as the edges of the node being dragged.
+    pub(crate) fn crosses_rect(&self, rect: Rect) -> bool {
+        let attached = rect.inflate(8.);
+        !attached.contains(self.src)
+            && !attached.contains(self.dst)
+            && edge_path(self.src, self.dst, 1.)
+                .points(8)
+                .iter()
+                .any(|point| rect.contains(*point))
+    }
+}
+
+/// Build the path for an edge between two terminals.
+pub(crate) fn edge_path(src: Vec2, dst: Vec2, width: f32) -> DrawablePath {
+    let mut path = DrawablePath::new(width);
+    let dx = (dst.x - src.x).abs().mul(0.3).min(20.);
+    let src1 = src + Vec2::new(dx, 0.);
+    let dst1 = dst - Vec2::new(dx, 0.);
+    path.move_to(src);
+    let mlen = src1.distance(dst1);
+    if mlen > 40. {
+        let src2 = src1.lerp(dst1, 20. / mlen);
+        let dst2 = src1.lerp(dst1, (mlen - 20.) / mlen);
+        path.quadratic_to(src1, src2);
+        path.line_to(dst2);
+        path.quadratic_to(dst1, dst);
+    } else {
+        let mid = src1.lerp(dst1, 0.5);
+        path.quadratic_to(src1, mid);
+        path.quadratic_to(dst1, dst);
+    }
+    path
+}
+
+/// Build a straight path between two terminals, used when the graph is zoomed far out.
+fn simplified_edge_path(src: Vec2, dst: Vec2, width: f32) -> DrawablePath {
+    let mut path = DrawablePath::new(width);
+    path.move_to(src);
+    path.line_to(dst);
+    path
+}
+
+/// Length of the sides of the arrowhead drawn on flow edges.
+const ARROW_SIZE: f32 = 5.;
+
+/// Distance from the end of a flow edge to the tip of its arrowhead, so that the arrowhead
+/// isn't hidden by the terminal.
+const ARROW_OFFSET: f32 = 6.;
+
+/// Build the path for an edge as it is displayed, including an arrowhead for flow edges.
+fn display_edge_path(
+    src: Vec2,
+    dst: Vec2,
+    width: f32,
+    simplified: bool,
+    kind: TerminalKind,
+) -> DrawablePath {
+    let mut path = if simplified {
+        simplified_edge_path(src, dst, width)
+    } else {
+        edge_path(src, dst, width)
+    };
+    if kind == TerminalKind::Flow {
+        // Curved edges always arrive horizontally.
+        let direction = if simplified {
+            (dst - src).normalize_or_zero()
+        } else {
+            Vec2::X
+        };
+        let tip = dst - direction * ARROW_OFFSET;
+        let back = tip - direction * ARROW_SIZE;
+        let side = direction.perp() * ARROW_SIZE;
+        path.move_to(back + side);
+        path.line_to(tip);
+        path.line_to(back - side);
+    }
+    path
+}
+
+/// Position the edge display entity so that it covers the bounds of its path.
+fn set_edge_bounds(style: &mut Style, bounds: Rect) {
+    style.left = ui::Val::Px(bounds.min.x);
+    style.top = ui::Val::Px(bounds.min.y);
+    style.width = ui::Val::Px(bounds.width());
+    style.height = ui::Val::Px(bounds.height());
+    style.position_type = ui::PositionType::Absolute;
+}
+
 /// Displays a stroked path between two nodes.
 #[derive(Clone, PartialEq)]
 pub struct EdgeDisplay {
@@ -37,17 +168,88 @@ pub struct EdgeDisplay {
     /// Pixel position of the source terminal.
     pub src_pos: IVec2,
 
+    /// Entity id of the source terminal. If this is set, the start of the edge follows the
+    /// terminal as it moves, without the edge having to be rebuilt. This keeps edges smooth
+    /// while nodes are being dragged.
+    pub src_terminal: Option<Entity>,
+
     /// Color of the edge at the source terminal
     pub src_color: Srgba,
 
+    /// Glyph drawn at the start of the edge. This is useful when the start of the edge isn't
+    /// attached to a terminal, such as while a new connection is being dragged.
+    pub src_marker: Option<TerminalShape>,
+
     /// Pixel position of the destination terminal.
     pub dst_pos: IVec2,
 
+    /// Entity id of the destination terminal. If this is set, the end of the edge follows the
+    /// terminal as it moves.
+    pub dst_terminal: Option<Entity>,
+
     /// Color of the edge at the destination terminal
     pub dst_color: Srgba,
 
+    /// Glyph drawn at the end of the edge.
+    pub dst_marker: Option<TerminalShape>,
+
     /// If true, the edge should not be displayed, but the display entities should still exist.
     pub hidden: bool,
+
+    /// Optional label displayed at the midpoint of the edge, such as a conversion or a weight.
+    /// The label is rotated to follow the direction of the edge, but is never upside-down.
+    pub label: Option<String>,
+
+    /// The label is only displayed when `zoom` is at least this value.
+    pub label_min_zoom: f32,
+
+    /// The current zoom factor of the graph view.
+    pub zoom: f32,
+
+    /// The kind of terminals which this edge connects. Flow edges are drawn thicker, with an
+    /// arrowhead pointing at the input.
+    pub kind: TerminalKind,
+}
+
+impl Default for EdgeDisplay {
+    fn default() -> Self {
+        Self {
+            edge_id: None,
+            src_pos: IVec2::default(),
+            src_terminal: None,
+            src_color: Srgba::default(),
+            src_marker: None,
+            dst_pos: IVec2::default(),
+            dst_terminal: None,
+            dst_color: Srgba::default(),
+            dst_marker: None,
+            hidden: false,
+            label: None,
+            label_min_zoom: 0.5,
+            zoom: 1.0,
+            kind: TerminalKind::Data,
+        }
+    }
+}
+
+/// Returns the rotation angle of an edge label at the midpoint of the edge, flipped if needed
+/// so that the text is not upside-down.
+fn edge_label_angle(src: Vec2, dst: Vec2) -> f32 {
+    // The edge is symmetric, so the tangent at the midpoint is the line between the two
+    // inner control points.
+    let dx = (dst.x - src.x).abs().mul(0.3).min(20.);
+    let tangent = (dst - Vec2::new(dx, 0.)) - (src + Vec2::new(dx, 0.));
+    if tangent == Vec2::ZERO {
+        return 0.;
+    }
+    let angle = tangent.y.atan2(tangent.x);
+    if angle > std::f32::consts::FRAC_PI_2 {
+        angle - std::f32::consts::PI
+    } else if angle < -std::f32::consts::FRAC_PI_2 {
+        angle + std::f32::consts::PI
+    } else {
+        angle
+    }
 }
 
 impl ViewTemplate for EdgeDisplay {
@@ -65,6 +267,23 @@ impl ViewTemplate for EdgeDisplay {
             (),
         );
         let material_id = material.id();
+        let label_id = cx.create_entity();
+        let label_hover = cx.is_hovered(label_id);
+        let src = self.src_pos.as_vec2();
+        let dst = self.dst_pos.as_vec2();
+        let simplified =
+            cx.use_inherited_component::<LevelOfDetail>() == Some(&LevelOfDetail::Simplified);
+        let show_label = !self.hidden && !simplified && self.zoom >= self.label_min_zoom;
+        let splice_target = self.edge_id.is_some()
+            && cx.use_resource::<GestureState>().splice_target == self.edge_id;
+        // Highlight the edge when a node is about to be dropped onto it.
+        let width = match (self.kind, splice_target) {
+            (TerminalKind::Data, false) => 1.7,
+            (TerminalKind::Data, true) => 3.,
+            (TerminalKind::Flow, false) => 2.5,
+            (TerminalKind::Flow, true) => 3.5,
+        };
+        let kind = self.kind;
 
         (
             Element::<MaterialNodeBundle<DrawPathMaterial>>::for_entity(display_id)
@@ -77,35 +296,39 @@ impl ViewTemplate for EdgeDisplay {
                     },
                     self.hidden,
                 )
+                .insert_dyn(
+                    |(edge_id, src, dst, src_color, dst_color)| EdgeGeometry {
+                        edge_id,
+                        src,
+                        dst,
+                        src_color,
+                        dst_color,
+                    },
+                    (self.edge_id, src, dst, self.src_color, self.dst_color),
+                )
+                .insert_dyn(
+                    |(src, dst, width, simplified, kind)| EdgeTerminals {
+                        src,
+                        dst,
+                        width,
+                        simplified,
+                        kind,
+                    },
+                    (
+                        self.src_terminal,
+                        self.dst_terminal,
+                        width,
+                        simplified,
+                        kind,
+                    ),
+                )
                 .effect(
-                    move |cx, ent, (src, dst, src_color, dst_color)| {
-                        let mut path = DrawablePath::new(1.7);
-                        let dx = (dst.x - src.x).abs().mul(0.3).min(20.);
-                        let src1 = src + Vec2::new(dx, 0.);
-                        let dst1 = dst - Vec2::new(dx, 0.);
-                        path.move_to(src);
-                        // TODO: Marker
-                        let mlen = src1.distance(dst1);
-                        if mlen > 40. {
-                            let src2 = src1.lerp(dst1, 20. / mlen);
-                            let dst2 = src1.lerp(dst1, (mlen - 20.) / mlen);
-                            path.quadratic_to(src1, src2);
-                            path.line_to(dst2);
-                            path.quadratic_to(dst1, dst);
-                        } else {
-                            let mid = src1.lerp(dst1, 0.5);
-                            path.quadratic_to(src1, mid);
-                            path.quadratic_to(dst1, dst);
-                        }
-                        // TODO: Marker
+                    move |cx, ent, (src, dst, src_color, dst_color, width, simplified, kind)| {
+                        let path = display_edge_path(src, dst, width, simplified, kind);
                         let bounds = path.bounds();
 
                         let mut style = cx.world_mut().get_mut::<Style>(ent).unwrap();
-                        style.left = ui::Val::Px(bounds.min.x);
-                        style.top = ui::Val::Px(bounds.min.y);
-                        style.width = ui::Val::Px(bounds.width());
-                        style.height = ui::Val::Px(bounds.height());
-                        style.position_type = ui::PositionType::Absolute;
+                        set_edge_bounds(&mut style, bounds);
 
                         let mut materials = cx
                             .world_mut()
@@ -125,13 +348,16 @@ impl ViewTemplate for EdgeDisplay {
                         self.dst_pos.as_vec2(),
                         self.src_color,
                         self.dst_color,
+                        width,
+                        simplified,
+                        kind,
                     ),
                 ),
             Cond::new(
                 self.edge_id.is_some(),
                 (
                     Element::<NodeBundle>::new()
-                        .insert_dyn(edge_event_handlers, (self.edge_id, display_id, false))
+                        .insert_dyn(edge_event_handlers, (self.edge_id, display_id, false, kind))
                         .style(style_edge_hitbox)
                         .style_dyn(
                             |pos, sb| {
@@ -140,7 +366,7 @@ impl ViewTemplate for EdgeDisplay {
                             self.src_pos.as_vec2(),
                         ),
                     Element::<NodeBundle>::new()
-                        .insert_dyn(edge_event_handlers, (self.edge_id, display_id, true))
+                        .insert_dyn(edge_event_handlers, (self.edge_id, display_id, true, kind))
                         .style(style_edge_hitbox)
                         .style_dyn(
                             |pos, sb| {
@@ -151,19 +377,146 @@ impl ViewTemplate for EdgeDisplay {
                 ),
                 (),
             ),
+            self.src_marker
+                .filter(|_| !self.hidden)
+                .map(|shape| TerminalGlyph {
+                    shape,
+                    color: self.src_color,
+                    position: src,
+                }),
+            self.dst_marker
+                .filter(|_| !self.hidden)
+                .map(|shape| TerminalGlyph {
+                    shape,
+                    color: self.dst_color,
+                    position: dst,
+                }),
+            Cond::new(
+                show_label,
+                self.label.clone().map(|label| {
+                    Element::<NodeBundle>::new()
+                        .named("NodeGraph::EdgeLabel")
+                        .style(style_edge_label_anchor)
+                        .style_dyn(
+                            |pos, sb| {
+                                sb.left(pos.x).top(pos.y);
+                            },
+                            src.lerp(dst, 0.5),
+                        )
+                        .effect(
+                            |cx, ent, angle| {
+                                let mut transform =
+                                    cx.world_mut().get_mut::<Transform>(ent).unwrap();
+                                transform.rotation = Quat::from_rotation_z(angle);
+                            },
+                            edge_label_angle(src, dst),
+                        )
+                        .children(
+                            Element::<NodeBundle>::for_entity(label_id)
+                                .style((typography::text_default, style_edge_label))
+                                .style_dyn(
+                                    |hover, sb| {
+                                        if hover {
+                                            sb.background_color(colors::U3)
+                                                .color(colors::FOREGROUND);
+                                        } else {
+                                            sb.background_color(colors::U1).color(colors::DIM);
+                                        }
+                                    },
+                                    label_hover,
+                                )
+                                .children(label),
+                        )
+                }),
+                (),
+            ),
         )
     }
 }
 
+/// Update the paths of edges which are attached to terminals whenever the terminals move,
+/// without going through the edge views. The edge views catch up when they are next rebuilt;
+/// until then, only the path is moved, and the drag handles at either end stay where they were.
+#[allow(clippy::type_complexity)]
+pub(crate) fn follow_edge_terminals(
+    mut edges: Query<(
+        &EdgeTerminals,
+        &mut EdgeGeometry,
+        &mut Style,
+        &Handle<DrawPathMaterial>,
+        &Parent,
+    )>,
+    transforms: Query<(&Node, Ref<GlobalTransform>)>,
+    mut materials: ResMut<Assets<DrawPathMaterial>>,
+) {
+    for (terminals, mut geometry, mut style, material, parent) in edges.iter_mut() {
+        if terminals.src.is_none() && terminals.dst.is_none() {
+            continue;
+        }
+        let moved = |terminal: Option<Entity>| {
+            terminal
+                .and_then(|t| transforms.get(t).ok())
+                .is_some_and(|(_, transform)| transform.is_changed())
+        };
+        if !moved(terminals.src) && !moved(terminals.dst) {
+            continue;
+        }
+        // Terminal positions are relative to the graph content, which is the edge's parent.
+        let Ok((content_node, content_transform)) = transforms.get(parent.get()) else {
+            continue;
+        };
+        let to_content = content_transform.affine().inverse();
+        let origin = content_node.size() * 0.5;
+        let position = |terminal: Option<Entity>, current: Vec2| {
+            terminal
+                .and_then(|t| transforms.get(t).ok())
+                .map_or(current, |(_, transform)| {
+                    // Round to whole pixels, the same as the positions passed to the view.
+                    (to_content
+                        .transform_point3(transform.translation())
+                        .truncate()
+                        + origin)
+                        .trunc()
+                })
+        };
+        let src = position(terminals.src, geometry.src);
+        let dst = position(terminals.dst, geometry.dst);
+        if src == geometry.src && dst == geometry.dst {
+            continue;
+        }
+
+        let path = display_edge_path(
+            src,
+            dst,
+            terminals.width,
+            terminals.simplified,
+            terminals.kind,
+        );
+        let bounds = path.bounds();
+        set_edge_bounds(&mut style, bounds);
+        if let Some(material) = materials.get_mut(material) {
+            material.update_path(&path);
+            material.update_color(
+                geometry.src_color,
+                src - bounds.min,
+                geometry.dst_color,
+                dst - bounds.min,
+            );
+        }
+        geometry.src = src;
+        geometry.dst = dst;
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn edge_event_handlers(
-    args: (Option<Entity>, Entity, bool),
+    args: (Option<Entity>, Entity, bool, TerminalKind),
 ) -> (
     On<Pointer<DragStart>>,
     On<Pointer<Drag>>,
     On<Pointer<DragEnd>>,
 ) {
-    let (edge_id, display_id, is_sink) = args;
+    let (edge_id, display_id, is_sink, kind) = args;
     (
         On::<Pointer<DragStart>>::run(
             move |mut event: ListenerMut<Pointer<DragStart>>,
@@ -187,6 +540,7 @@ fn edge_event_handlers(
                     ));
                     gesture_state.mode = DragMode::Connect;
                     gesture_state.anchor = Some(anchor);
+                    gesture_state.anchor_kind = kind;
                     writer.send(GraphEvent {
                         target: display_id,
                         gesture: Gesture::Connect(anchor, gesture_state.target, DragAction::Start),
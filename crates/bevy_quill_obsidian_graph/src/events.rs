@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 
+use crate::TerminalKind;
+
 /// For a connection drag, where we are dragging from.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ConnectionAnchor {
@@ -30,9 +32,10 @@ pub enum ConnectionTarget {
 
 #[derive(Clone, Debug)]
 pub enum Gesture {
-    /// Drag one or more nodes (ones that are currently selected).
-    /// The arguments are the drag vector, and whether this is the final drag value.
-    Move(Vec2, DragAction),
+    /// Drag one or more nodes: the node being dragged, along with any other selected nodes.
+    /// The arguments are the new position of each affected node, and the drag action. The set
+    /// of nodes is captured when the drag starts, and is the same for each event of the drag.
+    Move(Vec<(Entity, Vec2)>, DragAction),
 
     /// Drag a node onto the graph to create it.
     Create(Vec2),
@@ -43,9 +46,27 @@ pub enum Gesture {
     /// Option-click to scroll the view.
     Scroll(Vec2),
 
+    /// Zoom the view, such as with a touchpad pinch. The arguments are the scale factor to
+    /// apply, and the point to zoom around, relative to the graph content.
+    Zoom(f32, Vec2),
+
+    /// Insert a node into an edge. This is sent after the final `Move` gesture when a node is
+    /// dropped onto an edge. The arguments are the node and the edge; it is up to the
+    /// application to decide whether, and how, the node can be connected.
+    Splice(Entity, Entity),
+
+    /// Resize a note by dragging its corner. The arguments are the note, its new size, and the
+    /// drag action. The note's position is its upper-left corner, which stays where it is.
+    Resize(Entity, Vec2, DragAction),
+
     /// Select a rectangular region
     SelectRect(Rect, DragAction),
 
+    /// Select a freeform region, drawn by dragging with the alt key held down. The argument is
+    /// the outline of the lasso. When the lasso is finished, a `SelectAdd` gesture is sent for
+    /// each node within the lasso, before the `Finish` action.
+    SelectLasso(Vec<Vec2>, DragAction),
+
     /// Select the given node. If the node is already selected, does nothing. If the node is
     /// not selected, clears the selection and selects only the given node.
     Select(Entity),
@@ -83,7 +104,11 @@ pub(crate) enum DragMode {
     None,
     Move,
     RectSelect(Vec2),
+    Lasso(Vec<Vec2>),
     Connect,
+    Pan,
+    /// Resizing a note: the note, and its size when the drag started.
+    Resize(Entity, Vec2),
 }
 
 #[derive(Clone, Copy, Default, Debug, PartialEq)]
@@ -100,4 +125,31 @@ pub(crate) struct GestureState {
     pub(crate) mode: DragMode,
     pub(crate) anchor: Option<ConnectionAnchor>,
     pub(crate) target: ConnectionTarget,
+    /// The kind of terminal that the current connection drag started from. Only terminals of
+    /// the same kind can be connected to it.
+    pub(crate) anchor_kind: TerminalKind,
+    /// Nodes being moved by the current node drag, and their positions when the drag started.
+    pub(crate) moving: Vec<(Entity, Vec2)>,
+    /// Pointer distance of the current node drag.
+    pub(crate) drag_distance: Vec2,
+    /// Distance the graph has been auto-panned during the current node drag.
+    pub(crate) pan_offset: Vec2,
+    /// How long the graph has been auto-panning continuously, in seconds.
+    pub(crate) pan_time: f32,
+    /// Edge that the dragged node would be inserted into if it were dropped.
+    pub(crate) splice_target: Option<Entity>,
+}
+
+impl GestureState {
+    /// Build a `Move` gesture which offsets the nodes being moved by the current drag distance.
+    pub(crate) fn move_gesture(&self, action: DragAction) -> Gesture {
+        let offset = self.drag_distance + self.pan_offset;
+        Gesture::Move(
+            self.moving
+                .iter()
+                .map(|(node, start)| (*node, *start + offset))
+                .collect(),
+            action,
+        )
+    }
 }
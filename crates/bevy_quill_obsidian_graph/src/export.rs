@@ -0,0 +1,311 @@
+use std::{fmt::Write, path::Path};
+
+use bevy::{prelude::*, ui};
+use bevy_quill_obsidian::colors;
+
+use crate::{
+    edge_display::{edge_path, EdgeGeometry},
+    node_display::NodeDisplayTarget,
+};
+
+/// Spacing of the dots in the background grid, which matches the grid shader.
+const GRID_SPACING: f32 = 16.;
+
+/// Options for exporting a graph as an SVG document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphExportOptions {
+    /// Whether to draw the dotted background grid. If false, the background is transparent.
+    pub include_grid: bool,
+    /// Whether to draw the highlight around selected nodes.
+    pub include_selection: bool,
+    /// Space to leave around the nodes and edges, in pixels.
+    pub padding: f32,
+}
+
+impl Default for GraphExportOptions {
+    fn default() -> Self {
+        Self {
+            include_grid: true,
+            include_selection: false,
+            padding: 16.,
+        }
+    }
+}
+
+/// Render the entire contents of a graph as an SVG document, regardless of which part of it is
+/// currently scrolled into view. `content` is the content entity of the [`GraphDisplay`], which
+/// can be set with [`GraphDisplay::content_entity`].
+///
+/// The document is built from the laid-out UI nodes, so it should be called after layout has
+/// run at least once. Backgrounds, borders, text and edges are drawn; custom materials other
+/// than the grid are not.
+///
+/// Raster (PNG) export is out of scope: UI nodes are rendered by a single camera, so drawing
+/// the whole canvas offscreen would mean retargeting the graph away from the window while it
+/// is captured. To produce a PNG, convert the SVG with an external SVG renderer.
+///
+/// [`GraphDisplay`]: crate::GraphDisplay
+/// [`GraphDisplay::content_entity`]: crate::GraphDisplay::content_entity
+pub fn export_graph_svg(world: &World, content: Entity, options: &GraphExportOptions) -> String {
+    let origin = match (
+        world.get::<Node>(content),
+        world.get::<GlobalTransform>(content),
+    ) {
+        (Some(node), Some(transform)) => node.logical_rect(transform).min,
+        _ => Vec2::ZERO,
+    };
+    let mut writer = SvgWriter {
+        world,
+        origin,
+        options,
+        body: String::new(),
+        bounds: Rect {
+            min: Vec2::splat(f32::INFINITY),
+            max: Vec2::splat(f32::NEG_INFINITY),
+        },
+        next_id: 0,
+    };
+    if let Some(children) = world.get::<Children>(content) {
+        for child in children.iter() {
+            writer.write_entity(*child, false);
+        }
+    }
+
+    let bounds = if writer.bounds.is_empty() {
+        Rect::default()
+    } else {
+        writer.bounds.inflate(options.padding)
+    };
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+        bounds.min.x,
+        bounds.min.y,
+        bounds.width(),
+        bounds.height(),
+        bounds.width(),
+        bounds.height()
+    );
+    if options.include_grid {
+        let _ = writeln!(
+            svg,
+            r#"<defs><pattern id="grid" width="{0}" height="{0}" patternUnits="userSpaceOnUse"><rect width="{0}" height="{0}" {1}/><rect width="1.5" height="1.5" {2}/></pattern></defs>"#,
+            GRID_SPACING,
+            paint("fill", colors::U1),
+            paint("fill", colors::U3),
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="url(#grid)"/>"#,
+            bounds.min.x,
+            bounds.min.y,
+            bounds.width(),
+            bounds.height()
+        );
+    }
+    svg.push_str(&writer.body);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Render the entire contents of a graph as an SVG file. See [`export_graph_svg`].
+pub fn save_graph_svg(
+    world: &World,
+    content: Entity,
+    path: impl AsRef<Path>,
+    options: &GraphExportOptions,
+) -> std::io::Result<()> {
+    std::fs::write(path, export_graph_svg(world, content, options))
+}
+
+struct SvgWriter<'a> {
+    world: &'a World,
+    origin: Vec2,
+    options: &'a GraphExportOptions,
+    body: String,
+    bounds: Rect,
+    next_id: usize,
+}
+
+impl<'a> SvgWriter<'a> {
+    fn write_entity(&mut self, entity: Entity, selected: bool) {
+        let world = self.world;
+        if matches!(world.get::<Visibility>(entity), Some(Visibility::Hidden))
+            || world
+                .get::<InheritedVisibility>(entity)
+                .is_some_and(|visibility| !visibility.get())
+        {
+            return;
+        }
+
+        let selected = selected
+            || world
+                .get::<NodeDisplayTarget>(entity)
+                .is_some_and(|target| target.selected);
+
+        if let Some(edge) = world.get::<EdgeGeometry>(entity) {
+            self.write_edge(edge);
+        } else if let (Some(node), Some(transform)) = (
+            world.get::<Node>(entity),
+            world.get::<GlobalTransform>(entity),
+        ) {
+            let rect = node.logical_rect(transform);
+            let rect = Rect::from_corners(rect.min - self.origin, rect.max - self.origin);
+            self.write_box(entity, rect, selected);
+            self.write_text(entity, rect);
+        }
+
+        if let Some(children) = world.get::<Children>(entity) {
+            for child in children.iter() {
+                self.write_entity(*child, selected);
+            }
+        }
+    }
+
+    fn write_edge(&mut self, edge: &EdgeGeometry) {
+        let points = edge_path(edge.src, edge.dst, 1.7).points(8);
+        let Some(first) = points.first() else {
+            return;
+        };
+        let mut d = format!("M{} {}", first.x, first.y);
+        for point in points.iter().skip(1) {
+            let _ = write!(d, " L{} {}", point.x, point.y);
+            self.bounds = self.bounds.union_point(*point);
+        }
+        self.bounds = self.bounds.union_point(*first);
+
+        self.next_id += 1;
+        let _ = writeln!(
+            self.body,
+            r#"<defs><linearGradient id="edge{}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}"><stop offset="0" {}/><stop offset="1" {}/></linearGradient></defs>"#,
+            self.next_id,
+            edge.src.x,
+            edge.src.y,
+            edge.dst.x,
+            edge.dst.y,
+            paint("stop-color", edge.src_color),
+            paint("stop-color", edge.dst_color),
+        );
+        let _ = writeln!(
+            self.body,
+            r#"<path d="{}" fill="none" stroke="url(#edge{})" stroke-width="1.7"/>"#,
+            d, self.next_id
+        );
+    }
+
+    fn write_box(&mut self, entity: Entity, rect: Rect, selected: bool) {
+        let world = self.world;
+        let background = world
+            .get::<BackgroundColor>(entity)
+            .map(|bg| Srgba::from(bg.0))
+            .filter(|color| color.alpha > 0.);
+        let border_width = world
+            .get::<Style>(entity)
+            .and_then(|style| match style.border.left {
+                ui::Val::Px(width) if width > 0. => Some(width),
+                _ => None,
+            });
+        let border = world
+            .get::<BorderColor>(entity)
+            .map(|border| Srgba::from(border.0))
+            .filter(|color| color.alpha > 0.)
+            .zip(border_width)
+            .map(|(color, width)| {
+                // Selected nodes are highlighted with a brighter border.
+                if selected && !self.options.include_selection && color == colors::FOREGROUND {
+                    (colors::U4, width)
+                } else {
+                    (color, width)
+                }
+            });
+        if background.is_none() && border.is_none() {
+            return;
+        }
+
+        let radius = match world.get::<ui::BorderRadius>(entity) {
+            Some(ui::BorderRadius {
+                top_left: ui::Val::Px(radius),
+                ..
+            }) => *radius,
+            _ => 0.,
+        };
+        let mut attrs = match background {
+            Some(color) => paint("fill", color),
+            None => r#"fill="none""#.to_string(),
+        };
+        let mut shape = rect;
+        if let Some((color, width)) = border {
+            // SVG strokes are centered on the outline, so inset the shape by half the width.
+            shape = rect.inflate(-width * 0.5);
+            let _ = write!(
+                attrs,
+                r#" {} stroke-width="{}""#,
+                paint("stroke", color),
+                width
+            );
+        }
+        self.bounds = self.bounds.union(rect);
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {}/>"#,
+            shape.min.x,
+            shape.min.y,
+            shape.width(),
+            shape.height(),
+            radius,
+            attrs
+        );
+    }
+
+    fn write_text(&mut self, entity: Entity, rect: Rect) {
+        let Some(text) = self.world.get::<Text>(entity) else {
+            return;
+        };
+        let Some(font_size) = text.sections.first().map(|section| section.style.font_size) else {
+            return;
+        };
+        let _ = write!(
+            self.body,
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}">"#,
+            rect.min.x,
+            rect.min.y + font_size * 0.8,
+            font_size
+        );
+        for section in text.sections.iter() {
+            let _ = write!(
+                self.body,
+                "<tspan {}>{}</tspan>",
+                paint("fill", section.style.color),
+                escape(&section.value)
+            );
+        }
+        self.body.push_str("</text>\n");
+        self.bounds = self.bounds.union(rect);
+    }
+}
+
+/// Format an SVG paint attribute, such as `fill`, along with its opacity.
+fn paint(attr: &str, color: impl Into<Srgba>) -> String {
+    let color: Srgba = color.into();
+    let opacity = match attr {
+        "stop-color" => "stop-opacity".to_string(),
+        _ => format!("{}-opacity", attr),
+    };
+    format!(
+        r#"{}="rgb({},{},{})" {}="{}""#,
+        attr,
+        (color.red * 255.).round(),
+        (color.green * 255.).round(),
+        (color.blue * 255.).round(),
+        opacity,
+        color.alpha
+    )
+}
+
+/// Escape text for use in an XML document.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
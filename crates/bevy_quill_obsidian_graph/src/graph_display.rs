@@ -1,10 +1,27 @@
-use bevy::{prelude::*, ui};
+use bevy::{prelude::*, ui, window::PrimaryWindow};
 use bevy_mod_picking::prelude::*;
 use bevy_mod_stylebuilder::*;
 use bevy_quill_core::*;
-use bevy_quill_obsidian::{colors, controls::ScrollView};
+use bevy_quill_obsidian::{
+    colors,
+    controls::ScrollView,
+    scrolling::{ScrollArea, ScrollWheel},
+};
 
-use crate::{materials::DotGridMaterial, DragAction, DragMode, Gesture, GestureState, GraphEvent};
+use crate::{
+    auto_pan::GraphCanvas,
+    document::{GraphDocumentHost, GraphViewState},
+    input_bindings::InputBindings,
+    lasso::{lasso_contains_rect, LassoDisplay, LASSO_MIN_SEGMENT},
+    materials::DotGridMaterial,
+    node_display::NodeDisplayTarget,
+    spatial_index::{GraphSpatialIndex, GraphViewport},
+    touchpad::{ScrollMomentum, TouchpadSettings},
+    DragAction, DragMode, Gesture, GestureState, GraphEvent,
+};
+
+/// How much the view zooms per pixel of scrolling while the zoom modifier is held.
+const ZOOM_SENSITIVITY: f32 = 0.005;
 
 fn style_node_graph(ss: &mut StyleBuilder) {
     ss.background_color(colors::U1);
@@ -21,8 +38,21 @@ fn style_node_graph_scroll(ss: &mut StyleBuilder) {
     ss.min_width(ui::Val::Px(2000.0));
 }
 
+/// How much detail is shown for nodes and edges. This is inserted as a context component by
+/// [`GraphDisplay`], and can be read by node and edge views with `use_inherited_component`.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LevelOfDetail {
+    /// Nodes and edges are drawn in full.
+    #[default]
+    Full,
+
+    /// The graph is zoomed far out: nodes are drawn as title-only blocks without their
+    /// property rows or terminals, and edges are drawn as straight lines without labels.
+    Simplified,
+}
+
 /// An editable graph of nodes, connected by edges.
-#[derive(Default, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct GraphDisplay {
     /// Nodes within the node graph.
     pub children: ViewChild,
@@ -33,6 +63,38 @@ pub struct GraphDisplay {
     /// Optional entity id to use for the scrolling element. This is useful for querying the
     /// current scroll position.
     pub entity: Option<Entity>,
+
+    /// Optional entity id to use for the content element, which contains the nodes and edges.
+    /// Node positions are relative to this element.
+    pub content_entity: Option<Entity>,
+
+    /// The current zoom factor of the graph view.
+    pub zoom: f32,
+
+    /// Optional entity which represents the graph document being displayed. The view state of
+    /// the document, such as its scroll position and zoom, is kept in a [`GraphViewState`]
+    /// component on this entity, so that one graph display can switch between several
+    /// documents, for example in a tabbed editor, without mixing up their view state.
+    pub document: Option<Entity>,
+
+    /// When `zoom` is less than this value, nodes and edges are drawn in a simplified form,
+    /// see [`LevelOfDetail::Simplified`]. Since terminals are not displayed in this mode,
+    /// edge endpoints should be computed from the node positions instead.
+    pub detail_min_zoom: f32,
+}
+
+impl Default for GraphDisplay {
+    fn default() -> Self {
+        Self {
+            children: default(),
+            style: default(),
+            entity: None,
+            content_entity: None,
+            zoom: 1.0,
+            document: None,
+            detail_min_zoom: 0.35,
+        }
+    }
 }
 
 impl GraphDisplay {
@@ -59,6 +121,31 @@ impl GraphDisplay {
         self.entity = Some(entity);
         self
     }
+
+    /// Set the entity id to use for the content element. This is useful for computing the
+    /// position of elements within the graph, such as terminals.
+    pub fn content_entity(mut self, entity: Entity) -> Self {
+        self.content_entity = Some(entity);
+        self
+    }
+    /// Set the current zoom factor of the graph view.
+    pub fn zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// Set the entity of the graph document being displayed. If the entity doesn't have a
+    /// [`GraphViewState`], one is added.
+    pub fn document(mut self, document: Entity) -> Self {
+        self.document = Some(document);
+        self
+    }
+
+    /// Set the zoom factor below which nodes and edges are drawn in a simplified form.
+    pub fn detail_min_zoom(mut self, zoom: f32) -> Self {
+        self.detail_min_zoom = zoom;
+        self
+    }
 }
 
 impl ViewTemplate for GraphDisplay {
@@ -74,19 +161,64 @@ impl ViewTemplate for GraphDisplay {
             },
             (),
         );
+        let zoom = match self.document {
+            Some(document) => {
+                if cx.world().get::<GraphViewState>(document).is_none() {
+                    cx.world_mut()
+                        .entity_mut(document)
+                        .insert(GraphViewState::default());
+                }
+                cx.use_component::<GraphViewState>(document)
+                    .map_or(self.zoom, |state| state.zoom)
+            }
+            None => self.zoom,
+        };
+        let detail = if zoom < self.detail_min_zoom {
+            LevelOfDetail::Simplified
+        } else {
+            LevelOfDetail::Full
+        };
+        // Only insert when the level changes, since every node and edge depends on it.
+        if cx.world().get::<LevelOfDetail>(cx.owner()) != Some(&detail) {
+            cx.insert(detail);
+        }
+        let id_scroll_area = self.entity.unwrap_or_else(|| cx.create_entity());
+        cx.create_effect(
+            |world, (scroll_area, document)| {
+                let mut entt = world.entity_mut(scroll_area);
+                match document {
+                    Some(document) => entt.insert(GraphDocumentHost::new(document)),
+                    None => entt.remove::<GraphDocumentHost>(),
+                };
+                // A gesture in progress belongs to the previous document.
+                *world.resource_mut::<GestureState>() = GestureState::default();
+            },
+            (id_scroll_area, self.document),
+        );
+        let content = match self.content_entity {
+            Some(entity) => Element::<MaterialNodeBundle<DotGridMaterial>>::for_entity(entity),
+            None => Element::<MaterialNodeBundle<DotGridMaterial>>::new(),
+        };
 
         ScrollView::new()
-            .entity(self.entity)
+            .entity(Some(id_scroll_area))
             .children(
-                Element::<MaterialNodeBundle<DotGridMaterial>>::new()
+                content
                     .named("NodeGraph::Scroll")
+                    .insert_dyn(|scroll_area| GraphCanvas { scroll_area }, id_scroll_area)
                     .insert_dyn(
                         move |_| {
                             (
                                 On::<Pointer<Down>>::run(
                                     move |mut event: ListenerMut<Pointer<Down>>,
-                                    mut writer: EventWriter<GraphEvent>| {
+                                    mut writer: EventWriter<GraphEvent>,
+                                    keys: Res<ButtonInput<KeyCode>>,
+                                    bindings: Res<InputBindings>| {
                                         event.stop_propagation();
+                                        // Panning the view leaves the selection unchanged.
+                                        if bindings.pan.matches(event.button, &keys) {
+                                            return;
+                                        }
                                         writer.send(GraphEvent {
                                             target: event.target(),
                                             gesture: Gesture::SelectClear,
@@ -96,22 +228,36 @@ impl ViewTemplate for GraphDisplay {
                                     move |mut event: ListenerMut<Pointer<DragStart>>,
                                     mut gesture_state: ResMut<GestureState>,
                                     mut writer: EventWriter<GraphEvent>,
+                                    keys: Res<ButtonInput<KeyCode>>,
+                                    bindings: Res<InputBindings>,
                                     rel: crate::relative_pos::RelativeWorldPositions| {
                                         event.stop_propagation();
                                         let pos = rel.transform_relative(event.listener(), event.pointer_location.position, 1);
-                                        gesture_state.mode = DragMode::RectSelect(pos);
-                                        writer.send(GraphEvent {
-                                            target: event.target(),
-                                            gesture: Gesture::SelectRect(Rect::from_corners(
-                                                pos,
-                                                pos), DragAction::Start),
-                                        });
+                                        if bindings.lasso_select.matches(event.button, &keys) {
+                                            gesture_state.mode = DragMode::Lasso(vec![pos]);
+                                            writer.send(GraphEvent {
+                                                target: event.target(),
+                                                gesture: Gesture::SelectLasso(vec![pos], DragAction::Start),
+                                            });
+                                        } else if bindings.pan.matches(event.button, &keys) {
+                                            gesture_state.mode = DragMode::Pan;
+                                        } else if bindings.box_select.matches(event.button, &keys) {
+                                            gesture_state.mode = DragMode::RectSelect(pos);
+                                            writer.send(GraphEvent {
+                                                target: event.target(),
+                                                gesture: Gesture::SelectRect(Rect::from_corners(
+                                                    pos,
+                                                    pos), DragAction::Start),
+                                            });
+                                        }
                                 }),
                                 On::<Pointer<DragEnd>>::run(
                                     move |mut event: ListenerMut<Pointer<DragEnd>>,
                                     mut gesture_state: ResMut<GestureState>,
                                     mut writer: EventWriter<GraphEvent>,
-                                    rel: crate::relative_pos::RelativeWorldPositions| {
+                                    rel: crate::relative_pos::RelativeWorldPositions,
+                                    indices: Query<&GraphSpatialIndex>,
+                                    targets: Query<&NodeDisplayTarget>| {
                                         event.stop_propagation();
                                         if let DragMode::RectSelect(pos) = gesture_state.mode {
                                             writer.send(GraphEvent {
@@ -121,12 +267,36 @@ impl ViewTemplate for GraphDisplay {
                                                     pos), DragAction::Finish),
                                             });
                                             gesture_state.mode = DragMode::None;
+                                        } else if let DragMode::Lasso(ref points) = gesture_state.mode {
+                                            if let Ok(index) = indices.get(event.listener()) {
+                                                let bounds = points.iter().fold(Rect::from_corners(points[0], points[0]), |bounds, point| bounds.union_point(*point));
+                                                for (display, rect) in index.nodes.query(bounds) {
+                                                    let Ok(target) = targets.get(display) else {
+                                                        continue;
+                                                    };
+                                                    if lasso_contains_rect(points, rect) {
+                                                        writer.send(GraphEvent {
+                                                            target: event.target(),
+                                                            gesture: Gesture::SelectAdd(target.node),
+                                                        });
+                                                    }
+                                                }
+                                            }
+                                            writer.send(GraphEvent {
+                                                target: event.target(),
+                                                gesture: Gesture::SelectLasso(points.clone(), DragAction::Finish),
+                                            });
+                                            gesture_state.mode = DragMode::None;
+                                        } else if gesture_state.mode == DragMode::Pan {
+                                            gesture_state.mode = DragMode::None;
                                         }
                                 }),
                                 On::<Pointer<Drag>>::run({
                                     move |mut event: ListenerMut<Pointer<Drag>>,
-                                    gesture_state: ResMut<GestureState>,
+                                    mut gesture_state: ResMut<GestureState>,
                                     mut writer: EventWriter<GraphEvent>,
+                                    canvases: Query<&GraphCanvas>,
+                                    mut scroll_areas: Query<&mut ScrollArea>,
                                     rel: crate::relative_pos::RelativeWorldPositions
                                     | {
                                         event.stop_propagation();
@@ -137,16 +307,76 @@ impl ViewTemplate for GraphDisplay {
                                                 rel.transform_relative(event.listener(), event.pointer_location.position, 1),
                                                 pos), DragAction::Update),
                                         });
+                                    } else if let DragMode::Lasso(ref points) = gesture_state.mode {
+                                        let pos = rel.transform_relative(event.listener(), event.pointer_location.position, 1);
+                                        if points.last().is_none_or(|last| last.distance(pos) >= LASSO_MIN_SEGMENT) {
+                                            let mut points = points.clone();
+                                            points.push(pos);
+                                            writer.send(GraphEvent {
+                                                target: event.target(),
+                                                gesture: Gesture::SelectLasso(points.clone(), DragAction::Update),
+                                            });
+                                            gesture_state.mode = DragMode::Lasso(points);
+                                        }
+                                    } else if gesture_state.mode == DragMode::Pan {
+                                        let Ok(canvas) = canvases.get(event.listener()) else {
+                                            return;
+                                        };
+                                        if let Ok(mut scroll_area) = scroll_areas.get_mut(canvas.scroll_area) {
+                                            scroll_area.scroll_by(-event.delta.x, -event.delta.y);
+                                        }
                                     }
                                     }
                                 }),
+                                On::<ScrollWheel>::run(
+                                    move |mut event: ListenerMut<ScrollWheel>,
+                                    settings: Res<TouchpadSettings>,
+                                    bindings: Res<InputBindings>,
+                                    keys: Res<ButtonInput<KeyCode>>,
+                                    time: Res<Time>,
+                                    windows: Query<&Window, With<PrimaryWindow>>,
+                                    mut canvases: Query<(&GraphCanvas, &mut ScrollMomentum, &Node, &GlobalTransform)>,
+                                    mut scroll_areas: Query<&mut ScrollArea>,
+                                    mut writer: EventWriter<GraphEvent>| {
+                                        event.stop_propagation();
+                                        let Ok((canvas, mut momentum, node, transform)) = canvases.get_mut(event.listener()) else {
+                                            return;
+                                        };
+                                        if bindings.zoom.pressed(&keys) {
+                                            let pointer = windows
+                                                .get_single()
+                                                .ok()
+                                                .and_then(|window| window.cursor_position())
+                                                .unwrap_or_default();
+                                            writer.send(GraphEvent {
+                                                target: event.listener(),
+                                                gesture: Gesture::Zoom(
+                                                    1. + event.delta.y * ZOOM_SENSITIVITY,
+                                                    pointer - node.logical_rect(transform).min,
+                                                ),
+                                            });
+                                            return;
+                                        }
+                                        let delta = if settings.natural_scrolling {
+                                            event.delta
+                                        } else {
+                                            -event.delta
+                                        };
+                                        if let Ok(mut scroll_area) = scroll_areas.get_mut(canvas.scroll_area) {
+                                            scroll_area.scroll_by(-delta.x, -delta.y);
+                                        }
+                                        momentum.record(delta, time.elapsed_seconds());
+                                }),
                             )
                         },
                         (),
                     )
+                    .insert(ScrollMomentum::default())
+                    .insert(GraphSpatialIndex::default())
+                    .insert(GraphViewport::default())
                     .insert(material.clone())
                     .style(style_node_graph_scroll)
-                    .children(self.children.clone()),
+                    .children((self.children.clone(), LassoDisplay)),
             )
             .style((style_node_graph, self.style.clone()))
             .content_style(style_node_graph_content)
@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::PointerButton;
+
+use crate::{auto_pan::GraphCanvas, DragAction, DragMode, Gesture, GestureState, GraphEvent};
+
+/// A modifier key. Either the left or right key satisfies the modifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    /// The control key.
+    Ctrl,
+    /// The shift key.
+    Shift,
+    /// The alt key, also known as option on macOS.
+    Alt,
+    /// The command key on macOS, or the Windows key.
+    Super,
+}
+
+impl Modifier {
+    /// True if the modifier key is currently held down.
+    pub fn pressed(self, keys: &ButtonInput<KeyCode>) -> bool {
+        match self {
+            Modifier::Ctrl => keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            Modifier::Shift => keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            Modifier::Alt => keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+            Modifier::Super => {
+                keys.any_pressed([KeyCode::SuperLeft, KeyCode::SuperRight, KeyCode::Meta])
+            }
+        }
+    }
+}
+
+/// A mouse button, optionally combined with a modifier key which must be held down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MouseBinding {
+    /// The button which must be pressed.
+    pub button: PointerButton,
+    /// The modifier key which must be held, if any.
+    pub modifier: Option<Modifier>,
+}
+
+impl MouseBinding {
+    /// Create a binding for a mouse button with no modifier.
+    pub const fn new(button: PointerButton) -> Self {
+        Self {
+            button,
+            modifier: None,
+        }
+    }
+
+    /// Require a modifier key to be held along with the button.
+    pub const fn with(mut self, modifier: Modifier) -> Self {
+        self.modifier = Some(modifier);
+        self
+    }
+
+    /// True if the button matches, and the modifier key (if any) is held down.
+    pub fn matches(&self, button: PointerButton, keys: &ButtonInput<KeyCode>) -> bool {
+        button == self.button && self.modifier.is_none_or(|m| m.pressed(keys))
+    }
+}
+
+/// Resource which maps graph editing gestures to mouse buttons and keys, so that the editor
+/// can follow the conventions of other node editors. When a drag on the background matches
+/// more than one binding, lasso selection takes priority, followed by panning and then box
+/// selection.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct InputBindings {
+    /// Dragging on the background with this button pans the view.
+    pub pan: MouseBinding,
+
+    /// Holding this modifier while scrolling zooms the view instead of scrolling it.
+    pub zoom: Modifier,
+
+    /// Dragging on the background with this button selects the nodes within a rectangle.
+    pub box_select: MouseBinding,
+
+    /// Dragging on the background with this button selects the nodes within a freeform lasso.
+    pub lasso_select: MouseBinding,
+
+    /// Holding this modifier while clicking a node adds it to the selection.
+    pub add_to_selection: Modifier,
+
+    /// Holding this modifier while clicking a node toggles whether it is selected.
+    pub toggle_selection: Modifier,
+
+    /// Pressing this key cancels the current drag gesture.
+    pub cancel: KeyCode,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            pan: MouseBinding::new(PointerButton::Middle),
+            zoom: Modifier::Ctrl,
+            box_select: MouseBinding::new(PointerButton::Primary),
+            lasso_select: MouseBinding::new(PointerButton::Primary).with(Modifier::Alt),
+            add_to_selection: Modifier::Shift,
+            toggle_selection: if cfg!(target_os = "macos") {
+                Modifier::Super
+            } else {
+                Modifier::Ctrl
+            },
+            cancel: KeyCode::Escape,
+        }
+    }
+}
+
+/// Cancels the current drag gesture when the cancel key is pressed. Nodes being moved are
+/// returned to where they started.
+pub(crate) fn cancel_graph_gesture(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<InputBindings>,
+    mut gesture_state: ResMut<GestureState>,
+    canvases: Query<Entity, With<GraphCanvas>>,
+    mut writer: EventWriter<GraphEvent>,
+) {
+    if gesture_state.mode == DragMode::None || !keys.just_pressed(bindings.cancel) {
+        return;
+    }
+    let gesture = match gesture_state.mode {
+        DragMode::Move => {
+            gesture_state.drag_distance = Vec2::ZERO;
+            gesture_state.pan_offset = Vec2::ZERO;
+            gesture_state.move_gesture(DragAction::Finish)
+        }
+        DragMode::Pan => {
+            gesture_state.mode = DragMode::None;
+            return;
+        }
+        DragMode::Resize(note, size) => Gesture::Resize(note, size, DragAction::Finish),
+        _ => Gesture::Cancel,
+    };
+    for canvas_id in canvases.iter() {
+        writer.send(GraphEvent {
+            target: canvas_id,
+            gesture: gesture.clone(),
+        });
+    }
+    gesture_state.mode = DragMode::None;
+    gesture_state.anchor = None;
+    gesture_state.moving.clear();
+    gesture_state.splice_target = None;
+}
@@ -0,0 +1,238 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::prelude::*;
+use bevy_quill_obsidian::colors;
+
+use crate::{
+    materials::{DrawPathMaterial, DrawablePath},
+    DragMode, GestureState,
+};
+
+/// Minimum distance between successive points of a lasso, in pixels.
+pub(crate) const LASSO_MIN_SEGMENT: f32 = 4.;
+
+/// True if the point is inside the polygon, using the even-odd rule.
+fn polygon_contains_point(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    let mut prev = match polygon.last() {
+        Some(prev) => *prev,
+        None => return false,
+    };
+    for &curr in polygon {
+        if (curr.y > point.y) != (prev.y > point.y)
+            && point.x < (prev.x - curr.x) * (point.y - curr.y) / (prev.y - curr.y) + curr.x
+        {
+            inside = !inside;
+        }
+        prev = curr;
+    }
+    inside
+}
+
+/// True if the segments `a0-a1` and `b0-b1` cross at a single point.
+fn segments_cross(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> bool {
+    let side = |p: Vec2, q: Vec2, r: Vec2| (q - p).perp_dot(r - p);
+    let (d0, d1) = (side(b0, b1, a0), side(b0, b1, a1));
+    let (d2, d3) = (side(a0, a1, b0), side(a0, a1, b1));
+    d0 * d1 < 0. && d2 * d3 < 0.
+}
+
+/// True if the rectangle lies entirely within the lasso polygon. This is the case if all of the
+/// corners of the rectangle are inside the polygon, none of the polygon's vertices are inside
+/// the rectangle, and none of the polygon's edges cross the rectangle's edges. The last check
+/// catches concave lassos whose edges cut through the rectangle between its corners.
+pub(crate) fn lasso_contains_rect(polygon: &[Vec2], rect: Rect) -> bool {
+    let corners = [
+        rect.min,
+        Vec2::new(rect.max.x, rect.min.y),
+        rect.max,
+        Vec2::new(rect.min.x, rect.max.y),
+    ];
+    polygon.len() > 2
+        && corners
+            .iter()
+            .all(|corner| polygon_contains_point(polygon, *corner))
+        && !polygon.iter().any(|vertex| rect.contains(*vertex))
+        && !(0..polygon.len()).any(|i| {
+            let (p0, p1) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            (0..4).any(|j| segments_cross(p0, p1, corners[j], corners[(j + 1) % 4]))
+        })
+}
+
+fn style_lasso(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .pointer_events(false);
+}
+
+/// Draws the outline of the lasso while a lasso selection is in progress.
+#[derive(Clone, PartialEq)]
+pub(crate) struct LassoDisplay;
+
+impl ViewTemplate for LassoDisplay {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let points = match &cx.use_resource::<GestureState>().mode {
+            DragMode::Lasso(points) => points.clone(),
+            _ => Vec::new(),
+        };
+        let material = cx.create_memo(
+            |world, _| {
+                let mut ui_materials = world
+                    .get_resource_mut::<Assets<DrawPathMaterial>>()
+                    .unwrap();
+                ui_materials.add(DrawPathMaterial::default())
+            },
+            (),
+        );
+        let material_id = material.id();
+
+        Cond::new(
+            points.len() > 1,
+            Element::<MaterialNodeBundle<DrawPathMaterial>>::new()
+                .named("NodeGraph::Lasso")
+                .insert(material)
+                .style(style_lasso)
+                .effect(
+                    move |cx, ent, points| {
+                        let mut path = DrawablePath::new(1.3);
+                        path.move_to(points[0]);
+                        for point in points.iter().skip(1) {
+                            path.line_to(*point);
+                        }
+                        path.line_to(points[0]);
+                        let bounds = path.bounds();
+
+                        let mut style = cx.world_mut().get_mut::<Style>(ent).unwrap();
+                        style.left = ui::Val::Px(bounds.min.x);
+                        style.top = ui::Val::Px(bounds.min.y);
+                        style.width = ui::Val::Px(bounds.width());
+                        style.height = ui::Val::Px(bounds.height());
+
+                        let mut materials = cx
+                            .world_mut()
+                            .get_resource_mut::<Assets<DrawPathMaterial>>()
+                            .unwrap();
+                        let material = materials.get_mut(material_id).unwrap();
+                        material.update_path(&path);
+                        material.update_color(
+                            colors::FOCUS,
+                            Vec2::ZERO,
+                            colors::FOCUS,
+                            bounds.size(),
+                        );
+                    },
+                    points,
+                ),
+            (),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(f32, f32)]) -> Vec<Vec2> {
+        points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+    }
+
+    fn square() -> Vec<Vec2> {
+        polygon(&[(0., 0.), (100., 0.), (100., 100.), (0., 100.)])
+    }
+
+    /// A U shape, open at the bottom, with a notch between x = 10 and x = 20.
+    fn u_shape() -> Vec<Vec2> {
+        polygon(&[
+            (0., 0.),
+            (30., 0.),
+            (30., 30.),
+            (20., 30.),
+            (20., 10.),
+            (10., 10.),
+            (10., 30.),
+            (0., 30.),
+        ])
+    }
+
+    /// Two triangles which meet at (5, 5), drawn as a single self-intersecting path.
+    fn bowtie() -> Vec<Vec2> {
+        polygon(&[(0., 0.), (10., 10.), (10., 0.), (0., 10.)])
+    }
+
+    #[test]
+    fn test_contains_point_convex() {
+        let square = square();
+        assert!(polygon_contains_point(&square, Vec2::new(50., 50.)));
+        assert!(!polygon_contains_point(&square, Vec2::new(150., 50.)));
+        assert!(!polygon_contains_point(&square, Vec2::new(-1., 50.)));
+        assert!(!polygon_contains_point(&square, Vec2::new(50., 101.)));
+    }
+
+    #[test]
+    fn test_contains_point_degenerate() {
+        assert!(!polygon_contains_point(&[], Vec2::ZERO));
+        assert!(!polygon_contains_point(&[Vec2::ZERO], Vec2::ZERO));
+        let line = polygon(&[(0., 0.), (10., 10.)]);
+        assert!(!polygon_contains_point(&line, Vec2::new(5., 5.)));
+    }
+
+    #[test]
+    fn test_contains_point_concave() {
+        let u = u_shape();
+        assert!(polygon_contains_point(&u, Vec2::new(5., 20.)));
+        assert!(polygon_contains_point(&u, Vec2::new(25., 20.)));
+        assert!(polygon_contains_point(&u, Vec2::new(15., 5.)));
+        assert!(!polygon_contains_point(&u, Vec2::new(15., 20.)));
+    }
+
+    #[test]
+    fn test_contains_point_self_intersecting() {
+        let bowtie = bowtie();
+        assert!(polygon_contains_point(&bowtie, Vec2::new(2., 5.)));
+        assert!(polygon_contains_point(&bowtie, Vec2::new(8., 5.)));
+        assert!(!polygon_contains_point(&bowtie, Vec2::new(5., 2.)));
+        assert!(!polygon_contains_point(&bowtie, Vec2::new(5., 8.)));
+    }
+
+    #[test]
+    fn test_lasso_contains_rect() {
+        let square = square();
+        let inside = Rect::new(10., 10., 20., 20.);
+        assert!(lasso_contains_rect(&square, inside));
+        assert!(!lasso_contains_rect(
+            &square,
+            Rect::new(90., 90., 110., 110.)
+        ));
+        assert!(!lasso_contains_rect(
+            &square,
+            Rect::new(-20., -20., 120., 120.)
+        ));
+
+        // A lasso with fewer than three points selects nothing.
+        assert!(!lasso_contains_rect(&square[..2], inside));
+    }
+
+    #[test]
+    fn test_lasso_contains_rect_concave() {
+        let u = u_shape();
+        assert!(lasso_contains_rect(&u, Rect::new(2., 12., 8., 28.)));
+        assert!(lasso_contains_rect(&u, Rect::new(2., 2., 28., 8.)));
+
+        // The notch's vertices are inside this rectangle.
+        assert!(!lasso_contains_rect(&u, Rect::new(5., 5., 25., 25.)));
+
+        // All the corners are in the arms, but the notch cuts through the rectangle.
+        assert!(!lasso_contains_rect(&u, Rect::new(5., 15., 25., 25.)));
+    }
+
+    #[test]
+    fn test_lasso_contains_rect_self_intersecting() {
+        let bowtie = bowtie();
+        assert!(lasso_contains_rect(&bowtie, Rect::new(1., 4., 2., 6.)));
+        assert!(lasso_contains_rect(&bowtie, Rect::new(8., 4., 9., 6.)));
+
+        // Spans both triangles, across the crossing point.
+        assert!(!lasso_contains_rect(&bowtie, Rect::new(1., 4., 9., 6.)));
+    }
+}
@@ -1,28 +1,49 @@
 #![feature(impl_trait_in_assoc_type, associated_type_defaults)]
 
+mod auto_pan;
+mod document;
 mod edge_display;
 mod edge_display_ls;
 mod events;
+mod export;
 mod graph_display;
+mod input_bindings;
+mod lasso;
+mod markdown;
 mod materials;
 mod node_display;
+mod node_status;
+mod note_display;
 mod relative_pos;
+mod spatial_index;
 mod terminal_display;
+mod terminal_tooltip;
+mod touchpad;
 
 use bevy::{
-    app::{App, Plugin},
+    app::{App, Plugin, Update},
     asset::embedded_asset,
     sprite::Material2dPlugin,
     ui::UiMaterialPlugin,
 };
 
 use bevy_mod_picking::prelude::EventListenerPlugin;
+pub use document::GraphViewState;
 pub use edge_display::EdgeDisplay;
 pub use events::*;
-pub use graph_display::GraphDisplay;
+pub use export::{export_graph_svg, save_graph_svg, GraphExportOptions};
+pub use graph_display::{GraphDisplay, LevelOfDetail};
+pub use input_bindings::{InputBindings, Modifier, MouseBinding};
 use materials::{DotGridMaterial, DrawPathMaterial, LineMaterial};
 pub use node_display::NodeDisplay;
-pub use terminal_display::{InputTerminalDisplay, NoTerminalDisplay, OutputTerminalDisplay};
+pub use node_status::NodeStatus;
+pub use note_display::NoteDisplay;
+pub use spatial_index::{GraphViewport, SpatialGrid};
+pub use terminal_display::{
+    InputTerminalDisplay, NoTerminalDisplay, OutputTerminalDisplay, TerminalKind, TerminalShape,
+};
+pub use terminal_tooltip::{TerminalInfo, TerminalInfoProvider, TerminalTooltips};
+pub use touchpad::TouchpadSettings;
 
 /// Plugin for the Obsidian UI library.
 pub struct ObsidianGraphPlugin;
@@ -33,12 +54,27 @@ impl Plugin for ObsidianGraphPlugin {
         embedded_asset!(app, "assets/draw_path.wgsl");
         embedded_asset!(app, "assets/line_material.wgsl");
         app.init_resource::<GestureState>()
+            .init_resource::<InputBindings>()
+            .init_resource::<TerminalTooltips>()
+            .init_resource::<TouchpadSettings>()
             .add_plugins((
                 UiMaterialPlugin::<DotGridMaterial>::default(),
                 UiMaterialPlugin::<DrawPathMaterial>::default(),
                 Material2dPlugin::<LineMaterial>::default(),
                 EventListenerPlugin::<GraphEvent>::default(),
             ))
-            .add_event::<GraphEvent>();
+            .add_event::<GraphEvent>()
+            .add_systems(
+                Update,
+                (
+                    auto_pan::auto_pan_graph,
+                    document::sync_graph_view_state,
+                    edge_display::follow_edge_terminals,
+                    input_bindings::cancel_graph_gesture,
+                    spatial_index::update_spatial_index,
+                    touchpad::kinetic_scroll_graph,
+                    touchpad::pinch_zoom_graph,
+                ),
+            );
     }
 }
@@ -0,0 +1,368 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::prelude::*;
+use bevy_quill_obsidian::colors;
+
+const FONT_MEDIUM: &str =
+    "embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Medium.ttf";
+const FONT_ITALIC: &str =
+    "embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-MediumItalic.ttf";
+const FONT_BOLD: &str =
+    "embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Bold.ttf";
+const FONT_BOLD_ITALIC: &str =
+    "embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-BoldItalic.ttf";
+
+/// The kind of a block of markdown text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BlockKind {
+    Paragraph,
+    /// A heading, with a level from 1 to 3.
+    Heading(u8),
+    /// An item in a bulleted list.
+    Bullet,
+}
+
+/// A run of text with the same formatting.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Span {
+    pub(crate) text: String,
+    pub(crate) bold: bool,
+    pub(crate) italic: bool,
+    pub(crate) code: bool,
+}
+
+/// A paragraph, heading or list item.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Block {
+    pub(crate) kind: BlockKind,
+    pub(crate) spans: Vec<Span>,
+}
+
+/// Parse a small subset of markdown: `#` headings, `-` or `*` bullets, and paragraphs
+/// separated by blank lines, with `**bold**`, `*italic*` and `` `code` `` spans. Anything else
+/// is shown as plain text.
+pub(crate) fn parse_markdown(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph = String::new();
+    let flush = |paragraph: &mut String, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block {
+                kind: BlockKind::Paragraph,
+                spans: parse_spans(paragraph),
+            });
+            paragraph.clear();
+        }
+    };
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some((level, rest)) = parse_heading(line) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block {
+                kind: BlockKind::Heading(level),
+                spans: parse_spans(rest),
+            });
+        } else if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block {
+                kind: BlockKind::Bullet,
+                spans: parse_spans(rest.trim_start()),
+            });
+        } else {
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(line);
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn parse_heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|ch| *ch == '#').count();
+    if (1..=3).contains(&level) && line[level..].starts_with(' ') {
+        Some((level as u8, line[level..].trim()))
+    } else {
+        None
+    }
+}
+
+fn parse_spans(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut current = Span::default();
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        let marker = if current.code {
+            // Formatting markers are literal within code spans.
+            (ch == '`').then_some(1)
+        } else if rest.starts_with("**") {
+            Some(2)
+        } else if ch == '*' || ch == '`' {
+            Some(1)
+        } else {
+            None
+        };
+        match marker {
+            Some(len) => {
+                if !current.text.is_empty() {
+                    spans.push(current.clone());
+                    current.text.clear();
+                }
+                match (len, ch) {
+                    (2, _) => current.bold = !current.bold,
+                    (_, '`') => current.code = !current.code,
+                    _ => current.italic = !current.italic,
+                }
+                rest = &rest[len..];
+            }
+            None => {
+                current.text.push(ch);
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+    if !current.text.is_empty() {
+        spans.push(current);
+    }
+    spans
+}
+
+fn style_markdown(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .row_gap(4);
+}
+
+fn style_block(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .flex_wrap(ui::FlexWrap::Wrap)
+        .align_items(ui::AlignItems::Baseline);
+}
+
+fn style_bullet(ss: &mut StyleBuilder) {
+    ss.width(12).flex_shrink(0.);
+}
+
+fn style_code(ss: &mut StyleBuilder) {
+    ss.background_color(colors::U1.with_alpha(0.5))
+        .color(colors::FOREGROUND)
+        .border_radius(2.)
+        .padding((2, 0));
+}
+
+/// Displays text formatted with the subset of markdown understood by [`parse_markdown`].
+#[derive(Clone, PartialEq)]
+pub(crate) struct Markdown {
+    pub(crate) text: String,
+}
+
+impl ViewTemplate for Markdown {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .named("Markdown")
+            .style(style_markdown)
+            .children(For::index(&parse_markdown(&self.text), |block, _| {
+                MarkdownBlock {
+                    block: block.clone(),
+                }
+            }))
+    }
+}
+
+/// Displays one block of a [`Markdown`] text.
+#[derive(Clone, PartialEq)]
+struct MarkdownBlock {
+    block: Block,
+}
+
+impl ViewTemplate for MarkdownBlock {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let block = &self.block;
+        let font_size = match block.kind {
+            BlockKind::Heading(1) => 18.,
+            BlockKind::Heading(2) => 16.,
+            BlockKind::Heading(_) => 14.,
+            _ => 13.,
+        };
+        let heading = matches!(block.kind, BlockKind::Heading(_));
+        Element::<NodeBundle>::new()
+            .style(style_block)
+            .style_dyn(
+                |font_size, sb| {
+                    sb.font_size(font_size);
+                },
+                font_size,
+            )
+            .children((
+                Cond::new(
+                    block.kind == BlockKind::Bullet,
+                    Element::<NodeBundle>::new()
+                        .style(style_bullet)
+                        .children("\u{2022}"),
+                    (),
+                ),
+                For::index(&block.spans, move |span, _| {
+                    Element::<NodeBundle>::new()
+                        .style_dyn(
+                            |(bold, italic, code), sb| {
+                                sb.font(match (bold, italic) {
+                                    (false, false) => FONT_MEDIUM,
+                                    (false, true) => FONT_ITALIC,
+                                    (true, false) => FONT_BOLD,
+                                    (true, true) => FONT_BOLD_ITALIC,
+                                });
+                                if code {
+                                    style_code(sb);
+                                }
+                            },
+                            (span.bold || heading, span.italic, span.code),
+                        )
+                        .children(span.text.clone())
+                }),
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(text: &str) -> Span {
+        Span {
+            text: text.to_string(),
+            ..default()
+        }
+    }
+
+    fn block(kind: BlockKind, spans: Vec<Span>) -> Block {
+        Block { kind, spans }
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        assert!(parse_markdown("").is_empty());
+        assert!(parse_markdown("\n  \n").is_empty());
+        // Lines are joined until a blank line.
+        assert_eq!(
+            parse_markdown("one\n  two\n\nthree"),
+            [
+                block(BlockKind::Paragraph, vec![plain("one two")]),
+                block(BlockKind::Paragraph, vec![plain("three")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_headings() {
+        assert_eq!(
+            parse_markdown("# One\n## Two\n###   Three\ntext"),
+            [
+                block(BlockKind::Heading(1), vec![plain("One")]),
+                block(BlockKind::Heading(2), vec![plain("Two")]),
+                block(BlockKind::Heading(3), vec![plain("Three")]),
+                block(BlockKind::Paragraph, vec![plain("text")]),
+            ]
+        );
+        // Only three levels are supported, and a space is required after the marker.
+        assert_eq!(
+            parse_markdown("#### Four"),
+            [block(BlockKind::Paragraph, vec![plain("#### Four")])]
+        );
+        assert_eq!(
+            parse_markdown("#tag"),
+            [block(BlockKind::Paragraph, vec![plain("#tag")])]
+        );
+    }
+
+    #[test]
+    fn test_emphasis() {
+        let bold = |text: &str| Span {
+            bold: true,
+            ..plain(text)
+        };
+        let italic = |text: &str| Span {
+            italic: true,
+            ..plain(text)
+        };
+        assert_eq!(
+            parse_spans("a **b** *c* d"),
+            [plain("a "), bold("b"), plain(" "), italic("c"), plain(" d")]
+        );
+        // Emphasis can be nested.
+        assert_eq!(
+            parse_spans("**b *bi* b**"),
+            [
+                bold("b "),
+                Span {
+                    bold: true,
+                    italic: true,
+                    ..plain("bi")
+                },
+                bold(" b"),
+            ]
+        );
+        // Unterminated emphasis continues to the end of the block.
+        assert_eq!(parse_spans("a *b"), [plain("a "), italic("b")]);
+    }
+
+    #[test]
+    fn test_code_spans() {
+        let code = |text: &str| Span {
+            code: true,
+            ..plain(text)
+        };
+        assert_eq!(
+            parse_spans("run `cargo test` now"),
+            [plain("run "), code("cargo test"), plain(" now")]
+        );
+        // Formatting markers are literal within code spans.
+        assert_eq!(parse_spans("`a **b** *c*`"), [code("a **b** *c*")]);
+    }
+
+    #[test]
+    fn test_lists() {
+        assert_eq!(
+            parse_markdown("intro\n- one\n*  two\n-three"),
+            [
+                block(BlockKind::Paragraph, vec![plain("intro")]),
+                block(BlockKind::Bullet, vec![plain("one")]),
+                block(BlockKind::Bullet, vec![plain("two")]),
+                block(BlockKind::Paragraph, vec![plain("-three")]),
+            ]
+        );
+        // Items can contain formatting.
+        assert_eq!(
+            parse_markdown("- `x` item"),
+            [block(
+                BlockKind::Bullet,
+                vec![
+                    Span {
+                        code: true,
+                        ..plain("x")
+                    },
+                    plain(" item"),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_links() {
+        // Links aren't supported, so they are shown as written.
+        assert_eq!(
+            parse_markdown("see [docs](https://example.com)"),
+            [block(
+                BlockKind::Paragraph,
+                vec![plain("see [docs](https://example.com)")]
+            )]
+        );
+    }
+}
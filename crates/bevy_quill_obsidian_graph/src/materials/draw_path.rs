@@ -62,6 +62,26 @@ impl DrawablePath {
         }
         bounds.inflate(self.width * 0.5)
     }
+
+    /// Approximate the path as a list of points, subdividing each curve into `steps` lines.
+    pub fn points(&self, steps: usize) -> Vec<Vec2> {
+        let mut points = Vec::new();
+        for segment in &self.commands {
+            match segment {
+                DrawablePathSegment::Move(point) | DrawablePathSegment::Line(point) => {
+                    points.push(*point);
+                }
+                DrawablePathSegment::Quadratic((control, point)) => {
+                    let start = points.last().copied().unwrap_or(*control);
+                    for step in 1..=steps {
+                        let t = step as f32 / steps as f32;
+                        points.push(start.lerp(*control, t).lerp(control.lerp(*point, t), t));
+                    }
+                }
+            }
+        }
+        points
+    }
 }
 
 /// Type of drawing operation for each path segment.
@@ -10,7 +10,21 @@ use bevy_quill_obsidian::{
     hooks::{UseElementRect, UseIsHover},
 };
 
-use crate::{DragAction, DragMode, Gesture, GestureState, GraphEvent};
+use crate::{
+    edge_display::EdgeGeometry,
+    input_bindings::InputBindings,
+    node_status::{NodeStatus, NodeStatusStrip},
+    spatial_index::{node_display_rect, GraphSpatialIndex},
+    DragAction, DragMode, Gesture, GestureState, GraphEvent, LevelOfDetail,
+};
+
+/// Identifies the node which a node display entity represents.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct NodeDisplayTarget {
+    pub(crate) node: Entity,
+    pub(crate) position: Vec2,
+    pub(crate) selected: bool,
+}
 
 fn style_node_graph_node(ss: &mut StyleBuilder) {
     ss.display(ui::Display::Flex)
@@ -24,7 +38,11 @@ const NODE_BORDER_RADIUS: f32 = 5.;
 const NODE_BORDER_WIDTH: f32 = 1.;
 
 fn style_node_graph_node_title(ss: &mut StyleBuilder) {
-    ss.border(1)
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .column_gap(4)
+        .border(1)
         .border_color(colors::U4)
         .border(ui::UiRect {
             left: ui::Val::Px(NODE_BORDER_WIDTH),
@@ -38,7 +56,6 @@ fn style_node_graph_node_title(ss: &mut StyleBuilder) {
             bottom_left: ui::Val::Px(0.),
             bottom_right: ui::Val::Px(0.),
         })
-        .background_color(colors::Y_GREEN.darker(0.05))
         .padding((6, 2))
         .cursor(CursorIcon::Grab);
 }
@@ -102,6 +119,13 @@ pub struct NodeDisplay {
     pub width: ui::Val,
     /// The title of the node.
     pub title: String,
+    /// Background color of the node's title bar. The title text is drawn in a light or dark
+    /// color, whichever is more readable.
+    pub title_color: Srgba,
+    /// Custom content for the node's title bar, which replaces the title text. This can hold
+    /// icons and per-node actions such as an enable toggle or menu button. Dragging the title
+    /// bar moves the node, so interactive widgets should stop the propagation of drag events.
+    pub header: Option<ViewChild>,
     /// Whether the node is currently selected.
     pub selected: bool,
     /// The content of the node.
@@ -116,6 +140,8 @@ impl NodeDisplay {
             node_id,
             position: default(),
             title: default(),
+            title_color: colors::Y_GREEN.darker(0.05),
+            header: None,
             width: ui::Val::Auto,
             selected: false,
             children: default(),
@@ -146,6 +172,18 @@ impl NodeDisplay {
         self
     }
 
+    /// Set the background color of the node's title bar.
+    pub fn title_color(mut self, color: impl Into<Srgba>) -> Self {
+        self.title_color = color.into();
+        self
+    }
+
+    /// Set custom content for the node's title bar, in place of the title text.
+    pub fn header(mut self, header: impl IntoViewChild) -> Self {
+        self.header = Some(header.into_view_child());
+        self
+    }
+
     /// Set the children of the node.
     pub fn children(mut self, children: impl IntoViewChild) -> Self {
         self.children = children.into_view_child();
@@ -161,11 +199,25 @@ impl ViewTemplate for NodeDisplay {
         let display_id = self.display_id;
         let hovering = cx.is_hovered(display_id);
         let rect = cx.use_element_rect(display_id);
+        let simplified =
+            cx.use_inherited_component::<LevelOfDetail>() == Some(&LevelOfDetail::Simplified);
+        let status = cx
+            .use_component::<NodeStatus>(node_id)
+            .cloned()
+            .unwrap_or_default();
 
         Element::<NodeBundle>::for_entity(display_id)
             .named("NodeGraph::Node")
             .style(style_node_graph_node)
             .insert_dyn(move |_| node_event_handlers(display_id, node_id), ())
+            .insert_dyn(
+                |(node, position, selected)| NodeDisplayTarget {
+                    node,
+                    position: position.as_vec2(),
+                    selected,
+                },
+                (node_id, position, self.selected),
+            )
             .effect(
                 move |cx, ent, (position, size)| {
                     if size.x > 0 && size.y > 0 {
@@ -195,27 +247,73 @@ impl ViewTemplate for NodeDisplay {
                         },
                         self.selected,
                     )
-                    .insert_dyn(move |_| title_event_handlers(display_id), ())
-                    .children(self.title.clone()),
-                Element::<NodeBundle>::new()
-                    .style(style_node_graph_node_content)
                     .style_dyn(
-                        |selected, sb| {
-                            sb.border_color(if selected {
-                                colors::FOREGROUND
-                            } else {
-                                colors::U4
-                            });
+                        |color, sb| {
+                            sb.background_color(color)
+                                .color(colors::text_color_for(color));
                         },
-                        self.selected,
+                        self.title_color,
                     )
                     .style_dyn(
-                        |width, sb| {
-                            sb.width(width);
+                        |simplified, sb| {
+                            // Without the content below it, the title is a complete block.
+                            let bottom = if simplified { NODE_BORDER_RADIUS } else { 0. };
+                            sb.border(ui::UiRect {
+                                left: ui::Val::Px(NODE_BORDER_WIDTH),
+                                right: ui::Val::Px(NODE_BORDER_WIDTH),
+                                top: ui::Val::Px(NODE_BORDER_WIDTH),
+                                bottom: ui::Val::Px(if simplified {
+                                    NODE_BORDER_WIDTH
+                                } else {
+                                    0.
+                                }),
+                            })
+                            .border_radius(ui::BorderRadius {
+                                top_left: ui::Val::Px(NODE_BORDER_RADIUS),
+                                top_right: ui::Val::Px(NODE_BORDER_RADIUS),
+                                bottom_left: ui::Val::Px(bottom),
+                                bottom_right: ui::Val::Px(bottom),
+                            });
                         },
-                        self.width,
+                        simplified,
                     )
-                    .children(self.children.clone()),
+                    .insert_dyn(move |_| title_event_handlers(display_id, node_id, true), ())
+                    .children(
+                        self.header
+                            .clone()
+                            .unwrap_or_else(|| self.title.clone().into_view_child()),
+                    ),
+                Cond::new(
+                    status != NodeStatus::Idle,
+                    NodeStatusStrip {
+                        status,
+                        selected: self.selected,
+                    },
+                    (),
+                ),
+                Cond::new(
+                    !simplified,
+                    Element::<NodeBundle>::new()
+                        .style(style_node_graph_node_content)
+                        .style_dyn(
+                            |selected, sb| {
+                                sb.border_color(if selected {
+                                    colors::FOREGROUND
+                                } else {
+                                    colors::U4
+                                });
+                            },
+                            self.selected,
+                        )
+                        .style_dyn(
+                            |width, sb| {
+                                sb.width(width);
+                            },
+                            self.width,
+                        )
+                        .children(self.children.clone()),
+                    (),
+                ),
                 Cond::new(
                     hovering,
                     Element::<NodeBundle>::new()
@@ -228,22 +326,23 @@ impl ViewTemplate for NodeDisplay {
 }
 
 #[allow(clippy::type_complexity)]
-fn node_event_handlers(id: Entity, node_id: Entity) -> (On<Pointer<Down>>, On<Pointer<DragStart>>) {
+pub(crate) fn node_event_handlers(
+    id: Entity,
+    node_id: Entity,
+) -> (On<Pointer<Down>>, On<Pointer<DragStart>>) {
     (
         On::<Pointer<Down>>::run(
             move |mut event: ListenerMut<Pointer<Down>>,
                   mut writer: EventWriter<GraphEvent>,
-                  keys: Res<ButtonInput<KeyCode>>| {
+                  keys: Res<ButtonInput<KeyCode>>,
+                  bindings: Res<InputBindings>| {
                 event.stop_propagation();
-                let is_shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
-                let is_ctrl =
-                    keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::Meta]);
-                if is_ctrl {
+                if bindings.toggle_selection.pressed(&keys) {
                     writer.send(GraphEvent {
                         target: id,
                         gesture: Gesture::SelectToggle(node_id),
                     });
-                } else if is_shift {
+                } else if bindings.add_to_selection.pressed(&keys) {
                     writer.send(GraphEvent {
                         target: id,
                         gesture: Gesture::SelectAdd(node_id),
@@ -262,9 +361,33 @@ fn node_event_handlers(id: Entity, node_id: Entity) -> (On<Pointer<Down>>, On<Po
     )
 }
 
+/// Find the edge, if any, which passes underneath the node display.
+fn find_splice_target(
+    id: Entity,
+    nodes: &Query<(&Node, &Style, &Parent)>,
+    indices: &Query<&GraphSpatialIndex>,
+    edges: &Query<&EdgeGeometry>,
+) -> Option<Entity> {
+    let (node, style, parent) = nodes.get(id).ok()?;
+    let rect = node_display_rect(node, style)?;
+    let index = indices.get(parent.get()).ok()?;
+    index
+        .edges
+        .query(rect)
+        .into_iter()
+        .filter_map(|(edge, _)| edges.get(edge).ok())
+        .filter(|edge| edge.edge_id.is_some())
+        .find(|edge| edge.crosses_rect(rect))
+        .and_then(|edge| edge.edge_id)
+}
+
+/// Event handlers which move the node, and any other selected nodes, by dragging. If `splice`
+/// is true, dropping the node onto an edge sends a [`Gesture::Splice`].
 #[allow(clippy::type_complexity)]
-fn title_event_handlers(
+pub(crate) fn title_event_handlers(
     id: Entity,
+    node_id: Entity,
+    splice: bool,
 ) -> (
     On<Pointer<DragStart>>,
     On<Pointer<DragEnd>>,
@@ -274,12 +397,26 @@ fn title_event_handlers(
         On::<Pointer<DragStart>>::run(
             move |mut event: ListenerMut<Pointer<DragStart>>,
                   mut gesture_state: ResMut<GestureState>,
-                  mut writer: EventWriter<GraphEvent>| {
+                  mut writer: EventWriter<GraphEvent>,
+                  targets: Query<(Entity, &NodeDisplayTarget, &Parent)>| {
                 event.stop_propagation();
                 gesture_state.mode = DragMode::Move;
+                // Only move the selected nodes in the same graph as the dragged node, since other
+                // graph displays may be open at the same time.
+                let canvas = targets.get(id).ok().map(|(_, _, parent)| parent.get());
+                gesture_state.moving = targets
+                    .iter()
+                    .filter(|(display, target, parent)| {
+                        *display == id || (target.selected && Some(parent.get()) == canvas)
+                    })
+                    .map(|(_, target, _)| (target.node, target.position))
+                    .collect();
+                gesture_state.drag_distance = Vec2::ZERO;
+                gesture_state.pan_offset = Vec2::ZERO;
+                gesture_state.splice_target = None;
                 writer.send(GraphEvent {
                     target: id,
-                    gesture: Gesture::Move(Vec2::default(), DragAction::Start),
+                    gesture: gesture_state.move_gesture(DragAction::Start),
                 });
             },
         ),
@@ -290,22 +427,42 @@ fn title_event_handlers(
                 event.stop_propagation();
                 if gesture_state.mode == DragMode::Move {
                     gesture_state.mode = DragMode::None;
+                    gesture_state.drag_distance = event.distance;
                     writer.send(GraphEvent {
                         target: id,
-                        gesture: Gesture::Move(event.distance, DragAction::Finish),
+                        gesture: gesture_state.move_gesture(DragAction::Finish),
                     });
+                    gesture_state.moving.clear();
+                    if let Some(edge) = gesture_state.splice_target.take() {
+                        writer.send(GraphEvent {
+                            target: id,
+                            gesture: Gesture::Splice(node_id, edge),
+                        });
+                    }
                 }
             },
         ),
         On::<Pointer<Drag>>::run({
             move |mut event: ListenerMut<Pointer<Drag>>,
-                  gesture_state: ResMut<GestureState>,
-                  mut writer: EventWriter<GraphEvent>| {
+                  mut gesture_state: ResMut<GestureState>,
+                  mut writer: EventWriter<GraphEvent>,
+                  nodes: Query<(&Node, &Style, &Parent)>,
+                  indices: Query<&GraphSpatialIndex>,
+                  edges: Query<&EdgeGeometry>| {
                 event.stop_propagation();
                 if gesture_state.mode == DragMode::Move {
+                    gesture_state.drag_distance = event.distance;
+                    let splice_target = if splice {
+                        find_splice_target(id, &nodes, &indices, &edges)
+                    } else {
+                        None
+                    };
+                    if gesture_state.splice_target != splice_target {
+                        gesture_state.splice_target = splice_target;
+                    }
                     writer.send(GraphEvent {
                         target: id,
-                        gesture: Gesture::Move(event.distance, DragAction::Update),
+                        gesture: gesture_state.move_gesture(DragAction::Update),
                     });
                 }
             }
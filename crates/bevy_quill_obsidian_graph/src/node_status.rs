@@ -0,0 +1,124 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::prelude::*;
+use bevy_quill_obsidian::colors;
+
+/// The processing status of a graph node, such as a long-running bake or an asynchronous
+/// evaluation. Insert this component on the node entity (the `node_id` of the
+/// [`NodeDisplay`](crate::NodeDisplay)) to show a status strip below the node's title bar.
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub enum NodeStatus {
+    /// The node isn't doing anything. No status strip is shown.
+    #[default]
+    Idle,
+
+    /// The node is being processed. The argument is the fraction complete, from 0 to 1, or
+    /// `None` if the progress isn't known.
+    Running(Option<f32>),
+
+    /// The node has been processed successfully.
+    Done,
+
+    /// Processing the node failed, with the given message.
+    Error(String),
+}
+
+impl NodeStatus {
+    /// Color of the status strip.
+    fn color(&self) -> Srgba {
+        match self {
+            NodeStatus::Idle => colors::U3,
+            NodeStatus::Running(_) => colors::ACCENT,
+            NodeStatus::Done => colors::RESOURCE,
+            NodeStatus::Error(_) => colors::X_RED,
+        }
+    }
+
+    /// How much of the status strip is filled, from 0 to 1.
+    fn fill(&self) -> f32 {
+        match self {
+            NodeStatus::Running(Some(progress)) => progress.clamp(0., 1.),
+            _ => 1.,
+        }
+    }
+
+    /// Text displayed in the status strip.
+    fn label(&self) -> String {
+        match self {
+            NodeStatus::Idle => String::new(),
+            NodeStatus::Running(Some(progress)) => {
+                format!("{:.0}%", progress.clamp(0., 1.) * 100.)
+            }
+            NodeStatus::Running(None) => "Running".to_string(),
+            NodeStatus::Done => "Done".to_string(),
+            NodeStatus::Error(message) => message.clone(),
+        }
+    }
+}
+
+fn style_status_strip(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .justify_content(ui::JustifyContent::Center)
+        .height(14)
+        .border_left(1)
+        .border_right(1)
+        .overflow(ui::OverflowAxis::Clip)
+        .background_color(colors::U1)
+        .color(colors::FOREGROUND)
+        .font_size(11)
+        .pointer_events(false);
+}
+
+fn style_status_fill(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .left(0)
+        .top(0)
+        .bottom(0);
+}
+
+/// Strip between the title bar and content of a node, showing its [`NodeStatus`].
+#[derive(Clone, PartialEq)]
+pub(crate) struct NodeStatusStrip {
+    pub(crate) status: NodeStatus,
+    pub(crate) selected: bool,
+}
+
+impl ViewTemplate for NodeStatusStrip {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        // An indeterminate or finished status fills the strip, so dim it to keep the label
+        // readable.
+        let alpha = match self.status {
+            NodeStatus::Running(Some(_)) => 1.,
+            _ => 0.5,
+        };
+        Element::<NodeBundle>::new()
+            .named("NodeGraph::Node::Status")
+            .style(style_status_strip)
+            .style_dyn(
+                |selected, sb| {
+                    sb.border_color(if selected {
+                        colors::FOREGROUND
+                    } else {
+                        colors::U4
+                    });
+                },
+                self.selected,
+            )
+            .children((
+                Element::<NodeBundle>::new()
+                    .style(style_status_fill)
+                    .style_dyn(
+                        |(fill, color), sb| {
+                            sb.width(ui::Val::Percent(fill * 100.))
+                                .background_color(color);
+                        },
+                        (self.status.fill(), self.status.color().with_alpha(alpha)),
+                    ),
+                self.status.label(),
+            ))
+    }
+}
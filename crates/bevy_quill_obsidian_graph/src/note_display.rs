@@ -0,0 +1,432 @@
+use bevy::{a11y::Focus, prelude::*, ui};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::{prelude::*, IntoViewChild};
+use bevy_quill_obsidian::{
+    colors,
+    cursor::StyleBuilderCursor,
+    focus::{KeyCharEvent, KeyPressEvent},
+    hooks::{UseIsFocus, UseIsHover},
+};
+
+use crate::{
+    markdown::Markdown,
+    node_display::{node_event_handlers, title_event_handlers, NodeDisplayTarget},
+    DragAction, DragMode, Gesture, GestureState, GraphEvent,
+};
+
+/// Notes can't be resized smaller than this.
+const MIN_NOTE_SIZE: Vec2 = Vec2::new(80., 40.);
+
+/// Maximum interval between two clicks for them to count as a double-click, in seconds.
+const DOUBLE_CLICK_TIME: f32 = 0.4;
+
+const NOTE_BORDER_RADIUS: f32 = 3.;
+
+fn style_note(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .position(ui::PositionType::Absolute)
+        .border(1)
+        .border_radius(NOTE_BORDER_RADIUS);
+}
+
+fn style_note_body(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .flex_grow(1.)
+        .padding((8, 6))
+        .overflow(ui::OverflowAxis::Clip)
+        .font_size(13)
+        .cursor(CursorIcon::Grab);
+}
+
+fn style_note_editor(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .flex_wrap(ui::FlexWrap::Wrap)
+        .align_items(ui::AlignItems::End)
+        .cursor(CursorIcon::Text);
+}
+
+fn style_caret(ss: &mut StyleBuilder) {
+    ss.width(1).height(14);
+}
+
+fn style_note_resize_handle(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .right(0)
+        .bottom(0)
+        .width(10)
+        .height(10)
+        .border_radius(ui::BorderRadius {
+            top_left: ui::Val::Px(NOTE_BORDER_RADIUS),
+            top_right: ui::Val::Px(0.),
+            bottom_left: ui::Val::Px(0.),
+            bottom_right: ui::Val::Px(NOTE_BORDER_RADIUS),
+        })
+        .cursor(CursorIcon::SeResize);
+}
+
+fn style_note_outline(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .left(-4)
+        .top(-4)
+        .right(-4)
+        .bottom(-4)
+        .border(2)
+        .border_color(colors::FOCUS)
+        .border_radius(NOTE_BORDER_RADIUS + 3.)
+        .pointer_events(false);
+}
+
+/// The current text of a [`NoteDisplay`], stored on the note entity so that event handlers
+/// always see the latest value.
+#[derive(Component)]
+struct NoteDisplayText(String);
+
+/// A free-floating text note on the graph canvas. Notes are purely annotative: they have no
+/// terminals, but they can be selected, moved and lasso-selected along with nodes, using the
+/// same gestures. Dragging the lower-right corner sends a [`Gesture::Resize`].
+///
+/// The text is formatted as a simple subset of markdown: headings, bulleted lists, and
+/// bold, italic and code spans. Double-clicking the note edits the raw text in place; pressing
+/// Escape or moving focus elsewhere finishes the edit.
+#[derive(Clone, PartialEq)]
+pub struct NoteDisplay {
+    /// Entity id for the UI element to display.
+    pub display_id: Entity,
+    /// Entity id of the note.
+    pub note_id: Entity,
+    /// The coordinates of the note's upper-left corner.
+    pub position: IVec2,
+    /// The size of the note.
+    pub size: Vec2,
+    /// Background color of the note. The text is drawn in a light or dark color, whichever is
+    /// more readable.
+    pub color: Srgba,
+    /// The text of the note, as markdown.
+    pub text: String,
+    /// Whether the note is currently selected.
+    pub selected: bool,
+    /// Callback called with the new text when an edit is finished. Not called if the text is
+    /// unchanged.
+    pub on_change: Option<Callback<String>>,
+}
+
+impl NoteDisplay {
+    /// Create a new note display.
+    pub fn new(display_id: Entity, note_id: Entity) -> Self {
+        Self {
+            display_id,
+            note_id,
+            position: default(),
+            size: Vec2::new(200., 120.),
+            color: colors::LIGHT.darker(0.45),
+            text: default(),
+            selected: false,
+            on_change: None,
+        }
+    }
+
+    /// Set the position of the note's upper-left corner.
+    pub fn position(mut self, position: IVec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Set the size of the note.
+    pub fn size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the background color of the note.
+    pub fn color(mut self, color: impl Into<Srgba>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Set the text of the note.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    /// Set the selection state of the note.
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Set the callback called when an edit is finished.
+    pub fn on_change(mut self, on_change: Callback<String>) -> Self {
+        self.on_change = Some(on_change);
+        self
+    }
+}
+
+/// Finish editing, and report the new text if it has changed.
+fn commit_edit(
+    world: &mut World,
+    id: Entity,
+    editing: Mutable<Option<String>>,
+    on_change: Option<Callback<String>>,
+) {
+    let Some(text) = editing.get_clone(world) else {
+        return;
+    };
+    editing.set_clone(world, None);
+    let changed = world
+        .get::<NoteDisplayText>(id)
+        .is_none_or(|value| value.0 != text);
+    if let (true, Some(on_change)) = (changed, on_change) {
+        world.run_callback(on_change, text);
+    }
+}
+
+impl ViewTemplate for NoteDisplay {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let display_id = self.display_id;
+        let note_id = self.note_id;
+        let body_id = cx.create_entity();
+        let editing = cx.create_mutable::<Option<String>>(None);
+        let last_click = cx.create_mutable::<f32>(f32::NEG_INFINITY);
+        let hovering = cx.is_hovered(display_id);
+        let focused = cx.is_focused(body_id);
+        let on_change = self.on_change;
+
+        // Finish the edit when focus moves elsewhere.
+        cx.create_effect(
+            move |world, focused| {
+                if !focused {
+                    commit_edit(world, body_id, editing, on_change);
+                }
+            },
+            focused,
+        );
+
+        let text_color = colors::text_color_for(self.color);
+        let edit_text = editing.get_clone(cx);
+
+        Element::<NodeBundle>::for_entity(display_id)
+            .named("NodeGraph::Note")
+            .style(style_note)
+            .insert_dyn(move |_| node_event_handlers(display_id, note_id), ())
+            .insert_dyn(
+                |(note, position, selected)| NodeDisplayTarget {
+                    node: note,
+                    position: position.as_vec2(),
+                    selected,
+                },
+                (note_id, self.position, self.selected),
+            )
+            .style_dyn(
+                |(position, size), sb| {
+                    sb.left(position.x as f32)
+                        .top(position.y as f32)
+                        .width(size.x)
+                        .height(size.y);
+                },
+                (self.position, self.size),
+            )
+            .style_dyn(
+                |(color, selected), sb| {
+                    sb.background_color(color).border_color(if selected {
+                        colors::FOREGROUND
+                    } else {
+                        color.darker(0.1)
+                    });
+                },
+                (self.color, self.selected),
+            )
+            .children((
+                Element::<NodeBundle>::for_entity(body_id)
+                    .named("NodeGraph::Note::Body")
+                    .style(style_note_body)
+                    .style_dyn(
+                        |color, sb| {
+                            sb.color(color);
+                        },
+                        text_color,
+                    )
+                    .insert_dyn(NoteDisplayText, self.text.clone())
+                    .insert_dyn(
+                        move |_| {
+                            (
+                                title_event_handlers(display_id, note_id, false),
+                                On::<Pointer<Click>>::run(move |world: &mut World| {
+                                    if editing.get_clone(world).is_some() {
+                                        return;
+                                    }
+                                    let now = world.resource::<Time>().elapsed_seconds();
+                                    if now - last_click.get(world) < DOUBLE_CLICK_TIME {
+                                        last_click.set(world, f32::NEG_INFINITY);
+                                        let text = world
+                                            .get::<NoteDisplayText>(body_id)
+                                            .map(|text| text.0.clone())
+                                            .unwrap_or_default();
+                                        editing.set_clone(world, Some(text));
+                                        world.resource_mut::<Focus>().0 = Some(body_id);
+                                    } else {
+                                        last_click.set(world, now);
+                                    }
+                                }),
+                                On::<KeyCharEvent>::run(move |world: &mut World| {
+                                    let is_editing = editing.get_clone(world).is_some();
+                                    let mut event = world
+                                        .get_resource_mut::<ListenerInput<KeyCharEvent>>()
+                                        .unwrap();
+                                    let key = event.key;
+                                    if is_editing && !key.is_control() {
+                                        event.stop_propagation();
+                                        editing.update(world, |mut text| {
+                                            if let Some(text) = text.as_mut() {
+                                                text.push(key);
+                                            }
+                                        });
+                                    }
+                                }),
+                                On::<KeyPressEvent>::run(move |world: &mut World| {
+                                    let is_editing = editing.get_clone(world).is_some();
+                                    let mut event = world
+                                        .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                        .unwrap();
+                                    if !is_editing {
+                                        return;
+                                    }
+                                    // Don't let keyboard shortcuts act on the graph while
+                                    // typing.
+                                    event.stop_propagation();
+                                    match event.key_code {
+                                        KeyCode::Escape => {
+                                            commit_edit(world, body_id, editing, on_change);
+                                        }
+                                        KeyCode::Enter => {
+                                            editing.update(world, |mut text| {
+                                                if let Some(text) = text.as_mut() {
+                                                    text.push('\n');
+                                                }
+                                            });
+                                        }
+                                        KeyCode::Backspace => {
+                                            editing.update(world, |mut text| {
+                                                if let Some(text) = text.as_mut() {
+                                                    text.pop();
+                                                }
+                                            });
+                                        }
+                                        _ => {}
+                                    }
+                                }),
+                            )
+                        },
+                        (),
+                    )
+                    .children(match edit_text {
+                        Some(text) => Element::<NodeBundle>::new()
+                            .style(style_note_editor)
+                            .children((
+                                text,
+                                Element::<NodeBundle>::new().style(style_caret).style_dyn(
+                                    |color, sb| {
+                                        sb.background_color(color);
+                                    },
+                                    text_color,
+                                ),
+                            ))
+                            .into_view_child(),
+                        None => Markdown {
+                            text: self.text.clone(),
+                        }
+                        .into_view_child(),
+                    }),
+                Element::<NodeBundle>::new()
+                    .named("NodeGraph::Note::Resize")
+                    .style(style_note_resize_handle)
+                    .style_dyn(
+                        |color, sb| {
+                            sb.background_color(color.darker(0.1));
+                        },
+                        self.color,
+                    )
+                    .insert_dyn(move |_| resize_event_handlers(display_id, note_id), ()),
+                Cond::new(
+                    hovering,
+                    Element::<NodeBundle>::new()
+                        .named("NodeGraph::Note::Outline")
+                        .style(style_note_outline),
+                    (),
+                ),
+            ))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn resize_event_handlers(
+    id: Entity,
+    note_id: Entity,
+) -> (
+    On<Pointer<DragStart>>,
+    On<Pointer<DragEnd>>,
+    On<Pointer<Drag>>,
+) {
+    (
+        On::<Pointer<DragStart>>::run(
+            move |mut event: ListenerMut<Pointer<DragStart>>,
+                  mut gesture_state: ResMut<GestureState>,
+                  mut writer: EventWriter<GraphEvent>,
+                  nodes: Query<&Node>| {
+                event.stop_propagation();
+                let Ok(node) = nodes.get(id) else {
+                    return;
+                };
+                let size = node.size();
+                gesture_state.mode = DragMode::Resize(note_id, size);
+                writer.send(GraphEvent {
+                    target: id,
+                    gesture: Gesture::Resize(note_id, size, DragAction::Start),
+                });
+            },
+        ),
+        On::<Pointer<DragEnd>>::run(
+            move |mut event: ListenerMut<Pointer<DragEnd>>,
+                  mut gesture_state: ResMut<GestureState>,
+                  mut writer: EventWriter<GraphEvent>| {
+                event.stop_propagation();
+                if let DragMode::Resize(note, size) = gesture_state.mode {
+                    gesture_state.mode = DragMode::None;
+                    writer.send(GraphEvent {
+                        target: id,
+                        gesture: Gesture::Resize(
+                            note,
+                            (size + event.distance).max(MIN_NOTE_SIZE),
+                            DragAction::Finish,
+                        ),
+                    });
+                }
+            },
+        ),
+        On::<Pointer<Drag>>::run(
+            move |mut event: ListenerMut<Pointer<Drag>>,
+                  gesture_state: Res<GestureState>,
+                  mut writer: EventWriter<GraphEvent>| {
+                event.stop_propagation();
+                if let DragMode::Resize(note, size) = gesture_state.mode {
+                    writer.send(GraphEvent {
+                        target: id,
+                        gesture: Gesture::Resize(
+                            note,
+                            (size + event.distance).max(MIN_NOTE_SIZE),
+                            DragAction::Update,
+                        ),
+                    });
+                }
+            },
+        ),
+    )
+}
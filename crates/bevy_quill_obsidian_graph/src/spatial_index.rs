@@ -0,0 +1,304 @@
+use std::hash::Hash;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_quill_obsidian::scrolling::ScrollArea;
+
+use crate::{auto_pan::GraphCanvas, edge_display::EdgeGeometry, node_display::NodeDisplayTarget};
+
+/// Default size of the cells of a [`SpatialGrid`], in pixels.
+const CELL_SIZE: f32 = 256.;
+
+/// Edges curve outwards from their terminals by up to this distance.
+const EDGE_MARGIN: f32 = 20.;
+
+/// A uniform grid which maps rectangles to items, used to find the items within a region
+/// without visiting every item. Each item is stored in every cell that its rectangle overlaps.
+#[derive(Clone, Debug)]
+pub struct SpatialGrid<T> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<T>>,
+    items: HashMap<T, Rect>,
+}
+
+impl<T: Copy + Eq + Hash> Default for SpatialGrid<T> {
+    fn default() -> Self {
+        Self::new(CELL_SIZE)
+    }
+}
+
+impl<T: Copy + Eq + Hash> SpatialGrid<T> {
+    /// Create a new grid with the given cell size.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+            items: HashMap::default(),
+        }
+    }
+
+    /// Remove all items from the grid.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.items.clear();
+    }
+
+    /// The number of items in the grid.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// True if the grid contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The rectangle occupied by an item, if it is in the grid.
+    pub fn get(&self, item: T) -> Option<Rect> {
+        self.items.get(&item).copied()
+    }
+
+    /// Add an item which occupies the given rectangle. If the item is already in the grid, it
+    /// is moved to the new rectangle.
+    pub fn insert(&mut self, item: T, rect: Rect) {
+        self.remove(item);
+        self.items.insert(item, rect);
+        let (min, max) = self.cell_range(rect);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(item);
+            }
+        }
+    }
+
+    /// Move an item to a new rectangle, or add it if it is not in the grid. Does nothing if
+    /// the rectangle has not changed.
+    pub fn update(&mut self, item: T, rect: Rect) {
+        if self.get(item) != Some(rect) {
+            self.insert(item, rect);
+        }
+    }
+
+    /// Remove an item from the grid, returning the rectangle it occupied.
+    pub fn remove(&mut self, item: T) -> Option<Rect> {
+        let rect = self.items.remove(&item)?;
+        let (min, max) = self.cell_range(rect);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell = IVec2::new(x, y);
+                if let Some(items) = self.cells.get_mut(&cell) {
+                    items.retain(|i| *i != item);
+                    if items.is_empty() {
+                        self.cells.remove(&cell);
+                    }
+                }
+            }
+        }
+        Some(rect)
+    }
+
+    /// Return the items, and their rectangles, which overlap the given region. Rectangles
+    /// which only touch the region are included.
+    pub fn query(&self, region: Rect) -> Vec<(T, Rect)> {
+        let (min, max) = self.cell_range(region);
+        let mut found: Vec<(T, Rect)> = Vec::new();
+        // Items which span several cells are found more than once.
+        let mut seen: HashSet<T> = HashSet::default();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                if let Some(cell) = self.cells.get(&IVec2::new(x, y)) {
+                    for item in cell.iter() {
+                        let rect = self.items[item];
+                        if overlaps(rect, region) && seen.insert(*item) {
+                            found.push((*item, rect));
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    fn cell_range(&self, rect: Rect) -> (IVec2, IVec2) {
+        (
+            (rect.min / self.cell_size).floor().as_ivec2(),
+            (rect.max / self.cell_size).floor().as_ivec2(),
+        )
+    }
+}
+
+/// Like [`Rect::intersect`], but also true for rectangles which touch, or have zero size.
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.min.x <= b.max.x && a.max.x >= b.min.x && a.min.y <= b.max.y && a.max.y >= b.min.y
+}
+
+/// The region of the graph content which is currently visible, in the same coordinates as
+/// node positions. This is stored on the graph's content element, and can be used to avoid
+/// building views for nodes which are off-screen.
+#[derive(Component, Clone, Copy, PartialEq, Debug, Default)]
+pub struct GraphViewport(pub Rect);
+
+/// Spatial index of the node and edge display entities within a graph. This is stored on the
+/// graph's content element.
+#[derive(Component, Clone, Default)]
+pub(crate) struct GraphSpatialIndex {
+    pub(crate) nodes: SpatialGrid<Entity>,
+    pub(crate) edges: SpatialGrid<Entity>,
+}
+
+/// Rectangle occupied by a node display, relative to the graph content.
+pub(crate) fn node_display_rect(node: &Node, style: &Style) -> Option<Rect> {
+    let (Val::Px(left), Val::Px(top)) = (style.left, style.top) else {
+        return None;
+    };
+    let min = Vec2::new(left, top);
+    Some(Rect::from_corners(min, min + node.size()))
+}
+
+/// Rebuild the spatial index of each graph when its nodes or edges have changed, and update
+/// the visible region.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn update_spatial_index(
+    mut canvases: Query<(
+        &GraphCanvas,
+        Ref<Children>,
+        &mut GraphSpatialIndex,
+        &mut GraphViewport,
+    )>,
+    scroll_areas: Query<&ScrollArea>,
+    nodes: Query<(&Node, &Style), With<NodeDisplayTarget>>,
+    edges: Query<&EdgeGeometry>,
+    changed_nodes: Query<(), (With<NodeDisplayTarget>, Or<(Changed<Node>, Changed<Style>)>)>,
+    changed_edges: Query<(), Changed<EdgeGeometry>>,
+    mut removed_nodes: RemovedComponents<NodeDisplayTarget>,
+    mut removed_edges: RemovedComponents<EdgeGeometry>,
+) {
+    let dirty = !changed_nodes.is_empty()
+        || !changed_edges.is_empty()
+        || removed_nodes.read().count() > 0
+        || removed_edges.read().count() > 0;
+
+    for (canvas, children, mut index, mut viewport) in canvases.iter_mut() {
+        if let Ok(scroll_area) = scroll_areas.get(canvas.scroll_area) {
+            let min = Vec2::new(scroll_area.scroll_left, scroll_area.scroll_top);
+            let visible = Rect::from_corners(min, min + scroll_area.visible_size);
+            if viewport.0 != visible {
+                viewport.0 = visible;
+            }
+        }
+
+        if !dirty && !children.is_changed() {
+            continue;
+        }
+        let index = index.as_mut();
+        index.nodes.clear();
+        index.edges.clear();
+        for child in children.iter() {
+            if let Ok((node, style)) = nodes.get(*child) {
+                if let Some(rect) = node_display_rect(node, style) {
+                    index.nodes.insert(*child, rect);
+                }
+            } else if let Ok(edge) = edges.get(*child) {
+                let rect = Rect::from_corners(edge.src, edge.dst).inflate(EDGE_MARGIN);
+                index.edges.insert(*child, rect);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect::from_corners(Vec2::new(x, y), Vec2::new(x + w, y + h))
+    }
+
+    fn found(grid: &SpatialGrid<u32>, region: Rect) -> Vec<u32> {
+        let mut items: Vec<u32> = grid.query(region).into_iter().map(|(i, _)| i).collect();
+        items.sort();
+        items
+    }
+
+    #[test]
+    fn test_insert_and_query() {
+        let mut grid = SpatialGrid::new(100.);
+        grid.insert(1, rect(10., 10., 20., 20.));
+        grid.insert(2, rect(150., 10., 20., 20.));
+        // Spans four cells, but is only returned once.
+        grid.insert(3, rect(80., 80., 40., 40.));
+        assert_eq!(grid.len(), 3);
+
+        assert_eq!(found(&grid, rect(0., 0., 50., 50.)), [1]);
+        assert_eq!(found(&grid, rect(0., 0., 200., 200.)), [1, 2, 3]);
+        assert_eq!(found(&grid, rect(90., 90., 5., 5.)), [3]);
+        // Items in the same cell which don't overlap the region are excluded.
+        assert_eq!(found(&grid, rect(40., 40., 10., 10.)), Vec::<u32>::new());
+        // Touching counts as overlapping.
+        assert_eq!(found(&grid, rect(30., 30., 5., 5.)), [1]);
+        assert_eq!(
+            grid.query(rect(0., 0., 50., 50.)),
+            [(1, rect(10., 10., 20., 20.))]
+        );
+    }
+
+    #[test]
+    fn test_negative_coordinates() {
+        let mut grid = SpatialGrid::new(100.);
+        grid.insert(1, rect(-150., -50., 20., 20.));
+        assert_eq!(found(&grid, rect(-200., -100., 100., 100.)), [1]);
+        assert_eq!(found(&grid, rect(0., 0., 100., 100.)), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_zero_size() {
+        let mut grid = SpatialGrid::new(100.);
+        grid.insert(1, rect(50., 50., 0., 0.));
+        assert_eq!(found(&grid, rect(0., 0., 100., 100.)), [1]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut grid = SpatialGrid::new(100.);
+        grid.insert(1, rect(80., 80., 40., 40.));
+        grid.insert(2, rect(90., 90., 5., 5.));
+        assert_eq!(grid.remove(1), Some(rect(80., 80., 40., 40.)));
+        assert_eq!(grid.remove(1), None);
+        assert_eq!(grid.len(), 1);
+        assert_eq!(found(&grid, rect(0., 0., 200., 200.)), [2]);
+        grid.remove(2);
+        assert!(grid.is_empty());
+        assert!(grid.cells.is_empty());
+    }
+
+    #[test]
+    fn test_update() {
+        let mut grid = SpatialGrid::new(100.);
+        grid.insert(1, rect(10., 10., 20., 20.));
+        grid.update(1, rect(510., 10., 20., 20.));
+        assert_eq!(grid.len(), 1);
+        assert_eq!(grid.get(1), Some(rect(510., 10., 20., 20.)));
+        assert_eq!(found(&grid, rect(0., 0., 100., 100.)), Vec::<u32>::new());
+        assert_eq!(found(&grid, rect(500., 0., 100., 100.)), [1]);
+
+        // Inserting an item which is already present also moves it.
+        grid.insert(1, rect(10., 10., 20., 20.));
+        assert_eq!(grid.len(), 1);
+        assert_eq!(found(&grid, rect(500., 0., 100., 100.)), Vec::<u32>::new());
+
+        // Updating an item which is not present adds it.
+        grid.update(2, rect(10., 10., 5., 5.));
+        assert_eq!(found(&grid, rect(0., 0., 100., 100.)), [1, 2]);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut grid = SpatialGrid::new(100.);
+        grid.insert(1, rect(10., 10., 20., 20.));
+        grid.clear();
+        assert!(grid.is_empty());
+        assert!(grid.query(rect(0., 0., 100., 100.)).is_empty());
+    }
+}
@@ -2,12 +2,124 @@ use bevy::{prelude::*, ui};
 use bevy_mod_picking::prelude::*;
 use bevy_mod_stylebuilder::*;
 use bevy_quill_core::{prelude::*, ViewChild};
-use bevy_quill_obsidian::{colors, cursor::StyleBuilderCursor, hooks::UseIsHover};
+use bevy_quill_obsidian::{
+    colors, cursor::StyleBuilderCursor, floating::FloatSide, hooks::UseIsHover,
+};
 
 use crate::{
+    edge_display::{EdgeGeometry, EdgeTerminals},
+    terminal_tooltip::TerminalTooltip,
     ConnectionAnchor, ConnectionTarget, DragAction, DragMode, Gesture, GestureState, GraphEvent,
 };
 
+/// The kind of connection that a terminal makes. This is inserted as a component on the
+/// terminal entity by the terminal displays.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TerminalKind {
+    /// A data port, which passes a value from an output to the inputs connected to it.
+    #[default]
+    Data,
+
+    /// An execution port, which passes control from one node to the next, as in a visual
+    /// scripting or quest editor. When a node has finished, execution continues along the
+    /// edges leaving its flow output, in order. A flow input has a single predecessor:
+    /// dragging from a connected flow input picks up its existing edge instead of starting a
+    /// new one, and when a connection is made to a flow input which is already connected,
+    /// the application should replace the existing edge.
+    ///
+    /// Flow terminals are drawn as arrows, and only connect to other flow terminals. Edges
+    /// between them should set [`EdgeDisplay::kind`](crate::EdgeDisplay::kind) so that they
+    /// are drawn with arrowheads.
+    Flow,
+}
+
+/// The glyph drawn for a terminal. Shapes can be used alongside colors to tell different kinds
+/// of terminals apart, such as execution and data ports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TerminalShape {
+    /// A filled circle.
+    #[default]
+    Circle,
+    /// A filled square.
+    Square,
+    /// A filled square, rotated by 45 degrees.
+    Diamond,
+    /// A chevron pointing in the direction of flow, from outputs to inputs.
+    Arrow,
+}
+
+impl TerminalShape {
+    /// Style an 8-pixel glyph element with this shape and color.
+    pub(crate) fn style_glyph(self, sb: &mut StyleBuilder, color: Srgba) {
+        match self {
+            TerminalShape::Circle => {
+                sb.background_color(color).border_radius(5);
+            }
+            TerminalShape::Square | TerminalShape::Diamond => {
+                sb.background_color(color).border_radius(1);
+            }
+            TerminalShape::Arrow => {
+                // The top and right borders of a rotated square make a chevron.
+                sb.background_color(Option::<Color>::None)
+                    .border_top(2)
+                    .border_right(2)
+                    .border_color(color);
+            }
+        }
+    }
+
+    /// The rotation of the glyph element for this shape.
+    pub(crate) fn rotation(self) -> Quat {
+        match self {
+            TerminalShape::Circle | TerminalShape::Square => Quat::IDENTITY,
+            TerminalShape::Diamond | TerminalShape::Arrow => {
+                Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)
+            }
+        }
+    }
+}
+
+/// Effect which rotates a glyph element to suit its shape.
+fn rotate_glyph(cx: &mut Cx, entity: Entity, shape: TerminalShape) {
+    if let Some(mut transform) = cx.world_mut().get_mut::<Transform>(entity) {
+        transform.rotation = shape.rotation();
+    }
+}
+
+fn style_terminal_glyph(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .width(8)
+        .height(8)
+        .pointer_events(false);
+}
+
+/// A terminal glyph centered on a point within the graph, such as the loose end of an edge
+/// which is being dragged.
+#[derive(Clone, PartialEq)]
+pub(crate) struct TerminalGlyph {
+    pub(crate) shape: TerminalShape,
+    pub(crate) color: Srgba,
+    pub(crate) position: Vec2,
+}
+
+impl ViewTemplate for TerminalGlyph {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .named("NodeGraph::TerminalGlyph")
+            .style(style_terminal_glyph)
+            .style_dyn(
+                |(shape, color, position), sb| {
+                    shape.style_glyph(sb, color);
+                    sb.left(position.x - 4.).top(position.y - 4.);
+                },
+                (self.shape, self.color, self.position),
+            )
+            .effect(rotate_glyph, self.shape)
+    }
+}
+
 fn style_terminal_outline(ss: &mut StyleBuilder) {
     ss.position(ui::PositionType::Absolute)
         .left(-3)
@@ -42,8 +154,7 @@ fn style_input_terminal(ss: &mut StyleBuilder) {
         .left(-4)
         .top(6)
         .width(8)
-        .height(8)
-        .border_radius(5);
+        .height(8);
 }
 
 /// Depicts an input connector on a node.
@@ -54,6 +165,10 @@ pub struct InputTerminalDisplay {
     /// Color of the connector terminal, which is typically used to indicate the data-type
     /// of the connector.
     pub color: Srgba,
+    /// Shape of the connector terminal. Flow terminals are always drawn as arrows.
+    pub shape: TerminalShape,
+    /// Whether this is a data or an execution terminal.
+    pub kind: TerminalKind,
     /// Control rendered when the input is not connected.
     pub control: ViewChild,
 }
@@ -64,21 +179,34 @@ impl ViewTemplate for InputTerminalDisplay {
     fn create(&self, cx: &mut Cx) -> Self::View {
         let id = self.id;
         let is_hover = cx.is_hovered(id);
-        let color = self.color;
+        let shape = match self.kind {
+            TerminalKind::Data => self.shape,
+            TerminalKind::Flow => TerminalShape::Arrow,
+        };
         Element::<NodeBundle>::new()
             .named("InputTerminal")
             .style(style_input_connector)
             .children((
                 Element::<NodeBundle>::for_entity(self.id)
-                    .style((style_input_terminal, move |sb: &mut StyleBuilder| {
-                        sb.background_color(color);
-                    }))
-                    .insert_dyn(terminal_event_handlers, (id, false))
+                    .style(style_input_terminal)
+                    .style_dyn(
+                        |(shape, color), sb| shape.style_glyph(sb, color),
+                        (shape, self.color),
+                    )
+                    .effect(rotate_glyph, shape)
+                    .insert_dyn(|kind| kind, self.kind)
+                    .insert_dyn(terminal_event_handlers, (id, false, self.kind))
                     .children((
                         Element::<NodeBundle>::new().style(style_terminal_hitbox),
                         Cond::new(
                             is_hover,
-                            Element::<NodeBundle>::new().style(style_terminal_outline),
+                            (
+                                Element::<NodeBundle>::new().style(style_terminal_outline),
+                                TerminalTooltip {
+                                    terminal: id,
+                                    side: FloatSide::Left,
+                                },
+                            ),
                             (),
                         ),
                     )),
@@ -101,8 +229,7 @@ fn style_output_terminal(ss: &mut StyleBuilder) {
         .right(-4)
         .top(6)
         .width(8)
-        .height(8)
-        .border_radius(5);
+        .height(8);
 }
 
 /// Depicts an output connector on a node.
@@ -113,6 +240,10 @@ pub struct OutputTerminalDisplay {
     /// Color of the connector terminal, which is typically used to indicate the data-type
     /// of the connector.
     pub color: Srgba,
+    /// Shape of the connector terminal. Flow terminals are always drawn as arrows.
+    pub shape: TerminalShape,
+    /// Whether this is a data or an execution terminal.
+    pub kind: TerminalKind,
     /// The name of the output.
     pub label: String,
 }
@@ -123,21 +254,34 @@ impl ViewTemplate for OutputTerminalDisplay {
     fn create(&self, cx: &mut Cx) -> Self::View {
         let id = self.id;
         let is_hover = cx.is_hovered(id);
-        let color = self.color;
+        let shape = match self.kind {
+            TerminalKind::Data => self.shape,
+            TerminalKind::Flow => TerminalShape::Arrow,
+        };
         Element::<NodeBundle>::new()
             .named("OutputTerminal")
             .style(style_output_connector)
             .children((
                 Element::<NodeBundle>::for_entity(self.id)
-                    .style((style_output_terminal, move |sb: &mut StyleBuilder| {
-                        sb.background_color(color);
-                    }))
-                    .insert_dyn(terminal_event_handlers, (id, true))
+                    .style(style_output_terminal)
+                    .style_dyn(
+                        |(shape, color), sb| shape.style_glyph(sb, color),
+                        (shape, self.color),
+                    )
+                    .effect(rotate_glyph, shape)
+                    .insert_dyn(|kind| kind, self.kind)
+                    .insert_dyn(terminal_event_handlers, (id, true, self.kind))
                     .children((
                         Element::<NodeBundle>::new().style(style_terminal_hitbox),
                         Cond::new(
                             is_hover,
-                            Element::<NodeBundle>::new().style(style_terminal_outline),
+                            (
+                                Element::<NodeBundle>::new().style(style_terminal_outline),
+                                TerminalTooltip {
+                                    terminal: id,
+                                    side: FloatSide::Right,
+                                },
+                            ),
                             (),
                         ),
                     )),
@@ -175,7 +319,7 @@ impl ViewTemplate for NoTerminalDisplay {
 
 #[allow(clippy::type_complexity)]
 fn terminal_event_handlers(
-    args: (Entity, bool),
+    args: (Entity, bool, TerminalKind),
 ) -> (
     On<Pointer<DragStart>>,
     On<Pointer<Drag>>,
@@ -184,21 +328,31 @@ fn terminal_event_handlers(
     On<Pointer<DragLeave>>,
     On<Pointer<Drop>>,
 ) {
-    let (id, is_output) = args;
+    let (id, is_output, kind) = args;
     (
         On::<Pointer<DragStart>>::run(
             move |mut event: ListenerMut<Pointer<DragStart>>,
                   mut gesture_state: ResMut<GestureState>,
                   mut writer: EventWriter<GraphEvent>,
+                  edges: Query<(&EdgeTerminals, &EdgeGeometry)>,
                   rel: crate::relative_pos::RelativeWorldPositions| {
                 event.stop_propagation();
                 if gesture_state.mode != DragMode::Connect {
                     #[cfg(feature = "verbose")]
                     info!("Terminal::DragStart: {}", event.target());
-                    let anchor = if is_output {
-                        ConnectionAnchor::OutputTerminal(id)
+                    // A flow input can only have one predecessor, so pick up the edge which is
+                    // already connected to it, if there is one.
+                    let existing = if !is_output && kind == TerminalKind::Flow {
+                        edges.iter().find_map(|(terminals, geometry)| {
+                            geometry.edge_id.filter(|_| terminals.dst == Some(id))
+                        })
                     } else {
-                        ConnectionAnchor::InputTerminal(id)
+                        None
+                    };
+                    let anchor = match existing {
+                        Some(edge) => ConnectionAnchor::EdgeSink(edge),
+                        None if is_output => ConnectionAnchor::OutputTerminal(id),
+                        None => ConnectionAnchor::InputTerminal(id),
                     };
                     gesture_state.target = ConnectionTarget::Location(rel.transform_relative(
                         id,
@@ -207,6 +361,7 @@ fn terminal_event_handlers(
                     ));
                     gesture_state.mode = DragMode::Connect;
                     gesture_state.anchor = Some(anchor);
+                    gesture_state.anchor_kind = kind;
                     writer.send(GraphEvent {
                         target: id,
                         gesture: Gesture::Connect(anchor, gesture_state.target, DragAction::Start),
@@ -288,7 +443,7 @@ fn terminal_event_handlers(
                   mut gesture_state: ResMut<GestureState>,
                   mut writer: EventWriter<GraphEvent>| {
                 event.stop_propagation();
-                if gesture_state.mode == DragMode::Connect {
+                if gesture_state.mode == DragMode::Connect && gesture_state.anchor_kind == kind {
                     #[cfg(feature = "verbose")]
                     info!("Terminal::DragEnter: {}", event.target());
                     if is_output {
@@ -318,7 +473,7 @@ fn terminal_event_handlers(
                   mut writer: EventWriter<GraphEvent>,
                   rel: crate::relative_pos::RelativeWorldPositions| {
                 event.stop_propagation();
-                if gesture_state.mode == DragMode::Connect {
+                if gesture_state.mode == DragMode::Connect && gesture_state.anchor_kind == kind {
                     #[cfg(feature = "verbose")]
                     info!("Terminal::DragLeave: {}", event.target());
                     gesture_state.target = match gesture_state.target {
@@ -356,7 +511,7 @@ fn terminal_event_handlers(
                   mut gesture_state: ResMut<GestureState>,
                   mut writer: EventWriter<GraphEvent>| {
                 event.stop_propagation();
-                if gesture_state.mode == DragMode::Connect {
+                if gesture_state.mode == DragMode::Connect && gesture_state.anchor_kind == kind {
                     #[cfg(feature = "verbose")]
                     info!("Terminal::Drop: {}", event.target());
                     if let Some(anchor) = gesture_state.anchor {
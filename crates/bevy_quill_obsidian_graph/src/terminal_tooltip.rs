@@ -0,0 +1,155 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::{prelude::*, Portal};
+use bevy_quill_obsidian::{
+    colors,
+    floating::{FloatAlign, FloatPosition, FloatSide, Floating},
+    typography,
+};
+
+use crate::{DragMode, GestureState};
+
+/// Information about a terminal, which is displayed in a tooltip when hovering over it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TerminalInfo {
+    /// The name of the port.
+    pub name: String,
+    /// The name of the data type of the port.
+    pub data_type: String,
+    /// The current value of the port, or its default value if it is not connected.
+    pub value: Option<String>,
+    /// The number of edges connected to the terminal.
+    pub connections: usize,
+}
+
+/// Trait implemented by the host application to supply the contents of terminal tooltips.
+pub trait TerminalInfoProvider: Send + Sync + 'static {
+    /// Return information about the given terminal entity, or `None` if no tooltip should be
+    /// shown.
+    fn terminal_info(&self, world: &World, terminal: Entity) -> Option<TerminalInfo>;
+}
+
+/// Resource which holds the [`TerminalInfoProvider`] used for terminal tooltips. Tooltips are
+/// disabled until a provider is set:
+///
+/// ```ignore
+/// app.world_mut()
+///     .resource_mut::<TerminalTooltips>()
+///     .set_provider(GraphTerminalInfo);
+/// ```
+#[derive(Resource, Default)]
+pub struct TerminalTooltips {
+    provider: Option<Box<dyn TerminalInfoProvider>>,
+}
+
+impl TerminalTooltips {
+    /// Set the provider for terminal tooltips.
+    pub fn set_provider(&mut self, provider: impl TerminalInfoProvider) {
+        self.provider = Some(Box::new(provider));
+    }
+
+    /// Remove the provider, disabling terminal tooltips.
+    pub fn clear_provider(&mut self) {
+        self.provider = None;
+    }
+}
+
+fn style_tooltip(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::FlexStart)
+        .background_color(colors::U1)
+        .border_color(Srgba::BLACK)
+        .border(1)
+        .border_radius(4.0)
+        .padding((6, 3))
+        .color(colors::FOREGROUND)
+        .pointer_events(false);
+}
+
+fn style_tooltip_detail(ss: &mut StyleBuilder) {
+    ss.color(colors::DIM);
+}
+
+/// Tooltip which is displayed next to a hovered terminal. The terminal info is queried from the
+/// [`TerminalTooltips`] provider each time the tooltip is shown.
+#[derive(Clone, PartialEq)]
+pub(crate) struct TerminalTooltip {
+    /// The terminal entity.
+    pub(crate) terminal: Entity,
+    /// Which side of the terminal to place the tooltip.
+    pub(crate) side: FloatSide,
+}
+
+impl ViewTemplate for TerminalTooltip {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let terminal = self.terminal;
+        let info = cx
+            .use_resource::<TerminalTooltips>()
+            .provider
+            .as_ref()
+            .and_then(|provider| provider.terminal_info(cx.world(), terminal));
+        // Hide the tooltip while a connection is being dragged.
+        let dragging = cx.use_resource::<GestureState>().mode != DragMode::None;
+
+        Cond::new(
+            !dragging,
+            info.map(|info| {
+                let connections = match info.connections {
+                    0 => "Not connected".to_string(),
+                    1 => "1 connection".to_string(),
+                    n => format!("{} connections", n),
+                };
+                Portal::new(
+                    Element::<NodeBundle>::new()
+                        .named("TerminalTooltip")
+                        .style((typography::text_default, style_tooltip))
+                        .insert_dyn(
+                            |(side, anchor)| {
+                                (
+                                    Floating {
+                                        anchor,
+                                        position: vec![
+                                            FloatPosition {
+                                                side,
+                                                align: FloatAlign::Center,
+                                                stretch: false,
+                                                gap: 6.0,
+                                            },
+                                            FloatPosition {
+                                                side: side.mirror(),
+                                                align: FloatAlign::Center,
+                                                stretch: false,
+                                                gap: 6.0,
+                                            },
+                                        ],
+                                    },
+                                    ZIndex::Global(100),
+                                )
+                            },
+                            (self.side, terminal),
+                        )
+                        .children((
+                            Element::<NodeBundle>::new()
+                                .style(typography::text_strong)
+                                .children(format!("{}: {}", info.name, info.data_type)),
+                            info.value.map(|value| {
+                                Element::<NodeBundle>::new()
+                                    .style(style_tooltip_detail)
+                                    .children(value)
+                            }),
+                            Element::<NodeBundle>::new()
+                                .style(style_tooltip_detail)
+                                .children(connections),
+                        )),
+                )
+                .into_view_child()
+            })
+            .unwrap_or_default(),
+            (),
+        )
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::{input::gestures::PinchGesture, prelude::*, window::PrimaryWindow};
+use bevy_quill_obsidian::scrolling::ScrollArea;
+
+use crate::{auto_pan::GraphCanvas, DragMode, Gesture, GestureState, GraphEvent};
+
+/// Scroll events less than this far apart, in seconds, are considered part of the same swipe.
+const SWIPE_INTERVAL: f32 = 0.1;
+
+/// Momentum scrolling begins once no scroll events have arrived for this long, in seconds.
+const MOMENTUM_DELAY: f32 = 0.05;
+
+/// Momentum scrolling stops when the speed drops below this value, in pixels per second.
+const MOMENTUM_MIN_SPEED: f32 = 10.;
+
+/// Resource which configures how the graph responds to touchpad gestures.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct TouchpadSettings {
+    /// If true (the default), two-finger scrolling moves the canvas in the direction reported by
+    /// the operating system. If false, the direction is inverted.
+    pub natural_scrolling: bool,
+
+    /// If true, the canvas keeps gliding after a two-finger swipe ends. Some platforms, such as
+    /// macOS, already deliver momentum as scroll events, so this is disabled there by default.
+    pub kinetic_scrolling: bool,
+
+    /// How quickly kinetic scrolling slows down; higher values stop sooner.
+    pub friction: f32,
+
+    /// Multiplier applied to pinch gestures before they are reported as zoom gestures.
+    pub pinch_sensitivity: f32,
+}
+
+impl Default for TouchpadSettings {
+    fn default() -> Self {
+        Self {
+            natural_scrolling: true,
+            kinetic_scrolling: cfg!(not(target_os = "macos")),
+            friction: 4.,
+            pinch_sensitivity: 1.,
+        }
+    }
+}
+
+/// Tracks the velocity of two-finger scrolling on a graph, for kinetic scrolling.
+#[derive(Component, Clone, Default)]
+pub(crate) struct ScrollMomentum {
+    velocity: Vec2,
+    last_event: f32,
+}
+
+impl ScrollMomentum {
+    /// Record a scroll event, and update the estimated velocity.
+    pub(crate) fn record(&mut self, delta: Vec2, now: f32) {
+        let dt = now - self.last_event;
+        self.velocity = if dt > 0. && dt < SWIPE_INTERVAL {
+            self.velocity.lerp(delta / dt, 0.5)
+        } else {
+            Vec2::ZERO
+        };
+        self.last_event = now;
+    }
+}
+
+/// Continues scrolling the graph after a two-finger swipe ends.
+pub(crate) fn kinetic_scroll_graph(
+    settings: Res<TouchpadSettings>,
+    gesture_state: Res<GestureState>,
+    time: Res<Time>,
+    mut canvases: Query<(&GraphCanvas, &mut ScrollMomentum)>,
+    mut scroll_areas: Query<&mut ScrollArea>,
+) {
+    let now = time.elapsed_seconds();
+    let dt = time.delta_seconds();
+    for (canvas, mut momentum) in canvases.iter_mut() {
+        if momentum.velocity == Vec2::ZERO || now - momentum.last_event < MOMENTUM_DELAY {
+            continue;
+        }
+        if !settings.kinetic_scrolling
+            || gesture_state.mode != DragMode::None
+            || momentum.velocity.length() < MOMENTUM_MIN_SPEED
+        {
+            momentum.velocity = Vec2::ZERO;
+            continue;
+        }
+        if let Ok(mut scroll_area) = scroll_areas.get_mut(canvas.scroll_area) {
+            let delta = momentum.velocity * dt;
+            scroll_area.scroll_by(-delta.x, -delta.y);
+        }
+        momentum.velocity *= (-settings.friction * dt).exp();
+    }
+}
+
+/// Reports touchpad pinch gestures over a graph as zoom gestures.
+pub(crate) fn pinch_zoom_graph(
+    mut pinch_events: EventReader<PinchGesture>,
+    settings: Res<TouchpadSettings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    canvases: Query<(Entity, &GraphCanvas, &Node, &GlobalTransform)>,
+    scroll_areas: Query<(&Node, &GlobalTransform), With<ScrollArea>>,
+    mut writer: EventWriter<GraphEvent>,
+) {
+    let Some(pointer) = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+    else {
+        pinch_events.clear();
+        return;
+    };
+    for pinch in pinch_events.read() {
+        for (canvas_id, canvas, node, transform) in canvases.iter() {
+            let Ok((area_node, area_transform)) = scroll_areas.get(canvas.scroll_area) else {
+                continue;
+            };
+            if area_node.logical_rect(area_transform).contains(pointer) {
+                writer.send(GraphEvent {
+                    target: canvas_id,
+                    gesture: Gesture::Zoom(
+                        1. + pinch.0 * settings.pinch_sensitivity,
+                        pointer - node.logical_rect(transform).min,
+                    ),
+                });
+            }
+        }
+    }
+}
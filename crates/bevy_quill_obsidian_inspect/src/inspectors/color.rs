@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use bevy::{prelude::*, ui};
+use bevy_mod_picking::prelude::*;
 use bevy_mod_stylebuilder::*;
 use bevy_quill_core::*;
 use bevy_quill_obsidian::{
     colors,
-    controls::{Icon, MenuButton, MenuPopup, Spacer, Swatch},
+    controls::{Icon, MenuButton, MenuPopup, Spacer, Swatch, SwatchColor},
     floating::{FloatAlign, FloatSide},
     size::Size,
 };
@@ -71,46 +72,65 @@ impl ViewTemplate for SrgbaInspector {
             FieldLabel {
                 field: self.0.clone(),
             },
-            Element::<NodeBundle>::new().style(style_field).children((
-                Swatch::new(value).style(style_swatch),
-                value.to_hex(),
-                Spacer,
-                MenuButton::new()
-                    .children(
-                        Icon::new("embedded://bevy_quill_obsidian/assets/icons/tune.png")
-                            .size(Vec2::splat(16.0))
-                            .style(style_menu_icon)
-                            .color(Color::from(colors::DIM)),
-                    )
-                    .popup(
-                        MenuPopup::new()
-                            .side(FloatSide::Right)
-                            .align(FloatAlign::Start)
-                            .children(ColorEdit::new(
-                                state.get(cx),
-                                cx.create_callback(
-                                    move |st: In<ColorEditState>, world: &mut World| {
-                                        state.set(world, *st);
-                                    },
-                                ),
-                            )),
-                    )
-                    .size(Size::Xxs)
-                    .minimal(true)
-                    .no_caret(true)
-                    .on_state_change(cx.create_callback(
-                        move |open: In<bool>, world: &mut World| {
-                            // When popup closes, we're done editing, so add to recent colors.
-                            if !*open {
-                                // Add color to recent colors.
-                                let color = state.get(world).rgb;
-                                let mut recent_colors =
-                                    world.get_resource_mut::<RecentColors>().unwrap();
-                                recent_colors.add(color);
+            Element::<NodeBundle>::new()
+                .style(style_field)
+                // Accept swatches dropped from a palette.
+                .insert_dyn(
+                    move |_| {
+                        On::<Pointer<Drop>>::run(move |world: &mut World| {
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<Pointer<Drop>>>()
+                                .unwrap();
+                            event.stop_propagation();
+                            let dropped = event.dropped;
+                            if let Some(color) = SwatchColor::find(world, dropped) {
+                                let next_state = state.get(world).set_rgb(color);
+                                state.set(world, next_state);
                             }
-                        },
-                    )),
-            )),
+                        })
+                    },
+                    (),
+                )
+                .children((
+                    Swatch::new(value).style(style_swatch),
+                    value.to_hex(),
+                    Spacer,
+                    MenuButton::new()
+                        .children(
+                            Icon::new("embedded://bevy_quill_obsidian/assets/icons/tune.png")
+                                .size(Vec2::splat(16.0))
+                                .style(style_menu_icon)
+                                .color(Color::from(colors::DIM)),
+                        )
+                        .popup(
+                            MenuPopup::new()
+                                .side(FloatSide::Right)
+                                .align(FloatAlign::Start)
+                                .children(ColorEdit::new(
+                                    state.get(cx),
+                                    cx.create_callback(
+                                        move |st: In<ColorEditState>, world: &mut World| {
+                                            state.set(world, *st);
+                                        },
+                                    ),
+                                )),
+                        )
+                        .size(Size::Xxs)
+                        .minimal(true)
+                        .no_caret(true)
+                        .on_state_change(cx.create_callback(
+                            move |open: In<bool>, world: &mut World| {
+                                // When popup closes, we're done editing, so add to recent colors.
+                                if !*open {
+                                    // Add color to recent colors.
+                                    let color = state.get(world).rgb;
+                                    let mut recent_colors =
+                                        world.get_resource_mut::<RecentColors>().unwrap();
+                                    recent_colors.add(color);
+                                }
+                            },
+                        )),
+                )),
         )
     }
 }
@@ -5,6 +5,7 @@ mod inspectable;
 mod inspector;
 mod inspector_factory;
 mod inspectors;
+mod settings;
 mod templates;
 
 use bevy::app::{App, Plugin};
@@ -14,6 +15,7 @@ pub use attributes::*;
 pub use inspectable::*;
 pub use inspector::*;
 pub use inspector_factory::*;
+pub use settings::*;
 
 pub struct InspectorPlugin;
 
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use bevy::{
+    prelude::*,
+    reflect::{ParsedPath, ReflectPathError},
+    ui,
+};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+use bevy_quill_obsidian::{
+    controls::{Button, Dialog, DialogBody, DialogFooter, DialogHeader, ScrollView, Spacer},
+    settings::{SettingInfo, Settings},
+};
+
+use crate::{InspectableRoot, Inspector};
+
+/// A registered setting that can be inspected.
+pub struct InspectableSetting(pub SettingInfo);
+
+impl InspectableRoot for InspectableSetting {
+    fn name(&self, _cx: &Cx) -> String {
+        self.0.label.to_string()
+    }
+
+    fn reflect_path<'a>(&self, cx: &'a Cx, path: &ParsedPath) -> Option<&'a dyn Reflect> {
+        self.0.track(cx);
+        self.reflect_path_untracked(cx.world(), path)
+    }
+
+    fn reflect_path_untracked<'a>(
+        &self,
+        world: &'a World,
+        path: &ParsedPath,
+    ) -> Option<&'a dyn Reflect> {
+        match self.0.reflect(world).reflect_path(path) {
+            Ok(result) => Some(result),
+            Err(ReflectPathError::InvalidAccess(_)) => None,
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    fn set_path(&self, world: &mut World, path: &ParsedPath, value: &dyn Reflect) {
+        self.0.update(world, &|setting| {
+            setting.reflect_path_mut(path).unwrap().apply(value)
+        });
+    }
+
+    fn update_path(&self, world: &mut World, path: &ParsedPath, f: &dyn Fn(&mut dyn Reflect)) {
+        self.0
+            .update(world, &|setting| f(setting.reflect_path_mut(path).unwrap()));
+    }
+}
+
+fn style_settings_body(ss: &mut StyleBuilder) {
+    ss.height(ui::Val::Px(400.));
+}
+
+fn style_settings_list(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .gap(8);
+}
+
+/// Dialog which shows an inspector for each of the registered [`Settings`].
+#[derive(Clone, PartialEq, Default)]
+pub struct SettingsDialog {
+    /// Whether the dialog is shown.
+    pub open: bool,
+
+    /// Callback called when the dialog is closed.
+    pub on_close: Option<Callback>,
+}
+
+impl SettingsDialog {
+    /// Create a new settings dialog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the dialog is shown.
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    /// Set the callback called when the dialog is closed.
+    pub fn on_close(mut self, on_close: Callback) -> Self {
+        self.on_close = Some(on_close);
+        self
+    }
+}
+
+impl ViewTemplate for SettingsDialog {
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        // Settings are only registered during app setup, so the list doesn't need tracking.
+        // Creating the inspectors once also keeps them from being rebuilt while editing.
+        let inspectors = cx.create_memo(
+            |world, _| {
+                world
+                    .resource::<Settings>()
+                    .entries()
+                    .iter()
+                    .map(|entry| Inspector::new(Arc::new(InspectableSetting(*entry))))
+                    .collect::<Vec<_>>()
+            },
+            (),
+        );
+        let on_reset = cx.create_callback(Settings::reset_all);
+        let on_close = self.on_close;
+        let on_done = cx.create_callback(move |world: &mut World| {
+            if let Some(on_close) = on_close {
+                world.run_callback(on_close, ());
+            }
+        });
+
+        Dialog::new()
+            .width(ui::Val::Px(500.))
+            .open(self.open)
+            .on_close(on_done)
+            .children((
+                DialogHeader::new().children("Settings"),
+                DialogBody::new().children(
+                    ScrollView::new()
+                        .style(style_settings_body)
+                        .content_style(style_settings_list)
+                        .children(For::index(&inspectors, |inspector, _| inspector.clone())),
+                ),
+                DialogFooter::new().children((
+                    Button::new()
+                        .children("Restore Defaults")
+                        .on_click(on_reset),
+                    Spacer,
+                    Button::new()
+                        .children("Done")
+                        .autofocus(true)
+                        .on_click(on_done),
+                )),
+            ))
+    }
+}
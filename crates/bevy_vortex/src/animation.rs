@@ -0,0 +1,424 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commands::MarkModifiedCmd,
+    graph::GraphNode,
+    pipeline::{AnimatedParams, MAX_ANIMATED_PARAMS},
+};
+
+/// How a value changes between one keyframe and the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Start slowly and speed up.
+    EaseIn,
+    /// Start quickly and slow down.
+    EaseOut,
+    /// Start and end slowly.
+    #[default]
+    EaseInOut,
+    /// Hold the value until the next keyframe.
+    Step,
+}
+
+impl Easing {
+    /// Map the linear progress `t`, from 0 to 1, to the eased progress.
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2. - t),
+            Easing::EaseInOut => t * t * (3. - 2. * t),
+            Easing::Step => 0.,
+        }
+    }
+
+    /// The next easing mode, used for cycling through modes in the timeline.
+    pub fn next(self) -> Self {
+        match self {
+            Easing::Linear => Easing::EaseIn,
+            Easing::EaseIn => Easing::EaseOut,
+            Easing::EaseOut => Easing::EaseInOut,
+            Easing::EaseInOut => Easing::Step,
+            Easing::Step => Easing::Linear,
+        }
+    }
+}
+
+/// The value of a parameter at a point in time.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// Time of the keyframe, in seconds.
+    pub time: f32,
+    /// Value of the parameter.
+    pub value: f32,
+    /// How the value moves from this keyframe to the next one.
+    pub easing: Easing,
+}
+
+/// The keyframes for one parameter of a node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationTrack {
+    /// Entity of the node being animated.
+    pub node: Entity,
+    /// Name of the animated operator field.
+    pub field: &'static str,
+    /// Keyframes, in order of time.
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl AnimationTrack {
+    /// Add a keyframe, replacing any existing keyframe at the same time.
+    pub fn set_key(&mut self, time: f32, value: f32, easing: Easing) {
+        let key = Keyframe {
+            time,
+            value,
+            easing,
+        };
+        match self.keyframes.binary_search_by(|k| k.time.total_cmp(&time)) {
+            Ok(index) => self.keyframes[index] = key,
+            Err(index) => self.keyframes.insert(index, key),
+        }
+    }
+
+    /// Compute the value of the parameter at the given time. Before the first keyframe and
+    /// after the last, the value is held constant.
+    pub fn sample(&self, time: f32) -> Option<f32> {
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return self.keyframes.first().map(|k| k.value);
+        }
+        let prev = &self.keyframes[next - 1];
+        let Some(next) = self.keyframes.get(next) else {
+            return Some(prev.value);
+        };
+        let t = (time - prev.time) / (next.time - prev.time);
+        Some(prev.value + (next.value - prev.value) * prev.easing.apply(t))
+    }
+}
+
+/// An [`AnimationTrack`] as it is saved, with the node identified by its index among the
+/// saved nodes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedTrack {
+    /// Index of the animated node.
+    pub node: usize,
+    /// Name of the animated operator field.
+    pub field: String,
+    /// Keyframes, in order of time.
+    pub keyframes: Vec<Keyframe>,
+}
+
+/// Resource which holds the animation tracks and the playback state. The value of each track
+/// is written into the [`AnimatedParams`] uniform, rather than into the operator, so playback
+/// does not rebuild any shaders.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Timeline {
+    /// Animated parameters.
+    pub tracks: Vec<AnimationTrack>,
+    /// Current playback position, in seconds.
+    pub time: f32,
+    /// Length of the timeline, in seconds. Playback loops back to the start at the end.
+    pub duration: f32,
+    /// Whether the timeline is playing.
+    pub playing: bool,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            tracks: Vec::new(),
+            time: 0.,
+            duration: 4.,
+            playing: false,
+        }
+    }
+}
+
+impl Timeline {
+    /// Add a keyframe to the track for the given node field, creating the track if needed.
+    pub fn set_key(&mut self, node: Entity, field: &'static str, value: f32) {
+        let time = self.time;
+        match self
+            .tracks
+            .iter_mut()
+            .find(|t| t.node == node && t.field == field)
+        {
+            Some(track) => track.set_key(time, value, Easing::default()),
+            None => {
+                let mut track = AnimationTrack {
+                    node,
+                    field,
+                    keyframes: Vec::new(),
+                };
+                track.set_key(time, value, Easing::default());
+                self.tracks.push(track);
+            }
+        }
+    }
+
+    /// The animated operator fields, in the order of their slots in the [`AnimatedParams`].
+    pub fn params(&self) -> Vec<(Entity, &'static str)> {
+        self.tracks
+            .iter()
+            .take(MAX_ANIMATED_PARAMS)
+            .map(|track| (track.node, track.field))
+            .collect()
+    }
+
+    /// The tracks of the given nodes, for saving. `indices` maps each node to its index among
+    /// the saved nodes; tracks of other nodes are left out.
+    pub fn save_tracks(&self, indices: &HashMap<Entity, usize>) -> Vec<SavedTrack> {
+        self.tracks
+            .iter()
+            .filter_map(|track| {
+                Some(SavedTrack {
+                    node: *indices.get(&track.node)?,
+                    field: track.field.to_string(),
+                    keyframes: track.keyframes.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Add saved tracks, given the entities of the saved nodes in order, replacing any tracks
+    /// of the same fields. Tracks whose node or field no longer exists are dropped.
+    pub fn restore_tracks(
+        &mut self,
+        saved: &[SavedTrack],
+        nodes: &[Option<Entity>],
+        world: &World,
+    ) {
+        for saved in saved {
+            let Some(Some(node)) = nodes.get(saved.node).copied() else {
+                continue;
+            };
+            // The track refers to the field by its static name, so look it up in the type.
+            let Some(field) = world
+                .get::<GraphNode>(node)
+                .and_then(|node| node.operator_field_name(&saved.field))
+            else {
+                continue;
+            };
+            self.tracks
+                .retain(|track| track.node != node || track.field != field);
+            self.tracks.push(AnimationTrack {
+                node,
+                field,
+                keyframes: saved.keyframes.clone(),
+            });
+        }
+    }
+}
+
+/// Advances the timeline during playback, and writes the animated values into the
+/// [`AnimatedParams`] uniform. When tracks are added or removed, the shaders of the affected
+/// nodes are rebuilt so that they read their fields from the right slots.
+pub(crate) fn play_timeline(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timeline: ResMut<Timeline>,
+    mut params: ResMut<AnimatedParams>,
+    nodes: Query<(), With<GraphNode>>,
+    mut slots: Local<Vec<(Entity, &'static str)>>,
+) {
+    if timeline.playing {
+        let duration = timeline.duration.max(f32::EPSILON);
+        timeline.time = (timeline.time + time.delta_seconds()) % duration;
+    }
+    if !timeline.is_changed() {
+        return;
+    }
+    // Remove tracks for nodes which have been deleted.
+    if timeline
+        .tracks
+        .iter()
+        .any(|track| !nodes.contains(track.node))
+    {
+        timeline.tracks.retain(|track| nodes.contains(track.node));
+    }
+
+    let next_slots = timeline.params();
+    if next_slots != *slots {
+        let mut rebuild: Vec<Entity> = slots
+            .iter()
+            .chain(next_slots.iter())
+            .map(|(node, _)| *node)
+            .filter(|node| nodes.contains(*node))
+            .collect();
+        rebuild.sort();
+        rebuild.dedup();
+        for node in rebuild {
+            commands.add(MarkModifiedCmd { start: node });
+        }
+        *slots = next_slots;
+    }
+
+    let mut values = AnimatedParams::default();
+    for (slot, track) in timeline.tracks.iter().take(MAX_ANIMATED_PARAMS).enumerate() {
+        if let Some(value) = track.sample(timeline.time) {
+            values.set(slot, value);
+        }
+    }
+    params.set_if_neq(values);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(keys: &[(f32, f32, Easing)]) -> AnimationTrack {
+        let mut track = AnimationTrack {
+            node: Entity::PLACEHOLDER,
+            field: "value",
+            keyframes: Vec::new(),
+        };
+        for (time, value, easing) in keys {
+            track.set_key(*time, *value, *easing);
+        }
+        track
+    }
+
+    #[test]
+    fn test_sample_empty() {
+        assert_eq!(track(&[]).sample(1.), None);
+    }
+
+    #[test]
+    fn test_sample_linear() {
+        let t = track(&[(2., 10., Easing::Linear), (0., 0., Easing::Linear)]);
+        assert_eq!(t.keyframes[0].time, 0.);
+        assert_eq!(t.sample(-1.), Some(0.));
+        assert_eq!(t.sample(1.), Some(5.));
+        assert_eq!(t.sample(2.), Some(10.));
+        assert_eq!(t.sample(3.), Some(10.));
+    }
+
+    #[test]
+    fn test_sample_step() {
+        let t = track(&[(0., 0., Easing::Step), (2., 10., Easing::Linear)]);
+        assert_eq!(t.sample(1.9), Some(0.));
+        assert_eq!(t.sample(2.), Some(10.));
+    }
+
+    #[test]
+    fn test_set_key_replaces() {
+        let t = track(&[(1., 1., Easing::Linear), (1., 2., Easing::Linear)]);
+        assert_eq!(t.keyframes.len(), 1);
+        assert_eq!(t.sample(1.), Some(2.));
+    }
+
+    const EASINGS: [Easing; 5] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+        Easing::Step,
+    ];
+
+    #[test]
+    fn test_easing_curves() {
+        for easing in EASINGS {
+            if easing != Easing::Step {
+                assert_eq!(easing.apply(0.), 0., "{:?}", easing);
+                assert_eq!(easing.apply(1.), 1., "{:?}", easing);
+            }
+            // Every curve is monotonic and stays within the range of the keyframes.
+            let mut prev = easing.apply(0.);
+            for i in 1..=100 {
+                let value = easing.apply(i as f32 / 100.);
+                assert!(value >= prev, "{:?} decreases at {}", easing, i);
+                assert!(
+                    (0. ..=1.).contains(&value),
+                    "{:?} overshoots at {}",
+                    easing,
+                    i
+                );
+                prev = value;
+            }
+        }
+        for i in 1..100 {
+            let t = i as f32 / 100.;
+            assert!(Easing::EaseIn.apply(t) < t);
+            assert!(Easing::EaseOut.apply(t) > t);
+            // Ease-in-out is symmetric about the midpoint.
+            let sum = Easing::EaseInOut.apply(t) + Easing::EaseInOut.apply(1. - t);
+            assert!((sum - 1.).abs() < 1e-6);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_sample_segment_easings() {
+        // Each segment uses the easing of the keyframe at its start.
+        let t = track(&[
+            (0., 0., Easing::EaseIn),
+            (1., 10., Easing::EaseOut),
+            (2., 0., Easing::Step),
+            (3., 5., Easing::Linear),
+        ]);
+        assert_eq!(t.sample(0.5), Some(2.5));
+        assert_eq!(t.sample(1.5), Some(10. - 10. * 0.75));
+        assert_eq!(t.sample(2.5), Some(0.));
+        assert_eq!(t.sample(3.5), Some(5.));
+
+        // The value is continuous at the keyframes, except where a step jumps.
+        for (key, value) in [(1., 10.), (2., 0.)] {
+            let before = t.sample(key - 1e-4).unwrap();
+            assert!((before - value).abs() < 0.01, "{} at {}", before, key);
+            assert_eq!(t.sample(key), Some(value));
+        }
+        assert_eq!(t.sample(3. - 1e-4), Some(0.));
+        assert_eq!(t.sample(3.), Some(5.));
+    }
+
+    #[test]
+    fn test_sample_descending() {
+        for easing in EASINGS {
+            let t = track(&[(0., 8., easing), (4., -8., easing)]);
+            let mut prev = t.sample(0.).unwrap();
+            for i in 1..=40 {
+                let value = t.sample(i as f32 / 10.).unwrap();
+                assert!(value <= prev, "{:?} increases at {}", easing, i);
+                prev = value;
+            }
+            assert_eq!(t.sample(4.), Some(-8.));
+        }
+    }
+
+    #[test]
+    fn test_save_tracks() {
+        let node = Entity::from_raw(7);
+        let other = Entity::from_raw(8);
+        let mut timeline = Timeline::default();
+        timeline.set_key(node, "scale", 1.);
+        timeline.time = 2.;
+        timeline.set_key(node, "scale", 3.);
+        timeline.set_key(other, "factor", 0.5);
+        assert_eq!(timeline.params(), vec![(node, "scale"), (other, "factor")]);
+
+        // Only the tracks of saved nodes are kept, and they survive a round trip through RON.
+        let indices = HashMap::from_iter([(node, 4)]);
+        let saved = timeline.save_tracks(&indices);
+        let text = ron::to_string(&saved).unwrap();
+        let loaded: Vec<SavedTrack> = ron::from_str(&text).unwrap();
+        assert_eq!(loaded, saved);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].node, 4);
+        assert_eq!(loaded[0].field, "scale");
+        assert_eq!(loaded[0].keyframes, timeline.tracks[0].keyframes);
+    }
+
+    #[test]
+    fn test_animated_params_slots() {
+        let mut params = AnimatedParams::default();
+        params.set(0, 1.);
+        params.set(6, 2.);
+        params.set(MAX_ANIMATED_PARAMS - 1, 3.);
+        assert_eq!(params.values[0], Vec4::new(1., 0., 0., 0.));
+        assert_eq!(params.values[1], Vec4::new(0., 0., 2., 0.));
+        assert_eq!(params.values[MAX_ANIMATED_PARAMS / 4 - 1].w, 3.);
+    }
+}
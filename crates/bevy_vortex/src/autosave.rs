@@ -0,0 +1,320 @@
+use std::{io, path::PathBuf, time::Duration};
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_quill_obsidian::controls::{ConfirmOptions, Dialogs};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animation::{AnimationTrack, Timeline},
+    commands::{capture_nodes, instantiate_preset},
+    graph::{Connection, GraphNode, GraphNote, GraphResource},
+    preset::NodePreset,
+};
+
+/// How often the graph is saved to the recovery file while it has unsaved changes.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The directory where the recovery file is stored. This can be overridden with the
+/// `VORTEX_RECOVERY_DIR` environment variable.
+fn recovery_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("VORTEX_RECOVERY_DIR") {
+        return dir.into();
+    }
+    match std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        Some(home) => PathBuf::from(home).join(".vortex").join("recovery"),
+        None => PathBuf::from("recovery"),
+    }
+}
+
+/// The contents of the recovery file.
+#[derive(Serialize, Deserialize)]
+struct Recovery {
+    /// Position of the top-left corner of the graph.
+    origin: (i32, i32),
+    seed: u32,
+    /// The graph, including its animation tracks.
+    graph: NodePreset,
+    /// Length of the timeline, in seconds.
+    #[serde(default)]
+    duration: Option<f32>,
+}
+
+/// Resource which tracks whether the graph has changed since it was last autosaved.
+#[derive(Resource)]
+pub struct Autosave {
+    dir: PathBuf,
+    /// True if the graph has changed since the last autosave.
+    pub(crate) dirty: bool,
+    timer: Timer,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self {
+            dir: recovery_dir(),
+            dirty: false,
+            timer: Timer::new(AUTOSAVE_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Autosave {
+    fn recovery_path(&self) -> PathBuf {
+        self.dir.join("recovery.ron")
+    }
+
+    /// The lock file exists while Vortex is running. If it is present at startup, the
+    /// previous session did not exit cleanly.
+    fn lock_path(&self) -> PathBuf {
+        self.dir.join("session.lock")
+    }
+
+    fn write(&self, recovery: &Recovery) -> io::Result<()> {
+        let text = ron::ser::to_string_pretty(recovery, default()).map_err(io::Error::other)?;
+        // Write to a temporary file first, so that a crash while saving does not destroy
+        // the previous recovery file.
+        let temp = self.dir.join("recovery.ron.tmp");
+        std::fs::write(&temp, text)?;
+        std::fs::rename(temp, self.recovery_path())
+    }
+
+    fn read(&self) -> Option<Recovery> {
+        let text = std::fs::read_to_string(self.recovery_path()).ok()?;
+        match ron::from_str(&text) {
+            Ok(recovery) => Some(recovery),
+            Err(err) => {
+                warn!("Failed to read recovery file: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Remove the recovery file, and the lock file if `unlock` is true.
+    fn clear(&self, unlock: bool) {
+        let _ = std::fs::remove_file(self.recovery_path());
+        if unlock {
+            let _ = std::fs::remove_file(self.lock_path());
+        }
+    }
+}
+
+/// Offer to restore the recovery file if the previous session did not exit cleanly, then
+/// take the lock for this session.
+fn check_recovery(autosave: Res<Autosave>, mut dialogs: ResMut<Dialogs>) {
+    if autosave.lock_path().exists() {
+        if let Some(recovery) = autosave.read() {
+            dialogs
+                .confirm(
+                    "Recover Graph",
+                    "Vortex did not exit cleanly. Restore the graph from the last autosave?",
+                    ConfirmOptions {
+                        confirm_label: "Restore".to_string(),
+                        cancel_label: "Discard".to_string(),
+                        ..default()
+                    },
+                )
+                .then(move |world, confirmed| {
+                    if confirmed {
+                        restore(world, recovery);
+                    } else {
+                        world.resource::<Autosave>().clear(false);
+                    }
+                });
+        }
+    }
+    if let Err(err) = std::fs::create_dir_all(&autosave.dir)
+        .and_then(|_| std::fs::write(autosave.lock_path(), ""))
+    {
+        warn!(
+            "Failed to create lock file in {}: {}",
+            autosave.dir.display(),
+            err
+        );
+    }
+}
+
+fn restore(world: &mut World, recovery: Recovery) {
+    let origin = IVec2::new(recovery.origin.0, recovery.origin.1);
+    instantiate_preset(world, &recovery.graph, origin);
+    world.resource_mut::<GraphResource>().0.seed = recovery.seed;
+    if let Some(duration) = recovery.duration {
+        world.resource_mut::<Timeline>().duration = duration;
+    }
+    info!(
+        "Restored graph from {}",
+        world.resource::<Autosave>().recovery_path().display()
+    );
+}
+
+/// Mark the graph as dirty when nodes, connections, notes or animation tracks change.
+#[allow(clippy::too_many_arguments)]
+fn track_changes(
+    mut autosave: ResMut<Autosave>,
+    graph: Res<GraphResource>,
+    timeline: Res<Timeline>,
+    mut tracks: Local<Vec<AnimationTrack>>,
+    changed_nodes: Query<(), Changed<GraphNode>>,
+    changed_notes: Query<(), Changed<GraphNote>>,
+    added_connections: Query<(), Added<Connection>>,
+    mut removed_nodes: RemovedComponents<GraphNode>,
+    mut removed_notes: RemovedComponents<GraphNote>,
+    mut removed_connections: RemovedComponents<Connection>,
+) {
+    // The timeline also changes during playback, so compare the tracks.
+    let tracks_changed = timeline.is_changed() && timeline.tracks != *tracks;
+    if tracks_changed {
+        *tracks = timeline.tracks.clone();
+    }
+    let removed = removed_nodes.read().count()
+        + removed_notes.read().count()
+        + removed_connections.read().count()
+        > 0;
+    if !autosave.dirty
+        && (graph.is_changed()
+            || tracks_changed
+            || removed
+            || !changed_nodes.is_empty()
+            || !changed_notes.is_empty()
+            || !added_connections.is_empty())
+    {
+        autosave.dirty = true;
+    }
+}
+
+/// Capture the whole graph for the recovery file, or `None` if the graph is empty.
+fn capture(world: &mut World) -> Option<Recovery> {
+    let (origin, graph) = capture_nodes::<()>(world)?;
+    Some(Recovery {
+        origin: (origin.x, origin.y),
+        seed: world.resource::<GraphResource>().0.seed,
+        graph,
+        duration: Some(world.resource::<Timeline>().duration),
+    })
+}
+
+/// Write the graph to the recovery file, or remove the file if there is nothing to recover.
+fn save_recovery(world: &mut World) -> io::Result<()> {
+    let Some(recovery) = capture(world) else {
+        world.resource::<Autosave>().clear(false);
+        return Ok(());
+    };
+    let autosave = world.resource::<Autosave>();
+    autosave.write(&recovery)?;
+    info!("Autosaved graph to {}", autosave.recovery_path().display());
+    Ok(())
+}
+
+/// Save the graph to the recovery file periodically, while it has unsaved changes.
+fn autosave(world: &mut World) {
+    let delta = world.resource::<Time<Real>>().delta();
+    // Don't replace the recovery file while the user is deciding whether to restore it.
+    let prompting = world.resource::<Dialogs>().is_pending();
+    let mut autosave = world.resource_mut::<Autosave>();
+    if !autosave.timer.tick(delta).just_finished() || !autosave.dirty || prompting {
+        return;
+    }
+    // If saving fails, the graph stays dirty so that it is tried again at the next interval.
+    match save_recovery(world) {
+        Ok(()) => world.resource_mut::<Autosave>().dirty = false,
+        Err(err) => warn!("Autosave failed: {}", err),
+    }
+}
+
+/// Remove the recovery and lock files when the application exits normally.
+fn clear_on_exit(autosave: Res<Autosave>, mut exit: EventReader<AppExit>) {
+    if exit.read().count() > 0 {
+        autosave.clear(true);
+    }
+}
+
+pub struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Autosave>()
+            .add_systems(Startup, check_recovery)
+            .add_systems(Update, (track_changes, autosave).chain())
+            .add_systems(Last, clear_on_exit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{graph::testing::*, operator::Operator, ops::Mix};
+
+    fn recovery_world(dir: PathBuf) -> World {
+        let mut world = graph_world();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<LinearRgba>();
+            registry.register::<Mix>();
+        }
+        world.insert_resource(registry);
+        world.init_resource::<Timeline>();
+        world.insert_resource(Autosave {
+            dir,
+            dirty: true,
+            timer: Timer::new(AUTOSAVE_INTERVAL, TimerMode::Repeating),
+        });
+        world
+    }
+
+    fn mix() -> Box<dyn Operator> {
+        Box::<Mix>::default()
+    }
+
+    /// The positions of the nodes, and the connections between them by position.
+    fn layout(world: &World) -> (HashSet<IVec2>, HashSet<(IVec2, IVec2, &str)>) {
+        let position = |node| world.get::<GraphNode>(node).unwrap().position;
+        let graph = &world.resource::<GraphResource>().0;
+        (
+            graph
+                .iter_nodes()
+                .map(|(_, node)| position(*node))
+                .collect(),
+            edges(world)
+                .into_iter()
+                .map(|(from, to, input)| (position(from), position(to), input))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_recovery_round_trip() {
+        let dir = std::env::temp_dir().join(format!("vortex-recovery-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut world = recovery_world(dir.clone());
+        let a = add_node(&mut world, mix());
+        let b = add_node(&mut world, mix());
+        world.get_mut::<GraphNode>(a).unwrap().position = IVec2::new(40, 20);
+        world.get_mut::<GraphNode>(b).unwrap().position = IVec2::new(200, 60);
+        connect(&mut world, a, b, "input_a");
+        world.resource_mut::<GraphResource>().0.seed = 7;
+        world.resource_mut::<Timeline>().duration = 12.;
+
+        // Writing fails until the directory exists, in which case there is no recovery file.
+        assert!(save_recovery(&mut world).is_err());
+        std::fs::create_dir_all(&dir).unwrap();
+        save_recovery(&mut world).unwrap();
+        let recovery = world.resource::<Autosave>().read().unwrap();
+        assert_eq!(recovery.origin, (40, 20));
+
+        let mut restored = recovery_world(dir.clone());
+        restore(&mut restored, recovery);
+        assert_eq!(layout(&restored), layout(&world));
+        assert_eq!(restored.resource::<GraphResource>().0.seed, 7);
+        assert_eq!(restored.resource::<Timeline>().duration, 12.);
+
+        // Saving an empty graph removes the recovery file.
+        let mut empty = recovery_world(dir.clone());
+        save_recovery(&mut empty).unwrap();
+        assert!(empty.resource::<Autosave>().read().is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,276 @@
+use std::path::PathBuf;
+
+use bevy::{
+    ecs::{system::SystemState, world::Command},
+    prelude::*,
+};
+
+use crate::{
+    color_management::WorkingSpace,
+    commands::InstantiatePresetCmd,
+    gen::{export_node_shader, ShaderLanguage, TerminalReader},
+    graph::{GraphNode, GraphResource, Terminal},
+    ops::OperatorsPlugin,
+    preset::{NodePreset, PresetLibrary},
+};
+
+const USAGE: &str = "\
+Usage: bevy_vortex export <graph.ron> [--out <dir>] [--lang wgsl|glsl|hlsl]...
+                          [--working-space linear|srgb] [--seed <n>]
+
+Generates shaders for every node whose outputs are not connected to anything, and writes them
+to the output directory (default: the current directory). Graph files use the same format as
+saved presets. The graph is evaluated in the given working space (default: linear), and the
+shaders always output linear color. The seed (default: 0) randomizes noise.
+
+Only shader source is generated: textures are not baked, since rendering them needs a GPU.
+To produce images, compile the exported shaders in the target engine.";
+
+struct ExportOptions {
+    graph: PathBuf,
+    out: PathBuf,
+    languages: Vec<ShaderLanguage>,
+    working_space: WorkingSpace,
+    seed: u32,
+}
+
+fn parse_language(name: &str) -> Option<ShaderLanguage> {
+    [
+        ShaderLanguage::Wgsl,
+        ShaderLanguage::Glsl,
+        ShaderLanguage::Hlsl,
+    ]
+    .into_iter()
+    .find(|language| language.backend().name().eq_ignore_ascii_case(name))
+}
+
+fn parse_args(args: &[String]) -> Result<ExportOptions, String> {
+    let mut graph = None;
+    let mut out = PathBuf::from(".");
+    let mut languages = Vec::new();
+    let mut working_space = WorkingSpace::default();
+    let mut seed = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => {
+                out = args.next().ok_or("missing directory after --out")?.into();
+            }
+            "--lang" => {
+                let name = args.next().ok_or("missing language after --lang")?;
+                languages.push(
+                    parse_language(name).ok_or_else(|| format!("unknown language: {}", name))?,
+                );
+            }
+            "--working-space" => {
+                let name = args
+                    .next()
+                    .ok_or("missing working space after --working-space")?;
+                working_space = WorkingSpace::from_name(name)
+                    .ok_or_else(|| format!("unknown working space: {}", name))?;
+            }
+            "--seed" => {
+                let value = args.next().ok_or("missing number after --seed")?;
+                seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed: {}", value))?;
+            }
+            _ if graph.is_none() && !arg.starts_with('-') => graph = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    if languages.is_empty() {
+        languages.push(ShaderLanguage::Wgsl);
+    }
+    Ok(ExportOptions {
+        graph: graph.ok_or("missing graph file")?,
+        out,
+        languages,
+        working_space,
+        seed,
+    })
+}
+
+/// Generate shaders for a graph file without opening a window. Returns the process exit code.
+///
+/// This does not bake textures: the graph is never rendered, so it can run on machines with
+/// no GPU, such as CI servers.
+pub fn export(args: &[String]) -> i32 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return 2;
+        }
+    };
+    let graph = match std::fs::read_to_string(&options.graph)
+        .map_err(|err| err.to_string())
+        .and_then(|text| ron::from_str::<NodePreset>(&text).map_err(|err| err.to_string()))
+    {
+        Ok(graph) => graph,
+        Err(err) => {
+            eprintln!("Failed to read {}: {}", options.graph.display(), err);
+            return 1;
+        }
+    };
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), OperatorsPlugin))
+        .init_resource::<GraphResource>()
+        .insert_resource(PresetLibrary {
+            dir: PathBuf::new(),
+            presets: vec![graph],
+        });
+    let world = app.world_mut();
+    InstantiatePresetCmd {
+        preset: 0,
+        position: IVec2::ZERO,
+    }
+    .apply(world);
+
+    if let Err(err) = std::fs::create_dir_all(&options.out) {
+        eprintln!("Failed to create {}: {}", options.out.display(), err);
+        return 1;
+    }
+
+    let mut st: SystemState<(
+        TerminalReader,
+        Query<(Entity, &GraphNode)>,
+        Query<&Terminal>,
+    )> = SystemState::new(world);
+    let (reader, nodes, terminals) = st.get(world);
+    let mut failed = false;
+    for (node_id, node) in nodes.iter() {
+        // Only export the final nodes of the graph.
+        let is_target = !node.outputs.is_empty()
+            && node.outputs.iter().all(|(_, terminal_id)| {
+                terminals
+                    .get(*terminal_id)
+                    .map_or(true, |terminal| !terminal.is_connected())
+            });
+        if !is_target {
+            continue;
+        }
+        for language in options.languages.iter() {
+            let path = options.out.join(format!(
+                "{}_{}.{}",
+                node.title().to_lowercase(),
+                node.index.0,
+                language.backend().file_extension()
+            ));
+            match export_node_shader(
+                &reader,
+                node_id,
+                *language,
+                options.working_space,
+                options.seed,
+            ) {
+                Some(Ok(source)) => match std::fs::write(&path, source) {
+                    Ok(()) => println!("Wrote {}", path.display()),
+                    Err(err) => {
+                        eprintln!("Failed to write {}: {}", path.display(), err);
+                        failed = true;
+                    }
+                },
+                Some(Err(err)) => {
+                    eprintln!("Failed to generate {}: {}", path.display(), err);
+                    failed = true;
+                }
+                None => {}
+            }
+        }
+    }
+    i32::from(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<ExportOptions, String> {
+        parse_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let options = parse(&["graph.ron"]).unwrap();
+        assert_eq!(options.graph, PathBuf::from("graph.ron"));
+        assert_eq!(options.out, PathBuf::from("."));
+        assert_eq!(options.languages, [ShaderLanguage::Wgsl]);
+        assert_eq!(options.working_space, WorkingSpace::default());
+        assert_eq!(options.seed, 0);
+    }
+
+    #[test]
+    fn test_parse_all_flags() {
+        let options = parse(&[
+            "--lang",
+            "GLSL",
+            "graph.ron",
+            "--out",
+            "shaders",
+            "--lang",
+            "hlsl",
+            "--working-space",
+            "srgb",
+            "--seed",
+            "42",
+        ])
+        .unwrap();
+        assert_eq!(options.graph, PathBuf::from("graph.ron"));
+        assert_eq!(options.out, PathBuf::from("shaders"));
+        assert_eq!(
+            options.languages,
+            [ShaderLanguage::Glsl, ShaderLanguage::Hlsl]
+        );
+        assert_eq!(options.working_space, WorkingSpace::Srgb);
+        assert_eq!(options.seed, 42);
+    }
+
+    #[test]
+    fn test_parse_missing_values() {
+        assert_eq!(parse(&[]).err().unwrap(), "missing graph file");
+        assert_eq!(
+            parse(&["graph.ron", "--out"]).err().unwrap(),
+            "missing directory after --out"
+        );
+        assert_eq!(
+            parse(&["graph.ron", "--lang"]).err().unwrap(),
+            "missing language after --lang"
+        );
+        assert_eq!(
+            parse(&["graph.ron", "--working-space"]).err().unwrap(),
+            "missing working space after --working-space"
+        );
+        assert_eq!(
+            parse(&["graph.ron", "--seed"]).err().unwrap(),
+            "missing number after --seed"
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_values() {
+        assert_eq!(
+            parse(&["graph.ron", "--lang", "msl"]).err().unwrap(),
+            "unknown language: msl"
+        );
+        assert_eq!(
+            parse(&["graph.ron", "--working-space", "aces"])
+                .err()
+                .unwrap(),
+            "unknown working space: aces"
+        );
+        assert_eq!(
+            parse(&["graph.ron", "--seed", "-1"]).err().unwrap(),
+            "invalid seed: -1"
+        );
+        assert_eq!(
+            parse(&["graph.ron", "--bake"]).err().unwrap(),
+            "unexpected argument: --bake"
+        );
+        assert_eq!(
+            parse(&["a.ron", "b.ron"]).err().unwrap(),
+            "unexpected argument: b.ron"
+        );
+    }
+}
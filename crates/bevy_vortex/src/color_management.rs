@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use bevy::{core_pipeline::tonemapping::Tonemapping, prelude::*};
+
+use crate::{
+    gen::{DataType, Expr, ShaderAssembly},
+    graph::{GraphNode, NodeModified},
+};
+
+/// Name of the local variable which holds the shader output before it is converted from the
+/// working space.
+const WORKING_OUTPUT: &str = "working_output";
+
+/// The color space in which the graph is evaluated: colors entered in the graph, and the values
+/// computed by its nodes, are interpreted in this space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WorkingSpace {
+    /// Scene-linear values, which the renderer expects.
+    #[default]
+    Linear,
+    /// sRGB-encoded values, as found in most images and color pickers.
+    Srgb,
+}
+
+impl WorkingSpace {
+    /// Display name of the working space.
+    pub fn name(self) -> &'static str {
+        match self {
+            WorkingSpace::Linear => "Linear",
+            WorkingSpace::Srgb => "sRGB",
+        }
+    }
+
+    /// Parse a working space from its name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [WorkingSpace::Linear, WorkingSpace::Srgb]
+            .into_iter()
+            .find(|space| space.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Wrap the fragment value of a shader so that it is converted from the working space to
+    /// linear, which is what the preview renderer and exported shaders produce. The alpha
+    /// channel is left as it is.
+    pub(crate) fn convert_output(self, assembly: &mut ShaderAssembly, value: Expr) -> Expr {
+        match self {
+            WorkingSpace::Linear => value,
+            WorkingSpace::Srgb => {
+                // Evaluate the graph once, rather than for each use in the conversion.
+                assembly.declare_local(
+                    WORKING_OUTPUT.to_string(),
+                    DataType::LinearRgba,
+                    false,
+                    Arc::new(value),
+                );
+                let output = Arc::new(Expr::RefLocal(
+                    DataType::LinearRgba,
+                    WORKING_OUTPUT.to_string(),
+                ));
+                let rgb = Arc::new(Expr::GetAttr(DataType::Vec3, output.clone(), "rgb"));
+                Expr::FnCall(
+                    DataType::LinearRgba,
+                    "vec4f",
+                    vec![
+                        srgb_to_linear(rgb),
+                        Arc::new(Expr::GetAttr(DataType::F32, output, "a")),
+                    ],
+                )
+            }
+        }
+    }
+}
+
+fn splat(value: f32) -> Arc<Expr> {
+    Arc::new(Expr::ConstVec3(Vec3::splat(value)))
+}
+
+/// Build an expression which decodes an sRGB-encoded `vec3` using the piecewise sRGB transfer
+/// function. Only function calls are used, so that every shader backend can express it.
+fn srgb_to_linear(rgb: Arc<Expr>) -> Arc<Expr> {
+    // c / 12.92
+    let low = Arc::new(Expr::FnCall(
+        DataType::Vec3,
+        "fma",
+        vec![rgb.clone(), splat(1. / 12.92), splat(0.)],
+    ));
+    // ((c + 0.055) / 1.055) ^ 2.4
+    let high = Arc::new(Expr::FnCall(
+        DataType::Vec3,
+        "pow",
+        vec![
+            Arc::new(Expr::FnCall(
+                DataType::Vec3,
+                "fma",
+                vec![rgb.clone(), splat(1. / 1.055), splat(0.055 / 1.055)],
+            )),
+            splat(2.4),
+        ],
+    ));
+    let select = Arc::new(Expr::FnCall(
+        DataType::Vec3,
+        "step",
+        vec![splat(0.04045), rgb],
+    ));
+    Arc::new(Expr::FnCall(DataType::Vec3, "mix", vec![low, high, select]))
+}
+
+/// How the linear output of a shader is transformed for display in the preview.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ViewTransform {
+    /// Standard sRGB display encoding. Values outside of the display range are clipped.
+    #[default]
+    Srgb,
+    /// The ACES filmic tone curve, which compresses bright values rather than clipping them.
+    Aces,
+}
+
+impl ViewTransform {
+    /// Display name of the view transform.
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewTransform::Srgb => "sRGB",
+            ViewTransform::Aces => "ACES",
+        }
+    }
+
+    /// The camera tonemapping which implements this view transform. The sRGB encoding itself
+    /// is done by the render target.
+    fn tonemapping(self) -> Tonemapping {
+        match self {
+            ViewTransform::Srgb => Tonemapping::None,
+            ViewTransform::Aces => Tonemapping::AcesFitted,
+        }
+    }
+}
+
+/// Resource which holds the color management settings.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ColorManagement {
+    /// Color space in which the graph is evaluated.
+    pub working_space: WorkingSpace,
+    /// Transform applied to the preview, and to preset thumbnails rendered from it.
+    pub view_transform: ViewTransform,
+}
+
+/// Rebuild all node shaders when the working space changes.
+pub(crate) fn rebuild_on_working_space_change(
+    mut commands: Commands,
+    color: Res<ColorManagement>,
+    mut working_space: Local<WorkingSpace>,
+    nodes: Query<Entity, With<GraphNode>>,
+) {
+    if *working_space == color.working_space {
+        return;
+    }
+    *working_space = color.working_space;
+    for node in nodes.iter() {
+        commands.entity(node).insert(NodeModified);
+    }
+}
+
+/// Apply the view transform to the preview cameras.
+pub(crate) fn apply_view_transform(
+    color: Res<ColorManagement>,
+    mut cameras: Query<&mut Tonemapping, With<Camera3d>>,
+) {
+    let tonemapping = color.view_transform.tonemapping();
+    for mut camera in cameras.iter_mut() {
+        if *camera != tonemapping {
+            *camera = tonemapping;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::ShaderLanguage;
+
+    fn fragment_source(working_space: WorkingSpace, language: ShaderLanguage) -> String {
+        let mut assembly = ShaderAssembly::new("test".to_string());
+        let value = working_space.convert_output(
+            &mut assembly,
+            Expr::ConstColor(LinearRgba::new(0.5, 0.5, 0.5, 1.0)),
+        );
+        assembly.set_fragment_value(Arc::new(value));
+        assembly.generate(language.backend()).unwrap()
+    }
+
+    #[test]
+    fn test_linear_is_unchanged() {
+        let source = fragment_source(WorkingSpace::Linear, ShaderLanguage::Wgsl);
+        assert!(!source.contains(WORKING_OUTPUT));
+        assert!(!source.contains("pow("));
+    }
+
+    #[test]
+    fn test_srgb_decodes_output() {
+        let source = fragment_source(WorkingSpace::Srgb, ShaderLanguage::Wgsl);
+        assert!(source.contains("let working_output ="));
+        assert!(source.contains("pow("));
+        // The printer may break member accesses across lines.
+        let source: String = source.split_whitespace().collect();
+        assert!(source.contains("working_output.a)"));
+    }
+
+    #[test]
+    fn test_srgb_decode_uses_backend_names() {
+        let source = fragment_source(WorkingSpace::Srgb, ShaderLanguage::Hlsl);
+        assert!(source.contains("lerp("));
+        assert!(source.contains("mad("));
+        assert!(!source.contains("vec3f"));
+    }
+
+    #[test]
+    fn test_working_space_from_name() {
+        assert_eq!(WorkingSpace::from_name("srgb"), Some(WorkingSpace::Srgb));
+        assert_eq!(
+            WorkingSpace::from_name("LINEAR"),
+            Some(WorkingSpace::Linear)
+        );
+        assert_eq!(WorkingSpace::from_name("aces"), None);
+    }
+}
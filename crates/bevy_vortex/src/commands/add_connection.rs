@@ -3,19 +3,50 @@ use bevy::{
     prelude::*,
 };
 
-use crate::{commands::mark_modified::MarkModifiedCmd, graph::*};
+use crate::{
+    commands::{insert_conversion::InsertConversionCmd, mark_modified::MarkModifiedCmd},
+    conversion::ConversionRegistry,
+    graph::*,
+};
 
 pub(crate) struct AddConnectionCmd {
     /// Entity for the input terminal.
     pub(crate) input: Entity,
     /// Entity for the output terminal.
     pub(crate) output: Entity,
-    // If set, the entity of the connection to replace.
+    // If set, the entity of the connection to replace. If the input terminal was already
+    // connected, the existing connection is moved to the replaced connection's input, so that
+    // the two connections are swapped.
     pub(crate) replace: Option<Entity>,
 }
 
 impl Command for AddConnectionCmd {
     fn apply(self, world: &mut World) {
+        // If the terminals have different types, and there is a registered conversion between
+        // them, connect them through a conversion node instead.
+        if self.replace.is_none() {
+            let output_type = world.get::<Terminal>(self.output).map(|t| t.data_type);
+            let input_type = world.get::<Terminal>(self.input).map(|t| t.data_type);
+            if let (Some(output_type), Some(input_type), Some(conversions)) = (
+                output_type,
+                input_type,
+                world.get_resource::<ConversionRegistry>(),
+            ) {
+                if let Some(factory) = conversions.get(output_type, input_type) {
+                    InsertConversionCmd {
+                        output: self.output,
+                        input: self.input,
+                        operator: factory(),
+                    }
+                    .apply(world);
+                    return;
+                }
+            }
+        }
+
+        let replaced = self
+            .replace
+            .and_then(|replace| world.get::<Connection>(replace).copied());
         let mut st: SystemState<(ResMut<GraphResource>, Query<&mut Terminal>)> =
             SystemState::new(world);
         let (_, mut terminals) = st.get_mut(world);
@@ -47,16 +78,44 @@ impl Command for AddConnectionCmd {
             connections_to_remove.insert(replace);
         }
 
+        // When the sink end of a connection is dragged onto an occupied input, reconnect the
+        // displaced connection to the input the sink end was dragged away from.
+        let swapped: Vec<Connection> = match replaced.map(|replaced| replaced.input) {
+            Some(old_input) if old_input.terminal_id != self.input => connections_to_remove
+                .iter()
+                .filter(|conn_id| Some(**conn_id) != self.replace)
+                .filter_map(|conn_id| world.get::<Connection>(*conn_id).copied())
+                // Don't connect a node to itself.
+                .filter(|conn| conn.output.node_id != old_input.node_id)
+                .map(|conn| Connection {
+                    output: conn.output,
+                    input: old_input,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
         let mut action = UndoAction::new("Add Connection");
+        for conn_id in connections_to_remove.iter() {
+            if let Some(conn) = world.get::<Connection>(*conn_id) {
+                action.mutations.push(UndoMutation::RemoveConnection(*conn));
+            }
+        }
         action
             .mutations
             .push(UndoMutation::AddConnection(connection));
         let id = world.spawn(connection).id();
+        let mut swapped_ids = Vec::with_capacity(swapped.len());
+        for conn in swapped.iter() {
+            action.mutations.push(UndoMutation::AddConnection(*conn));
+            swapped_ids.push(world.spawn(*conn).id());
+        }
         let (mut graph, _) = st.get_mut(world);
         for conn_id in connections_to_remove.iter() {
             graph.0.connections.remove(conn_id);
         }
         graph.0.connections.insert(id);
+        graph.0.connections.extend(swapped_ids.iter().copied());
         graph.0.add_undo_action(action);
 
         // Insert the new connection.
@@ -68,6 +127,20 @@ impl Command for AddConnectionCmd {
         let mut output_terminal = terminals.get_mut(self.output).unwrap();
         output_terminal.connections.insert(id);
 
+        // Insert the swapped connections.
+        for (conn, conn_id) in swapped.iter().zip(swapped_ids.iter()) {
+            for terminal in [conn.output.terminal_id, conn.input.terminal_id] {
+                if let Ok(mut terminal) = terminals.get_mut(terminal) {
+                    terminal.connections.insert(*conn_id);
+                }
+            }
+        }
+        for conn in swapped.iter() {
+            world.commands().add(MarkModifiedCmd {
+                start: conn.input.node_id,
+            });
+        }
+
         // Mark input node as modified.
         world.commands().add(MarkModifiedCmd { start: input_node });
 
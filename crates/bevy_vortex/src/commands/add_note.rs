@@ -0,0 +1,29 @@
+use bevy::{ecs::world::Command, prelude::*};
+use bevy_quill_obsidian_graph::GraphViewport;
+
+use crate::graph::*;
+
+/// Add a sticky note in the middle of the visible part of the graph, and select it.
+pub(crate) struct AddNoteCmd;
+
+impl Command for AddNoteCmd {
+    fn apply(self, world: &mut World) {
+        let mut query = world.query_filtered::<Entity, With<NodeSelected>>();
+        let selected: Vec<Entity> = query.iter(world).collect();
+        for entity in selected {
+            world.entity_mut(entity).remove::<NodeSelected>();
+        }
+
+        let mut note = GraphNote::default();
+        let mut viewports = world.query::<&GraphViewport>();
+        if let Some(viewport) = viewports.iter(world).find(|v| !v.0.is_empty()) {
+            note.position = (viewport.0.center() - note.size * 0.5).as_ivec2();
+        }
+        let note_id = world.spawn((note, NodeSelected)).id();
+        world
+            .resource_mut::<GraphResource>()
+            .0
+            .notes
+            .insert(note_id);
+    }
+}
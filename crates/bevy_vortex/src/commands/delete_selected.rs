@@ -37,16 +37,28 @@ impl Command for DeleteSelectedCmd {
             })
             .collect::<HashSet<_>>();
 
-        // Despawn old nodes
+        // Despawn old connections, then old nodes
         world.resource_scope(|world, mut graph: Mut<GraphResource>| {
+            for conn_id in connections_to_remove.drain() {
+                graph.0.remove_connection(world, conn_id, &mut action);
+            }
             for (_node_entity, node_index) in selected_nodes.iter() {
                 graph.0.delete_node(world, *node_index, &mut action);
             }
+            if !action.mutations.is_empty() {
+                graph.0.add_undo_action(action);
+            }
         });
 
-        // Despawn old connections
-        for conn_id in connections_to_remove.drain() {
-            world.entity_mut(conn_id).despawn();
+        // Notes aren't part of the undo history, so they are simply despawned.
+        let mut notes = world.query_filtered::<Entity, (With<GraphNote>, With<NodeSelected>)>();
+        let selected_notes: Vec<Entity> = notes.iter(world).collect();
+        let mut graph = world.resource_mut::<GraphResource>();
+        for note in selected_notes.iter() {
+            graph.0.notes.remove(note);
+        }
+        for note in selected_notes {
+            world.entity_mut(note).despawn();
         }
     }
 }
@@ -0,0 +1,185 @@
+use bevy::{ecs::world::Command, prelude::*};
+
+use crate::{commands::mark_modified::MarkModifiedCmd, graph::*, operator::Operator};
+
+/// Connect an output terminal to an input terminal through a new conversion node.
+pub(crate) struct InsertConversionCmd {
+    /// Entity for the output terminal.
+    pub(crate) output: Entity,
+    /// Entity for the input terminal.
+    pub(crate) input: Entity,
+    /// The conversion operator.
+    pub(crate) operator: Box<dyn Operator>,
+}
+
+impl Command for InsertConversionCmd {
+    fn apply(self, world: &mut World) {
+        let (Some(output_terminal), Some(input_terminal)) = (
+            world.get::<Terminal>(self.output),
+            world.get::<Terminal>(self.input),
+        ) else {
+            return;
+        };
+        let output_id = OutputTerminalId {
+            node_id: output_terminal.node_id,
+            terminal_name: output_terminal.name,
+            terminal_id: self.output,
+        };
+        let input_id = InputTerminalId {
+            node_id: input_terminal.node_id,
+            terminal_name: input_terminal.name,
+            terminal_id: self.input,
+        };
+        // Any previous connection to the input is replaced.
+        let replaced: Vec<Entity> = input_terminal.connections.iter().copied().collect();
+        let (Some(output_node), Some(input_node)) = (
+            world.get::<GraphNode>(output_id.node_id),
+            world.get::<GraphNode>(input_id.node_id),
+        ) else {
+            return;
+        };
+        // Place the conversion node between the two nodes.
+        let position = IVec2::new(
+            (output_node.position.x + output_node.size.x + input_node.position.x) / 2 - 40,
+            (output_node.position.y + input_node.position.y) / 2,
+        );
+
+        // The node and both of its connections are recorded in a single action, so that they
+        // are undone together.
+        let mut action = UndoAction::new("Add Conversion");
+        world.resource_scope(|world, mut graph: Mut<GraphResource>| {
+            let id =
+                graph
+                    .0
+                    .create_node(&mut world.commands(), self.operator, position, &mut action);
+            let node_id = graph.0.nodes[&id];
+
+            // Spawn the node and its terminals. The conversion node is not selected, since it
+            // was not added by the user.
+            world.flush();
+            world.entity_mut(node_id).remove::<NodeSelected>();
+
+            let node = world.get::<GraphNode>(node_id).unwrap();
+            let (Some(&(node_input_name, node_input)), Some(&(node_output_name, node_output))) =
+                (node.inputs.first(), node.outputs.first())
+            else {
+                graph.0.add_undo_action(action);
+                return;
+            };
+            for conn_id in replaced {
+                graph.0.remove_connection(world, conn_id, &mut action);
+            }
+            let connections = [
+                Connection {
+                    output: output_id,
+                    input: InputTerminalId {
+                        node_id,
+                        terminal_name: node_input_name,
+                        terminal_id: node_input,
+                    },
+                },
+                Connection {
+                    output: OutputTerminalId {
+                        node_id,
+                        terminal_name: node_output_name,
+                        terminal_id: node_output,
+                    },
+                    input: input_id,
+                },
+            ];
+            for connection in connections {
+                let id = graph.0.add_connection(world, connection, &mut action);
+                for terminal in [connection.output.terminal_id, connection.input.terminal_id] {
+                    if let Some(mut terminal) = world.get_mut::<Terminal>(terminal) {
+                        terminal.connections.insert(id);
+                    }
+                }
+            }
+            graph.0.add_undo_action(action);
+        });
+
+        world.commands().add(MarkModifiedCmd {
+            start: input_id.node_id,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bevy::{ecs::world::Command, prelude::*};
+
+    use super::InsertConversionCmd;
+    use crate::{
+        graph::{testing::*, GraphNode, GraphResource},
+        operator::Operator,
+        ops::{ColorToVector, Mix},
+    };
+
+    fn mix() -> Box<dyn Operator> {
+        Box::<Mix>::default()
+    }
+
+    fn insert_conversion(world: &mut World, from: Entity, to: Entity, input: &'static str) {
+        let output = world.get::<GraphNode>(from).unwrap().outputs[0].1;
+        let input = world
+            .get::<GraphNode>(to)
+            .unwrap()
+            .get_input_terminal(input)
+            .unwrap();
+        InsertConversionCmd {
+            output,
+            input,
+            operator: Box::<ColorToVector>::default(),
+        }
+        .apply(world);
+        world.flush();
+    }
+
+    #[test]
+    fn test_insert_conversion() {
+        let mut world = graph_world();
+        let a = add_node(&mut world, mix());
+        let b = add_node(&mut world, mix());
+        insert_conversion(&mut world, a, b, "input_a");
+
+        let graph = &world.resource::<GraphResource>().0;
+        assert_eq!(graph.iter_nodes().len(), 3);
+        let conversion = *graph
+            .iter_nodes()
+            .map(|(_, entity)| entity)
+            .find(|entity| **entity != a && **entity != b)
+            .unwrap();
+        assert_eq!(
+            edges(&world),
+            HashSet::from([(a, conversion, "input"), (conversion, b, "input_a")])
+        );
+    }
+
+    #[test]
+    fn test_insert_conversion_undo() {
+        let mut world = graph_world();
+        let a = add_node(&mut world, mix());
+        let b = add_node(&mut world, mix());
+        let c = add_node(&mut world, mix());
+        connect(&mut world, c, b, "input_a");
+        let expected = HashSet::from([(c, b, "input_a")]);
+
+        // The conversion replaces the existing connection to the input.
+        insert_conversion(&mut world, a, b, "input_a");
+        assert_eq!(edges(&world).len(), 2);
+        assert!(!edges(&world).contains(&(c, b, "input_a")));
+
+        // A single undo removes the conversion node and both of its connections, and restores
+        // the replaced connection.
+        assert!(undo(&mut world));
+        assert_eq!(edges(&world), expected);
+        let graph = &world.resource::<GraphResource>().0;
+        assert_eq!(graph.iter_nodes().len(), 3);
+
+        // The next undo reverts the connection which was made before the conversion.
+        assert!(undo(&mut world));
+        assert!(edges(&world).is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::{ecs::world::Command, prelude::*};
+
+use crate::{
+    animation::Timeline,
+    graph::*,
+    preset::{load_operator, NodePreset, PresetLibrary},
+};
+
+/// Add the nodes and connections of a preset to the graph, as a single undoable action. The
+/// preset's notes are added as well, but aren't part of the undo action.
+pub(crate) struct InstantiatePresetCmd {
+    /// Index of the preset in the [`PresetLibrary`].
+    pub(crate) preset: usize,
+    /// Position of the top-left corner of the preset.
+    pub(crate) position: IVec2,
+}
+
+impl Command for InstantiatePresetCmd {
+    fn apply(self, world: &mut World) {
+        let Some(preset) = world
+            .resource::<PresetLibrary>()
+            .presets
+            .get(self.preset)
+            .cloned()
+        else {
+            return;
+        };
+        instantiate_preset(world, &preset, self.position);
+    }
+}
+
+/// Add the nodes, connections, notes and animation tracks of a preset to the graph, with the
+/// top-left corner of the preset at `position`.
+pub(crate) fn instantiate_preset(world: &mut World, preset: &NodePreset, position: IVec2) {
+    // The new nodes replace the current selection.
+    let mut query = world.query_filtered::<Entity, With<NodeSelected>>();
+    let selected: Vec<Entity> = query.iter(world).collect();
+    for node_id in selected {
+        world.entity_mut(node_id).remove::<NodeSelected>();
+    }
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let mut action = UndoAction::new("Add Preset");
+    let mut node_ids: Vec<Option<Entity>> = Vec::with_capacity(preset.nodes.len());
+    world.resource_scope(|world, mut graph: Mut<GraphResource>| {
+        let mut commands = world.commands();
+        for node in preset.nodes.iter() {
+            let Some(operator) = load_operator(&registry, &node.operator) else {
+                warn!("Preset {} contains an unknown operator", preset.name);
+                node_ids.push(None);
+                continue;
+            };
+            let position = position + IVec2::new(node.offset.0, node.offset.1);
+            let id = graph
+                .0
+                .create_node(&mut commands, operator, position, &mut action);
+            node_ids.push(graph.0.nodes.get(&id).copied());
+        }
+    });
+    // Spawn the nodes and their terminals.
+    world.flush();
+
+    world.resource_scope(|world, mut timeline: Mut<Timeline>| {
+        timeline.restore_tracks(&preset.tracks, &node_ids, world);
+    });
+
+    let mut connection_ids = Vec::with_capacity(preset.connections.len());
+    for conn in preset.connections.iter() {
+        let (Some(Some(output_node)), Some(Some(input_node))) =
+            (node_ids.get(conn.output.0), node_ids.get(conn.input.0))
+        else {
+            continue;
+        };
+        let output = world
+            .get::<GraphNode>(*output_node)
+            .and_then(|node| node.outputs.iter().find(|t| t.0 == conn.output.1))
+            .copied();
+        let input = world
+            .get::<GraphNode>(*input_node)
+            .and_then(|node| node.inputs.iter().find(|t| t.0 == conn.input.1))
+            .copied();
+        let (Some(output), Some(input)) = (output, input) else {
+            continue;
+        };
+        let connection = Connection {
+            output: OutputTerminalId {
+                node_id: *output_node,
+                terminal_name: output.0,
+                terminal_id: output.1,
+            },
+            input: InputTerminalId {
+                node_id: *input_node,
+                terminal_name: input.0,
+                terminal_id: input.1,
+            },
+        };
+        action
+            .mutations
+            .push(UndoMutation::AddConnection(connection));
+        let id = world.spawn(connection).id();
+        for terminal_id in [output.1, input.1] {
+            if let Some(mut terminal) = world.get_mut::<Terminal>(terminal_id) {
+                terminal.connections.insert(id);
+            }
+        }
+        connection_ids.push(id);
+    }
+
+    let mut note_ids = Vec::with_capacity(preset.notes.len());
+    for note in preset.notes.iter() {
+        let note = GraphNote {
+            position: position + IVec2::new(note.offset.0, note.offset.1),
+            size: Vec2::new(note.size.0, note.size.1),
+            color: Srgba::new(note.color.0, note.color.1, note.color.2, 1.0),
+            text: note.text.clone(),
+        };
+        note_ids.push(world.spawn((note, NodeSelected)).id());
+    }
+
+    let mut graph = world.resource_mut::<GraphResource>();
+    graph.0.connections.extend(connection_ids);
+    graph.0.notes.extend(note_ids);
+    graph.0.add_undo_action(action);
+}
@@ -0,0 +1,38 @@
+use bevy::{
+    ecs::world::Command,
+    prelude::*,
+    reflect::{ReflectRef, TypeInfo},
+};
+
+use crate::{animation::Timeline, graph::*};
+
+/// Add a keyframe at the current time for every numeric parameter of the selected nodes.
+pub(crate) struct KeyframeSelectedCmd;
+
+impl Command for KeyframeSelectedCmd {
+    fn apply(self, world: &mut World) {
+        let mut query = world.query_filtered::<(Entity, &GraphNode), With<NodeSelected>>();
+        let mut keys: Vec<(Entity, &'static str, f32)> = Vec::new();
+        for (entity, node) in query.iter(world) {
+            let reflect = node.operator_reflect();
+            let (Some(TypeInfo::Struct(st_info)), ReflectRef::Struct(st)) =
+                (reflect.get_represented_type_info(), reflect.reflect_ref())
+            else {
+                continue;
+            };
+            for field in st_info.iter() {
+                if node.get_output_terminal(field.name()).is_some() {
+                    continue;
+                }
+                if let Some(value) = st.field(field.name()).and_then(|f| f.downcast_ref::<f32>()) {
+                    keys.push((entity, field.name(), *value));
+                }
+            }
+        }
+
+        let mut timeline = world.resource_mut::<Timeline>();
+        for (node, field, value) in keys {
+            timeline.set_key(node, field, value);
+        }
+    }
+}
@@ -1,7 +1,26 @@
 mod add_connection;
+mod add_note;
 mod delete_selected;
+mod insert_conversion;
+mod instantiate_preset;
+mod keyframe_selected;
 mod mark_modified;
+mod save_preset;
+mod set_seed;
+mod splice_node;
+mod toggle_bypass;
+mod toggle_review;
+mod undo;
 
 pub(crate) use add_connection::AddConnectionCmd;
+pub(crate) use add_note::AddNoteCmd;
 pub(crate) use delete_selected::DeleteSelectedCmd;
+pub(crate) use instantiate_preset::{instantiate_preset, InstantiatePresetCmd};
+pub(crate) use keyframe_selected::KeyframeSelectedCmd;
 pub(crate) use mark_modified::MarkModifiedCmd;
+pub(crate) use save_preset::{capture_nodes, SavePresetCmd};
+pub(crate) use set_seed::SetSeedCmd;
+pub(crate) use splice_node::SpliceNodeCmd;
+pub(crate) use toggle_bypass::ToggleBypassCmd;
+pub(crate) use toggle_review::ToggleReviewCmd;
+pub(crate) use undo::{RedoCmd, UndoCmd};
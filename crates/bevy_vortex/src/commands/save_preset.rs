@@ -0,0 +1,129 @@
+use bevy::{
+    ecs::{query::QueryFilter, world::Command},
+    prelude::*,
+    utils::HashMap,
+};
+
+use crate::{
+    animation::Timeline,
+    gen::NodeOutput,
+    graph::*,
+    preset::{save_operator, NodePreset, PresetConnection, PresetLibrary, PresetNode, PresetNote},
+    preview::capture_thumbnail,
+};
+
+/// Save the selected nodes and notes, and the connections between the nodes, as a new preset.
+pub(crate) struct SavePresetCmd;
+
+impl Command for SavePresetCmd {
+    fn apply(self, world: &mut World) {
+        let Some((_, mut preset)) = capture_nodes::<With<NodeSelected>>(world) else {
+            warn!("No nodes selected");
+            return;
+        };
+        // The thumbnail shows the output of the node which the preset is named after.
+        let mut query = world.query_filtered::<(&GraphNode, &NodeOutput), With<NodeSelected>>();
+        let shader = query
+            .iter(world)
+            .max_by_key(|(node, _)| (node.position.x, node.position.y))
+            .map(|(_, output)| output.shader.clone());
+        if let Some(shader) = shader {
+            preset.thumbnail = Some(capture_thumbnail(world, shader));
+        }
+
+        let mut library = world.resource_mut::<PresetLibrary>();
+        preset.name = library.unique_name(&preset.name);
+        let name = preset.name.clone();
+        match library.save(preset) {
+            Ok(()) => info!("Saved preset {}", name),
+            Err(err) => warn!("Failed to save preset {}: {}", name, err),
+        }
+    }
+}
+
+/// Capture the nodes and notes which match the filter `F`, and the connections between the
+/// nodes, as a preset named after its output node. Returns the position of the top-left
+/// corner of the preset along with it, or `None` if nothing matches.
+pub(crate) fn capture_nodes<F: QueryFilter>(world: &mut World) -> Option<(IVec2, NodePreset)> {
+    let mut query = world.query_filtered::<(Entity, &GraphNode), F>();
+    let mut note_query = world.query_filtered::<&GraphNote, F>();
+    let mut selected: Vec<(Entity, &GraphNode)> = query.iter(world).collect();
+    let selected_notes: Vec<&GraphNote> = note_query.iter(world).collect();
+    if selected.is_empty() && selected_notes.is_empty() {
+        return None;
+    }
+    selected.sort_by_key(|(_, node)| (node.position.x, node.position.y));
+    let origin = selected
+        .iter()
+        .map(|(_, node)| node.position)
+        .chain(selected_notes.iter().map(|note| note.position))
+        .reduce(IVec2::min)
+        .unwrap();
+
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut nodes = Vec::with_capacity(selected.len());
+    let mut indices = HashMap::<Entity, usize>::default();
+    for (entity, node) in selected.iter() {
+        let Some(operator) = save_operator(&registry, node.operator_reflect()) else {
+            warn!("Failed to serialize {}", node.title());
+            return None;
+        };
+        let offset = node.position - origin;
+        indices.insert(*entity, nodes.len());
+        nodes.push(PresetNode {
+            offset: (offset.x, offset.y),
+            operator,
+        });
+    }
+
+    let connections = world
+        .resource::<GraphResource>()
+        .0
+        .iter_connections()
+        .filter_map(|id| world.get::<Connection>(*id))
+        .filter_map(|conn| {
+            Some(PresetConnection {
+                output: (
+                    *indices.get(&conn.output.node_id)?,
+                    conn.output.terminal_name.to_string(),
+                ),
+                input: (
+                    *indices.get(&conn.input.node_id)?,
+                    conn.input.terminal_name.to_string(),
+                ),
+            })
+        })
+        .collect();
+
+    let notes = selected_notes
+        .iter()
+        .map(|note| {
+            let offset = note.position - origin;
+            PresetNote {
+                offset: (offset.x, offset.y),
+                size: (note.size.x, note.size.y),
+                color: (note.color.red, note.color.green, note.color.blue),
+                text: note.text.clone(),
+            }
+        })
+        .collect();
+
+    let tracks = world.resource::<Timeline>().save_tracks(&indices);
+
+    // Name the preset after the right-most node, which is usually the output of the group.
+    let title = selected
+        .last()
+        .map_or("Notes", |(_, node)| node.title())
+        .to_string();
+    Some((
+        origin,
+        NodePreset {
+            name: title,
+            nodes,
+            connections,
+            notes,
+            tracks,
+            thumbnail: None,
+        },
+    ))
+}
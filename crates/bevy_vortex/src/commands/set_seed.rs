@@ -0,0 +1,22 @@
+use bevy::{ecs::world::Command, prelude::*};
+
+use crate::graph::*;
+
+/// Set the random seed of the graph, and rebuild the shaders of all nodes.
+pub(crate) struct SetSeedCmd {
+    pub(crate) seed: u32,
+}
+
+impl Command for SetSeedCmd {
+    fn apply(self, world: &mut World) {
+        let mut graph = world.resource_mut::<GraphResource>();
+        if graph.0.seed == self.seed {
+            return;
+        }
+        graph.0.seed = self.seed;
+        let nodes: Vec<Entity> = graph.0.iter_nodes().map(|(_, entity)| *entity).collect();
+        for node in nodes {
+            world.entity_mut(node).insert(NodeModified);
+        }
+    }
+}
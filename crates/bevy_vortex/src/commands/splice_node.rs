@@ -0,0 +1,207 @@
+use bevy::{ecs::world::Command, prelude::*};
+
+use crate::{commands::mark_modified::MarkModifiedCmd, graph::*};
+
+/// Insert a node into an existing connection: the connection is replaced by one from its
+/// output to an input of the node, and one from an output of the node to its input.
+pub(crate) struct SpliceNodeCmd {
+    /// Entity for the node to insert.
+    pub(crate) node: Entity,
+    /// Entity for the connection to split.
+    pub(crate) connection: Entity,
+}
+
+impl Command for SpliceNodeCmd {
+    fn apply(self, world: &mut World) {
+        let Some(split) = world.get::<Connection>(self.connection).copied() else {
+            return;
+        };
+        if split.output.node_id == self.node || split.input.node_id == self.node {
+            return;
+        }
+        let Some(node) = world.get::<GraphNode>(self.node) else {
+            return;
+        };
+        let data_type = |terminal: Entity| world.get::<Terminal>(terminal).map(|t| t.data_type);
+        let src_type = data_type(split.output.terminal_id);
+        let dst_type = data_type(split.input.terminal_id);
+
+        // Prefer terminals whose data type matches the connection, and only use inputs which
+        // are not already connected.
+        let free_inputs: Vec<(&'static str, Entity)> = node
+            .inputs
+            .iter()
+            .filter(|(_, id)| {
+                world
+                    .get::<Terminal>(*id)
+                    .is_some_and(|t| !t.is_connected())
+            })
+            .copied()
+            .collect();
+        let Some(&(input_name, input)) = free_inputs
+            .iter()
+            .find(|(_, id)| data_type(*id) == src_type)
+            .or(free_inputs.first())
+        else {
+            return;
+        };
+        let Some(&(output_name, output)) = node
+            .outputs
+            .iter()
+            .find(|(_, id)| data_type(*id) == dst_type)
+            .or(node.outputs.first())
+        else {
+            return;
+        };
+
+        // Don't splice the node in if doing so would create a loop, i.e. if the node is already
+        // upstream of the connection's source or downstream of its sink.
+        if !can_connect(world, split.output.terminal_id, input)
+            || !can_connect(world, output, split.input.terminal_id)
+        {
+            return;
+        }
+
+        let connections = [
+            Connection {
+                output: split.output,
+                input: InputTerminalId {
+                    node_id: self.node,
+                    terminal_name: input_name,
+                    terminal_id: input,
+                },
+            },
+            Connection {
+                output: OutputTerminalId {
+                    node_id: self.node,
+                    terminal_name: output_name,
+                    terminal_id: output,
+                },
+                input: split.input,
+            },
+        ];
+
+        let mut action = UndoAction::new("Insert Node");
+        world.resource_scope(|world, mut graph: Mut<GraphResource>| {
+            graph
+                .0
+                .remove_connection(world, self.connection, &mut action);
+            for connection in connections {
+                let id = graph.0.add_connection(world, connection, &mut action);
+                for terminal in [connection.output.terminal_id, connection.input.terminal_id] {
+                    if let Some(mut terminal) = world.get_mut::<Terminal>(terminal) {
+                        terminal.connections.insert(id);
+                    }
+                }
+            }
+            graph.0.add_undo_action(action);
+        });
+
+        world.commands().add(MarkModifiedCmd { start: self.node });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bevy::{ecs::world::Command, prelude::*};
+
+    use super::SpliceNodeCmd;
+    use crate::{
+        graph::{testing::*, GraphNode, Terminal},
+        operator::Operator,
+        ops::Mix,
+    };
+
+    fn mix() -> Box<dyn Operator> {
+        Box::<Mix>::default()
+    }
+
+    fn input_connection(world: &World, node: Entity, input: &'static str) -> Entity {
+        let terminal = world
+            .get::<GraphNode>(node)
+            .unwrap()
+            .get_input_terminal(input)
+            .unwrap();
+        let terminal = world.get::<Terminal>(terminal).unwrap();
+        *terminal.connections.iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut world = graph_world();
+        let a = add_node(&mut world, mix());
+        let b = add_node(&mut world, mix());
+        let c = add_node(&mut world, mix());
+        connect(&mut world, a, c, "input_a");
+        let connection = input_connection(&world, c, "input_a");
+
+        SpliceNodeCmd {
+            node: b,
+            connection,
+        }
+        .apply(&mut world);
+        world.flush();
+        assert_eq!(
+            edges(&world),
+            HashSet::from([(a, b, "input_a"), (b, c, "input_a")])
+        );
+        assert!(world.get_entity(connection).is_none());
+    }
+
+    #[test]
+    fn test_splice_rejects_cycle() {
+        let mut world = graph_world();
+        let a = add_node(&mut world, mix());
+        let b = add_node(&mut world, mix());
+        let c = add_node(&mut world, mix());
+        connect(&mut world, a, b, "input_a");
+        connect(&mut world, b, c, "input_a");
+        let expected = HashSet::from([(a, b, "input_a"), (b, c, "input_a")]);
+
+        // `a` is upstream of the connection from `b` to `c`.
+        let connection = input_connection(&world, c, "input_a");
+        SpliceNodeCmd {
+            node: a,
+            connection,
+        }
+        .apply(&mut world);
+        world.flush();
+        assert_eq!(edges(&world), expected);
+
+        // `c` is downstream of the connection from `a` to `b`.
+        let connection = input_connection(&world, b, "input_a");
+        SpliceNodeCmd {
+            node: c,
+            connection,
+        }
+        .apply(&mut world);
+        world.flush();
+        assert_eq!(edges(&world), expected);
+    }
+
+    #[test]
+    fn test_splice_undo() {
+        let mut world = graph_world();
+        let a = add_node(&mut world, mix());
+        let b = add_node(&mut world, mix());
+        let c = add_node(&mut world, mix());
+        connect(&mut world, a, c, "input_a");
+        let connection = input_connection(&world, c, "input_a");
+        SpliceNodeCmd {
+            node: b,
+            connection,
+        }
+        .apply(&mut world);
+        world.flush();
+
+        assert!(undo(&mut world));
+        assert_eq!(edges(&world), HashSet::from([(a, c, "input_a")]));
+        assert!(redo(&mut world));
+        assert_eq!(
+            edges(&world),
+            HashSet::from([(a, b, "input_a"), (b, c, "input_a")])
+        );
+    }
+}
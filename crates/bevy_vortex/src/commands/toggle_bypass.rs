@@ -0,0 +1,25 @@
+use bevy::{ecs::world::Command, prelude::*};
+
+use crate::{commands::mark_modified::MarkModifiedCmd, graph::*};
+
+/// Toggle bypass on the selected nodes. If any of the selected nodes is not bypassed, then all
+/// of them are bypassed; otherwise bypass is turned off for all of them.
+pub(crate) struct ToggleBypassCmd;
+
+impl Command for ToggleBypassCmd {
+    fn apply(self, world: &mut World) {
+        let mut query = world.query_filtered::<(Entity, &GraphNode), With<NodeSelected>>();
+        let selected: Vec<(Entity, bool)> = query
+            .iter(world)
+            .map(|(entity, node)| (entity, node.bypass))
+            .collect();
+        let bypass = selected.iter().any(|(_, bypass)| !bypass);
+
+        for (node_id, _) in selected {
+            if let Some(mut node) = world.get_mut::<GraphNode>(node_id) {
+                node.bypass = bypass;
+            }
+            world.commands().add(MarkModifiedCmd { start: node_id });
+        }
+    }
+}
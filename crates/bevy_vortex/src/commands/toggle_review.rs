@@ -0,0 +1,35 @@
+use bevy::{ecs::world::Command, prelude::*};
+
+use crate::diff::{GraphDiff, GraphReview, GraphSnapshot};
+
+/// Start reviewing changes to the graph, or finish the current review. Starting a review takes a
+/// snapshot of the graph; finishing it logs a summary of the changes made since the snapshot.
+pub(crate) struct ToggleReviewCmd;
+
+impl Command for ToggleReviewCmd {
+    fn apply(self, world: &mut World) {
+        let current = GraphSnapshot::capture(world);
+        let mut review = world.resource_mut::<GraphReview>();
+        match review.baseline.take() {
+            Some(baseline) => {
+                let diff = GraphDiff::between(&baseline, &current);
+                if diff.is_empty() {
+                    info!("Review finished: no changes");
+                } else {
+                    info!(
+                        "Review finished: {} nodes added, {} removed, {} changed, {} moved; {} connections added, {} removed",
+                        diff.added_nodes.len(),
+                        diff.removed_nodes.len(),
+                        diff.changed_nodes.len(),
+                        diff.moved_nodes.len(),
+                        diff.added_connections.len(),
+                        diff.removed_connections.len(),
+                    );
+                }
+            }
+            None => {
+                review.baseline = Some(current);
+            }
+        }
+    }
+}
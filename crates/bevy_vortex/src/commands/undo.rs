@@ -0,0 +1,25 @@
+use bevy::{ecs::world::Command, prelude::*};
+
+use crate::graph::*;
+
+/// Undo the most recent change to the graph.
+pub(crate) struct UndoCmd;
+
+impl Command for UndoCmd {
+    fn apply(self, world: &mut World) {
+        world.resource_scope(|world, mut graph: Mut<GraphResource>| {
+            graph.0.undo(world);
+        });
+    }
+}
+
+/// Redo the most recently undone change to the graph.
+pub(crate) struct RedoCmd;
+
+impl Command for RedoCmd {
+    fn apply(self, world: &mut World) {
+        world.resource_scope(|world, mut graph: Mut<GraphResource>| {
+            graph.0.redo(world);
+        });
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{graph::ConnectionDataType, operator::Operator};
+
+/// Function which creates a conversion operator.
+pub type ConversionFactory = fn() -> Box<dyn Operator>;
+
+/// Resource which lists the operators that are inserted automatically when an output is
+/// connected to an input of a different type. The operator created for a conversion must have
+/// a single input of the source type, and a single output of the destination type.
+///
+/// Type combinations which are not registered are connected directly, and the value is cast
+/// to the input type when the shader is generated.
+#[derive(Resource, Default)]
+pub struct ConversionRegistry {
+    conversions: HashMap<(ConnectionDataType, ConnectionDataType), ConversionFactory>,
+}
+
+impl ConversionRegistry {
+    /// Register the operator used to convert from one terminal type to another.
+    pub fn register(
+        &mut self,
+        from: ConnectionDataType,
+        to: ConnectionDataType,
+        factory: ConversionFactory,
+    ) -> &mut Self {
+        self.conversions.insert((from, to), factory);
+        self
+    }
+
+    /// Look up the conversion between two terminal types.
+    pub fn get(
+        &self,
+        from: ConnectionDataType,
+        to: ConnectionDataType,
+    ) -> Option<ConversionFactory> {
+        if from == to {
+            return None;
+        }
+        self.conversions.get(&(from, to)).copied()
+    }
+}
@@ -0,0 +1,1000 @@
+use bevy::{
+    prelude::*,
+    reflect::{ReflectRef, TypeInfo, TypeRegistry},
+    ui,
+    utils::{HashMap, HashSet},
+};
+use bevy_mod_stylebuilder::*;
+use bevy_quill::*;
+use bevy_quill_obsidian::{
+    controls::{
+        Button, ButtonVariant, Checkbox, Dialog, DialogBody, DialogFooter, DialogHeader, ListView,
+    },
+    typography::text_strong,
+};
+
+use crate::{
+    animation::SavedTrack,
+    graph::{Connection, GraphNode, GraphNodeId, GraphResource},
+    preset::{load_operator, NodePreset, PresetConnection, PresetLibrary},
+};
+
+/// The state of a single node at the time a snapshot was taken.
+#[derive(Clone, PartialEq)]
+pub struct NodeSnapshot {
+    /// Type path of the node's operator.
+    pub operator: &'static str,
+    /// Short name of the node's operator, for display.
+    pub title: &'static str,
+    /// Position of the node.
+    pub position: IVec2,
+    /// Operator properties, formatted as text so that they can be compared.
+    pub properties: Vec<(&'static str, String)>,
+    /// Whether the node was bypassed.
+    pub bypass: bool,
+}
+
+impl NodeSnapshot {
+    fn capture(node: &GraphNode) -> Self {
+        Self::new(node.operator_reflect(), node.position, node.bypass)
+    }
+
+    fn new(reflect: &dyn Reflect, position: IVec2, bypass: bool) -> Self {
+        let mut snapshot = Self {
+            operator: "",
+            title: "",
+            position,
+            properties: Vec::new(),
+            bypass,
+        };
+        if let (Some(TypeInfo::Struct(st_info)), ReflectRef::Struct(st)) =
+            (reflect.get_represented_type_info(), reflect.reflect_ref())
+        {
+            snapshot.operator = st_info.type_path();
+            snapshot.title = st_info.type_path_table().short_path();
+            for field in st_info.iter() {
+                if let Some(value) = st.field(field.name()) {
+                    snapshot
+                        .properties
+                        .push((field.name(), format!("{:?}", value)));
+                }
+            }
+        }
+        snapshot
+    }
+
+    /// Names of the properties which differ between this and another snapshot of the node.
+    pub fn changed_properties(&self, other: &NodeSnapshot) -> Vec<&'static str> {
+        self.properties
+            .iter()
+            .filter(|(name, value)| {
+                !other
+                    .properties
+                    .iter()
+                    .any(|(other_name, other_value)| other_name == name && other_value == value)
+            })
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+/// A connection within a snapshot, identified by the nodes and terminal names at each end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConnectionSnapshot {
+    pub output: (GraphNodeId, &'static str),
+    pub input: (GraphNodeId, &'static str),
+}
+
+impl ConnectionSnapshot {
+    /// Identify a connection by the indices of the nodes at each end.
+    pub fn capture(world: &World, connection: &Connection) -> Option<Self> {
+        let output = world.get::<GraphNode>(connection.output.node_id)?.index;
+        let input = world.get::<GraphNode>(connection.input.node_id)?.index;
+        Some(Self {
+            output: (output, connection.output.terminal_name),
+            input: (input, connection.input.terminal_name),
+        })
+    }
+
+    /// Returns a copy of the connection with both ends renamed by `f`.
+    fn map_nodes(&self, f: impl Fn(GraphNodeId) -> Option<GraphNodeId>) -> Option<Self> {
+        Some(Self {
+            output: (f(self.output.0)?, self.output.1),
+            input: (f(self.input.0)?, self.input.1),
+        })
+    }
+}
+
+/// A copy of the structure of a graph, which can be compared against another graph.
+#[derive(Clone, Default)]
+pub struct GraphSnapshot {
+    pub nodes: HashMap<GraphNodeId, NodeSnapshot>,
+    pub connections: HashSet<ConnectionSnapshot>,
+}
+
+impl GraphSnapshot {
+    /// Take a snapshot of the current graph.
+    pub fn capture(world: &World) -> Self {
+        let graph = &world.resource::<GraphResource>().0;
+        let nodes = graph
+            .iter_nodes()
+            .filter_map(|(id, entity)| {
+                let node = world.get::<GraphNode>(*entity)?;
+                Some((*id, NodeSnapshot::capture(node)))
+            })
+            .collect();
+        let connections = graph
+            .iter_connections()
+            .filter_map(|entity| {
+                let connection = world.get::<Connection>(*entity)?;
+                ConnectionSnapshot::capture(world, connection)
+            })
+            .collect();
+        Self { nodes, connections }
+    }
+
+    /// Take a snapshot of a saved preset. Nodes are identified by their index in the preset,
+    /// and their positions are relative to the top-left corner of the preset. Nodes with an
+    /// unknown operator are left out.
+    pub fn from_preset(preset: &NodePreset, registry: &TypeRegistry) -> Self {
+        let operators: Vec<_> = preset
+            .nodes
+            .iter()
+            .map(|node| load_operator(registry, &node.operator))
+            .collect();
+        let nodes = preset
+            .nodes
+            .iter()
+            .zip(operators.iter())
+            .enumerate()
+            .filter_map(|(index, (node, operator))| {
+                let position = IVec2::new(node.offset.0, node.offset.1);
+                let snapshot = NodeSnapshot::new(operator.as_ref()?.as_reflect(), position, false);
+                Some((GraphNodeId(index), snapshot))
+            })
+            .collect();
+        // Terminal names are replaced by the names of the operator fields, which are static.
+        let field_name = |index: usize, name: &str| -> Option<&'static str> {
+            let operator = operators.get(index)?.as_ref()?;
+            match operator.as_reflect().get_represented_type_info()? {
+                TypeInfo::Struct(st_info) => st_info.field(name).map(|field| field.name()),
+                _ => None,
+            }
+        };
+        let connections = preset
+            .connections
+            .iter()
+            .filter_map(|conn| {
+                Some(ConnectionSnapshot {
+                    output: (
+                        GraphNodeId(conn.output.0),
+                        field_name(conn.output.0, &conn.output.1)?,
+                    ),
+                    input: (
+                        GraphNodeId(conn.input.0),
+                        field_name(conn.input.0, &conn.input.1)?,
+                    ),
+                })
+            })
+            .collect();
+        Self { nodes, connections }
+    }
+
+    /// Re-identify the nodes of this snapshot so that they match the corresponding nodes of
+    /// `base`. This is needed to compare presets, since their nodes are only identified by
+    /// order. Nodes are paired with nodes of `base` which have the same operator, preferring
+    /// identical properties, then the nearest position. Unpaired nodes are given ids which
+    /// `base` doesn't use. Returns the matched snapshot, along with the original id of each
+    /// node.
+    pub fn matched_to(
+        &self,
+        base: &GraphSnapshot,
+    ) -> (GraphSnapshot, HashMap<GraphNodeId, GraphNodeId>) {
+        let mut candidates: Vec<(bool, i32, GraphNodeId, GraphNodeId)> = Vec::new();
+        for (id, node) in self.nodes.iter() {
+            for (base_id, base_node) in base.nodes.iter() {
+                if base_node.operator == node.operator {
+                    candidates.push((
+                        base_node.properties != node.properties,
+                        (base_node.position - node.position).abs().element_sum(),
+                        *base_id,
+                        *id,
+                    ));
+                }
+            }
+        }
+        candidates.sort();
+
+        let mut mapping = HashMap::<GraphNodeId, GraphNodeId>::default();
+        let mut used = HashSet::<GraphNodeId>::default();
+        for (_, _, base_id, id) in candidates {
+            if !mapping.contains_key(&id) && used.insert(base_id) {
+                mapping.insert(id, base_id);
+            }
+        }
+        let next_id = base.nodes.keys().map(|id| id.0 + 1).max().unwrap_or(0);
+        let mut unmatched: Vec<GraphNodeId> = self
+            .nodes
+            .keys()
+            .filter(|id| !mapping.contains_key(*id))
+            .copied()
+            .collect();
+        unmatched.sort();
+        for (id, new_id) in unmatched.into_iter().zip(next_id..) {
+            mapping.insert(id, GraphNodeId(new_id));
+        }
+
+        let matched = GraphSnapshot {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(id, node)| (mapping[id], node.clone()))
+                .collect(),
+            connections: self
+                .connections
+                .iter()
+                .filter_map(|conn| conn.map_nodes(|id| mapping.get(&id).copied()))
+                .collect(),
+        };
+        let original = mapping.into_iter().map(|(id, new)| (new, id)).collect();
+        (matched, original)
+    }
+}
+
+/// How a node differs from its state in an earlier snapshot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeChange {
+    /// The node was added since the snapshot.
+    Added,
+    /// The node's properties or bypass state have changed.
+    Changed,
+    /// The node has been moved, but is otherwise unchanged.
+    Moved,
+}
+
+/// The structural differences between two snapshots of a graph.
+#[derive(Default)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<GraphNodeId>,
+    pub removed_nodes: Vec<GraphNodeId>,
+    pub changed_nodes: Vec<GraphNodeId>,
+    pub moved_nodes: Vec<GraphNodeId>,
+    pub added_connections: Vec<ConnectionSnapshot>,
+    pub removed_connections: Vec<ConnectionSnapshot>,
+}
+
+impl GraphDiff {
+    /// Compute the differences between an old and a new snapshot.
+    pub fn between(old: &GraphSnapshot, new: &GraphSnapshot) -> Self {
+        let mut diff = Self::default();
+        for (id, node) in new.nodes.iter() {
+            match node_change(old.nodes.get(id), node) {
+                Some(NodeChange::Added) => diff.added_nodes.push(*id),
+                Some(NodeChange::Changed) => diff.changed_nodes.push(*id),
+                Some(NodeChange::Moved) => diff.moved_nodes.push(*id),
+                None => {}
+            }
+        }
+        diff.removed_nodes = old
+            .nodes
+            .keys()
+            .filter(|id| !new.nodes.contains_key(*id))
+            .copied()
+            .collect();
+        diff.added_connections = new
+            .connections
+            .difference(&old.connections)
+            .copied()
+            .collect();
+        diff.removed_connections = old
+            .connections
+            .difference(&new.connections)
+            .copied()
+            .collect();
+        // Sort the changes, so that they are listed in a stable order.
+        diff.added_nodes.sort();
+        diff.removed_nodes.sort();
+        diff.changed_nodes.sort();
+        diff.moved_nodes.sort();
+        diff.added_connections.sort();
+        diff.removed_connections.sort();
+        diff
+    }
+
+    /// True if the snapshots are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.moved_nodes.is_empty()
+            && self.added_connections.is_empty()
+            && self.removed_connections.is_empty()
+    }
+
+    /// List the differences individually, so that they can be reviewed and merged one by one.
+    pub fn entries(&self) -> Vec<DiffEntry> {
+        let mut entries = Vec::new();
+        entries.extend(
+            self.removed_nodes
+                .iter()
+                .copied()
+                .map(DiffEntry::RemoveNode),
+        );
+        entries.extend(self.added_nodes.iter().copied().map(DiffEntry::AddNode));
+        entries.extend(
+            self.changed_nodes
+                .iter()
+                .copied()
+                .map(DiffEntry::ChangeNode),
+        );
+        entries.extend(self.moved_nodes.iter().copied().map(DiffEntry::MoveNode));
+        entries.extend(
+            self.removed_connections
+                .iter()
+                .copied()
+                .map(DiffEntry::RemoveConnection),
+        );
+        entries.extend(
+            self.added_connections
+                .iter()
+                .copied()
+                .map(DiffEntry::AddConnection),
+        );
+        entries
+    }
+}
+
+/// A single difference between two graphs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffEntry {
+    AddNode(GraphNodeId),
+    RemoveNode(GraphNodeId),
+    ChangeNode(GraphNodeId),
+    MoveNode(GraphNodeId),
+    AddConnection(ConnectionSnapshot),
+    RemoveConnection(ConnectionSnapshot),
+}
+
+fn node_change(old: Option<&NodeSnapshot>, new: &NodeSnapshot) -> Option<NodeChange> {
+    match old {
+        None => Some(NodeChange::Added),
+        Some(old) if old.properties != new.properties || old.bypass != new.bypass => {
+            Some(NodeChange::Changed)
+        }
+        Some(old) if old.position != new.position => Some(NodeChange::Moved),
+        Some(_) => None,
+    }
+}
+
+/// Resource which holds the baseline snapshot while reviewing changes to the graph. While a
+/// review is in progress, the graph view highlights nodes and connections which have been
+/// added or changed since the baseline was taken.
+#[derive(Resource, Default)]
+pub struct GraphReview {
+    pub(crate) baseline: Option<GraphSnapshot>,
+}
+
+impl GraphReview {
+    /// How the given node differs from the baseline, or `None` if it is unchanged or no
+    /// review is in progress.
+    pub fn node_change(&self, node: &GraphNode) -> Option<NodeChange> {
+        let baseline = self.baseline.as_ref()?;
+        node_change(
+            baseline.nodes.get(&node.index),
+            &NodeSnapshot::capture(node),
+        )
+    }
+
+    /// True if the connection has been added since the baseline.
+    pub fn is_connection_added(&self, connection: &ConnectionSnapshot) -> bool {
+        self.baseline
+            .as_ref()
+            .is_some_and(|baseline| !baseline.connections.contains(connection))
+    }
+}
+
+/// The differences between two saved presets.
+pub struct PresetDiff {
+    /// Snapshot of the preset the changes are compared against.
+    pub base: GraphSnapshot,
+    /// Snapshot of the changed preset, with nodes matched to those of `base`.
+    pub changed: GraphSnapshot,
+    /// The individual differences.
+    pub entries: Vec<DiffEntry>,
+    /// Index in the changed preset of each node of `changed`.
+    changed_indices: HashMap<GraphNodeId, usize>,
+}
+
+impl PresetDiff {
+    /// Compare two presets.
+    pub fn new(base: &NodePreset, changed: &NodePreset, registry: &TypeRegistry) -> Self {
+        let base = GraphSnapshot::from_preset(base, registry);
+        let (changed, original) = GraphSnapshot::from_preset(changed, registry).matched_to(&base);
+        let entries = GraphDiff::between(&base, &changed).entries();
+        Self {
+            base,
+            changed,
+            entries,
+            changed_indices: original
+                .into_iter()
+                .map(|(id, index)| (id, index.0))
+                .collect(),
+        }
+    }
+
+    /// Describe an entry as it appears in the base and changed presets, for displaying the
+    /// presets side by side. A side is empty if the entry does not exist there.
+    pub fn describe(&self, entry: &DiffEntry) -> (String, String) {
+        let title = |snapshot: &GraphSnapshot, id: &GraphNodeId| {
+            snapshot.nodes.get(id).map_or("?", |node| node.title)
+        };
+        let connection = |snapshot: &GraphSnapshot, conn: &ConnectionSnapshot| {
+            format!(
+                "{}.{} -> {}.{}",
+                title(snapshot, &conn.output.0),
+                conn.output.1,
+                title(snapshot, &conn.input.0),
+                conn.input.1
+            )
+        };
+        match entry {
+            DiffEntry::AddNode(id) => (String::new(), title(&self.changed, id).to_string()),
+            DiffEntry::RemoveNode(id) => (title(&self.base, id).to_string(), String::new()),
+            DiffEntry::ChangeNode(id) => {
+                let changed = match (self.base.nodes.get(id), self.changed.nodes.get(id)) {
+                    (Some(base), Some(changed)) => changed.changed_properties(base).join(", "),
+                    _ => String::new(),
+                };
+                (
+                    title(&self.base, id).to_string(),
+                    format!("{} ({})", title(&self.changed, id), changed),
+                )
+            }
+            DiffEntry::MoveNode(id) => {
+                let at = |snapshot: &GraphSnapshot| match snapshot.nodes.get(id) {
+                    Some(node) => {
+                        format!("{} at {}, {}", node.title, node.position.x, node.position.y)
+                    }
+                    None => String::new(),
+                };
+                (at(&self.base), at(&self.changed))
+            }
+            DiffEntry::AddConnection(conn) => (String::new(), connection(&self.changed, conn)),
+            DiffEntry::RemoveConnection(conn) => (connection(&self.base, conn), String::new()),
+        }
+    }
+
+    /// Build a new preset from `base`, with the entries for which `accepted` is true taken
+    /// from `changed`. The presets must be the ones that were compared.
+    pub fn merge(&self, base: &NodePreset, changed: &NodePreset, accepted: &[bool]) -> NodePreset {
+        let accepted: Vec<DiffEntry> = self
+            .entries
+            .iter()
+            .zip(accepted.iter())
+            .filter(|(_, accepted)| **accepted)
+            .map(|(entry, _)| *entry)
+            .collect();
+        let is_accepted = |entry: DiffEntry| accepted.contains(&entry);
+
+        let mut merged = NodePreset {
+            name: base.name.clone(),
+            nodes: Vec::new(),
+            connections: Vec::new(),
+            notes: base.notes.clone(),
+            tracks: Vec::new(),
+            thumbnail: None,
+        };
+        // Index of each node within the merged preset.
+        let mut indices = HashMap::<GraphNodeId, usize>::default();
+        let mut add_node = |merged: &mut NodePreset, id: GraphNodeId, from_changed: bool| {
+            let (preset, index) = if from_changed {
+                (changed, self.changed_indices[&id])
+            } else {
+                (base, id.0)
+            };
+            indices.insert(id, merged.nodes.len());
+            merged.nodes.push(preset.nodes[index].clone());
+            merged.tracks.extend(
+                preset
+                    .tracks
+                    .iter()
+                    .filter(|track| track.node == index)
+                    .map(|track| SavedTrack {
+                        node: merged.nodes.len() - 1,
+                        ..track.clone()
+                    }),
+            );
+        };
+        for index in 0..base.nodes.len() {
+            let id = GraphNodeId(index);
+            if !self.base.nodes.contains_key(&id) || is_accepted(DiffEntry::RemoveNode(id)) {
+                continue;
+            }
+            let from_changed =
+                is_accepted(DiffEntry::ChangeNode(id)) || is_accepted(DiffEntry::MoveNode(id));
+            add_node(&mut merged, id, from_changed);
+        }
+        for entry in accepted.iter() {
+            if let DiffEntry::AddNode(id) = entry {
+                add_node(&mut merged, *id, true);
+            }
+        }
+
+        let mut connections: Vec<ConnectionSnapshot> = self
+            .base
+            .connections
+            .iter()
+            .filter(|conn| !is_accepted(DiffEntry::RemoveConnection(**conn)))
+            .copied()
+            .collect();
+        connections.sort();
+        for entry in accepted.iter() {
+            if let DiffEntry::AddConnection(conn) = entry {
+                // An input can only have one connection.
+                connections.retain(|c| c.input != conn.input);
+                connections.push(*conn);
+            }
+        }
+        merged.connections = connections
+            .iter()
+            .filter_map(|conn| {
+                Some(PresetConnection {
+                    output: (*indices.get(&conn.output.0)?, conn.output.1.to_string()),
+                    input: (*indices.get(&conn.input.0)?, conn.input.1.to_string()),
+                })
+            })
+            .collect();
+        merged
+    }
+}
+
+fn style_diff_columns(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .gap(8);
+}
+
+fn style_diff_column(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .flex_grow(1.)
+        .flex_basis(0)
+        .gap(2);
+}
+
+fn style_diff_list(ss: &mut StyleBuilder) {
+    ss.min_height(60).max_height(240);
+}
+
+fn style_diff_row(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(8)
+        .padding(2);
+}
+
+fn style_diff_cell(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.)
+        .flex_basis(0)
+        .overflow(ui::OverflowAxis::Clip);
+}
+
+/// Dialog which shows the differences between two saved presets side by side. The selected
+/// changes can be merged into a copy of the base preset, which is saved as a new preset.
+#[derive(Clone, PartialEq)]
+pub(crate) struct PresetDiffDialog {
+    pub(crate) open: Mutable<bool>,
+}
+
+impl ViewTemplate for PresetDiffDialog {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let open = self.open;
+        let base = cx.create_mutable::<Option<usize>>(None);
+        let changed = cx.create_mutable::<Option<usize>>(None);
+        let accepted = cx.create_mutable::<Vec<bool>>(Vec::new());
+        let on_close = cx.create_callback(move |world: &mut World| {
+            open.set(world, false);
+        });
+        let on_merge = cx.create_callback(move |world: &mut World| {
+            let (Some(base_index), Some(changed_index)) = (base.get(world), changed.get(world))
+            else {
+                return;
+            };
+            let selection = accepted.get_clone(world);
+            let registry = world.resource::<AppTypeRegistry>().clone();
+            let mut library = world.resource_mut::<PresetLibrary>();
+            let (Some(base_preset), Some(changed_preset)) = (
+                library.presets.get(base_index).cloned(),
+                library.presets.get(changed_index).cloned(),
+            ) else {
+                return;
+            };
+            let diff = PresetDiff::new(&base_preset, &changed_preset, &registry.read());
+            let mut merged = diff.merge(&base_preset, &changed_preset, &selection);
+            merged.name = library.unique_name(&format!("{} merged", base_preset.name));
+            let name = merged.name.clone();
+            match library.save(merged) {
+                Ok(()) => info!("Saved merged preset {}", name),
+                Err(err) => warn!("Failed to save preset {}: {}", name, err),
+            }
+            // Saving the preset changes the order of the library.
+            base.set(world, None);
+            changed.set(world, None);
+            accepted.set_clone(world, Vec::new());
+            open.set(world, false);
+        });
+
+        let library = cx.use_resource::<PresetLibrary>();
+        let names: Vec<String> = library.presets.iter().map(|p| p.name.clone()).collect();
+        let selected = (base.get(cx), changed.get(cx));
+        let rows: Vec<DiffRow> = match selected {
+            (Some(b), Some(c)) if b != c && b < names.len() && c < names.len() => {
+                let registry = cx.use_resource::<AppTypeRegistry>().clone();
+                let diff =
+                    PresetDiff::new(&library.presets[b], &library.presets[c], &registry.read());
+                let checked = accepted.get_clone(cx);
+                diff.entries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| {
+                        let (left, right) = diff.describe(entry);
+                        DiffRow {
+                            index,
+                            left,
+                            right,
+                            checked: checked.get(index).copied().unwrap_or(false),
+                            accepted,
+                        }
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let can_merge = rows.iter().any(|row| row.checked);
+        let status = match selected {
+            (Some(b), Some(c)) if b != c => "No differences",
+            _ => "Select two presets to compare",
+        };
+
+        Dialog::new()
+            .width(ui::Val::Px(560.))
+            .open(open.get(cx))
+            .on_close(on_close)
+            .children((
+                DialogHeader::new().children("Compare Presets"),
+                DialogBody::new().children((
+                    Element::<NodeBundle>::new()
+                        .style(style_diff_columns)
+                        .children((
+                            PresetPicker {
+                                label: "Base",
+                                names: names.clone(),
+                                selected: base,
+                                accepted,
+                            },
+                            PresetPicker {
+                                label: "Changed",
+                                names,
+                                selected: changed,
+                                accepted,
+                            },
+                        )),
+                    ListView::new().style(style_diff_list).children(Cond::new(
+                        rows.is_empty(),
+                        status.to_string(),
+                        For::each(rows, |row| row.clone()),
+                    )),
+                )),
+                DialogFooter::new().children((
+                    Button::new().children("Cancel").on_click(on_close),
+                    Button::new()
+                        .children("Merge")
+                        .variant(ButtonVariant::Primary)
+                        .disabled(!can_merge)
+                        .on_click(on_merge),
+                )),
+            ))
+    }
+}
+
+/// Column of buttons which picks one of the saved presets.
+#[derive(Clone, PartialEq)]
+struct PresetPicker {
+    label: &'static str,
+    names: Vec<String>,
+    selected: Mutable<Option<usize>>,
+    /// The merge selection, which is cleared when a different preset is picked.
+    accepted: Mutable<Vec<bool>>,
+}
+
+impl ViewTemplate for PresetPicker {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let current = self.selected.get(cx);
+        let buttons: Vec<PresetPickerButton> = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| PresetPickerButton {
+                index,
+                name: name.clone(),
+                checked: current == Some(index),
+                selected: self.selected,
+                accepted: self.accepted,
+            })
+            .collect();
+
+        Element::<NodeBundle>::new()
+            .style(style_diff_column)
+            .children((
+                Element::<NodeBundle>::new()
+                    .style(text_strong)
+                    .children(self.label),
+                For::each(buttons, |button| button.clone()),
+            ))
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct PresetPickerButton {
+    index: usize,
+    name: String,
+    checked: bool,
+    selected: Mutable<Option<usize>>,
+    accepted: Mutable<Vec<bool>>,
+}
+
+impl ViewTemplate for PresetPickerButton {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let index = self.index;
+        let selected = self.selected;
+        let accepted = self.accepted;
+        let on_click = cx.create_callback(move |world: &mut World| {
+            selected.set(world, Some(index));
+            accepted.set_clone(world, Vec::new());
+        });
+
+        Button::new()
+            .children(self.name.clone())
+            .selected(self.checked)
+            .on_click(on_click)
+    }
+}
+
+/// One difference between the presets, with its state in the base preset on the left and in
+/// the changed preset on the right. The checkbox selects the change for merging.
+#[derive(Clone, PartialEq)]
+struct DiffRow {
+    index: usize,
+    left: String,
+    right: String,
+    checked: bool,
+    accepted: Mutable<Vec<bool>>,
+}
+
+impl ViewTemplate for DiffRow {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let index = self.index;
+        let accepted = self.accepted;
+        let on_change = cx.create_callback(move |checked: In<bool>, world: &mut World| {
+            accepted.update(world, |mut accepted| {
+                if accepted.len() <= index {
+                    accepted.resize(index + 1, false);
+                }
+                accepted[index] = checked.0;
+            });
+        });
+
+        Element::<NodeBundle>::new()
+            .style(style_diff_row)
+            .children((
+                Checkbox::new().checked(self.checked).on_change(on_change),
+                Element::<NodeBundle>::new()
+                    .style(style_diff_cell)
+                    .children(self.left.clone()),
+                Element::<NodeBundle>::new()
+                    .style(style_diff_cell)
+                    .children(self.right.clone()),
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ops::Mix, preset::save_operator, preset::PresetNode};
+
+    fn node(title: &'static str, x: i32, factor: f32) -> NodeSnapshot {
+        NodeSnapshot {
+            operator: title,
+            title,
+            position: IVec2::new(x, 0),
+            properties: vec![("factor", format!("{}", factor))],
+            bypass: false,
+        }
+    }
+
+    fn connection(output: usize, input: usize) -> ConnectionSnapshot {
+        ConnectionSnapshot {
+            output: (GraphNodeId(output), "output"),
+            input: (GraphNodeId(input), "input_a"),
+        }
+    }
+
+    fn snapshot(nodes: &[NodeSnapshot], connections: &[(usize, usize)]) -> GraphSnapshot {
+        GraphSnapshot {
+            nodes: nodes
+                .iter()
+                .enumerate()
+                .map(|(index, node)| (GraphNodeId(index), node.clone()))
+                .collect(),
+            connections: connections
+                .iter()
+                .map(|(output, input)| connection(*output, *input))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let old = snapshot(&[node("Mix", 0, 0.5), node("Noise", 100, 0.)], &[(1, 0)]);
+        assert!(GraphDiff::between(&old, &old.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_between() {
+        let old = snapshot(
+            &[
+                node("Mix", 0, 0.5),
+                node("Noise", 100, 0.),
+                node("Mix", 200, 0.),
+            ],
+            &[(1, 0), (2, 0)],
+        );
+        let mut new = snapshot(&[node("Mix", 0, 0.7), node("Noise", 150, 0.)], &[(1, 0)]);
+        new.nodes.insert(GraphNodeId(3), node("Bricks", 300, 0.));
+        new.connections.insert(connection(3, 1));
+
+        let diff = GraphDiff::between(&old, &new);
+        assert_eq!(diff.added_nodes, vec![GraphNodeId(3)]);
+        assert_eq!(diff.removed_nodes, vec![GraphNodeId(2)]);
+        assert_eq!(diff.changed_nodes, vec![GraphNodeId(0)]);
+        assert_eq!(diff.moved_nodes, vec![GraphNodeId(1)]);
+        assert_eq!(diff.added_connections, vec![connection(3, 1)]);
+        assert_eq!(diff.removed_connections, vec![connection(2, 0)]);
+        assert_eq!(
+            diff.entries(),
+            vec![
+                DiffEntry::RemoveNode(GraphNodeId(2)),
+                DiffEntry::AddNode(GraphNodeId(3)),
+                DiffEntry::ChangeNode(GraphNodeId(0)),
+                DiffEntry::MoveNode(GraphNodeId(1)),
+                DiffEntry::RemoveConnection(connection(2, 0)),
+                DiffEntry::AddConnection(connection(3, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matched_to() {
+        let base = snapshot(&[node("Mix", 0, 0.5), node("Noise", 100, 0.)], &[(1, 0)]);
+        // The same nodes in a different order, with one node inserted before them and the
+        // mix node modified.
+        let changed = snapshot(
+            &[
+                node("Noise", -100, 0.),
+                node("Noise", 100, 0.),
+                node("Mix", 10, 0.6),
+            ],
+            &[(0, 2), (1, 2)],
+        );
+        let (matched, original) = changed.matched_to(&base);
+        assert_eq!(original[&GraphNodeId(0)], GraphNodeId(2));
+        assert_eq!(original[&GraphNodeId(1)], GraphNodeId(1));
+        assert_eq!(original[&GraphNodeId(2)], GraphNodeId(0));
+
+        let diff = GraphDiff::between(&base, &matched);
+        assert_eq!(diff.added_nodes, vec![GraphNodeId(2)]);
+        assert_eq!(diff.changed_nodes, vec![GraphNodeId(0)]);
+        assert!(diff.removed_nodes.is_empty());
+        assert!(diff.moved_nodes.is_empty());
+        assert_eq!(diff.added_connections, vec![connection(2, 0)]);
+        assert!(diff.removed_connections.is_empty());
+    }
+
+    fn preset(
+        registry: &TypeRegistry,
+        nodes: &[(i32, f32)],
+        connections: &[(usize, usize)],
+    ) -> NodePreset {
+        NodePreset {
+            name: "Test".to_string(),
+            nodes: nodes
+                .iter()
+                .map(|(x, factor)| PresetNode {
+                    offset: (*x, 0),
+                    operator: save_operator(
+                        registry,
+                        &Mix {
+                            factor: *factor,
+                            ..default()
+                        },
+                    )
+                    .unwrap(),
+                })
+                .collect(),
+            connections: connections
+                .iter()
+                .map(|(output, input)| PresetConnection {
+                    output: (*output, "output".to_string()),
+                    input: (*input, "input_a".to_string()),
+                })
+                .collect(),
+            notes: Vec::new(),
+            tracks: Vec::new(),
+            thumbnail: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_presets() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<LinearRgba>();
+        registry.register::<Mix>();
+        let base = preset(&registry, &[(0, 0.1), (100, 0.)], &[(0, 1)]);
+        let changed = preset(
+            &registry,
+            &[(-100, 0.9), (0, 0.5), (100, 0.)],
+            &[(0, 1), (1, 2)],
+        );
+
+        let diff = PresetDiff::new(&base, &changed, &registry);
+        let added = GraphNodeId(2);
+        assert_eq!(
+            diff.entries,
+            vec![
+                DiffEntry::AddNode(added),
+                DiffEntry::ChangeNode(GraphNodeId(0)),
+                DiffEntry::AddConnection(ConnectionSnapshot {
+                    output: (added, "output"),
+                    input: (GraphNodeId(0), "input_a"),
+                }),
+            ]
+        );
+        assert_eq!(
+            diff.describe(&diff.entries[0]),
+            (String::new(), "Mix".to_string())
+        );
+
+        // Merging everything reproduces the changed preset.
+        let merged = diff.merge(&base, &changed, &[true, true, true]);
+        let merged_diff = PresetDiff::new(&changed, &merged, &registry);
+        assert!(merged_diff.entries.is_empty());
+
+        // Merging only the new node keeps the base version of the changed node.
+        let merged = diff.merge(&base, &changed, &[true, false, true]);
+        assert_eq!(merged.nodes.len(), 3);
+        assert_eq!(merged.nodes[0].operator, base.nodes[0].operator);
+        assert_eq!(merged.nodes[2].operator, changed.nodes[0].operator);
+        let connections: Vec<_> = merged
+            .connections
+            .iter()
+            .map(|conn| (conn.output.0, conn.input.0))
+            .collect();
+        assert_eq!(connections, vec![(0, 1), (2, 0)]);
+
+        // Merging nothing reproduces the base preset.
+        let merged = diff.merge(&base, &changed, &[false, false, false]);
+        assert!(PresetDiff::new(&base, &merged, &registry)
+            .entries
+            .is_empty());
+    }
+}
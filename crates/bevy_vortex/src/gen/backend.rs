@@ -0,0 +1,315 @@
+use std::fmt::{self, Write};
+
+use super::{DataType, ShaderAssembly, ShaderInclude};
+
+/// Error produced when generating shader source.
+#[derive(Debug)]
+pub enum ShaderGenError {
+    /// Writing the output failed.
+    Format(fmt::Error),
+}
+
+impl From<fmt::Error> for ShaderGenError {
+    fn from(err: fmt::Error) -> Self {
+        ShaderGenError::Format(err)
+    }
+}
+
+impl fmt::Display for ShaderGenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderGenError::Format(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// A code generator for a particular shading language. The expression tree is the same for all
+/// languages; backends translate the names of builtin functions and types, and write the
+/// surrounding module.
+pub trait ShaderBackend: Send + Sync {
+    /// Name of the shading language.
+    fn name(&self) -> &'static str;
+
+    /// File extension for exported shaders, without the dot.
+    fn file_extension(&self) -> &'static str;
+
+    /// Translate the name of a WGSL builtin function or type constructor, as produced by the
+    /// operators and the typecast lowering pass.
+    fn function_name(&self, name: &'static str) -> &'static str {
+        name
+    }
+
+    /// The keyword or type used to declare a local variable.
+    fn local_decl(&self, data_type: DataType, mutable: bool) -> &'static str;
+
+    /// The source of a utility function, in this language.
+    fn include_source(&self, include: &ShaderInclude) -> &'static str;
+
+    /// Write the complete shader module.
+    fn write_module(
+        &self,
+        assembly: &ShaderAssembly,
+        out: &mut String,
+    ) -> Result<(), ShaderGenError>;
+}
+
+/// Backend which generates WGSL modules for Bevy's renderer. This is used for the preview.
+pub struct WgslBackend;
+
+impl ShaderBackend for WgslBackend {
+    fn name(&self) -> &'static str {
+        "WGSL"
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "wgsl"
+    }
+
+    fn local_decl(&self, _data_type: DataType, mutable: bool) -> &'static str {
+        if mutable {
+            "var"
+        } else {
+            "let"
+        }
+    }
+
+    fn include_source(&self, include: &ShaderInclude) -> &'static str {
+        include.wgsl
+    }
+
+    fn write_module(
+        &self,
+        assembly: &ShaderAssembly,
+        out: &mut String,
+    ) -> Result<(), ShaderGenError> {
+        assembly.write_wgsl_module(self, out)?;
+        Ok(())
+    }
+}
+
+/// Backend which generates a GLSL fragment shader. The fragment inputs are declared in an
+/// interface block named `mesh`, which the vertex stage of the host engine must provide.
+pub struct GlslBackend;
+
+impl ShaderBackend for GlslBackend {
+    fn name(&self) -> &'static str {
+        "GLSL"
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "frag"
+    }
+
+    fn function_name(&self, name: &'static str) -> &'static str {
+        match name {
+            "f32" => "float",
+            "i32" => "int",
+            "vec2f" | "vec2<f32>" => "vec2",
+            "vec3f" | "vec3<f32>" => "vec3",
+            "vec4f" | "vec4<f32>" => "vec4",
+            _ => name,
+        }
+    }
+
+    fn local_decl(&self, data_type: DataType, _mutable: bool) -> &'static str {
+        match data_type {
+            DataType::Void => "void",
+            DataType::I32 => "int",
+            DataType::F32 => "float",
+            DataType::Vec2 => "vec2",
+            DataType::Vec3 => "vec3",
+            DataType::Vec4 | DataType::LinearRgba => "vec4",
+        }
+    }
+
+    fn include_source(&self, include: &ShaderInclude) -> &'static str {
+        include.glsl
+    }
+
+    fn write_module(
+        &self,
+        assembly: &ShaderAssembly,
+        out: &mut String,
+    ) -> Result<(), ShaderGenError> {
+        writeln!(out, "// Shader for {}", assembly.name())?;
+        out.push_str("#version 450\n\n");
+        out.push_str("in VertexOutput {\n");
+        out.push_str("    vec4 world_position;\n");
+        if assembly.needs_normal {
+            out.push_str("    vec3 world_normal;\n");
+        }
+        if assembly.needs_uv {
+            out.push_str("    vec2 uv;\n");
+        }
+        if assembly.needs_position {
+            out.push_str("    vec3 position_local;\n");
+        }
+        out.push_str("} mesh;\n\n");
+        out.push_str("layout(location = 0) out vec4 frag_color;\n\n");
+        assembly.write_includes(self, out)?;
+        out.push_str("vec4 fragment() {\n");
+        assembly.write_fragment_body(self, out)?;
+        out.push_str("}\n\n");
+        out.push_str("void main() {\n");
+        out.push_str("    frag_color = fragment();\n");
+        out.push_str("}\n");
+        Ok(())
+    }
+}
+
+/// Backend which generates an HLSL pixel shader, with the entry point `fragment`.
+pub struct HlslBackend;
+
+impl ShaderBackend for HlslBackend {
+    fn name(&self) -> &'static str {
+        "HLSL"
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "hlsl"
+    }
+
+    fn function_name(&self, name: &'static str) -> &'static str {
+        match name {
+            "f32" => "float",
+            "i32" => "int",
+            "vec2f" | "vec2<f32>" => "float2",
+            "vec3f" | "vec3<f32>" => "float3",
+            "vec4f" | "vec4<f32>" => "float4",
+            "mix" => "lerp",
+            "fract" => "frac",
+            "fma" => "mad",
+            _ => name,
+        }
+    }
+
+    fn local_decl(&self, data_type: DataType, _mutable: bool) -> &'static str {
+        match data_type {
+            DataType::Void => "void",
+            DataType::I32 => "int",
+            DataType::F32 => "float",
+            DataType::Vec2 => "float2",
+            DataType::Vec3 => "float3",
+            DataType::Vec4 | DataType::LinearRgba => "float4",
+        }
+    }
+
+    fn include_source(&self, include: &ShaderInclude) -> &'static str {
+        include.hlsl
+    }
+
+    fn write_module(
+        &self,
+        assembly: &ShaderAssembly,
+        out: &mut String,
+    ) -> Result<(), ShaderGenError> {
+        writeln!(out, "// Shader for {}\n", assembly.name())?;
+        out.push_str("struct VertexOutput {\n");
+        out.push_str("    float4 position : SV_Position;\n");
+        out.push_str("    float4 world_position : TEXCOORD0;\n");
+        if assembly.needs_normal {
+            out.push_str("    float3 world_normal : TEXCOORD1;\n");
+        }
+        if assembly.needs_uv {
+            out.push_str("    float2 uv : TEXCOORD2;\n");
+        }
+        if assembly.needs_position {
+            out.push_str("    float3 position_local : TEXCOORD3;\n");
+        }
+        out.push_str("};\n\n");
+        assembly.write_includes(self, out)?;
+        out.push_str("float4 fragment(VertexOutput mesh) : SV_Target {\n");
+        assembly.write_fragment_body(self, out)?;
+        out.push_str("}\n");
+        Ok(())
+    }
+}
+
+/// The shading languages which shaders can be exported to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShaderLanguage {
+    #[default]
+    Wgsl,
+    Glsl,
+    Hlsl,
+}
+
+impl ShaderLanguage {
+    /// The code generator for this language.
+    pub fn backend(self) -> &'static dyn ShaderBackend {
+        match self {
+            ShaderLanguage::Wgsl => &WgslBackend,
+            ShaderLanguage::Glsl => &GlslBackend,
+            ShaderLanguage::Hlsl => &HlslBackend,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::gen::Expr;
+
+    const SQUARE: ShaderInclude = ShaderInclude {
+        wgsl: "fn square(x: f32) -> f32 { return x * x; }\n",
+        glsl: "float square(float x) { return x * x; }\n",
+        hlsl: "float square(float x) { return x * x; }\n",
+    };
+
+    fn assembly() -> ShaderAssembly {
+        let mut assembly = ShaderAssembly::new("Test".to_string());
+        assembly.add_include(SQUARE);
+        let square = Expr::FnCall(
+            DataType::F32,
+            "square",
+            vec![Arc::new(Expr::FnCall(
+                DataType::F32,
+                "fract",
+                vec![Arc::new(Expr::ConstF32(1.5))],
+            ))],
+        );
+        assembly.set_fragment_value(Arc::new(Expr::FnCall(
+            DataType::LinearRgba,
+            "vec4f",
+            vec![Arc::new(square)],
+        )));
+        assembly
+    }
+
+    /// Position of `pattern` in `source`, which must contain it.
+    fn position(source: &str, pattern: &str) -> usize {
+        source
+            .find(pattern)
+            .unwrap_or_else(|| panic!("{:?} not found in:\n{}", pattern, source))
+    }
+
+    #[test]
+    fn test_wgsl_module() {
+        let source = assembly().generate(&WgslBackend).unwrap();
+        let fragment = position(&source, "fn fragment(");
+        assert!(position(&source, "fn square(x: f32)") > fragment);
+        assert!(position(&source, "vec4f(square(fract(") > fragment);
+    }
+
+    #[test]
+    fn test_glsl_module() {
+        let source = assembly().generate(&GlslBackend).unwrap();
+        assert!(source.contains("#version 450"));
+        // Functions must be declared before they are used.
+        let fragment = position(&source, "vec4 fragment()");
+        assert!(position(&source, "float square(float x)") < fragment);
+        assert!(position(&source, "vec4(square(fract(") > fragment);
+        assert!(!source.contains("fn "));
+    }
+
+    #[test]
+    fn test_hlsl_module() {
+        let source = assembly().generate(&HlslBackend).unwrap();
+        let fragment = position(&source, "float4 fragment(VertexOutput mesh) : SV_Target");
+        assert!(position(&source, "float square(float x)") < fragment);
+        assert!(position(&source, "float4(square(frac(") > fragment);
+        assert!(!source.contains("fract("));
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{Expr, ShaderAssembly, TerminalReader};
+
+/// Estimated number of instructions for a call to the named function, not counting the
+/// arguments.
+fn function_cost(name: &str) -> u32 {
+    match name {
+        "noised_octaves" => 80,
+        "bricks" => 24,
+        "textureSample" | "textureSampleLevel" => 16,
+        "mix" | "dot" | "clamp" | "smoothstep" => 3,
+        // Constructors and conversions.
+        "vec2<f32>" | "vec3f" | "vec4f" | "vec2f" | "f32" | "i32" => 0,
+        _ => 1,
+    }
+}
+
+/// Estimate the GPU cost of evaluating an expression, in approximate instructions.
+pub fn expr_cost(expr: &Expr) -> u32 {
+    match expr {
+        Expr::LiteralStr(_)
+        | Expr::ConstI32(_)
+        | Expr::ConstF32(_)
+        | Expr::ConstVec2(_)
+        | Expr::ConstVec3(_)
+        | Expr::ConstVec4(_)
+        | Expr::ConstColor(_)
+        | Expr::RefLocal(_, _)
+        | Expr::RefInput(_, _)
+        | Expr::RefUniform(_, _) => 0,
+        Expr::LocalDefn(_, _, init) => init.as_ref().map_or(0, |init| expr_cost(init)),
+        Expr::Assign(_, _, value) => expr_cost(value),
+        Expr::TypeCast(_, value) => 1 + expr_cost(value),
+        Expr::GetAttr(_, value, _) => expr_cost(value),
+        Expr::BinOp(_, _, lhs, rhs) => 1 + expr_cost(lhs) + expr_cost(rhs),
+        Expr::FnCall(_, name, args) => {
+            function_cost(name) + args.iter().map(|arg| expr_cost(arg)).sum::<u32>()
+        }
+        Expr::OvCall(_, name, args) => {
+            function_cost(name) + args.iter().map(|arg| expr_cost(arg)).sum::<u32>()
+        }
+    }
+}
+
+/// Estimate the cost of each of the given nodes, not including the cost of the nodes which
+/// feed into it.
+pub(crate) fn estimate_node_costs(
+    reader: &TerminalReader,
+    node_ids: impl IntoIterator<Item = Entity>,
+) -> HashMap<Entity, u32> {
+    // Total cost of computing each node, including its inputs.
+    let mut totals = HashMap::<Entity, u32>::default();
+    for node_id in node_ids {
+        let Ok(node) = reader.nodes.get(node_id) else {
+            continue;
+        };
+        let mut assembly = ShaderAssembly::new(node.name().to_owned());
+        let cost = node
+            .outputs
+            .iter()
+            .map(|(name, _)| expr_cost(&node.gen(&mut assembly, reader, node_id, name)))
+            .sum::<u32>();
+        totals.insert(node_id, cost + assembly.locals_cost());
+    }
+
+    totals
+        .iter()
+        .map(|(node_id, total)| {
+            let inputs = reader
+                .upstream_nodes(*node_id)
+                .iter()
+                .filter_map(|upstream| totals.get(upstream))
+                .sum::<u32>();
+            (*node_id, total.saturating_sub(inputs))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::gen::DataType;
+
+    #[test]
+    fn test_constant_is_free() {
+        assert_eq!(expr_cost(&Expr::ConstF32(1.0)), 0);
+    }
+
+    #[test]
+    fn test_call_includes_arguments() {
+        let expr = Expr::FnCall(
+            DataType::LinearRgba,
+            "mix",
+            vec![
+                Arc::new(Expr::ConstColor(LinearRgba::RED)),
+                Arc::new(Expr::TypeCast(
+                    DataType::LinearRgba,
+                    Arc::new(Expr::ConstF32(0.5)),
+                )),
+                Arc::new(Expr::FnCall(DataType::F32, "bricks", Vec::new())),
+            ],
+        );
+        assert_eq!(expr_cost(&expr), 3 + 1 + 24);
+    }
+}
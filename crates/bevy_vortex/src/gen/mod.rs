@@ -1,3 +1,5 @@
+mod backend;
+mod cost;
 mod expr;
 mod output_chunk;
 mod pass;
@@ -7,14 +9,20 @@ mod terminal_reader;
 
 use std::sync::Arc;
 
-use crate::graph::NodeModified;
+use crate::{
+    animation::Timeline,
+    color_management::{ColorManagement, WorkingSpace},
+    graph::{GraphNode, GraphResource, NodeModified},
+};
+pub use backend::{ShaderGenError, ShaderLanguage};
 use bevy::tasks::futures_lite::future;
 use bevy::{
     prelude::*,
     tasks::{block_on, AsyncComputeTaskPool, Task},
 };
+pub(crate) use cost::estimate_node_costs;
 pub use expr::*;
-pub use shader_assembly::ShaderAssembly;
+pub use shader_assembly::{ShaderAssembly, ShaderInclude};
 pub use terminal_reader::TerminalReader;
 
 /// Component used to indicate that a node is being observed. These nodes have higher priority
@@ -65,6 +73,9 @@ pub(crate) fn begin_build_shaders(
     mut commands: Commands,
     reader: TerminalReader,
     q_modified: Query<Entity, With<NodeModified>>,
+    color: Res<ColorManagement>,
+    graph: Res<GraphResource>,
+    timeline: Res<Timeline>,
 ) {
     // Spawn tasks for any nodes that are modified.
     // TODO: Limit
@@ -76,17 +87,18 @@ pub(crate) fn begin_build_shaders(
             // Need to walk the graph and build expression tree here.
             // Not sure that we need an async task since a lot of the effort is just querying
             // the graph, which is not accessible in a thread.
-            let Some(output) = node.outputs.first() else {
+            let Some(mut assembly) = assemble_node_shader(
+                node,
+                &reader,
+                modified,
+                color.working_space,
+                graph.0.seed,
+                timeline.params(),
+            ) else {
                 println!("Node has no outputs: {}", node.name());
                 continue;
             };
 
-            // println!("Task spawned");
-            let mut assembly = ShaderAssembly::new(node.name().to_owned());
-            assembly.add_common_imports();
-            let expr = Arc::new(node.gen(&mut assembly, &reader, modified, output.0));
-            assembly.set_fragment_value(expr);
-
             let task = task_pool.spawn(async move {
                 // println!("Task spawned");
                 // let assembly = ShaderAssembly::new(modified);
@@ -98,3 +110,58 @@ pub(crate) fn begin_build_shaders(
         }
     }
 }
+
+/// Build the shader assembly for the first output of a node, or `None` if it has no outputs.
+/// The output is converted from the working space to linear. The fields in `animated_params`
+/// are read from the animated parameter uniform.
+fn assemble_node_shader(
+    node: &GraphNode,
+    reader: &TerminalReader,
+    node_id: Entity,
+    working_space: WorkingSpace,
+    seed: u32,
+    animated_params: Vec<(Entity, &'static str)>,
+) -> Option<ShaderAssembly> {
+    let output = node.outputs.first()?;
+    let mut assembly = ShaderAssembly::new(node.name().to_owned());
+    assembly.add_common_imports();
+    assembly.set_seed(seed);
+    assembly.set_animated_params(animated_params);
+    let expr = node.gen(&mut assembly, reader, node_id, output.0);
+    let expr = working_space.convert_output(&mut assembly, expr);
+    assembly.set_fragment_value(Arc::new(expr));
+    Some(assembly)
+}
+
+/// Generate the source of the shader for a node, in the given language. The shader outputs
+/// linear color, whatever the working space of the graph. Animated fields are exported with
+/// the values set in their operators.
+pub(crate) fn export_node_shader(
+    reader: &TerminalReader,
+    node_id: Entity,
+    language: ShaderLanguage,
+    working_space: WorkingSpace,
+    seed: u32,
+) -> Option<Result<String, ShaderGenError>> {
+    let node = reader.nodes.get(node_id).ok()?;
+    let assembly = assemble_node_shader(node, reader, node_id, working_space, seed, Vec::new())?;
+    Some(assembly.generate(language.backend()))
+}
+
+/// Build the preview shader for a node immediately, rather than in a task. This is used to
+/// render variations of a node with other seeds.
+pub(crate) fn build_node_shader(
+    reader: &TerminalReader,
+    node_id: Entity,
+    working_space: WorkingSpace,
+    seed: u32,
+) -> Option<Shader> {
+    let node = reader.nodes.get(node_id).ok()?;
+    let mut assembly =
+        assemble_node_shader(node, reader, node_id, working_space, seed, Vec::new())?;
+    assembly.run_passes().ok()?;
+    Some(Shader::from_wgsl(
+        assembly.source().to_owned(),
+        "".to_string(),
+    ))
+}
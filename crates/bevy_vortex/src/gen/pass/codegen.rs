@@ -1,6 +1,7 @@
-use crate::gen::{output_chunk::OutputChunk, Expr};
+use crate::gen::{backend::ShaderBackend, output_chunk::OutputChunk, Expr};
 
-pub fn codegen(expr: &Expr) -> OutputChunk {
+/// Generate code for an expression, using the function names of the given backend.
+pub fn codegen(expr: &Expr, backend: &dyn ShaderBackend) -> OutputChunk {
     match expr {
         Expr::LiteralStr(_) => todo!(),
         Expr::ConstI32(n) => OutputChunk::Literal(n.to_string()),
@@ -13,50 +14,53 @@ pub fn codegen(expr: &Expr) -> OutputChunk {
             }
         }
         Expr::ConstVec2(v) => OutputChunk::FCall {
-            func: "vec2f",
-            args: vec![codegen(&Expr::ConstF32(v.x)), codegen(&Expr::ConstF32(v.y))],
+            func: backend.function_name("vec2f"),
+            args: vec![
+                codegen(&Expr::ConstF32(v.x), backend),
+                codegen(&Expr::ConstF32(v.y), backend),
+            ],
         },
         Expr::ConstVec3(v) => OutputChunk::FCall {
-            func: "vec3f",
+            func: backend.function_name("vec3f"),
             args: vec![
-                codegen(&Expr::ConstF32(v.x)),
-                codegen(&Expr::ConstF32(v.y)),
-                codegen(&Expr::ConstF32(v.z)),
+                codegen(&Expr::ConstF32(v.x), backend),
+                codegen(&Expr::ConstF32(v.y), backend),
+                codegen(&Expr::ConstF32(v.z), backend),
             ],
         },
         Expr::ConstVec4(v) => OutputChunk::FCall {
-            func: "vec4f",
+            func: backend.function_name("vec4f"),
             args: vec![
-                codegen(&Expr::ConstF32(v.x)),
-                codegen(&Expr::ConstF32(v.y)),
-                codegen(&Expr::ConstF32(v.z)),
-                codegen(&Expr::ConstF32(v.w)),
+                codegen(&Expr::ConstF32(v.x), backend),
+                codegen(&Expr::ConstF32(v.y), backend),
+                codegen(&Expr::ConstF32(v.z), backend),
+                codegen(&Expr::ConstF32(v.w), backend),
             ],
         },
         Expr::ConstColor(color) => OutputChunk::FCall {
-            func: "vec4f",
+            func: backend.function_name("vec4f"),
             args: vec![
-                codegen(&Expr::ConstF32(color.red)),
-                codegen(&Expr::ConstF32(color.green)),
-                codegen(&Expr::ConstF32(color.blue)),
-                codegen(&Expr::ConstF32(color.alpha)),
+                codegen(&Expr::ConstF32(color.red), backend),
+                codegen(&Expr::ConstF32(color.green), backend),
+                codegen(&Expr::ConstF32(color.blue), backend),
+                codegen(&Expr::ConstF32(color.alpha), backend),
             ],
         },
         Expr::LocalDefn(_, _, _) => todo!(),
         Expr::Assign(_, _, _) => todo!(),
         Expr::RefLocal(_, name) => OutputChunk::Literal(name.clone()),
         Expr::RefInput(_, _) => todo!(),
-        Expr::RefUniform(_, _) => todo!(),
+        Expr::RefUniform(_, name) => OutputChunk::Literal(name.clone()),
         Expr::TypeCast(_, _) => unreachable!("TypeCast should have been lowered"),
         Expr::GetAttr(_, expr, fieldname) => OutputChunk::Infix {
             oper: ".".to_string(),
             precedence: 1,
-            args: vec![codegen(expr), OutputChunk::Str(fieldname)],
+            args: vec![codegen(expr, backend), OutputChunk::Str(fieldname)],
         },
         Expr::BinOp(_, _, _, _) => todo!(),
         Expr::FnCall(_, f, args) => OutputChunk::FCall {
-            func: f,
-            args: args.iter().map(|arg| codegen(arg)).collect(),
+            func: backend.function_name(f),
+            args: args.iter().map(|arg| codegen(arg, backend)).collect(),
         },
         Expr::OvCall(_, _, _) => todo!(),
     }
@@ -5,13 +5,27 @@ use std::{
 
 use bevy::prelude::*;
 
+use crate::pipeline::MAX_ANIMATED_PARAMS;
+
 use super::{
+    backend::{ShaderBackend, ShaderGenError, WgslBackend},
+    cost::expr_cost,
     output_chunk::{LineWrapping, OutputChunk},
     pass::{codegen, lower_typecasts},
     shader_imports::ShaderImports,
-    Expr,
+    DataType, Expr,
 };
 
+/// A utility function which can be included in the shader module, written in each of the
+/// supported shading languages. In languages other than WGSL, functions must be declared before
+/// they are used, so includes are written in the order they were added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShaderInclude {
+    pub wgsl: &'static str,
+    pub glsl: &'static str,
+    pub hlsl: &'static str,
+}
+
 struct ShaderLocalVar {
     name: String,
     data_type: super::DataType,
@@ -34,7 +48,7 @@ pub struct ShaderAssembly {
     imports: ShaderImports,
 
     /// Code snippets that are included in the shader module.
-    includes: Vec<&'static str>,
+    includes: Vec<ShaderInclude>,
 
     /// Local variable definitions. Note we use a vector here so we can preserve declaration order.
     locals: Vec<ShaderLocalVar>,
@@ -47,6 +61,26 @@ pub struct ShaderAssembly {
 
     /// Whether the fragment shader needs texture coordinates.
     pub(crate) needs_uv: bool,
+
+    /// Texture coordinates which replace the mesh UVs, while generating the inputs of an
+    /// operator that evaluates them at other coordinates.
+    uv_override: Option<Arc<Expr>>,
+
+    /// Identifies the current texture coordinate override, or zero if there is none.
+    uv_variant: usize,
+
+    /// Number of texture coordinate overrides so far.
+    uv_variant_count: usize,
+
+    /// Random seed used by operators which generate noise.
+    seed: u32,
+
+    /// Operator fields which are animated, in the order of their slots in the parameter
+    /// uniform.
+    animated_params: Vec<(Entity, &'static str)>,
+
+    /// Whether the fragment shader reads the animated parameter uniform.
+    pub(crate) needs_params: bool,
 }
 
 impl ShaderAssembly {
@@ -62,11 +96,54 @@ impl ShaderAssembly {
             needs_position: false,
             needs_normal: false,
             needs_uv: false,
+            uv_override: None,
+            uv_variant: 0,
+            uv_variant_count: 0,
+            seed: 0,
+            animated_params: Vec::new(),
+            needs_params: false,
+        }
+    }
+
+    /// Random seed used by operators which generate noise.
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Set the random seed used by operators which generate noise.
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    /// Set the operator fields which are animated. Each field is read from the slot of the
+    /// parameter uniform with the same index, rather than being compiled in as a constant, so
+    /// that playback does not need to rebuild the shader.
+    pub fn set_animated_params(&mut self, params: Vec<(Entity, &'static str)>) {
+        self.animated_params = params;
+    }
+
+    /// Expression for the value of an operator field: a reference to the parameter uniform if
+    /// the field is animated, otherwise the constant `value`.
+    pub fn param_f32(&mut self, node_id: Entity, field: &'static str, value: f32) -> Expr {
+        let slot = self
+            .animated_params
+            .iter()
+            .position(|param| *param == (node_id, field));
+        match slot {
+            Some(slot) => {
+                self.needs_params = true;
+                Expr::RefUniform(
+                    DataType::F32,
+                    format!("params.values[{}][{}]", slot / 4, slot % 4),
+                )
+            }
+            None => Expr::ConstF32(value),
         }
     }
 
-    /// Include a utility function in the shader.
-    pub fn add_include(&mut self, include: &'static str) {
+    /// Include a utility function in the shader. Functions which it depends on must be included
+    /// first.
+    pub fn add_include(&mut self, include: ShaderInclude) {
         if !self.includes.contains(&include) {
             self.includes.push(include);
         }
@@ -106,23 +183,96 @@ impl ShaderAssembly {
         });
     }
 
+    /// Return the name to use for a local variable which caches a value computed by a node.
+    /// Values computed under a texture coordinate override get a distinct name.
+    pub fn local_name(&self, name: String) -> String {
+        match self.uv_variant {
+            0 => name,
+            variant => format!("{}_uv{}", name, variant),
+        }
+    }
+
+    /// Expression for the texture coordinates at which the graph is being evaluated.
+    pub fn uv(&mut self) -> Expr {
+        match self.uv_override {
+            Some(ref uv) => uv.as_ref().clone(),
+            None => {
+                self.needs_uv = true;
+                Expr::RefLocal(DataType::Vec2, "mesh.uv".to_string())
+            }
+        }
+    }
+
+    /// Generate expressions using `uv` in place of the texture coordinates.
+    pub fn with_uv<R>(&mut self, uv: Expr, gen: impl FnOnce(&mut Self) -> R) -> R {
+        self.uv_variant_count += 1;
+        let prev_uv = self.uv_override.replace(Arc::new(uv));
+        let prev_variant = std::mem::replace(&mut self.uv_variant, self.uv_variant_count);
+        let result = gen(self);
+        self.uv_override = prev_uv;
+        self.uv_variant = prev_variant;
+        result
+    }
+
     /// Return true if the given local variable has already been declared.
     pub fn local_exists(&self, name: &String) -> bool {
         self.locals.iter().any(|var| var.name == *name)
     }
 
+    /// Estimated cost of initializing the local variables.
+    pub(crate) fn locals_cost(&self) -> u32 {
+        self.locals.iter().map(|var| expr_cost(&var.init)).sum()
+    }
+
     /// Return the source code for the shader.
     pub fn source(&self) -> &str {
         self.source.as_str()
     }
 
+    /// Name of this shader.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Write the included utility functions, in the language of the given backend.
+    pub(crate) fn write_includes(
+        &self,
+        backend: &dyn ShaderBackend,
+        source: &mut String,
+    ) -> Result<(), Error> {
+        for include in &self.includes {
+            source.write_str(backend.include_source(include))?;
+            source.write_char('\n')?;
+        }
+        Ok(())
+    }
+
     /// Run transpilation passes
     pub fn run_passes(&mut self) -> Result<(), Error> {
         let mut source = String::new();
+        self.write_wgsl_module(&WgslBackend, &mut source)?;
+        // println!("Shader source:\n{}", source);
+        self.source = source;
+        Ok(())
+    }
+
+    /// Generate the shader source in the language of the given backend.
+    pub fn generate(&self, backend: &dyn ShaderBackend) -> Result<String, ShaderGenError> {
+        let mut source = String::new();
+        backend.write_module(self, &mut source)?;
+        Ok(source)
+    }
+
+    /// Write a WGSL module containing both the vertex and fragment shaders.
+    pub(crate) fn write_wgsl_module(
+        &self,
+        backend: &dyn ShaderBackend,
+        source: &mut String,
+    ) -> Result<(), Error> {
         source.write_fmt(format_args!("// Shader for {}\n\n", self.name))?;
 
         // Write imports
-        self.imports.write(&mut source)?;
+        self.imports.write(source)?;
         source.write_str("\n")?;
 
         // Write vertex input format
@@ -152,6 +302,17 @@ impl ShaderAssembly {
         }
         source.write_str("};\n\n")?;
 
+        // Write the animated parameters, which are bound by the preview pipeline.
+        if self.needs_params {
+            source.write_str("struct AnimatedParams {\n")?;
+            source.write_fmt(format_args!(
+                "    values: array<vec4<f32>, {}>,\n",
+                MAX_ANIMATED_PARAMS / 4
+            ))?;
+            source.write_str("};\n\n")?;
+            source.write_str("@group(2) @binding(0) var<uniform> params: AnimatedParams;\n\n")?;
+        }
+
         // Write vertex shader
         source.write_str("@vertex\n")?;
         source.write_str("fn vertex(vertex: Vertex) -> VertexOutput {\n")?;
@@ -182,24 +343,40 @@ impl ShaderAssembly {
         source.write_str("    @builtin(front_facing) is_front: bool,\n")?;
         source.write_str("    mesh: VertexOutput,\n")?;
         source.write_str(") -> @location(0) vec4<f32> {\n")?;
+        self.write_fragment_body(backend, source)?;
+        source.write_str("}\n")?;
 
+        // Add includes
+        for include in &self.includes {
+            source.write_char('\n')?;
+            source.write_str(backend.include_source(include))?;
+        }
+        Ok(())
+    }
+
+    /// Write the local variables and return statement of the fragment shader.
+    pub(crate) fn write_fragment_body(
+        &self,
+        backend: &dyn ShaderBackend,
+        source: &mut String,
+    ) -> Result<(), Error> {
         let mut wrap = LineWrapping::new(100);
         wrap.indent();
 
         // Write local variables
         for local in &self.locals {
-            wrap.write_indent(&mut source)?;
+            wrap.write_indent(source)?;
             let init = lower_typecasts(Arc::new(Expr::TypeCast(
                 local.data_type,
                 local.init.clone(),
             )));
             let chunk = OutputChunk::Stmt(vec![
-                OutputChunk::Str(if local.mutable { "var" } else { "let" }),
+                OutputChunk::Str(backend.local_decl(local.data_type, local.mutable)),
                 OutputChunk::Literal(local.name.clone()),
                 OutputChunk::Str("="),
-                codegen(init.as_ref()),
+                codegen(init.as_ref(), backend),
             ]);
-            chunk.format(&mut source, &mut wrap)?;
+            chunk.format(source, &mut wrap)?;
         }
 
         // Write return result
@@ -209,21 +386,12 @@ impl ShaderAssembly {
                 self.fragment_value.clone(),
             )))
             .as_ref(),
+            backend,
         )));
 
-        wrap.write_indent(&mut source)?;
-        out.format(&mut source, &mut wrap)?;
+        wrap.write_indent(source)?;
+        out.format(source, &mut wrap)?;
         source.write_str("\n")?;
-        source.write_str("}\n")?;
-
-        // Add includes
-        for include in &self.includes {
-            source.write_char('\n')?;
-            source.write_str(include)?;
-        }
-
-        // println!("Shader source:\n{}", source);
-        self.source = source;
         Ok(())
     }
 
@@ -57,6 +57,20 @@ impl<'w, 's> TerminalReader<'w, 's> {
         }
     }
 
+    /// Return the nodes which are connected to the inputs of a node.
+    pub(crate) fn upstream_nodes(&self, node_id: Entity) -> Vec<Entity> {
+        let Ok(node) = self.nodes.get(node_id) else {
+            return Vec::new();
+        };
+        node.inputs
+            .iter()
+            .filter_map(|(_, terminal_id)| self.terminals.get(*terminal_id).ok())
+            .flat_map(|terminal| terminal.connections.iter())
+            .filter_map(|connection_id| self.connections.get(*connection_id).ok())
+            .map(|connection| connection.output.node_id)
+            .collect()
+    }
+
     pub fn get_node_index(&self, node_id: Entity) -> GraphNodeId {
         self.nodes.get(node_id).unwrap().index
     }
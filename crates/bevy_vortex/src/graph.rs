@@ -1,5 +1,5 @@
 use bevy::{
-    ecs::{system::SystemState, world::Command},
+    ecs::world::Command,
     hierarchy::BuildChildren,
     math::IVec2,
     prelude::*,
@@ -9,14 +9,16 @@ use bevy::{
 use smallvec::SmallVec;
 
 use crate::{
-    gen::{Expr, ShaderAssembly, TerminalReader},
+    commands::MarkModifiedCmd,
+    gen::{DataType, Expr, ShaderAssembly, TerminalReader},
+    graph_view::DragState,
     operator::{Operator, OperatorInput, OperatorOutput},
 };
 
 #[derive(Resource, Default)]
 pub struct GraphResource(pub(crate) Graph);
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct GraphNodeId(pub(crate) usize);
 
 /// A component that indicates that a particular view is displaying the output of a node,
@@ -33,6 +35,9 @@ pub struct Graph {
     pub(crate) nodes: HashMap<GraphNodeId, Entity>,
     next_id: usize,
     pub(crate) connections: HashSet<Entity>,
+    pub(crate) notes: HashSet<Entity>,
+    /// Random seed for the operators which generate noise.
+    pub(crate) seed: u32,
     undo_stack: Vec<UndoAction>,
     redo_stack: Vec<UndoAction>,
 }
@@ -48,6 +53,11 @@ impl Graph {
         self.connections.iter()
     }
 
+    /// Return an iterator of the sticky notes on the graph.
+    pub fn iter_notes(&self) -> bevy::utils::hashbrown::hash_set::Iter<Entity> {
+        self.notes.iter()
+    }
+
     /// Create a new node, given an operator.
     pub fn create_node(
         &mut self,
@@ -66,6 +76,7 @@ impl Graph {
             operator,
             inputs: default(),
             outputs: default(),
+            bypass: false,
         };
         node.create_terminals(commands, entity);
         commands
@@ -137,6 +148,189 @@ impl Graph {
         self.redo_stack.clear();
         self.undo_stack.push(action);
     }
+
+    /// Revert the most recent action on the undo stack, and move it to the redo stack. Returns
+    /// false if there was nothing to undo.
+    pub fn undo(&mut self, world: &mut World) -> bool {
+        let Some(action) = self.undo_stack.pop() else {
+            return false;
+        };
+        let inverse = self.revert(world, action);
+        self.redo_stack.push(inverse);
+        true
+    }
+
+    /// Re-apply the most recently undone action. Returns false if there was nothing to redo.
+    pub fn redo(&mut self, world: &mut World) -> bool {
+        let Some(action) = self.redo_stack.pop() else {
+            return false;
+        };
+        let inverse = self.revert(world, action);
+        self.undo_stack.push(inverse);
+        true
+    }
+
+    /// Apply the inverse of each mutation in `action`, in reverse order, returning an action
+    /// which reverts the changes.
+    fn revert(&mut self, world: &mut World, action: UndoAction) -> UndoAction {
+        // Restored nodes get new terminal entities, so connections to the old terminals are
+        // remapped. Maps old terminal => (new terminal, new node).
+        let mut remap = HashMap::<Entity, (Entity, Entity)>::default();
+        let mut modified = Vec::<Entity>::new();
+        let mut inverse = UndoAction::new(action.label);
+        for mutation in action.mutations.into_iter().rev() {
+            match mutation {
+                UndoMutation::AddNode(node_id, _) => self.delete_node(world, node_id, &mut inverse),
+                UndoMutation::RemoveNode(node_id, node) => {
+                    let entity = self.restore_node(world, node_id, node, &mut remap);
+                    inverse
+                        .mutations
+                        .push(UndoMutation::AddNode(node_id, entity));
+                    modified.push(entity);
+                }
+                UndoMutation::AddConnection(connection) => {
+                    let connection = remap_connection(connection, &remap);
+                    let entity = self
+                        .connections
+                        .iter()
+                        .copied()
+                        .find(|id| world.get::<Connection>(*id) == Some(&connection));
+                    if let Some(entity) = entity {
+                        self.remove_connection(world, entity, &mut inverse);
+                        modified.push(connection.input.node_id);
+                    }
+                }
+                UndoMutation::RemoveConnection(connection) => {
+                    let connection = remap_connection(connection, &remap);
+                    let id = self.add_connection(world, connection, &mut inverse);
+                    for terminal in [connection.output.terminal_id, connection.input.terminal_id] {
+                        if let Some(mut terminal) = world.get_mut::<Terminal>(terminal) {
+                            terminal.connections.insert(id);
+                        }
+                    }
+                    modified.push(connection.input.node_id);
+                }
+            }
+        }
+
+        // Older actions may refer to the terminals of restored nodes as well.
+        if !remap.is_empty() {
+            for mutation in self
+                .undo_stack
+                .iter_mut()
+                .chain(self.redo_stack.iter_mut())
+                .flat_map(|action| action.mutations.iter_mut())
+            {
+                if let UndoMutation::AddConnection(connection)
+                | UndoMutation::RemoveConnection(connection) = mutation
+                {
+                    *connection = remap_connection(*connection, &remap);
+                }
+            }
+        }
+
+        for node_id in modified {
+            world.commands().add(MarkModifiedCmd { start: node_id });
+        }
+        inverse
+    }
+
+    /// Re-create a node which was removed by [`Graph::delete_node`], with new terminals.
+    fn restore_node(
+        &mut self,
+        world: &mut World,
+        node_id: GraphNodeId,
+        mut node: GraphNode,
+        remap: &mut HashMap<Entity, (Entity, Entity)>,
+    ) -> Entity {
+        let entity = world.spawn_empty().id();
+        let old_terminals: Vec<Entity> = node
+            .inputs
+            .iter()
+            .chain(node.outputs.iter())
+            .map(|t| t.1)
+            .collect();
+        node.inputs.clear();
+        node.outputs.clear();
+        node.create_terminals(&mut world.commands(), entity);
+        world.flush();
+        // Terminals are always created in field order, so they line up with the old ones.
+        for (old, new) in old_terminals
+            .into_iter()
+            .zip(node.inputs.iter().chain(node.outputs.iter()))
+        {
+            remap.insert(old, (new.1, entity));
+        }
+        world.entity_mut(entity).insert((node, NodeModified));
+        self.nodes.insert(node_id, entity);
+        entity
+    }
+}
+
+/// Replace references to the terminals of deleted nodes with their restored counterparts.
+fn remap_connection(
+    mut connection: Connection,
+    remap: &HashMap<Entity, (Entity, Entity)>,
+) -> Connection {
+    if let Some(&(terminal_id, node_id)) = remap.get(&connection.output.terminal_id) {
+        connection.output.terminal_id = terminal_id;
+        connection.output.node_id = node_id;
+    }
+    if let Some(&(terminal_id, node_id)) = remap.get(&connection.input.terminal_id) {
+        connection.input.terminal_id = terminal_id;
+        connection.input.node_id = node_id;
+    }
+    connection
+}
+
+/// Returns true if `to` can be reached by following the connections from the outputs of
+/// `from`, or if they are the same node.
+pub(crate) fn is_downstream(world: &World, from: Entity, to: Entity) -> bool {
+    let mut visited = HashSet::<Entity>::default();
+    let mut to_visit = vec![from];
+    while let Some(node_id) = to_visit.pop() {
+        if node_id == to {
+            return true;
+        }
+        if !visited.insert(node_id) {
+            continue;
+        }
+        let Some(node) = world.get::<GraphNode>(node_id) else {
+            continue;
+        };
+        for (_, terminal_id) in node.outputs.iter() {
+            let Some(terminal) = world.get::<Terminal>(*terminal_id) else {
+                continue;
+            };
+            to_visit.extend(
+                terminal
+                    .connections
+                    .iter()
+                    .filter_map(|conn_id| world.get::<Connection>(*conn_id))
+                    .map(|conn| conn.input.node_id),
+            );
+        }
+    }
+    false
+}
+
+/// Returns true if a connection can be made from the `output` terminal to the `input` terminal.
+/// The terminals must be an output and an input of two different nodes, and the connection must
+/// not create a loop. Data types are not checked, since mismatched types are always converted
+/// or coerced (see [`ConnectionDataType`]).
+pub(crate) fn can_connect(world: &World, output: Entity, input: Entity) -> bool {
+    let (Some(output_terminal), Some(input_terminal)) =
+        (world.get::<Terminal>(output), world.get::<Terminal>(input))
+    else {
+        return false;
+    };
+    let is_output = world
+        .get::<GraphNode>(output_terminal.node_id)
+        .is_some_and(|node| node.outputs.iter().any(|t| t.1 == output));
+    let is_input = world
+        .get::<GraphNode>(input_terminal.node_id)
+        .is_some_and(|node| node.inputs.iter().any(|t| t.1 == input));
+    is_output && is_input && !is_downstream(world, input_terminal.node_id, output_terminal.node_id)
 }
 
 /// Component indicating whether a graph node is selected.
@@ -145,6 +339,31 @@ impl Graph {
 #[derive(Component)]
 pub struct NodeSelected;
 
+/// A sticky note on the graph canvas. Notes are annotations only, and have no effect on the
+/// generated shader. They are selected, moved and deleted along with nodes.
+#[derive(Component, Clone)]
+pub struct GraphNote {
+    /// Position of the note's upper-left corner, relative to graph origin.
+    pub(crate) position: IVec2,
+    /// Size of the note.
+    pub(crate) size: Vec2,
+    /// Background color of the note.
+    pub(crate) color: Srgba,
+    /// Text of the note, as markdown.
+    pub(crate) text: String,
+}
+
+impl Default for GraphNote {
+    fn default() -> Self {
+        Self {
+            position: IVec2::ZERO,
+            size: Vec2::new(200., 120.),
+            color: Srgba::new(0.54, 0.455, 0.166, 1.0),
+            text: String::new(),
+        }
+    }
+}
+
 /// A node within a node graph. The behavior and attributes of the node are determined by the
 /// operator.
 // #[derive(Clone)]
@@ -162,6 +381,9 @@ pub struct GraphNode {
     pub(crate) inputs: SmallVec<[(&'static str, Entity); 4]>,
     /// List of output terminals, derived from operator, with computed positions.
     pub(crate) outputs: SmallVec<[(&'static str, Entity); 1]>,
+    /// If true, the node is bypassed: its primary input is passed through to the output
+    /// unchanged.
+    pub(crate) bypass: bool,
 }
 
 impl GraphNode {
@@ -169,6 +391,14 @@ impl GraphNode {
         self.operator.reflect_short_type_path()
     }
 
+    /// The rectangle occupied by the node, relative to the graph origin.
+    pub fn rect(&self) -> Rect {
+        Rect::from_corners(
+            self.position.as_vec2(),
+            (self.position + self.size).as_vec2(),
+        )
+    }
+
     pub fn operator_reflect(&self) -> &dyn Reflect {
         self.operator.as_reflect()
     }
@@ -177,6 +407,14 @@ impl GraphNode {
         self.operator.as_reflect_mut()
     }
 
+    /// The static name of the operator field called `name`, if there is one.
+    pub fn operator_field_name(&self, name: &str) -> Option<&'static str> {
+        match self.operator_reflect().get_represented_type_info()? {
+            TypeInfo::Struct(st_info) => st_info.field(name).map(|field| field.name()),
+            _ => None,
+        }
+    }
+
     /// For each node input or output, create an entry which holds the entity used to position
     /// that terminal on the graph view.
     fn create_terminals(&mut self, commands: &mut Commands, parent: Entity) {
@@ -197,7 +435,10 @@ impl GraphNode {
             let mut data_type = ConnectionDataType::Scalar;
             if type_name.contains("color") {
                 data_type = ConnectionDataType::Color;
-            } else if type_name == "Vec2" || type_name == "Vec3" || type_name == "Vec4" {
+            } else if matches!(
+                type_name,
+                "glam::Vec2" | "glam::Vec3" | "glam::Vec4" | "Vec2" | "Vec3" | "Vec4"
+            ) {
                 data_type = ConnectionDataType::Vector;
             }
             // println!("Field: {} ({})", name, type_name);
@@ -248,8 +489,43 @@ impl GraphNode {
         node_id: Entity,
         out_id: &str,
     ) -> Expr {
+        if self.bypass {
+            // Pass the first input through. If it's not connected, there is nothing to pass
+            // through, so the node is evaluated as usual.
+            if let Some(input) = self
+                .inputs
+                .first()
+                .and_then(|(name, _)| reader.read_input_terminal(assembly, node_id, name))
+            {
+                return match self.output_data_type(out_id) {
+                    Some(data_type) => input.cast(data_type),
+                    None => input,
+                };
+            }
+        }
         self.operator.gen(assembly, reader, node_id, out_id)
     }
+
+    /// Return the shader data type of the named output.
+    fn output_data_type(&self, name: &str) -> Option<DataType> {
+        let info = self.operator_reflect().get_represented_type_info()?;
+        let TypeInfo::Struct(st_info) = info else {
+            return None;
+        };
+        let type_name = st_info.field(name)?.type_path();
+        if type_name.contains("color") {
+            Some(DataType::LinearRgba)
+        } else {
+            match type_name {
+                "f32" => Some(DataType::F32),
+                "i32" => Some(DataType::I32),
+                "glam::Vec2" | "Vec2" => Some(DataType::Vec2),
+                "glam::Vec3" | "Vec3" => Some(DataType::Vec3),
+                "glam::Vec4" | "Vec4" => Some(DataType::Vec4),
+                _ => None,
+            }
+        }
+    }
 }
 
 impl Clone for GraphNode {
@@ -261,6 +537,7 @@ impl Clone for GraphNode {
             operator: self.operator.to_boxed_clone(),
             inputs: self.inputs.clone(),
             outputs: self.outputs.clone(),
+            bypass: self.bypass,
         }
     }
 }
@@ -270,10 +547,6 @@ impl Clone for GraphNode {
 #[derive(Component)]
 pub struct NodeModified;
 
-/// Component used to store the position of a node while dragging.
-#[derive(Component)]
-pub struct NodeBasePosition(pub IVec2);
-
 #[derive(Component, Clone, Debug)]
 pub struct Terminal {
     /// Entity id of the node that owns this terminal.
@@ -312,8 +585,10 @@ pub struct Connection {
     pub input: InputTerminalId,
 }
 
-/// The type of an input or output terminal. If the data type does not match, then
-/// values will be coerced to the proper type.
+/// The type of an input or output terminal. If the data type does not match, then a
+/// conversion node is inserted if one is registered in the
+/// [`ConversionRegistry`](crate::conversion::ConversionRegistry), otherwise values will be
+/// coerced to the proper type.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ConnectionDataType {
     /// A boolean value
@@ -325,7 +600,6 @@ pub enum ConnectionDataType {
 }
 
 /// Represents a user-level action which can be undone or redone.
-#[allow(dead_code)]
 pub struct UndoAction {
     label: &'static str,
     pub(crate) mutations: Vec<UndoMutation>,
@@ -349,7 +623,7 @@ pub enum UndoMutation {
     RemoveConnection(Connection),
 }
 
-#[allow(dead_code)]
+/// Checks whether the connection being dragged is valid, and updates the drag state.
 pub(crate) struct ValidateConnectionCmd {
     pub(crate) input: Entity,
     pub(crate) output: Entity,
@@ -357,16 +631,13 @@ pub(crate) struct ValidateConnectionCmd {
 
 impl Command for ValidateConnectionCmd {
     fn apply(self, world: &mut World) {
-        let mut st: SystemState<(ResMut<GraphResource>, Query<&Terminal>, Query<&GraphNode>)> =
-            SystemState::new(world);
-        // TODO: Need to inject DragState here
-        let (_, _terminals, _nodes) = st.get_mut(world);
-        // TODO: Validate connection:
-        // - can't connect to self
-        // - can't connect outputs to outputs
-        // - can't connect intputs to inputs
-        // - can't connect if data is incompatible
-        // - can't create loops
+        let valid = can_connect(world, self.output, self.input);
+        let mut drag_states = world.query::<&mut DragState>();
+        for mut drag_state in drag_states.iter_mut(world) {
+            if drag_state.valid_connection != valid {
+                drag_state.valid_connection = valid;
+            }
+        }
     }
 }
 
@@ -388,3 +659,90 @@ pub(crate) fn sync_connection_refs(world: &mut World) {
             }
         });
 }
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use bevy::{ecs::world::Command, math::IVec2, prelude::*};
+
+    use super::*;
+    use crate::commands::AddConnectionCmd;
+
+    /// Create a world containing an empty graph.
+    pub(crate) fn graph_world() -> World {
+        let mut world = World::new();
+        sync_connection_refs(&mut world);
+        world.init_resource::<GraphResource>();
+        world
+    }
+
+    /// Add a node to the graph, and return its entity.
+    pub(crate) fn add_node(world: &mut World, operator: Box<dyn Operator>) -> Entity {
+        let entity = world.resource_scope(|world, mut graph: Mut<GraphResource>| {
+            let mut action = UndoAction::new("Add Node");
+            let id = graph
+                .0
+                .create_node(&mut world.commands(), operator, IVec2::ZERO, &mut action);
+            graph.0.add_undo_action(action);
+            graph.0.nodes[&id]
+        });
+        world.flush();
+        entity
+    }
+
+    /// Connect the first output of `from` to the named input of `to`.
+    pub(crate) fn connect(world: &mut World, from: Entity, to: Entity, input: &'static str) {
+        let output = world.get::<GraphNode>(from).unwrap().outputs[0].1;
+        let input = world
+            .get::<GraphNode>(to)
+            .unwrap()
+            .get_input_terminal(input)
+            .unwrap();
+        AddConnectionCmd {
+            output,
+            input,
+            replace: None,
+        }
+        .apply(world);
+        world.flush();
+    }
+
+    /// The connections in the graph, as (output node, input node, input name). Also checks
+    /// that the terminals refer back to each connection.
+    pub(crate) fn edges(world: &World) -> std::collections::HashSet<(Entity, Entity, &str)> {
+        let graph = &world.resource::<GraphResource>().0;
+        graph
+            .iter_connections()
+            .map(|id| {
+                let conn = world.get::<Connection>(*id).unwrap();
+                for terminal in [conn.output.terminal_id, conn.input.terminal_id] {
+                    assert!(world
+                        .get::<Terminal>(terminal)
+                        .unwrap()
+                        .connections
+                        .contains(id));
+                }
+                (
+                    conn.output.node_id,
+                    conn.input.node_id,
+                    conn.input.terminal_name,
+                )
+            })
+            .collect()
+    }
+
+    /// Undo the most recent action.
+    pub(crate) fn undo(world: &mut World) -> bool {
+        let result =
+            world.resource_scope(|world, mut graph: Mut<GraphResource>| graph.0.undo(world));
+        world.flush();
+        result
+    }
+
+    /// Redo the most recently undone action.
+    pub(crate) fn redo(world: &mut World) -> bool {
+        let result =
+            world.resource_scope(|world, mut graph: Mut<GraphResource>| graph.0.redo(world));
+        world.flush();
+        result
+    }
+}
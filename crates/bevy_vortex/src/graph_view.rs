@@ -1,17 +1,26 @@
 use crate::{
-    graph::{Connection, GraphNode, GraphResource, NodeSelected, Terminal},
-    operator::{DisplayName, DisplayWidth, OperatorInput, OperatorInputOnly, OperatorOutput},
+    diff::{ConnectionSnapshot, GraphReview, NodeChange},
+    graph::{Connection, GraphNode, GraphNote, GraphResource, NodeSelected, Terminal},
+    heatmap::{heat_color, Heatmap},
+    operator::{
+        CompactDisplay, DisplayName, DisplayWidth, OperatorInput, OperatorInputOnly, OperatorOutput,
+    },
     propedit::GraphNodePropertyEdit,
 };
-use bevy::{color::Color, prelude::*, reflect::TypeInfo, ui};
+use bevy::{color::Color, prelude::*, reflect::TypeInfo, ui, utils::HashSet};
 use bevy_mod_stylebuilder::*;
 use bevy_quill::{prelude::*, Dynamic, IntoViewChild};
-use bevy_quill_obsidian::{colors, hooks::UseElementRect};
+use bevy_quill_obsidian::colors;
 use bevy_quill_obsidian_graph::{
-    ConnectionAnchor, ConnectionTarget, EdgeDisplay, GraphDisplay, InputTerminalDisplay,
-    NoTerminalDisplay, NodeDisplay, OutputTerminalDisplay,
+    ConnectionAnchor, ConnectionTarget, EdgeDisplay, GraphDisplay, GraphViewport,
+    InputTerminalDisplay, NoTerminalDisplay, NodeDisplay, NoteDisplay, OutputTerminalDisplay,
+    SpatialGrid, TerminalKind, TerminalShape,
 };
 
+/// Nodes within this distance of the visible region are still built, so that they are ready
+/// before they scroll into view.
+const CULL_MARGIN: f32 = 200.;
+
 fn style_node_graph(ss: &mut StyleBuilder) {
     ss.flex_grow(1.)
         .border_left(1)
@@ -19,10 +28,83 @@ fn style_node_graph(ss: &mut StyleBuilder) {
         .min_width(100);
 }
 
+/// Resource which indexes the graph nodes by the rectangle they occupy, so that the nodes
+/// within the visible region can be found without visiting every node.
+#[derive(Resource, Default)]
+pub(crate) struct NodeSpatialIndex(pub(crate) SpatialGrid<Entity>);
+
+/// Keep the [`NodeSpatialIndex`] up to date as nodes are added, moved, resized and removed.
+pub(crate) fn update_node_spatial_index(
+    mut index: ResMut<NodeSpatialIndex>,
+    nodes: Query<(Entity, &GraphNode), Changed<GraphNode>>,
+    mut removed: RemovedComponents<GraphNode>,
+) {
+    // Only mark the index as changed when it actually changes, since the graph view is
+    // rebuilt whenever it does.
+    for entity in removed.read() {
+        if index.0.get(entity).is_some() {
+            index.0.remove(entity);
+        }
+    }
+    for (entity, node) in nodes.iter() {
+        let rect = node.rect();
+        if index.0.get(entity) != Some(rect) {
+            index.0.insert(entity, rect);
+        }
+    }
+}
+
+/// Whether a node or note lies within the rectangle which is being dragged out to select nodes.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub(crate) struct InSelectionRect(pub(crate) bool);
+
+/// Store of which nodes and notes lie within the selection rectangle, by entity. Each node view
+/// subscribes to its own entry, so that dragging the rectangle only rebuilds the nodes which
+/// enter or leave it, rather than every node in the graph.
+pub(crate) type SelectionRectStore = KeyedStore<Entity, InSelectionRect>;
+
+/// Update the [`SelectionRectStore`] when the selection rectangle changes.
+pub(crate) fn update_selection_rect(world: &mut World, mut prev: Local<Option<Rect>>) {
+    let mut drag_states = world.query::<&DragState>();
+    let rect = drag_states
+        .iter(world)
+        .find_map(|drag_state| drag_state.selection_rect);
+    if rect == *prev {
+        return;
+    }
+    *prev = rect;
+    let Some(rect) = rect else {
+        SelectionRectStore::clear(world);
+        return;
+    };
+    let contains = |r: Rect| rect.contains(r.min) && rect.contains(r.max);
+    let mut nodes = world.query::<(Entity, &GraphNode)>();
+    let mut notes = world.query::<(Entity, &GraphNote)>();
+    let entries: Vec<(Entity, bool)> = nodes
+        .iter(world)
+        .map(|(entity, node)| (entity, contains(node.rect())))
+        .chain(notes.iter(world).map(|(entity, note)| {
+            let position = note.position.as_vec2();
+            (
+                entity,
+                contains(Rect::from_corners(position, position + note.size)),
+            )
+        }))
+        .collect();
+    for (entity, inside) in entries {
+        SelectionRectStore::set(world, entity, InSelectionRect(inside));
+    }
+}
+
 /// Component which stores the entity id of the graph view. Used for programmatic scrolling.
 #[derive(Component)]
 pub struct GraphViewId(pub(crate) Entity);
 
+/// Component which stores the entity id of the graph content element. Terminal positions are
+/// measured relative to this element.
+#[derive(Component)]
+pub(crate) struct GraphContentId(pub(crate) Entity);
+
 /// Component which stores the current dragging state.
 #[derive(Component, Default)]
 pub struct DragState {
@@ -34,6 +116,8 @@ pub struct DragState {
     pub(crate) valid_connection: bool,
     /// The rectangle to display when selecting by dragging.
     pub(crate) selection_rect: Option<Rect>,
+    /// Whether nodes are being moved by dragging.
+    pub(crate) moving_nodes: bool,
 }
 
 /// View template for graph. Entity is the id for the graph view.
@@ -43,16 +127,54 @@ pub struct GraphView;
 impl ViewTemplate for GraphView {
     type View = impl View;
     fn create(&self, cx: &mut Cx) -> Self::View {
-        let graph = cx.use_resource::<GraphResource>();
-        let node_ids: Vec<_> = graph.0.iter_nodes().map(|(_, v)| *v).collect();
-        let connection_ids: Vec<_> = graph.0.iter_connections().cloned().collect();
         let graph_view_id = cx.use_inherited_component::<GraphViewId>().unwrap().0;
+        let content_id = cx.create_entity();
+        cx.insert(GraphContentId(content_id));
+
+        // Don't build views for nodes which are off-screen. The viewport is empty until the
+        // first layout, in which case everything is built.
+        let viewport = cx
+            .use_component::<GraphViewport>(content_id)
+            .map(|v| v.0)
+            .filter(|v| !v.is_empty())
+            .map(|v| v.inflate(CULL_MARGIN));
+        let graph = cx.use_resource::<GraphResource>();
+        let index = cx.use_resource::<NodeSpatialIndex>();
+        let mut node_ids: Vec<_> = match viewport {
+            Some(viewport) => index
+                .0
+                .query(viewport)
+                .into_iter()
+                .map(|(node, _)| node)
+                // The index is updated after the graph, so it may still hold deleted nodes.
+                .filter(|node| cx.world().get::<GraphNode>(*node).is_some())
+                .collect(),
+            None => graph.0.iter_nodes().map(|(_, v)| *v).collect(),
+        };
+        // Keep the order of the nodes stable as the view scrolls.
+        node_ids.sort();
+        let visible: HashSet<Entity> = node_ids.iter().copied().collect();
+        // Connections are kept if either end is visible.
+        let connection_ids: Vec<_> = graph
+            .0
+            .iter_connections()
+            .filter(|conn| {
+                viewport.is_none()
+                    || cx.world().get::<Connection>(**conn).is_none_or(|c| {
+                        visible.contains(&c.output.node_id) || visible.contains(&c.input.node_id)
+                    })
+            })
+            .cloned()
+            .collect();
+        let note_ids: Vec<_> = graph.0.iter_notes().cloned().collect();
 
         GraphDisplay::new()
             .entity(graph_view_id)
+            .content_entity(content_id)
             .style(style_node_graph)
             .children((
                 SelectionRectView,
+                For::each(note_ids, |note| GraphNoteView(*note)),
                 For::each(connection_ids, |conn| ConnectionView(*conn)),
                 For::each(node_ids, |node| GraphNodeView(*node)),
                 ConnectionProxyView,
@@ -68,7 +190,7 @@ impl ViewTemplate for GraphNodeView {
     fn create(&self, cx: &mut Cx) -> Self::View {
         let display_id = cx.create_entity();
         let node_id = self.0;
-        let size = cx.use_element_size(display_id);
+        let size = cx.use_measured_size(display_id).unwrap_or_default();
         let node = cx.use_component::<GraphNode>(node_id).unwrap();
         if node.size != size.as_ivec2() {
             // Save the node size
@@ -76,15 +198,10 @@ impl ViewTemplate for GraphNodeView {
             let mut node = entt.get_mut::<GraphNode>().unwrap();
             node.size = size.as_ivec2();
         }
+        let is_selected = cx.use_keyed::<Entity, InSelectionRect>(&node_id).0
+            || cx.use_component::<NodeSelected>(node_id).is_some();
         let node = cx.use_component::<GraphNode>(node_id).unwrap();
 
-        let relative_rect = Rect::from_center_size(node.position.as_vec2(), size);
-        let drag_state = cx.use_inherited_component::<DragState>().unwrap();
-        let is_selected = cx.use_component::<NodeSelected>(node_id).is_some()
-            || drag_state.selection_rect.map_or_else(
-                || false,
-                |r| r.contains(relative_rect.min) && r.contains(relative_rect.max),
-            );
         let reflect = node.operator_reflect();
         let info = reflect.get_represented_type_info().unwrap();
         let TypeInfo::Struct(st_info) = info else {
@@ -105,10 +222,30 @@ impl ViewTemplate for GraphNodeView {
             names
         };
 
-        NodeDisplay::new(display_id, node_id)
+        let mut display = NodeDisplay::new(display_id, node_id);
+        if st_info.custom_attributes().contains::<CompactDisplay>() {
+            // Conversion nodes are drawn in a subdued color, since they were added
+            // automatically.
+            display = display.title_color(colors::U2);
+        }
+        if node.bypass {
+            // Grey out bypassed nodes.
+            display = display.title_color(colors::U3);
+        }
+        match cx.use_resource::<GraphReview>().node_change(node) {
+            Some(NodeChange::Added) => display = display.title_color(colors::RESOURCE),
+            Some(NodeChange::Changed) => display = display.title_color(colors::LIGHT),
+            _ => {}
+        }
+        let mut title = node.title().to_string();
+        if let Some((cost, fraction)) = cx.use_resource::<Heatmap>().node_cost(node_id) {
+            display = display.title_color(heat_color(fraction));
+            title = format!("{} ({})", title, cost);
+        }
+        display
             .position(node.position)
             .width(display_width)
-            .title(node.title())
+            .title(title)
             .selected(is_selected)
             .children(For::each(field_names, move |field| GraphNodePropertyView {
                 node: node_id,
@@ -117,6 +254,37 @@ impl ViewTemplate for GraphNodeView {
     }
 }
 
+#[derive(Clone, PartialEq)]
+pub struct GraphNoteView(Entity);
+
+impl ViewTemplate for GraphNoteView {
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let display_id = cx.create_entity();
+        let note_id = self.0;
+        let note = cx
+            .use_component::<GraphNote>(note_id)
+            .cloned()
+            .unwrap_or_default();
+        let is_selected = cx.use_keyed::<Entity, InSelectionRect>(&note_id).0
+            || cx.use_component::<NodeSelected>(note_id).is_some();
+        let on_change =
+            cx.create_callback(move |text: In<String>, mut notes: Query<&mut GraphNote>| {
+                if let Ok(mut note) = notes.get_mut(note_id) {
+                    note.text = text.0;
+                }
+            });
+
+        NoteDisplay::new(display_id, note_id)
+            .position(note.position)
+            .size(note.size)
+            .color(note.color)
+            .text(note.text)
+            .selected(is_selected)
+            .on_change(on_change)
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct GraphNodePropertyView {
     node: Entity,
@@ -150,6 +318,8 @@ impl ViewTemplate for GraphNodePropertyView {
                     InputTerminalDisplay {
                         id,
                         color: get_terminal_color(cx, id),
+                        shape: get_terminal_shape(cx, id),
+                        kind: TerminalKind::Data,
                         control: GraphNodePropertyEdit {
                             node: self.node,
                             display_name,
@@ -165,6 +335,8 @@ impl ViewTemplate for GraphNodePropertyView {
                     OutputTerminalDisplay {
                         id,
                         color: get_terminal_color(cx, id),
+                        shape: get_terminal_shape(cx, id),
+                        kind: TerminalKind::Data,
                         label: display_name.to_string(),
                     }
                     .into_view_child()
@@ -199,15 +371,34 @@ impl ViewTemplate for ConnectionView {
                 src_color: colors::U3,
                 dst_color: colors::U3,
                 hidden: true,
+                ..default()
             };
         };
         let Connection { output, input } = connection;
-        let src_pos = get_terminal_position(cx, output.terminal_id);
-        let dst_pos = get_terminal_position(cx, input.terminal_id);
-        let src_color = get_terminal_edge_color(cx, output.terminal_id);
-        let dst_color = get_terminal_edge_color(cx, input.terminal_id);
-
         let drag_state = cx.use_inherited_component::<DragState>().unwrap();
+        // While nodes are being dragged, the edge display follows the terminals by itself, so
+        // there's no need to rebuild the connection every time they move.
+        let (src_pos, dst_pos) = if drag_state.moving_nodes {
+            (
+                get_terminal_position_untracked(cx, output.terminal_id),
+                get_terminal_position_untracked(cx, input.terminal_id),
+            )
+        } else {
+            (
+                get_terminal_position(cx, output.terminal_id),
+                get_terminal_position(cx, input.terminal_id),
+            )
+        };
+        let mut src_color = get_terminal_edge_color(cx, output.terminal_id);
+        let mut dst_color = get_terminal_edge_color(cx, input.terminal_id);
+        let review = cx.use_resource::<GraphReview>();
+        if ConnectionSnapshot::capture(cx.world(), connection)
+            .is_some_and(|c| review.is_connection_added(&c))
+        {
+            src_color = colors::RESOURCE;
+            dst_color = colors::RESOURCE;
+        }
+
         let hidden = match drag_state.connect_from {
             Some(ConnectionAnchor::EdgeSink(edge)) | Some(ConnectionAnchor::EdgeSource(edge)) => {
                 edge == self.0
@@ -218,10 +409,13 @@ impl ViewTemplate for ConnectionView {
         EdgeDisplay {
             edge_id: Some(self.0),
             src_pos,
+            src_terminal: Some(output.terminal_id),
             dst_pos,
+            dst_terminal: Some(input.terminal_id),
             src_color,
             dst_color,
             hidden,
+            ..default()
         }
     }
 }
@@ -276,6 +470,33 @@ impl ViewTemplate for ConnectionProxyView {
             }
             None => (IVec2::default(), IVec2::default(), colors::U3, colors::U3),
         };
+        // The loose end of the edge is marked with the shape of the terminal it's dragged from.
+        let connect_to = drag_state.connect_to;
+        let (src_marker, dst_marker) = match drag_state.connect_from {
+            Some(ConnectionAnchor::OutputTerminal(term)) => {
+                (None, get_target_marker(cx, connect_to, term))
+            }
+            Some(ConnectionAnchor::InputTerminal(term)) => {
+                (get_target_marker(cx, connect_to, term), None)
+            }
+            Some(ConnectionAnchor::EdgeSink(edge)) => {
+                let output = cx
+                    .use_component::<Connection>(edge)
+                    .unwrap()
+                    .output
+                    .terminal_id;
+                (None, get_target_marker(cx, connect_to, output))
+            }
+            Some(ConnectionAnchor::EdgeSource(edge)) => {
+                let input = cx
+                    .use_component::<Connection>(edge)
+                    .unwrap()
+                    .input
+                    .terminal_id;
+                (get_target_marker(cx, connect_to, input), None)
+            }
+            None => (None, None),
+        };
         Cond::new(
             drag_state.connect_from.is_some(),
             EdgeDisplay {
@@ -284,7 +505,10 @@ impl ViewTemplate for ConnectionProxyView {
                 dst_pos,
                 src_color,
                 dst_color,
+                src_marker,
+                dst_marker,
                 hidden: false,
+                ..default()
             },
             (),
         )
@@ -292,8 +516,36 @@ impl ViewTemplate for ConnectionProxyView {
 }
 
 fn get_terminal_position(cx: &Cx, terminal_id: Entity) -> IVec2 {
-    let rect = get_relative_rect(cx, terminal_id, 4);
-    rect.map_or(IVec2::default(), |f| f.center().as_ivec2())
+    let content_id = cx.use_inherited_component::<GraphContentId>().unwrap().0;
+    match cx.rect_relative_to(terminal_id, content_id) {
+        Some(rect) => rect.center().as_ivec2(),
+        // The terminal's node may have been culled, so use the position of the node instead.
+        None => cx
+            .use_component::<Terminal>(terminal_id)
+            .and_then(|terminal| cx.use_component::<GraphNode>(terminal.node_id))
+            .map_or(IVec2::default(), |node| node.position),
+    }
+}
+
+/// Same as [`get_terminal_position`], but without subscribing to changes in the layout.
+fn get_terminal_position_untracked(cx: &Cx, terminal_id: Entity) -> IVec2 {
+    let content_id = cx.use_inherited_component::<GraphContentId>().unwrap().0;
+    let world = cx.world();
+    match (
+        world.get::<GlobalTransform>(terminal_id),
+        world.get::<Node>(content_id),
+        world.get::<GlobalTransform>(content_id),
+    ) {
+        (Some(transform), Some(content_node), Some(content_transform)) => {
+            let position = content_transform
+                .affine()
+                .inverse()
+                .transform_point3(transform.translation())
+                .truncate();
+            (position + content_node.size() * 0.5).as_ivec2()
+        }
+        _ => get_terminal_position(cx, terminal_id),
+    }
 }
 
 fn get_terminal_color(cx: &Cx, terminal_id: Entity) -> Srgba {
@@ -308,6 +560,19 @@ fn get_terminal_color(cx: &Cx, terminal_id: Entity) -> Srgba {
     }
 }
 
+/// Terminals are shaped according to their data type, so that they can be told apart without
+/// relying on color alone.
+fn get_terminal_shape(cx: &Cx, terminal_id: Entity) -> TerminalShape {
+    match cx.use_component::<Terminal>(terminal_id) {
+        Some(terminal) => match terminal.data_type {
+            crate::graph::ConnectionDataType::Scalar => TerminalShape::Circle,
+            crate::graph::ConnectionDataType::Vector => TerminalShape::Diamond,
+            crate::graph::ConnectionDataType::Color => TerminalShape::Square,
+        },
+        None => TerminalShape::Circle,
+    }
+}
+
 fn get_terminal_edge_color(cx: &Cx, terminal_id: Entity) -> Srgba {
     get_terminal_color(cx, terminal_id).mix(&Srgba::BLACK, 0.3)
 }
@@ -322,6 +587,19 @@ fn get_target_position(cx: &Cx, target: Option<ConnectionTarget>) -> IVec2 {
     }
 }
 
+/// Returns the glyph to draw at the loose end of a connection being dragged from `anchor`, or
+/// `None` if the end is over a terminal.
+fn get_target_marker(
+    cx: &Cx,
+    target: Option<ConnectionTarget>,
+    anchor: Entity,
+) -> Option<TerminalShape> {
+    match target {
+        Some(ConnectionTarget::InputTerminal(_) | ConnectionTarget::OutputTerminal(_)) => None,
+        _ => Some(get_terminal_shape(cx, anchor)),
+    }
+}
+
 fn get_target_color(cx: &Cx, target: Option<ConnectionTarget>) -> Srgba {
     match target {
         Some(ConnectionTarget::InputTerminal(term) | ConnectionTarget::OutputTerminal(term)) => {
@@ -331,27 +609,6 @@ fn get_target_color(cx: &Cx, target: Option<ConnectionTarget>) -> Srgba {
     }
 }
 
-fn get_relative_rect(cx: &Cx, id: Entity, levels: usize) -> Option<Rect> {
-    cx.world().get_entity(id)?;
-    let node = cx.use_component::<Node>(id)?;
-    let transform = cx.use_component::<GlobalTransform>(id)?;
-    let mut rect = node.logical_rect(transform);
-    let mut current = id;
-    for _ in 0..levels {
-        if let Some(parent) = cx.use_component::<Parent>(current) {
-            current = parent.get();
-        } else {
-            return None;
-        }
-    }
-    let node = cx.use_component::<Node>(current)?;
-    let transform = cx.use_component::<GlobalTransform>(current)?;
-    let ancestor_rect = node.logical_rect(transform);
-    rect.min -= ancestor_rect.min;
-    rect.max -= ancestor_rect.min;
-    Some(rect)
-}
-
 fn style_selection_rect(ss: &mut StyleBuilder) {
     ss.background_color(colors::TEXT_SELECT.with_alpha(0.02))
         .border_color(colors::TEXT_SELECT.with_alpha(0.1))
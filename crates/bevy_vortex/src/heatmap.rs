@@ -0,0 +1,69 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_quill_obsidian::colors;
+
+use crate::{
+    gen::{estimate_node_costs, TerminalReader},
+    graph::{Connection, GraphNode, GraphResource},
+};
+
+/// Resource which holds the estimated GPU cost of each node. While the heatmap is enabled,
+/// the graph view tints each node according to its share of the cost of the graph.
+#[derive(Resource, Default)]
+pub struct Heatmap {
+    pub(crate) enabled: bool,
+    /// Estimated cost of each node, in approximate shader instructions.
+    pub(crate) costs: HashMap<Entity, u32>,
+    /// The largest cost of any node.
+    pub(crate) max_cost: u32,
+}
+
+impl Heatmap {
+    /// The estimated cost of a node, and that cost as a fraction of the largest node cost.
+    /// Returns `None` if the heatmap is not enabled.
+    pub fn node_cost(&self, node_id: Entity) -> Option<(u32, f32)> {
+        if !self.enabled {
+            return None;
+        }
+        let cost = self.costs.get(&node_id).copied().unwrap_or(0);
+        Some((cost, cost as f32 / self.max_cost.max(1) as f32))
+    }
+}
+
+/// Color on the heatmap scale for a fraction from 0 (cheap) to 1 (expensive).
+pub fn heat_color(fraction: f32) -> Srgba {
+    let fraction = fraction.clamp(0., 1.);
+    if fraction < 0.5 {
+        colors::Y_GREEN.mix(&colors::LIGHT, fraction * 2.)
+    } else {
+        colors::LIGHT.mix(&colors::X_RED, fraction * 2. - 1.)
+    }
+}
+
+/// Re-estimate the node costs when the graph changes while the heatmap is enabled.
+pub(crate) fn update_heatmap(
+    mut heatmap: ResMut<Heatmap>,
+    graph: Res<GraphResource>,
+    reader: TerminalReader,
+    changed_nodes: Query<(), Changed<GraphNode>>,
+    added_connections: Query<(), Added<Connection>>,
+    mut removed_connections: RemovedComponents<Connection>,
+    mut was_enabled: Local<bool>,
+) {
+    let connections_removed = removed_connections.read().count() > 0;
+    let just_enabled = heatmap.enabled && !*was_enabled;
+    *was_enabled = heatmap.enabled;
+    if !heatmap.enabled {
+        return;
+    }
+    if !just_enabled
+        && changed_nodes.is_empty()
+        && added_connections.is_empty()
+        && !connections_removed
+    {
+        return;
+    }
+    let node_ids = graph.0.iter_nodes().map(|(_, node_id)| *node_id);
+    let costs = estimate_node_costs(&reader, node_ids);
+    heatmap.max_cost = costs.values().copied().max().unwrap_or(0);
+    heatmap.costs = costs;
+}
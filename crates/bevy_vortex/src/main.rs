@@ -1,18 +1,32 @@
 #![feature(impl_trait_in_assoc_type)]
 
 mod add_node;
+mod animation;
+mod autosave;
 mod catalog;
+mod cli;
+mod color_management;
 mod commands;
+mod conversion;
+mod diff;
 mod gen;
 mod graph;
 mod graph_view;
+mod heatmap;
 mod operator;
 mod ops;
 mod pipeline;
+mod preset;
 mod preview;
 mod propedit;
+mod readback;
+mod scopes;
+mod timeline;
+mod variations;
 
 use add_node::AddNodeButton;
+use animation::{play_timeline, Timeline};
+use autosave::AutosavePlugin;
 use bevy_mod_picking::{
     debug::DebugPickingMode,
     picking_core::Pickable,
@@ -22,24 +36,39 @@ use bevy_mod_picking::{
 use bevy_mod_stylebuilder::*;
 use bevy_quill_obsidian::{
     colors,
-    controls::{Splitter, SplitterDirection},
+    controls::{DialogHost, Splitter, SplitterDirection},
     focus::{DefaultKeyListener, KeyPressEvent, TabGroup},
+    layout::{LayoutPersistence, SavedLayout, SplitterPosition},
     typography, viewport, ObsidianUiPlugin,
 };
 use bevy_quill_obsidian_graph::{
     ConnectionAnchor, ConnectionTarget, DragAction, Gesture, GraphEvent, ObsidianGraphPlugin,
 };
 use catalog::{build_operator_catalog, CatalogView, OperatorCatalog, SelectedCatalogEntry};
-use commands::{AddConnectionCmd, DeleteSelectedCmd};
+use color_management::{apply_view_transform, rebuild_on_working_space_change, ColorManagement};
+use commands::{
+    AddConnectionCmd, AddNoteCmd, DeleteSelectedCmd, KeyframeSelectedCmd, RedoCmd, SpliceNodeCmd,
+    ToggleBypassCmd, ToggleReviewCmd, UndoCmd,
+};
+use diff::GraphReview;
 use gen::{begin_build_shaders, finish_build_shaders};
 use graph::{
-    sync_connection_refs, Connection, GraphNode, GraphResource, NodeBasePosition, NodeSelected,
+    sync_connection_refs, Connection, GraphNode, GraphNote, GraphResource, NodeSelected,
     ValidateConnectionCmd,
 };
-use graph_view::{DragState, GraphView, GraphViewId};
+use graph_view::{
+    update_node_spatial_index, update_selection_rect, DragState, GraphView, GraphViewId,
+    NodeSpatialIndex, SelectionRectStore,
+};
+use heatmap::{update_heatmap, Heatmap};
 use ops::OperatorsPlugin;
 use pipeline::NodeShaderMeshPlugin;
+use preset::{load_presets, save_preset_thumbnails, PresetLibrary, PresetListView};
 use preview::{PreviewControls, PreviewPlugin};
+use readback::ImageReadbackPlugin;
+use scopes::{ScopesPanel, ScopesPlugin};
+use timeline::TimelinePanel;
+use variations::{VariationsPanel, VariationsPlugin};
 
 use bevy::{asset::embedded_asset, prelude::*, ui};
 use bevy_quill::*;
@@ -81,28 +110,57 @@ fn style_viewport(ss: &mut StyleBuilder) {
 #[derive(Resource)]
 pub struct PanelWidth(f32);
 
+/// Restore the width of the side panel from the previous session.
+fn restore_panel_width(layout: Res<SavedLayout>, mut panel_width: ResMut<PanelWidth>) {
+    panel_width.0 = layout
+        .splitter("side_panel", SplitterPosition::Pixels(panel_width.0))
+        .to_pixels(0.);
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("export") {
+        std::process::exit(cli::export(&args[1..]));
+    }
+
     App::new()
         .init_resource::<OperatorCatalog>()
+        .init_resource::<ColorManagement>()
         .init_resource::<GraphResource>()
+        .init_resource::<GraphReview>()
+        .init_resource::<Heatmap>()
+        .init_resource::<NodeSpatialIndex>()
+        .init_resource::<PresetLibrary>()
         .init_resource::<SelectedCatalogEntry>()
+        .init_resource::<SelectionRectStore>()
+        .init_resource::<Timeline>()
         .insert_resource(PanelWidth(300.))
         .init_resource::<viewport::ViewportInset>()
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
         .add_plugins((DefaultPickingPlugins,))
         .insert_resource(DebugPickingMode::Disabled)
         .add_plugins((
+            ImageReadbackPlugin,
             PreviewPlugin,
+            ScopesPlugin,
+            VariationsPlugin,
+            AutosavePlugin,
             QuillPlugin,
             ObsidianUiPlugin,
             ObsidianGraphPlugin,
+            LayoutPersistence::new("vortex"),
             VortexPlugin,
             OperatorsPlugin,
             NodeShaderMeshPlugin,
         ))
         .add_systems(
             Startup,
-            (sync_connection_refs, setup_ui.pipe(setup_view_root)),
+            (
+                sync_connection_refs,
+                load_presets,
+                restore_panel_width,
+                setup_ui.pipe(setup_view_root),
+            ),
         )
         .add_systems(
             Update,
@@ -111,8 +169,15 @@ fn main() {
                 build_operator_catalog,
                 viewport::update_viewport_inset,
                 viewport::update_camera_viewport,
+                play_timeline.before(begin_build_shaders),
+                rebuild_on_working_space_change.before(begin_build_shaders),
                 begin_build_shaders,
                 finish_build_shaders,
+                update_heatmap,
+                apply_view_transform,
+                save_preset_thumbnails,
+                update_node_spatial_index,
+                update_selection_rect,
             ),
         )
         .run();
@@ -164,11 +229,38 @@ impl ViewTemplate for VortexUi {
                         TargetCamera(camera),
                         DefaultKeyListener,
                         On::<KeyPressEvent>::run(
-                            |event: Listener<KeyPressEvent>, mut commands: Commands| {
-                                if event.key_code == KeyCode::Delete
+                            |event: Listener<KeyPressEvent>,
+                             mut commands: Commands,
+                             keys: Res<ButtonInput<KeyCode>>,
+                             mut heatmap: ResMut<Heatmap>| {
+                                let ctrl = keys.any_pressed([
+                                    KeyCode::ControlLeft,
+                                    KeyCode::ControlRight,
+                                    KeyCode::SuperLeft,
+                                    KeyCode::SuperRight,
+                                ]);
+                                if ctrl && event.key_code == KeyCode::KeyZ {
+                                    if event.shift {
+                                        commands.add(RedoCmd);
+                                    } else {
+                                        commands.add(UndoCmd);
+                                    }
+                                } else if event.key_code == KeyCode::Delete
                                     || event.key_code == KeyCode::Backspace
                                 {
                                     commands.add(DeleteSelectedCmd);
+                                } else if event.key_code == KeyCode::KeyM
+                                    || event.key_code == KeyCode::KeyB
+                                {
+                                    commands.add(ToggleBypassCmd);
+                                } else if event.key_code == KeyCode::KeyD {
+                                    commands.add(ToggleReviewCmd);
+                                } else if event.key_code == KeyCode::KeyK {
+                                    commands.add(KeyframeSelectedCmd);
+                                } else if event.key_code == KeyCode::KeyH {
+                                    heatmap.enabled = !heatmap.enabled;
+                                } else if event.key_code == KeyCode::KeyN {
+                                    commands.add(AddNoteCmd);
                                 }
                             },
                         ),
@@ -189,6 +281,7 @@ impl ViewTemplate for VortexUi {
                     .children((
                         AddNodeButton,
                         CatalogView,
+                        PresetListView,
                         PreviewControls,
                         Element::<NodeBundle>::new()
                             .named("Preview")
@@ -200,6 +293,8 @@ impl ViewTemplate for VortexUi {
                                 panel_width,
                             )
                             .insert((viewport::ViewportInsetElement, Pickable::IGNORE)),
+                        ScopesPanel,
+                        VariationsPanel,
                     )),
                 Splitter::new()
                     .direction(SplitterDirection::Vertical)
@@ -207,8 +302,13 @@ impl ViewTemplate for VortexUi {
                     .on_change(cx.create_callback(|value: In<f32>, world: &mut World| {
                         let mut panel_width = world.get_resource_mut::<PanelWidth>().unwrap();
                         panel_width.0 = value.clamp(200., 800.);
+                        let width = panel_width.0;
+                        world
+                            .resource_mut::<SavedLayout>()
+                            .set_splitter("side_panel", SplitterPosition::Pixels(width));
                     })),
                 CenterPanel,
+                DialogHost,
             ))
     }
 }
@@ -238,42 +338,37 @@ impl ViewTemplate for CenterPanel {
                             Entity,
                             &mut GraphNode,
                             Option<&NodeSelected>,
-                            Option<&NodeBasePosition>,
+                        )>,
+                         mut query_notes: Query<(
+                            Entity,
+                            &mut GraphNote,
+                            Option<&NodeSelected>,
                         )>,
                          mut query_connections: Query<&mut Connection>| {
                             let mut drag_state = query_drag_state.single_mut();
                             match event.gesture {
                                 // Move nodes by dragging.
-                                Gesture::Move(position, action) => {
-                                    let offset = position.as_ivec2();
-                                    match action {
-                                        DragAction::Start => {
-                                            for (ent, node, selected, _base) in
-                                                query_graph_nodes.iter_mut()
-                                            {
-                                                if selected.is_some() {
-                                                    commands
-                                                        .entity(ent)
-                                                        .insert(NodeBasePosition(node.position));
-                                                }
-                                            }
-                                        }
-
-                                        DragAction::Update => {
-                                            for (_, mut node, _, base) in
-                                                query_graph_nodes.iter_mut()
-                                            {
-                                                if let Some(base) = base {
-                                                    node.position = base.0 + offset;
-                                                }
-                                            }
+                                Gesture::Move(ref positions, action) => {
+                                    let moving = action != DragAction::Finish;
+                                    if drag_state.moving_nodes != moving {
+                                        drag_state.moving_nodes = moving;
+                                    }
+                                    for (node_entity, position) in positions.iter() {
+                                        if let Ok((_, mut node, _)) =
+                                            query_graph_nodes.get_mut(*node_entity)
+                                        {
+                                            node.position = position.as_ivec2();
+                                        } else if let Ok((_, mut note, _)) =
+                                            query_notes.get_mut(*node_entity)
+                                        {
+                                            note.position = position.as_ivec2();
                                         }
+                                    }
+                                }
 
-                                        DragAction::Finish => {
-                                            for (ent, _, _, _) in query_graph_nodes.iter_mut() {
-                                                commands.entity(ent).remove::<NodeBasePosition>();
-                                            }
-                                        }
+                                Gesture::Resize(note_id, size, _) => {
+                                    if let Ok((_, mut note, _)) = query_notes.get_mut(note_id) {
+                                        note.size = size;
                                     }
                                 }
 
@@ -291,15 +386,15 @@ impl ViewTemplate for CenterPanel {
                                             }
 
                                             (
-                                                ConnectionAnchor::InputTerminal(output),
-                                                ConnectionTarget::OutputTerminal(input),
+                                                ConnectionAnchor::InputTerminal(input),
+                                                ConnectionTarget::OutputTerminal(output),
                                             ) => {
                                                 commands
                                                     .add(ValidateConnectionCmd { output, input });
                                             }
 
                                             (
-                                                ConnectionAnchor::EdgeSink(edge),
+                                                ConnectionAnchor::EdgeSource(edge),
                                                 ConnectionTarget::OutputTerminal(output),
                                             ) => {
                                                 let conn = query_connections.get_mut(edge).unwrap();
@@ -309,7 +404,7 @@ impl ViewTemplate for CenterPanel {
                                             }
 
                                             (
-                                                ConnectionAnchor::EdgeSource(edge),
+                                                ConnectionAnchor::EdgeSink(edge),
                                                 ConnectionTarget::InputTerminal(input),
                                             ) => {
                                                 let conn = query_connections.get_mut(edge).unwrap();
@@ -325,6 +420,12 @@ impl ViewTemplate for CenterPanel {
                                     }
 
                                     DragAction::Finish => {
+                                        // Connections which failed validation are dropped.
+                                        let target = if drag_state.valid_connection {
+                                            target
+                                        } else {
+                                            ConnectionTarget::None
+                                        };
                                         match (anchor, target) {
                                             (
                                                 ConnectionAnchor::OutputTerminal(output),
@@ -338,8 +439,8 @@ impl ViewTemplate for CenterPanel {
                                             }
 
                                             (
-                                                ConnectionAnchor::InputTerminal(output),
-                                                ConnectionTarget::OutputTerminal(input),
+                                                ConnectionAnchor::InputTerminal(input),
+                                                ConnectionTarget::OutputTerminal(output),
                                             ) => {
                                                 commands.add(AddConnectionCmd {
                                                     output,
@@ -386,38 +487,64 @@ impl ViewTemplate for CenterPanel {
                                 // bevy_quill_obsidian_graph::Gesture::Scroll(_) => todo!(),
                                 Gesture::SelectRect(rect, action) => {
                                     if action == DragAction::Finish {
-                                        for (node_entity, node, _, _) in
-                                            query_graph_nodes.iter_mut()
-                                        {
-                                            let node_rect = Rect::from_center_size(
-                                                node.position.as_vec2(),
-                                                node.size.as_vec2(),
-                                            );
+                                        for (node_entity, node, _) in query_graph_nodes.iter_mut() {
+                                            let node_rect = node.rect();
                                             if rect.contains(node_rect.min)
                                                 && rect.contains(node_rect.max)
                                             {
                                                 commands.entity(node_entity).insert(NodeSelected);
                                             }
                                         }
+                                        for (note_entity, note, _) in query_notes.iter() {
+                                            let note_rect = Rect::from_corners(
+                                                note.position.as_vec2(),
+                                                note.position.as_vec2() + note.size,
+                                            );
+                                            if rect.contains(note_rect.min)
+                                                && rect.contains(note_rect.max)
+                                            {
+                                                commands.entity(note_entity).insert(NodeSelected);
+                                            }
+                                        }
                                         drag_state.selection_rect = None;
                                     } else {
                                         drag_state.selection_rect = Some(rect);
                                     }
                                 }
 
+                                Gesture::Splice(node, connection) => {
+                                    commands.add(SpliceNodeCmd { node, connection });
+                                }
+
+                                // The graph view doesn't support zooming yet.
+                                Gesture::Zoom(..) => {}
+
+                                // Nodes within the lasso are reported via `SelectAdd`.
+                                Gesture::SelectLasso(..) => {}
+
                                 Gesture::Select(node) => {
                                     catalog_selection.0 = None;
                                     let is_selected = query_graph_nodes
                                         .get_mut(node)
-                                        .map_or(false, |(_, _, selected, _)| selected.is_some());
+                                        .map_or(false, |(_, _, selected)| selected.is_some())
+                                        || query_notes
+                                            .get(node)
+                                            .map_or(false, |(_, _, selected)| selected.is_some());
                                     if !is_selected {
-                                        for (ent, _, selected, _) in query_graph_nodes.iter_mut() {
+                                        for (ent, _, selected) in query_graph_nodes.iter_mut() {
                                             if ent == node {
                                                 commands.entity(node).insert(NodeSelected);
                                             } else if selected.is_some() {
                                                 commands.entity(node).remove::<NodeSelected>();
                                             }
                                         }
+                                        for (ent, _, selected) in query_notes.iter() {
+                                            if ent == node {
+                                                commands.entity(ent).insert(NodeSelected);
+                                            } else if selected.is_some() {
+                                                commands.entity(ent).remove::<NodeSelected>();
+                                            }
+                                        }
                                     }
                                 }
 
@@ -432,8 +559,13 @@ impl ViewTemplate for CenterPanel {
 
                                 Gesture::SelectToggle(node) => {
                                     catalog_selection.0 = None;
-                                    if let Ok((_, _, selected, _)) = query_graph_nodes.get_mut(node)
-                                    {
+                                    if let Ok((_, _, selected)) = query_graph_nodes.get_mut(node) {
+                                        if selected.is_some() {
+                                            commands.entity(node).remove::<NodeSelected>();
+                                        } else {
+                                            commands.entity(node).insert(NodeSelected);
+                                        }
+                                    } else if let Ok((_, _, selected)) = query_notes.get(node) {
                                         if selected.is_some() {
                                             commands.entity(node).remove::<NodeSelected>();
                                         } else {
@@ -443,14 +575,20 @@ impl ViewTemplate for CenterPanel {
                                 }
 
                                 Gesture::SelectClear => {
-                                    for (node, _, selected, _) in query_graph_nodes.iter_mut() {
+                                    for (node, _, selected) in query_graph_nodes.iter_mut() {
                                         if selected.is_some() {
                                             commands.entity(node).remove::<NodeSelected>();
                                         }
                                     }
+                                    for (note, _, selected) in query_notes.iter() {
+                                        if selected.is_some() {
+                                            commands.entity(note).remove::<NodeSelected>();
+                                        }
+                                    }
                                 }
 
                                 Gesture::Cancel => {
+                                    drag_state.moving_nodes = false;
                                     drag_state.connect_from = None;
                                     drag_state.connect_to = None;
                                     drag_state.selection_rect = None;
@@ -465,7 +603,7 @@ impl ViewTemplate for CenterPanel {
                 },
                 (),
             )
-            .children(GraphView)
+            .children((GraphView, TimelinePanel))
             .style(wrapper_style)
     }
 }
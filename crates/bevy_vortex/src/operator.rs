@@ -63,6 +63,11 @@ pub trait Operator: Send + Sync + Reflect {
 #[derive(Debug, Clone, Reflect)]
 pub struct DisplayWidth(pub i32);
 
+/// Indicates that nodes for this operator should be drawn in a minimized form. Used for
+/// operators which are inserted automatically, such as type conversions.
+#[derive(Debug, Clone, Reflect)]
+pub struct CompactDisplay;
+
 /// Name of this operator or operator property.
 #[derive(Debug, Clone, Reflect)]
 pub struct DisplayName(pub &'static str);
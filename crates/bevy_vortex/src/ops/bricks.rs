@@ -10,7 +10,7 @@ use crate::{
     },
 };
 
-use super::wgsl::{BRICKS, SMOOTHERSTEP};
+use super::includes::{BRICKS, SMOOTHERSTEP};
 
 #[derive(Debug, Reflect, Clone)]
 #[reflect(Operator, Default, @OperatorClass(OperatorCategory::Pattern), @OperatorDescription("
@@ -91,15 +91,12 @@ impl Operator for Bricks {
         node_id: Entity,
         _out_id: &str,
     ) -> Expr {
-        assembly.add_include(BRICKS);
         assembly.add_include(SMOOTHERSTEP);
+        assembly.add_include(BRICKS);
 
         let uv = match reader.read_input_terminal(assembly, node_id, "uv") {
             Some(expr) => expr.cast(DataType::Vec2),
-            None => {
-                assembly.needs_uv = true;
-                Expr::RefLocal(DataType::Vec3, "mesh.uv".to_string())
-            }
+            None => assembly.uv(),
         };
 
         Expr::FnCall(
@@ -109,11 +106,11 @@ impl Operator for Bricks {
                 Arc::new(uv),
                 Arc::new(Expr::ConstI32(self.x_count)),
                 Arc::new(Expr::ConstI32(self.y_count)),
-                Arc::new(Expr::ConstF32(self.x_spacing)),
-                Arc::new(Expr::ConstF32(self.y_spacing)),
-                Arc::new(Expr::ConstF32(self.x_blur)),
-                Arc::new(Expr::ConstF32(self.y_blur)),
-                Arc::new(Expr::ConstF32(self.stagger)),
+                Arc::new(assembly.param_f32(node_id, "x_spacing", self.x_spacing)),
+                Arc::new(assembly.param_f32(node_id, "y_spacing", self.y_spacing)),
+                Arc::new(assembly.param_f32(node_id, "x_blur", self.x_blur)),
+                Arc::new(assembly.param_f32(node_id, "y_blur", self.y_blur)),
+                Arc::new(assembly.param_f32(node_id, "stagger", self.stagger)),
                 Arc::new(Expr::ConstI32(0)),
             ],
         )
@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+
+use crate::{
+    gen::{DataType, Expr, ShaderAssembly, TerminalReader},
+    operator::{
+        CompactDisplay, DisplayName, DisplayWidth, Operator, OperatorCategory, OperatorClass,
+        OperatorDescription, OperatorInput, OperatorInputOnly, OperatorOutput, ReflectOperator,
+    },
+};
+
+/// Read the input terminal of a conversion operator, and cast it to the output type.
+fn convert(
+    assembly: &mut ShaderAssembly,
+    reader: &TerminalReader,
+    node_id: Entity,
+    default: Expr,
+    data_type: DataType,
+) -> Expr {
+    match reader.read_input_terminal(assembly, node_id, "input") {
+        Some(expr) => expr.cast(data_type),
+        None => default,
+    }
+}
+
+#[derive(Debug, Reflect, Clone, Default)]
+#[reflect(
+    Operator,
+    Default,
+    @OperatorClass(OperatorCategory::Converter),
+    @DisplayName("Scalar to Vector"),
+    @DisplayWidth(80),
+    @CompactDisplay,
+    @OperatorDescription("
+Converts a scalar to a vector with the same value in each component.
+"))]
+pub struct ScalarToVector {
+    /// Output vector
+    #[reflect(@OperatorOutput, @DisplayName("Out"))]
+    pub output: Vec3,
+
+    /// Input scalar
+    #[reflect(@OperatorInput, @OperatorInputOnly, @DisplayName("In"))]
+    pub input: f32,
+}
+
+impl Operator for ScalarToVector {
+    fn to_boxed_clone(&self) -> Box<dyn Operator> {
+        Box::new(self.clone())
+    }
+
+    fn gen(
+        &self,
+        assembly: &mut ShaderAssembly,
+        reader: &TerminalReader,
+        node_id: Entity,
+        _out_id: &str,
+    ) -> Expr {
+        let default = Expr::ConstVec3(Vec3::splat(self.input));
+        convert(assembly, reader, node_id, default, DataType::Vec3)
+    }
+}
+
+#[derive(Debug, Reflect, Clone, Default)]
+#[reflect(
+    Operator,
+    Default,
+    @OperatorClass(OperatorCategory::Converter),
+    @DisplayName("Scalar to Color"),
+    @DisplayWidth(80),
+    @CompactDisplay,
+    @OperatorDescription("
+Converts a scalar to an opaque gray color.
+"))]
+pub struct ScalarToColor {
+    /// Output color
+    #[reflect(@OperatorOutput, @DisplayName("Out"))]
+    pub output: LinearRgba,
+
+    /// Input scalar
+    #[reflect(@OperatorInput, @OperatorInputOnly, @DisplayName("In"))]
+    pub input: f32,
+}
+
+impl Operator for ScalarToColor {
+    fn to_boxed_clone(&self) -> Box<dyn Operator> {
+        Box::new(self.clone())
+    }
+
+    fn gen(
+        &self,
+        assembly: &mut ShaderAssembly,
+        reader: &TerminalReader,
+        node_id: Entity,
+        _out_id: &str,
+    ) -> Expr {
+        let default = Expr::ConstColor(LinearRgba::rgb(self.input, self.input, self.input));
+        convert(assembly, reader, node_id, default, DataType::LinearRgba)
+    }
+}
+
+#[derive(Debug, Reflect, Clone, Default)]
+#[reflect(
+    Operator,
+    Default,
+    @OperatorClass(OperatorCategory::Converter),
+    @DisplayName("Color to Vector"),
+    @DisplayWidth(80),
+    @CompactDisplay,
+    @OperatorDescription("
+Converts a color to a four-component vector.
+"))]
+pub struct ColorToVector {
+    /// Output vector
+    #[reflect(@OperatorOutput, @DisplayName("Out"))]
+    pub output: Vec4,
+
+    /// Input color
+    #[reflect(@OperatorInput, @OperatorInputOnly, @DisplayName("In"))]
+    pub input: LinearRgba,
+}
+
+impl Operator for ColorToVector {
+    fn to_boxed_clone(&self) -> Box<dyn Operator> {
+        Box::new(self.clone())
+    }
+
+    fn gen(
+        &self,
+        assembly: &mut ShaderAssembly,
+        reader: &TerminalReader,
+        node_id: Entity,
+        _out_id: &str,
+    ) -> Expr {
+        let default = Expr::ConstVec4(Vec4::new(
+            self.input.red,
+            self.input.green,
+            self.input.blue,
+            self.input.alpha,
+        ));
+        convert(assembly, reader, node_id, default, DataType::Vec4)
+    }
+}
+
+#[derive(Debug, Reflect, Clone, Default)]
+#[reflect(
+    Operator,
+    Default,
+    @OperatorClass(OperatorCategory::Converter),
+    @DisplayName("Vector to Color"),
+    @DisplayWidth(80),
+    @CompactDisplay,
+    @OperatorDescription("
+Converts a vector to an opaque color.
+"))]
+pub struct VectorToColor {
+    /// Output color
+    #[reflect(@OperatorOutput, @DisplayName("Out"))]
+    pub output: LinearRgba,
+
+    /// Input vector
+    #[reflect(@OperatorInput, @OperatorInputOnly, @DisplayName("In"))]
+    pub input: Vec3,
+}
+
+impl Operator for VectorToColor {
+    fn to_boxed_clone(&self) -> Box<dyn Operator> {
+        Box::new(self.clone())
+    }
+
+    fn gen(
+        &self,
+        assembly: &mut ShaderAssembly,
+        reader: &TerminalReader,
+        node_id: Entity,
+        _out_id: &str,
+    ) -> Expr {
+        let default = Expr::ConstColor(LinearRgba::rgb(self.input.x, self.input.y, self.input.z));
+        convert(assembly, reader, node_id, default, DataType::LinearRgba)
+    }
+}
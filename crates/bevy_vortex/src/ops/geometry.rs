@@ -51,10 +51,7 @@ impl Operator for Geometry {
                 assembly.needs_normal = true;
                 Expr::RefLocal(DataType::Vec3, "mesh.world_normal".to_string())
             }
-            "uv" => {
-                assembly.needs_uv = true;
-                Expr::RefLocal(DataType::Vec2, "mesh.uv".to_string())
-            }
+            "uv" => assembly.uv(),
             _ => panic!("Unknown output ID: {}", out_id),
         }
     }
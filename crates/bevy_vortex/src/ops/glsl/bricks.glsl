@@ -0,0 +1,27 @@
+float bricks(
+    vec2 uv,
+    int x_count,
+    int y_count,
+    float x_spacing,
+    float y_spacing,
+    float x_blur,
+    float y_blur,
+    float stagger,
+    int corner) {
+  float y = uv.y * float(y_count);
+  float yr = floor(y);
+  float yi = floor(y + 0.5);
+  float yf = smootherstep(y_spacing, y_spacing + y_blur, abs(y - yi));
+  float x = uv.x * float(x_count) + (floor(yr * 0.5) * 2.0 == yr ? stagger : 0.0);
+  float xi = floor(x + 0.5);
+  float xf = smootherstep(x_spacing, x_spacing + x_blur, abs(x - xi));
+  float value;
+  if (corner == 1) { // Mitered
+    value = max(0., (xf + yf) - 1.0);
+  } else if (corner == 2) { // Rounded
+    value = max(0., 1. - sqrt((1.-xf) * (1.-xf) + (1.-yf) * (1.-yf)));
+  } else { // Square
+    value = min(xf, yf);
+  }
+  return value;
+}
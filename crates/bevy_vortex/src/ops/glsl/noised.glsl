@@ -0,0 +1,59 @@
+float hash(ivec3 n) {
+  return fract(10000.0 * sin(float(n.x + n.y * 13 + n.z * 17)));
+}
+
+// https://iquilezles.org/articles/gradientnoise/
+// returns 3D value noise (in .x)  and its derivatives (in .yzw)
+// MIT License. © Inigo Quilez - https://iquilezles.org/articles/morenoise/
+vec4 noised(vec3 x) {
+  ivec3 p = ivec3(floor(x));
+  vec3 w = fract(x);
+
+  vec3 u = w * w * w * (w * (w * 6.0 - 15.0) + 10.0);
+  vec3 du = 30.0 * w * w * (w * (w - 2.0) + 1.0);
+
+  float a = hash(p + ivec3(0, 0, 0));
+  float b = hash(p + ivec3(1, 0, 0));
+  float c = hash(p + ivec3(0, 1, 0));
+  float d = hash(p + ivec3(1, 1, 0));
+  float e = hash(p + ivec3(0, 0, 1));
+  float f = hash(p + ivec3(1, 0, 1));
+  float g = hash(p + ivec3(0, 1, 1));
+  float h = hash(p + ivec3(1, 1, 1));
+
+  float k0 =   a;
+  float k1 =   b - a;
+  float k2 =   c - a;
+  float k3 =   e - a;
+  float k4 =   a - b - c + d;
+  float k5 =   a - c - e + g;
+  float k6 =   a - b - e + f;
+  float k7 = - a + b + c - d + e - f - g + h;
+
+  return
+    vec4( -1.0 + 2.0 * (k0 + k1*u.x + k2*u.y + k3*u.z + k4*u.x*u.y + k5*u.y*u.z + k6*u.z*u.x + k7*u.x*u.y*u.z),
+           2.0 * du * vec3(k1 + k4*u.y + k6*u.z + k7*u.y*u.z,
+                           k2 + k5*u.z + k4*u.x + k7*u.z*u.x,
+                           k3 + k6*u.x + k5*u.y + k7*u.x*u.y ) );
+}
+
+vec4 noised_octaves(
+    vec3 v,
+    float scale,
+    int octaves,
+    float roughness,
+    float distortion) {
+
+    vec3 pos = v * scale;
+    vec4 result = vec4(0.0);
+    float coeff = 1.0;
+    float total = 0.0;
+    for (int i = 0; i < octaves; i++) {
+        result += noised(pos) * coeff;
+        total += coeff;
+        coeff *= roughness;
+        // pos *= distortion;
+        pos *= 2.0;
+    }
+    return result / total * vec4(0.5, 1.0, 1.0, 1.0) + vec4(0.5, 0.0, 0.0, 0.0);
+}
@@ -0,0 +1,59 @@
+float hash(int3 n) {
+  return frac(10000.0 * sin(float(n.x + n.y * 13 + n.z * 17)));
+}
+
+// https://iquilezles.org/articles/gradientnoise/
+// returns 3D value noise (in .x)  and its derivatives (in .yzw)
+// MIT License. © Inigo Quilez - https://iquilezles.org/articles/morenoise/
+float4 noised(float3 x) {
+  int3 p = (int3)floor(x);
+  float3 w = frac(x);
+
+  float3 u = w * w * w * (w * (w * 6.0 - 15.0) + 10.0);
+  float3 du = 30.0 * w * w * (w * (w - 2.0) + 1.0);
+
+  float a = hash(p + int3(0, 0, 0));
+  float b = hash(p + int3(1, 0, 0));
+  float c = hash(p + int3(0, 1, 0));
+  float d = hash(p + int3(1, 1, 0));
+  float e = hash(p + int3(0, 0, 1));
+  float f = hash(p + int3(1, 0, 1));
+  float g = hash(p + int3(0, 1, 1));
+  float h = hash(p + int3(1, 1, 1));
+
+  float k0 =   a;
+  float k1 =   b - a;
+  float k2 =   c - a;
+  float k3 =   e - a;
+  float k4 =   a - b - c + d;
+  float k5 =   a - c - e + g;
+  float k6 =   a - b - e + f;
+  float k7 = - a + b + c - d + e - f - g + h;
+
+  return
+    float4( -1.0 + 2.0 * (k0 + k1*u.x + k2*u.y + k3*u.z + k4*u.x*u.y + k5*u.y*u.z + k6*u.z*u.x + k7*u.x*u.y*u.z),
+           2.0 * du * float3(k1 + k4*u.y + k6*u.z + k7*u.y*u.z,
+                           k2 + k5*u.z + k4*u.x + k7*u.z*u.x,
+                           k3 + k6*u.x + k5*u.y + k7*u.x*u.y ) );
+}
+
+float4 noised_octaves(
+    float3 v,
+    float scale,
+    int octaves,
+    float roughness,
+    float distortion) {
+
+    float3 pos = v * scale;
+    float4 result = float4(0.0, 0.0, 0.0, 0.0);
+    float coeff = 1.0;
+    float total = 0.0;
+    for (int i = 0; i < octaves; i++) {
+        result += noised(pos) * coeff;
+        total += coeff;
+        coeff *= roughness;
+        // pos *= distortion;
+        pos *= 2.0;
+    }
+    return result / total * float4(0.5, 1.0, 1.0, 1.0) + float4(0.5, 0.0, 0.0, 0.0);
+}
@@ -0,0 +1,19 @@
+use crate::gen::ShaderInclude;
+
+pub const BRICKS: ShaderInclude = ShaderInclude {
+    wgsl: include_str!("wgsl/bricks.wgsl"),
+    glsl: include_str!("glsl/bricks.glsl"),
+    hlsl: include_str!("hlsl/bricks.hlsl"),
+};
+
+pub const NOISED: ShaderInclude = ShaderInclude {
+    wgsl: include_str!("wgsl/noised.wgsl"),
+    glsl: include_str!("glsl/noised.glsl"),
+    hlsl: include_str!("hlsl/noised.hlsl"),
+};
+
+pub const SMOOTHERSTEP: ShaderInclude = ShaderInclude {
+    wgsl: include_str!("wgsl/smootherstep.wgsl"),
+    glsl: include_str!("glsl/smootherstep.glsl"),
+    hlsl: include_str!("hlsl/smootherstep.hlsl"),
+};
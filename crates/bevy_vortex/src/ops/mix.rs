@@ -58,7 +58,7 @@ impl Operator for Mix {
         };
         let factor = match reader.read_input_terminal(assembly, node_id, "factor") {
             Some(expr) => expr.cast(DataType::F32),
-            None => Expr::ConstF32(self.factor),
+            None => assembly.param_f32(node_id, "factor", self.factor),
         };
         // TODO: Constant folding. Maybe this should be done as a post-process? We'll need
         // to make a 'mix' object that can evaluate it's arguments.
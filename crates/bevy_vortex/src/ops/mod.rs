@@ -1,21 +1,28 @@
 use bevy::{app::Plugin, asset::embedded_asset};
 
+use crate::{conversion::ConversionRegistry, graph::ConnectionDataType};
+
 mod bricks;
 mod color;
+mod convert;
 mod geometry;
 mod grayscale;
+mod includes;
 mod mix;
 mod noise;
 mod output;
-mod wgsl;
+mod tileable;
 
 use bricks::Bricks;
 use color::ConstColor;
+pub(crate) use convert::ColorToVector;
+use convert::{ScalarToColor, ScalarToVector, VectorToColor};
 use geometry::Geometry;
 use grayscale::Grayscale;
-use mix::Mix;
+pub(crate) use mix::Mix;
 use noise::Noise;
 use output::Output;
+use tileable::MakeTileable;
 
 pub struct OperatorsPlugin;
 
@@ -27,8 +34,36 @@ impl Plugin for OperatorsPlugin {
             .register_type::<ConstColor>()
             .register_type::<Geometry>()
             .register_type::<Grayscale>()
+            .register_type::<MakeTileable>()
             .register_type::<Mix>()
             .register_type::<Output>()
-            .register_type::<Noise>();
+            .register_type::<Noise>()
+            .register_type::<ScalarToVector>()
+            .register_type::<ScalarToColor>()
+            .register_type::<ColorToVector>()
+            .register_type::<VectorToColor>()
+            .init_resource::<ConversionRegistry>();
+        app.world_mut()
+            .resource_mut::<ConversionRegistry>()
+            .register(
+                ConnectionDataType::Scalar,
+                ConnectionDataType::Vector,
+                || Box::<ScalarToVector>::default(),
+            )
+            .register(
+                ConnectionDataType::Scalar,
+                ConnectionDataType::Color,
+                || Box::<ScalarToColor>::default(),
+            )
+            .register(
+                ConnectionDataType::Color,
+                ConnectionDataType::Vector,
+                || Box::<ColorToVector>::default(),
+            )
+            .register(
+                ConnectionDataType::Vector,
+                ConnectionDataType::Color,
+                || Box::<VectorToColor>::default(),
+            );
     }
 }
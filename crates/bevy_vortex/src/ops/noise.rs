@@ -10,7 +10,7 @@ use crate::{
     },
 };
 
-use super::wgsl::NOISED;
+use super::includes::NOISED;
 
 #[derive(Debug, Reflect, Clone)]
 #[reflect(
@@ -64,6 +64,20 @@ pub struct Noise {
     pub distortion: f32,
 }
 
+/// Offset of the noise coordinates for a seed, from -64 to 64 along each axis.
+fn seed_offset(seed: u32) -> Vec3 {
+    let mut state = seed as u64;
+    Vec3::from_array(std::array::from_fn(|_| {
+        // SplitMix64
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 40) as f32 / (1u64 << 24) as f32 * 128. - 64.
+    }))
+}
+
 impl Operator for Noise {
     fn to_boxed_clone(&self) -> Box<dyn Operator> {
         Box::new(self.clone())
@@ -85,26 +99,39 @@ impl Operator for Noise {
                 Expr::RefLocal(DataType::Vec3, "mesh.position_local.xyz".to_string())
             }
         };
+        // Each seed samples a different region of the noise.
+        let vector = match assembly.seed() {
+            0 => vector,
+            seed => Expr::FnCall(
+                DataType::Vec3,
+                "fma",
+                vec![
+                    Arc::new(vector),
+                    Arc::new(Expr::ConstVec3(Vec3::ONE)),
+                    Arc::new(Expr::ConstVec3(seed_offset(seed))),
+                ],
+            ),
+        };
 
         let scale = match reader.read_input_terminal(assembly, node_id, "scale") {
             Some(expr) => expr.cast(DataType::F32),
-            None => Expr::ConstF32(self.scale),
+            None => assembly.param_f32(node_id, "scale", self.scale),
         };
 
         let octaves = Expr::ConstI32(self.octaves);
 
         let roughness = match reader.read_input_terminal(assembly, node_id, "roughness") {
             Some(expr) => expr.cast(DataType::F32),
-            None => Expr::ConstF32(self.roughness),
+            None => assembly.param_f32(node_id, "roughness", self.roughness),
         };
 
         let distortion = match reader.read_input_terminal(assembly, node_id, "distortion") {
             Some(expr) => expr.cast(DataType::F32),
-            None => Expr::ConstF32(self.distortion),
+            None => assembly.param_f32(node_id, "distortion", self.distortion),
         };
 
         let id = reader.get_node_index(node_id);
-        let var_name = format!("noise_out_{}", id.0);
+        let var_name = assembly.local_name(format!("noise_out_{}", id.0));
         if !assembly.local_exists(&var_name) {
             assembly.declare_local(
                 var_name.clone(),
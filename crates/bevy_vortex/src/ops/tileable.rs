@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::{
+    gen::{DataType, Expr, ShaderAssembly, TerminalReader},
+    operator::{
+        DisplayName, OpValuePrecision, OpValueRange, Operator, OperatorCategory, OperatorClass,
+        OperatorDescription, OperatorInput, OperatorOutput, ReflectOperator,
+    },
+};
+
+#[derive(Debug, Reflect, Clone)]
+#[reflect(Operator, Default, @OperatorClass(OperatorCategory::Filter), @OperatorDescription("
+Makes the input tile seamlessly, by blending it with copies of itself offset by half a tile.
+* **Blend** is the width of the blended region along each edge of the tile.
+
+Only inputs which depend on the texture coordinates are affected.
+"))]
+pub struct MakeTileable {
+    /// Output color
+    #[reflect(@OperatorOutput, @DisplayName("Out"))]
+    pub output: LinearRgba,
+
+    /// Input color
+    #[reflect(@OperatorInput, @DisplayName("In"))]
+    pub input: LinearRgba,
+
+    /// Width of the blended region, as a fraction of the tile size.
+    #[reflect(
+        @OperatorInput,
+        @DisplayName("Blend"),
+        @OpValueRange::<f32>(0.01..=0.5),
+        @OpValuePrecision(3))]
+    pub blend: f32,
+}
+
+impl MakeTileable {
+    /// Evaluate the input with the texture coordinates offset by `offset`, wrapping around.
+    fn sample(
+        &self,
+        assembly: &mut ShaderAssembly,
+        reader: &TerminalReader,
+        node_id: Entity,
+        uv: Arc<Expr>,
+        offset: Vec2,
+    ) -> Arc<Expr> {
+        let shifted = Expr::FnCall(
+            DataType::Vec2,
+            "fract",
+            vec![Arc::new(Expr::FnCall(
+                DataType::Vec2,
+                "fma",
+                vec![
+                    uv,
+                    Arc::new(Expr::ConstVec2(Vec2::ONE)),
+                    Arc::new(Expr::ConstVec2(offset)),
+                ],
+            ))],
+        );
+        let input = assembly.with_uv(shifted, |assembly| {
+            reader.read_input_terminal(assembly, node_id, "input")
+        });
+        Arc::new(match input {
+            Some(expr) => expr.cast(DataType::LinearRgba),
+            None => Expr::ConstColor(self.input),
+        })
+    }
+}
+
+impl Operator for MakeTileable {
+    fn to_boxed_clone(&self) -> Box<dyn Operator> {
+        Box::new(self.clone())
+    }
+
+    fn gen(
+        &self,
+        assembly: &mut ShaderAssembly,
+        reader: &TerminalReader,
+        node_id: Entity,
+        _out_id: &str,
+    ) -> Expr {
+        let Some(center) = reader.read_input_terminal(assembly, node_id, "input") else {
+            return Expr::ConstColor(self.input);
+        };
+        let center = Arc::new(center.cast(DataType::LinearRgba));
+
+        let blend = match reader.read_input_terminal(assembly, node_id, "blend") {
+            Some(expr) => expr.cast(DataType::F32),
+            None => assembly.param_f32(node_id, "blend", self.blend),
+        };
+
+        let uv = Arc::new(assembly.uv());
+        let across_x = self.sample(assembly, reader, node_id, uv.clone(), Vec2::new(0.5, 0.));
+        let across_y = self.sample(assembly, reader, node_id, uv.clone(), Vec2::new(0., 0.5));
+        let across_xy = self.sample(assembly, reader, node_id, uv.clone(), Vec2::splat(0.5));
+
+        // Distance to the nearest edge of the tile, along each axis.
+        let edge_distance = Arc::new(Expr::FnCall(
+            DataType::Vec2,
+            "min",
+            vec![
+                uv.clone(),
+                Arc::new(Expr::FnCall(
+                    DataType::Vec2,
+                    "fma",
+                    vec![
+                        uv,
+                        Arc::new(Expr::ConstVec2(Vec2::splat(-1.))),
+                        Arc::new(Expr::ConstVec2(Vec2::ONE)),
+                    ],
+                )),
+            ],
+        ));
+        // Weight of the offset copies: one at the edges, falling to zero over the blend width.
+        let weight = Arc::new(Expr::FnCall(
+            DataType::Vec2,
+            "fma",
+            vec![
+                Arc::new(Expr::FnCall(
+                    DataType::Vec2,
+                    "smoothstep",
+                    vec![
+                        Arc::new(Expr::ConstVec2(Vec2::ZERO)),
+                        Arc::new(Expr::FnCall(DataType::Vec2, "vec2f", vec![Arc::new(blend)])),
+                        edge_distance,
+                    ],
+                )),
+                Arc::new(Expr::ConstVec2(Vec2::splat(-1.))),
+                Arc::new(Expr::ConstVec2(Vec2::ONE)),
+            ],
+        ));
+        let weight_x = Arc::new(Expr::GetAttr(DataType::F32, weight.clone(), "x"));
+        let weight_y = Arc::new(Expr::GetAttr(DataType::F32, weight, "y"));
+
+        // Each copy has a seam in the middle of the tile along the axis it is offset on,
+        // where its weight is zero.
+        let row = Arc::new(Expr::FnCall(
+            DataType::LinearRgba,
+            "mix",
+            vec![center, across_x, weight_x.clone()],
+        ));
+        let row_across_y = Arc::new(Expr::FnCall(
+            DataType::LinearRgba,
+            "mix",
+            vec![across_y, across_xy, weight_x],
+        ));
+        Expr::FnCall(
+            DataType::LinearRgba,
+            "mix",
+            vec![row, row_across_y, weight_y],
+        )
+    }
+}
+
+impl Default for MakeTileable {
+    fn default() -> Self {
+        MakeTileable {
+            output: LinearRgba::BLACK,
+            input: LinearRgba::BLACK,
+            blend: 0.15,
+        }
+    }
+}
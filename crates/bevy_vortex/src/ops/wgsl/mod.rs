@@ -1,3 +0,0 @@
-pub const BRICKS: &str = include_str!("bricks.wgsl");
-pub const NOISED: &str = include_str!("noised.wgsl");
-pub const SMOOTHERSTEP: &str = include_str!("smootherstep.wgsl");
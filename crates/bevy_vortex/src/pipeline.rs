@@ -1,5 +1,6 @@
 use bevy::{
     core_pipeline::core_3d::{Opaque3d, Opaque3dBinKey, CORE_3D_DEPTH_FORMAT},
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
     pbr::{
         DrawMesh, MeshPipeline, MeshPipelineKey, MeshPipelineViewLayoutKey, RenderMeshInstances,
         SetMeshBindGroup, SetMeshViewBindGroup,
@@ -7,19 +8,22 @@ use bevy::{
     prelude::*,
     render::{
         extract_component::{ExtractComponent, ExtractComponentPlugin},
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
         mesh::{GpuMesh, MeshVertexBufferLayoutRef},
         render_asset::RenderAssets,
         render_phase::{
-            AddRenderCommand, BinnedRenderPhaseType, DrawFunctions, SetItemPipeline,
-            ViewBinnedRenderPhases,
+            AddRenderCommand, BinnedRenderPhaseType, DrawFunctions, PhaseItem, RenderCommand,
+            RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewBinnedRenderPhases,
         },
         render_resource::{
-            BlendState, ColorTargetState, ColorWrites, CompareFunction, DepthBiasState,
-            DepthStencilState, Face, FragmentState, FrontFace, MultisampleState, PipelineCache,
-            PolygonMode, PrimitiveState, RenderPipelineDescriptor, SpecializedMeshPipeline,
-            SpecializedMeshPipelineError, SpecializedMeshPipelines, StencilState, TextureFormat,
-            VertexState,
+            binding_types::uniform_buffer, BindGroup, BindGroupEntries, BindGroupLayout,
+            BindGroupLayoutEntries, BlendState, ColorTargetState, ColorWrites, CompareFunction,
+            DepthBiasState, DepthStencilState, Face, FragmentState, FrontFace, MultisampleState,
+            PipelineCache, PolygonMode, PrimitiveState, RenderPipelineDescriptor, ShaderStages,
+            ShaderType, SpecializedMeshPipeline, SpecializedMeshPipelineError,
+            SpecializedMeshPipelines, StencilState, TextureFormat, UniformBuffer, VertexState,
         },
+        renderer::{RenderDevice, RenderQueue},
         texture::BevyDefault,
         view::{self, ExtractedView, ViewTarget, VisibilitySystems, VisibleEntities},
         Render, RenderApp, RenderSet,
@@ -34,11 +38,37 @@ pub struct NodeShader3dHandle(pub Handle<Shader>);
 /// rendered entity.
 type WithNodeShader3dHandle = With<NodeShader3dHandle>;
 
+/// Maximum number of animated operator fields. Fields beyond this are not animated.
+pub const MAX_ANIMATED_PARAMS: usize = 64;
+
+/// Current values of the animated operator fields, which generated shaders read from a uniform
+/// in bind group 2. Slot `i` is component `i % 4` of element `i / 4`.
+#[derive(Resource, Clone, Default, PartialEq, ExtractResource, ShaderType)]
+pub struct AnimatedParams {
+    pub values: [Vec4; MAX_ANIMATED_PARAMS / 4],
+}
+
+impl AnimatedParams {
+    /// Set the value of a slot.
+    pub fn set(&mut self, slot: usize, value: f32) {
+        self.values[slot / 4][slot % 4] = value;
+    }
+}
+
+/// Render-world buffer and bind group for the [`AnimatedParams`].
+#[derive(Resource, Default)]
+struct AnimatedParamsBuffer {
+    buffer: UniformBuffer<AnimatedParams>,
+    bind_group: Option<BindGroup>,
+}
+
 /// Custom pipeline for meshes with vertex colors
 #[derive(Resource)]
 pub struct NodeShaderMesh3dPipeline {
     /// this pipeline wraps the standard [`MeshPipeline`]
     mesh_pipeline: MeshPipeline,
+    /// Layout of the animated parameters bind group.
+    params_layout: BindGroupLayout,
 }
 
 #[derive(Clone, Hash, PartialEq, Eq)]
@@ -51,8 +81,16 @@ pub struct NodeShaderMesh3dPipelineKey {
 
 impl FromWorld for NodeShaderMesh3dPipeline {
     fn from_world(world: &mut World) -> Self {
+        let params_layout = world.resource::<RenderDevice>().create_bind_group_layout(
+            "animated_params_layout",
+            &BindGroupLayoutEntries::single(
+                ShaderStages::FRAGMENT,
+                uniform_buffer::<AnimatedParams>(false),
+            ),
+        );
         Self {
             mesh_pipeline: MeshPipeline::from_world(world),
+            params_layout,
         }
     }
 }
@@ -119,6 +157,8 @@ impl SpecializedMeshPipeline for NodeShaderMesh3dPipeline {
                     .clone(),
                 // Bind group 1 is the mesh uniform
                 self.mesh_pipeline.mesh_layouts.model_only.clone(),
+                // Bind group 2 is the animated parameters
+                self.params_layout.clone(),
             ],
             push_constant_ranges: vec![],
             primitive: PrimitiveState {
@@ -155,10 +195,58 @@ type DrawNodeShaderMeshCommands = (
     SetMeshViewBindGroup<0>,
     // Set the mesh uniform as bind group 1
     SetMeshBindGroup<1>,
+    // Set the animated parameters as bind group 2
+    SetAnimatedParamsBindGroup<2>,
     // Draw the mesh
     DrawMesh,
 );
 
+/// Render command which binds the [`AnimatedParams`] uniform.
+struct SetAnimatedParamsBindGroup<const I: usize>;
+
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetAnimatedParamsBindGroup<I> {
+    type Param = SRes<AnimatedParamsBuffer>;
+    type ViewQuery = ();
+    type ItemQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        _entity: Option<()>,
+        params: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(bind_group) = params.into_inner().bind_group.as_ref() else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+/// Upload the animated parameters, and create the bind group the first time.
+fn prepare_animated_params(
+    params: Res<AnimatedParams>,
+    pipeline: Res<NodeShaderMesh3dPipeline>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut buffer: ResMut<AnimatedParamsBuffer>,
+) {
+    if buffer.bind_group.is_some() && !params.is_changed() {
+        return;
+    }
+    buffer.buffer.set(params.clone());
+    buffer.buffer.write_buffer(&render_device, &render_queue);
+    if buffer.bind_group.is_none() {
+        let bind_group = render_device.create_bind_group(
+            "animated_params",
+            &pipeline.params_layout,
+            &BindGroupEntries::single(buffer.buffer.binding().unwrap()),
+        );
+        buffer.bind_group = Some(bind_group);
+    }
+}
+
 /// A render-world system that enqueues the entity with custom rendering into
 /// the opaque render phases of each view.
 #[allow(clippy::too_many_arguments)]
@@ -244,7 +332,11 @@ pub struct NodeShaderMeshPlugin;
 
 impl Plugin for NodeShaderMeshPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ExtractComponentPlugin::<NodeShader3dHandle>::default())
+        app.init_resource::<AnimatedParams>()
+            .add_plugins((
+                ExtractComponentPlugin::<NodeShader3dHandle>::default(),
+                ExtractResourcePlugin::<AnimatedParams>::default(),
+            ))
             .add_systems(
                 PostUpdate,
                 // Make sure to tell Bevy to check our entity for visibility. Bevy won't
@@ -259,8 +351,15 @@ impl Plugin for NodeShaderMeshPlugin {
         };
         render_app
             .init_resource::<SpecializedMeshPipelines<NodeShaderMesh3dPipeline>>()
+            .init_resource::<AnimatedParamsBuffer>()
             .add_render_command::<Opaque3d, DrawNodeShaderMeshCommands>()
-            .add_systems(Render, queue_node_shader_item.in_set(RenderSet::Queue));
+            .add_systems(
+                Render,
+                (
+                    queue_node_shader_item.in_set(RenderSet::Queue),
+                    prepare_animated_params.in_set(RenderSet::PrepareBindGroups),
+                ),
+            );
     }
 
     fn finish(&self, app: &mut App) {
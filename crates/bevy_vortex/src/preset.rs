@@ -0,0 +1,659 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::{ReflectDeserializer, ReflectSerializer},
+        std_traits::ReflectDefault,
+        TypeRegistry,
+    },
+    render::render_asset::RenderAssetUsages,
+    ui,
+};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill::*;
+use bevy_quill_obsidian::{
+    colors,
+    controls::{Button, EditableLabel, ListView},
+    typography::text_strong,
+};
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+
+use crate::{
+    animation::SavedTrack,
+    commands::{InstantiatePresetCmd, SavePresetCmd},
+    diff::PresetDiffDialog,
+    graph_view::{GraphContentId, GraphViewId},
+    operator::{Operator, ReflectOperator},
+    readback::ImageReadbackEvent,
+};
+
+/// A node within a preset.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PresetNode {
+    /// Position of the node, relative to the top-left corner of the preset.
+    pub offset: (i32, i32),
+    /// The operator and its properties, as reflected RON.
+    pub operator: String,
+}
+
+/// A connection between two nodes of a preset, identified by node index and terminal name.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PresetConnection {
+    pub output: (usize, String),
+    pub input: (usize, String),
+}
+
+/// A sticky note within a preset.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PresetNote {
+    /// Position of the note's upper-left corner, relative to the top-left corner of the preset.
+    pub offset: (i32, i32),
+    pub size: (f32, f32),
+    /// Background color of the note, as sRGB.
+    pub color: (f32, f32, f32),
+    pub text: String,
+}
+
+/// A configured node, or a small group of connected nodes, which can be added to the graph.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NodePreset {
+    pub name: String,
+    pub nodes: Vec<PresetNode>,
+    pub connections: Vec<PresetConnection>,
+    /// Notes which annotate the nodes. Presets saved before notes were added have none.
+    #[serde(default)]
+    pub notes: Vec<PresetNote>,
+    /// Animation tracks of the nodes.
+    #[serde(default)]
+    pub tracks: Vec<SavedTrack>,
+    /// Image of the preset's output at the time it was saved. This is stored on disk as a PNG
+    /// file next to the preset file.
+    #[serde(skip)]
+    pub thumbnail: Option<Handle<Image>>,
+}
+
+/// Serialize an operator as reflected RON.
+pub(crate) fn save_operator(registry: &TypeRegistry, operator: &dyn Reflect) -> Option<String> {
+    ron::ser::to_string(&ReflectSerializer::new(operator, registry)).ok()
+}
+
+/// Construct an operator from reflected RON. Fields which are missing from the data keep their
+/// default values.
+pub(crate) fn load_operator(registry: &TypeRegistry, data: &str) -> Option<Box<dyn Operator>> {
+    let mut deserializer = ron::Deserializer::from_str(data).ok()?;
+    let reflect = ReflectDeserializer::new(registry)
+        .deserialize(&mut deserializer)
+        .ok()?;
+    let type_path = reflect.get_represented_type_info()?.type_path();
+    let operator_type = registry.get_with_type_path(type_path)?;
+    let mut value = operator_type.data::<ReflectDefault>()?.default();
+    value.apply(reflect.as_ref());
+    registry
+        .get_type_data::<ReflectOperator>(operator_type.type_id())?
+        .get_boxed(value)
+        .ok()
+}
+
+/// Resource which holds the presets found in the user's preset directory.
+#[derive(Resource)]
+pub struct PresetLibrary {
+    pub(crate) dir: PathBuf,
+    pub(crate) presets: Vec<NodePreset>,
+}
+
+impl Default for PresetLibrary {
+    fn default() -> Self {
+        Self {
+            dir: preset_dir(),
+            presets: Vec::new(),
+        }
+    }
+}
+
+/// The directory where presets are stored. This can be overridden with the `VORTEX_PRESET_DIR`
+/// environment variable.
+fn preset_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("VORTEX_PRESET_DIR") {
+        return dir.into();
+    }
+    match std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        Some(home) => PathBuf::from(home).join(".vortex").join("presets"),
+        None => PathBuf::from("presets"),
+    }
+}
+
+/// The name of the files for a preset, without extension. Characters which are not allowed in
+/// file names, or which could refer to another directory, are replaced with underscores.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.is_empty() {
+        "_".to_string()
+    } else {
+        stem
+    }
+}
+
+impl PresetLibrary {
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.ron", file_stem(name)))
+    }
+
+    fn thumbnail_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.png", file_stem(name)))
+    }
+
+    /// Whether a preset other than the one at `except` uses the name, or the same files.
+    fn is_taken(&self, name: &str, except: Option<usize>) -> bool {
+        let stem = file_stem(name);
+        self.presets.iter().enumerate().any(|(index, p)| {
+            Some(index) != except && (p.name == name || file_stem(&p.name) == stem)
+        })
+    }
+
+    /// Read all of the presets in the preset directory.
+    pub fn load(&mut self) {
+        self.presets.clear();
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "ron") {
+                continue;
+            }
+            let preset = std::fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|text| ron::from_str::<NodePreset>(&text).map_err(|err| err.to_string()));
+            match preset {
+                Ok(preset) => self.presets.push(preset),
+                Err(err) => warn!("Failed to load preset {}: {}", path.display(), err),
+            }
+        }
+        self.presets.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Returns a name, based on `base`, which is not used by any existing preset.
+    pub fn unique_name(&self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut suffix = 1;
+        while self.is_taken(&name, None) {
+            suffix += 1;
+            name = format!("{} {}", base, suffix);
+        }
+        name
+    }
+
+    /// Write a preset to the preset directory and add it to the library.
+    pub fn save(&mut self, preset: NodePreset) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let text = ron::ser::to_string_pretty(&preset, default()).map_err(io::Error::other)?;
+        std::fs::write(self.path(&preset.name), text)?;
+        let index = self.presets.partition_point(|p| p.name < preset.name);
+        self.presets.insert(index, preset);
+        Ok(())
+    }
+
+    /// Rename a preset, along with its files.
+    pub fn rename(&mut self, index: usize, name: String) -> io::Result<()> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a preset name cannot be empty",
+            ));
+        }
+        if self.is_taken(&name, Some(index)) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("a preset named {} already exists", name),
+            ));
+        }
+        let Some(preset) = self.presets.get(index) else {
+            return Ok(());
+        };
+        // The name is stored in the file too, so it is written out again rather than moved.
+        let renamed = NodePreset {
+            name: name.clone(),
+            ..preset.clone()
+        };
+        let text = ron::ser::to_string_pretty(&renamed, default()).map_err(io::Error::other)?;
+        std::fs::write(self.path(&name), text)?;
+        if self.path(&preset.name) != self.path(&name) {
+            std::fs::remove_file(self.path(&preset.name))?;
+        }
+        match std::fs::rename(
+            self.thumbnail_path(&preset.name),
+            self.thumbnail_path(&name),
+        ) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        let mut preset = self.presets.remove(index);
+        preset.name = name;
+        let index = self.presets.partition_point(|p| p.name < preset.name);
+        self.presets.insert(index, preset);
+        Ok(())
+    }
+
+    /// Load the thumbnails of the presets which have one.
+    pub fn load_thumbnails(&mut self, images: &mut Assets<Image>) {
+        for index in 0..self.presets.len() {
+            let path = self.thumbnail_path(&self.presets[index].name);
+            if !path.exists() {
+                continue;
+            }
+            match read_thumbnail(&path) {
+                Ok(image) => self.presets[index].thumbnail = Some(images.add(image)),
+                Err(err) => warn!("Failed to load thumbnail {}: {}", path.display(), err),
+            }
+        }
+    }
+
+    /// Write the thumbnail of the preset which displays `image`, if any.
+    fn save_thumbnail(&self, image: AssetId<Image>, size: UVec2, data: &[u8]) -> io::Result<()> {
+        let Some(preset) = self
+            .presets
+            .iter()
+            .find(|p| p.thumbnail.as_ref().map(|t| t.id()) == Some(image))
+        else {
+            return Ok(());
+        };
+        write_thumbnail(&self.thumbnail_path(&preset.name), size, data)
+    }
+}
+
+/// Write RGBA pixels as a PNG file.
+fn write_thumbnail(path: &Path, size: UVec2, data: &[u8]) -> io::Result<()> {
+    image::save_buffer(path, data, size.x, size.y, image::ColorType::Rgba8)
+        .map_err(io::Error::other)
+}
+
+/// Read a PNG file as an image.
+fn read_thumbnail(path: &Path) -> io::Result<Image> {
+    let bytes = std::fs::read(path)?;
+    let image = image::load_from_memory_with_format(&bytes, image::ImageFormat::Png)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(Image::from_dynamic(
+        image,
+        true,
+        RenderAssetUsages::default(),
+    ))
+}
+
+pub(crate) fn load_presets(mut library: ResMut<PresetLibrary>, mut images: ResMut<Assets<Image>>) {
+    library.load();
+    library.load_thumbnails(&mut images);
+}
+
+/// Write the thumbnails of saved presets once they have been rendered.
+pub(crate) fn save_preset_thumbnails(
+    library: Res<PresetLibrary>,
+    mut readbacks: EventReader<ImageReadbackEvent>,
+) {
+    for readback in readbacks.read() {
+        if let Err(err) = library.save_thumbnail(readback.image, readback.size, &readback.data) {
+            warn!("Failed to save preset thumbnail: {}", err);
+        }
+    }
+}
+
+fn style_preset_list(ss: &mut StyleBuilder) {
+    ss.min_height(60).max_height(160);
+}
+
+fn style_preset_header(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .justify_content(ui::JustifyContent::SpaceBetween);
+}
+
+fn style_preset_row(ss: &mut StyleBuilder) {
+    ss.align_self(ui::AlignSelf::Stretch)
+        .display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(4)
+        .padding(2);
+}
+
+fn style_preset_thumbnail(ss: &mut StyleBuilder) {
+    ss.width(24)
+        .height(24)
+        .flex_shrink(0.)
+        .border_radius(3.)
+        .background_color(colors::U1);
+}
+
+fn style_preset_name(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.).overflow(ui::OverflowAxis::Clip);
+}
+
+/// Displays the saved presets. Presets are added to the graph by dragging them onto it.
+#[derive(Clone, PartialEq)]
+pub struct PresetListView;
+
+impl ViewTemplate for PresetListView {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let library = cx.use_resource::<PresetLibrary>();
+        let rows: Vec<PresetRow> = library
+            .presets
+            .iter()
+            .enumerate()
+            .map(|(index, preset)| PresetRow {
+                index,
+                name: preset.name.clone(),
+                thumbnail: preset.thumbnail.clone(),
+            })
+            .collect();
+        let on_save = cx.create_callback(|mut commands: Commands| {
+            commands.add(SavePresetCmd);
+        });
+        let compare_open = cx.create_mutable(false);
+        let on_compare = cx.create_callback(move |world: &mut World| {
+            compare_open.set(world, true);
+        });
+
+        (
+            Element::<NodeBundle>::new()
+                .style(style_preset_header)
+                .children((
+                    Element::<NodeBundle>::new()
+                        .style(text_strong)
+                        .children("Presets"),
+                    Element::<NodeBundle>::new().children((
+                        Button::new().children("Compare...").on_click(on_compare),
+                        Button::new().children("Save").on_click(on_save),
+                    )),
+                )),
+            ListView::new()
+                .style(style_preset_list)
+                .children(For::each(rows, |row| row.clone())),
+            PresetDiffDialog { open: compare_open },
+        )
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct PresetRow {
+    index: usize,
+    name: String,
+    thumbnail: Option<Handle<Image>>,
+}
+
+impl ViewTemplate for PresetRow {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let index = self.index;
+        let on_rename = cx.create_callback(
+            move |name: In<String>, mut library: ResMut<PresetLibrary>| {
+                if let Err(err) = library.rename(index, name.0) {
+                    warn!("Failed to rename preset: {}", err);
+                }
+            },
+        );
+
+        Element::<NodeBundle>::new()
+            .named("PresetRow")
+            .style(style_preset_row)
+            .insert_dyn(
+                move |_| {
+                    On::<Pointer<DragEnd>>::run(
+                        move |event: Listener<Pointer<DragEnd>>,
+                              views: Query<&GraphViewId>,
+                              contents: Query<&GraphContentId>,
+                              nodes: Query<(&Node, &GlobalTransform)>,
+                              mut commands: Commands| {
+                            // Add the preset if it was dropped within the visible part of
+                            // the graph.
+                            let pointer = event.pointer_location.position;
+                            let (Ok(view), Ok(content)) =
+                                (views.get_single(), contents.get_single())
+                            else {
+                                return;
+                            };
+                            let (Ok(view), Ok(content)) = (nodes.get(view.0), nodes.get(content.0))
+                            else {
+                                return;
+                            };
+                            if !view.0.logical_rect(view.1).contains(pointer) {
+                                return;
+                            }
+                            let position = pointer - content.0.logical_rect(content.1).min;
+                            commands.add(InstantiatePresetCmd {
+                                preset: index,
+                                position: position.as_ivec2(),
+                            });
+                        },
+                    )
+                },
+                (),
+            )
+            .children((
+                Element::<NodeBundle>::new()
+                    .style(style_preset_thumbnail)
+                    .style_dyn(
+                        |thumbnail, sb| match thumbnail {
+                            Some(image) => {
+                                sb.background_image(image);
+                            }
+                            None => {
+                                sb.background_image(MaybeHandleOrPath::None);
+                            }
+                        },
+                        self.thumbnail.clone(),
+                    ),
+                EditableLabel::new()
+                    .value(self.name.clone())
+                    .style(style_preset_name)
+                    .on_change(on_rename),
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::{Easing, Keyframe},
+        ops::Mix,
+    };
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<LinearRgba>();
+        registry.register::<Mix>();
+        registry
+    }
+
+    fn sample_preset(name: &str) -> NodePreset {
+        let registry = registry();
+        let operator = save_operator(&registry, &Mix::default()).unwrap();
+        NodePreset {
+            name: name.to_string(),
+            nodes: vec![
+                PresetNode {
+                    offset: (0, 0),
+                    operator: operator.clone(),
+                },
+                PresetNode {
+                    offset: (120, 40),
+                    operator,
+                },
+            ],
+            connections: vec![PresetConnection {
+                output: (0, "output".to_string()),
+                input: (1, "input_a".to_string()),
+            }],
+            notes: vec![PresetNote {
+                offset: (0, 100),
+                size: (160., 60.),
+                color: (0.9, 0.8, 0.3),
+                text: "A \"quoted\" note,\nover two lines".to_string(),
+            }],
+            tracks: vec![SavedTrack {
+                node: 1,
+                field: "factor".to_string(),
+                keyframes: vec![
+                    Keyframe {
+                        time: 0.,
+                        value: 0.,
+                        easing: Easing::Linear,
+                    },
+                    Keyframe {
+                        time: 2.,
+                        value: 1.,
+                        easing: Easing::EaseOut,
+                    },
+                ],
+            }],
+            thumbnail: None,
+        }
+    }
+
+    /// A directory for a test to store presets in, which is empty at the start of the test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vortex-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_preset_ron_round_trip() {
+        let preset = sample_preset("Mixer");
+        let text = ron::ser::to_string_pretty(&preset, default()).unwrap();
+        let loaded: NodePreset = ron::from_str(&text).unwrap();
+        assert_eq!(loaded.name, "Mixer");
+        assert_eq!(loaded.nodes.len(), 2);
+        assert_eq!(loaded.nodes[1].offset, (120, 40));
+        assert_eq!(loaded.connections[0].input, (1, "input_a".to_string()));
+        assert_eq!(loaded.notes[0].text, preset.notes[0].text);
+        assert_eq!(loaded.tracks, preset.tracks);
+        assert_eq!(
+            ron::ser::to_string_pretty(&loaded, default()).unwrap(),
+            text
+        );
+
+        // The operators can be reconstructed from the saved data.
+        let registry = registry();
+        let operator = load_operator(&registry, &loaded.nodes[0].operator).unwrap();
+        assert!(operator.as_reflect().represents::<Mix>());
+    }
+
+    #[test]
+    fn test_preset_ron_without_notes_or_tracks() {
+        let text = r#"(
+            name: "Old",
+            nodes: [(offset: (0, 0), operator: "{}")],
+            connections: [],
+        )"#;
+        let loaded: NodePreset = ron::from_str(text).unwrap();
+        assert_eq!(loaded.name, "Old");
+        assert!(loaded.notes.is_empty());
+        assert!(loaded.tracks.is_empty());
+    }
+
+    #[test]
+    fn test_file_stem() {
+        assert_eq!(file_stem("Noise 2"), "Noise 2");
+        assert_eq!(file_stem("../../etc/passwd"), "______etc_passwd");
+        assert_eq!(file_stem(" a\\b: c "), "a_b_ c");
+        assert_eq!(file_stem("   "), "_");
+    }
+
+    #[test]
+    fn test_library_save_load_rename() {
+        let dir = test_dir("presets");
+        let mut library = PresetLibrary {
+            dir: dir.clone(),
+            presets: Vec::new(),
+        };
+        library.save(sample_preset("Mixer")).unwrap();
+        library.save(sample_preset("a/b")).unwrap();
+        assert!(dir.join("Mixer.ron").exists());
+        assert!(dir.join("a_b.ron").exists());
+
+        // Names which would share a file with an existing preset are taken.
+        assert_eq!(library.unique_name("a_b"), "a_b 2");
+
+        let mut loaded = PresetLibrary {
+            dir: dir.clone(),
+            presets: Vec::new(),
+        };
+        loaded.load();
+        let names: Vec<&str> = loaded.presets.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Mixer", "a/b"]);
+        assert_eq!(loaded.presets[0].tracks, library.presets[0].tracks);
+
+        assert_eq!(
+            loaded.rename(0, "  ".to_string()).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            loaded.rename(0, "a:b".to_string()).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+        loaded.rename(0, " Blend ".to_string()).unwrap();
+        assert_eq!(loaded.presets[0].name, "Blend");
+        assert!(dir.join("Blend.ron").exists());
+        assert!(!dir.join("Mixer.ron").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_thumbnail_round_trip() {
+        let dir = test_dir("thumbnails");
+        let mut images = Assets::<Image>::default();
+        let mut library = PresetLibrary {
+            dir: dir.clone(),
+            presets: Vec::new(),
+        };
+        let mut preset = sample_preset("Mixer");
+        let thumbnail = images.add(Image::default());
+        preset.thumbnail = Some(thumbnail.clone());
+        library.save(preset).unwrap();
+
+        let pixels = [255, 0, 0, 255, 0, 0, 255, 128];
+        library
+            .save_thumbnail(thumbnail.id(), UVec2::new(2, 1), &pixels)
+            .unwrap();
+        assert!(dir.join("Mixer.png").exists());
+
+        // Renaming the preset moves its thumbnail.
+        library.rename(0, "Blend".to_string()).unwrap();
+        assert!(dir.join("Blend.png").exists());
+
+        let mut loaded = PresetLibrary {
+            dir: dir.clone(),
+            presets: Vec::new(),
+        };
+        loaded.load();
+        loaded.load_thumbnails(&mut images);
+        let image = images
+            .get(loaded.presets[0].thumbnail.as_ref().unwrap())
+            .unwrap();
+        assert_eq!(image.size(), UVec2::new(2, 1));
+        assert_eq!(image.data, pixels);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,14 +1,37 @@
 use std::f32::consts::PI;
 
-use bevy::{color::palettes, prelude::*, ui};
+use bevy::{
+    color::palettes,
+    ecs::system::SystemState,
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode},
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+    ui,
+};
 use bevy_mod_stylebuilder::{StyleBuilder, StyleBuilderLayout};
 use bevy_quill::*;
 use bevy_quill_obsidian::{
-    controls::{Button, IconButton, ToolIconButton, ToolPalette},
+    controls::{
+        Button, ButtonVariant, Dialog, DialogBody, DialogFooter, DialogHeader, IconButton,
+        ToolIconButton, ToolPalette,
+    },
     viewport, RoundedCorners,
 };
 
-use crate::{gen::NodeOutput, graph::NodeSelected, pipeline::NodeShader3dHandle};
+use crate::{
+    color_management::{ColorManagement, ViewTransform, WorkingSpace},
+    gen::{export_node_shader, NodeOutput, ShaderLanguage, TerminalReader},
+    graph::{GraphResource, NodeSelected},
+    pipeline::NodeShader3dHandle,
+    readback::ImageReadback,
+    scopes::Scopes,
+    variations::Variations,
+};
 
 #[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 enum PreviewMode {
@@ -22,6 +45,18 @@ enum PreviewMode {
     Torus,
 }
 
+impl PreviewMode {
+    /// Number of tiles along each axis, for the flat preview modes.
+    fn tile_count(self) -> Option<u32> {
+        match self {
+            PreviewMode::Square => Some(1),
+            PreviewMode::Square2X2 => Some(2),
+            PreviewMode::Square3X3 => Some(3),
+            _ => None,
+        }
+    }
+}
+
 // Computed state that says whether we are in 3D preview mode vs 2d. This sets up and tears
 // down the 3d scene.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -42,6 +77,19 @@ impl ComputedStates for PreviewMode3d {
     }
 }
 
+// Computed state that says whether we are in one of the flat preview modes, which show the
+// output on a grid of tiles. This sets up and tears down the camera for them.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct PreviewModeTiled;
+
+impl ComputedStates for PreviewModeTiled {
+    type SourceStates = PreviewMode;
+
+    fn compute(sources: PreviewMode) -> Option<PreviewModeTiled> {
+        sources.tile_count().map(|_| PreviewModeTiled)
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct PreviewControls;
 
@@ -56,18 +104,199 @@ fn style_preview_controls(ss: &mut StyleBuilder) {
 impl ViewTemplate for PreviewControls {
     type View = impl View;
 
-    fn create(&self, _cx: &mut bevy_quill::Cx) -> Self::View {
+    fn create(&self, cx: &mut bevy_quill::Cx) -> Self::View {
+        let export_open = cx.create_mutable(false);
+        let on_export = cx.create_callback(move |world: &mut World| {
+            export_open.set(world, true);
+        });
+        let scopes_enabled = cx.use_resource::<Scopes>().enabled;
+        let on_scopes = cx.create_callback(|mut scopes: ResMut<Scopes>| {
+            scopes.enabled = !scopes.enabled;
+        });
+        let variations_enabled = cx.use_resource::<Variations>().enabled;
+        let on_variations = cx.create_callback(|mut variations: ResMut<Variations>| {
+            variations.enabled = !variations.enabled;
+        });
+
         Element::<NodeBundle>::new()
             .style(style_preview_controls)
             .children((
                 PreviewModeButtons,
+                SeamsButton,
+                ColorManagementButtons,
                 IconButton::new("embedded://bevy_quill_obsidian/assets/icons/lock.png"),
                 Button::new().children("Source..."),
-                Button::new().children("Export..."),
+                Button::new().children("Export...").on_click(on_export),
+                Button::new()
+                    .children("Scopes")
+                    .selected(scopes_enabled)
+                    .on_click(on_scopes),
+                Button::new()
+                    .children("Variations")
+                    .selected(variations_enabled)
+                    .on_click(on_variations),
+                ExportDialog { open: export_open },
+            ))
+    }
+}
+
+/// Dialog which exports the shader for the selected node in one of the supported languages.
+#[derive(Clone, PartialEq)]
+struct ExportDialog {
+    open: Mutable<bool>,
+}
+
+impl ViewTemplate for ExportDialog {
+    type View = impl View;
+
+    fn create(&self, cx: &mut bevy_quill::Cx) -> Self::View {
+        let open = self.open;
+        let language = cx.create_mutable(ShaderLanguage::default());
+        let on_close = cx.create_callback(move |world: &mut World| {
+            open.set(world, false);
+        });
+        let on_export = cx.create_callback(move |world: &mut World| {
+            let language = language.get(world);
+            let working_space = world.resource::<ColorManagement>().working_space;
+            let seed = world.resource::<GraphResource>().0.seed;
+            let mut state: SystemState<(TerminalReader, Query<Entity, With<NodeSelected>>)> =
+                SystemState::new(world);
+            let (reader, selection) = state.get(world);
+            for node_id in selection.iter() {
+                let Ok(node) = reader.nodes.get(node_id) else {
+                    continue;
+                };
+                let path = format!(
+                    "{}.{}",
+                    node.title().to_lowercase(),
+                    language.backend().file_extension()
+                );
+                match export_node_shader(&reader, node_id, language, working_space, seed) {
+                    Some(Ok(source)) => match std::fs::write(&path, source) {
+                        Ok(()) => info!("Exported shader to {}", path),
+                        Err(err) => warn!("Failed to write {}: {}", path, err),
+                    },
+                    Some(Err(err)) => warn!("Failed to export {}: {}", path, err),
+                    None => {}
+                }
+            }
+            open.set(world, false);
+        });
+
+        Dialog::new()
+            .width(ui::Val::Px(300.))
+            .open(open.get(cx))
+            .on_close(on_close)
+            .children((
+                DialogHeader::new().children("Export Shader"),
+                DialogBody::new().children((
+                    ExportLanguageButton {
+                        language: ShaderLanguage::Wgsl,
+                        selected: language,
+                    },
+                    ExportLanguageButton {
+                        language: ShaderLanguage::Glsl,
+                        selected: language,
+                    },
+                    ExportLanguageButton {
+                        language: ShaderLanguage::Hlsl,
+                        selected: language,
+                    },
+                )),
+                DialogFooter::new().children((
+                    Button::new().children("Cancel").on_click(on_close),
+                    Button::new()
+                        .children("Export")
+                        .variant(ButtonVariant::Primary)
+                        .autofocus(true)
+                        .on_click(on_export),
+                )),
             ))
     }
 }
 
+/// Button which selects the language to export.
+#[derive(Clone, PartialEq)]
+struct ExportLanguageButton {
+    language: ShaderLanguage,
+    selected: Mutable<ShaderLanguage>,
+}
+
+impl ViewTemplate for ExportLanguageButton {
+    type View = impl View;
+
+    fn create(&self, cx: &mut bevy_quill::Cx) -> Self::View {
+        let language = self.language;
+        let selected = self.selected;
+        let on_click = cx.create_callback(move |world: &mut World| {
+            selected.set(world, language);
+        });
+
+        Button::new()
+            .children(language.backend().name())
+            .selected(selected.get(cx) == language)
+            .on_click(on_click)
+    }
+}
+
+/// Buttons which switch the working space of the graph, and the view transform of the preview.
+#[derive(Clone, PartialEq)]
+struct ColorManagementButtons;
+
+impl ViewTemplate for ColorManagementButtons {
+    type View = impl View;
+
+    fn create(&self, cx: &mut bevy_quill::Cx) -> Self::View {
+        let color = *cx.use_resource::<ColorManagement>();
+        let on_working_space = cx.create_callback(|mut color: ResMut<ColorManagement>| {
+            color.working_space = match color.working_space {
+                WorkingSpace::Linear => WorkingSpace::Srgb,
+                WorkingSpace::Srgb => WorkingSpace::Linear,
+            };
+        });
+        let on_view_transform = cx.create_callback(|mut color: ResMut<ColorManagement>| {
+            color.view_transform = match color.view_transform {
+                ViewTransform::Srgb => ViewTransform::Aces,
+                ViewTransform::Aces => ViewTransform::Srgb,
+            };
+        });
+
+        (
+            Button::new()
+                .children(format!("Working: {}", color.working_space.name()))
+                .on_click(on_working_space),
+            Button::new()
+                .children(format!("View: {}", color.view_transform.name()))
+                .on_click(on_view_transform),
+        )
+    }
+}
+
+/// Button which toggles the outlines between tiles, shown in the tiled preview modes.
+#[derive(Clone, PartialEq)]
+struct SeamsButton;
+
+impl ViewTemplate for SeamsButton {
+    type View = impl View;
+
+    fn create(&self, cx: &mut bevy_quill::Cx) -> Self::View {
+        let mode = *cx.use_resource::<State<PreviewMode>>().get();
+        let seams = cx.use_resource::<PreviewSeams>().0;
+        let on_click = cx.create_callback(|mut seams: ResMut<PreviewSeams>| {
+            seams.0 = !seams.0;
+        });
+
+        Cond::new(
+            mode.tile_count().is_some_and(|count| count > 1),
+            Button::new()
+                .children("Seams")
+                .selected(seams)
+                .on_click(on_click),
+            (),
+        )
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct PreviewModeButtons;
 
@@ -156,6 +385,20 @@ pub(crate) struct PreviewShape;
 #[derive(Component)]
 pub(crate) struct Preview3DEntity;
 
+/// A marker component for the tiles of the flat preview modes.
+#[derive(Component)]
+pub(crate) struct PreviewTile;
+
+#[derive(Component)]
+pub(crate) struct PreviewTiledEntity;
+
+/// Whether to outline the tiles of the flat preview, so that seams are easier to spot.
+#[derive(Resource, Default)]
+struct PreviewSeams(bool);
+
+/// Width of the grid of tiles in the flat preview modes, in world units.
+const TILED_PREVIEW_SIZE: f32 = 2.;
+
 #[derive(Resource, Default)]
 pub struct PreviewShaderHandle(pub Handle<Shader>);
 
@@ -210,9 +453,79 @@ fn exit_preview_3d(mut commands: Commands, query: Query<Entity, With<Preview3DEn
     }
 }
 
-// pub fn enter_mode_square(mut commands: Commands) {}
+fn enter_preview_tiled(mut commands: Commands) {
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0., 0., 5.).looking_at(Vec3::ZERO, Vec3::Y),
+            projection: Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical(TILED_PREVIEW_SIZE * 1.2),
+                ..default()
+            }),
+            ..default()
+        },
+        viewport::ViewportCamera,
+        PreviewTiledEntity,
+    ));
+}
+
+fn exit_preview_tiled(mut commands: Commands, query: Query<Entity, With<PreviewTiledEntity>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn()
+    }
+}
+
+/// Spawn a grid of tiles which all show the output, so that seams between them are visible.
+fn enter_mode_tiled(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mode: Res<State<PreviewMode>>,
+    shader: Res<PreviewShaderHandle>,
+) {
+    let Some(count) = mode.get().tile_count() else {
+        return;
+    };
+    let size = TILED_PREVIEW_SIZE / count as f32;
+    let shape = meshes.add(Rectangle::new(size, size));
+    for y in 0..count {
+        for x in 0..count {
+            let offset = (Vec2::new(x as f32, y as f32) - (count - 1) as f32 * 0.5) * size;
+            commands.spawn((
+                shape.clone(),
+                NodeShader3dHandle(shader.0.clone()),
+                SpatialBundle {
+                    transform: Transform::from_translation(offset.extend(0.)),
+                    ..SpatialBundle::INHERITED_IDENTITY
+                },
+                PreviewShape,
+                PreviewTile,
+            ));
+        }
+    }
+}
 
-// pub fn exit_mode_square(mut commands: Commands) {}
+fn draw_preview_seams(seams: Res<PreviewSeams>, mode: Res<State<PreviewMode>>, mut gizmos: Gizmos) {
+    let Some(count) = mode.get().tile_count() else {
+        return;
+    };
+    if !seams.0 {
+        return;
+    }
+    let half = TILED_PREVIEW_SIZE * 0.5;
+    let size = TILED_PREVIEW_SIZE / count as f32;
+    for i in 1..count {
+        let p = i as f32 * size - half;
+        gizmos.line(
+            Vec3::new(p, -half, 0.01),
+            Vec3::new(p, half, 0.01),
+            palettes::css::FUCHSIA,
+        );
+        gizmos.line(
+            Vec3::new(-half, p, 0.01),
+            Vec3::new(half, p, 0.01),
+            palettes::css::FUCHSIA,
+        );
+    }
+}
 
 fn exit_mode_shape3d(mut commands: Commands, query: Query<Entity, With<PreviewShape>>) {
     for shape in query.iter() {
@@ -289,7 +602,10 @@ fn enter_mode_torus(
     ));
 }
 
-fn rotate_preview_shapes(mut query: Query<&mut Transform, With<PreviewShape>>, time: Res<Time>) {
+fn rotate_preview_shapes(
+    mut query: Query<&mut Transform, (With<PreviewShape>, Without<PreviewTile>)>,
+    time: Res<Time>,
+) {
     for mut transform in &mut query {
         transform.rotate_y(time.delta_seconds() / 2.);
     }
@@ -339,15 +655,120 @@ fn update_preview_shader(
 #[derive(Resource, Default)]
 struct PlaceholderShaderHandle(pub Handle<Shader>);
 
+/// Size of preset thumbnails, in pixels.
+const THUMBNAIL_SIZE: u32 = 48;
+
+/// Render layer for thumbnail captures, so that they do not appear in the preview.
+const THUMBNAIL_LAYER: usize = 2;
+
+/// Camera, or tile, which renders a shader into a thumbnail image. They are despawned once the
+/// count reaches zero, giving the renderer time to draw the image, and the image is then read
+/// back so that it can be saved.
+#[derive(Component)]
+struct ThumbnailCapture(u32);
+
+/// Render the output of a node shader, as a flat tile, into a new image for use as a
+/// thumbnail. The pixels of the image are sent as an
+/// [`ImageReadbackEvent`](crate::readback::ImageReadbackEvent) once it has been drawn.
+pub(crate) fn capture_thumbnail(world: &mut World, shader: Handle<Shader>) -> Handle<Image> {
+    let size = Extent3d {
+        width: THUMBNAIL_SIZE,
+        height: THUMBNAIL_SIZE,
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_SRC
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let image = world.resource_mut::<Assets<Image>>().add(image);
+    let tile = world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Rectangle::new(1., 1.));
+
+    world.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(image.clone()),
+                order: -1,
+                ..default()
+            },
+            transform: Transform::from_xyz(0., 0., 5.).looking_at(Vec3::ZERO, Vec3::Y),
+            projection: Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: 1.,
+                    height: 1.,
+                },
+                ..default()
+            }),
+            ..default()
+        },
+        RenderLayers::layer(THUMBNAIL_LAYER),
+        ThumbnailCapture(2),
+    ));
+    world.spawn((
+        tile,
+        NodeShader3dHandle(shader),
+        SpatialBundle::INHERITED_IDENTITY,
+        RenderLayers::layer(THUMBNAIL_LAYER),
+        ThumbnailCapture(2),
+    ));
+    image
+}
+
+fn finish_thumbnail_captures(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ThumbnailCapture, Option<&Camera>)>,
+    mut readback: ResMut<ImageReadback>,
+) {
+    for (entity, mut capture, camera) in query.iter_mut() {
+        if capture.0 == 0 {
+            if let Some(RenderTarget::Image(image)) = camera.map(|camera| &camera.target) {
+                readback.request(image.clone());
+            }
+            commands.entity(entity).despawn();
+        } else {
+            capture.0 -= 1;
+        }
+    }
+}
+
 pub struct PreviewPlugin;
 
 impl Plugin for PreviewPlugin {
     fn build(&self, app: &mut App) {
         app.insert_state(PreviewMode::Cuboid)
             .add_computed_state::<PreviewMode3d>()
-            .add_systems(Update, (update_preview_shader, rotate_preview_shapes))
+            .add_computed_state::<PreviewModeTiled>()
+            .init_resource::<PreviewSeams>()
+            .add_systems(
+                Update,
+                (
+                    update_preview_shader,
+                    rotate_preview_shapes,
+                    draw_preview_seams,
+                    finish_thumbnail_captures,
+                ),
+            )
             .add_systems(OnEnter(PreviewMode3d), enter_preview_3d)
             .add_systems(OnExit(PreviewMode3d), exit_preview_3d)
+            .add_systems(OnEnter(PreviewModeTiled), enter_preview_tiled)
+            .add_systems(OnExit(PreviewModeTiled), exit_preview_tiled)
+            .add_systems(OnEnter(PreviewMode::Square), enter_mode_tiled)
+            .add_systems(OnEnter(PreviewMode::Square2X2), enter_mode_tiled)
+            .add_systems(OnEnter(PreviewMode::Square3X3), enter_mode_tiled)
             .add_systems(OnEnter(PreviewMode::Cuboid), enter_mode_cuboid)
             .add_systems(OnEnter(PreviewMode::Sphere), enter_mode_sphere)
             .add_systems(OnEnter(PreviewMode::Tetra), enter_mode_tetra)
@@ -355,7 +776,10 @@ impl Plugin for PreviewPlugin {
             .add_systems(OnExit(PreviewMode::Sphere), exit_mode_shape3d)
             .add_systems(OnExit(PreviewMode::Cuboid), exit_mode_shape3d)
             .add_systems(OnExit(PreviewMode::Tetra), exit_mode_shape3d)
-            .add_systems(OnExit(PreviewMode::Torus), exit_mode_shape3d);
+            .add_systems(OnExit(PreviewMode::Torus), exit_mode_shape3d)
+            .add_systems(OnExit(PreviewMode::Square), exit_mode_shape3d)
+            .add_systems(OnExit(PreviewMode::Square2X2), exit_mode_shape3d)
+            .add_systems(OnExit(PreviewMode::Square3X3), exit_mode_shape3d);
     }
 
     fn finish(&self, app: &mut App) {
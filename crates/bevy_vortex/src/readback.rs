@@ -0,0 +1,221 @@
+use std::sync::{
+    mpsc::{channel, Receiver, Sender, TryRecvError},
+    Mutex,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        render_asset::RenderAssets,
+        render_graph::{self, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel},
+        render_resource::{
+            Buffer, BufferAsyncError, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer,
+            ImageDataLayout, Maintain, MapMode,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::GpuImage,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+/// Resource used to request that images be copied back from the GPU. Each requested image is
+/// copied once, after the cameras have rendered, and its pixels are delivered as an
+/// [`ImageReadbackEvent`] on a later frame. The image must have `COPY_SRC` usage and a format
+/// with four bytes per pixel.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub(crate) struct ImageReadback {
+    requests: Vec<Handle<Image>>,
+}
+
+impl ImageReadback {
+    /// Copy the current contents of an image back to the main world.
+    pub(crate) fn request(&mut self, image: Handle<Image>) {
+        self.requests.push(image);
+    }
+}
+
+/// Sent when the pixels of an image requested with [`ImageReadback`] have been read back.
+#[derive(Event)]
+pub(crate) struct ImageReadbackEvent {
+    pub(crate) image: AssetId<Image>,
+    pub(crate) size: UVec2,
+    /// The pixels, four bytes each, with no padding between rows.
+    pub(crate) data: Vec<u8>,
+}
+
+/// Receives the pixels of copied images from the render world.
+#[derive(Resource)]
+struct ReadbackReceiver(Mutex<Receiver<ImageReadbackEvent>>);
+
+/// Render world resource used to send the pixels of copied images to the main world.
+#[derive(Resource)]
+struct ReadbackSender(Sender<ImageReadbackEvent>);
+
+/// A copy of an image which is waiting for its buffer to be mapped.
+struct PendingReadback {
+    image: AssetId<Image>,
+    size: UVec2,
+    stride: usize,
+    buffer: Buffer,
+    /// Receives the result of mapping the buffer, once mapping has been requested.
+    mapped: Option<Receiver<Result<(), BufferAsyncError>>>,
+}
+
+/// Render world resource which holds the copies which have not been read yet.
+#[derive(Resource, Default)]
+struct PendingReadbacks(Mutex<Vec<PendingReadback>>);
+
+fn clear_readback_requests(mut readback: ResMut<ImageReadback>) {
+    if !readback.requests.is_empty() {
+        readback.requests.clear();
+    }
+}
+
+fn receive_readbacks(receiver: Res<ReadbackReceiver>, mut events: EventWriter<ImageReadbackEvent>) {
+    events.send_batch(receiver.0.lock().unwrap().try_iter());
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, RenderLabel)]
+struct ReadbackCopyLabel;
+
+/// Render graph node which copies each requested image into a new readback buffer.
+#[derive(Default)]
+struct ReadbackCopyNode;
+
+impl render_graph::Node for ReadbackCopyNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let readback = world.resource::<ImageReadback>();
+        if readback.requests.is_empty() {
+            return Ok(());
+        }
+        let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+        let mut pending = world.resource::<PendingReadbacks>().0.lock().unwrap();
+        for handle in readback.requests.iter() {
+            let Some(image) = gpu_images.get(handle) else {
+                continue;
+            };
+            let stride = RenderDevice::align_copy_bytes_per_row(image.size.x as usize * 4);
+            let buffer = render_context
+                .render_device()
+                .create_buffer(&BufferDescriptor {
+                    label: Some("image_readback_buffer"),
+                    size: (stride * image.size.y as usize) as u64,
+                    usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+            render_context.command_encoder().copy_texture_to_buffer(
+                image.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer: &buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(stride as u32),
+                        rows_per_image: None,
+                    },
+                },
+                Extent3d {
+                    width: image.size.x,
+                    height: image.size.y,
+                    ..default()
+                },
+            );
+            pending.push(PendingReadback {
+                image: handle.id(),
+                size: image.size,
+                stride,
+                buffer,
+                mapped: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Start mapping the buffers which were copied this frame, and send the contents of the ones
+/// which have finished mapping to the main world. This never waits for the GPU, so a copy is
+/// usually delivered a frame or two after it was made.
+fn map_readbacks(
+    pending: Res<PendingReadbacks>,
+    sender: Res<ReadbackSender>,
+    render_device: Res<RenderDevice>,
+) {
+    let mut pending = pending.0.lock().unwrap();
+    if pending.is_empty() {
+        return;
+    }
+    for readback in pending.iter_mut().filter(|r| r.mapped.is_none()) {
+        let (mapped_tx, mapped_rx) = channel();
+        readback
+            .buffer
+            .slice(..)
+            .map_async(MapMode::Read, move |result| {
+                let _ = mapped_tx.send(result);
+            });
+        readback.mapped = Some(mapped_rx);
+    }
+    render_device.poll(Maintain::Poll);
+
+    pending.retain(|readback| {
+        let Some(mapped) = readback.mapped.as_ref() else {
+            return true;
+        };
+        match mapped.try_recv() {
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => false,
+            Ok(Err(err)) => {
+                warn!("Failed to read back image: {}", err);
+                false
+            }
+            Ok(Ok(())) => {
+                let row = readback.size.x as usize * 4;
+                let mut data = Vec::with_capacity(row * readback.size.y as usize);
+                {
+                    let mapped = readback.buffer.slice(..).get_mapped_range();
+                    for y in 0..readback.size.y as usize {
+                        data.extend_from_slice(&mapped[y * readback.stride..][..row]);
+                    }
+                }
+                readback.buffer.unmap();
+                let _ = sender.0.send(ImageReadbackEvent {
+                    image: readback.image,
+                    size: readback.size,
+                    data,
+                });
+                false
+            }
+        }
+    });
+}
+
+pub struct ImageReadbackPlugin;
+
+impl Plugin for ImageReadbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ImageReadback>()
+            .add_event::<ImageReadbackEvent>()
+            .add_plugins(ExtractResourcePlugin::<ImageReadback>::default())
+            .add_systems(First, clear_readback_requests)
+            .add_systems(PreUpdate, receive_readbacks);
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .init_resource::<PendingReadbacks>()
+            .add_systems(Render, map_readbacks.after(RenderSet::Render));
+        let mut graph = render_app.world_mut().resource_mut::<RenderGraph>();
+        graph.add_node(ReadbackCopyLabel, ReadbackCopyNode);
+        graph.add_node_edge(bevy::render::graph::CameraDriverLabel, ReadbackCopyLabel);
+    }
+
+    fn finish(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        app.insert_resource(ReadbackReceiver(Mutex::new(receiver)));
+        app.sub_app_mut(RenderApp)
+            .insert_resource(ReadbackSender(sender));
+    }
+}
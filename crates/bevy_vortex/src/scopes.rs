@@ -0,0 +1,427 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode},
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    ui,
+};
+use bevy_mod_stylebuilder::*;
+use bevy_quill::*;
+use bevy_quill_obsidian::colors;
+
+use crate::{
+    pipeline::NodeShader3dHandle,
+    preview::PreviewShaderHandle,
+    readback::{ImageReadback, ImageReadbackEvent},
+};
+
+/// Width and height of the image of the previewed output which the scopes analyze.
+const SCOPE_SIZE: u32 = 64;
+
+/// Number of bins in the histogram.
+const HISTOGRAM_BINS: usize = 64;
+
+/// Height of the histogram and waveform images, in pixels. This is also the number of
+/// luminance levels in the waveform.
+const SCOPE_HEIGHT: usize = 48;
+
+/// Render layer for the scope capture, so that it does not appear in the preview.
+const SCOPE_LAYER: usize = 1;
+
+/// Number of frames to wait for a readback of the scope image before requesting another one,
+/// in case the request was dropped because the image wasn't ready on the GPU.
+const READBACK_TIMEOUT: u32 = 30;
+
+/// Minimum, maximum and mean of a color channel, from 0 to 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChannelStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+}
+
+/// Resource which holds the analysis of the previewed output. While the scopes are enabled,
+/// the output is rendered offscreen and analyzed whenever it changes.
+#[derive(Resource)]
+pub struct Scopes {
+    pub(crate) enabled: bool,
+    /// Histogram of the red, green and blue channels.
+    pub(crate) histogram: Handle<Image>,
+    /// Distribution of luminance for each column of the output.
+    pub(crate) waveform: Handle<Image>,
+    /// Statistics for the red, green and blue channels.
+    pub(crate) stats: [ChannelStats; 3],
+}
+
+/// Analysis of the pixels of an image.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ScopeAnalysis {
+    /// Number of pixels in each bin, for the red, green and blue channels.
+    pub(crate) histogram: [[u32; HISTOGRAM_BINS]; 3],
+    /// For each column of the image, the number of pixels at each luminance level, from
+    /// darkest to brightest.
+    pub(crate) waveform: Vec<[u32; SCOPE_HEIGHT]>,
+    pub(crate) stats: [ChannelStats; 3],
+}
+
+fn bin(value: f32, bins: usize) -> usize {
+    ((value * bins as f32) as usize).min(bins - 1)
+}
+
+/// Analyze an RGBA image with 8 bits per channel, whose rows are `stride` bytes apart.
+pub(crate) fn analyze(pixels: &[u8], width: usize, height: usize, stride: usize) -> ScopeAnalysis {
+    let mut histogram = [[0; HISTOGRAM_BINS]; 3];
+    let mut waveform = vec![[0; SCOPE_HEIGHT]; width];
+    let mut min = [1f32; 3];
+    let mut max = [0f32; 3];
+    let mut sum = [0f32; 3];
+    for y in 0..height {
+        let row = &pixels[y * stride..][..width * 4];
+        for (x, pixel) in row.chunks_exact(4).enumerate() {
+            let rgb = [pixel[0], pixel[1], pixel[2]].map(|value| value as f32 / 255.);
+            for channel in 0..3 {
+                let value = rgb[channel];
+                histogram[channel][bin(value, HISTOGRAM_BINS)] += 1;
+                min[channel] = min[channel].min(value);
+                max[channel] = max[channel].max(value);
+                sum[channel] += value;
+            }
+            let luminance = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+            waveform[x][bin(luminance, SCOPE_HEIGHT)] += 1;
+        }
+    }
+
+    let count = width * height;
+    let stats = std::array::from_fn(|channel| {
+        if count == 0 {
+            ChannelStats::default()
+        } else {
+            ChannelStats {
+                min: min[channel],
+                max: max[channel],
+                mean: sum[channel] / count as f32,
+            }
+        }
+    });
+    ScopeAnalysis {
+        histogram,
+        waveform,
+        stats,
+    }
+}
+
+/// Draw the histogram as overlapping red, green and blue bars.
+fn draw_histogram(analysis: &ScopeAnalysis, data: &mut [u8]) {
+    let peak = analysis
+        .histogram
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(0);
+    data.fill(0);
+    for (x, pixel) in data.chunks_exact_mut(4).enumerate() {
+        let column = x % HISTOGRAM_BINS;
+        let level = SCOPE_HEIGHT - 1 - x / HISTOGRAM_BINS;
+        for (value, histogram) in pixel.iter_mut().zip(&analysis.histogram) {
+            let bar = histogram[column] as usize * SCOPE_HEIGHT;
+            if peak > 0 && bar > level * peak as usize {
+                *value = 255;
+            }
+        }
+        pixel[3] = 255;
+    }
+}
+
+/// Draw the waveform, with the brightness of each point showing how many pixels it covers.
+fn draw_waveform(analysis: &ScopeAnalysis, data: &mut [u8]) {
+    let width = analysis.waveform.len();
+    // Saturate at an eighth of the column, so that sparse values are still visible.
+    let full = (SCOPE_SIZE as usize / 8).max(1);
+    for (x, pixel) in data.chunks_exact_mut(4).enumerate() {
+        let level = SCOPE_HEIGHT - 1 - x / width;
+        let count = analysis.waveform[x % width][level] as usize;
+        let value = (count * 255 / full).min(255) as u8;
+        pixel.copy_from_slice(&[value, value, value, 255]);
+    }
+}
+
+/// Marker for the camera which renders the previewed output for the scopes.
+#[derive(Component)]
+struct ScopeCamera;
+
+/// Marker for the quad which shows the previewed output to the scope camera.
+#[derive(Component)]
+struct ScopeTile;
+
+/// The image which the scope camera renders into, and whether it should be read back.
+#[derive(Resource)]
+struct ScopeTarget {
+    image: Handle<Image>,
+    enabled: bool,
+    /// Number of frames since the pending readback was requested, if there is one. Only one
+    /// readback is in flight at a time.
+    pending: Option<u32>,
+}
+
+fn setup_scopes(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    shader: Res<PreviewShaderHandle>,
+) {
+    let size = Extent3d {
+        width: SCOPE_SIZE,
+        height: SCOPE_SIZE,
+        ..default()
+    };
+    let mut target = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    target.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_SRC
+        | TextureUsages::COPY_DST
+        | TextureUsages::RENDER_ATTACHMENT;
+    let target = images.add(target);
+
+    let mut scope_image = |width: usize| {
+        images.add(Image::new_fill(
+            Extent3d {
+                width: width as u32,
+                height: SCOPE_HEIGHT as u32,
+                ..default()
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        ))
+    };
+    commands.insert_resource(Scopes {
+        enabled: false,
+        histogram: scope_image(HISTOGRAM_BINS),
+        waveform: scope_image(SCOPE_SIZE as usize),
+        stats: default(),
+    });
+    commands.insert_resource(ScopeTarget {
+        image: target.clone(),
+        enabled: false,
+        pending: None,
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(target),
+                order: -2,
+                is_active: false,
+                ..default()
+            },
+            transform: Transform::from_xyz(0., 0., 5.).looking_at(Vec3::ZERO, Vec3::Y),
+            projection: Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: 1.,
+                    height: 1.,
+                },
+                ..default()
+            }),
+            ..default()
+        },
+        RenderLayers::layer(SCOPE_LAYER),
+        ScopeCamera,
+    ));
+    commands.spawn((
+        meshes.add(Rectangle::new(1., 1.)),
+        NodeShader3dHandle(shader.0.clone()),
+        SpatialBundle::INHERITED_IDENTITY,
+        RenderLayers::layer(SCOPE_LAYER),
+        ScopeTile,
+    ));
+}
+
+/// Show the previewed output on the scope tile, only render it while the scopes are enabled,
+/// and request a readback of the rendered image unless one is already pending.
+fn update_scope_capture(
+    scopes: Res<Scopes>,
+    shader: Res<PreviewShaderHandle>,
+    mut target: ResMut<ScopeTarget>,
+    mut readback: ResMut<ImageReadback>,
+    mut cameras: Query<&mut Camera, With<ScopeCamera>>,
+    mut tiles: Query<&mut NodeShader3dHandle, With<ScopeTile>>,
+) {
+    if target.enabled != scopes.enabled {
+        target.enabled = scopes.enabled;
+        for mut camera in cameras.iter_mut() {
+            camera.is_active = scopes.enabled;
+        }
+    }
+    if shader.is_changed() {
+        for mut tile in tiles.iter_mut() {
+            tile.0 = shader.0.clone();
+        }
+    }
+    if !target.enabled {
+        return;
+    }
+    match target.pending {
+        Some(frames) if frames < READBACK_TIMEOUT => target.pending = Some(frames + 1),
+        _ => {
+            readback.request(target.image.clone());
+            target.pending = Some(0);
+        }
+    }
+}
+
+/// Analyze the pixels read back from the render world, when they differ from the last ones.
+fn update_scopes(
+    mut scopes: ResMut<Scopes>,
+    mut images: ResMut<Assets<Image>>,
+    mut target: ResMut<ScopeTarget>,
+    mut events: EventReader<ImageReadbackEvent>,
+    mut previous: Local<Vec<u8>>,
+) {
+    let image_id = target.image.id();
+    let Some(event) = events.read().filter(|event| event.image == image_id).last() else {
+        return;
+    };
+    target.pending = None;
+    if !scopes.enabled || event.data == *previous {
+        return;
+    }
+    // Read back rows are tightly packed.
+    let width = event.size.x as usize;
+    let analysis = analyze(&event.data, width, event.size.y as usize, width * 4);
+    *previous = event.data.clone();
+    if let Some(image) = images.get_mut(&scopes.histogram) {
+        draw_histogram(&analysis, &mut image.data);
+    }
+    if let Some(image) = images.get_mut(&scopes.waveform) {
+        draw_waveform(&analysis, &mut image.data);
+    }
+    scopes.stats = analysis.stats;
+}
+
+fn style_scopes(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .gap(4)
+        .padding(4)
+        .background_color(colors::U1)
+        .border_radius(4.);
+}
+
+fn style_scope_image(ss: &mut StyleBuilder) {
+    ss.height(SCOPE_HEIGHT as f32 * 1.5)
+        .align_self(ui::AlignSelf::Stretch);
+}
+
+fn style_stats_row(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .justify_content(ui::JustifyContent::SpaceBetween)
+        .font_size(12.);
+}
+
+/// Panel which shows the histogram, waveform and channel statistics of the previewed output.
+#[derive(Clone, PartialEq)]
+pub struct ScopesPanel;
+
+impl ViewTemplate for ScopesPanel {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let scopes = cx.use_resource::<Scopes>();
+        let enabled = scopes.enabled;
+        let histogram = scopes.histogram.clone();
+        let waveform = scopes.waveform.clone();
+        let stats = scopes.stats;
+
+        Cond::new(
+            enabled,
+            Element::<NodeBundle>::new()
+                .named("Scopes")
+                .style(style_scopes)
+                .children((
+                    Element::<NodeBundle>::new()
+                        .style(style_scope_image)
+                        .style_dyn(
+                            |histogram, sb| {
+                                sb.background_image(histogram);
+                            },
+                            histogram,
+                        ),
+                    Element::<NodeBundle>::new()
+                        .style(style_scope_image)
+                        .style_dyn(
+                            |waveform, sb| {
+                                sb.background_image(waveform);
+                            },
+                            waveform,
+                        ),
+                    For::index(&["R", "G", "B"], move |channel, index| {
+                        let stats = stats[index];
+                        Element::<NodeBundle>::new()
+                            .style(style_stats_row)
+                            .children((
+                                *channel,
+                                format!("min {:.3}", stats.min),
+                                format!("max {:.3}", stats.max),
+                                format!("mean {:.3}", stats.mean),
+                            ))
+                    }),
+                )),
+            (),
+        )
+    }
+}
+
+pub struct ScopesPlugin;
+
+impl Plugin for ScopesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_scopes)
+            .add_systems(Update, (update_scopes, update_scope_capture).chain());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_stats() {
+        // Two pixels: black, and half-grey with full red.
+        let pixels = [0, 0, 0, 255, 255, 128, 128, 255];
+        let analysis = analyze(&pixels, 2, 1, 8);
+        assert_eq!(analysis.stats[0].min, 0.);
+        assert_eq!(analysis.stats[0].max, 1.);
+        assert_eq!(analysis.stats[0].mean, 0.5);
+        assert_eq!(analysis.histogram[0][0], 1);
+        assert_eq!(analysis.histogram[0][HISTOGRAM_BINS - 1], 1);
+        assert_eq!(analysis.histogram[1][HISTOGRAM_BINS / 2], 1);
+        assert_eq!(analysis.waveform[0][0], 1);
+    }
+
+    #[test]
+    fn test_analyze_skips_row_padding() {
+        // One pixel per row, with padding which would count as white if it were read.
+        let pixels = [0, 0, 0, 255, 255, 255, 255, 255, 0, 0, 0, 255];
+        let analysis = analyze(&pixels, 1, 2, 8);
+        assert_eq!(analysis.histogram[0][0], 2);
+        assert_eq!(analysis.stats[2].max, 0.);
+        assert_eq!(analysis.waveform[0][0], 2);
+    }
+
+    #[test]
+    fn test_analyze_empty() {
+        let analysis = analyze(&[], 0, 0, 0);
+        assert_eq!(analysis.stats, [ChannelStats::default(); 3]);
+    }
+}
@@ -0,0 +1,255 @@
+use bevy::{prelude::*, ui};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill::*;
+use bevy_quill_obsidian::{colors, controls::Button};
+
+use crate::{
+    animation::{Easing, Timeline},
+    commands::KeyframeSelectedCmd,
+    graph::GraphNode,
+};
+
+fn style_timeline(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch)
+        .background_color(colors::U2)
+        .border_top(1)
+        .border_left(1)
+        .border_color(Color::BLACK)
+        .padding(4)
+        .gap(4);
+}
+
+fn style_timeline_controls(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(6);
+}
+
+fn style_track(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(6);
+}
+
+fn style_track_label(ss: &mut StyleBuilder) {
+    ss.width(160).color(colors::DIM);
+}
+
+fn style_track_lane(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.)
+        .height(12)
+        .background_color(colors::U1)
+        .border_radius(2.);
+}
+
+fn style_keyframe(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .top(2)
+        .width(8)
+        .height(8)
+        .margin_left(-4)
+        .border_radius(2.);
+}
+
+fn style_playhead(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .top(0)
+        .bottom(0)
+        .width(1)
+        .background_color(colors::FOREGROUND)
+        .pointer_events(false);
+}
+
+/// Color used to draw a keyframe, which indicates its easing mode.
+fn easing_color(easing: Easing) -> Srgba {
+    match easing {
+        Easing::Linear => colors::LIGHT,
+        Easing::EaseIn => colors::RESOURCE,
+        Easing::EaseOut => colors::ASSET,
+        Easing::EaseInOut => colors::ACCENT,
+        Easing::Step => colors::U4,
+    }
+}
+
+/// Dope-sheet style panel which shows the animated parameters and their keyframes, along with
+/// the playback controls.
+#[derive(Clone, PartialEq)]
+pub struct TimelinePanel;
+
+impl ViewTemplate for TimelinePanel {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let timeline = cx.use_resource::<Timeline>();
+        let playing = timeline.playing;
+        let status = format!("{:.2} / {:.2} s", timeline.time, timeline.duration);
+        let tracks: Vec<TimelineTrack> = timeline
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(index, track)| TimelineTrack {
+                index,
+                node: track.node,
+                field: track.field,
+                keys: track.keyframes.iter().map(|k| (k.time, k.easing)).collect(),
+            })
+            .collect();
+
+        let on_play = cx.create_callback(|mut timeline: ResMut<Timeline>| {
+            timeline.playing = !timeline.playing;
+        });
+        let on_rewind = cx.create_callback(|mut timeline: ResMut<Timeline>| {
+            timeline.time = 0.;
+        });
+        let on_key = cx.create_callback(|mut commands: Commands| {
+            commands.add(KeyframeSelectedCmd);
+        });
+
+        Element::<NodeBundle>::new()
+            .named("Timeline")
+            .style(style_timeline)
+            .children((
+                Element::<NodeBundle>::new()
+                    .style(style_timeline_controls)
+                    .children((
+                        Button::new()
+                            .children(if playing { "Pause" } else { "Play" })
+                            .on_click(on_play),
+                        Button::new().children("Rewind").on_click(on_rewind),
+                        Button::new().children("Key Selected").on_click(on_key),
+                        status,
+                    )),
+                For::each(tracks, |track| track.clone()),
+            ))
+    }
+}
+
+/// Set the timeline position from the horizontal position of the pointer within a lane.
+fn scrub(
+    lane: Entity,
+    pointer: Vec2,
+    query: &Query<(&Node, &GlobalTransform)>,
+    timeline: &mut Timeline,
+) {
+    if let Ok((node, transform)) = query.get(lane) {
+        let rect = node.logical_rect(transform);
+        let fraction = ((pointer.x - rect.min.x) / rect.width().max(1.)).clamp(0., 1.);
+        timeline.time = fraction * timeline.duration;
+    }
+}
+
+/// A row in the timeline, showing the keyframes for one parameter.
+#[derive(Clone, PartialEq)]
+struct TimelineTrack {
+    index: usize,
+    node: Entity,
+    field: &'static str,
+    keys: Vec<(f32, Easing)>,
+}
+
+impl ViewTemplate for TimelineTrack {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let timeline = cx.use_resource::<Timeline>();
+        let duration = timeline.duration.max(f32::EPSILON);
+        let playhead = timeline.time / duration;
+        let label = match cx.use_component::<GraphNode>(self.node) {
+            Some(node) => format!("{}.{}", node.title(), self.field),
+            None => self.field.to_string(),
+        };
+        let track_index = self.index;
+        let keys: Vec<(usize, f32, Easing)> = self
+            .keys
+            .iter()
+            .enumerate()
+            .map(|(index, (time, easing))| (index, *time / duration, *easing))
+            .collect();
+
+        Element::<NodeBundle>::new().style(style_track).children((
+            Element::<NodeBundle>::new()
+                .style(style_track_label)
+                .children(label),
+            Element::<NodeBundle>::new()
+                .named("Timeline::Lane")
+                .style(style_track_lane)
+                .insert_dyn(
+                    |_| {
+                        (
+                            On::<Pointer<Down>>::run(
+                                |event: Listener<Pointer<Down>>,
+                                 query: Query<(&Node, &GlobalTransform)>,
+                                 mut timeline: ResMut<Timeline>| {
+                                    scrub(
+                                        event.listener(),
+                                        event.pointer_location.position,
+                                        &query,
+                                        &mut timeline,
+                                    );
+                                },
+                            ),
+                            On::<Pointer<Drag>>::run(
+                                |event: Listener<Pointer<Drag>>,
+                                 query: Query<(&Node, &GlobalTransform)>,
+                                 mut timeline: ResMut<Timeline>| {
+                                    scrub(
+                                        event.listener(),
+                                        event.pointer_location.position,
+                                        &query,
+                                        &mut timeline,
+                                    );
+                                },
+                            ),
+                        )
+                    },
+                    (),
+                )
+                .children((
+                    For::each(keys, move |(key_index, position, easing)| {
+                        let key_index = *key_index;
+                        Element::<NodeBundle>::new()
+                            .style(style_keyframe)
+                            .style_dyn(
+                                |(position, easing), sb| {
+                                    sb.left(ui::Val::Percent(position * 100.))
+                                        .background_color(easing_color(easing));
+                                },
+                                (*position, *easing),
+                            )
+                            .insert_dyn(
+                                move |_| {
+                                    // Clicking a keyframe cycles through the easing modes.
+                                    On::<Pointer<Down>>::run(
+                                            move |mut event: ListenerMut<Pointer<Down>>,
+                                                  mut timeline: ResMut<Timeline>| {
+                                                event.stop_propagation();
+                                                if let Some(key) = timeline
+                                                    .tracks
+                                                    .get_mut(track_index)
+                                                    .and_then(|t| t.keyframes.get_mut(key_index))
+                                                {
+                                                    key.easing = key.easing.next();
+                                                }
+                                            },
+                                        )
+                                },
+                                (),
+                            )
+                    }),
+                    Element::<NodeBundle>::new()
+                        .style(style_playhead)
+                        .style_dyn(
+                            |playhead, sb| {
+                                sb.left(ui::Val::Percent(playhead * 100.));
+                            },
+                            playhead,
+                        ),
+                )),
+        ))
+    }
+}
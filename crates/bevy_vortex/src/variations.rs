@@ -0,0 +1,335 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode},
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    ui,
+};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill::*;
+use bevy_quill_obsidian::{
+    colors,
+    controls::{Button, SpinBox},
+};
+
+use crate::{
+    color_management::{ColorManagement, WorkingSpace},
+    commands::SetSeedCmd,
+    gen::{build_node_shader, NodeOutput, TerminalReader},
+    graph::{GraphNode, GraphResource, NodeSelected},
+    pipeline::NodeShader3dHandle,
+    preview::PreviewShaderHandle,
+};
+
+/// Number of variations shown at a time.
+const VARIATION_COUNT: usize = 9;
+
+/// Size of the images of the variations, in pixels.
+const VARIATION_SIZE: u32 = 96;
+
+/// Render layer for the variation tiles, so that they do not appear in the preview. The tiles
+/// are spaced apart, so that each camera only sees its own tile.
+const VARIATION_LAYER: usize = 2;
+
+/// Resource which holds the state of the variation explorer. While it is enabled, the
+/// selected node is rendered offscreen with several seeds, so that the user can pick one.
+#[derive(Resource, Default)]
+pub struct Variations {
+    pub(crate) enabled: bool,
+    /// Seed of the first variation. The other variations use the seeds which follow it.
+    pub(crate) first_seed: u32,
+    /// Node whose variations are shown.
+    pub(crate) node: Option<Entity>,
+    /// Image of each variation.
+    pub(crate) images: Vec<Handle<Image>>,
+}
+
+/// Marker for the cameras and tiles which render the variations.
+#[derive(Component)]
+struct VariationEntity;
+
+/// The tile which shows a variation, with its index.
+#[derive(Component)]
+struct VariationTile(usize);
+
+/// Spawn the cameras and tiles for the variations when the explorer is enabled, and despawn
+/// them when it is disabled.
+fn spawn_variations(
+    mut commands: Commands,
+    mut variations: ResMut<Variations>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    shader: Res<PreviewShaderHandle>,
+    query: Query<Entity, With<VariationEntity>>,
+) {
+    if variations.enabled == !variations.images.is_empty() {
+        return;
+    }
+    if !variations.enabled {
+        for entity in query.iter() {
+            commands.entity(entity).despawn();
+        }
+        variations.images.clear();
+        variations.node = None;
+        return;
+    }
+
+    let size = Extent3d {
+        width: VARIATION_SIZE,
+        height: VARIATION_SIZE,
+        ..default()
+    };
+    let shape = meshes.add(Rectangle::new(1., 1.));
+    for index in 0..VARIATION_COUNT {
+        let mut image = Image::new_fill(
+            size,
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_DST
+            | TextureUsages::RENDER_ATTACHMENT;
+        let image = images.add(image);
+        let position = Vec3::new(index as f32 * 2., 0., 0.);
+        commands.spawn((
+            Camera3dBundle {
+                camera: Camera {
+                    target: RenderTarget::Image(image.clone()),
+                    order: -3,
+                    ..default()
+                },
+                transform: Transform::from_translation(position + Vec3::Z * 5.)
+                    .looking_at(position, Vec3::Y),
+                projection: Projection::Orthographic(OrthographicProjection {
+                    scaling_mode: ScalingMode::Fixed {
+                        width: 1.,
+                        height: 1.,
+                    },
+                    ..default()
+                }),
+                ..default()
+            },
+            RenderLayers::layer(VARIATION_LAYER),
+            VariationEntity,
+        ));
+        commands.spawn((
+            shape.clone(),
+            NodeShader3dHandle(shader.0.clone()),
+            SpatialBundle {
+                transform: Transform::from_translation(position),
+                ..SpatialBundle::INHERITED_IDENTITY
+            },
+            RenderLayers::layer(VARIATION_LAYER),
+            VariationEntity,
+            VariationTile(index),
+        ));
+        variations.images.push(image);
+    }
+}
+
+/// Rebuild the variation shaders when the selected node, its output, the first seed or the
+/// working space changes.
+#[allow(clippy::too_many_arguments)]
+fn update_variations(
+    mut variations: ResMut<Variations>,
+    reader: TerminalReader,
+    color: Res<ColorManagement>,
+    selection: Query<Entity, (With<NodeSelected>, With<GraphNode>)>,
+    changed_outputs: Query<(), Changed<NodeOutput>>,
+    added_tiles: Query<(), Added<VariationTile>>,
+    mut tiles: Query<(&VariationTile, &mut NodeShader3dHandle)>,
+    mut shaders: ResMut<Assets<Shader>>,
+    mut built: Local<Option<(Entity, u32, WorkingSpace)>>,
+    mut handles: Local<Vec<Handle<Shader>>>,
+) {
+    if !variations.enabled {
+        *built = None;
+        return;
+    }
+    let mut selected = selection.iter();
+    let node = match (selected.next(), selected.next()) {
+        (Some(node), None) => Some(node),
+        _ => None,
+    };
+    if variations.node != node {
+        variations.node = node;
+    }
+    let Some(node) = node else {
+        *built = None;
+        return;
+    };
+
+    let key = (node, variations.first_seed, color.working_space);
+    if *built == Some(key) && added_tiles.is_empty() && changed_outputs.get(node).is_err() {
+        return;
+    }
+    *built = Some(key);
+
+    handles.resize_with(VARIATION_COUNT, || shaders.reserve_handle());
+    for (tile, mut shader_handle) in tiles.iter_mut() {
+        let seed = variations.first_seed.wrapping_add(tile.0 as u32);
+        let Some(shader) = build_node_shader(&reader, node, color.working_space, seed) else {
+            continue;
+        };
+        let handle = handles[tile.0].clone();
+        shaders.insert(handle.id(), shader);
+        if shader_handle.0 != handle {
+            shader_handle.0 = handle;
+        }
+    }
+}
+
+fn style_variations(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .gap(4)
+        .padding(4)
+        .background_color(colors::U1)
+        .border_radius(4.);
+}
+
+fn style_seed_row(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(4);
+}
+
+fn style_seed_spinbox(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.);
+}
+
+fn style_variation_grid(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Grid)
+        .grid_template_columns(vec![ui::RepeatedGridTrack::fr(3, 1.)])
+        .gap(4);
+}
+
+fn style_variation(ss: &mut StyleBuilder) {
+    ss.aspect_ratio(1.).border(2).border_radius(3.);
+}
+
+fn style_variation_hint(ss: &mut StyleBuilder) {
+    ss.color(colors::DIM).font_size(12.);
+}
+
+/// Panel which shows variations of the selected node with different seeds. Clicking a
+/// variation makes its seed the seed of the graph.
+#[derive(Clone, PartialEq)]
+pub struct VariationsPanel;
+
+impl ViewTemplate for VariationsPanel {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let variations = cx.use_resource::<Variations>();
+        let enabled = variations.enabled;
+        let has_node = variations.node.is_some();
+        let first_seed = variations.first_seed;
+        let items: Vec<(u32, Handle<Image>)> = variations
+            .images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| (first_seed.wrapping_add(index as u32), image.clone()))
+            .collect();
+        let seed = cx.use_resource::<GraphResource>().0.seed;
+
+        let on_seed = cx.create_callback(|value: In<f32>, mut commands: Commands| {
+            commands.add(SetSeedCmd {
+                seed: value.max(0.) as u32,
+            });
+        });
+        let on_shuffle = cx.create_callback(|mut variations: ResMut<Variations>| {
+            variations.first_seed = variations.first_seed.wrapping_add(VARIATION_COUNT as u32);
+        });
+
+        Cond::new(
+            enabled,
+            Element::<NodeBundle>::new()
+                .named("Variations")
+                .style(style_variations)
+                .children((
+                    Element::<NodeBundle>::new()
+                        .style(style_seed_row)
+                        .children((
+                            "Seed",
+                            SpinBox::new()
+                                .style(style_seed_spinbox)
+                                .value(seed as f32)
+                                .range(0. ..=99999.)
+                                .precision(0)
+                                .step(1.)
+                                .on_change(on_seed),
+                            Button::new().children("Shuffle").on_click(on_shuffle),
+                        )),
+                    Cond::new(
+                        has_node,
+                        Element::<NodeBundle>::new()
+                            .style(style_variation_grid)
+                            .children(For::each(items, move |(variation_seed, image)| {
+                                VariationButton {
+                                    seed: *variation_seed,
+                                    image: image.clone(),
+                                    selected: *variation_seed == seed,
+                                }
+                            })),
+                        Element::<NodeBundle>::new()
+                            .style(style_variation_hint)
+                            .children("Select a node to see its variations."),
+                    ),
+                )),
+            (),
+        )
+    }
+}
+
+/// One of the variations, which adopts its seed when clicked.
+#[derive(Clone, PartialEq)]
+struct VariationButton {
+    seed: u32,
+    image: Handle<Image>,
+    selected: bool,
+}
+
+impl ViewTemplate for VariationButton {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        let seed = self.seed;
+        Element::<NodeBundle>::new()
+            .style(style_variation)
+            .style_dyn(
+                |(image, selected), sb| {
+                    sb.background_image(image).border_color(if selected {
+                        colors::ACCENT
+                    } else {
+                        colors::TRANSPARENT
+                    });
+                },
+                (self.image.clone(), self.selected),
+            )
+            .insert_dyn(
+                move |_| {
+                    On::<Pointer<Click>>::run(move |mut commands: Commands| {
+                        commands.add(SetSeedCmd { seed });
+                    })
+                },
+                (),
+            )
+    }
+}
+
+pub struct VariationsPlugin;
+
+impl Plugin for VariationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Variations>()
+            .add_systems(Update, (spawn_variations, update_variations).chain());
+    }
+}